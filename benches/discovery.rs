@@ -0,0 +1,65 @@
+// Discovery benchmarks over a large generated pack
+//
+// Copies the bundled workflows under new IDs until there are `PACK_SIZE` of
+// them, then measures a first scan (every header parsed), a start with the parse
+// cache saved by an earlier run, and a rescan of an open discovery.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::fs;
+use std::path::Path;
+
+use raps_demo_workflows::workflow::discovery::WorkflowDiscovery;
+use raps_demo_workflows::workflow::parse_cache::ParseCache;
+use raps_demo_workflows::workflow::tour::is_tour_file;
+use raps_demo_workflows::workflow::vars::is_vars_file;
+
+/// Workflows in the generated pack
+const PACK_SIZE: usize = 500;
+
+/// Fill `dir` with `PACK_SIZE` workflows made from the bundled ones
+fn generate_pack(dir: &Path) {
+    let mut sources = Vec::new();
+    for entry in walkdir::WalkDir::new("workflows").into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "yaml") && !is_tour_file(path) && !is_vars_file(path) {
+            let content = fs::read_to_string(path).unwrap();
+            if let Some(id) = content.lines().find_map(|l| l.trim().strip_prefix("id:")) {
+                sources.push((id.trim().trim_matches('"').to_string(), content.clone()));
+            }
+        }
+    }
+    assert!(!sources.is_empty(), "run from the repository root");
+    for i in 0..PACK_SIZE {
+        let (id, content) = &sources[i % sources.len()];
+        let copy = content.replacen(id.as_str(), &format!("{}-{}", id, i), 1);
+        fs::write(dir.join(format!("{}-{}.yaml", id, i)), copy).unwrap();
+    }
+}
+
+fn discovery(c: &mut Criterion) {
+    let pack = tempfile::tempdir().unwrap();
+    generate_pack(pack.path());
+    let cache_file = pack.path().join("cache").join("workflow-cache.json");
+    let dirs = || [pack.path().to_path_buf()];
+
+    let mut group = c.benchmark_group("discovery");
+    group.sample_size(10);
+    group.bench_function("first scan", |b| {
+        b.iter(|| WorkflowDiscovery::with_dirs(dirs()).unwrap());
+    });
+
+    // Save the cache once, as a previous start would have
+    WorkflowDiscovery::with_parse_cache(dirs(), ParseCache::load_from(&cache_file)).unwrap();
+    group.bench_function("start with saved cache", |b| {
+        b.iter(|| WorkflowDiscovery::with_parse_cache(dirs(), ParseCache::load_from(&cache_file)).unwrap());
+    });
+
+    let mut open = WorkflowDiscovery::with_dirs(dirs()).unwrap();
+    group.bench_function("rescan", |b| {
+        b.iter(|| open.discover_workflows().unwrap());
+    });
+    group.finish();
+}
+
+criterion_group!(benches, discovery);
+criterion_main!(benches);
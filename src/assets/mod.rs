@@ -15,12 +15,17 @@
 //! These sample files are intended for educational and demonstration purposes only.
 
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::fs;
 use std::io::Write;
+use std::sync::OnceLock;
+
+use crate::error::{AssetError, RapsDemoError};
 
 /// Asset category for organizing downloads
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AssetCategory {
     Inventor,
     Revit,
@@ -52,7 +57,7 @@ impl AssetCategory {
 }
 
 /// Represents a downloadable Autodesk sample asset
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetDefinition {
     /// Display name for the asset
     pub name: String,
@@ -63,17 +68,25 @@ pub struct AssetDefinition {
     /// Category of the asset
     pub category: AssetCategory,
     /// Whether this is a ZIP file that needs extraction
+    #[serde(default)]
     pub is_archive: bool,
     /// Estimated size in bytes (for display purposes)
+    #[serde(default)]
     pub estimated_size_mb: f32,
 }
 
+/// Additional assets listed in an `assets.yaml` manifest
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetManifest {
+    pub assets: Vec<AssetDefinition>,
+}
+
 impl AssetDefinition {
     /// Get the filename from the URL
     pub fn filename(&self) -> String {
         self.url
             .split('/')
-            .last()
+            .next_back()
             .unwrap_or("unknown")
             .to_string()
     }
@@ -182,6 +195,31 @@ impl AssetRegistry {
         Self { assets }
     }
 
+    /// Create a registry of only `assets`, without the known samples
+    pub fn from_assets(assets: Vec<AssetDefinition>) -> Self {
+        Self { assets }
+    }
+
+    /// Add the assets of an `assets.yaml` manifest (skipping known URLs)
+    pub fn with_manifest<P: AsRef<Path>>(mut self, path: P) -> Result<Self, RapsDemoError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|source| AssetError::ManifestUnreadable {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let manifest: AssetManifest =
+            serde_yaml::from_str(&content).map_err(|source| AssetError::InvalidManifest {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        for asset in manifest.assets {
+            if !self.assets.iter().any(|a| a.url == asset.url) {
+                self.assets.push(asset);
+            }
+        }
+        Ok(self)
+    }
+
     /// Get all registered assets
     pub fn all(&self) -> &[AssetDefinition] {
         &self.assets
@@ -201,38 +239,50 @@ impl AssetRegistry {
     }
 }
 
+/// Callback invoked with (message, current, total) during downloads
+pub type ProgressCallback = Box<dyn Fn(&str, usize, usize) + Send + Sync>;
+
 /// Downloads and manages Autodesk sample assets
 pub struct AssetDownloader {
     /// Base directory for storing downloaded assets
     base_dir: PathBuf,
-    /// HTTP client for downloads
-    client: reqwest::blocking::Client,
+    /// HTTP client for downloads, built on the first download: a blocking
+    /// client can't be created on an async runtime's thread, where the TUI
+    /// only checks which assets exist
+    client: OnceLock<reqwest::blocking::Client>,
     /// Progress callback
-    progress_callback: Option<Box<dyn Fn(&str, usize, usize) + Send + Sync>>,
+    progress_callback: Option<ProgressCallback>,
 }
 
 impl AssetDownloader {
     /// Create a new asset downloader with the specified base directory
-    pub fn new<P: AsRef<Path>>(base_dir: P) -> Result<Self> {
+    pub fn new<P: AsRef<Path>>(base_dir: P) -> Result<Self, RapsDemoError> {
         let base_dir = base_dir.as_ref().to_path_buf();
         
         // Create base directory if it doesn't exist
         if !base_dir.exists() {
             fs::create_dir_all(&base_dir)
-                .context("Failed to create assets directory")?;
+                .context("Failed to create assets directory")
+                .map_err(AssetError::Other)?;
         }
 
+        Ok(Self {
+            base_dir,
+            client: OnceLock::new(),
+            progress_callback: None,
+        })
+    }
+
+    fn client(&self) -> Result<&reqwest::blocking::Client> {
+        if let Some(client) = self.client.get() {
+            return Ok(client);
+        }
         let client = reqwest::blocking::Client::builder()
             .timeout(std::time::Duration::from_secs(300)) // 5 minute timeout for large files
             .user_agent("RAPS-Demo/1.0 (Autodesk Platform Services Demo)")
             .build()
             .context("Failed to create HTTP client")?;
-
-        Ok(Self {
-            base_dir,
-            client,
-            progress_callback: None,
-        })
+        Ok(self.client.get_or_init(|| client))
     }
 
     /// Set a progress callback for download updates
@@ -257,7 +307,18 @@ impl AssetDownloader {
     }
 
     /// Download a single asset
-    pub fn download(&self, asset: &AssetDefinition) -> Result<PathBuf> {
+    pub fn download(&self, asset: &AssetDefinition) -> Result<PathBuf, RapsDemoError> {
+        self.fetch(asset).map_err(|source| {
+            AssetError::Download {
+                asset: asset.name.clone(),
+                source,
+            }
+            .into()
+        })
+    }
+
+    /// Download `asset` unless it is already there, extracting archives
+    fn fetch(&self, asset: &AssetDefinition) -> Result<PathBuf> {
         let target_dir = self.base_dir.join(asset.category.folder_name());
         if !target_dir.exists() {
             fs::create_dir_all(&target_dir)
@@ -277,17 +338,12 @@ impl AssetDownloader {
         }
 
         // Download the file
-        let response = self.client
+        let response = self.client()?
             .get(&asset.url)
-            .send()
-            .context(format!("Failed to download {}", asset.name))?;
+            .send()?;
 
         if !response.status().is_success() {
-            anyhow::bail!(
-                "Failed to download {}: HTTP {}",
-                asset.name,
-                response.status()
-            );
+            anyhow::bail!("HTTP {}", response.status());
         }
 
         let total_size = response.content_length().unwrap_or(0) as usize;
@@ -315,7 +371,7 @@ impl AssetDownloader {
     }
 
     /// Download all assets in a category
-    pub fn download_category(&self, category: AssetCategory) -> Result<Vec<PathBuf>> {
+    pub fn download_category(&self, category: AssetCategory) -> Result<Vec<PathBuf>, RapsDemoError> {
         let registry = AssetRegistry::new();
         let assets = registry.by_category(category);
         
@@ -329,7 +385,7 @@ impl AssetDownloader {
     }
 
     /// Download all registered assets
-    pub fn download_all(&self) -> Result<Vec<PathBuf>> {
+    pub fn download_all(&self) -> Result<Vec<PathBuf>, RapsDemoError> {
         let registry = AssetRegistry::new();
         
         let mut paths = Vec::new();
@@ -498,4 +554,21 @@ mod tests {
         };
         assert_eq!(asset.filename(), "file.zip");
     }
+
+    #[test]
+    fn test_asset_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("assets.yaml");
+        fs::write(
+            &path,
+            "assets:\n  - name: Bracket\n    description: Custom part\n    url: https://example.com/bracket.ipt\n    category: inventor\n",
+        )
+        .unwrap();
+
+        let builtin = AssetRegistry::new().all().len();
+        let registry = AssetRegistry::new().with_manifest(&path).unwrap();
+        assert_eq!(registry.all().len(), builtin + 1);
+        assert_eq!(registry.all().last().unwrap().filename(), "bracket.ipt");
+        assert!(AssetRegistry::new().with_manifest(dir.path().join("missing.yaml")).is_err());
+    }
 }
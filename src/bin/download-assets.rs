@@ -199,7 +199,7 @@ fn main() -> Result<()> {
             }
             Err(e) => {
                 println!(" ❌");
-                println!("   Error: {}", e);
+                println!("   Error: {:#}", e);
                 error_count += 1;
             }
         }
@@ -0,0 +1,264 @@
+// Library entry point for embedding RAPS Demo Workflows
+//
+// `RapsDemoBuilder` gathers everything that used to be hardcoded (the
+// workflows and assets directories, the demo configuration, the RAPS client
+// settings and where execution updates go) and builds the workflow engine,
+// the TUI or a demo manager from it. Other tools and tests can point it at
+// their own directories instead of changing the working directory.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use crate::config::types::ConfigPaths;
+use crate::config::{ConfigManager, DemoConfig};
+use crate::demo::DemoManager;
+use crate::resource::ResourceManager;
+use crate::tui::TuiApp;
+use crate::workflow::client::{ProfileEnvironments, RapsClientConfig};
+use crate::workflow::metrics::WorkflowMetrics;
+use crate::workflow::pack;
+use crate::workflow::parse_cache::ParseCache;
+use crate::workflow::{ExecutionUpdate, WorkflowEngine, WorkflowExecutor};
+
+/// Builder for the workflow engine, TUI and demo manager
+#[derive(Default)]
+pub struct RapsDemoBuilder {
+    workflows_dirs: Option<Vec<PathBuf>>,
+    packs_dir: Option<PathBuf>,
+    assets_dir: Option<PathBuf>,
+    demo_config: Option<DemoConfig>,
+    executor_config: Option<RapsClientConfig>,
+    profile_environments: ProfileEnvironments,
+    update_sink: Option<mpsc::UnboundedSender<ExecutionUpdate>>,
+    metrics: Option<Arc<WorkflowMetrics>>,
+    resource_manager: Option<ResourceManager>,
+    stream_output: bool,
+    parse_cache: bool,
+}
+
+impl RapsDemoBuilder {
+    /// Start with the defaults (`./workflows`, `./sample-models/autodesk`)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Discover workflows in `dir` (created if missing)
+    pub fn workflows_dir<P: Into<PathBuf>>(self, dir: P) -> Self {
+        self.workflows_dirs([dir.into()])
+    }
+
+    /// Discover workflows in several directories (e.g. a built-in pack and a
+    /// user pack), later ones overriding same-ID workflows of earlier ones
+    pub fn workflows_dirs<I: IntoIterator<Item = PathBuf>>(mut self, dirs: I) -> Self {
+        self.workflows_dirs = Some(dirs.into_iter().collect());
+        self
+    }
+
+    /// Also discover the workflows of packs installed in `dir`; they come
+    /// before the workflows directories, which can override them
+    pub fn installed_packs<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.packs_dir = Some(dir.into());
+        self
+    }
+
+    /// Download and look up sample assets in `dir`
+    pub fn assets_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.assets_dir = Some(dir.into());
+        self
+    }
+
+    /// Take the demo settings and the RAPS profiles from a loaded
+    /// configuration manager
+    pub fn config_manager(self, manager: &ConfigManager) -> Self {
+        self.demo_config(manager.demo_config().clone())
+            .profile_environments(manager.profile_environments())
+    }
+
+    /// Environments of the RAPS profiles that workflows and steps may run as
+    /// (`profile:`)
+    pub fn profile_environments(mut self, environments: ProfileEnvironments) -> Self {
+        self.profile_environments = environments;
+        self
+    }
+
+    /// Use these demo settings (the defaults otherwise); their workflows and
+    /// assets directories apply unless set on the builder
+    pub fn demo_config(mut self, config: DemoConfig) -> Self {
+        self.demo_config = Some(config);
+        self
+    }
+
+    /// Run RAPS with these client settings (binary path, timeout, environment)
+    pub fn executor_config(mut self, config: RapsClientConfig) -> Self {
+        self.executor_config = Some(config);
+        self
+    }
+
+    /// Also send every execution update to `sink`, alongside the engine's subscribers
+    pub fn update_sink(mut self, sink: mpsc::UnboundedSender<ExecutionUpdate>) -> Self {
+        self.update_sink = Some(sink);
+        self
+    }
+
+    /// Record execution metrics
+    pub fn metrics(mut self, metrics: Option<Arc<WorkflowMetrics>>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Send each line printed by RAPS commands as a `StepOutput` update
+    pub fn stream_output(mut self, stream: bool) -> Self {
+        self.stream_output = stream;
+        self
+    }
+
+    /// Save the parsed workflow headers in the configuration directory, so
+    /// the next start only parses the files that changed; off by default,
+    /// leaving the user's configuration alone when embedded or tested
+    pub fn parse_cache(mut self, enabled: bool) -> Self {
+        self.parse_cache = enabled;
+        self
+    }
+
+    /// Record interrupted workflows with this resource manager
+    pub fn resource_manager(mut self, resource_manager: ResourceManager) -> Self {
+        self.resource_manager = Some(resource_manager);
+        self
+    }
+
+    /// Workflows directories that will be used, installed packs first
+    pub fn workflows_paths(&self) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = match &self.packs_dir {
+            Some(packs_dir) => pack::list_packs(packs_dir)
+                .iter()
+                .filter(|installed| {
+                    // Packs may run arbitrary commands, so tampered ones stay out
+                    let modified = installed.trust() == pack::PackTrust::Modified;
+                    if modified {
+                        tracing::warn!("Not loading pack {}: files changed since installation", installed.label());
+                    }
+                    !modified
+                })
+                .map(|installed| installed.workflows_dir())
+                .collect(),
+            None => Vec::new(),
+        };
+        match (&self.workflows_dirs, &self.demo_config) {
+            (Some(configured), _) => dirs.extend(configured.iter().cloned()),
+            (None, Some(config)) => dirs.extend(config.workflows_path.iter().cloned()),
+            (None, None) => dirs.push(PathBuf::from(ConfigPaths::DEFAULT_WORKFLOWS_DIR)),
+        }
+        dirs
+    }
+
+    /// Assets directory that will be used
+    pub fn assets_path(&self) -> &Path {
+        match (&self.assets_dir, &self.demo_config) {
+            (Some(dir), _) => dir,
+            (None, Some(config)) if config.asset_base_path != Path::new(DemoConfig::DEFAULT_ASSET_BASE_PATH) => {
+                &config.asset_base_path
+            },
+            _ => Path::new(ConfigPaths::DEFAULT_ASSETS_DIR),
+        }
+    }
+
+    /// Build the workflow engine
+    pub fn build_engine(self) -> Result<WorkflowEngine> {
+        let workflows_dirs = self.workflows_paths();
+        let config = self.demo_config.unwrap_or_default();
+        // Client settings given to the builder win over the demo's rate limits
+        let mut client_config = self
+            .executor_config
+            .unwrap_or_else(|| RapsClientConfig::for_demo(&config));
+        for (name, environment) in self.profile_environments {
+            client_config.profiles.entry(name).or_insert(environment);
+        }
+        let executor = WorkflowExecutor::with_config(client_config)
+        .with_policy(config.policy.clone())
+        .with_strict_placeholders(config.strict_placeholders)
+        .with_region(config.region)
+        .with_output_streaming(self.stream_output)
+        .with_auto_reauth(config.auto_reauth)
+        .with_max_output_bytes(config.max_output_bytes)
+        .with_watchdog(config.watchdog.clone());
+        if let Some(sink) = self.update_sink {
+            executor.events().forward_to(sink);
+        }
+
+        let cache = if self.parse_cache {
+            ParseCache::load()
+        } else {
+            ParseCache::default()
+        };
+        let mut engine = WorkflowEngine::open_with_executor(
+            &workflows_dirs,
+            executor,
+            self.metrics,
+            cache,
+            config.builtin_workflows,
+        )?;
+        engine = engine.with_config(config);
+        if let Some(resource_manager) = self.resource_manager {
+            engine = engine.with_resource_manager(resource_manager);
+        }
+        Ok(engine)
+    }
+
+    /// Build the TUI, with locale and glyphs from the demo settings
+    pub fn build_tui(self) -> Result<TuiApp> {
+        let assets_dir = self.assets_path().to_path_buf();
+        let packs_dir = self.packs_dir.clone();
+        let config = self.demo_config.clone().unwrap_or_default();
+        let mut app = TuiApp::with_engine(self.build_engine()?)?;
+        if let Some(packs_dir) = packs_dir {
+            app = app.with_installed_packs(packs_dir);
+        }
+        Ok(app
+            .with_assets_dir(assets_dir)
+            .with_locale(config.locale)
+            .with_glyphs(config.glyphs)
+            .with_console_capacity(config.console_log_lines))
+    }
+
+    /// Build a demo manager over the workflows directories
+    pub fn build_demo_manager(self) -> Result<DemoManager> {
+        DemoManager::with_workflows_dirs(self.workflows_paths())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_builder_uses_custom_paths_and_sink() {
+        let dir = tempfile::tempdir().unwrap();
+        let workflows_dir = dir.path().join("flows");
+        let (sink, mut sink_receiver) = mpsc::unbounded_channel();
+
+        let builder = RapsDemoBuilder::new()
+            .workflows_dir(&workflows_dir)
+            .assets_dir(dir.path().join("assets"))
+            .update_sink(sink);
+        assert_eq!(builder.assets_path(), dir.path().join("assets"));
+
+        let engine = builder.build_engine().unwrap();
+        assert!(workflows_dir.is_dir());
+        assert_eq!(engine.workflows_dirs(), std::slice::from_ref(&workflows_dir));
+        // A fresh directory still lists the built-in pack
+        assert_eq!(engine.list().len(), crate::workflow::builtin::BUILTIN_WORKFLOWS.len());
+        // Updates reach both the sink and subscribers
+        let mut receiver = engine.subscribe();
+        engine.executor().events().send(ExecutionUpdate::Cancelled {
+            handle: crate::workflow::ExecutionHandle::new("builder-test".to_string()),
+        });
+        assert!(sink_receiver.try_recv().is_ok());
+        assert!(receiver.try_recv().is_some());
+
+        let mut manager = RapsDemoBuilder::new().workflows_dir(&workflows_dir).build_demo_manager().unwrap();
+        manager.initialize().unwrap();
+        assert!(manager.get_workflows().is_empty());
+    }
+}
@@ -10,6 +10,12 @@ use std::process::Command;
 
 use super::types::{AuthTokens, RapsConfig, ValidationResult};
 
+/// APS Developer Portal, where apps and credentials are managed
+pub const APS_PORTAL_URL: &str = "https://aps.autodesk.com/developer/overview";
+
+/// Troubleshooting guide for obtaining 3-legged tokens
+pub const OAUTH_GUIDE_URL: &str = "https://aps.autodesk.com/en/docs/oauth/v2/tutorials/get-3-legged-token/";
+
 /// Authentication validation service
 #[derive(Debug, Clone)]
 pub struct AuthValidator {
@@ -65,7 +71,7 @@ impl AuthValidator {
     async fn validate_token(&self, tokens: &AuthTokens) -> Result<bool> {
         // Use RAPS CLI to validate the token by making a simple API call
         let output = Command::new("raps")
-            .args(&["auth", "status"])
+            .args(["auth", "status"])
             .env("APS_ACCESS_TOKEN", &tokens.access_token)
             .output();
 
@@ -93,7 +99,7 @@ impl AuthValidator {
         // Try to make a simple HTTP request to the APS base URL
         let client = reqwest::Client::new();
         let response = client
-            .get(&format!("{}/health", self.base_url))
+            .get(format!("{}/health", self.base_url))
             .timeout(std::time::Duration::from_secs(10))
             .send()
             .await;
@@ -121,7 +127,7 @@ impl AuthSetupGuide {
                 title: "Obtain APS Client ID".to_string(),
                 description: "Get your Client ID from the APS Developer Portal".to_string(),
                 action: SetupAction::VisitUrl {
-                    url: "https://aps.autodesk.com/developer/overview".to_string(),
+                    url: APS_PORTAL_URL.to_string(),
                     description: "Visit the APS Developer Portal to create an app and get your Client ID".to_string(),
                 },
                 required: true,
@@ -133,7 +139,7 @@ impl AuthSetupGuide {
                 title: "Obtain APS Client Secret".to_string(),
                 description: "Get your Client Secret from the APS Developer Portal".to_string(),
                 action: SetupAction::VisitUrl {
-                    url: "https://aps.autodesk.com/developer/overview".to_string(),
+                    url: APS_PORTAL_URL.to_string(),
                     description: "Visit the APS Developer Portal to get your Client Secret".to_string(),
                 },
                 required: true,
@@ -178,7 +184,7 @@ impl AuthSetupGuide {
                     problem: "Missing APS credentials".to_string(),
                     solution: "Follow the setup instructions to obtain your Client ID and Client Secret from the APS Developer Portal".to_string(),
                     commands: vec!["raps auth login".to_string()],
-                    links: vec!["https://aps.autodesk.com/developer/overview".to_string()],
+                    links: vec![APS_PORTAL_URL.to_string()],
                 });
             }
 
@@ -187,7 +193,7 @@ impl AuthSetupGuide {
                     problem: "No authentication tokens found".to_string(),
                     solution: "Run the authentication command to get access tokens".to_string(),
                     commands: vec!["raps auth login".to_string()],
-                    links: vec!["https://aps.autodesk.com/en/docs/oauth/v2/tutorials/get-3-legged-token/".to_string()],
+                    links: vec![OAUTH_GUIDE_URL.to_string()],
                 });
             }
 
@@ -196,7 +202,7 @@ impl AuthSetupGuide {
                     problem: "Authentication token is invalid".to_string(),
                     solution: "Your token may have expired or been revoked. Re-authenticate to get a new token".to_string(),
                     commands: vec!["raps auth logout".to_string(), "raps auth login".to_string()],
-                    links: vec!["https://aps.autodesk.com/en/docs/oauth/v2/tutorials/get-3-legged-token/".to_string()],
+                    links: vec![OAUTH_GUIDE_URL.to_string()],
                 });
             }
         }
@@ -207,7 +213,7 @@ impl AuthSetupGuide {
                     problem: "Access token has expired".to_string(),
                     solution: "Refresh your access token or re-authenticate".to_string(),
                     commands: vec!["raps auth refresh".to_string()],
-                    links: vec!["https://aps.autodesk.com/en/docs/oauth/v2/tutorials/get-3-legged-token/".to_string()],
+                    links: vec![OAUTH_GUIDE_URL.to_string()],
                 });
             }
 
@@ -216,7 +222,7 @@ impl AuthSetupGuide {
                     problem: "Access token expires soon".to_string(),
                     solution: "Consider refreshing your token to avoid interruption".to_string(),
                     commands: vec!["raps auth refresh".to_string()],
-                    links: vec!["https://aps.autodesk.com/en/docs/oauth/v2/tutorials/get-3-legged-token/".to_string()],
+                    links: vec![OAUTH_GUIDE_URL.to_string()],
                 });
             }
         }
@@ -344,7 +350,7 @@ impl TokenRefresher {
             if let Some(_refresh_token) = &tokens.refresh_token {
                 // Use RAPS CLI to refresh the token with JSON output
                 let output = Command::new("raps")
-                    .args(&["auth", "refresh", "--output", "json"])
+                    .args(["auth", "refresh", "--output", "json"])
                     .output()
                     .context("Failed to run RAPS CLI for token refresh")?;
 
@@ -6,9 +6,12 @@
 use anyhow::{Context, Result};
 use std::collections::HashMap;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::fs as async_fs;
 
+use crate::error::{ConfigError, RapsDemoError};
+use crate::workflow::client::ProfileEnvironments;
+
 use super::auth::{AuthSetupGuide, AuthValidator, TokenRefresher, SetupInstructions, TroubleshootingGuide};
 use super::types::{
     AuthTokens, ConfigPaths, DemoConfig, EnvVars, LogLevel, Profile, RapsConfig, ValidationResult,
@@ -25,16 +28,41 @@ pub struct ConfigManager {
     profiles: HashMap<String, Profile>,
     /// Path to configuration directory
     config_dir: PathBuf,
+    /// Demo configuration file given explicitly (e.g. `--config`)
+    demo_config_file: Option<PathBuf>,
     /// Authentication validator
     auth_validator: AuthValidator,
 }
 
 impl ConfigManager {
     /// Create a new configuration manager
-    pub async fn new() -> Result<Self> {
-        tracing::debug!("Initializing configuration manager");
+    pub async fn new() -> Result<Self, RapsDemoError> {
+        let config_dir = Self::determine_config_dir().map_err(ConfigError::Other)?;
+        Self::load(config_dir, None).await.map_err(|e| ConfigError::Other(e).into())
+    }
+
+    /// Create a configuration manager from an explicit path, overriding
+    /// `RAPS_CONFIG_DIR` and the default location
+    ///
+    /// A directory is used as the configuration directory; a file is read as
+    /// the demo configuration, with the other files taken from its directory.
+    pub async fn with_config_path<P: AsRef<Path>>(path: P) -> Result<Self, RapsDemoError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(ConfigError::NotFound(path.to_path_buf()).into());
+        }
+        let config_dir = ConfigPaths::config_dir_of(path);
+        let loaded = if path.is_dir() {
+            Self::load(config_dir, None).await
+        } else {
+            Self::load(config_dir, Some(path.to_path_buf())).await
+        };
+        loaded.map_err(|e| ConfigError::Other(e).into())
+    }
 
-        let config_dir = Self::determine_config_dir()?;
+    /// Load the configuration from `config_dir`
+    async fn load(config_dir: PathBuf, demo_config_file: Option<PathBuf>) -> Result<Self> {
+        tracing::debug!("Initializing configuration manager");
         tracing::debug!("Using configuration directory: {:?}", config_dir);
 
         // Ensure configuration directory exists
@@ -50,6 +78,7 @@ impl ConfigManager {
             demo_config: DemoConfig::default(),
             profiles: HashMap::new(),
             config_dir,
+            demo_config_file,
             auth_validator: AuthValidator::new("https://developer.api.autodesk.com".to_string()),
         };
 
@@ -61,13 +90,8 @@ impl ConfigManager {
 
     /// Determine the configuration directory to use
     fn determine_config_dir() -> Result<PathBuf> {
-        // Check environment variable first
-        if let Ok(config_dir) = env::var(EnvVars::CONFIG_DIR) {
-            return Ok(PathBuf::from(config_dir));
-        }
-
-        // Use default configuration directory
-        ConfigPaths::default_config_dir()
+        // `--config`, then the environment variable, then the default
+        ConfigPaths::resolved_config_dir()
     }
 
     /// Load configuration from files and environment variables
@@ -91,6 +115,19 @@ impl ConfigManager {
             self.apply_profile(profile_name)?;
         }
 
+        // The locale is a per-presenter choice, so the environment wins over files
+        if let Ok(locale) = env::var(EnvVars::LOCALE) {
+            match locale.parse() {
+                Ok(locale) => self.demo_config.locale = locale,
+                Err(e) => tracing::warn!("Ignoring {}: {}", EnvVars::LOCALE, e),
+            }
+        }
+
+        // Likewise for the workflow packs to load
+        if let Some(path) = env::var_os(EnvVars::WORKFLOWS_PATH).filter(|p| !p.is_empty()) {
+            self.demo_config.workflows_path = env::split_paths(&path).collect();
+        }
+
         tracing::info!("Configuration loaded successfully");
         Ok(())
     }
@@ -184,7 +221,7 @@ impl ConfigManager {
 
     /// Load demo configuration from file
     async fn load_demo_config(&mut self) -> Result<()> {
-        let config_file = self.config_dir.join(ConfigPaths::DEMO_CONFIG_FILE);
+        let config_file = self.demo_config_path();
         
         if !config_file.exists() {
             tracing::debug!("Demo configuration file not found, using defaults");
@@ -195,10 +232,10 @@ impl ConfigManager {
         
         let content = async_fs::read_to_string(&config_file)
             .await
-            .context("Failed to read demo configuration file")?;
+            .with_context(|| format!("Failed to read demo configuration file: {}", config_file.display()))?;
 
         self.demo_config = toml::from_str(&content)
-            .context("Failed to parse demo configuration file")?;
+            .with_context(|| format!("Failed to parse demo configuration file: {}", config_file.display()))?;
 
         tracing::debug!("Demo configuration loaded from file");
         Ok(())
@@ -268,6 +305,41 @@ impl ConfigManager {
         Ok(())
     }
 
+    /// Load the configuration again from the same files, e.g. after they
+    /// were edited; `self` is left as it was if they no longer parse
+    pub async fn reload(&self) -> Result<Self, RapsDemoError> {
+        Self::load(self.config_dir.clone(), self.demo_config_file.clone())
+            .await
+            .map_err(|e| ConfigError::Other(e).into())
+    }
+
+    /// The demo configuration file: the one given explicitly, or the
+    /// default one in the configuration directory
+    fn demo_config_path(&self) -> PathBuf {
+        self.demo_config_file
+            .clone()
+            .unwrap_or_else(|| self.config_dir.join(ConfigPaths::DEMO_CONFIG_FILE))
+    }
+
+    /// Files the configuration is read from: the RAPS and demo
+    /// configuration and each profile, existing or not
+    pub fn config_files(&self) -> Vec<PathBuf> {
+        let mut files = vec![
+            self.config_dir.join(ConfigPaths::RAPS_CONFIG_FILE),
+            self.demo_config_path(),
+        ];
+        let profiles_dir = self.config_dir.join(ConfigPaths::PROFILES_DIR);
+        if let Ok(entries) = std::fs::read_dir(&profiles_dir) {
+            let mut profiles: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("toml"))
+                .collect();
+            profiles.sort();
+            files.extend(profiles);
+        }
+        files
+    }
+
     /// Get the current RAPS configuration
     pub fn raps_config(&self) -> &RapsConfig {
         &self.raps_config
@@ -283,19 +355,35 @@ impl ConfigManager {
         &self.profiles
     }
 
+    /// Environment of every profile, so single steps can run as another
+    /// account without switching the current profile. The access token is
+    /// left out: the RAPS CLI reads the profile's current token when each
+    /// command runs, so a refreshed token is picked up.
+    pub fn profile_environments(&self) -> ProfileEnvironments {
+        self.profiles
+            .iter()
+            .map(|(name, profile)| {
+                let mut env = profile.raps_config.environment();
+                env.remove(EnvVars::ACCESS_TOKEN);
+                env.insert(EnvVars::PROFILE.to_string(), name.clone());
+                (name.clone(), env)
+            })
+            .collect()
+    }
+
     /// Get the current profile name
     pub fn current_profile(&self) -> Option<&str> {
         self.raps_config.current_profile.as_deref()
     }
 
     /// Switch to a different profile
-    pub fn switch_profile(&mut self, profile_name: &str) -> Result<()> {
+    pub fn switch_profile(&mut self, profile_name: &str) -> Result<(), RapsDemoError> {
         if !self.profiles.contains_key(profile_name) {
-            return Err(anyhow::anyhow!("Profile '{}' not found", profile_name));
+            return Err(ConfigError::ProfileNotFound(profile_name.to_string()).into());
         }
 
         self.raps_config.current_profile = Some(profile_name.to_string());
-        self.apply_profile(profile_name)?;
+        self.apply_profile(profile_name).map_err(ConfigError::Other)?;
 
         // Mark profile as used
         if let Some(profile) = self.profiles.get_mut(profile_name) {
@@ -344,33 +432,37 @@ impl ConfigManager {
     }
 
     /// Save the current configuration to files
-    pub async fn save(&self) -> Result<()> {
+    pub async fn save(&self) -> Result<(), RapsDemoError> {
         tracing::debug!("Saving configuration");
 
         // Save RAPS configuration
         let raps_config_file = self.config_dir.join(ConfigPaths::RAPS_CONFIG_FILE);
         let raps_content = toml::to_string_pretty(&self.raps_config)
-            .context("Failed to serialize RAPS configuration")?;
+            .context("Failed to serialize RAPS configuration")
+            .map_err(ConfigError::Other)?;
         async_fs::write(&raps_config_file, raps_content)
             .await
-            .context("Failed to write RAPS configuration file")?;
+            .context("Failed to write RAPS configuration file")
+            .map_err(ConfigError::Other)?;
 
         // Save demo configuration
-        let demo_config_file = self.config_dir.join(ConfigPaths::DEMO_CONFIG_FILE);
+        let demo_config_file = self.demo_config_path();
         let demo_content = toml::to_string_pretty(&self.demo_config)
-            .context("Failed to serialize demo configuration")?;
+            .context("Failed to serialize demo configuration")
+            .map_err(ConfigError::Other)?;
         async_fs::write(&demo_config_file, demo_content)
             .await
-            .context("Failed to write demo configuration file")?;
+            .context("Failed to write demo configuration file")
+            .map_err(ConfigError::Other)?;
 
         tracing::info!("Configuration saved successfully");
         Ok(())
     }
 
     /// Create a new profile
-    pub async fn create_profile(&mut self, name: String, description: Option<String>) -> Result<()> {
+    pub async fn create_profile(&mut self, name: String, description: Option<String>) -> Result<(), RapsDemoError> {
         if self.profiles.contains_key(&name) {
-            return Err(anyhow::anyhow!("Profile '{}' already exists", name));
+            return Err(ConfigError::ProfileExists(name).into());
         }
 
         let profile = Profile::new(name.clone(), description);
@@ -378,12 +470,16 @@ impl ConfigManager {
         // Save profile to file
         let profiles_dir = self.config_dir.join(ConfigPaths::PROFILES_DIR);
         if !profiles_dir.exists() {
-            async_fs::create_dir_all(&profiles_dir).await?;
+            async_fs::create_dir_all(&profiles_dir)
+                .await
+                .map_err(|e| ConfigError::Other(e.into()))?;
         }
 
         let profile_file = profiles_dir.join(format!("{}.toml", name));
-        let content = toml::to_string_pretty(&profile)?;
-        async_fs::write(&profile_file, content).await?;
+        let content = toml::to_string_pretty(&profile).map_err(|e| ConfigError::Other(e.into()))?;
+        async_fs::write(&profile_file, content)
+            .await
+            .map_err(|e| ConfigError::Other(e.into()))?;
 
         self.profiles.insert(name.clone(), profile);
         tracing::info!("Created profile: {}", name);
@@ -392,11 +488,11 @@ impl ConfigManager {
     }
 
     /// Validate and refresh authentication if needed
-    pub async fn validate_and_refresh_auth(&mut self) -> Result<ValidationResult> {
+    pub async fn validate_and_refresh_auth(&mut self) -> Result<ValidationResult, RapsDemoError> {
         tracing::debug!("Validating authentication");
 
         // First validate current credentials
-        let mut validation_result = self.auth_validator.validate_credentials(&self.raps_config).await?;
+        let mut validation_result = self.validate_auth_only().await?;
 
         // If tokens are expired or expiring soon, try to refresh
         if let Some(tokens) = &self.raps_config.auth_tokens {
@@ -406,7 +502,7 @@ impl ConfigManager {
                 match TokenRefresher::refresh_token(&self.raps_config).await {
                     Ok(Some(new_tokens)) => {
                         self.raps_config.auth_tokens = Some(new_tokens);
-                        validation_result = self.auth_validator.validate_credentials(&self.raps_config).await?;
+                        validation_result = self.validate_auth_only().await?;
                         tracing::info!("Successfully refreshed authentication tokens");
                     }
                     Ok(None) => {
@@ -433,13 +529,16 @@ impl ConfigManager {
     }
 
     /// Check APS connectivity
-    pub async fn check_aps_connectivity(&self) -> Result<bool> {
-        self.auth_validator.check_connectivity().await
+    pub async fn check_aps_connectivity(&self) -> Result<bool, RapsDemoError> {
+        self.auth_validator.check_connectivity().await.map_err(|e| ConfigError::Other(e).into())
     }
 
     /// Validate authentication without refreshing
-    pub async fn validate_auth_only(&self) -> Result<ValidationResult> {
-        self.auth_validator.validate_credentials(&self.raps_config).await
+    pub async fn validate_auth_only(&self) -> Result<ValidationResult, RapsDemoError> {
+        self.auth_validator
+            .validate_credentials(&self.raps_config)
+            .await
+            .map_err(|e| ConfigError::Other(e).into())
     }
 
     /// Update authentication tokens (e.g., after manual login)
@@ -464,21 +563,23 @@ impl ConfigManager {
     }
 
     /// Delete a profile
-    pub async fn delete_profile(&mut self, name: &str) -> Result<()> {
+    pub async fn delete_profile(&mut self, name: &str) -> Result<(), RapsDemoError> {
         if !self.profiles.contains_key(name) {
-            return Err(anyhow::anyhow!("Profile '{}' not found", name));
+            return Err(ConfigError::ProfileNotFound(name.to_string()).into());
         }
 
         // Don't allow deleting the current profile
         if self.current_profile() == Some(name) {
-            return Err(anyhow::anyhow!("Cannot delete the current profile"));
+            return Err(ConfigError::CurrentProfile(name.to_string()).into());
         }
 
         // Remove profile file
         let profiles_dir = self.config_dir.join(ConfigPaths::PROFILES_DIR);
         let profile_file = profiles_dir.join(format!("{}.toml", name));
         if profile_file.exists() {
-            async_fs::remove_file(&profile_file).await?;
+            async_fs::remove_file(&profile_file)
+                .await
+                .map_err(|e| ConfigError::Other(e.into()))?;
         }
 
         self.profiles.remove(name);
@@ -572,6 +673,49 @@ mod tests {
         manager.switch_profile("test-profile").unwrap();
         assert_eq!(manager.current_profile(), Some("test-profile"));
 
+        // Every profile's credentials are available as an environment
+        let profile = manager.profiles.get_mut("test-profile").unwrap();
+        profile.raps_config.client_id = "account-b".to_string();
+        profile.raps_config.auth_tokens = Some(AuthTokens {
+            access_token: "pinned".to_string(),
+            refresh_token: None,
+            expires_at: chrono::Utc::now() + chrono::Duration::hours(1),
+            scopes: Vec::new(),
+        });
+        let environments = manager.profile_environments();
+        let env = &environments["test-profile"];
+        assert_eq!(env[EnvVars::CLIENT_ID], "account-b");
+        assert_eq!(env[EnvVars::PROFILE], "test-profile");
+        assert!(!env.contains_key(EnvVars::ACCESS_TOKEN));
+
         env::remove_var(EnvVars::CONFIG_DIR);
     }
+
+    #[tokio::test]
+    async fn test_explicit_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("missing.toml");
+        let err = ConfigManager::with_config_path(&missing).await.unwrap_err();
+        assert!(err.to_string().contains("Configuration file not found"));
+
+        let config = DemoConfig {
+            workflows_path: vec![PathBuf::from("/opt/demo/workflows")],
+            ..Default::default()
+        };
+        let file = temp_dir.path().join("custom.toml");
+        std::fs::write(&file, toml::to_string(&config).unwrap()).unwrap();
+
+        let manager = ConfigManager::with_config_path(&file).await.unwrap();
+        assert_eq!(manager.config_dir, temp_dir.path());
+        assert_eq!(manager.demo_config().workflows_path, vec![PathBuf::from("/opt/demo/workflows")]);
+
+        // Saved back to the same file, not to the default one next to it
+        let mut manager = manager;
+        manager.demo_config.max_output_bytes = 1024;
+        manager.save().await.unwrap();
+        assert!(!temp_dir.path().join(ConfigPaths::DEMO_CONFIG_FILE).exists());
+        let reloaded = ConfigManager::with_config_path(&file).await.unwrap();
+        assert_eq!(reloaded.demo_config().max_output_bytes, 1024);
+        assert_eq!(reloaded.demo_config().workflows_path, vec![PathBuf::from("/opt/demo/workflows")]);
+    }
 }
\ No newline at end of file
@@ -5,7 +5,9 @@
 
 pub mod auth;
 pub mod manager;
+pub mod secrets;
 pub mod types;
+pub mod watch;
 
 // Re-export commonly used types
 pub use manager::ConfigManager;
@@ -0,0 +1,127 @@
+// Secret lookup for RAPS Demo Workflows
+//
+// Workflow steps can request secrets (e.g. extra tokens for Design Automation
+// activities) by key instead of embedding them in YAML. Keys are resolved
+// from, in order: a `RAPS_SECRET_<KEY>` environment variable, the OS keyring
+// and the `secrets.toml` file in the RAPS config directory.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::types::ConfigPaths;
+
+/// Keyring service name secrets are stored under
+pub const KEYRING_SERVICE: &str = "raps-demo";
+
+/// Prefix of environment variables that provide secrets
+pub const SECRET_ENV_PREFIX: &str = "RAPS_SECRET_";
+
+/// Resolves secret keys to their values
+#[derive(Debug, Clone, Default)]
+pub struct SecretStore {
+    /// `secrets.toml` to read from, if any
+    file: Option<PathBuf>,
+    /// Whether the OS keyring is consulted
+    use_keyring: bool,
+}
+
+impl SecretStore {
+    /// Store using the environment, the OS keyring and the default secrets file
+    pub fn new() -> Self {
+        Self {
+            file: ConfigPaths::secrets_file().ok(),
+            use_keyring: true,
+        }
+    }
+
+    /// Store using only the environment and the given secrets file
+    pub fn with_file<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            file: Some(path.as_ref().to_path_buf()),
+            use_keyring: false,
+        }
+    }
+
+    /// Resolve a secret, failing with setup instructions if it is missing
+    pub fn resolve(&self, key: &str) -> Result<String> {
+        if let Ok(value) = std::env::var(secret_env_var(key)) {
+            return Ok(value);
+        }
+
+        if self.use_keyring {
+            match keyring::Entry::new(KEYRING_SERVICE, key).and_then(|entry| entry.get_password()) {
+                Ok(value) => return Ok(value),
+                Err(keyring::Error::NoEntry) => {},
+                Err(e) => tracing::debug!("Keyring lookup for secret '{}' failed: {}", key, e),
+            }
+        }
+
+        if let Some(value) = self.file_secrets()?.remove(key) {
+            return Ok(value);
+        }
+
+        anyhow::bail!(
+            "Secret '{}' not found; set {}, store it in the keyring (service '{}') or add it to {}",
+            key,
+            secret_env_var(key),
+            KEYRING_SERVICE,
+            self.file
+                .as_ref()
+                .map_or_else(|| ConfigPaths::SECRETS_FILE.to_string(), |p| p.display().to_string())
+        )
+    }
+
+    /// Resolve a map of environment variable name to secret key
+    pub fn resolve_all(&self, secrets: &HashMap<String, String>) -> Result<HashMap<String, String>> {
+        secrets
+            .iter()
+            .map(|(var, key)| Ok((var.clone(), self.resolve(key)?)))
+            .collect()
+    }
+
+    /// Secrets declared in the secrets file
+    fn file_secrets(&self) -> Result<HashMap<String, String>> {
+        let Some(path) = self.file.as_ref().filter(|p| p.exists()) else {
+            return Ok(HashMap::new());
+        };
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read secrets file: {:?}", path))?;
+        toml::from_str(&content).with_context(|| format!("Failed to parse secrets file: {:?}", path))
+    }
+}
+
+/// Environment variable consulted for a secret key (`da-token` -> `RAPS_SECRET_DA_TOKEN`)
+pub fn secret_env_var(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    format!("{}{}", SECRET_ENV_PREFIX, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_secret_resolution_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("secrets.toml");
+        fs::write(&path, "file-only = \"from-file\"\nshadowed = \"from-file\"\n").unwrap();
+        std::env::set_var("RAPS_SECRET_SHADOWED", "from-env");
+
+        let store = SecretStore::with_file(&path);
+        assert_eq!(store.resolve("file-only").unwrap(), "from-file");
+        assert_eq!(store.resolve("shadowed").unwrap(), "from-env");
+
+        let error = store.resolve("missing-key").unwrap_err().to_string();
+        assert!(error.contains("RAPS_SECRET_MISSING_KEY"));
+
+        let mapping = HashMap::from([("DA_TOKEN".to_string(), "file-only".to_string())]);
+        assert_eq!(store.resolve_all(&mapping).unwrap()["DA_TOKEN"], "from-file");
+        std::env::remove_var("RAPS_SECRET_SHADOWED");
+    }
+}
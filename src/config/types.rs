@@ -6,27 +6,33 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 use crate::resource::CleanupPolicy;
+use crate::tui::glyphs::GlyphMode;
+use crate::tui::i18n::Locale;
+use crate::utils::update::UpdateConfig;
+use crate::workflow::chaos::ChaosOptions;
+use crate::workflow::metrics::MetricsConfig;
+use crate::workflow::policy::StepPolicy;
+use crate::workflow::environment::EnvironmentFilter;
+use crate::workflow::rate_limit::RateLimitConfig;
+use crate::workflow::region::Region;
+use crate::workflow::watchdog::WatchdogConfig;
 
 /// Log level for the demo system
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LogLevel {
     Error,
     Warn,
+    #[default]
     Info,
     Debug,
     Trace,
 }
 
-impl Default for LogLevel {
-    fn default() -> Self {
-        LogLevel::Info
-    }
-}
-
 impl std::fmt::Display for LogLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -50,14 +56,88 @@ pub struct DemoConfig {
     pub log_level: LogLevel,
     /// Base path for asset files
     pub asset_base_path: PathBuf,
+    /// Directories workflows are discovered in (a path or a list of paths);
+    /// workflows in later directories override same-ID ones in earlier ones
+    #[serde(
+        default = "default_workflows_path",
+        alias = "workflows_dir",
+        with = "crate::utils::serde_helpers::one_or_many"
+    )]
+    pub workflows_path: Vec<PathBuf>,
+    /// List the workflow pack embedded in the binary under "Built-in"
+    #[serde(default = "default_builtin_workflows")]
+    pub builtin_workflows: bool,
+    /// Fail validation and steps on undefined `{placeholders}` instead of
+    /// passing them to RAPS verbatim
+    #[serde(default = "default_strict_placeholders")]
+    pub strict_placeholders: bool,
+    /// Refresh the APS token and retry a step once when it fails with an
+    /// authentication error
+    #[serde(default = "default_auto_reauth")]
+    pub auto_reauth: bool,
+    /// Bytes of each output stream kept in step results (0 keeps everything);
+    /// longer output is truncated and saved in full under `outputs/`
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: usize,
     /// Base path for temporary directories
     pub temp_dir_base: PathBuf,
+    /// Keep each run's temporary directory (`{temp_dir}`) after it
+    /// finishes, e.g. to look at downloaded files
+    #[serde(default)]
+    pub keep_temp_dirs: bool,
     /// Maximum workflow execution timeout in seconds
     pub max_execution_timeout_seconds: u64,
+    /// Seconds between `translate status` polls of steps waiting for a
+    /// translation (`wait: true`)
+    #[serde(default = "default_translation_poll_interval_seconds")]
+    pub translation_poll_interval_seconds: u64,
+    /// Longest time in seconds a step waits for a translation to finish
+    #[serde(default = "default_translation_timeout_seconds")]
+    pub translation_timeout_seconds: u64,
     /// Whether to show cost warnings
     pub show_cost_warnings: bool,
     /// Cost warning threshold in USD
     pub cost_warning_threshold: f64,
+    /// Prometheus metrics exporter settings
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    /// Language of the TUI (en, ja or de)
+    #[serde(default)]
+    pub locale: Locale,
+    /// Icon set: auto (detect the terminal), unicode or ascii
+    #[serde(default)]
+    pub glyphs: GlyphMode,
+    /// Console lines kept in the TUI; older ones stay in the session log
+    #[serde(default = "default_console_log_lines")]
+    pub console_log_lines: usize,
+    /// Restrictions on what workflow steps may run
+    #[serde(default)]
+    pub policy: StepPolicy,
+    /// Limits on how fast RAPS commands are sent, and retries after a 429
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    /// Which of the presenter's environment variables RAPS commands inherit
+    #[serde(default)]
+    pub subprocess_env: EnvironmentFilter,
+    /// Detection of runs stuck without step progress
+    #[serde(default)]
+    pub watchdog: WatchdogConfig,
+    /// Seed for generated names (`{uuid}`, `{random:N}`), for reproducible demos
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Faults injected into step commands, for teaching troubleshooting
+    #[serde(default)]
+    pub chaos: ChaosOptions,
+    /// Environment whose `vars.<env>.yaml` overlays the workflows' shared variables
+    #[serde(default)]
+    pub vars_env: Option<String>,
+    /// Region (US, EMEA or APAC) buckets are created and models translated
+    /// in when a step names none
+    #[serde(default)]
+    pub region: Option<Region>,
+    /// Startup check for newer releases and `self-update` settings
+    #[serde(default)]
+    pub update: UpdateConfig,
 }
 
 impl Default for DemoConfig {
@@ -66,15 +146,74 @@ impl Default for DemoConfig {
             default_cleanup_policy: CleanupPolicy::default(),
             max_concurrent_workflows: 3,
             log_level: LogLevel::default(),
-            asset_base_path: PathBuf::from("Assets"),
+            asset_base_path: PathBuf::from(DemoConfig::DEFAULT_ASSET_BASE_PATH),
+            workflows_path: default_workflows_path(),
+            builtin_workflows: default_builtin_workflows(),
+            strict_placeholders: default_strict_placeholders(),
+            auto_reauth: default_auto_reauth(),
+            max_output_bytes: default_max_output_bytes(),
             temp_dir_base: std::env::temp_dir(),
+            keep_temp_dirs: false,
             max_execution_timeout_seconds: 1800, // 30 minutes
+            translation_poll_interval_seconds: default_translation_poll_interval_seconds(),
+            translation_timeout_seconds: default_translation_timeout_seconds(),
             show_cost_warnings: true,
             cost_warning_threshold: 1.0, // $1.00
+            metrics: MetricsConfig::default(),
+            locale: Locale::default(),
+            glyphs: GlyphMode::default(),
+            console_log_lines: default_console_log_lines(),
+            policy: StepPolicy::default(),
+            rate_limit: RateLimitConfig::default(),
+            subprocess_env: EnvironmentFilter::default(),
+            watchdog: WatchdogConfig::default(),
+            seed: None,
+            chaos: ChaosOptions::default(),
+            vars_env: None,
+            region: None,
+            update: UpdateConfig::default(),
         }
     }
 }
 
+impl DemoConfig {
+    /// `asset_base_path` unless configured; assets are then downloaded to
+    /// [`ConfigPaths::DEFAULT_ASSETS_DIR`]
+    pub const DEFAULT_ASSET_BASE_PATH: &'static str = "Assets";
+}
+
+fn default_workflows_path() -> Vec<PathBuf> {
+    vec![PathBuf::from(ConfigPaths::DEFAULT_WORKFLOWS_DIR)]
+}
+
+fn default_builtin_workflows() -> bool {
+    true
+}
+
+fn default_strict_placeholders() -> bool {
+    true
+}
+
+fn default_auto_reauth() -> bool {
+    true
+}
+
+fn default_console_log_lines() -> usize {
+    crate::tui::console::DEFAULT_CONSOLE_CAPACITY
+}
+
+fn default_max_output_bytes() -> usize {
+    crate::workflow::output::DEFAULT_MAX_OUTPUT_BYTES
+}
+
+fn default_translation_poll_interval_seconds() -> u64 {
+    5
+}
+
+fn default_translation_timeout_seconds() -> u64 {
+    2 * 60 * 60
+}
+
 /// APS authentication tokens
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AuthTokens {
@@ -159,6 +298,23 @@ impl RapsConfig {
             .filter(|tokens| !tokens.is_expired())
             .map(|tokens| tokens.access_token.as_str())
     }
+
+    /// Environment variables making the RAPS CLI use these credentials
+    pub fn environment(&self) -> std::collections::HashMap<String, String> {
+        let mut env = std::collections::HashMap::new();
+        let mut set = |name: &str, value: &str| {
+            if !value.is_empty() {
+                env.insert(name.to_string(), value.to_string());
+            }
+        };
+        set(EnvVars::CLIENT_ID, &self.client_id);
+        set(EnvVars::CLIENT_SECRET, &self.client_secret);
+        set(EnvVars::CALLBACK_URL, self.callback_url.as_deref().unwrap_or_default());
+        set(EnvVars::ENVIRONMENT, &self.environment);
+        set(EnvVars::BASE_URL, &self.base_url);
+        set(EnvVars::ACCESS_TOKEN, self.get_access_token().unwrap_or_default());
+        env
+    }
 }
 
 /// Profile configuration for different environments or accounts
@@ -259,11 +415,17 @@ impl EnvVars {
     pub const PROFILE: &'static str = "RAPS_PROFILE";
     pub const CONFIG_DIR: &'static str = "RAPS_CONFIG_DIR";
     pub const LOG_LEVEL: &'static str = "RAPS_LOG_LEVEL";
+    pub const LOCALE: &'static str = "RAPS_LOCALE";
+    /// Workflows directories, separated like `PATH`
+    pub const WORKFLOWS_PATH: &'static str = "RAPS_WORKFLOWS_PATH";
 }
 
 /// Configuration file paths and names
 pub struct ConfigPaths;
 
+/// Configuration directory given with `--config`
+static CONFIG_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
 impl ConfigPaths {
     /// Default RAPS configuration directory name
     pub const CONFIG_DIR_NAME: &'static str = ".raps";
@@ -280,6 +442,55 @@ impl ConfigPaths {
     /// Credentials file name
     pub const CREDENTIALS_FILE: &'static str = "credentials.toml";
     
+    /// Persisted TUI layout/state file name
+    pub const UI_STATE_FILE: &'static str = "ui-state.toml";
+    
+    /// TUI key bindings file name
+    pub const KEYMAP_FILE: &'static str = "keymap.toml";
+    
+    /// Execution duration history file name
+    pub const HISTORY_FILE: &'static str = "history.toml";
+    
+    /// Session log directory name
+    pub const LOGS_DIR: &'static str = "logs";
+    
+    /// Step secrets file name
+    pub const SECRETS_FILE: &'static str = "secrets.toml";
+    
+    /// Run artifacts directory name
+    pub const ARTIFACTS_DIR: &'static str = "artifacts";
+    
+    /// Full step output directory name
+    pub const OUTPUTS_DIR: &'static str = "outputs";
+    
+    /// Crash report directory name
+    pub const CRASHES_DIR: &'static str = "crashes";
+    
+    /// Installed workflow packs directory name
+    pub const PACKS_DIR: &'static str = "packs";
+    
+    /// Scheduled runs file name
+    pub const SCHEDULE_FILE: &'static str = "schedule.toml";
+    
+    /// Cache of parsed workflow files
+    pub const PARSE_CACHE_FILE: &'static str = "workflow-cache.json";
+    
+    /// Outcome of the last check for a newer release
+    pub const UPDATE_CHECK_FILE: &'static str = "update-check.toml";
+    
+    /// Resources created by runs, kept in the configuration directory given
+    /// with `--config`
+    pub const RESOURCE_STATE_FILE: &'static str = "resource_tracker.json";
+    
+    /// Default directory workflows are discovered in
+    pub const DEFAULT_WORKFLOWS_DIR: &'static str = "./workflows";
+    
+    /// Default directory for downloaded sample assets
+    pub const DEFAULT_ASSETS_DIR: &'static str = "./sample-models/autodesk";
+    
+    /// Manifest of additional sample assets, read from the working directory
+    pub const DEFAULT_ASSETS_MANIFEST: &'static str = "./assets.yaml";
+    
     /// Get the default configuration directory
     pub fn default_config_dir() -> Result<PathBuf> {
         dirs::home_dir()
@@ -306,6 +517,97 @@ impl ConfigPaths {
     pub fn credentials_file() -> Result<PathBuf> {
         Ok(Self::default_config_dir()?.join(Self::CREDENTIALS_FILE))
     }
+    
+    /// Configuration directory of an explicit configuration path: the path
+    /// itself if it is a directory, or else the directory of the file
+    pub fn config_dir_of(path: &Path) -> PathBuf {
+        if path.is_dir() {
+            return path.to_path_buf();
+        }
+        match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        }
+    }
+    
+    /// Keep the state, history, cache and log files of this process in `dir`
+    /// (the directory of `--config`), ahead of `RAPS_CONFIG_DIR`; only the
+    /// first call has an effect
+    pub fn set_config_dir(dir: PathBuf) {
+        let _ = CONFIG_DIR_OVERRIDE.set(dir);
+    }
+    
+    /// The directory given to [`ConfigPaths::set_config_dir`], if any
+    pub fn config_dir_override() -> Option<&'static Path> {
+        CONFIG_DIR_OVERRIDE.get().map(PathBuf::as_path)
+    }
+    
+    /// Get the configuration directory, honoring `--config` and
+    /// `RAPS_CONFIG_DIR`
+    pub fn resolved_config_dir() -> Result<PathBuf> {
+        if let Some(dir) = Self::config_dir_override() {
+            return Ok(dir.to_path_buf());
+        }
+        match std::env::var(EnvVars::CONFIG_DIR) {
+            Ok(dir) => Ok(PathBuf::from(dir)),
+            Err(_) => Self::default_config_dir(),
+        }
+    }
+    
+    /// Get the TUI state file path
+    pub fn ui_state_file() -> Result<PathBuf> {
+        Ok(Self::resolved_config_dir()?.join(Self::UI_STATE_FILE))
+    }
+    
+    /// Get the TUI keymap file path
+    pub fn keymap_file() -> Result<PathBuf> {
+        Ok(Self::resolved_config_dir()?.join(Self::KEYMAP_FILE))
+    }
+    
+    /// Get the execution duration history file path
+    pub fn history_file() -> Result<PathBuf> {
+        Ok(Self::resolved_config_dir()?.join(Self::HISTORY_FILE))
+    }
+    
+    /// Get the parsed workflow cache file path
+    pub fn parse_cache_file() -> Result<PathBuf> {
+        Ok(Self::resolved_config_dir()?.join(Self::PARSE_CACHE_FILE))
+    }
+    
+    /// Get the session log directory path
+    pub fn logs_dir() -> Result<PathBuf> {
+        Ok(Self::resolved_config_dir()?.join(Self::LOGS_DIR))
+    }
+    
+    /// Get the step secrets file path
+    pub fn secrets_file() -> Result<PathBuf> {
+        Ok(Self::resolved_config_dir()?.join(Self::SECRETS_FILE))
+    }
+    
+    /// Get the crash report directory path
+    pub fn crashes_dir() -> Result<PathBuf> {
+        Ok(Self::resolved_config_dir()?.join(Self::CRASHES_DIR))
+    }
+    
+    /// Get the run artifacts directory path
+    pub fn artifacts_dir() -> Result<PathBuf> {
+        Ok(Self::resolved_config_dir()?.join(Self::ARTIFACTS_DIR))
+    }
+    
+    /// Get the full step output directory path
+    pub fn outputs_dir() -> Result<PathBuf> {
+        Ok(Self::resolved_config_dir()?.join(Self::OUTPUTS_DIR))
+    }
+    
+    /// Get the installed workflow packs directory path
+    pub fn packs_dir() -> Result<PathBuf> {
+        Ok(Self::resolved_config_dir()?.join(Self::PACKS_DIR))
+    }
+    
+    /// Get the scheduled runs file path
+    pub fn schedule_file() -> Result<PathBuf> {
+        Ok(Self::resolved_config_dir()?.join(Self::SCHEDULE_FILE))
+    }
 }
 
 #[cfg(test)]
@@ -327,6 +629,15 @@ mod tests {
         assert_eq!(config.log_level, LogLevel::Info);
         assert!(config.show_cost_warnings);
         assert_eq!(config.cost_warning_threshold, 1.0);
+        assert_eq!(config.asset_base_path, PathBuf::from("Assets"));
+    }
+
+    #[test]
+    fn test_config_dir_of() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(ConfigPaths::config_dir_of(temp_dir.path()), temp_dir.path());
+        assert_eq!(ConfigPaths::config_dir_of(&temp_dir.path().join("demo.toml")), temp_dir.path());
+        assert_eq!(ConfigPaths::config_dir_of(Path::new("demo.toml")), PathBuf::from("."));
     }
 
     #[test]
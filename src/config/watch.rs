@@ -0,0 +1,252 @@
+// Hot reload of the configuration
+//
+// The TUI stays open for a whole session while the presenter edits
+// credentials, profiles or demo settings in another terminal. The watcher
+// polls the configuration files by modification time, as `raps-demo watch`
+// does for workflows, reloads them when one changed and reports what is
+// different: the credentials and the environment each profile gives the RAPS
+// CLI, and which demo settings changed, naming those only a restart applies.
+// Files that no longer parse leave the previous configuration in place, so a
+// half-saved edit never takes effect.
+
+use super::types::DemoConfig;
+use super::ConfigManager;
+use crate::error::RapsDemoError;
+use crate::workflow::watch::FileStamps;
+
+/// What a reload changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigChanges {
+    /// The credentials (or the tokens) the RAPS CLI gets changed
+    pub credentials: bool,
+    /// Profiles added, removed or with another environment, by name
+    pub profiles: Vec<String>,
+    /// Demo settings applied to the next runs, as named in `demo.toml`
+    pub settings: Vec<&'static str>,
+    /// Demo settings that only take effect after a restart
+    pub restart_required: Vec<&'static str>,
+}
+
+impl ConfigChanges {
+    /// Differences between the configuration before and after a reload
+    pub fn between(old: &ConfigManager, new: &ConfigManager) -> Self {
+        let old_profiles = old.profile_environments();
+        let new_profiles = new.profile_environments();
+        let mut profiles: Vec<String> = old_profiles
+            .keys()
+            .chain(new_profiles.keys())
+            .filter(|name| old_profiles.get(*name) != new_profiles.get(*name))
+            .cloned()
+            .collect();
+        profiles.sort();
+        profiles.dedup();
+
+        let (settings, restart_required) = changed_settings(old.demo_config(), new.demo_config());
+        Self {
+            credentials: old.raps_config().environment() != new.raps_config().environment(),
+            profiles,
+            settings,
+            restart_required,
+        }
+    }
+
+    /// Whether nothing that matters changed (e.g. only a comment was edited)
+    pub fn is_empty(&self) -> bool {
+        !self.credentials && self.profiles.is_empty() && self.settings.is_empty() && self.restart_required.is_empty()
+    }
+
+    /// `current` with the settings this reload changed taken from `reloaded`;
+    /// the rest, including command-line overrides such as `--seed`, stay
+    pub fn apply_settings(&self, current: &DemoConfig, reloaded: &DemoConfig) -> DemoConfig {
+        let mut config = current.clone();
+        for setting in &self.settings {
+            match *setting {
+                "locale" => config.locale = reloaded.locale,
+                "glyphs" => config.glyphs = reloaded.glyphs,
+                "console_log_lines" => config.console_log_lines = reloaded.console_log_lines,
+                "strict_placeholders" => config.strict_placeholders = reloaded.strict_placeholders,
+                "auto_reauth" => config.auto_reauth = reloaded.auto_reauth,
+                "max_output_bytes" => config.max_output_bytes = reloaded.max_output_bytes,
+                "max_execution_timeout_seconds" => {
+                    config.max_execution_timeout_seconds = reloaded.max_execution_timeout_seconds
+                },
+                "keep_temp_dirs" => config.keep_temp_dirs = reloaded.keep_temp_dirs,
+                "policy" => config.policy = reloaded.policy.clone(),
+                "watchdog" => config.watchdog = reloaded.watchdog.clone(),
+                "seed" => config.seed = reloaded.seed,
+                "chaos" => config.chaos = reloaded.chaos.clone(),
+                "vars_env" => config.vars_env = reloaded.vars_env.clone(),
+                "region" => config.region = reloaded.region,
+                _ => {},
+            }
+        }
+        config
+    }
+
+    /// One line per kind of change, for the console and the toast
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.credentials {
+            lines.push("Credentials changed".to_string());
+        }
+        if !self.profiles.is_empty() {
+            lines.push(format!("Profiles changed: {}", self.profiles.join(", ")));
+        }
+        if !self.settings.is_empty() {
+            lines.push(format!("Settings applied: {}", self.settings.join(", ")));
+        }
+        if !self.restart_required.is_empty() {
+            lines.push(format!("Restart to apply: {}", self.restart_required.join(", ")));
+        }
+        lines
+    }
+}
+
+/// Changed settings that apply to the next runs, and those that need a restart
+fn changed_settings(old: &DemoConfig, new: &DemoConfig) -> (Vec<&'static str>, Vec<&'static str>) {
+    let live = [
+        ("locale", old.locale != new.locale),
+        ("glyphs", old.glyphs != new.glyphs),
+        ("console_log_lines", old.console_log_lines != new.console_log_lines),
+        ("strict_placeholders", old.strict_placeholders != new.strict_placeholders),
+        ("auto_reauth", old.auto_reauth != new.auto_reauth),
+        ("max_output_bytes", old.max_output_bytes != new.max_output_bytes),
+        ("max_execution_timeout_seconds", old.max_execution_timeout_seconds != new.max_execution_timeout_seconds),
+        ("keep_temp_dirs", old.keep_temp_dirs != new.keep_temp_dirs),
+        ("policy", old.policy != new.policy),
+        ("watchdog", old.watchdog != new.watchdog),
+        ("seed", old.seed != new.seed),
+        ("chaos", old.chaos != new.chaos),
+        ("vars_env", old.vars_env != new.vars_env),
+        ("region", old.region != new.region),
+    ];
+    let restart = [
+        ("workflows_path", old.workflows_path != new.workflows_path),
+        ("builtin_workflows", old.builtin_workflows != new.builtin_workflows),
+        ("asset_base_path", old.asset_base_path != new.asset_base_path),
+        ("rate_limit", old.rate_limit != new.rate_limit),
+        (
+            "translation_poll_interval_seconds",
+            old.translation_poll_interval_seconds != new.translation_poll_interval_seconds,
+        ),
+        ("translation_timeout_seconds", old.translation_timeout_seconds != new.translation_timeout_seconds),
+        ("subprocess_env", old.subprocess_env != new.subprocess_env),
+        ("metrics", old.metrics != new.metrics),
+        ("update", old.update != new.update),
+    ];
+    let changed = |fields: &[(&'static str, bool)]| fields.iter().filter(|(_, changed)| *changed).map(|(name, _)| *name).collect();
+    (changed(&live), changed(&restart))
+}
+
+/// A loaded configuration, reloaded when its files change
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    manager: ConfigManager,
+    stamps: FileStamps,
+}
+
+impl ConfigWatcher {
+    /// Watch the files `manager` was loaded from
+    pub fn new(manager: ConfigManager) -> Self {
+        let stamps = FileStamps::capture(&manager.config_files());
+        Self { manager, stamps }
+    }
+
+    /// The configuration as last loaded successfully
+    pub fn manager(&self) -> &ConfigManager {
+        &self.manager
+    }
+
+    /// Reload if a file was edited, created or deleted since the last poll:
+    /// `None` if none was, the changes, or why the files couldn't be loaded
+    pub async fn poll(&mut self) -> Option<Result<ConfigChanges, RapsDemoError>> {
+        // Listed again each time, so new profiles are noticed
+        let stamps = FileStamps::capture(&self.manager.config_files());
+        if self.stamps.changed_since(&stamps).is_empty() {
+            return None;
+        }
+        self.stamps = stamps;
+        Some(self.manager.reload().await.map(|reloaded| {
+            let changes = ConfigChanges::between(&self.manager, &reloaded);
+            self.manager = reloaded;
+            changes
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::ConfigPaths;
+    use crate::config::Profile;
+
+    #[tokio::test]
+    async fn test_reload_reports_changes_and_survives_broken_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let demo_file = dir.path().join(ConfigPaths::DEMO_CONFIG_FILE);
+        std::fs::write(&demo_file, toml::to_string(&DemoConfig::default()).unwrap()).unwrap();
+        let mut watcher = ConfigWatcher::new(ConfigManager::with_config_path(dir.path()).await.unwrap());
+        assert!(watcher.poll().await.is_none());
+
+        // A new profile and edited settings
+        let profiles_dir = dir.path().join(ConfigPaths::PROFILES_DIR);
+        std::fs::create_dir_all(&profiles_dir).unwrap();
+        let mut profile = Profile::new("staging".to_string(), None);
+        profile.raps_config.client_id = "staging-client".to_string();
+        std::fs::write(profiles_dir.join("staging.toml"), toml::to_string(&profile).unwrap()).unwrap();
+        let config = DemoConfig {
+            max_output_bytes: 1024,
+            workflows_path: vec![dir.path().join("workflows")],
+            ..Default::default()
+        };
+        std::fs::write(&demo_file, toml::to_string(&config).unwrap()).unwrap();
+
+        let changes = watcher.poll().await.unwrap().unwrap();
+        assert_eq!(changes.profiles, vec!["staging"]);
+        assert_eq!(changes.settings, vec!["max_output_bytes"]);
+        assert_eq!(changes.restart_required, vec!["workflows_path"]);
+        assert_eq!(watcher.manager().demo_config().max_output_bytes, 1024);
+        assert!(watcher.manager().profile_environments().contains_key("staging"));
+        assert!(watcher.poll().await.is_none());
+
+        // A broken edit keeps the previous configuration, and is retried once fixed
+        std::fs::write(&demo_file, "max_output_bytes = [").unwrap();
+        assert!(watcher.poll().await.unwrap().is_err());
+        assert_eq!(watcher.manager().demo_config().max_output_bytes, 1024);
+        std::fs::write(&demo_file, toml::to_string(&DemoConfig::default()).unwrap()).unwrap();
+        let changes = watcher.poll().await.unwrap().unwrap();
+        assert!(changes.settings.contains(&"max_output_bytes"));
+        assert!(changes.profiles.is_empty());
+    }
+
+    #[test]
+    fn test_apply_settings_keeps_command_line_overrides() {
+        // Started with `--seed 7 --keep-temp-dirs --env staging`
+        let current = DemoConfig {
+            seed: Some(7),
+            keep_temp_dirs: true,
+            vars_env: Some("staging".to_string()),
+            ..Default::default()
+        };
+        let reloaded = DemoConfig {
+            max_output_bytes: 1024,
+            ..Default::default()
+        };
+        let changes = ConfigChanges {
+            settings: vec!["max_output_bytes"],
+            ..Default::default()
+        };
+        let config = changes.apply_settings(&current, &reloaded);
+        assert_eq!(config.max_output_bytes, 1024);
+        assert_eq!(config.seed, Some(7));
+        assert!(config.keep_temp_dirs);
+        assert_eq!(config.vars_env.as_deref(), Some("staging"));
+
+        // An edited setting replaces its override
+        let changes = ConfigChanges {
+            settings: vec!["seed"],
+            ..Default::default()
+        };
+        assert_eq!(changes.apply_settings(&current, &reloaded).seed, None);
+    }
+}
@@ -0,0 +1,145 @@
+// Scaffolding for `raps-demo init`
+//
+// Creates a demo environment in one step: a workflows directory holding the
+// built-in workflow pack, an example asset manifest, a starter profile and a
+// .gitignore. Existing files are kept unless `force` is set, so running init
+// again only fills in what is missing.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::assets::{AssetCategory, AssetDefinition, AssetManifest};
+use crate::config::types::{ConfigPaths, Profile};
+use crate::workflow::builtin;
+
+/// Name of the starter profile
+pub const STARTER_PROFILE: &str = "demo";
+
+/// What the demo writes into the directory it runs in; run artifacts,
+/// logs and credentials live in the RAPS config directory instead
+const GITIGNORE: &str = "\
+# Downloaded sample assets
+sample-models/
+# Diagrams and tutorials written by `raps-demo export` (<workflow-id>.<ext>)
+/*.mmd
+/*.dot
+/*.svg
+/*.md
+!/README.md
+# Packs built by `raps-demo pack build`
+*.rapspack
+# Session recordings (--record)
+*.cast
+";
+
+/// Files written (or kept) by `init`
+#[derive(Debug, Default)]
+pub struct InitReport {
+    pub created: Vec<PathBuf>,
+    /// Files that already existed and were left alone
+    pub skipped: Vec<PathBuf>,
+}
+
+/// Scaffold a demo environment in `dir`
+pub fn init_demo_environment(dir: &Path, force: bool) -> Result<InitReport> {
+    let mut report = InitReport::default();
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+    let workflows_dir = dir.join("workflows");
+    std::fs::create_dir_all(&workflows_dir)
+        .with_context(|| format!("Failed to create directory: {}", workflows_dir.display()))?;
+    let written = builtin::extract(&workflows_dir, force)?;
+    for workflow in &builtin::BUILTIN_WORKFLOWS {
+        let path = workflows_dir.join(workflow.path);
+        if written.contains(&path) {
+            report.created.push(path);
+        } else {
+            report.skipped.push(path);
+        }
+    }
+
+    let assets = serde_yaml::to_string(&example_manifest()).context("Failed to serialize asset manifest")?;
+    write_file(&dir.join("assets.yaml"), &assets, force, &mut report)?;
+
+    let profile = Profile::new(
+        STARTER_PROFILE.to_string(),
+        Some("Starter profile created by raps-demo init".to_string()),
+    );
+    let profile = toml::to_string_pretty(&profile).context("Failed to serialize starter profile")?;
+    let profile_path = dir
+        .join(ConfigPaths::PROFILES_DIR)
+        .join(format!("{}.toml", STARTER_PROFILE));
+    write_file(&profile_path, &profile, force, &mut report)?;
+
+    write_file(&dir.join(".gitignore"), GITIGNORE, force, &mut report)?;
+    Ok(report)
+}
+
+/// Example manifest with one extra asset to copy from
+fn example_manifest() -> AssetManifest {
+    AssetManifest {
+        assets: vec![AssetDefinition {
+            name: "Revit Basic Sample Family".to_string(),
+            description: "Small Revit family, quick to upload and translate".to_string(),
+            url: "https://damassets.autodesk.net/content/dam/autodesk/www/revit-downloads/racbasicsamplefamily.rfa"
+                .to_string(),
+            category: AssetCategory::Revit,
+            is_archive: false,
+            estimated_size_mb: 0.3,
+        }],
+    }
+}
+
+/// Write `content` to `path` unless it exists (and `force` is off)
+fn write_file(path: &Path, content: &str, force: bool, report: &mut InitReport) -> Result<()> {
+    if path.exists() && !force {
+        report.skipped.push(path.to_path_buf());
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+    report.created.push(path.to_path_buf());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assets::AssetRegistry;
+    use crate::workflow::diagram::DiagramFormat;
+    use crate::workflow::pack::PACK_EXTENSION;
+
+    #[test]
+    fn test_init_demo_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = init_demo_environment(dir.path(), false).unwrap();
+        assert_eq!(report.created.len(), builtin::BUILTIN_WORKFLOWS.len() + 3);
+        assert!(report.skipped.is_empty());
+        assert!(dir.path().join("workflows/oss/bucket-lifecycle.yaml").is_file());
+        assert!(dir.path().join(".gitignore").is_file());
+
+        // The scaffolded files load back
+        let profile: Profile =
+            toml::from_str(&std::fs::read_to_string(dir.path().join("profiles/demo.toml")).unwrap()).unwrap();
+        assert_eq!(profile.name, STARTER_PROFILE);
+        assert!(AssetRegistry::new().with_manifest(dir.path().join("assets.yaml")).is_ok());
+
+        // Running again keeps everything
+        let report = init_demo_environment(dir.path(), false).unwrap();
+        assert!(report.created.is_empty());
+    }
+
+    #[test]
+    fn test_gitignore_covers_written_files() {
+        let entries: Vec<&str> = GITIGNORE.lines().filter(|line| !line.starts_with('#')).collect();
+        for format in [DiagramFormat::Mermaid, DiagramFormat::Dot, DiagramFormat::Svg, DiagramFormat::Markdown] {
+            assert!(entries.contains(&format!("/*.{}", format.extension()).as_str()));
+        }
+        assert!(entries.contains(&format!("*.{}", PACK_EXTENSION).as_str()));
+        let assets_dir = ConfigPaths::DEFAULT_ASSETS_DIR.trim_start_matches("./");
+        assert!(entries.iter().any(|entry| assets_dir.starts_with(entry.trim_end_matches('/'))));
+    }
+}
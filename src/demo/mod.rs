@@ -3,18 +3,22 @@
 // This module provides workflow discovery, metadata management, and execution
 // orchestration for demo workflows.
 
+pub mod init;
+pub mod preflight;
+
 use anyhow::Result;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::config::types::ConfigPaths;
 use crate::workflow::{
     WorkflowDefinition, WorkflowDiscovery, WorkflowId, WorkflowMetadata
 };
 
 /// Manages demo workflow discovery and organization
 pub struct DemoManager {
-    /// Path to workflows directory
-    workflows_dir: PathBuf,
+    /// Workflows directories, later ones overriding earlier ones
+    workflows_dirs: Vec<PathBuf>,
     /// Workflow discovery instance
     discovery: Option<WorkflowDiscovery>,
     /// Cached workflow metadata
@@ -26,10 +30,10 @@ impl DemoManager {
     pub fn new() -> Result<Self> {
         tracing::debug!("Initializing demo manager");
 
-        let workflows_dir = PathBuf::from("./workflows");
+        let workflows_dir = PathBuf::from(ConfigPaths::DEFAULT_WORKFLOWS_DIR);
 
         Ok(Self {
-            workflows_dir,
+            workflows_dirs: vec![workflows_dir],
             discovery: None,
             workflow_metadata: Vec::new(),
         })
@@ -37,10 +41,13 @@ impl DemoManager {
 
     /// Create a demo manager with a custom workflows directory
     pub fn with_workflows_dir<P: Into<PathBuf>>(path: P) -> Result<Self> {
-        let workflows_dir = path.into();
-        
+        Self::with_workflows_dirs([path.into()])
+    }
+
+    /// Create a demo manager over several workflows directories
+    pub fn with_workflows_dirs<I: IntoIterator<Item = PathBuf>>(paths: I) -> Result<Self> {
         Ok(Self {
-            workflows_dir,
+            workflows_dirs: paths.into_iter().collect(),
             discovery: None,
             workflow_metadata: Vec::new(),
         })
@@ -48,12 +55,14 @@ impl DemoManager {
 
     /// Initialize workflow discovery
     pub fn initialize(&mut self) -> Result<()> {
-        // Ensure workflows directory exists
-        if !self.workflows_dir.exists() {
-            std::fs::create_dir_all(&self.workflows_dir)?;
+        // Ensure workflows directories exist
+        for workflows_dir in &self.workflows_dirs {
+            if !workflows_dir.exists() {
+                std::fs::create_dir_all(workflows_dir)?;
+            }
         }
 
-        let mut discovery = WorkflowDiscovery::new(&self.workflows_dir)?;
+        let mut discovery = WorkflowDiscovery::with_dirs(self.workflows_dirs.clone())?;
         self.workflow_metadata = discovery.discover_workflows()?;
         self.discovery = Some(discovery);
 
@@ -0,0 +1,283 @@
+// Preflight report for `raps-demo preflight`
+//
+// Before an event a demo laptop is checked the night before, often by a
+// provisioning script rather than a person. The report covers the machine
+// (RAPS CLI installed, APS reachable) and then each workflow asked for: the
+// TUI's preflight checks (authentication, assets, other prerequisites) plus
+// whether the stored token carries the scopes the workflow's commands need.
+// It prints as a checklist or as JSON.
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use crate::config::types::AuthTokens;
+use crate::tui::preflight::{CheckAction, CheckResult, PreflightChecker};
+use crate::workflow::client::RapsClient;
+use crate::workflow::{
+    BucketAction, DataMgmtAction, ObjectAction, RapsCommand, TranslateAction, WorkflowDefinition,
+};
+
+/// How long to wait for APS to answer the connectivity check
+const CONNECTIVITY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One line of the checklist
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportCheck {
+    pub name: String,
+    pub passed: bool,
+    pub message: String,
+    /// What to do when the check fails
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl ReportCheck {
+    fn new(name: &str, passed: bool, message: String, fix: Option<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            passed,
+            message,
+            fix,
+        }
+    }
+}
+
+impl From<CheckResult> for ReportCheck {
+    fn from(check: CheckResult) -> Self {
+        let fix = check.action.filter(|_| !check.passed).map(|action| match action {
+            CheckAction::Login => "raps auth login".to_string(),
+            CheckAction::DownloadAssets(assets) => {
+                let names: Vec<String> = assets.iter().map(|asset| asset.name.clone()).collect();
+                format!("download {} (press d in the TUI)", names.join(", "))
+            },
+            CheckAction::RunCommand(command) => command,
+            CheckAction::Instruction(instruction) => instruction,
+        });
+        Self {
+            name: check.name,
+            passed: check.passed,
+            message: check.message,
+            fix,
+        }
+    }
+}
+
+/// Checks of one workflow
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkflowReport {
+    pub id: String,
+    pub ready: bool,
+    pub checks: Vec<ReportCheck>,
+}
+
+/// Checks of the machine and of the workflows asked for
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PreflightReport {
+    /// Whether every check passed
+    pub ready: bool,
+    pub machine: Vec<ReportCheck>,
+    pub workflows: Vec<WorkflowReport>,
+}
+
+impl PreflightReport {
+    pub fn new(machine: Vec<ReportCheck>, workflows: Vec<WorkflowReport>) -> Self {
+        let ready = machine.iter().all(|check| check.passed) && workflows.iter().all(|workflow| workflow.ready);
+        Self {
+            ready,
+            machine,
+            workflows,
+        }
+    }
+
+    /// The report as a checklist, one line per check
+    pub fn checklist(&self) -> Vec<String> {
+        let sections = std::iter::once(("Machine", &self.machine))
+            .chain(self.workflows.iter().map(|workflow| (workflow.id.as_str(), &workflow.checks)));
+        let width = self
+            .machine
+            .iter()
+            .chain(self.workflows.iter().flat_map(|workflow| &workflow.checks))
+            .map(|check| check.name.len())
+            .max()
+            .unwrap_or(0);
+
+        let mut lines = Vec::new();
+        for (title, checks) in sections {
+            lines.push(title.to_string());
+            for check in checks {
+                let mark = if check.passed { "✓" } else { "✗" };
+                lines.push(format!("  {} {:<width$}  {}", mark, check.name, check.message, width = width));
+                if let Some(fix) = &check.fix {
+                    lines.push(format!("    {:<width$}  fix: {}", "", fix, width = width));
+                }
+            }
+        }
+        let ready = self.workflows.iter().filter(|workflow| workflow.ready).count();
+        lines.push(String::new());
+        lines.push(format!(
+            "{} {} of {} workflow(s) ready{}",
+            if self.ready { "✓" } else { "✗" },
+            ready,
+            self.workflows.len(),
+            if self.machine.iter().all(|check| check.passed) { "" } else { ", machine checks failed" }
+        ));
+        lines
+    }
+}
+
+/// Machine checks: the RAPS CLI runs and APS at `base_url` answers
+pub async fn machine_checks(client: &RapsClient, base_url: &str) -> Vec<ReportCheck> {
+    let cli = match client.validate_raps_cli() {
+        Ok(()) => ReportCheck::new("RAPS CLI", true, format!("{} runs", client.config().raps_binary_path), None),
+        Err(e) => ReportCheck::new(
+            "RAPS CLI",
+            false,
+            format!("{:#}", e),
+            Some("install the RAPS CLI or set its path in the configuration".to_string()),
+        ),
+    };
+
+    // Any HTTP answer means the network path works; the status is irrelevant
+    let http = reqwest::Client::builder().timeout(CONNECTIVITY_TIMEOUT).build();
+    let connectivity = match http {
+        Ok(http) => http.get(base_url).send().await.map(|_| ()).map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    let connectivity = match connectivity {
+        Ok(()) => ReportCheck::new("Connectivity", true, format!("{} reachable", base_url), None),
+        Err(e) => ReportCheck::new(
+            "Connectivity",
+            false,
+            format!("{} unreachable: {}", base_url, e),
+            Some("check the network, proxy and firewall settings".to_string()),
+        ),
+    };
+    vec![cli, connectivity]
+}
+
+/// APS scopes the commands of `workflow` need, its steps' and cleanup
+/// commands' alike
+pub fn required_scopes(workflow: &WorkflowDefinition) -> BTreeSet<&'static str> {
+    let commands = workflow
+        .steps
+        .iter()
+        .flat_map(|step| std::iter::once(&step.command).chain(&step.cleanup_commands))
+        .chain(&workflow.cleanup);
+    commands.flat_map(|command| command_scopes(command).iter().copied()).collect()
+}
+
+/// APS scopes one command needs
+fn command_scopes(command: &RapsCommand) -> &'static [&'static str] {
+    match command {
+        RapsCommand::Bucket { action, .. } => match action {
+            BucketAction::Create => &["bucket:create"],
+            BucketAction::Delete => &["bucket:delete"],
+            BucketAction::List | BucketAction::Details => &["bucket:read"],
+        },
+        RapsCommand::Object { action, .. } => match action {
+            ObjectAction::Upload | ObjectAction::Delete => &["data:write"],
+            _ => &["data:read"],
+        },
+        RapsCommand::Translate { action, .. } => match action {
+            TranslateAction::Start => &["data:read", "data:write"],
+            _ => &["data:read"],
+        },
+        RapsCommand::DataManagement { action, .. } => match action {
+            DataMgmtAction::FolderCreate => &["data:create"],
+            DataMgmtAction::ItemBind => &["data:write"],
+            _ => &["data:read"],
+        },
+        RapsCommand::DesignAutomation { .. } => &["code:all"],
+        RapsCommand::Auth { .. } | RapsCommand::Custom { .. } => &[],
+    }
+}
+
+/// Checks of `workflow`: the preflight checks of `checker`, and the scopes of
+/// the stored token when there is one
+pub fn workflow_report(
+    checker: &PreflightChecker,
+    workflow: &WorkflowDefinition,
+    tokens: Option<&AuthTokens>,
+) -> WorkflowReport {
+    let mut checks: Vec<ReportCheck> = checker.check_definition(workflow).checks.into_iter().map(Into::into).collect();
+
+    let required = required_scopes(workflow);
+    if let (Some(tokens), false) = (tokens, required.is_empty()) {
+        let missing: Vec<&str> = required.iter().copied().filter(|scope| !tokens.has_scope(scope)).collect();
+        checks.push(if missing.is_empty() {
+            ReportCheck::new("Scopes", true, format!("token has {}", required.into_iter().collect::<Vec<_>>().join(" ")), None)
+        } else {
+            ReportCheck::new(
+                "Scopes",
+                false,
+                format!("token lacks {}", missing.join(" ")),
+                Some("log in again requesting these scopes".to_string()),
+            )
+        });
+    }
+
+    WorkflowReport {
+        id: workflow.metadata.id.clone(),
+        ready: checks.iter().all(|check| check.passed),
+        checks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    const WORKFLOW: &str = r#"
+metadata:
+  id: "upload"
+  name: "Upload"
+  description: "Upload a model"
+  category: "oss"
+  estimated_duration: 30
+steps:
+  - id: "create-bucket"
+    name: "Create"
+    description: "Create the bucket"
+    command: {type: bucket, action: create, bucket_name: "demo"}
+  - id: "upload"
+    name: "Upload"
+    description: "Upload the model"
+    command: {type: object, action: upload, bucket_name: "demo", file_path: "model.rvt"}
+"#;
+
+    #[test]
+    fn test_workflow_report_scopes() {
+        let workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        assert_eq!(required_scopes(&workflow).into_iter().collect::<Vec<_>>(), vec!["bucket:create", "data:write"]);
+        // Teardown needs its scopes too
+        let mut with_cleanup = workflow.clone();
+        with_cleanup.cleanup = vec![serde_yaml::from_str("{type: bucket, action: delete, bucket_name: demo}").unwrap()];
+        with_cleanup.steps[1].cleanup_commands =
+            vec![serde_yaml::from_str("{type: object, action: delete, bucket_name: demo, object_key: model.rvt}").unwrap()];
+        assert_eq!(
+            required_scopes(&with_cleanup).into_iter().collect::<Vec<_>>(),
+            vec!["bucket:create", "bucket:delete", "data:write"]
+        );
+
+        let tokens = AuthTokens {
+            access_token: "token".to_string(),
+            refresh_token: None,
+            expires_at: Utc::now(),
+            scopes: vec!["data:read".to_string(), "data:write".to_string()],
+        };
+        let report = workflow_report(&PreflightChecker::new(), &workflow, Some(&tokens));
+        let scopes = report.checks.iter().find(|check| check.name == "Scopes").unwrap();
+        assert_eq!(scopes.message, "token lacks bucket:create");
+        assert!(!report.ready);
+
+        let machine = vec![ReportCheck::new("RAPS CLI", true, "raps runs".to_string(), None)];
+        let summary = PreflightReport::new(machine, vec![report]);
+        assert!(!summary.ready);
+        let lines = summary.checklist();
+        assert_eq!(lines[0], "Machine");
+        assert!(lines.iter().any(|line| line.trim_start() == "fix: log in again requesting these scopes"));
+        assert_eq!(lines.last().unwrap(), "✗ 0 of 1 workflow(s) ready");
+    }
+}
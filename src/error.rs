@@ -0,0 +1,267 @@
+// Errors of the library API
+//
+// Internally the demo builds errors with `anyhow`, adding context at each
+// layer, and that chain is what the CLI and the TUI print. Tools embedding
+// the engine also need to tell failures apart without parsing messages: a
+// workflow that could not be found or loaded is handled differently from a
+// run that failed to start or a configuration file that doesn't parse.
+// `RapsDemoError` tags each failure with the part of the library it came
+// from, and every part has its own enum naming the causes callers act on (an
+// unknown workflow or profile, missing prerequisites, a policy violation, a
+// failed download, ...); `Other` carries the `anyhow` chain of the rest.
+// `{:#}` prints the causes the way `anyhow` does, so messages don't change.
+
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::workflow::executor::PrerequisiteError;
+use crate::workflow::types::WorkflowId;
+
+/// Failure of the engine, configuration, resource or asset API, by category
+#[derive(Debug)]
+pub enum RapsDemoError {
+    /// Workflow directories, files or definitions: missing directories,
+    /// unreadable or invalid workflows, unknown workflow IDs or profiles
+    Discovery(DiscoveryError),
+    /// Starting or controlling a run: prerequisites, policy, cancelling,
+    /// pausing and resuming
+    Execution(RunError),
+    /// Loading, saving or switching the configuration and its profiles,
+    /// and checking credentials
+    Config(ConfigError),
+    /// Reading asset manifests and downloading sample assets
+    Asset(AssetError),
+    /// Tracking created resources and recording interrupted workflows for
+    /// cleanup
+    Cleanup(CleanupError),
+}
+
+/// Why workflows could not be discovered or looked up
+#[derive(Debug, thiserror::Error)]
+pub enum DiscoveryError {
+    /// No workflows directory was given
+    #[error("No workflows directory configured")]
+    NoDirectory,
+    /// A workflows directory doesn't exist
+    #[error("Workflows directory does not exist: {}", .0.display())]
+    MissingDirectory(PathBuf),
+    /// No discovered workflow has this ID
+    #[error("Workflow not found: {0}")]
+    WorkflowNotFound(WorkflowId),
+    /// The workflow has no profile (step subset) of this name
+    #[error("{}", unknown_profile(.workflow, .profile, .available))]
+    UnknownProfile {
+        workflow: WorkflowId,
+        profile: String,
+        /// Profiles the workflow does have, sorted
+        available: Vec<String>,
+    },
+    /// A listed workflow whose steps don't parse
+    #[error("Failed to parse workflow YAML: {}: {message}", .path.display())]
+    InvalidWorkflow { path: PathBuf, message: String },
+    /// Workflows depending on each other in a cycle
+    #[error("Circular dependency detected involving workflow: {0}")]
+    CircularDependency(WorkflowId),
+    /// Anything else, e.g. a workflow file that can't be read
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// Why a run could not be started or controlled
+#[derive(Debug, thiserror::Error)]
+pub enum RunError {
+    /// The RAPS CLI, a login or required assets are missing
+    #[error(transparent)]
+    Prerequisites(PrerequisiteError),
+    /// Steps of the workflow are forbidden by the configured policy
+    #[error("Workflow {workflow} is blocked by policy:\n{}", .violations.join("\n"))]
+    Blocked {
+        workflow: WorkflowId,
+        violations: Vec<String>,
+    },
+    /// Anything else, e.g. an unknown execution handle
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// Why the configuration could not be loaded, saved or changed
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    /// The configuration path given doesn't exist
+    #[error("Configuration file not found: {}", .0.display())]
+    NotFound(PathBuf),
+    /// No profile has this name
+    #[error("Profile '{0}' not found")]
+    ProfileNotFound(String),
+    /// A profile of this name already exists
+    #[error("Profile '{0}' already exists")]
+    ProfileExists(String),
+    /// The profile to delete is the current one
+    #[error("Cannot delete the current profile")]
+    CurrentProfile(String),
+    /// Anything else, e.g. a file that doesn't parse
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// Why an asset manifest could not be read or an asset downloaded
+#[derive(Debug, thiserror::Error)]
+pub enum AssetError {
+    /// The manifest can't be read
+    #[error("Failed to read asset manifest: {}", .path.display())]
+    ManifestUnreadable {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    /// The manifest isn't valid YAML of assets
+    #[error("Failed to parse asset manifest: {}", .path.display())]
+    InvalidManifest {
+        path: PathBuf,
+        #[source]
+        source: serde_yaml::Error,
+    },
+    /// Downloading or unpacking the asset of this name failed
+    #[error("Failed to download {asset}")]
+    Download {
+        asset: String,
+        #[source]
+        source: anyhow::Error,
+    },
+    /// Anything else, e.g. an assets directory that can't be created
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// Why resources could not be tracked or interrupted workflows recorded
+#[derive(Debug, thiserror::Error)]
+pub enum CleanupError {
+    /// There is no configuration directory to keep the records in
+    #[error("Could not determine config directory")]
+    NoStateDirectory,
+    /// Anything else, e.g. a record file that can't be written
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// Result of the library API
+pub type Result<T, E = RapsDemoError> = std::result::Result<T, E>;
+
+/// Message of [`DiscoveryError::UnknownProfile`]
+fn unknown_profile(workflow: &str, profile: &str, available: &[String]) -> String {
+    if available.is_empty() {
+        format!("Workflow {} has no profiles", workflow)
+    } else {
+        format!(
+            "Workflow {} has no profile '{}' (available: {})",
+            workflow,
+            profile,
+            available.join(", ")
+        )
+    }
+}
+
+impl RapsDemoError {
+    /// The error of the category
+    fn cause(&self) -> &(dyn Error + 'static) {
+        match self {
+            Self::Discovery(e) => e,
+            Self::Execution(e) => e,
+            Self::Config(e) => e,
+            Self::Asset(e) => e,
+            Self::Cleanup(e) => e,
+        }
+    }
+}
+
+impl fmt::Display for RapsDemoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.cause())?;
+        if f.alternate() {
+            let mut source = self.cause().source();
+            while let Some(cause) = source {
+                write!(f, ": {}", cause)?;
+                source = cause.source();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error for RapsDemoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.cause().source()
+    }
+}
+
+impl From<DiscoveryError> for RapsDemoError {
+    fn from(error: DiscoveryError) -> Self {
+        Self::Discovery(error)
+    }
+}
+
+impl From<RunError> for RapsDemoError {
+    fn from(error: RunError) -> Self {
+        Self::Execution(error)
+    }
+}
+
+impl From<ConfigError> for RapsDemoError {
+    fn from(error: ConfigError) -> Self {
+        Self::Config(error)
+    }
+}
+
+impl From<AssetError> for RapsDemoError {
+    fn from(error: AssetError) -> Self {
+        Self::Asset(error)
+    }
+}
+
+impl From<CleanupError> for RapsDemoError {
+    fn from(error: CleanupError) -> Self {
+        Self::Cleanup(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_displays_like_the_underlying_chain() {
+        let error: RapsDemoError = ConfigError::Other(
+            Err::<(), _>(anyhow::anyhow!("invalid TOML")).context("Failed to load demo.toml").unwrap_err(),
+        )
+        .into();
+        assert!(matches!(error, RapsDemoError::Config(ConfigError::Other(_))));
+        assert_eq!(error.to_string(), "Failed to load demo.toml");
+        assert_eq!(format!("{:#}", error), "Failed to load demo.toml: invalid TOML");
+
+        // Converted back into `anyhow`, e.g. by `?` in an application
+        let error: anyhow::Error = error.into();
+        assert_eq!(format!("{:#}", error), "Failed to load demo.toml: invalid TOML");
+    }
+
+    #[test]
+    fn test_concrete_causes_keep_their_sources() {
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        let error: RapsDemoError = AssetError::ManifestUnreadable {
+            path: PathBuf::from("assets.yaml"),
+            source: io,
+        }
+        .into();
+        assert_eq!(format!("{:#}", error), "Failed to read asset manifest: assets.yaml: no such file");
+        let error: anyhow::Error = error.into();
+        assert_eq!(format!("{:#}", error), "Failed to read asset manifest: assets.yaml: no such file");
+
+        let error = DiscoveryError::UnknownProfile {
+            workflow: "upload".to_string(),
+            profile: "teaser".to_string(),
+            available: vec!["full".to_string(), "quick".to_string()],
+        };
+        assert_eq!(error.to_string(), "Workflow upload has no profile 'teaser' (available: full, quick)");
+    }
+}
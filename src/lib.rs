@@ -4,16 +4,20 @@
 //! including workflow discovery, execution, and resource management.
 
 pub mod assets;
+pub mod builder;
 pub mod config;
 pub mod demo;
+pub mod error;
 pub mod resource;
 pub mod tui;
 pub mod utils;
 pub mod workflow;
 
 // Re-export main types for convenience
+pub use builder::RapsDemoBuilder;
 pub use config::ConfigManager;
 pub use demo::DemoManager;
+pub use error::{AssetError, CleanupError, ConfigError, DiscoveryError, RapsDemoError, RunError};
 pub use resource::ResourceManager;
 pub use tui::TuiApp;
 pub use workflow::WorkflowEngine;
@@ -3,19 +3,47 @@
 // This application provides a Terminal User Interface (TUI) for discovering and executing
 // demo workflows that showcase APS capabilities through the RAPS CLI.
 
-use anyhow::Result;
-use clap::Parser;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
 
-mod assets;
-mod config;
-mod demo;
-mod resource;
-mod tui;
-mod utils;
-mod workflow;
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use tracing_appender::non_blocking::WorkerGuard;
 
-use crate::tui::TuiApp;
-use crate::workflow::{ExecutionOptions, WorkflowDiscovery, WorkflowExecutor};
+use raps_demo_workflows::config::types::ConfigPaths;
+use raps_demo_workflows::config::types::RapsConfig;
+use raps_demo_workflows::config::watch::ConfigWatcher;
+use raps_demo_workflows::config::DemoConfig;
+use raps_demo_workflows::demo::init::init_demo_environment;
+use raps_demo_workflows::demo::preflight::{self, PreflightReport};
+use raps_demo_workflows::tui::doctor;
+use raps_demo_workflows::tui::glyphs::Glyphs;
+use raps_demo_workflows::tui::i18n::Locale;
+use raps_demo_workflows::tui::preflight::PreflightChecker;
+use raps_demo_workflows::{ConfigManager, RapsDemoBuilder, ResourceManager};
+use raps_demo_workflows::utils::cast;
+use raps_demo_workflows::utils::crash::{self, CrashLogLayer};
+use raps_demo_workflows::utils::interrupt::interrupted_summary;
+use raps_demo_workflows::utils::logging::create_session_log;
+use raps_demo_workflows::utils::redact::{redact_text, RedactingWriter};
+use raps_demo_workflows::utils::telemetry::{self, TelemetryGuard};
+use raps_demo_workflows::utils::update::{self, UpdateOutcome};
+use raps_demo_workflows::workflow::diagram::{render_diagram, DiagramFormat};
+use raps_demo_workflows::workflow::diff::{self, WorkflowDiff};
+use raps_demo_workflows::workflow::metrics::{push_metrics, serve_metrics, WorkflowMetrics};
+use raps_demo_workflows::workflow::pack::{self, InstallOptions};
+use raps_demo_workflows::workflow::chaos::ChaosOptions;
+use raps_demo_workflows::workflow::client::{ProfileEnvironments, RapsClientConfig};
+use raps_demo_workflows::workflow::mock::{self, FailureInjection, FixtureRecorder, MockBackend};
+use raps_demo_workflows::workflow::schedule::{Schedule, ScheduleEntry};
+use raps_demo_workflows::workflow::catalog::{self, CatalogEntry, ListFormat};
+use raps_demo_workflows::workflow::detail::{AssetAvailability, DetailFormat, WorkflowDetail};
+use raps_demo_workflows::workflow::exit_code::RunExit;
+use raps_demo_workflows::workflow::run_summary;
+use raps_demo_workflows::workflow::snapshot::{self, TestOutcome};
+use raps_demo_workflows::workflow::watch::{self, FileStamps};
+use raps_demo_workflows::workflow::{self, ExecutionOptions, WorkflowDefinition, WorkflowEngine};
 
 /// RAPS Demo Workflows - Interactive APS demonstration system
 #[derive(Parser)]
@@ -25,13 +53,33 @@ use crate::workflow::{ExecutionOptions, WorkflowDiscovery, WorkflowExecutor};
 )]
 #[command(version)]
 struct Args {
-    /// Enable verbose logging
-    #[arg(short, long)]
-    verbose: bool,
+    /// Enable verbose logging; with --no-tui, -vv also prints everything
+    /// RAPS commands print
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// With --no-tui, print only errors and the final status (for cron)
+    #[arg(short, long, conflicts_with = "verbose")]
+    quiet: bool,
 
-    /// Configuration file path
+    /// OpenTelemetry collector endpoint for exporting spans (requires the `otlp` feature)
+    #[arg(long, env = "OTEL_EXPORTER_OTLP_ENDPOINT")]
+    otlp_endpoint: Option<String>,
+
+    /// Demo configuration file (or configuration directory) to use instead of
+    /// RAPS_CONFIG_DIR or ~/.raps
     #[arg(short, long)]
-    config: Option<String>,
+    config: Option<PathBuf>,
+
+    /// Workflows directory (repeatable; later directories override same-ID
+    /// workflows of earlier ones). Overrides workflows_path and RAPS_WORKFLOWS_PATH
+    #[arg(long = "workflows-dir", value_name = "DIR")]
+    workflows_dirs: Vec<PathBuf>,
+
+    /// Environment whose vars.<env>.yaml overlays the workflows' shared
+    /// variables (overrides `vars_env` in demo.toml)
+    #[arg(long, value_name = "NAME", env = "RAPS_DEMO_ENV")]
+    env: Option<String>,
 
     /// Run in non-interactive mode (skip TUI)
     #[arg(long)]
@@ -41,144 +89,1244 @@ struct Args {
     #[arg(long)]
     list: bool,
 
+    /// Format of --list: table, json or yaml
+    #[arg(long, value_name = "FORMAT", default_value = "table")]
+    format: ListFormat,
+
     /// Workflow to execute directly (bypasses TUI)
     #[arg(long)]
     workflow: Option<String>,
+
+    /// Run only the steps of this profile of the workflow (with --workflow)
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Use the recorded outputs of an earlier run as parameters (repeatable,
+    /// with --workflow), e.g. upload:latest or upload:latest~1
+    #[arg(long, value_name = "WORKFLOW[:latest]", requires = "workflow")]
+    from_outputs: Vec<String>,
+
+    /// Start the TUI in presentation mode (big step banner, no side panels)
+    #[arg(long)]
+    presentation: bool,
+
+    /// Record the TUI session to an asciinema v2 cast file
+    #[arg(long, value_name = "FILE")]
+    record: Option<PathBuf>,
+
+    /// Seed for generated names ({uuid}, {random:N}) so recorded demos
+    /// create the same buckets and objects (overrides `seed` in demo.toml)
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Language of the TUI: en, ja or de (overrides `locale` in demo.toml)
+    #[arg(long)]
+    locale: Option<Locale>,
+
+    /// Keep each run's temporary directory ({temp_dir}) after it finishes
+    /// (same as `keep_temp_dirs = true` in demo.toml)
+    #[arg(long)]
+    keep_temp_dirs: bool,
+
+    /// Answer RAPS commands from this fixture file instead of running the CLI
+    #[arg(long, value_name = "FIXTURES")]
+    mock: Option<PathBuf>,
+
+    /// Make a step's commands fail against the mock backend (repeatable):
+    /// step-id, or step-id=kind with kind auth, quota, conflict, not-found,
+    /// network or rate-limit
+    #[arg(long, value_name = "STEP[=KIND]")]
+    inject_failure: Vec<FailureInjection>,
+
+    /// Inject faults into step commands to teach troubleshooting, e.g.
+    /// failure=10,timeout=5,rate-limit=10 (percent; overrides `[chaos]` in demo.toml)
+    #[arg(long, value_name = "KIND=PERCENT,...")]
+    chaos: Option<ChaosOptions>,
+
+    /// Save the real RAPS output of this run, redacted, as a fixture file for --mock
+    #[arg(long, value_name = "FILE", conflicts_with = "mock")]
+    record_fixtures: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
+/// Subcommands that run without the TUI
+#[derive(Subcommand)]
+enum Command {
+    /// Export a workflow as a diagram (Mermaid, Graphviz DOT or SVG) or a Markdown tutorial
+    Export {
+        /// Workflow ID to export
+        workflow_id: String,
+
+        /// Format: mermaid, dot, svg or markdown (a tutorial with step notes)
+        #[arg(short, long, default_value = "mermaid")]
+        format: DiagramFormat,
+
+        /// Output file (defaults to <workflow-id>.<ext>; use - for stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Show a workflow's metadata, prerequisites, required assets, resolved
+    /// step commands and cost estimate without running it
+    Show {
+        /// Workflow ID to show
+        workflow_id: String,
+
+        /// Format: text, json, yaml or md
+        #[arg(short, long, default_value = "text")]
+        format: DetailFormat,
+    },
+
+    /// Check that a demo machine is ready: RAPS CLI, APS connectivity, and
+    /// each workflow's authentication, assets, prerequisites and token scopes
+    Preflight {
+        /// Workflow ID to check
+        #[arg(required_unless_present = "all")]
+        workflow_id: Option<String>,
+
+        /// Check every workflow
+        #[arg(long, conflicts_with = "workflow_id")]
+        all: bool,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Install the latest release over this executable, after checking its
+    /// signature
+    SelfUpdate {
+        /// Only report whether a newer release exists
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Check the terminal for colour depth, Unicode and wide-character
+    /// handling, mouse support and size, with recommendations
+    DoctorTerminal,
+
+    /// Create a demo environment: workflows (the built-in pack), an example
+    /// assets.yaml, a starter profile and a .gitignore
+    Init {
+        /// Directory to set up
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+
+        /// Overwrite files that already exist
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Build, install and manage workflow packs (.rapspack)
+    Pack {
+        #[command(subcommand)]
+        command: PackCommand,
+    },
+
+    /// Run workflows on a cron schedule (e.g. as nightly regression tests)
+    Schedule {
+        #[command(subcommand)]
+        command: ScheduleCommand,
+    },
+
+    /// Show what changed in a workflow: metadata, steps and their commands
+    Diff {
+        /// Workflow ID to compare
+        workflow_id: String,
+
+        /// Version to compare against: git:<rev> (e.g. git:HEAD~1) or a workflow file
+        #[arg(long, default_value = "git:HEAD")]
+        against: String,
+    },
+
+    /// Run workflows against the mock backend and compare them with their snapshots
+    Test {
+        /// Workflows to test (defaults to every workflow with a snapshot)
+        workflow_ids: Vec<String>,
+
+        /// Directory of <workflow-id>.snap.yaml files
+        #[arg(long, default_value = "snapshots")]
+        snapshots: PathBuf,
+
+        /// Record new snapshots instead of comparing
+        #[arg(long)]
+        update: bool,
+    },
+
+    /// Re-validate and re-run a workflow whenever its YAML or assets change
+    Watch {
+        /// Workflow ID to watch
+        workflow_id: String,
+
+        /// Only validate and show the resolved commands, don't run anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Run only the steps of this profile of the workflow
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+
+        /// Milliseconds between checks for changes
+        #[arg(long, default_value_t = 500)]
+        interval: u64,
+    },
+
+    /// Convert a session log into an asciinema v2 cast for documentation
+    Render {
+        /// Session log (`.jsonl` from the logs directory)
+        log: PathBuf,
+
+        /// Output cast file (defaults to the log name with a .cast extension)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Terminal width of the cast
+        #[arg(long, default_value_t = 100)]
+        width: u16,
+
+        /// Terminal height of the cast
+        #[arg(long, default_value_t = 30)]
+        height: u16,
+    },
+}
+
+/// `raps-demo schedule` subcommands
+#[derive(Subcommand)]
+enum ScheduleCommand {
+    /// Schedule a workflow
+    Add {
+        /// Workflow ID to run
+        workflow_id: String,
+
+        /// When to run it: minute hour day month weekday, e.g. "0 6 * * *"
+        #[arg(long)]
+        cron: String,
+
+        /// Name of the schedule (defaults to the workflow ID)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Run only the steps of this profile of the workflow
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+    },
+
+    /// Remove a schedule
+    Remove {
+        /// Schedule name
+        name: String,
+    },
+
+    /// List schedules with their next and last runs
+    List,
+
+    /// Stay in the foreground and run workflows when they are due
+    Run,
+}
+
+/// `raps-demo pack` subcommands
+#[derive(Subcommand)]
+enum PackCommand {
+    /// Build a .rapspack from a directory with pack.yaml and workflows/
+    Build {
+        /// Pack source directory
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+
+        /// Output file (defaults to <name>-<version>.rapspack)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Install a pack from a file or an http(s) URL
+    Install {
+        /// Pack file or URL
+        source: String,
+
+        /// Require (and pin) this pack version
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Replace a pack pinned to another version
+        #[arg(long)]
+        force: bool,
+
+        /// Expected SHA-256 of the pack (defaults to the published <source>.sha256)
+        #[arg(long, value_name = "HEX")]
+        sha256: Option<String>,
+
+        /// Install even if no checksum is available to verify the pack
+        #[arg(long)]
+        allow_unverified: bool,
+    },
+
+    /// Remove an installed pack
+    Remove {
+        /// Pack name
+        name: String,
+    },
+
+    /// List installed packs
+    List,
+}
+
+// Returns the exit code rather than calling `process::exit`, so the log and
+// span guards are dropped and flush on the way out
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let args = Args::parse();
+    // Logs, history, caches and resource state live next to `--config`
+    if let Some(path) = &args.config {
+        ConfigPaths::set_config_dir(ConfigPaths::config_dir_of(path));
+    }
+    crash::install_panic_hook();
 
-    // Initialize logging
-    init_logging(args.verbose)?;
+    // Initialize logging (the guards flush the session log and spans on exit)
+    let console_level = console_log_level(args.quiet, args.verbose);
+    let _log_guards = init_logging(console_level, args.otlp_endpoint.as_deref())?;
 
     tracing::info!("Starting RAPS Demo Workflows system");
+    let mut exit_code = ExitCode::SUCCESS;
+
+    // Optional metrics exporter (configured in demo.toml)
+    let (mut demo_config, profiles) = load_demo_config(args.config.as_deref()).await?;
+    if !args.workflows_dirs.is_empty() {
+        demo_config.workflows_path = args.workflows_dirs.clone();
+    }
+    if args.seed.is_some() {
+        demo_config.seed = args.seed;
+    }
+    if args.keep_temp_dirs {
+        demo_config.keep_temp_dirs = true;
+    }
+    if args.env.is_some() {
+        demo_config.vars_env = args.env.clone();
+    }
+    if let Some(chaos) = &args.chaos {
+        demo_config.chaos = chaos.clone();
+    }
+    let metrics_config = demo_config.metrics.clone();
+    let metrics = metrics_config.enabled.then(WorkflowMetrics::new);
+    if let (Some(metrics), Some(address)) = (&metrics, &metrics_config.listen_address) {
+        serve_metrics(Arc::clone(metrics), address).await?;
+    }
 
-    if args.no_tui {
+    if let Some(command) = args.command {
+        match command {
+            Command::Export {
+                workflow_id,
+                format,
+                output,
+            } => {
+                let engine = demo_builder(&demo_config, &profiles, metrics.clone()).build_engine()?;
+                export_workflow(&engine, &workflow_id, format, output)?;
+            },
+            Command::Show { workflow_id, format } => {
+                let builder = demo_builder(&demo_config, &profiles, metrics.clone());
+                let checker = PreflightChecker::new().with_assets_dir(builder.assets_path());
+                let engine = builder.build_engine()?;
+                let definition = engine.get_workflows().load(&workflow_id)?;
+                let detail = WorkflowDetail::new(definition, engine.executor().raps_client(), |asset| {
+                    if asset.exists() {
+                        AssetAvailability::Present
+                    } else if checker.is_downloadable(asset) {
+                        AssetAvailability::Downloadable
+                    } else {
+                        AssetAvailability::Missing
+                    }
+                });
+                print!("{}", detail.render(format)?);
+            },
+            Command::Preflight { workflow_id, all, json } => {
+                let raps_config = load_raps_config(args.config.as_deref()).await;
+                let builder = demo_builder(&demo_config, &profiles, metrics.clone());
+                let checker = PreflightChecker::new().with_assets_dir(builder.assets_path());
+                let engine = builder.build_engine()?;
+                let workflows: Vec<&WorkflowDefinition> = match (&workflow_id, all) {
+                    (Some(id), false) => vec![engine.get_workflows().load(id)?],
+                    // `--all` (clap requires it without a workflow ID)
+                    _ => engine.list().iter().filter_map(|w| engine.get_workflow(&w.id)).collect(),
+                };
+                let machine = preflight::machine_checks(engine.executor().raps_client(), &raps_config.base_url).await;
+                let tokens = raps_config.auth_tokens.as_ref();
+                let report = PreflightReport::new(
+                    machine,
+                    workflows.iter().map(|w| preflight::workflow_report(&checker, w, tokens)).collect(),
+                );
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                } else {
+                    for line in report.checklist() {
+                        println!("{}", line);
+                    }
+                }
+                if !report.ready {
+                    exit_code = RunExit::Preflight.into();
+                }
+            },
+            Command::SelfUpdate { check } => {
+                let current = update::Version::current();
+                if check {
+                    let release = update::latest_release(&demo_config.update).await?;
+                    if release.version > current {
+                        println!("Version {} is available (running {}): {}", release.version, current, release.url);
+                    } else {
+                        println!("raps-demo {} is up to date", current);
+                    }
+                } else {
+                    match update::self_update(&demo_config.update).await? {
+                        UpdateOutcome::UpToDate(version) => println!("raps-demo {} is up to date", version),
+                        UpdateOutcome::Installed(version, path) => {
+                            println!("Updated raps-demo {} -> {} ({})", current, version, path.display())
+                        },
+                    }
+                }
+            },
+            Command::DoctorTerminal => {
+                let checks = doctor::diagnose(|name| std::env::var(name).ok(), &doctor::TerminalProbe::current());
+                for line in doctor::checklist(&checks, Glyphs::for_mode(demo_config.glyphs)) {
+                    println!("{}", line);
+                }
+            },
+            Command::Init { dir, force } => {
+                let report = init_demo_environment(&dir, force)?;
+                for path in &report.created {
+                    println!("  created  {}", path.display());
+                }
+                for path in &report.skipped {
+                    println!("  exists   {}", path.display());
+                }
+                println!(
+                    "\nDemo environment ready. Next: cd {} && raps-demo --config .",
+                    dir.display()
+                );
+            },
+            Command::Pack { command } => run_pack_command(command).await?,
+            Command::Schedule { command } => run_schedule_command(command, &demo_config, &profiles, metrics.clone()).await?,
+            Command::Diff { workflow_id, against } => {
+                let engine = demo_builder(&demo_config, &profiles, metrics.clone()).build_engine()?;
+                let current = engine
+                    .get_workflow(&workflow_id)
+                    .with_context(|| format!("Workflow '{}' not found", workflow_id))?;
+                let previous = diff::load_version(&against, &current.metadata.script_path)?;
+                println!("{} ({} -> working copy)", workflow_id, against);
+                for line in WorkflowDiff::between(&previous, current, engine.executor().raps_client()).render() {
+                    println!("{}", line);
+                }
+            },
+            Command::Test {
+                workflow_ids,
+                snapshots,
+                update,
+            } => {
+                let mock = Arc::new(MockBackend::default());
+                let engine = demo_builder(&demo_config, &profiles, metrics.clone())
+                    .executor_config(RapsClientConfig {
+                        mock: Some(Arc::clone(&mock)),
+                        ..Default::default()
+                    })
+                    .build_engine()?;
+                if !test_workflows(&engine, &mock, &snapshots, workflow_ids, update).await? {
+                    exit_code = ExitCode::FAILURE;
+                }
+            },
+            Command::Watch {
+                workflow_id,
+                dry_run,
+                profile,
+                interval,
+            } => {
+                let engine = demo_builder(&demo_config, &profiles, metrics.clone()).build_engine()?;
+                watch_workflow(engine, &workflow_id, dry_run, profile, interval).await?;
+            },
+            Command::Render {
+                log,
+                output,
+                width,
+                height,
+            } => {
+                let output = output.unwrap_or_else(|| log.with_extension("cast"));
+                let events = cast::render_session_log(&log, &output, width, height)?;
+                println!("Rendered {} events to {}", events, output.display());
+            },
+        }
+    } else if args.no_tui {
         // Run in non-interactive mode
         tracing::info!("Running in non-interactive mode");
-        run_cli_mode(args.workflow, args.list).await?;
+        let (builder, recorder) = backend_builder(&args, &demo_config, &profiles, metrics.clone())?;
+        let tier = OutputTier::from_flags(args.quiet, args.verbose);
+        let assets_dir = builder.assets_path().to_path_buf();
+        let engine = builder.stream_output(tier == OutputTier::Streamed).build_engine()?;
+        let exit = match args.workflow {
+            Some(workflow_id) if !args.list => {
+                run_cli_mode(engine, workflow_id, args.profile, args.from_outputs, tier).await?
+            },
+            _ => {
+                list_workflows(&engine, args.format, &assets_dir)?;
+                RunExit::Success
+            },
+        };
+        save_fixtures(recorder, args.record_fixtures.as_deref())?;
+        exit_code = exit.into();
     } else {
         // Launch TUI application
         tracing::info!("Launching TUI application");
-        let mut app = TuiApp::new().await?;
+        let (builder, recorder) = backend_builder(&args, &demo_config, &profiles, metrics.clone())?;
+        let mut app = builder
+            .build_tui()?
+            .with_recording(args.record)
+            .with_presentation(args.presentation)
+            .with_update_check(&demo_config.update);
+        if let Some(locale) = args.locale {
+            app = app.with_locale(locale);
+        }
+        // Pick up edits of the configuration files during the session
+        let manager = match args.config.as_deref() {
+            Some(path) => ConfigManager::with_config_path(path).await,
+            None => ConfigManager::new().await,
+        };
+        match manager {
+            Ok(manager) => app = app.with_config_watcher(ConfigWatcher::new(manager)),
+            Err(e) => tracing::debug!("Configuration not watched: {:#}", e),
+        }
         app.run().await?;
+        // The terminal is restored by now, so leftovers can be reported
+        for line in interrupted_summary(app.interrupted_workflows()) {
+            eprintln!("{}", line);
+        }
+        save_fixtures(recorder, args.record_fixtures.as_deref())?;
+    }
+
+    if let (Some(metrics), Some(url)) = (&metrics, &metrics_config.pushgateway_url) {
+        match push_metrics(metrics, url, &metrics_config.job_name).await {
+            Ok(()) => tracing::info!("Pushed metrics to {}", url),
+            Err(e) => tracing::warn!("Failed to push metrics: {:#}", e),
+        }
     }
 
     tracing::info!("RAPS Demo Workflows system shutdown complete");
+    Ok(exit_code)
+}
+
+/// Builder for the workflow engine shared by the TUI and CLI modes
+fn demo_builder(
+    config: &DemoConfig,
+    profiles: &ProfileEnvironments,
+    metrics: Option<Arc<WorkflowMetrics>>,
+) -> RapsDemoBuilder {
+    let mut builder = RapsDemoBuilder::new()
+        .demo_config(config.clone())
+        .profile_environments(profiles.clone())
+        .metrics(metrics)
+        .parse_cache(true);
+    if let Ok(packs_dir) = ConfigPaths::packs_dir() {
+        builder = builder.installed_packs(packs_dir);
+    }
+    match ResourceManager::new() {
+        Ok(resource_manager) => builder.resource_manager(resource_manager),
+        Err(e) => {
+            tracing::warn!("Resource tracking unavailable: {:#}", e);
+            builder
+        },
+    }
+}
+
+/// [`demo_builder`] with the RAPS backend chosen by `--mock`,
+/// `--inject-failure` and `--record-fixtures`, and the fixture recorder if any
+fn backend_builder(
+    args: &Args,
+    config: &DemoConfig,
+    profiles: &ProfileEnvironments,
+    metrics: Option<Arc<WorkflowMetrics>>,
+) -> Result<(RapsDemoBuilder, Option<Arc<FixtureRecorder>>)> {
+    let builder = demo_builder(config, profiles, metrics);
+    let mock = if args.mock.is_some() || !args.inject_failure.is_empty() {
+        let responses = match &args.mock {
+            Some(path) => mock::load_fixtures(path)?,
+            None => Vec::new(),
+        };
+        let backend = MockBackend::new(responses);
+        backend.set_failures(args.inject_failure.clone());
+        Some(Arc::new(backend))
+    } else {
+        None
+    };
+    let recorder = args.record_fixtures.as_ref().map(|_| Arc::new(FixtureRecorder::default()));
+    if mock.is_none() && recorder.is_none() {
+        return Ok((builder, None));
+    }
+    let builder = builder.executor_config(RapsClientConfig {
+        mock,
+        recorder: recorder.clone(),
+        ..RapsClientConfig::for_demo(config)
+    });
+    Ok((builder, recorder))
+}
+
+/// Write the fixtures recorded during the run to `path`
+fn save_fixtures(recorder: Option<Arc<FixtureRecorder>>, path: Option<&std::path::Path>) -> Result<()> {
+    if let (Some(recorder), Some(path)) = (recorder, path) {
+        recorder.save(path)?;
+        println!("Recorded {} fixture(s) to {}", recorder.responses().len(), path.display());
+    }
     Ok(())
 }
 
-/// Run in non-interactive CLI mode
-async fn run_cli_mode(workflow_id: Option<String>, list_only: bool) -> Result<()> {
-    let workflows_dir = std::path::Path::new("./workflows");
-    
-    // Ensure workflows directory exists
-    if !workflows_dir.exists() {
-        std::fs::create_dir_all(workflows_dir)?;
+/// Run a `raps-demo schedule` subcommand
+async fn run_schedule_command(
+    command: ScheduleCommand,
+    config: &DemoConfig,
+    profiles: &ProfileEnvironments,
+    metrics: Option<Arc<WorkflowMetrics>>,
+) -> Result<()> {
+    let mut schedule = Schedule::load()?;
+    match command {
+        ScheduleCommand::Add {
+            workflow_id,
+            cron,
+            name,
+            profile,
+        } => {
+            let engine = demo_builder(config, profiles, metrics).build_engine()?;
+            let definition = engine
+                .get_workflow(&workflow_id)
+                .with_context(|| format!("Workflow '{}' not found", workflow_id))?;
+            if let Some(profile) = &profile {
+                definition.with_profile(profile)?;
+            }
+            let name = name.unwrap_or_else(|| workflow_id.clone());
+            schedule.add(ScheduleEntry {
+                name: name.clone(),
+                workflow_id,
+                cron,
+                profile,
+                last_run: None,
+                last_success: None,
+                last_message: None,
+            })?;
+            schedule.save()?;
+            if let Some((next, _)) = schedule.upcoming(chrono::Local::now()).into_iter().find(|(_, e)| e.name == name) {
+                println!("Scheduled {}; next run {}", name, next.format("%Y-%m-%d %H:%M"));
+            }
+        },
+        ScheduleCommand::Remove { name } => {
+            schedule.remove(&name)?;
+            schedule.save()?;
+            println!("Removed {}", name);
+        },
+        ScheduleCommand::List => {
+            if schedule.entries.is_empty() {
+                println!("No schedules. Add one with: raps-demo schedule add <workflow-id> --cron \"0 6 * * *\"");
+            }
+            for (next, entry) in schedule.upcoming(chrono::Local::now()) {
+                let profile = entry.profile.as_ref().map(|p| format!(" (profile {})", p)).unwrap_or_default();
+                println!("  {} - {}{} at \"{}\"", entry.name, entry.workflow_id, profile, entry.cron);
+                println!("      next: {}", next.format("%Y-%m-%d %H:%M"));
+                if let (Some(at), Some(success)) = (entry.last_run, entry.last_success) {
+                    println!(
+                        "      last: {} {} {}",
+                        at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+                        if success { "✓" } else { "✗" },
+                        entry.last_message.as_deref().unwrap_or_default()
+                    );
+                }
+            }
+        },
+        ScheduleCommand::Run => {
+            let engine = demo_builder(config, profiles, metrics).build_engine()?;
+            loop {
+                // Reload so schedules added or removed meanwhile take effect
+                let schedule = Schedule::load()?;
+                let upcoming = schedule.upcoming(chrono::Local::now());
+                let Some(&(next, _)) = upcoming.first() else {
+                    anyhow::bail!("Nothing scheduled; add a schedule with `raps-demo schedule add`");
+                };
+                let due: Vec<ScheduleEntry> =
+                    upcoming.iter().filter(|(time, _)| *time == next).map(|(_, e)| (*e).clone()).collect();
+                let names: Vec<&str> = due.iter().map(|e| e.name.as_str()).collect();
+                println!("Next run: {} at {}", names.join(", "), next.format("%Y-%m-%d %H:%M"));
+
+                let wait = (next - chrono::Local::now()).to_std().unwrap_or_default();
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {},
+                    _ = tokio::signal::ctrl_c() => {
+                        println!("Scheduler stopped");
+                        return Ok(());
+                    },
+                }
+
+                for entry in due {
+                    println!("Running {} ({})", entry.name, entry.workflow_id);
+                    let (success, message) = match run_unattended(&engine, &entry.workflow_id, entry.profile.clone()).await {
+                        Ok(outcome) => outcome,
+                        Err(e) => (false, format!("{:#}", e)),
+                    };
+                    println!("  {} {}: {}", if success { "✓" } else { "✗" }, entry.name, message);
+                    let mut schedule = Schedule::load()?;
+                    if let Some(current) = schedule.get_mut(&entry.name) {
+                        current.record(success, message);
+                        schedule.save()?;
+                    }
+                }
+            }
+        },
     }
-    
-    let mut discovery = WorkflowDiscovery::new(workflows_dir)?;
-    let workflows = discovery.discover_workflows()?;
-
-    // If --list flag is set, or no workflow specified, list workflows
-    if list_only || workflow_id.is_none() {
-        // List available workflows
-        println!("Available workflows:\n");
-        
-        if workflows.is_empty() {
-            println!("  No workflows found in ./workflows/");
-            println!("\n  Create workflow YAML files in the workflows/ directory to get started.");
-        } else {
-            for workflow in &workflows {
-                println!("  {} - {}", workflow.id, workflow.name);
-                println!("    Category: {}", workflow.category);
-                println!("    {}\n", workflow.description);
-            }
-            
-            println!("Run a workflow with: raps-demo --no-tui --workflow <workflow-id>");
+    Ok(())
+}
+
+/// Snapshot-test workflows, returning whether all of them passed
+async fn test_workflows(
+    engine: &WorkflowEngine,
+    mock: &MockBackend,
+    dir: &std::path::Path,
+    workflow_ids: Vec<String>,
+    update: bool,
+) -> Result<bool> {
+    let workflow_ids = if workflow_ids.is_empty() {
+        snapshot::snapshot_ids(dir)
+    } else {
+        workflow_ids
+    };
+    if workflow_ids.is_empty() {
+        println!(
+            "No snapshots in {}; record one with: raps-demo test <workflow-id>",
+            dir.display()
+        );
+        return Ok(true);
+    }
+
+    let (mut passed, mut failed, mut recorded) = (0, 0, 0);
+    for workflow_id in &workflow_ids {
+        match snapshot::test_workflow(engine, mock, dir, workflow_id, update).await {
+            Ok(TestOutcome::Passed) => {
+                passed += 1;
+                println!("  ✓ {}", workflow_id);
+            },
+            Ok(TestOutcome::Recorded) => {
+                recorded += 1;
+                println!("  + {} (snapshot recorded)", workflow_id);
+            },
+            Ok(TestOutcome::Failed(diff)) => {
+                failed += 1;
+                println!("  ✗ {}", workflow_id);
+                for line in diff {
+                    println!("      {}", line);
+                }
+            },
+            Err(e) => {
+                failed += 1;
+                println!("  ✗ {}: {:#}", workflow_id, e);
+            },
         }
-        return Ok(());
     }
-    
-    if let Some(workflow_id) = workflow_id {
-        // Execute specific workflow
-        tracing::info!("Executing workflow: {}", workflow_id);
-        
-        if let Some(definition) = discovery.get_workflow(&workflow_id) {
-            let definition = definition.clone();
-            let (executor, mut receiver) = WorkflowExecutor::new().with_progress_reporting();
-            
-            println!("Starting workflow: {} - {}", definition.metadata.name, definition.metadata.description);
-            
-            let options = ExecutionOptions {
-                interactive: false,
-                verbose: true,
-                auto_cleanup: true,
-                ..Default::default()
+    println!("\n{} passed, {} failed, {} recorded", passed, failed, recorded);
+    Ok(failed == 0)
+}
+
+/// Watch a workflow's files, re-validating it and printing how its commands
+/// changed after each edit, then running it unless `dry_run`
+async fn watch_workflow(
+    mut engine: WorkflowEngine,
+    workflow_id: &str,
+    dry_run: bool,
+    profile: Option<String>,
+    interval: u64,
+) -> Result<()> {
+    let definition = engine
+        .get_workflow(&workflow_id.to_string())
+        .with_context(|| format!("Workflow '{}' not found", workflow_id))?
+        .clone();
+    let path = definition.metadata.script_path.clone();
+    if path.as_os_str().is_empty() || !path.exists() {
+        anyhow::bail!("Workflow '{}' has no file to watch (built-in workflows can't be watched)", workflow_id);
+    }
+    println!("Watching {} ({}); Ctrl-C to stop", workflow_id, path.display());
+
+    let params = engine.execution_options().params;
+    let mut previous: Option<Vec<String>> = None;
+    loop {
+        let (definition, validation) = match engine.reload_workflow(&path) {
+            Ok(reloaded) => reloaded,
+            Err(e) => {
+                println!("✗ {:#}", e);
+                (definition.clone(), workflow::ValidationResult::with_errors(Vec::new()))
+            },
+        };
+        // Taken before running, so edits made during the run count as changes
+        let paths = watch::watched_paths(&definition);
+        let stamps = FileStamps::capture(&paths);
+        for error in &validation.errors {
+            println!("✗ {}", error);
+        }
+        for warning in validation.warnings.iter().filter(|w| !w.is_empty()) {
+            println!("⚠ {}", warning);
+        }
+
+        if validation.is_valid {
+            let selected = match &profile {
+                Some(profile) => definition.with_profile(profile)?,
+                None => definition.clone(),
             };
-            
-            let _handle = executor.execute_workflow(definition, options).await?;
-            
-            // Wait for execution updates
-            while let Some(update) = receiver.recv().await {
-                match update {
-                    workflow::ExecutionUpdate::StepStarted { step, .. } => {
-                        println!("  → Step: {}", step.name);
-                    }
-                    workflow::ExecutionUpdate::StepCompleted { result, .. } => {
-                        let status = if result.status == workflow::ExecutionStatus::Completed {
-                            "✓"
-                        } else {
-                            "✗"
-                        };
-                        println!("  {} Completed: {}", status, result.step_id);
+            let commands = watch::resolved_commands(engine.executor().raps_client(), &selected, &params);
+            match &previous {
+                None => commands.iter().for_each(|line| println!("  {}", line)),
+                Some(old) => {
+                    let diff = watch::diff_lines(old, &commands);
+                    if diff.is_empty() {
+                        println!("  (commands unchanged)");
                     }
-                    workflow::ExecutionUpdate::Completed { result, .. } => {
-                        if result.success {
-                            println!("\n✓ Workflow completed successfully ({} steps)", result.steps_completed);
-                        } else {
-                            println!("\n✗ Workflow failed after {} steps", result.steps_completed);
-                        }
-                        break;
+                    diff.iter().for_each(|line| println!("  {}", line));
+                },
+            }
+            previous = Some(commands);
+
+            if !dry_run {
+                println!("Running {}", workflow_id);
+                match run_unattended(&engine, workflow_id, profile.clone()).await {
+                    Ok((success, message)) => println!("{} {}", if success { "✓" } else { "✗" }, message),
+                    Err(e) => println!("✗ {:#}", e),
+                }
+            }
+        }
+
+        println!("Waiting for changes...");
+        loop {
+            let changed = stamps.changed_since(&FileStamps::capture(&paths));
+            if !changed.is_empty() {
+                for path in &changed {
+                    println!("\nChanged: {}", path.display());
+                }
+                break;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(std::time::Duration::from_millis(interval.max(50))) => {},
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Run a workflow non-interactively to the end, returning whether it
+/// succeeded and a summary
+async fn run_unattended(engine: &WorkflowEngine, workflow_id: &str, profile: Option<String>) -> Result<(bool, String)> {
+    let mut receiver = engine.subscribe();
+    let options = ExecutionOptions {
+        interactive: false,
+        auto_cleanup: true,
+        profile,
+        ..engine.execution_options()
+    };
+    let handle = engine.execute(&workflow_id.to_string(), options).await?;
+    while let Some(update) = receiver.recv().await {
+        if update.handle().id != handle.id {
+            continue;
+        }
+        match update {
+            workflow::ExecutionUpdate::Completed { result, .. } => {
+                let message = format!(
+                    "{} step(s) in {}",
+                    result.steps_completed,
+                    workflow::history::format_duration_secs(result.duration.num_milliseconds() as f64 / 1000.0)
+                );
+                return Ok((result.success, message));
+            },
+            workflow::ExecutionUpdate::Failed { error, .. } => return Ok((false, error.message)),
+            workflow::ExecutionUpdate::Cancelled { .. } => return Ok((false, "cancelled".to_string())),
+            _ => {},
+        }
+    }
+    anyhow::bail!("Execution updates stopped before the run finished")
+}
+
+/// Run a `raps-demo pack` subcommand
+async fn run_pack_command(command: PackCommand) -> Result<()> {
+    let packs_dir = ConfigPaths::packs_dir()?;
+    match command {
+        PackCommand::Build { dir, output } => {
+            let path = pack::build_pack(&dir, output)?;
+            println!("Built {}", path.display());
+        },
+        PackCommand::Install {
+            source,
+            version,
+            force,
+            sha256,
+            allow_unverified,
+        } => {
+            let options = InstallOptions {
+                version,
+                force,
+                sha256,
+                allow_unverified,
+            };
+            let manifest = pack::install_pack(&source, &packs_dir, &options).await?;
+            println!(
+                "Installed {} {} ({} workflows, {}){}",
+                manifest.name,
+                manifest.version,
+                manifest.workflows.len(),
+                manifest.trust,
+                if manifest.pinned { ", pinned" } else { "" }
+            );
+        },
+        PackCommand::Remove { name } => {
+            pack::remove_pack(&name, &packs_dir)?;
+            println!("Removed {}", name);
+        },
+        PackCommand::List => {
+            let packs = pack::list_packs(&packs_dir);
+            if packs.is_empty() {
+                println!("No packs installed in {}", packs_dir.display());
+            }
+            for installed in packs {
+                let manifest = &installed.manifest;
+                println!(
+                    "  {}{} - {}",
+                    installed.label(),
+                    if manifest.pinned { " [pinned]" } else { "" },
+                    manifest.description
+                );
+                if let Some(author) = &manifest.provenance.author {
+                    println!("      author: {}", author);
+                }
+                if let Some(from) = &manifest.provenance.installed_from {
+                    println!("      from:   {}", from);
+                }
+            }
+        },
+    }
+    Ok(())
+}
+
+/// How much the `--no-tui` runner prints; independent of the log level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputTier {
+    /// Errors and the final status
+    Quiet,
+    /// Preamble, step progress and the summary table
+    Normal,
+    /// Also every line RAPS commands print (`-vv`)
+    Streamed,
+}
+
+impl OutputTier {
+    fn from_flags(quiet: bool, verbose: u8) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Self::Quiet,
+            (false, 0 | 1) => Self::Normal,
+            (false, _) => Self::Streamed,
+        }
+    }
+}
+
+/// Print the workflow catalog for `--list`
+fn list_workflows(engine: &WorkflowEngine, format: ListFormat, assets_dir: &std::path::Path) -> Result<()> {
+    let checker = PreflightChecker::new().with_assets_dir(assets_dir);
+    let entries: Vec<CatalogEntry> = engine
+        .list()
+        .iter()
+        .filter_map(|workflow| engine.get_workflow(&workflow.id))
+        .map(|definition| CatalogEntry::new(definition, checker.check_definition(definition).blocking_checks))
+        .collect();
+
+    if format == ListFormat::Table && entries.is_empty() {
+        let dirs: Vec<String> = engine.workflows_dirs().iter().map(|d| d.display().to_string()).collect();
+        println!("No workflows found in {}", dirs.join(", "));
+        println!("\nCreate workflow YAML files in a workflows directory to get started.");
+        return Ok(());
+    }
+    print!("{}", catalog::render(&entries, format)?);
+    if format == ListFormat::Table {
+        println!("\nRun a workflow with: raps-demo --no-tui --workflow <workflow-id>");
+    }
+    Ok(())
+}
+
+/// Run a workflow in non-interactive CLI mode
+async fn run_cli_mode(
+    mut engine: WorkflowEngine,
+    workflow_id: String,
+    profile: Option<String>,
+    from_outputs: Vec<String>,
+    tier: OutputTier,
+) -> Result<RunExit> {
+    tracing::info!("Executing workflow: {}", workflow_id);
+    let Some(definition) = engine.get_workflow(&workflow_id).cloned() else {
+        // Listed but with steps that don't parse
+        if engine.get_workflows().contains_key(&workflow_id) {
+            engine.get_workflows().load(&workflow_id)?;
+        }
+        eprintln!("Error: Workflow '{}' not found", workflow_id);
+        eprintln!("\nAvailable workflows:");
+        for workflow in engine.list() {
+            println!("  - {} ({})", workflow.id, workflow.name);
+        }
+        return Ok(RunExit::Validation);
+    };
+    let quiet = tier == OutputTier::Quiet;
+    if !quiet {
+        println!("Starting workflow: {} - {}", definition.metadata.name, definition.metadata.description);
+        let preamble = {
+            let history = engine.history().and_then(|h| h.read().ok());
+            run_summary::preamble(&definition, history.as_deref())
+        };
+        for line in preamble {
+            println!("{}", line);
+        }
+        println!();
+    }
+    let started = std::time::Instant::now();
+    let mut finished_steps = Vec::new();
+    let mut receiver = engine.subscribe();
+    
+    let options = ExecutionOptions {
+        interactive: false,
+        verbose: true,
+        auto_cleanup: true,
+        profile,
+        from_outputs,
+        ..engine.execution_options()
+    };
+    
+    if let Err(e) = engine.execute(&workflow_id, options).await {
+        eprintln!("✗ {:#}", e);
+        return Ok(RunExit::not_started(&e));
+    }
+    
+    // Wait for execution updates; Ctrl-C cancels the run and records it
+    loop {
+        let update = tokio::select! {
+            update = receiver.recv() => update,
+            _ = tokio::signal::ctrl_c() => {
+                println!("\n✗ Interrupted");
+                for line in interrupted_summary(&engine.interrupt().await?) {
+                    println!("  {}", line);
+                }
+                return Ok(RunExit::Interrupted);
+            },
+        };
+        let Some(update) = update else {
+            break;
+        };
+        match update {
+            workflow::ExecutionUpdate::StepStarted { step, .. } if !quiet => {
+                println!("  → Step: {}", step.name);
+            }
+            workflow::ExecutionUpdate::StepOutput { line, stderr, .. } => {
+                if stderr {
+                    eprintln!("    │ {}", line);
+                } else {
+                    println!("    │ {}", line);
+                }
+            }
+            workflow::ExecutionUpdate::ArtifactsCollected { directory, files, .. } if !quiet => {
+                println!("  Artifacts: {} file(s) saved to {}", files.len(), directory.display());
+            }
+            workflow::ExecutionUpdate::CleanupCompleted { results, .. } if !quiet => {
+                for result in &results {
+                    match &result.error {
+                        None => println!("  ✓ Cleanup: {}", result.command),
+                        Some(error) => println!("  ✗ Cleanup: {} ({})", result.command, error),
                     }
-                    workflow::ExecutionUpdate::Failed { error, .. } => {
-                        println!("\n✗ Workflow failed: {}", error.message);
-                        for suggestion in &error.recovery_suggestions {
-                            println!("  Suggestion: {}", suggestion);
-                        }
-                        break;
+                }
+            }
+            workflow::ExecutionUpdate::HookCompleted { event, action, success, message, .. } if !quiet => {
+                let status = if success { "✓" } else { "✗" };
+                println!("  {} Hook {}: {} ({})", status, event, action, message);
+            }
+            workflow::ExecutionUpdate::StepCompleted { .. } if quiet => {}
+            workflow::ExecutionUpdate::StepCompleted { result, .. } => {
+                match result.status {
+                    workflow::ExecutionStatus::Completed => println!("  ✓ Completed: {}", result.step_id),
+                    workflow::ExecutionStatus::AlreadyExists => {
+                        println!("  ↷ Skipped (already exists): {}", result.step_id)
+                    },
+                    _ => println!("  ✗ Completed: {}", result.step_id),
+                }
+                finished_steps.push(result);
+            }
+            workflow::ExecutionUpdate::Completed { result, .. } => {
+                let exit = RunExit::finished(&result);
+                if quiet {
+                    let status = if result.success { "✓ completed" } else { "✗ failed" };
+                    println!("{} {} ({} steps)", workflow_id, status, result.steps_completed);
+                    return Ok(exit);
+                }
+                if result.success {
+                    println!("\n✓ Workflow completed successfully ({} steps)", result.steps_completed);
+                    for (name, value) in &result.outputs {
+                        println!("  Output {}: {}", name, redact_text(value));
                     }
-                    _ => {}
+                } else {
+                    println!("\n✗ Workflow failed after {} steps", result.steps_completed);
+                }
+                println!();
+                for line in run_summary::result_table(&result) {
+                    println!("{}", line);
+                }
+                return Ok(exit);
+            }
+            workflow::ExecutionUpdate::Failed { error, .. } => {
+                println!("\n✗ Workflow failed: {}", error.message);
+                if let Some(kind) = error.kind {
+                    let retry = if kind.is_retryable() { ", retryable" } else { "" };
+                    println!("  Kind: {}{}", kind, retry);
+                }
+                for suggestion in &error.recovery_suggestions {
+                    println!("  Suggestion: {}", suggestion);
+                }
+                let exit = RunExit::StepFailure;
+                if quiet {
+                    return Ok(exit);
+                }
+                finished_steps.extend(run_summary::failed_step(&error));
+                println!();
+                for line in run_summary::step_table(&finished_steps) {
+                    println!("{}", line);
                 }
+                println!(
+                    "  Total: failed after {}",
+                    workflow::history::format_duration_secs(started.elapsed().as_secs_f64())
+                );
+                return Ok(exit);
             }
-        } else {
-            eprintln!("Error: Workflow '{}' not found", workflow_id);
-            eprintln!("\nAvailable workflows:");
-            for workflow in &workflows {
-                println!("  - {} ({})", workflow.id, workflow.name);
+            workflow::ExecutionUpdate::WaitingForLock { wait, .. } if !quiet => {
+                println!("  ⏳ Waiting for lock '{}' held by {}", wait.lock, wait.holder);
             }
-            std::process::exit(1);
+            workflow::ExecutionUpdate::Stalled { report, .. } if !quiet => {
+                println!("  ⚠ Stalled: {} (press Ctrl-C to cancel)", report.summary());
+            }
+            _ => {}
         }
     }
-    
+
+    Ok(RunExit::Success)
+}
+
+/// Load the demo configuration, falling back to defaults (metrics disabled,
+/// English), and the environments of the RAPS profiles
+///
+/// A configuration given with `--config` must exist and parse.
+async fn load_demo_config(path: Option<&std::path::Path>) -> Result<(DemoConfig, ProfileEnvironments)> {
+    if let Some(path) = path {
+        let manager = ConfigManager::with_config_path(path).await?;
+        return Ok((manager.demo_config().clone(), manager.profile_environments()));
+    }
+    Ok(match ConfigManager::new().await {
+        Ok(manager) => (manager.demo_config().clone(), manager.profile_environments()),
+        Err(e) => {
+            tracing::debug!("Using default demo settings: {:#}", e);
+            (DemoConfig::default(), ProfileEnvironments::new())
+        },
+    })
+}
+
+/// Load the RAPS configuration (base URL, stored tokens), falling back to
+/// defaults when there is none
+async fn load_raps_config(path: Option<&std::path::Path>) -> RapsConfig {
+    let manager = match path {
+        Some(path) => ConfigManager::with_config_path(path).await,
+        None => ConfigManager::new().await,
+    };
+    match manager {
+        Ok(manager) => manager.raps_config().clone(),
+        Err(e) => {
+            tracing::debug!("Using default RAPS settings: {:#}", e);
+            RapsConfig::default()
+        },
+    }
+}
+
+/// Export a workflow definition as a diagram file
+fn export_workflow(
+    engine: &WorkflowEngine,
+    workflow_id: &str,
+    format: DiagramFormat,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let definition = engine
+        .get_workflow(&workflow_id.to_string())
+        .with_context(|| format!("Workflow '{}' not found", workflow_id))?;
+
+    let diagram = render_diagram(definition, format);
+    let output = output
+        .unwrap_or_else(|| PathBuf::from(format!("{}.{}", workflow_id, format.extension())));
+
+    if output.as_os_str() == "-" {
+        print!("{}", diagram);
+    } else {
+        std::fs::write(&output, diagram)
+            .with_context(|| format!("Failed to write diagram: {}", output.display()))?;
+        println!("Exported {} to {}", workflow_id, output.display());
+    }
+
     Ok(())
 }
 
-/// Initialize logging based on verbosity level
-fn init_logging(verbose: bool) -> Result<()> {
-    use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+/// Level of the logs printed to the console: errors only with `--quiet`,
+/// debug with `-v` (`-vv` streams command output but logs no more than `-v`)
+fn console_log_level(quiet: bool, verbose: u8) -> &'static str {
+    match (quiet, verbose) {
+        (true, _) => "error",
+        (false, 0) => "info",
+        (false, _) => "debug",
+    }
+}
+
+/// Initialize logging based on verbosity level.
+///
+/// Console output honors `RUST_LOG`, or else `log_level`; independently of
+/// both, a JSON session transcript is written to the config directory's
+/// `logs/` folder at debug level. Both have credentials redacted. With an OTLP endpoint, spans are also exported to that collector.
+/// The returned guards must be kept alive until exit.
+fn init_logging(
+    log_level: &str,
+    otlp_endpoint: Option<&str>,
+) -> Result<(Option<WorkerGuard>, Option<TelemetryGuard>)> {
+    use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+    let console_layer = tracing_subscriber::fmt::layer()
+        .with_writer(|| RedactingWriter::new(std::io::stderr()))
+        .with_filter(
+            EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| format!("raps_demo_workflows={}", log_level).into()),
+        );
 
-    let log_level = if verbose { "debug" } else { "info" };
+    // Session transcript; logging still works if the file can't be created
+    let (file_layer, guard) = match create_session_log() {
+        Ok((_path, file)) => {
+            let (writer, guard) = tracing_appender::non_blocking(RedactingWriter::new(file));
+            let layer = tracing_subscriber::fmt::layer()
+                .json()
+                .with_current_span(false)
+                .with_writer(writer)
+                .with_filter(EnvFilter::new("raps_demo=debug,raps_demo_workflows=debug"));
+            (Some(layer), Some(guard))
+        },
+        Err(e) => {
+            eprintln!("Warning: session log disabled: {:#}", e);
+            (None, None)
+        },
+    };
+
+    // Span export; a missing collector must not stop the demo
+    let (otlp_layer, telemetry_guard) = match otlp_endpoint.map(telemetry::otlp_layer) {
+        Some(Ok((layer, guard))) => (Some(layer), Some(guard)),
+        Some(Err(e)) => {
+            eprintln!("Warning: span export disabled: {:#}", e);
+            (None, None)
+        },
+        None => (None, None),
+    };
 
     tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| format!("raps_demo_workflows={}", log_level).into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
+        .with(otlp_layer)
+        .with(console_layer)
+        .with(file_layer)
+        .with(CrashLogLayer.with_filter(EnvFilter::new("raps_demo=debug,raps_demo_workflows=debug")))
         .init();
 
-    Ok(())
+    Ok((guard, telemetry_guard))
 }
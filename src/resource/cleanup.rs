@@ -3,10 +3,11 @@
 // This module implements comprehensive cleanup orchestration with support for
 // automatic, manual, and policy-based cleanup of APS resources.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info};
@@ -85,6 +86,8 @@ pub struct CleanupOrchestrator<T: ResourceTracker + CostEstimator> {
     workflow_strategies: HashMap<WorkflowId, CleanupStrategy>,
     /// Interrupted workflow tracking
     interrupted_workflows: HashMap<WorkflowId, InterruptedWorkflowCleanup>,
+    /// File the interrupted workflows are persisted to, if any
+    interrupted_file: Option<PathBuf>,
 }
 
 impl<T: ResourceTracker + CostEstimator + Send + Sync> CleanupOrchestrator<T> {
@@ -95,9 +98,36 @@ impl<T: ResourceTracker + CostEstimator + Send + Sync> CleanupOrchestrator<T> {
             default_policies: Self::create_default_policies(),
             workflow_strategies: HashMap::new(),
             interrupted_workflows: HashMap::new(),
+            interrupted_file: None,
         }
     }
 
+    /// Persist interrupted workflows to `path`, loading the ones recorded earlier
+    pub fn with_interrupted_file<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if path.exists() {
+            let json = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read interrupted workflows: {}", path.display()))?;
+            self.interrupted_workflows = serde_json::from_str(&json)
+                .with_context(|| format!("Failed to parse interrupted workflows: {}", path.display()))?;
+        }
+        self.interrupted_file = Some(path);
+        Ok(self)
+    }
+
+    /// Write the interrupted workflows to the configured file
+    fn save_interrupted_workflows(&self) -> Result<()> {
+        let Some(path) = &self.interrupted_file else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(&self.interrupted_workflows)
+            .context("Failed to serialize interrupted workflows")?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write interrupted workflows: {}", path.display()))?;
+        debug!("Saved interrupted workflows to: {}", path.display());
+        Ok(())
+    }
+
     /// Create default cleanup policies for different resource types
     fn create_default_policies() -> HashMap<String, CleanupPolicy> {
         let mut policies = HashMap::new();
@@ -519,6 +549,7 @@ impl<T: ResourceTracker + CostEstimator + Send + Sync> CleanupOrchestrator<T> {
 
         // Store for later reference
         self.interrupted_workflows.insert(workflow_id, cleanup_info.clone());
+        self.save_interrupted_workflows()?;
 
         Ok(cleanup_info)
     }
@@ -531,6 +562,9 @@ impl<T: ResourceTracker + CostEstimator + Send + Sync> CleanupOrchestrator<T> {
     /// Clear interrupted workflow tracking after cleanup
     pub fn clear_interrupted_workflow(&mut self, workflow_id: &WorkflowId) {
         self.interrupted_workflows.remove(workflow_id);
+        if let Err(e) = self.save_interrupted_workflows() {
+            error!("Failed to persist interrupted workflows: {:#}", e);
+        }
     }
 
     /// Get cleanup policy for a resource type
@@ -712,6 +746,26 @@ mod tests {
         assert!(!cleanup_info.manual_instructions.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_interrupted_workflows_are_persisted() {
+        let (orchestrator, temp_dir) = create_test_orchestrator().await;
+        let path = temp_dir.path().join("interrupted.json");
+        let mut orchestrator = orchestrator.with_interrupted_file(&path).unwrap();
+        let workflow_id = "interrupted-workflow".to_string();
+
+        orchestrator
+            .handle_interrupted_workflow(workflow_id.clone(), Utc::now())
+            .await
+            .unwrap();
+        assert!(path.exists());
+
+        let tracker = FileBasedResourceTracker::new(temp_dir.path().join("other.json")).unwrap();
+        let reloaded = CleanupOrchestrator::new(Arc::new(RwLock::new(tracker)))
+            .with_interrupted_file(&path)
+            .unwrap();
+        assert_eq!(reloaded.get_interrupted_workflows()[0].workflow_id, workflow_id);
+    }
+
     #[tokio::test]
     async fn test_cleanup_strategy_immediate() {
         let (mut orchestrator, _temp_dir) = create_test_orchestrator().await;
@@ -728,7 +782,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_cost_based_cleanup() {
-        let (mut orchestrator, _temp_dir) = create_test_orchestrator().await;
+        let (orchestrator, _temp_dir) = create_test_orchestrator().await;
         
         let workflow_id = "cost-test-workflow".to_string();
         
@@ -8,44 +8,58 @@ pub mod tracker;
 pub mod types;
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::config::types::ConfigPaths;
+use crate::error::{CleanupError, RapsDemoError};
+use crate::workflow::WorkflowId;
 
 // Re-export commonly used types
 pub use tracker::FileBasedResourceTracker;
+pub use cleanup::{CleanupOrchestrator, InterruptedWorkflowCleanup};
 pub use types::{CleanupPolicy, CleanupResult, ResourceId, ResourceType, TrackedResource};
 
+/// File next to the tracker state recording interrupted workflows
+const INTERRUPTED_FILE: &str = "interrupted_workflows.json";
+
 /// High-level resource manager that coordinates tracking and cleanup
 pub struct ResourceManager {
     tracker: FileBasedResourceTracker,
+    /// Where interrupted workflows are recorded
+    interrupted_file: PathBuf,
 }
 
 impl ResourceManager {
     /// Create a new resource manager instance
-    pub fn new() -> Result<Self> {
+    pub fn new() -> Result<Self, RapsDemoError> {
         tracing::debug!("Initializing resource manager");
 
         // Use default state file location
         let state_file = Self::default_state_file()?;
-        let tracker = FileBasedResourceTracker::new(state_file)?;
-
-        Ok(Self { tracker })
+        Self::with_state_file(state_file)
     }
 
     /// Create a resource manager with a custom state file
-    pub fn with_state_file<P: Into<PathBuf>>(state_file: P) -> Result<Self> {
-        let tracker = FileBasedResourceTracker::new(state_file.into())?;
-        Ok(Self { tracker })
+    pub fn with_state_file<P: Into<PathBuf>>(state_file: P) -> Result<Self, RapsDemoError> {
+        let state_file = state_file.into();
+        let interrupted_file = state_file.with_file_name(INTERRUPTED_FILE);
+        let tracker = FileBasedResourceTracker::new(state_file).map_err(CleanupError::Other)?;
+        Ok(Self { tracker, interrupted_file })
     }
 
     /// Get the default state file location
-    fn default_state_file() -> Result<PathBuf> {
-        let config_dir = dirs::config_dir()
-            .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
+    fn default_state_file() -> Result<PathBuf, CleanupError> {
+        // With `--config`, next to the rest of that configuration
+        let raps_dir = match ConfigPaths::config_dir_override() {
+            Some(dir) => dir.to_path_buf(),
+            None => dirs::config_dir().ok_or(CleanupError::NoStateDirectory)?.join("raps-demo"),
+        };
+        std::fs::create_dir_all(&raps_dir).map_err(|e| CleanupError::Other(e.into()))?;
         
-        let raps_dir = config_dir.join("raps-demo");
-        std::fs::create_dir_all(&raps_dir)?;
-        
-        Ok(raps_dir.join("resource_tracker.json"))
+        Ok(raps_dir.join(ConfigPaths::RESOURCE_STATE_FILE))
     }
 
     /// Get access to the underlying tracker
@@ -57,4 +71,26 @@ impl ResourceManager {
     pub fn tracker_mut(&mut self) -> &mut FileBasedResourceTracker {
         &mut self.tracker
     }
+
+    /// Record workflows stopped by an interrupt, with cleanup instructions for
+    /// the resources they created, and persist the records for later sessions
+    pub async fn record_interrupted_workflows(
+        self,
+        workflow_ids: &[WorkflowId],
+        interrupted_at: DateTime<Utc>,
+    ) -> Result<Vec<InterruptedWorkflowCleanup>, RapsDemoError> {
+        let mut orchestrator = CleanupOrchestrator::new(Arc::new(RwLock::new(self.tracker)))
+            .with_interrupted_file(&self.interrupted_file)
+            .map_err(CleanupError::Other)?;
+        let mut records = Vec::with_capacity(workflow_ids.len());
+        for workflow_id in workflow_ids {
+            records.push(
+                orchestrator
+                    .handle_interrupted_workflow(workflow_id.clone(), interrupted_at)
+                    .await
+                    .map_err(CleanupError::Other)?,
+            );
+        }
+        Ok(records)
+    }
 }
\ No newline at end of file
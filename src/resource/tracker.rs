@@ -245,6 +245,8 @@ impl FileBasedResourceTracker {
                         file_path: None,
                         batch: None,
                         expires_in: None,
+                        chunk_size_mb: None,
+                        parallel_parts: None,
                     },
                 }]
             },
@@ -285,7 +287,7 @@ impl ResourceTracker for FileBasedResourceTracker {
         // Add to workflow index
         self.workflow_resources
             .entry(workflow_id)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(resource_id);
 
         // Save state to disk
@@ -464,20 +466,16 @@ impl CostEstimator for FileBasedResourceTracker {
 
         for command in workflow_steps {
             let estimated_cost = match command {
-                RapsCommand::Bucket { action, .. } => {
-                    match action {
-                        crate::workflow::BucketAction::Create => 0.01, // Minimal bucket cost
-                        _ => 0.0,
-                    }
-                },
-                RapsCommand::Object { action, params: _ } => {
-                    match action {
-                        crate::workflow::ObjectAction::Upload => {
-                            // Estimate based on typical file sizes
-                            0.023 // Assume 1GB file
-                        },
-                        _ => 0.0,
-                    }
+                RapsCommand::Bucket {
+                    action: crate::workflow::BucketAction::Create,
+                    ..
+                } => 0.01, // Minimal bucket cost
+                RapsCommand::Object {
+                    action: crate::workflow::ObjectAction::Upload,
+                    ..
+                } => {
+                    // Estimate based on typical file sizes
+                    0.023 // Assume 1GB file
                 },
                 RapsCommand::Translate { .. } => 0.50, // Per translation
                 RapsCommand::DesignAutomation { .. } => 0.10, // Per work item
@@ -628,6 +626,7 @@ mod tests {
                     format: Some("svf2".to_string()),
                     output_dir: None,
                     wait: None,
+                    region: None,
                 },
             },
         ];
@@ -140,9 +140,10 @@ impl TrackedResource {
 }
 
 /// Cleanup policy for resources
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CleanupPolicy {
     /// Clean up immediately after workflow completion
+    #[default]
     Immediate,
     /// Clean up after a specified duration
     Delayed { duration: Duration },
@@ -152,12 +153,6 @@ pub enum CleanupPolicy {
     Never,
 }
 
-impl Default for CleanupPolicy {
-    fn default() -> Self {
-        CleanupPolicy::Immediate
-    }
-}
-
 /// Result of a cleanup operation
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CleanupResult {
@@ -260,6 +255,22 @@ impl ResourceNaming {
         format!("raps-demo-photoscene-{}", timestamp)
     }
 
+    /// Bucket name following the demo convention, derived from `name` so
+    /// every step referring to it gets the same result
+    pub fn demo_bucket_name_for(name: &str) -> String {
+        format!("raps-demo-{}", name)
+    }
+
+    /// Object key following the demo convention, derived from `key`
+    pub fn demo_object_key_for(key: &str) -> String {
+        format!("demo-{}", key)
+    }
+
+    /// Folder name following the demo convention, derived from `name`
+    pub fn demo_folder_name_for(name: &str) -> String {
+        format!("RAPS Demo - {}", name)
+    }
+
     /// Check if a name follows demo conventions
     pub fn is_demo_name(name: &str) -> bool {
         name.contains("demo-") || name.contains("test-") || name.contains("raps-demo-") || name.contains("RAPS Demo")
@@ -295,6 +306,9 @@ mod tests {
         assert!(ResourceNaming::is_demo_name("test-resource"));
         assert!(ResourceNaming::is_demo_name("RAPS Demo - Test Folder"));
         assert!(!ResourceNaming::is_demo_name("production-bucket"));
+        assert!(ResourceNaming::is_demo_name(&ResourceNaming::demo_bucket_name_for("production-bucket")));
+        assert!(ResourceNaming::is_demo_name(&ResourceNaming::demo_object_key_for("model.rvt")));
+        assert!(ResourceNaming::is_demo_name(&ResourceNaming::demo_folder_name_for("Specs")));
     }
 
     #[test]
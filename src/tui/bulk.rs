@@ -0,0 +1,123 @@
+//! Bulk actions on workflows marked in the sidebar
+
+use std::collections::VecDeque;
+
+use crate::workflow::WorkflowMetadata;
+
+/// Workflows queued to run one after another
+#[derive(Debug, Clone, Default)]
+pub struct RunQueue {
+    pending: VecDeque<String>,
+    current: Option<String>,
+    succeeded: Vec<String>,
+    failed: Vec<String>,
+}
+
+impl RunQueue {
+    /// Create a queue for the given workflow IDs, run in order
+    pub fn new<I: IntoIterator<Item = String>>(ids: I) -> Self {
+        Self {
+            pending: ids.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Take the next workflow to start, if none is currently running
+    pub fn start_next(&mut self) -> Option<String> {
+        if self.current.is_some() {
+            return None;
+        }
+        self.current = self.pending.pop_front();
+        self.current.clone()
+    }
+
+    /// Workflow currently running from this queue
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    /// Record the outcome of the current workflow
+    pub fn finish_current(&mut self, success: bool) {
+        if let Some(id) = self.current.take() {
+            if success {
+                self.succeeded.push(id);
+            } else {
+                self.failed.push(id);
+            }
+        }
+    }
+
+    /// Number of workflows not yet started
+    pub fn remaining(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Whether every queued workflow has finished
+    pub fn is_done(&self) -> bool {
+        self.current.is_none() && self.pending.is_empty()
+    }
+
+    /// Summary of the finished run
+    pub fn summary(&self) -> String {
+        let mut text = format!(
+            "{} succeeded, {} failed",
+            self.succeeded.len(),
+            self.failed.len()
+        );
+        if !self.failed.is_empty() {
+            text.push_str(&format!("\n\nFailed: {}", self.failed.join(", ")));
+        }
+        text
+    }
+}
+
+/// Combined cost and duration estimate for a set of workflows
+pub fn combined_estimate(workflows: &[&WorkflowMetadata]) -> String {
+    let total_cost: f64 = workflows
+        .iter()
+        .filter_map(|w| w.cost_estimate.as_ref())
+        .map(|c| c.max_cost_usd)
+        .sum();
+    let total_secs: i64 = workflows
+        .iter()
+        .map(|w| w.estimated_duration.num_seconds())
+        .sum();
+
+    let mut lines: Vec<String> = workflows
+        .iter()
+        .map(|w| match &w.cost_estimate {
+            Some(cost) => format!("  {:<28} ${:.2}", w.id, cost.max_cost_usd),
+            None => format!("  {:<28} free", w.id),
+        })
+        .collect();
+    lines.push(String::new());
+    lines.push(format!("Total: up to ${:.2}", total_cost));
+    lines.push(format!(
+        "Estimated time: {}m {:02}s",
+        total_secs / 60,
+        total_secs % 60
+    ));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_queue_runs_in_order() {
+        let mut queue = RunQueue::new(vec!["a".to_string(), "b".to_string()]);
+
+        assert_eq!(queue.start_next().as_deref(), Some("a"));
+        // Nothing else starts while `a` is running
+        assert_eq!(queue.start_next(), None);
+        queue.finish_current(true);
+
+        assert_eq!(queue.start_next().as_deref(), Some("b"));
+        assert_eq!(queue.remaining(), 0);
+        queue.finish_current(false);
+
+        assert!(queue.is_done());
+        assert_eq!(queue.summary(), "1 succeeded, 1 failed\n\nFailed: b");
+    }
+}
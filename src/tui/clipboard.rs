@@ -0,0 +1,68 @@
+// System clipboard access for the TUI
+//
+// Mouse capture disables the terminal's own text selection, so values such
+// as URNs and signed URLs are copied programmatically. The system clipboard
+// is used when available; otherwise (e.g. over SSH) the text is sent with an
+// OSC 52 escape sequence, which most modern terminals forward to the local
+// clipboard.
+
+use anyhow::Result;
+use std::io::Write;
+
+use crate::utils::encoding::base64_encode;
+
+/// How a value ended up on the clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyMethod {
+    System,
+    Osc52,
+}
+
+/// Lazily opened system clipboard
+#[derive(Default)]
+pub struct Clipboard {
+    system: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    /// Copy text, falling back to OSC 52 if the system clipboard is unavailable
+    pub fn copy(&mut self, text: &str) -> Result<CopyMethod> {
+        if self.system.is_none() {
+            match arboard::Clipboard::new() {
+                Ok(clipboard) => self.system = Some(clipboard),
+                Err(e) => tracing::debug!("System clipboard unavailable: {}", e),
+            }
+        }
+
+        if let Some(clipboard) = self.system.as_mut() {
+            match clipboard.set_text(text) {
+                Ok(()) => return Ok(CopyMethod::System),
+                Err(e) => tracing::debug!("System clipboard copy failed: {}", e),
+            }
+        }
+
+        let mut stdout = std::io::stdout();
+        stdout.write_all(osc52_sequence(text).as_bytes())?;
+        stdout.flush()?;
+        Ok(CopyMethod::Osc52)
+    }
+}
+
+/// OSC 52 escape sequence setting the clipboard to `text`
+pub fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(osc52_sequence("urn"), "\x1b]52;c;dXJu\x07");
+    }
+}
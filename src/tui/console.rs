@@ -0,0 +1,108 @@
+//! Bounded console log
+//!
+//! Keeps the most recent console lines in a ring buffer so a full demo day
+//! doesn't grow memory without limit. Repeated lines collapse into one with a
+//! `(xN)` counter. Every line is also written to the session transcript, which
+//! holds the complete log.
+
+use std::collections::VecDeque;
+
+use crate::utils::redact::redact_text;
+
+/// Default number of console lines kept in memory
+pub const DEFAULT_CONSOLE_CAPACITY: usize = 1000;
+
+/// Ring buffer of console lines with duplicate collapsing
+#[derive(Debug, Clone)]
+pub struct ConsoleLog {
+    /// Lines and how many times each was pushed in a row
+    lines: VecDeque<(String, usize)>,
+    capacity: usize,
+}
+
+impl ConsoleLog {
+    /// Empty log keeping at most `capacity` lines (at least one)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Change the capacity, dropping the oldest lines if needed
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.lines.len() > self.capacity {
+            self.lines.pop_front();
+        }
+    }
+
+    /// Append a line, or bump the counter if it repeats the last one
+    pub fn push(&mut self, line: String) {
+        let line = redact_text(&line);
+        tracing::info!(target: "raps_demo::console", "{}", line);
+        match self.lines.back_mut() {
+            Some((last, count)) if *last == line => *count += 1,
+            _ => {
+                if self.lines.len() == self.capacity {
+                    self.lines.pop_front();
+                }
+                self.lines.push_back((line, 1));
+            },
+        }
+    }
+
+    /// Lines as displayed, repeated ones suffixed with `(xN)`
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = String> + '_ {
+        self.lines.iter().map(|(line, count)| match count {
+            1 => line.clone(),
+            n => format!("{} (x{})", line, n),
+        })
+    }
+
+    /// The last `n` displayed lines, oldest first
+    pub fn tail(&self, n: usize) -> Vec<String> {
+        let skip = self.lines.len().saturating_sub(n);
+        self.iter().skip(skip).collect()
+    }
+
+    /// Number of (collapsed) lines kept
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Whether the log is empty
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+}
+
+impl Default for ConsoleLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_CONSOLE_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_and_duplicates() {
+        let mut log = ConsoleLog::new(3);
+        log.push("a".to_string());
+        log.push("b".to_string());
+        log.push("b".to_string());
+        log.push("b".to_string());
+        assert_eq!(log.tail(10), vec!["a", "b (x3)"]);
+
+        log.push("c".to_string());
+        log.push("d".to_string());
+        assert_eq!(log.len(), 3);
+        assert_eq!(log.tail(2), vec!["c", "d"]);
+        assert_eq!(log.iter().next().unwrap(), "b (x3)");
+
+        log.set_capacity(1);
+        assert_eq!(log.tail(10), vec!["d"]);
+    }
+}
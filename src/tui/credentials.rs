@@ -0,0 +1,262 @@
+// Auth panel: which credentials is this demo using?
+//
+// Combines `raps auth status` with the RAPS configuration loaded through
+// `ConfigManager` to show the active profile, the token type (2-legged app
+// token or 3-legged user token), its scopes and a live expiry countdown.
+// The panel also refreshes the token, logs in or logs out through the CLI.
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::config::ConfigManager;
+use crate::workflow::client::RapsClient;
+use crate::workflow::{AuthAction, RapsCommand};
+
+/// How the token was obtained
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenType {
+    /// Client credentials: acts as the app
+    TwoLegged,
+    /// Authorization code: acts as a signed-in user
+    ThreeLegged,
+}
+
+impl TokenType {
+    fn label(self) -> &'static str {
+        match self {
+            Self::TwoLegged => "2-legged (app)",
+            Self::ThreeLegged => "3-legged (user)",
+        }
+    }
+
+    /// Type named by a status field such as `3-legged` or `client_credentials`
+    fn from_text(text: &str) -> Option<Self> {
+        let text = text.to_lowercase();
+        if text.contains('3') || text.contains("three") || text.contains("authorization_code") || text.contains("user") {
+            Some(Self::ThreeLegged)
+        } else if text.contains('2') || text.contains("two") || text.contains("client_credentials") || text.contains("app") {
+            Some(Self::TwoLegged)
+        } else {
+            None
+        }
+    }
+}
+
+/// What is known about the current credentials
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuthInfo {
+    /// Whether RAPS reports a usable login
+    pub authenticated: bool,
+    pub profile: Option<String>,
+    pub token_type: Option<TokenType>,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Signed-in user of a 3-legged token
+    pub user: Option<String>,
+    /// Client ID, shortened for display
+    pub client_id: Option<String>,
+    /// Problems gathering the information
+    pub errors: Vec<String>,
+}
+
+impl AuthInfo {
+    /// Information from `raps auth status` JSON output
+    pub fn from_status(json: &Value, now: DateTime<Utc>) -> Self {
+        let text = |keys: &[&str]| keys.iter().find_map(|k| json.get(*k).and_then(Value::as_str)).map(str::to_string);
+
+        let scopes = match json.get("scopes").or_else(|| json.get("scope")) {
+            Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).map(str::to_string).collect(),
+            Some(Value::String(list)) => list.split_whitespace().map(str::to_string).collect(),
+            _ => Vec::new(),
+        };
+        let expires_at = text(&["expires_at", "expiresAt"])
+            .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+            .map(|at| at.with_timezone(&Utc))
+            .or_else(|| {
+                let seconds = json.get("expires_in").or_else(|| json.get("expiresIn"))?.as_i64()?;
+                Some(now + chrono::Duration::seconds(seconds))
+            });
+        let user = text(&["user", "email", "userName", "username"]);
+        let token_type = text(&["token_type", "type", "grant_type", "auth_type"])
+            .and_then(|t| TokenType::from_text(&t))
+            .or_else(|| user.as_ref().map(|_| TokenType::ThreeLegged));
+
+        Self {
+            authenticated: json
+                .get("authenticated")
+                .or_else(|| json.get("logged_in"))
+                .and_then(Value::as_bool)
+                .unwrap_or(expires_at.is_some()),
+            profile: text(&["profile", "current_profile"]),
+            token_type,
+            scopes,
+            expires_at,
+            user,
+            client_id: text(&["client_id", "clientId"]).map(|id| shorten_id(&id)),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Fill in what the status did not say from the RAPS configuration
+    pub fn merge_config(&mut self, manager: &ConfigManager) {
+        let config = manager.raps_config();
+        if self.profile.is_none() {
+            self.profile = manager.current_profile().map(str::to_string);
+        }
+        if self.client_id.is_none() && !config.client_id.is_empty() {
+            self.client_id = Some(shorten_id(&config.client_id));
+        }
+        if let Some(tokens) = &config.auth_tokens {
+            if self.scopes.is_empty() {
+                self.scopes = tokens.scopes.clone();
+            }
+            self.expires_at = self.expires_at.or(Some(tokens.expires_at));
+            // Only user logins come with a refresh token
+            if self.token_type.is_none() {
+                self.token_type = Some(match tokens.refresh_token {
+                    Some(_) => TokenType::ThreeLegged,
+                    None => TokenType::TwoLegged,
+                });
+            }
+        }
+    }
+
+    /// Panel text at time `now`
+    pub fn message(&self, now: DateTime<Utc>) -> String {
+        let unknown = || "unknown".to_string();
+        let mut lines = vec![
+            format!("Status:   {}", if self.authenticated { "logged in" } else { "not logged in" }),
+            format!("Profile:  {}", self.profile.clone().unwrap_or_else(|| "default".to_string())),
+            format!("Token:    {}", self.token_type.map(|t| t.label().to_string()).unwrap_or_else(unknown)),
+        ];
+        if let Some(user) = &self.user {
+            lines.push(format!("User:     {}", user));
+        }
+        if let Some(client_id) = &self.client_id {
+            lines.push(format!("Client:   {}", client_id));
+        }
+        lines.push(format!(
+            "Expires:  {}",
+            self.expires_at.map(|at| countdown(at, now)).unwrap_or_else(unknown)
+        ));
+        if self.scopes.is_empty() {
+            lines.push("Scopes:   unknown".to_string());
+        } else {
+            lines.push(format!("Scopes:   {}", self.scopes.join(" ")));
+        }
+        for error in &self.errors {
+            lines.push(format!("!! {}", error));
+        }
+        lines.push(String::new());
+        lines.push("r: refresh token, l: log in, x: log out, Esc: close".to_string());
+        lines.join("\n")
+    }
+}
+
+/// `in 42m 10s` or `expired 3m 5s ago`
+pub fn countdown(expires_at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (expires_at - now).num_seconds();
+    let span = |s: i64| {
+        let (h, m, s) = (s / 3600, s % 3600 / 60, s % 60);
+        if h > 0 {
+            format!("{}h {}m", h, m)
+        } else if m > 0 {
+            format!("{}m {}s", m, s)
+        } else {
+            format!("{}s", s)
+        }
+    };
+    if seconds > 0 {
+        format!("in {} ({})", span(seconds), expires_at.format("%H:%M:%S UTC"))
+    } else {
+        format!("expired {} ago", span(-seconds))
+    }
+}
+
+/// First and last four characters of an ID
+fn shorten_id(id: &str) -> String {
+    let chars: Vec<char> = id.chars().collect();
+    if chars.len() <= 12 {
+        return id.to_string();
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+/// Run `action` (if any), then gather the current credentials; returns the
+/// action's outcome for the console along with them
+pub async fn fetch_auth_info(client: &RapsClient, action: Option<AuthAction>) -> (Option<String>, AuthInfo) {
+    let outcome = match action {
+        Some(action) => {
+            let name = format!("{:?}", action).to_lowercase();
+            let outcome = match client.execute_command_async(&RapsCommand::Auth { action }).await {
+                Ok(result) if result.success => format!("raps auth {} succeeded", name),
+                Ok(result) => format!("raps auth {} failed: {}", name, result.error_message().unwrap_or_default()),
+                Err(e) => format!("raps auth {} failed: {:#}", name, e),
+            };
+            Some(outcome)
+        },
+        None => None,
+    };
+
+    let now = Utc::now();
+    let mut info = match client
+        .execute_command_async(&RapsCommand::Auth {
+            action: AuthAction::Status,
+        })
+        .await
+    {
+        Ok(result) => match &result.json_output {
+            Some(json) => AuthInfo {
+                authenticated: result.success,
+                ..AuthInfo::from_status(json, now)
+            },
+            None => AuthInfo {
+                authenticated: result.success,
+                ..AuthInfo::default()
+            },
+        },
+        Err(e) => AuthInfo {
+            errors: vec![format!("raps auth status: {:#}", e)],
+            ..AuthInfo::default()
+        },
+    };
+    match ConfigManager::new().await {
+        Ok(manager) => info.merge_config(&manager),
+        Err(e) => tracing::debug!("No RAPS configuration for the auth panel: {:#}", e),
+    }
+    (outcome, info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auth_status_and_countdown() {
+        let now = Utc::now();
+        let status = serde_json::json!({
+            "authenticated": true,
+            "profile": "demo",
+            "token_type": "3-legged",
+            "user": "presenter@example.com",
+            "client_id": "abcdefghijklmnopqrstuvwxyz",
+            "scope": "data:read bucket:create",
+            "expires_in": 2530
+        });
+        let info = AuthInfo::from_status(&status, now);
+        assert_eq!(info.token_type, Some(TokenType::ThreeLegged));
+        assert_eq!(info.scopes, vec!["data:read", "bucket:create"]);
+        assert_eq!(info.client_id.as_deref(), Some("abcd…wxyz"));
+
+        let message = info.message(now);
+        assert!(message.contains("Token:    3-legged (user)"));
+        assert!(message.contains("Expires:  in 42m 10s"));
+        assert_eq!(countdown(now - chrono::Duration::seconds(185), now), "expired 3m 5s ago");
+
+        let app = AuthInfo::from_status(&serde_json::json!({"grant_type": "client_credentials"}), now);
+        assert_eq!(app.token_type, Some(TokenType::TwoLegged));
+        assert!(!app.authenticated);
+    }
+}
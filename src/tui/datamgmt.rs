@@ -0,0 +1,408 @@
+// Data Management navigator for the Data tab
+//
+// Browses Hubs → Projects → Folders → Items through the RAPS CLI's Data
+// Management commands. Each level is listed only when its parent is first
+// expanded, so large hubs stay responsive. The IDs of the selected node and
+// its ancestors can be used as workflow parameters (`{params.hub_id}`,
+// `{params.project_id}`, `{params.folder_id}`, `{params.item_id}`), so ACC
+// workflows run against real project data instead of hand-typed IDs.
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use serde_json::Value;
+
+use crate::workflow::{DataMgmtAction, DataMgmtParams, RapsCommand};
+
+/// Level of a node in the Data Management hierarchy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DmKind {
+    Hub,
+    Project,
+    Folder,
+    Item,
+}
+
+impl DmKind {
+    /// Every level, outermost first
+    pub const ALL: [Self; 4] = [Self::Hub, Self::Project, Self::Folder, Self::Item];
+
+    /// Workflow parameter the node's ID is used as
+    pub fn param(self) -> &'static str {
+        match self {
+            Self::Hub => "hub_id",
+            Self::Project => "project_id",
+            Self::Folder => "folder_id",
+            Self::Item => "item_id",
+        }
+    }
+
+    /// Kind named by a JSON:API `type` such as `hubs` or `items`
+    fn from_type(kind: &str) -> Option<Self> {
+        let kind = kind.to_lowercase();
+        if kind.contains("hub") {
+            Some(Self::Hub)
+        } else if kind.contains("project") {
+            Some(Self::Project)
+        } else if kind.contains("folder") {
+            Some(Self::Folder)
+        } else if kind.contains("item") {
+            Some(Self::Item)
+        } else {
+            None
+        }
+    }
+
+    /// Kind of the nodes listed under a node of this kind (`None`: the hubs)
+    fn child_of(parent: Option<Self>) -> Self {
+        match parent {
+            None => Self::Hub,
+            Some(Self::Hub) => Self::Project,
+            Some(Self::Project) | Some(Self::Folder) | Some(Self::Item) => Self::Folder,
+        }
+    }
+}
+
+/// Whether a node's children have been listed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadState {
+    NotLoaded,
+    Loading,
+    Loaded,
+    Failed(String),
+}
+
+/// A hub, project, folder or item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DmNode {
+    pub kind: DmKind,
+    pub id: String,
+    pub name: String,
+    pub depth: usize,
+    pub expanded: bool,
+    pub children: LoadState,
+}
+
+impl DmNode {
+    /// Key identifying the node across reloads
+    fn key(&self) -> String {
+        format!("{}:{}", self.kind.param(), self.id)
+    }
+}
+
+/// Listing of the children of a node (or of the hubs), to run in the background
+#[derive(Debug, Clone, PartialEq)]
+pub struct DmRequest {
+    /// Key of the node being expanded; empty for the hubs
+    pub parent: String,
+    pub command: RapsCommand,
+}
+
+/// The Data tab's tree with lazy loading and selection
+#[derive(Debug, Clone)]
+pub struct DataBrowser {
+    /// Nodes in tree order; children directly follow their parent
+    nodes: Vec<DmNode>,
+    /// Whether the hubs have been listed
+    root: LoadState,
+    /// Index of the selected visible row
+    selected: usize,
+}
+
+impl Default for DataBrowser {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            root: LoadState::NotLoaded,
+            selected: 0,
+        }
+    }
+}
+
+impl DataBrowser {
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Request listing the hubs, the first time the tab is shown
+    pub fn root_request(&mut self) -> Option<DmRequest> {
+        if self.root != LoadState::NotLoaded {
+            return None;
+        }
+        self.root = LoadState::Loading;
+        Some(DmRequest {
+            parent: String::new(),
+            command: dm_command(DataMgmtAction::HubList, DataMgmtParams::default()),
+        })
+    }
+
+    /// Indices of the visible nodes (those whose ancestors are all expanded)
+    fn visible(&self) -> Vec<usize> {
+        let mut visible = Vec::new();
+        let mut hidden_below: Option<usize> = None;
+        for (i, node) in self.nodes.iter().enumerate() {
+            if hidden_below.is_some_and(|depth| node.depth > depth) {
+                continue;
+            }
+            hidden_below = (!node.expanded).then_some(node.depth);
+            visible.push(i);
+        }
+        visible
+    }
+
+    /// Index of the selected row
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        self.visible().get(self.selected).copied()
+    }
+
+    /// Move the selection by `delta` rows, staying within the visible rows
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.visible().len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Expand or collapse the selected node; expanding it the first time
+    /// returns the request listing its children
+    pub fn toggle_selected(&mut self) -> Option<DmRequest> {
+        let index = self.selected_index()?;
+        let node = &mut self.nodes[index];
+        if node.kind == DmKind::Item {
+            return None;
+        }
+        node.expanded = !node.expanded;
+        if !node.expanded || node.children == LoadState::Loading || node.children == LoadState::Loaded {
+            return None;
+        }
+        node.children = LoadState::Loading;
+
+        let params = self.params_of(index);
+        let node = &self.nodes[index];
+        let value = |name: &str| params.iter().find(|(p, _)| *p == name).map(|(_, v)| v.clone());
+        let (action, params) = match node.kind {
+            DmKind::Hub => (
+                DataMgmtAction::ProjectList,
+                DataMgmtParams {
+                    hub_id: Some(node.id.clone()),
+                    ..Default::default()
+                },
+            ),
+            DmKind::Project => (
+                DataMgmtAction::FolderList,
+                DataMgmtParams {
+                    project_id: Some(node.id.clone()),
+                    ..Default::default()
+                },
+            ),
+            DmKind::Folder | DmKind::Item => (
+                DataMgmtAction::FolderList,
+                DataMgmtParams {
+                    project_id: value("project_id"),
+                    folder_id: Some(node.id.clone()),
+                    ..Default::default()
+                },
+            ),
+        };
+        Some(DmRequest {
+            parent: node.key(),
+            command: dm_command(action, params),
+        })
+    }
+
+    /// Insert the listed children of `parent` (a request's key), or record
+    /// why listing them failed
+    pub fn apply(&mut self, parent: &str, result: Result<Value, String>) {
+        let (position, depth, kind) = if parent.is_empty() {
+            (0, 0, None)
+        } else {
+            match self.nodes.iter().position(|n| n.key() == parent) {
+                Some(i) => (i + 1, self.nodes[i].depth + 1, Some(self.nodes[i].kind)),
+                None => return,
+            }
+        };
+        let state = match result {
+            Ok(json) => {
+                let children = parse_entries(&json, kind).into_iter().map(|(kind, id, name)| DmNode {
+                    kind,
+                    id,
+                    name,
+                    depth,
+                    expanded: false,
+                    children: LoadState::NotLoaded,
+                });
+                self.nodes.splice(position..position, children);
+                LoadState::Loaded
+            },
+            Err(error) => LoadState::Failed(error),
+        };
+        if parent.is_empty() {
+            self.root = state;
+        } else {
+            self.nodes[position - 1].children = state;
+        }
+    }
+
+    /// Parameters from the node at `index` and its ancestors, outermost first
+    fn params_of(&self, index: usize) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        let mut depth = self.nodes[index].depth + 1;
+        for node in self.nodes[..=index].iter().rev() {
+            if node.depth < depth {
+                depth = node.depth;
+                if !node.id.is_empty() {
+                    params.push((node.kind.param(), node.id.clone()));
+                }
+            }
+        }
+        params.reverse();
+        params
+    }
+
+    /// Workflow parameters for the selected node: its ID and its ancestors'
+    pub fn selected_params(&self) -> Vec<(&'static str, String)> {
+        self.selected_index().map(|i| self.params_of(i)).unwrap_or_default()
+    }
+
+    /// Render the visible rows, highlighting the selection
+    pub fn lines(&self) -> Vec<Line<'static>> {
+        match &self.root {
+            LoadState::NotLoaded | LoadState::Loading => return vec![Line::from("Listing hubs...")],
+            LoadState::Failed(error) => return vec![Line::from(format!("Failed to list hubs: {}", error))],
+            LoadState::Loaded if self.nodes.is_empty() => return vec![Line::from("No hubs accessible")],
+            LoadState::Loaded => {},
+        }
+
+        self.visible()
+            .into_iter()
+            .enumerate()
+            .map(|(row, index)| {
+                let node = &self.nodes[index];
+                let marker = match (node.kind, node.expanded) {
+                    (DmKind::Item, _) => "  ",
+                    (_, true) => "▾ ",
+                    (_, false) => "▸ ",
+                };
+                let color = match node.kind {
+                    DmKind::Hub => Color::Cyan,
+                    DmKind::Project => Color::Green,
+                    DmKind::Folder => Color::Yellow,
+                    DmKind::Item => Color::White,
+                };
+                let mut spans = vec![
+                    Span::raw("  ".repeat(node.depth)),
+                    Span::styled(marker, Style::default().fg(Color::DarkGray)),
+                    Span::styled(node.name.clone(), Style::default().fg(color)),
+                    Span::styled(format!("  {}", node.id), Style::default().fg(Color::DarkGray)),
+                ];
+                if node.expanded {
+                    match &node.children {
+                        LoadState::Loading => spans.push(Span::raw("  loading...")),
+                        LoadState::Failed(error) => {
+                            spans.push(Span::styled(format!("  {}", error), Style::default().fg(Color::Red)))
+                        },
+                        _ => {},
+                    }
+                }
+                let mut line = Line::from(spans);
+                if row == self.selected {
+                    line.patch_style(Style::default().add_modifier(Modifier::REVERSED));
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+fn dm_command(action: DataMgmtAction, params: DataMgmtParams) -> RapsCommand {
+    RapsCommand::DataManagement { action, params }
+}
+
+/// Entries of a listing: a JSON array, or JSON:API `{"data": [...]}`, of
+/// objects with an `id`, a name and optionally a `type`
+fn parse_entries(json: &Value, parent: Option<DmKind>) -> Vec<(DmKind, String, String)> {
+    let entries = match json {
+        Value::Array(items) => items.as_slice(),
+        Value::Object(map) => map
+            .get("data")
+            .or_else(|| map.values().find(|v| v.is_array()))
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default(),
+        _ => &[],
+    };
+
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let id = entry.get("id").and_then(Value::as_str).filter(|id| !id.trim().is_empty())?.to_string();
+            let text = |value: &Value, key: &str| value.get(key).and_then(Value::as_str).map(str::to_string);
+            let attributes = entry.get("attributes").unwrap_or(&Value::Null);
+            let name = text(attributes, "displayName")
+                .or_else(|| text(attributes, "name"))
+                .or_else(|| text(entry, "displayName"))
+                .or_else(|| text(entry, "name"))
+                .unwrap_or_else(|| id.clone());
+            let kind = entry
+                .get("type")
+                .and_then(Value::as_str)
+                .and_then(DmKind::from_type)
+                .unwrap_or_else(|| DmKind::child_of(parent));
+            Some((kind, id, name))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lazy_tree_and_params() {
+        let mut browser = DataBrowser::default();
+        let request = browser.root_request().unwrap();
+        assert!(matches!(request.command, RapsCommand::DataManagement { action: DataMgmtAction::HubList, .. }));
+        assert!(browser.root_request().is_none());
+        browser.apply("", Ok(serde_json::json!([{"id": "b.hub", "name": "Acme"}])));
+
+        // Expanding the hub lists its projects (JSON:API output)
+        let request = browser.toggle_selected().unwrap();
+        browser.apply(
+            &request.parent,
+            Ok(serde_json::json!({"data": [{"id": "b.proj", "type": "projects", "attributes": {"name": "Tower"}}]})),
+        );
+        browser.move_selection(1);
+        let request = browser.toggle_selected().unwrap();
+        match &request.command {
+            RapsCommand::DataManagement { action, params } => {
+                assert_eq!(*action, DataMgmtAction::FolderList);
+                assert_eq!(params.project_id.as_deref(), Some("b.proj"));
+            },
+            other => panic!("unexpected command {:?}", other),
+        }
+        browser.apply(
+            &request.parent,
+            Ok(serde_json::json!({"data": [
+                {"id": "urn:folder", "type": "folders", "attributes": {"displayName": "Project Files"}},
+                {"id": "urn:item", "type": "items", "attributes": {"displayName": "tower.rvt"}},
+                {"id": "", "type": "items", "attributes": {"displayName": "no id"}}
+            ]})),
+        );
+        // Entries without an ID can't be used as parameters and aren't listed
+        assert_eq!(browser.lines().len(), 4);
+        browser.move_selection(2);
+        assert_eq!(
+            browser.selected_params(),
+            vec![("hub_id", "b.hub".to_string()), ("project_id", "b.proj".to_string()), ("item_id", "urn:item".to_string())]
+        );
+        assert!(browser.toggle_selected().is_none());
+
+        // Collapsing the hub hides everything below it
+        browser.move_selection(-3);
+        assert!(browser.toggle_selected().is_none());
+        assert_eq!(browser.lines().len(), 1);
+    }
+}
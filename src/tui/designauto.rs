@@ -0,0 +1,392 @@
+// Design Automation inspector for the DA tab
+//
+// Lists the app bundles and activities visible to the account through the
+// RAPS CLI's Design Automation commands. Expanding an entry fetches its
+// definition and shows the engine, app bundles and parameters (with their
+// verbs), so a workflow can be pointed at a real activity: the selected ID is
+// passed to runs as `{params.activity_id}` or `{params.appbundle_id}`.
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use serde_json::Value;
+
+use super::datamgmt::LoadState;
+use crate::workflow::{DesignAutoAction, DesignAutoParams, RapsCommand};
+
+/// What a DA listing or definition describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DaKind {
+    AppBundle,
+    Activity,
+}
+
+impl DaKind {
+    /// Workflow parameter the entry's ID is used as
+    pub fn param(self) -> &'static str {
+        match self {
+            Self::AppBundle => "appbundle_id",
+            Self::Activity => "activity_id",
+        }
+    }
+
+    fn heading(self) -> &'static str {
+        match self {
+            Self::AppBundle => "App bundles",
+            Self::Activity => "Activities",
+        }
+    }
+
+    /// Command listing every entry, or fetching the definition of `id`
+    fn command(self, id: Option<&str>) -> RapsCommand {
+        let id = id.map(str::to_string);
+        let (action, params) = match self {
+            Self::AppBundle => (
+                DesignAutoAction::AppBundles,
+                DesignAutoParams {
+                    app_bundle_id: id,
+                    ..Default::default()
+                },
+            ),
+            Self::Activity => (
+                DesignAutoAction::Activities,
+                DesignAutoParams {
+                    activity_id: id,
+                    ..Default::default()
+                },
+            ),
+        };
+        RapsCommand::DesignAutomation { action, params }
+    }
+}
+
+/// What a request fetches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DaTarget {
+    /// Every app bundle or activity
+    List(DaKind),
+    /// Definition of one entry
+    Details(DaKind, String),
+}
+
+/// Fetch to run in the background
+#[derive(Debug, Clone, PartialEq)]
+pub struct DaRequest {
+    pub target: DaTarget,
+    pub command: RapsCommand,
+}
+
+/// An activity or app bundle parameter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaParameter {
+    pub name: String,
+    /// `get`, `put`, `read`, ...
+    pub verb: String,
+    pub required: bool,
+    pub description: String,
+}
+
+/// An app bundle or activity, with its definition once fetched
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaEntry {
+    pub kind: DaKind,
+    pub id: String,
+    pub expanded: bool,
+    pub details: LoadState,
+    /// Engine, app bundles, version, ... from the definition
+    pub summary: Vec<(String, String)>,
+    pub parameters: Vec<DaParameter>,
+}
+
+/// The DA tab's lists with selection
+#[derive(Debug, Clone)]
+pub struct DaInspector {
+    app_bundles: LoadState,
+    activities: LoadState,
+    /// App bundles first, then activities
+    entries: Vec<DaEntry>,
+    /// Index of the selected entry
+    selected: usize,
+}
+
+impl Default for DaInspector {
+    fn default() -> Self {
+        Self {
+            app_bundles: LoadState::NotLoaded,
+            activities: LoadState::NotLoaded,
+            entries: Vec::new(),
+            selected: 0,
+        }
+    }
+}
+
+impl DaInspector {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Requests listing app bundles and activities, the first time the tab is shown
+    pub fn list_requests(&mut self) -> Vec<DaRequest> {
+        let mut requests = Vec::new();
+        for (kind, state) in [
+            (DaKind::AppBundle, &mut self.app_bundles),
+            (DaKind::Activity, &mut self.activities),
+        ] {
+            if *state == LoadState::NotLoaded {
+                *state = LoadState::Loading;
+                requests.push(DaRequest {
+                    target: DaTarget::List(kind),
+                    command: kind.command(None),
+                });
+            }
+        }
+        requests
+    }
+
+    /// Move the selection by `delta` entries
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.entries.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Show or hide the selected entry's definition; the first time returns
+    /// the request fetching it
+    pub fn toggle_selected(&mut self) -> Option<DaRequest> {
+        let entry = self.entries.get_mut(self.selected)?;
+        entry.expanded = !entry.expanded;
+        if !entry.expanded || entry.details != LoadState::NotLoaded {
+            return None;
+        }
+        entry.details = LoadState::Loading;
+        Some(DaRequest {
+            target: DaTarget::Details(entry.kind, entry.id.clone()),
+            command: entry.kind.command(Some(&entry.id)),
+        })
+    }
+
+    /// Workflow parameter for the selected entry
+    pub fn selected_param(&self) -> Option<(&'static str, String)> {
+        self.entries.get(self.selected).map(|e| (e.kind.param(), e.id.clone()))
+    }
+
+    /// Store a fetched listing or definition, or why fetching it failed
+    pub fn apply(&mut self, target: DaTarget, result: Result<Value, String>) {
+        match target {
+            DaTarget::List(kind) => {
+                let state = match result {
+                    Ok(json) => {
+                        let entries = list_ids(&json).into_iter().map(|id| DaEntry {
+                            kind,
+                            id,
+                            expanded: false,
+                            details: LoadState::NotLoaded,
+                            summary: Vec::new(),
+                            parameters: Vec::new(),
+                        });
+                        // App bundles stay ahead of activities whichever arrives first
+                        let position = match kind {
+                            DaKind::AppBundle => self.entries.iter().take_while(|e| e.kind == DaKind::AppBundle).count(),
+                            DaKind::Activity => self.entries.len(),
+                        };
+                        self.entries.splice(position..position, entries);
+                        LoadState::Loaded
+                    },
+                    Err(error) => LoadState::Failed(error),
+                };
+                match kind {
+                    DaKind::AppBundle => self.app_bundles = state,
+                    DaKind::Activity => self.activities = state,
+                }
+            },
+            DaTarget::Details(kind, id) => {
+                let Some(entry) = self.entries.iter_mut().find(|e| e.kind == kind && e.id == id) else {
+                    return;
+                };
+                match result {
+                    Ok(json) => {
+                        entry.summary = summary(&json);
+                        entry.parameters = parameters(&json);
+                        entry.details = LoadState::Loaded;
+                    },
+                    Err(error) => entry.details = LoadState::Failed(error),
+                }
+            },
+        }
+    }
+
+    /// Render both lists with expanded definitions; also returns the line
+    /// of the selected entry, to keep it in view
+    pub fn lines(&self) -> (Vec<Line<'static>>, usize) {
+        let mut lines = Vec::new();
+        let mut selected_line = 0;
+        for (kind, state) in [(DaKind::AppBundle, &self.app_bundles), (DaKind::Activity, &self.activities)] {
+            lines.push(Line::from(Span::styled(
+                kind.heading(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+            match state {
+                LoadState::NotLoaded | LoadState::Loading => lines.push(Line::from("  Listing...")),
+                LoadState::Failed(error) => {
+                    lines.push(Line::from(Span::styled(format!("  {}", error), Style::default().fg(Color::Red))))
+                },
+                LoadState::Loaded if !self.entries.iter().any(|e| e.kind == kind) => lines.push(Line::from("  None")),
+                LoadState::Loaded => {},
+            }
+
+            for (index, entry) in self.entries.iter().enumerate().filter(|(_, e)| e.kind == kind) {
+                let marker = if entry.expanded { "▾ " } else { "▸ " };
+                let mut line = Line::from(vec![
+                    Span::styled(format!("  {}", marker), Style::default().fg(Color::DarkGray)),
+                    Span::styled(entry.id.clone(), Style::default().fg(Color::Cyan)),
+                ]);
+                if index == self.selected {
+                    line.patch_style(Style::default().add_modifier(Modifier::REVERSED));
+                    selected_line = lines.len();
+                }
+                lines.push(line);
+                if entry.expanded {
+                    push_details(&mut lines, entry);
+                }
+            }
+        }
+        (lines, selected_line)
+    }
+}
+
+/// Append the definition lines of an expanded entry
+fn push_details(lines: &mut Vec<Line<'static>>, entry: &DaEntry) {
+    let dim = Style::default().fg(Color::DarkGray);
+    match &entry.details {
+        LoadState::NotLoaded | LoadState::Loading => lines.push(Line::from("      Fetching definition...")),
+        LoadState::Failed(error) => {
+            lines.push(Line::from(Span::styled(format!("      {}", error), Style::default().fg(Color::Red))))
+        },
+        LoadState::Loaded => {
+            for (key, value) in &entry.summary {
+                lines.push(Line::from(vec![Span::styled(format!("      {}: ", key), dim), Span::raw(value.clone())]));
+            }
+            if !entry.parameters.is_empty() {
+                lines.push(Line::from(Span::styled("      parameters:", dim)));
+            }
+            for param in &entry.parameters {
+                let mut spans = vec![
+                    Span::styled(format!("        {}", param.name), Style::default().fg(Color::Yellow)),
+                    Span::styled(format!(" ({}{})", param.verb, if param.required { ", required" } else { "" }), dim),
+                ];
+                if !param.description.is_empty() {
+                    spans.push(Span::raw(format!("  {}", param.description)));
+                }
+                lines.push(Line::from(spans));
+            }
+        },
+    }
+}
+
+/// IDs in a listing: `{"data": [...]}` or a plain array, of strings or
+/// objects with an `id`
+fn list_ids(json: &Value) -> Vec<String> {
+    let items = json
+        .get("data")
+        .and_then(Value::as_array)
+        .or_else(|| json.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Value::String(id) => Some(id.clone()),
+            other => other.get("id").and_then(Value::as_str).map(str::to_string),
+        })
+        .collect()
+}
+
+/// Scalar facts of a definition worth showing
+fn summary(json: &Value) -> Vec<(String, String)> {
+    ["engine", "version", "description", "commandLine", "appbundles"]
+        .into_iter()
+        .filter_map(|key| {
+            let value = match json.get(key)? {
+                Value::String(text) => text.clone(),
+                Value::Array(items) => items.iter().filter_map(Value::as_str).collect::<Vec<_>>().join(", "),
+                Value::Number(number) => number.to_string(),
+                _ => return None,
+            };
+            Some((key.to_string(), value))
+        })
+        .filter(|(_, value)| !value.is_empty())
+        .collect()
+}
+
+/// `parameters` of a definition, in name order
+fn parameters(json: &Value) -> Vec<DaParameter> {
+    let Some(params) = json.get("parameters").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let mut parameters: Vec<DaParameter> = params
+        .iter()
+        .map(|(name, param)| {
+            let text = |key: &str| param.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+            DaParameter {
+                name: name.clone(),
+                verb: text("verb"),
+                required: param.get("required").and_then(Value::as_bool).unwrap_or(false),
+                description: text("description"),
+            }
+        })
+        .collect();
+    parameters.sort_by(|a, b| a.name.cmp(&b.name));
+    parameters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lists_and_activity_parameters() {
+        let mut inspector = DaInspector::default();
+        assert_eq!(inspector.list_requests().len(), 2);
+        assert!(inspector.list_requests().is_empty());
+
+        // Activities arriving first still list after the app bundles
+        inspector.apply(
+            DaTarget::List(DaKind::Activity),
+            Ok(serde_json::json!({"data": ["AutoCAD.PlotToPDF+prod"]})),
+        );
+        inspector.apply(DaTarget::List(DaKind::AppBundle), Ok(serde_json::json!([{"id": "Demo.Bundle+dev"}])));
+        assert_eq!(inspector.selected_param(), Some(("appbundle_id", "Demo.Bundle+dev".to_string())));
+
+        inspector.move_selection(1);
+        let request = inspector.toggle_selected().unwrap();
+        assert_eq!(request.target, DaTarget::Details(DaKind::Activity, "AutoCAD.PlotToPDF+prod".to_string()));
+        inspector.apply(
+            request.target,
+            Ok(serde_json::json!({
+                "engine": "Autodesk.AutoCAD+24",
+                "parameters": {
+                    "Result": {"verb": "put", "description": "PDF output"},
+                    "HostDwg": {"verb": "get", "required": true}
+                }
+            })),
+        );
+
+        let (lines, selected_line) = inspector.lines();
+        let text: Vec<String> = lines
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(selected_line, 3);
+        assert_eq!(
+            text[3..],
+            [
+                "  ▾ AutoCAD.PlotToPDF+prod",
+                "      engine: Autodesk.AutoCAD+24",
+                "      parameters:",
+                "        HostDwg (get, required)",
+                "        Result (put)  PDF output",
+            ]
+        );
+        assert_eq!(inspector.selected_param(), Some(("activity_id", "AutoCAD.PlotToPDF+prod".to_string())));
+    }
+}
@@ -0,0 +1,280 @@
+// Terminal diagnostics for `raps-demo doctor-terminal`
+//
+// When the TUI looks broken at a venue it is usually the terminal rather than
+// the demo: a legacy Windows console without Unicode fonts, a TERM that hides
+// colours, a multiplexer pane too small for the panels. The doctor checks
+// colour depth, Unicode and wide-character handling, mouse reporting and the
+// window size, and says what to change for each problem. The rules work on a
+// snapshot of the environment so they can be tested; only the wide-character
+// probe and the size query touch the real terminal.
+
+use crossterm::{
+    cursor,
+    execute,
+    style::Print,
+    terminal::{self, Clear, ClearType},
+};
+use std::io::{self, IsTerminal};
+
+use super::glyphs::{supports_unicode_with, Glyphs};
+
+/// Narrowest terminal the TUI lays out correctly in
+pub const MIN_WIDTH: u16 = 80;
+/// Shortest terminal the TUI lays out correctly in
+pub const MIN_HEIGHT: u16 = 24;
+
+/// Character used to measure how wide the terminal draws East Asian text
+const WIDE_PROBE: &str = "界";
+
+/// Number of colours the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    Monochrome,
+    /// The 16 ANSI colours
+    Basic,
+    /// The 256-colour palette
+    Indexed,
+    /// 24-bit RGB
+    TrueColor,
+}
+
+impl ColorDepth {
+    fn describe(self) -> &'static str {
+        match self {
+            ColorDepth::Monochrome => "no colour",
+            ColorDepth::Basic => "16 colours",
+            ColorDepth::Indexed => "256 colours",
+            ColorDepth::TrueColor => "24-bit colour",
+        }
+    }
+}
+
+/// What was measured on the real terminal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TerminalProbe {
+    /// Whether the program runs on Windows
+    pub windows: bool,
+    /// Columns and rows, when stdout is a terminal
+    pub size: Option<(u16, u16)>,
+    /// Columns the cursor moved after printing a wide character
+    pub wide_char_width: Option<u16>,
+}
+
+impl TerminalProbe {
+    /// Probe the terminal the program runs in
+    pub fn current() -> Self {
+        let tty = io::stdout().is_terminal();
+        Self {
+            windows: cfg!(windows),
+            size: if tty { terminal::size().ok() } else { None },
+            wide_char_width: if tty { probe_wide_char_width().ok() } else { None },
+        }
+    }
+}
+
+/// One diagnosed capability
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerminalCheck {
+    pub name: &'static str,
+    pub passed: bool,
+    pub message: String,
+    /// What to change when the check fails
+    pub recommendation: Option<String>,
+}
+
+impl TerminalCheck {
+    fn pass(name: &'static str, message: String) -> Self {
+        Self {
+            name,
+            passed: true,
+            message,
+            recommendation: None,
+        }
+    }
+
+    fn fail(name: &'static str, message: String, recommendation: &str) -> Self {
+        Self {
+            name,
+            passed: false,
+            message,
+            recommendation: Some(recommendation.to_string()),
+        }
+    }
+}
+
+/// Colour depth announced by the environment
+pub fn color_depth(env: &impl Fn(&str) -> Option<String>, windows: bool) -> ColorDepth {
+    let colorterm = env("COLORTERM").unwrap_or_default().to_ascii_lowercase();
+    if colorterm == "truecolor" || colorterm == "24bit" || (windows && env("WT_SESSION").is_some()) {
+        return ColorDepth::TrueColor;
+    }
+    match env("TERM").as_deref() {
+        Some("dumb") => ColorDepth::Monochrome,
+        Some(term) if term.contains("256color") => ColorDepth::Indexed,
+        _ => ColorDepth::Basic,
+    }
+}
+
+/// Whether `env` describes the legacy Windows console host
+fn is_conhost(env: &impl Fn(&str) -> Option<String>, windows: bool) -> bool {
+    windows
+        && env("WT_SESSION").is_none()
+        && env("TERM_PROGRAM").is_none()
+        && env("ConEmuANSI").as_deref() != Some("ON")
+        && env("TERM").is_none()
+}
+
+/// Checks of the terminal described by `env` and `probe`
+pub fn diagnose(env: impl Fn(&str) -> Option<String>, probe: &TerminalProbe) -> Vec<TerminalCheck> {
+    let conhost = is_conhost(&env, probe.windows);
+    let mut checks = Vec::new();
+
+    if conhost {
+        checks.push(TerminalCheck::fail(
+            "Console",
+            "Windows conhost detected".to_string(),
+            "run in Windows Terminal for colours, Unicode and mouse support",
+        ));
+    }
+
+    let depth = color_depth(&env, probe.windows);
+    checks.push(if depth >= ColorDepth::Indexed {
+        TerminalCheck::pass("Colour", depth.describe().to_string())
+    } else {
+        TerminalCheck::fail(
+            "Colour",
+            format!("{} (TERM={})", depth.describe(), env("TERM").unwrap_or_default()),
+            "set TERM=xterm-256color or use a terminal with 256-colour support",
+        )
+    });
+
+    checks.push(if supports_unicode_with(&env, probe.windows) {
+        TerminalCheck::pass("Unicode", "icons and box drawing render".to_string())
+    } else {
+        TerminalCheck::fail(
+            "Unicode",
+            "no UTF-8 locale; the ASCII glyph set will be used".to_string(),
+            "set LANG to a UTF-8 locale such as en_US.UTF-8",
+        )
+    });
+
+    checks.push(match probe.wide_char_width {
+        Some(2) => TerminalCheck::pass("Wide characters", format!("{} takes 2 columns", WIDE_PROBE)),
+        Some(width) => TerminalCheck::fail(
+            "Wide characters",
+            format!("{} takes {} column(s); Japanese text will misalign", WIDE_PROBE, width),
+            "use a terminal with current Unicode width tables",
+        ),
+        None => TerminalCheck::pass("Wide characters", "not probed (output is not a terminal)".to_string()),
+    });
+
+    let term = env("TERM").unwrap_or_default();
+    checks.push(if conhost || term == "dumb" || term == "linux" {
+        TerminalCheck::fail(
+            "Mouse",
+            "the terminal does not report mouse events".to_string(),
+            "use a terminal emulator with xterm mouse reporting; the keyboard works everywhere",
+        )
+    } else {
+        TerminalCheck::pass("Mouse", "xterm mouse reporting".to_string())
+    });
+
+    checks.push(match probe.size {
+        Some((width, height)) if width >= MIN_WIDTH && height >= MIN_HEIGHT => {
+            TerminalCheck::pass("Size", format!("{}x{}", width, height))
+        },
+        Some((width, height)) => TerminalCheck::fail(
+            "Size",
+            format!("{}x{}, below the minimum of {}x{}", width, height, MIN_WIDTH, MIN_HEIGHT),
+            if env("TMUX").is_some() {
+                "enlarge the window or zoom the tmux pane (prefix z)"
+            } else {
+                "enlarge the window or lower the font size"
+            },
+        ),
+        None => TerminalCheck::pass("Size", "not probed (output is not a terminal)".to_string()),
+    });
+
+    checks
+}
+
+/// The checks as a checklist, one line per check plus its recommendation
+pub fn checklist(checks: &[TerminalCheck], glyphs: &Glyphs) -> Vec<String> {
+    let width = checks.iter().map(|check| check.name.len()).max().unwrap_or(0);
+    let mut lines = Vec::new();
+    for check in checks {
+        lines.push(format!(
+            "{} {:<width$}  {}",
+            glyphs.status(check.passed),
+            check.name,
+            check.message,
+            width = width
+        ));
+        if let Some(recommendation) = &check.recommendation {
+            lines.push(format!("  {:<width$}  {} {}", "", glyphs.narration, recommendation, width = width));
+        }
+    }
+    let failed = checks.iter().filter(|check| !check.passed).count();
+    lines.push(String::new());
+    lines.push(if failed == 0 {
+        "The terminal is ready for the demo".to_string()
+    } else {
+        format!("{} problem(s) found", failed)
+    });
+    lines
+}
+
+/// Columns the cursor moves when printing a wide character
+fn probe_wide_char_width() -> io::Result<u16> {
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    let measured = (|| {
+        execute!(stdout, Print("\r"))?;
+        let (start, _) = cursor::position()?;
+        execute!(stdout, Print(WIDE_PROBE))?;
+        let (end, _) = cursor::position()?;
+        Ok(end.saturating_sub(start))
+    })();
+    let _ = execute!(stdout, Print("\r"), Clear(ClearType::CurrentLine));
+    terminal::disable_raw_mode()?;
+    measured
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::glyphs::ASCII;
+
+    fn env(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+    }
+
+    #[test]
+    fn test_diagnose_terminals() {
+        let good = TerminalProbe {
+            windows: false,
+            size: Some((120, 40)),
+            wide_char_width: Some(2),
+        };
+        let checks = diagnose(env(&[("TERM", "xterm-256color"), ("LANG", "en_US.UTF-8")]), &good);
+        assert!(checks.iter().all(|check| check.passed), "{:?}", checks);
+        assert_eq!(color_depth(&env(&[("COLORTERM", "truecolor")]), false), ColorDepth::TrueColor);
+
+        let conhost = TerminalProbe {
+            windows: true,
+            size: Some((80, 20)),
+            wide_char_width: Some(1),
+        };
+        let checks = diagnose(env(&[]), &conhost);
+        let failed: Vec<&str> = checks.iter().filter(|check| !check.passed).map(|check| check.name).collect();
+        assert_eq!(failed, vec!["Console", "Colour", "Unicode", "Wide characters", "Mouse", "Size"]);
+
+        let lines = checklist(&checks, &ASCII);
+        assert_eq!(lines[0], "x Console          Windows conhost detected");
+        assert_eq!(
+            lines[1],
+            "                   >> run in Windows Terminal for colours, Unicode and mouse support"
+        );
+        assert_eq!(lines.last().unwrap(), "6 problem(s) found");
+    }
+}
@@ -0,0 +1,274 @@
+// Headless driver for end-to-end tests of the TUI
+//
+// Runs a `TuiApp` against a `TestBackend` instead of the real terminal: the
+// test sends key presses and clicks, the driver applies them the way the main
+// loop does (input first, then downloads, execution updates and the run
+// queue), redraws, and the test reads the screen back as text. Workflows run
+// in the background as usual, so `wait_for` keeps processing their updates
+// until the expected text shows up. Built for the crate's own tests and,
+// with the `test-driver` feature, for integration tests and other crates.
+
+use anyhow::{bail, Result};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use std::time::{Duration, Instant};
+
+use super::TuiApp;
+use crate::utils::interrupt::InterruptSignal;
+
+/// How often `wait_for` looks at the screen again
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A `TuiApp` driven by synthetic input and drawn into a test buffer
+pub struct HeadlessDriver {
+    app: TuiApp,
+    terminal: Terminal<TestBackend>,
+    interrupt: InterruptSignal,
+}
+
+impl HeadlessDriver {
+    /// Drive `app` in a `width` x `height` terminal, drawing the first frame
+    pub fn new(app: TuiApp, width: u16, height: u16) -> Result<Self> {
+        let mut driver = Self {
+            app,
+            terminal: Terminal::new(TestBackend::new(width, height))?,
+            interrupt: InterruptSignal::default(),
+        };
+        driver.draw()?;
+        Ok(driver)
+    }
+
+    /// Press `code` without modifiers
+    pub async fn key(&mut self, code: KeyCode) -> Result<()> {
+        self.key_with(code, KeyModifiers::NONE).await
+    }
+
+    /// Press `code` with `modifiers` (e.g. Ctrl-C)
+    pub async fn key_with(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+        self.send(Event::Key(KeyEvent::new(code, modifiers))).await
+    }
+
+    /// Left-click the cell (`x`, `y`)
+    pub async fn click(&mut self, x: u16, y: u16) -> Result<()> {
+        self.send(Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: x,
+            row: y,
+            modifiers: KeyModifiers::NONE,
+        }))
+        .await
+    }
+
+    /// Left-click the first cell showing `text`
+    pub async fn click_text(&mut self, text: &str) -> Result<()> {
+        match self.find(text) {
+            Some((x, y)) => self.click(x, y).await,
+            None => bail!("'{}' is not on screen:\n{}", text, self.screen().join("\n")),
+        }
+    }
+
+    /// Apply any input event, then the work it queued, and redraw
+    pub async fn send(&mut self, event: Event) -> Result<()> {
+        self.app.handle_event(event, &self.interrupt).await?;
+        self.settle().await
+    }
+
+    /// Resize the terminal and redraw
+    pub fn resize(&mut self, width: u16, height: u16) -> Result<()> {
+        self.terminal.backend_mut().resize(width, height);
+        self.draw()
+    }
+
+    /// Redraw until `text` is on screen, processing execution updates and
+    /// background results meanwhile; fails with the screen after `timeout`
+    pub async fn wait_for(&mut self, text: &str, timeout: Duration) -> Result<()> {
+        if !self.wait_until(timeout, |driver| driver.contains(text)).await? {
+            bail!("Timed out waiting for '{}':\n{}", text, self.screen().join("\n"));
+        }
+        Ok(())
+    }
+
+    /// Like [`wait_for`](Self::wait_for), until a console line contains
+    /// `text` (e.g. the end of a download)
+    pub async fn wait_for_log(&mut self, text: &str, timeout: Duration) -> Result<()> {
+        if !self.wait_until(timeout, |driver| driver.logs().iter().any(|line| line.contains(text))).await? {
+            bail!("Timed out waiting for '{}' in the console:\n{}", text, self.logs().join("\n"));
+        }
+        Ok(())
+    }
+
+    /// Settle and redraw until `done` holds (`true`) or `timeout` passes (`false`)
+    async fn wait_until(&mut self, timeout: Duration, done: impl Fn(&Self) -> bool) -> Result<bool> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            self.settle().await?;
+            if done(self) {
+                return Ok(true);
+            }
+            if Instant::now() >= deadline {
+                return Ok(false);
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Text of each row of the last frame, trailing spaces trimmed
+    pub fn screen(&self) -> Vec<String> {
+        let buffer = self.terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                let row: String = (0..buffer.area.width).map(|x| buffer.get(x, y).symbol()).collect();
+                row.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    /// Whether `text` appears on one row of the last frame
+    pub fn contains(&self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+
+    /// Cell where `text` first appears in the last frame, searching row by row
+    pub fn find(&self, text: &str) -> Option<(u16, u16)> {
+        self.screen().iter().enumerate().find_map(|(y, row)| {
+            // Columns count cells, not bytes
+            row.find(text).map(|byte| (row[..byte].chars().count() as u16, y as u16))
+        })
+    }
+
+    /// Console lines logged so far, oldest first
+    pub fn logs(&self) -> Vec<String> {
+        self.app.logs.iter().collect()
+    }
+
+    /// Whether the app asked to quit
+    pub fn quit_requested(&self) -> bool {
+        self.app.should_quit
+    }
+
+    /// The driven app
+    pub fn app(&self) -> &TuiApp {
+        &self.app
+    }
+
+    /// Queued work and interrupts as in the main loop, then a new frame;
+    /// editing a workflow needs a real terminal, so it is only logged
+    async fn settle(&mut self) -> Result<()> {
+        if self.interrupt.take() {
+            self.app.handle_interrupt().await;
+        }
+        if std::mem::take(&mut self.app.pending_edit) {
+            self.app.logs.push("Editing needs a terminal; skipped by the headless driver".to_string());
+        }
+        self.app.process_pending().await?;
+        self.draw()
+    }
+
+    fn draw(&mut self) -> Result<()> {
+        let app = &mut self.app;
+        self.terminal.draw(|f| app.draw(f))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::RapsDemoBuilder;
+
+    #[tokio::test]
+    async fn test_driver_switches_tabs_and_quits() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = RapsDemoBuilder::new()
+            .workflows_dir(dir.path().join("workflows"))
+            .assets_dir(dir.path().join("assets"))
+            .build_tui()
+            .unwrap();
+        let mut driver = HeadlessDriver::new(app, 120, 40).unwrap();
+        assert!(driver.contains("Workflows"));
+
+        driver.key(KeyCode::Char('4')).await.unwrap();
+        assert!(driver.contains("AUTODESK SAMPLE ASSETS"), "{}", driver.screen().join("\n"));
+
+        driver.resize(40, 10).unwrap();
+        assert!(driver.find("80x24").is_some(), "{}", driver.screen().join("\n"));
+
+        driver.key(KeyCode::Char('q')).await.unwrap();
+        assert!(driver.quit_requested());
+    }
+
+    #[tokio::test]
+    async fn test_driver_queues_popups_and_drops_stale_decisions() {
+        use crate::workflow::executor::{ExecutionError, ExecutionUpdate};
+        use crate::workflow::types::{ExecutionHandle, ExecutionStep};
+        use crate::tui::PopupKind;
+
+        let dir = tempfile::tempdir().unwrap();
+        let app = RapsDemoBuilder::new()
+            .workflows_dir(dir.path().join("workflows"))
+            .assets_dir(dir.path().join("assets"))
+            .build_tui()
+            .unwrap();
+        let mut driver = HeadlessDriver::new(app, 120, 40).unwrap();
+        let step: ExecutionStep = serde_yaml::from_str(
+            "id: upload\nname: Upload\ndescription: Upload\ncommand:\n  type: custom\n  command: raps\n  args: []\n",
+        )
+        .unwrap();
+        let failed = |handle: &ExecutionHandle| ExecutionUpdate::StepFailed {
+            handle: handle.clone(),
+            step: step.clone(),
+            error: ExecutionError::new("upload failed".to_string()),
+        };
+
+        // A failed step waits behind the open popup instead of replacing it
+        driver.key(KeyCode::Char('?')).await.unwrap();
+        let first = ExecutionHandle::new("first".into());
+        driver.app.handle_execution_update(failed(&first));
+        driver.key(KeyCode::Char('x')).await.unwrap();
+        assert!(driver.contains("Step 'Upload' failed"), "{}", driver.screen().join("\n"));
+
+        // A click doesn't dismiss it, and another failure queues behind it
+        driver.click_text("upload failed").await.unwrap();
+        let second = ExecutionHandle::new("second".into());
+        driver.app.handle_execution_update(failed(&second));
+        assert_eq!(driver.app.popup.as_ref().map(|p| p.kind.clone()), Some(PopupKind::Decision(first.clone())));
+
+        // A run that ended no longer waits for its decision
+        driver.app.handle_execution_update(ExecutionUpdate::Cancelled { handle: first });
+        assert_eq!(driver.app.popup.as_ref().map(|p| p.kind.clone()), Some(PopupKind::Decision(second.clone())));
+        driver.app.handle_execution_update(ExecutionUpdate::Cancelled { handle: second });
+        assert!(driver.app.popup.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_driver_shows_the_lock_a_run_waits_for() {
+        use crate::workflow::executor::ExecutionUpdate;
+        use crate::workflow::locks::LockWait;
+        use crate::workflow::types::ExecutionHandle;
+
+        let dir = tempfile::tempdir().unwrap();
+        let app = RapsDemoBuilder::new()
+            .workflows_dir(dir.path().join("workflows"))
+            .assets_dir(dir.path().join("assets"))
+            .build_tui()
+            .unwrap();
+        let mut driver = HeadlessDriver::new(app, 160, 40).unwrap();
+        let handle = ExecutionHandle::new("upload".into());
+        let wait = LockWait {
+            lock: "bucket:demo".to_string(),
+            holder: "translate".to_string(),
+        };
+        driver.app.handle_execution_update(ExecutionUpdate::Started {
+            handle: handle.clone(),
+            workflow_id: "upload".to_string(),
+        });
+        driver.app.handle_execution_update(ExecutionUpdate::WaitingForLock { handle: handle.clone(), wait });
+        driver.draw().unwrap();
+        assert!(driver.contains("Waiting for lock 'bucket:demo' held by translate"), "{}", driver.screen().join("\n"));
+
+        driver.app.handle_execution_update(ExecutionUpdate::Cancelled { handle });
+        driver.draw().unwrap();
+        assert!(!driver.contains("Waiting for lock"));
+    }
+}
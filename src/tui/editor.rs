@@ -0,0 +1,57 @@
+//! External editor integration for the YAML tab
+//!
+//! Opens a workflow file in `$VISUAL` / `$EDITOR` while the TUI is suspended.
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+
+/// Resolve the editor command line from the environment
+pub fn editor_command() -> Vec<String> {
+    let configured = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty());
+
+    match configured {
+        Some(value) => split_command(&value),
+        None if cfg!(windows) => vec!["notepad".to_string()],
+        None => vec!["vi".to_string()],
+    }
+}
+
+/// Split an editor setting such as `code --wait` into program and arguments
+fn split_command(value: &str) -> Vec<String> {
+    value.split_whitespace().map(str::to_string).collect()
+}
+
+/// Open a file in the configured editor and wait for it to exit
+pub fn open_in_editor(path: &Path) -> Result<()> {
+    let command = editor_command();
+    let (program, args) = command
+        .split_first()
+        .context("No editor configured (set $EDITOR)")?;
+
+    let status = Command::new(program)
+        .args(args)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to launch editor '{}'", program))?;
+
+    if !status.success() {
+        bail!("Editor '{}' exited with {}", program, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_command_with_args() {
+        assert_eq!(split_command("code --wait"), vec!["code", "--wait"]);
+        assert_eq!(split_command("  nvim "), vec!["nvim"]);
+    }
+}
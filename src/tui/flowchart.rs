@@ -7,19 +7,35 @@ use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Widget, StatefulWidget},
+    widgets::{Block, Paragraph, Widget, StatefulWidget},
 };
 
 use crate::workflow::{WorkflowDefinition, RapsCommand};
 
+/// Level of detail used when rendering steps
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FlowchartZoom {
+    /// One line per step
+    Compact,
+    /// Boxed steps with name and command
+    #[default]
+    Detailed,
+}
+
 /// State for the flowchart widget (scroll position and execution state)
 #[derive(Default, Clone)]
 pub struct FlowchartState {
     pub scroll: usize,
+    /// Horizontal pan offset (columns)
+    pub h_scroll: usize,
+    /// Current zoom level
+    pub zoom: FlowchartZoom,
     /// Current executing step index (if any)
     pub executing_step: Option<usize>,
     /// Completed step indices
     pub completed_steps: Vec<usize>,
+    /// Step the view was last centered on, so manual scrolling sticks between steps
+    centered_step: Option<usize>,
 }
 
 impl FlowchartState {
@@ -31,8 +47,28 @@ impl FlowchartState {
         self.scroll += amount;
     }
     
+    pub fn pan_left(&mut self, amount: usize) {
+        self.h_scroll = self.h_scroll.saturating_sub(amount);
+    }
+    
+    pub fn pan_right(&mut self, amount: usize) {
+        self.h_scroll += amount;
+    }
+    
+    /// Switch between compact and detailed rendering
+    pub fn toggle_zoom(&mut self) {
+        self.zoom = match self.zoom {
+            FlowchartZoom::Compact => FlowchartZoom::Detailed,
+            FlowchartZoom::Detailed => FlowchartZoom::Compact,
+        };
+        self.scroll = 0;
+        self.centered_step = None;
+    }
+    
     pub fn reset(&mut self) {
         self.scroll = 0;
+        self.h_scroll = 0;
+        self.centered_step = None;
     }
     
     pub fn set_execution_state(&mut self, executing: Option<usize>, completed: &[usize]) {
@@ -41,6 +77,12 @@ impl FlowchartState {
     }
 }
 
+/// Rendered flowchart lines plus the first line of each step
+struct FlowchartLayout<'a> {
+    lines: Vec<Line<'a>>,
+    step_offsets: Vec<usize>,
+}
+
 /// A flowchart widget that renders workflow steps
 pub struct FlowchartWidget<'a> {
     workflow: Option<&'a WorkflowDefinition>,
@@ -107,16 +149,96 @@ impl<'a> FlowchartWidget<'a> {
         }
     }
     
-    /// Build flowchart lines with execution state
-    fn build_lines(&self, state: &FlowchartState) -> Vec<Line<'a>> {
+    /// Render the flowchart as plain ASCII text (for export), without execution state
+    pub fn to_ascii(&self, zoom: FlowchartZoom) -> String {
+        let state = FlowchartState {
+            zoom,
+            ..FlowchartState::default()
+        };
+        self.build_layout(&state)
+            .lines
+            .iter()
+            .map(|line| {
+                let text: String = line.spans.iter().map(|span| span.content.as_ref()).collect();
+                text.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+    
+    /// Build flowchart lines for the current zoom level
+    fn build_layout(&self, state: &FlowchartState) -> FlowchartLayout<'a> {
         let Some(def) = self.workflow else {
-            return vec![Line::from(Span::styled(
-                "<- Select a workflow to view its flowchart",
-                Style::default().fg(Color::DarkGray),
-            ))];
+            return FlowchartLayout {
+                lines: vec![Line::from(Span::styled(
+                    "<- Select a workflow to view its flowchart",
+                    Style::default().fg(Color::DarkGray),
+                ))],
+                step_offsets: Vec::new(),
+            };
         };
         
+        match state.zoom {
+            FlowchartZoom::Compact => Self::build_compact_lines(def, state),
+            FlowchartZoom::Detailed => Self::build_detailed_lines(def, state),
+        }
+    }
+    
+    /// Build a compact one-line-per-step flowchart
+    fn build_compact_lines(def: &WorkflowDefinition, state: &FlowchartState) -> FlowchartLayout<'a> {
+        let arrow_style = Style::default().fg(Color::DarkGray);
+        let connector = || Line::from(Span::styled("      |", arrow_style));
+        let mut lines: Vec<Line<'a>> = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "  ( START )",
+                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+            )),
+        ];
+        let mut step_offsets = Vec::new();
+        
+        for (i, step) in def.steps.iter().enumerate() {
+            let (style, marker) = if state.completed_steps.contains(&i) {
+                (Style::default().fg(Color::Green), "[OK]")
+            } else if state.executing_step == Some(i) {
+                (Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD), "[>>]")
+            } else {
+                (Style::default().fg(Color::Cyan), "[  ]")
+            };
+            lines.push(connector());
+            step_offsets.push(lines.len());
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {} ", marker), style),
+                Span::styled(format!("{:>2}. {}", i + 1, step.name), style),
+                Span::styled(
+                    format!("  {}", Self::format_command(&step.command)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+        }
+        
+        if !def.cleanup.is_empty() {
+            lines.push(connector());
+            lines.push(Line::from(Span::styled(
+                format!("  ( Cleanup: {} cmds )", def.cleanup.len()),
+                Style::default().fg(Color::Magenta),
+            )));
+        }
+        
+        lines.push(connector());
+        lines.push(Line::from(Span::styled(
+            "  ( END )",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Line::from(""));
+        
+        FlowchartLayout { lines, step_offsets }
+    }
+    
+    /// Build the detailed boxed flowchart with execution state
+    fn build_detailed_lines(def: &WorkflowDefinition, state: &FlowchartState) -> FlowchartLayout<'a> {
         let mut lines: Vec<Line<'a>> = Vec::new();
+        let mut step_offsets = Vec::new();
         
         // Styles
         let border_start = Style::default().fg(Color::Green);
@@ -128,7 +250,6 @@ impl<'a> FlowchartWidget<'a> {
         let arrow_style = Style::default().fg(Color::DarkGray);
         let title_style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
         let cmd_style = Style::default().fg(Color::Gray);
-        let label_style = Style::default().fg(Color::White).add_modifier(Modifier::DIM);
         
         // Box dimensions
         let box_width = 38;
@@ -186,6 +307,7 @@ impl<'a> FlowchartWidget<'a> {
             lines.push(Line::from(Span::styled(format!("{}v", arrow_indent), arrow_style)));
             
             // Step box with step number and status
+            step_offsets.push(lines.len());
             let step_label = format!("Step {} {}", i + 1, status_indicator);
             let dashes = box_width - 6 - step_label.len();
             let top = format!("{}+-- {} {}", indent, step_label, h_line(dashes.max(1), '-'));
@@ -268,7 +390,7 @@ impl<'a> FlowchartWidget<'a> {
         // Empty line at bottom
         lines.push(Line::from(""));
         
-        lines
+        FlowchartLayout { lines, step_offsets }
     }
 }
 
@@ -288,9 +410,17 @@ impl<'a> StatefulWidget for FlowchartWidget<'a> {
         };
         
         // Build all lines with execution state
-        let all_lines = self.build_lines(state);
+        let FlowchartLayout { lines: all_lines, step_offsets } = self.build_layout(state);
         let total_lines = all_lines.len();
         
+        // Center on the executing step whenever a new step starts
+        if state.executing_step != state.centered_step {
+            if let Some(offset) = state.executing_step.and_then(|i| step_offsets.get(i)) {
+                state.scroll = offset.saturating_sub(inner_area.height as usize / 2);
+            }
+            state.centered_step = state.executing_step;
+        }
+        
         // Clamp scroll to valid range
         let max_scroll = total_lines.saturating_sub(inner_area.height as usize);
         if state.scroll > max_scroll {
@@ -304,8 +434,8 @@ impl<'a> StatefulWidget for FlowchartWidget<'a> {
             .take(inner_area.height as usize)
             .collect();
         
-        // Render as paragraph
-        let paragraph = Paragraph::new(visible_lines);
+        // Render as paragraph, panned horizontally
+        let paragraph = Paragraph::new(visible_lines).scroll((0, state.h_scroll as u16));
         paragraph.render(inner_area, buf);
     }
 }
@@ -317,3 +447,62 @@ impl<'a> Widget for FlowchartWidget<'a> {
         StatefulWidget::render(self, area, buf, &mut state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_definition() -> WorkflowDefinition {
+        serde_yaml::from_str(
+            r#"
+metadata:
+  id: "flowchart-test"
+  name: "Flowchart Test"
+  description: "Flowchart test"
+  category: oss
+  prerequisites: []
+  estimated_duration: 60
+  required_assets: []
+steps:
+  - id: "login"
+    name: "Check Auth"
+    description: "Check authentication"
+    command:
+      type: auth
+      action: status
+  - id: "list"
+    name: "List Buckets"
+    description: "List buckets"
+    command:
+      type: bucket
+      action: list
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ascii_export_zoom_levels() {
+        let def = sample_definition();
+        let widget = FlowchartWidget::new(Some(&def));
+
+        let detailed = widget.to_ascii(FlowchartZoom::Detailed);
+        let compact = widget.to_ascii(FlowchartZoom::Compact);
+
+        assert!(detailed.contains("[START]") && detailed.contains("[END]"));
+        assert!(detailed.contains("Check Auth"));
+        assert!(compact.contains(" 2. List Buckets"));
+        assert!(compact.lines().count() < detailed.lines().count());
+    }
+
+    #[test]
+    fn test_toggle_zoom_resets_scroll() {
+        let mut state = FlowchartState {
+            scroll: 10,
+            ..FlowchartState::default()
+        };
+        state.toggle_zoom();
+        assert_eq!(state.zoom, FlowchartZoom::Compact);
+        assert_eq!(state.scroll, 0);
+    }
+}
@@ -0,0 +1,172 @@
+// Glyph sets for terminals with and without Unicode support
+//
+// Status icons (✓ ⚠ ⬇ ←) and section rules render as garbage on legacy
+// Windows consoles. Views take their glyphs from a `Glyphs` set instead of
+// writing them inline; the ASCII set is picked when the terminal doesn't look
+// Unicode-capable, or when `glyphs = "ascii"` is set in the demo configuration
+// or `RAPS_DEMO_GLYPHS=ascii` in the environment.
+
+use serde::{Deserialize, Serialize};
+
+/// Environment variable forcing the glyph set (`unicode` or `ascii`)
+pub const GLYPHS_ENV: &str = "RAPS_DEMO_GLYPHS";
+
+/// Which glyph set to use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GlyphMode {
+    /// Detect from the terminal
+    #[default]
+    Auto,
+    Unicode,
+    Ascii,
+}
+
+/// Icons and rules used across the sidebar, tabs, Overview and Assets views
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glyphs {
+    pub ok: &'static str,
+    pub fail: &'static str,
+    pub warn: &'static str,
+    pub download: &'static str,
+    pub play: &'static str,
+    pub star: &'static str,
+    pub bullet: &'static str,
+    pub narration: &'static str,
+    pub arrow_left: &'static str,
+    pub arrows_up_down: &'static str,
+    /// Horizontal rule for section headers
+    pub rule: &'static str,
+    /// Heavy horizontal rule
+    pub double_rule: &'static str,
+    pub corner_left: &'static str,
+    pub corner_right: &'static str,
+    pub copyright: &'static str,
+}
+
+pub const UNICODE: Glyphs = Glyphs {
+    ok: "✓",
+    fail: "✗",
+    warn: "⚠",
+    download: "⬇",
+    play: "▶",
+    star: "★",
+    bullet: "•",
+    narration: "»",
+    arrow_left: "←",
+    arrows_up_down: "↑↓",
+    rule: "─",
+    double_rule: "═",
+    corner_left: "┌",
+    corner_right: "┐",
+    copyright: "©",
+};
+
+pub const ASCII: Glyphs = Glyphs {
+    ok: "+",
+    fail: "x",
+    warn: "!",
+    download: "v",
+    play: ">",
+    star: "(*)",
+    bullet: "-",
+    narration: ">>",
+    arrow_left: "<-",
+    arrows_up_down: "Up/Down",
+    rule: "-",
+    double_rule: "=",
+    corner_left: "+",
+    corner_right: "+",
+    copyright: "(c)",
+};
+
+impl Glyphs {
+    /// Glyph set for a configured mode, detecting the terminal for `Auto`
+    pub fn for_mode(mode: GlyphMode) -> &'static Glyphs {
+        match mode {
+            GlyphMode::Unicode => &UNICODE,
+            GlyphMode::Ascii => &ASCII,
+            GlyphMode::Auto if supports_unicode() => &UNICODE,
+            GlyphMode::Auto => &ASCII,
+        }
+    }
+
+    /// Status icon for a passed or failed check
+    pub fn status(&self, passed: bool) -> &'static str {
+        if passed {
+            self.ok
+        } else {
+            self.fail
+        }
+    }
+
+    /// Section header such as `─── Description ───`
+    pub fn section(&self, title: &str) -> String {
+        let rule = self.rule.repeat(3);
+        format!("{} {} {}", rule, title, rule)
+    }
+
+    /// Boxed title such as `┌─ Name ─┐`
+    pub fn boxed(&self, title: &str) -> String {
+        format!("{}{} {} {}{}", self.corner_left, self.rule, title, self.rule, self.corner_right)
+    }
+}
+
+/// Whether the current terminal is likely to render Unicode icons
+pub fn supports_unicode() -> bool {
+    supports_unicode_with(|name| std::env::var(name).ok(), cfg!(windows))
+}
+
+/// Unicode detection with a custom environment lookup
+pub(crate) fn supports_unicode_with(env: impl Fn(&str) -> Option<String>, windows: bool) -> bool {
+    match env(GLYPHS_ENV).as_deref() {
+        Some("unicode") => return true,
+        Some("ascii") => return false,
+        _ => {},
+    }
+
+    if windows {
+        // The legacy console host has no Unicode fonts; modern hosts announce themselves
+        return env("WT_SESSION").is_some()
+            || env("TERM_PROGRAM").is_some()
+            || env("ConEmuANSI").as_deref() == Some("ON")
+            || env("TERM").is_some_and(|t| t.starts_with("xterm"));
+    }
+
+    if env("TERM").as_deref() == Some("linux") {
+        return false;
+    }
+    // The first locale variable that is set decides the character set
+    match ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| env(name).filter(|v| !v.is_empty()))
+    {
+        Some(locale) => {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        },
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unicode_detection() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+        assert!(!supports_unicode_with(env(&[]), true));
+        assert!(supports_unicode_with(env(&[("WT_SESSION", "abc")]), true));
+        assert!(!supports_unicode_with(env(&[("WT_SESSION", "abc"), (GLYPHS_ENV, "ascii")]), true));
+        assert!(supports_unicode_with(env(&[("LANG", "en_US.UTF-8")]), false));
+        assert!(!supports_unicode_with(env(&[("LC_ALL", "C"), ("LANG", "en_US.UTF-8")]), false));
+        assert!(!supports_unicode_with(env(&[("TERM", "linux")]), false));
+
+        assert_eq!(ASCII.section("Assets"), "--- Assets ---");
+        assert_eq!(UNICODE.boxed("OSS"), "┌─ OSS ─┐");
+        assert!(Glyphs::for_mode(GlyphMode::Ascii).ok.is_ascii());
+    }
+}
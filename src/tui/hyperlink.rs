@@ -0,0 +1,251 @@
+// Terminal hyperlinks (OSC 8) for URLs shown in the TUI
+//
+// ratatui measures every cell, so escape sequences can't be embedded in the
+// rendered text. Instead the positions of URLs are recorded while drawing and
+// the same text is re-printed over them wrapped in OSC 8 sequences after each
+// frame. Positions are in terminal columns, so wide characters before a URL
+// count twice, and the parts of a URL a popup is drawn over are dropped
+// before printing. Terminals that don't support OSC 8 are detected from the
+// environment and simply keep the plain text.
+
+use anyhow::Result;
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    style::{Attribute, Color, Print, SetAttribute, SetForegroundColor, ResetColor},
+};
+use ratatui::layout::Rect;
+use std::io::Write;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Environment variable forcing hyperlinks on (`1`) or off (`0`)
+pub const HYPERLINKS_ENV: &str = "RAPS_DEMO_HYPERLINKS";
+
+/// A URL drawn at a screen position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkSpot {
+    pub x: u16,
+    pub y: u16,
+    pub url: String,
+    /// Visible text (the URL, possibly clipped to the panel)
+    pub text: String,
+    /// Foreground the text was drawn with
+    pub color: Option<Color>,
+}
+
+/// Whether the current terminal is likely to understand OSC 8 hyperlinks
+pub fn supports_hyperlinks() -> bool {
+    supports_hyperlinks_with(|name| std::env::var(name).ok())
+}
+
+/// Hyperlink detection with a custom environment lookup
+fn supports_hyperlinks_with(env: impl Fn(&str) -> Option<String>) -> bool {
+    match env(HYPERLINKS_ENV).as_deref() {
+        Some("1") | Some("true") => return true,
+        Some("0") | Some("false") => return false,
+        _ => {},
+    }
+
+    let term = env("TERM").unwrap_or_default();
+    if term == "dumb" {
+        return false;
+    }
+    if env("WT_SESSION").is_some() || env("KITTY_WINDOW_ID").is_some() || env("WEZTERM_PANE").is_some() {
+        return true;
+    }
+    if let Some(program) = env("TERM_PROGRAM") {
+        if matches!(program.as_str(), "iTerm.app" | "WezTerm" | "vscode" | "Hyper" | "ghostty" | "Tabby") {
+            return true;
+        }
+    }
+    if env("VTE_VERSION").and_then(|v| v.parse::<u32>().ok()).is_some_and(|v| v >= 5000) {
+        return true;
+    }
+    ["kitty", "alacritty", "foot", "wezterm", "ghostty"]
+        .iter()
+        .any(|name| term.contains(name))
+}
+
+/// Wrap `text` in an OSC 8 hyperlink to `url`
+pub fn osc8(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// URLs in a line as `(column, url)`
+pub fn find_urls(line: &str) -> Vec<(usize, &str)> {
+    let mut urls = Vec::new();
+    let mut search_from = 0;
+    while let Some(found) = ["https://", "http://"]
+        .iter()
+        .filter_map(|scheme| line[search_from..].find(scheme))
+        .min()
+    {
+        let start = search_from + found;
+        let len = line[start..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>' | ')' | ']'))
+            .unwrap_or(line.len() - start);
+        let url = line[start..start + len].trim_end_matches(['.', ',', ';', ':']);
+        urls.push((line[..start].width(), url));
+        search_from = start + len.max(1);
+    }
+    urls
+}
+
+/// Link spots for the URLs in `line`, drawn at (`x`, `y`) and clipped to `width`
+pub fn line_links(line: &str, x: u16, y: u16, width: u16, color: Option<Color>) -> Vec<LinkSpot> {
+    find_urls(line)
+        .into_iter()
+        .filter_map(|(column, url)| {
+            let available = (width as usize).checked_sub(column).filter(|w| *w > 0)?;
+            Some(LinkSpot {
+                x: x + column as u16,
+                y,
+                url: url.to_string(),
+                text: take_columns(url, available).to_string(),
+                color,
+            })
+        })
+        .collect()
+}
+
+/// Drop the parts of the spots that `area` covers, e.g. a popup drawn over them
+pub fn uncover(spots: &mut Vec<LinkSpot>, area: Rect) {
+    let mut visible = Vec::with_capacity(spots.len());
+    for spot in spots.drain(..) {
+        let end = spot.x as usize + spot.text.width();
+        if spot.y < area.y || spot.y >= area.bottom() || end <= area.x as usize || spot.x >= area.right() {
+            visible.push(spot);
+            continue;
+        }
+        if spot.x < area.x {
+            visible.push(LinkSpot {
+                text: take_columns(&spot.text, (area.x - spot.x) as usize).to_string(),
+                ..spot.clone()
+            });
+        }
+        if end > area.right() as usize {
+            let (column, rest) = skip_columns(&spot.text, (area.right() - spot.x) as usize);
+            if !rest.is_empty() {
+                visible.push(LinkSpot {
+                    x: spot.x + column as u16,
+                    text: rest.to_string(),
+                    ..spot
+                });
+            }
+        }
+    }
+    *spots = visible;
+}
+
+/// The longest start of `text` that fits in `columns`
+fn take_columns(text: &str, columns: usize) -> &str {
+    let mut used = 0;
+    for (index, c) in text.char_indices() {
+        used += c.width().unwrap_or(0);
+        if used > columns {
+            return &text[..index];
+        }
+    }
+    text
+}
+
+/// The rest of `text` starting at or after `columns`, with the column it
+/// starts at (a wide character split by `columns` is dropped)
+fn skip_columns(text: &str, columns: usize) -> (usize, &str) {
+    let mut used = 0;
+    for (index, c) in text.char_indices() {
+        if used >= columns {
+            return (used, &text[index..]);
+        }
+        used += c.width().unwrap_or(0);
+    }
+    (used, "")
+}
+
+/// Re-print the given spots as hyperlinks
+pub fn write_links<W: Write>(out: &mut W, spots: &[LinkSpot]) -> Result<()> {
+    for spot in spots {
+        queue!(out, MoveTo(spot.x, spot.y))?;
+        if let Some(color) = spot.color {
+            queue!(out, SetForegroundColor(color))?;
+        }
+        queue!(
+            out,
+            SetAttribute(Attribute::Underlined),
+            Print(osc8(&spot.url, &spot.text)),
+            SetAttribute(Attribute::NoUnderline),
+            ResetColor
+        )?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_urls_and_clipping() {
+        let line = "  ✓ Viewer: https://aps.autodesk.com/viewer, docs (https://example.com/a).";
+        assert_eq!(
+            find_urls(line),
+            vec![(12, "https://aps.autodesk.com/viewer"), (51, "https://example.com/a")]
+        );
+
+        let spots = line_links(line, 1, 2, 20, None);
+        assert_eq!(spots.len(), 1);
+        assert_eq!((spots[0].x, spots[0].y), (13, 2));
+        assert_eq!(spots[0].text, "https://");
+        assert_eq!(osc8("u", "t"), "\x1b]8;;u\x1b\\t\x1b]8;;\x1b\\");
+    }
+
+    #[test]
+    fn test_columns_count_wide_characters() {
+        let line = "📦 Bucket: https://example.com/b";
+        assert_eq!(find_urls(line), vec![(11, "https://example.com/b")]);
+        assert_eq!(line_links(line, 0, 0, 40, None)[0].x, 11);
+        assert_eq!(take_columns("a界b", 2), "a");
+        assert_eq!(skip_columns("a界b", 2), (3, "b"));
+    }
+
+    #[test]
+    fn test_uncover_drops_covered_cells() {
+        let spot = |x: u16, y: u16| LinkSpot {
+            x,
+            y,
+            url: "https://example.com/a".to_string(),
+            text: "https://example.com/a".to_string(),
+            color: None,
+        };
+        let mut spots = vec![spot(0, 0), spot(0, 5), spot(30, 5)];
+        uncover(&mut spots, Rect::new(10, 3, 5, 4));
+        let parts: Vec<(u16, u16, &str)> = spots.iter().map(|s| (s.x, s.y, s.text.as_str())).collect();
+        assert_eq!(
+            parts,
+            vec![
+                (0, 0, "https://example.com/a"),
+                (0, 5, "https://ex"),
+                (15, 5, ".com/a"),
+                (30, 5, "https://example.com/a"),
+            ]
+        );
+        assert!(spots.iter().all(|s| s.url == "https://example.com/a"));
+
+        let mut spots = vec![spot(12, 4)];
+        uncover(&mut spots, Rect::new(10, 3, 40, 4));
+        assert!(spots.is_empty());
+    }
+
+    #[test]
+    fn test_hyperlink_detection() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+        assert!(supports_hyperlinks_with(env(&[("TERM_PROGRAM", "iTerm.app")])));
+        assert!(supports_hyperlinks_with(env(&[("VTE_VERSION", "6800")])));
+        assert!(!supports_hyperlinks_with(env(&[("TERM", "xterm-256color")])));
+        assert!(!supports_hyperlinks_with(env(&[("WT_SESSION", "1"), (HYPERLINKS_ENV, "0")])));
+        assert!(supports_hyperlinks_with(env(&[("TERM", "dumb"), (HYPERLINKS_ENV, "1")])));
+    }
+}
@@ -0,0 +1,311 @@
+// Locale catalog for user-facing TUI strings
+//
+// Tabs, the help bar, popups and preflight messages are looked up here
+// instead of being written inline, so demos can be given in front of
+// non-English audiences. The locale comes from `locale` in the demo
+// configuration (or `RAPS_LOCALE`); anything without a translation, such as
+// workflow names and RAPS output, stays as is.
+//
+// Messages with values use `{0}`, `{1}`, ... placeholders filled by
+// `Locale::format`.
+
+use serde::{Deserialize, Serialize};
+
+/// Language of the TUI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+    De,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::Ja, Locale::De];
+
+    /// Language code, as used in the configuration
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ja => "ja",
+            Locale::De => "de",
+        }
+    }
+
+    /// Catalog text for `text`
+    pub fn text(self, text: Text) -> &'static str {
+        let [en, ja, de] = catalog(text);
+        match self {
+            Locale::En => en,
+            Locale::Ja => ja,
+            Locale::De => de,
+        }
+    }
+
+    /// Catalog text with `{0}`, `{1}`, ... replaced by `args`
+    pub fn format(self, text: Text, args: &[&str]) -> String {
+        args.iter()
+            .enumerate()
+            .fold(self.text(text).to_string(), |s, (i, arg)| s.replace(&format!("{{{}}}", i), arg))
+    }
+
+    /// Display name of a preflight check, which is keyed by its English name
+    pub fn check_name(self, name: &str) -> &str {
+        let text = match name {
+            "Authentication" => Text::CheckAuthentication,
+            "Assets" => Text::CheckAssets,
+            "Permissions" => Text::CheckPermissions,
+            "External Tool" => Text::CheckExternalTool,
+            _ => return name,
+        };
+        self.text(text)
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl std::str::FromStr for Locale {
+    type Err = String;
+
+    /// Accepts codes like `ja`, `de-DE` or `en_US.UTF-8`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s
+            .split(['_', '-', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Locale::ALL
+            .into_iter()
+            .find(|l| l.code() == language)
+            .ok_or_else(|| format!("Unsupported locale '{}' (expected en, ja or de)", s))
+    }
+}
+
+/// Keys of the translated strings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    // Detail tabs
+    TabOverview,
+    TabSteps,
+    TabFlowchart,
+    TabAssets,
+    TabYaml,
+    TabTimeline,
+    TabVariables,
+    TabResults,
+    TabData,
+    TabDesignAutomation,
+    Details,
+    ConsoleOutput,
+
+    // Help bar
+    HelpScroll,
+    HelpTabs,
+    HelpWidth,
+    HelpHeight,
+    HelpDrawer,
+    HelpRun,
+    HelpQuit,
+
+    // Popups
+    KeyBindings,
+    WorkflowComplete,
+    WorkflowCompleted,
+    TranslationCompleted,
+    MissingRequirements,
+    CannotRun,
+    Missing,
+    DownloadFromAssetsTab,
+    ResolveRequirements,
+    QueuedRunComplete,
+    Validation,
+    TourComplete,
+    TourFinished,
+    PopupClose,
+    PopupUrlHint,
+    Configuration,
+    ConfigurationReloaded,
+    ConfigurationNotReloaded,
+
+    // Preflight
+    CheckAuthentication,
+    CheckAssets,
+    CheckPermissions,
+    CheckExternalTool,
+    AuthNotRequired,
+    AuthFound,
+    AuthRequired,
+    NoAssetsRequired,
+    AssetsAvailable,
+    FilesMissing,
+    AssetsNeedDownload,
+    ReadyToRun,
+    MissingSeeAssets,
+    StepsNeedAssets,
+    Checking,
+
+    // Terminal size
+    TerminalTooSmall,
+    TerminalSizeNeeded,
+    TerminalDoctorHint,
+}
+
+/// `[en, ja, de]` text for a key
+fn catalog(text: Text) -> [&'static str; 3] {
+    match text {
+        Text::TabOverview => ["Overview", "概要", "Übersicht"],
+        Text::TabSteps => ["Steps", "ステップ", "Schritte"],
+        Text::TabFlowchart => ["Flowchart", "フロー図", "Ablauf"],
+        Text::TabAssets => ["Assets", "アセット", "Dateien"],
+        Text::TabYaml => ["YAML", "YAML", "YAML"],
+        Text::TabTimeline => ["Timeline", "タイムライン", "Zeitachse"],
+        Text::TabVariables => ["Variables", "変数", "Variablen"],
+        Text::TabResults => ["Results", "結果", "Ergebnisse"],
+        Text::TabData => ["Data", "データ", "Daten"],
+        Text::TabDesignAutomation => ["DA", "DA", "DA"],
+        Text::Details => ["Details", "詳細", "Details"],
+        Text::ConsoleOutput => ["Console Output", "コンソール出力", "Konsolenausgabe"],
+
+        Text::HelpScroll => ["Scroll", "スクロール", "Blättern"],
+        Text::HelpTabs => ["Tabs", "タブ", "Tabs"],
+        Text::HelpWidth => ["Width", "幅", "Breite"],
+        Text::HelpHeight => ["Height", "高さ", "Höhe"],
+        Text::HelpDrawer => ["Workflows", "ワークフロー", "Workflows"],
+        Text::HelpRun => ["Run", "実行", "Starten"],
+        Text::HelpQuit => ["Quit", "終了", "Beenden"],
+
+        Text::KeyBindings => ["Key Bindings", "キー割り当て", "Tastenbelegung"],
+        Text::WorkflowComplete => ["Workflow Complete", "ワークフロー完了", "Workflow abgeschlossen"],
+        Text::WorkflowCompleted => [
+            "Workflow '{0}' completed successfully!",
+            "ワークフロー '{0}' が正常に完了しました！",
+            "Workflow '{0}' wurde erfolgreich abgeschlossen!",
+        ],
+        Text::TranslationCompleted => [
+            "Model translation '{0}' completed successfully!",
+            "モデル変換 '{0}' が正常に完了しました！",
+            "Modellübersetzung '{0}' wurde erfolgreich abgeschlossen!",
+        ],
+        Text::MissingRequirements => ["Missing Requirements", "前提条件が不足しています", "Fehlende Voraussetzungen"],
+        Text::CannotRun => ["Cannot run '{0}'", "'{0}' を実行できません", "'{0}' kann nicht gestartet werden"],
+        Text::Missing => ["Missing: {0}", "不足: {0}", "Fehlt: {0}"],
+        Text::DownloadFromAssetsTab => [
+            "Go to Assets tab (press 4) to download required files.",
+            "アセットタブ（4キー）で必要なファイルをダウンロードしてください。",
+            "Im Tab Dateien (Taste 4) die benötigten Dateien herunterladen.",
+        ],
+        Text::ResolveRequirements => [
+            "Please resolve these requirements first.",
+            "先にこれらの前提条件を解決してください。",
+            "Bitte zuerst diese Voraussetzungen erfüllen.",
+        ],
+        Text::QueuedRunComplete => ["Queued Run Complete", "キュー実行完了", "Warteschlange abgeschlossen"],
+        Text::Validation => ["Validation", "検証", "Validierung"],
+        Text::TourComplete => ["Tour Complete", "ツアー完了", "Tour abgeschlossen"],
+        Text::TourFinished => [
+            "That's the end of '{0}'. Thanks for watching!",
+            "'{0}' は以上です。ご覧いただきありがとうございました！",
+            "Das war '{0}'. Danke fürs Zuschauen!",
+        ],
+        Text::PopupClose => [
+            "(Press any key to close)",
+            "（任意のキーで閉じる）",
+            "(Beliebige Taste zum Schließen)",
+        ],
+        Text::PopupUrlHint => [
+            "(Press 'o' to open in browser, {0} to copy, any key to close)",
+            "（'o' でブラウザを開く、{0} でコピー、任意のキーで閉じる）",
+            "('o' öffnet im Browser, {0} kopiert, beliebige Taste schließt)",
+        ],
+        Text::Configuration => ["Configuration", "設定", "Konfiguration"],
+        Text::ConfigurationReloaded => ["Configuration reloaded", "設定を再読み込みしました", "Konfiguration neu geladen"],
+        Text::ConfigurationNotReloaded => [
+            "Not reloaded: see the console",
+            "再読み込みされませんでした: コンソールを参照",
+            "Nicht neu geladen: siehe Konsole",
+        ],
+
+        Text::CheckAuthentication => ["Authentication", "認証", "Authentifizierung"],
+        Text::CheckAssets => ["Assets", "アセット", "Dateien"],
+        Text::CheckPermissions => ["Permissions", "権限", "Berechtigungen"],
+        Text::CheckExternalTool => ["External Tool", "外部ツール", "Externes Werkzeug"],
+        Text::AuthNotRequired => [
+            "Not required for this workflow",
+            "このワークフローでは不要です",
+            "Für diesen Workflow nicht nötig",
+        ],
+        Text::AuthFound => ["APS credentials found", "APS 認証情報が見つかりました", "APS-Zugangsdaten gefunden"],
+        Text::AuthRequired => ["APS authentication required", "APS 認証が必要です", "APS-Authentifizierung erforderlich"],
+        Text::NoAssetsRequired => ["No assets required", "アセットは不要です", "Keine Dateien nötig"],
+        Text::AssetsAvailable => ["{0} asset(s) available", "{0} 個のアセットが利用可能", "{0} Datei(en) vorhanden"],
+        Text::FilesMissing => ["{0} file(s) missing", "{0} 個のファイルが不足", "{0} Datei(en) fehlen"],
+        Text::AssetsNeedDownload => [
+            "{0} asset(s) need download",
+            "{0} 個のアセットのダウンロードが必要",
+            "{0} Datei(en) müssen heruntergeladen werden",
+        ],
+        Text::ReadyToRun => [
+            "Ready to run! Press ENTER to execute",
+            "実行できます！ENTER キーで実行",
+            "Bereit! Mit ENTER starten",
+        ],
+        Text::MissingSeeAssets => [
+            "Missing requirements - see Assets tab",
+            "前提条件が不足しています - アセットタブを参照",
+            "Fehlende Voraussetzungen - siehe Tab Dateien",
+        ],
+        Text::StepsNeedAssets => [
+            "Steps needing missing assets: {0}",
+            "不足しているアセットが必要なステップ: {0}",
+            "Schritte mit fehlenden Dateien: {0}",
+        ],
+        Text::Checking => ["Checking...", "確認中...", "Wird geprüft..."],
+
+        Text::TerminalTooSmall => ["Terminal too small", "ターミナルが小さすぎます", "Terminal zu klein"],
+        Text::TerminalSizeNeeded => [
+            "The window is {0}; the demo needs at least {1}. Enlarge it to continue.",
+            "ウィンドウは {0} です。{1} 以上が必要です。拡大すると続行できます。",
+            "Das Fenster ist {0} groß; die Demo braucht mindestens {1}. Zum Fortfahren vergrößern.",
+        ],
+        Text::TerminalDoctorHint => [
+            "raps-demo doctor-terminal checks the terminal",
+            "raps-demo doctor-terminal でターミナルを確認できます",
+            "raps-demo doctor-terminal prüft das Terminal",
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_lookup_and_parsing() {
+        assert_eq!("ja_JP.UTF-8".parse::<Locale>(), Ok(Locale::Ja));
+        assert_eq!("de-DE".parse::<Locale>(), Ok(Locale::De));
+        assert!("fr".parse::<Locale>().is_err());
+
+        assert_eq!(Locale::De.text(Text::TabSteps), "Schritte");
+        assert_eq!(Locale::Ja.format(Text::CannotRun, &["OSS"]), "'OSS' を実行できません");
+        assert_eq!(Locale::De.check_name("Assets"), "Dateien");
+        assert_eq!(Locale::Ja.check_name("Custom"), "Custom");
+
+        // Every translation keeps the placeholders of the English text
+        for text in [
+            Text::WorkflowCompleted,
+            Text::Missing,
+            Text::AssetsAvailable,
+            Text::TourFinished,
+            Text::TerminalSizeNeeded,
+        ] {
+            for locale in Locale::ALL {
+                assert!(locale.text(text).contains("{0}"), "{:?} {:?}", locale, text);
+            }
+        }
+    }
+}
@@ -0,0 +1,569 @@
+//! Configurable key bindings for the TUI
+//!
+//! Defaults mirror the built-in controls. A `keymap.toml` in the config
+//! directory can rebind any action, e.g.:
+//!
+//! ```toml
+//! [bindings]
+//! run = ["r", "Enter"]
+//! sidebar_shrink = ["<"]
+//! sidebar_grow = [">"]
+//! ```
+//!
+//! Listing an action replaces all of its default keys. Conflicting bindings
+//! are rejected at load time.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use crate::config::types::ConfigPaths;
+
+/// An action that can be bound to one or more keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Action {
+    Quit,
+    Help,
+    Up,
+    Down,
+    PrevTab,
+    NextTab,
+    CycleTab,
+    Run,
+    ToggleFavorite,
+    ToggleMark,
+    ClearMarks,
+    BulkRun,
+    BulkValidate,
+    BulkExport,
+    BulkCost,
+    TabOverview,
+    TabSteps,
+    TabFlowchart,
+    TabAssets,
+    TabYaml,
+    TabTimeline,
+    TabVariables,
+    TabResults,
+    TabData,
+    TabDesignAutomation,
+    Download,
+    Edit,
+    FlowchartZoom,
+    PanLeft,
+    PanRight,
+    Export,
+    OpenArtifacts,
+    ViewFullOutput,
+    KillStalled,
+    FailStalled,
+    PauseResume,
+    RunFromStep,
+    PickProfile,
+    Copy,
+    CopyConsole,
+    ShowQrCode,
+    BrowseDerivatives,
+    ShowAuth,
+    Presentation,
+    PageUp,
+    PageDown,
+    Home,
+    SidebarShrink,
+    SidebarGrow,
+    ToggleSidebar,
+    ConsoleShrink,
+    ConsoleGrow,
+}
+
+impl Action {
+    /// All actions in display order
+    pub const ALL: [Action; 52] = [
+        Action::Quit,
+        Action::Help,
+        Action::Up,
+        Action::Down,
+        Action::PrevTab,
+        Action::NextTab,
+        Action::CycleTab,
+        Action::Run,
+        Action::ToggleFavorite,
+        Action::ToggleMark,
+        Action::ClearMarks,
+        Action::BulkRun,
+        Action::BulkValidate,
+        Action::BulkExport,
+        Action::BulkCost,
+        Action::TabOverview,
+        Action::TabSteps,
+        Action::TabFlowchart,
+        Action::TabAssets,
+        Action::TabYaml,
+        Action::TabTimeline,
+        Action::TabVariables,
+        Action::TabResults,
+        Action::TabData,
+        Action::TabDesignAutomation,
+        Action::Download,
+        Action::Edit,
+        Action::FlowchartZoom,
+        Action::PanLeft,
+        Action::PanRight,
+        Action::Export,
+        Action::OpenArtifacts,
+        Action::ViewFullOutput,
+        Action::KillStalled,
+        Action::FailStalled,
+        Action::PauseResume,
+        Action::RunFromStep,
+        Action::PickProfile,
+        Action::Copy,
+        Action::CopyConsole,
+        Action::ShowQrCode,
+        Action::BrowseDerivatives,
+        Action::ShowAuth,
+        Action::Presentation,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Home,
+        Action::SidebarShrink,
+        Action::SidebarGrow,
+        Action::ToggleSidebar,
+        Action::ConsoleShrink,
+        Action::ConsoleGrow,
+    ];
+
+    /// Name used in `keymap.toml`
+    pub fn config_name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Help => "help",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::PrevTab => "prev_tab",
+            Action::NextTab => "next_tab",
+            Action::CycleTab => "cycle_tab",
+            Action::Run => "run",
+            Action::ToggleFavorite => "toggle_favorite",
+            Action::ToggleMark => "toggle_mark",
+            Action::ClearMarks => "clear_marks",
+            Action::BulkRun => "bulk_run",
+            Action::BulkValidate => "bulk_validate",
+            Action::BulkExport => "bulk_export",
+            Action::BulkCost => "bulk_cost",
+            Action::TabOverview => "tab_overview",
+            Action::TabSteps => "tab_steps",
+            Action::TabFlowchart => "tab_flowchart",
+            Action::TabAssets => "tab_assets",
+            Action::TabYaml => "tab_yaml",
+            Action::TabTimeline => "tab_timeline",
+            Action::TabVariables => "tab_variables",
+            Action::TabResults => "tab_results",
+            Action::TabData => "tab_data",
+            Action::TabDesignAutomation => "tab_design_automation",
+            Action::Download => "download",
+            Action::Edit => "edit",
+            Action::FlowchartZoom => "flowchart_zoom",
+            Action::PanLeft => "pan_left",
+            Action::PanRight => "pan_right",
+            Action::Export => "export",
+            Action::OpenArtifacts => "open_artifacts",
+            Action::ViewFullOutput => "view_full_output",
+            Action::KillStalled => "kill_stalled",
+            Action::FailStalled => "fail_stalled",
+            Action::PauseResume => "pause_resume",
+            Action::RunFromStep => "run_from_step",
+            Action::PickProfile => "pick_profile",
+            Action::Copy => "copy",
+            Action::CopyConsole => "copy_console",
+            Action::ShowQrCode => "show_qr_code",
+            Action::BrowseDerivatives => "browse_derivatives",
+            Action::ShowAuth => "show_auth",
+            Action::Presentation => "presentation",
+            Action::PageUp => "page_up",
+            Action::PageDown => "page_down",
+            Action::Home => "home",
+            Action::SidebarShrink => "sidebar_shrink",
+            Action::SidebarGrow => "sidebar_grow",
+            Action::ToggleSidebar => "toggle_sidebar",
+            Action::ConsoleShrink => "console_shrink",
+            Action::ConsoleGrow => "console_grow",
+        }
+    }
+
+    /// Human-readable description for the help overlay
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Help => "Show key bindings",
+            Action::Up => "Move up / scroll up",
+            Action::Down => "Move down / scroll down",
+            Action::PrevTab => "Previous tab",
+            Action::NextTab => "Next tab",
+            Action::CycleTab => "Cycle tabs",
+            Action::Run => "Run workflow",
+            Action::ToggleFavorite => "Toggle favorite",
+            Action::ToggleMark => "Mark/unmark workflow",
+            Action::ClearMarks => "Clear marked workflows",
+            Action::BulkRun => "Run marked workflows in sequence",
+            Action::BulkValidate => "Validate marked workflows",
+            Action::BulkExport => "Export diagrams of marked workflows",
+            Action::BulkCost => "Estimate cost of marked workflows",
+            Action::TabOverview => "Overview tab",
+            Action::TabSteps => "Steps tab",
+            Action::TabFlowchart => "Flowchart tab",
+            Action::TabAssets => "Assets tab",
+            Action::TabYaml => "YAML tab",
+            Action::TabTimeline => "Timeline tab",
+            Action::TabVariables => "Variables tab",
+            Action::TabResults => "Results tab",
+            Action::TabData => "Data Management tab (hubs, projects, folders, items)",
+            Action::TabDesignAutomation => "Design Automation tab (app bundles, activities)",
+            Action::Download => "Download asset",
+            Action::Edit => "Edit workflow YAML",
+            Action::FlowchartZoom => "Toggle flowchart detail",
+            Action::PanLeft => "Pan flowchart left",
+            Action::PanRight => "Pan flowchart right",
+            Action::Export => "Export flowchart",
+            Action::OpenArtifacts => "Open artifacts of the last run",
+            Action::ViewFullOutput => "View the full output of the last truncated step",
+            Action::KillStalled => "Kill the stalled run's command (cancels the run)",
+            Action::FailStalled => "Mark the stalled run as failed",
+            Action::PauseResume => "Pause the running workflow after its current step, or resume it",
+            Action::RunFromStep => "Run the workflow from the top step of the Steps tab",
+            Action::PickProfile => "Choose the profile (subset of steps) the selected workflow runs",
+            Action::Copy => "Copy the popup URL, selected value or last console line",
+            Action::CopyConsole => "Copy the visible console output",
+            Action::ShowQrCode => "Show the selected URL or the last signed URL as a QR code",
+            Action::BrowseDerivatives => "Pick translated derivatives to download into the artifacts folder",
+            Action::ShowAuth => "Show the current credentials: profile, token type, scopes and expiry",
+            Action::Presentation => "Toggle presentation mode",
+            Action::PageUp => "Page up",
+            Action::PageDown => "Page down",
+            Action::Home => "Scroll to top",
+            Action::SidebarShrink => "Shrink sidebar",
+            Action::SidebarGrow => "Grow sidebar",
+            Action::ToggleSidebar => "Open or close the workflow drawer (narrow terminals)",
+            Action::ConsoleShrink => "Shrink console",
+            Action::ConsoleGrow => "Grow console",
+        }
+    }
+
+    /// Look up an action by its config name
+    pub fn from_config_name(name: &str) -> Option<Action> {
+        Self::ALL.iter().copied().find(|a| a.config_name() == name)
+    }
+
+    /// Default key specs for this action
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q"],
+            Action::Help => &["?"],
+            Action::Up => &["Up", "k"],
+            Action::Down => &["Down", "j"],
+            Action::PrevTab => &["Left", "h"],
+            Action::NextTab => &["Right", "l"],
+            Action::CycleTab => &["Tab"],
+            Action::Run => &["Enter"],
+            Action::ToggleFavorite => &["*"],
+            Action::ToggleMark => &["Space"],
+            Action::ClearMarks => &["Esc"],
+            Action::BulkRun => &["R"],
+            Action::BulkValidate => &["V"],
+            Action::BulkExport => &["X"],
+            Action::BulkCost => &["C"],
+            Action::TabOverview => &["1"],
+            Action::TabSteps => &["2"],
+            Action::TabFlowchart => &["3"],
+            Action::TabAssets => &["4"],
+            Action::TabYaml => &["5"],
+            Action::TabTimeline => &["6"],
+            Action::TabVariables => &["7"],
+            Action::TabResults => &["8"],
+            Action::TabData => &["9"],
+            Action::TabDesignAutomation => &["0"],
+            Action::Download => &["d", "D"],
+            Action::Edit => &["e"],
+            Action::FlowchartZoom => &["z"],
+            Action::PanLeft => &["H", "Alt+Left"],
+            Action::PanRight => &["L", "Alt+Right"],
+            Action::Export => &["x"],
+            Action::OpenArtifacts => &["a"],
+            Action::ViewFullOutput => &["o"],
+            Action::KillStalled => &["K"],
+            Action::FailStalled => &["F"],
+            Action::PauseResume => &["P"],
+            Action::RunFromStep => &["g"],
+            Action::PickProfile => &["f"],
+            Action::Copy => &["y"],
+            Action::CopyConsole => &["Y"],
+            Action::ShowQrCode => &["u"],
+            Action::BrowseDerivatives => &["b"],
+            Action::ShowAuth => &["A"],
+            Action::Presentation => &["p"],
+            Action::PageUp => &["PageUp"],
+            Action::PageDown => &["PageDown"],
+            Action::Home => &["Home"],
+            Action::SidebarShrink => &["[", "<"],
+            Action::SidebarGrow => &["]", ">"],
+            Action::ToggleSidebar => &["s"],
+            Action::ConsoleShrink => &["-"],
+            Action::ConsoleGrow => &["+", "="],
+        }
+    }
+}
+
+/// A single key (with optional Ctrl/Alt modifier)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeySpec {
+    code: KeyCode,
+    ctrl: bool,
+    alt: bool,
+}
+
+impl KeySpec {
+    /// Parse a key spec such as `q`, `Enter`, `PageUp`, `F5` or `Ctrl+r`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut ctrl = false;
+        let mut alt = false;
+        let mut rest = spec.trim();
+
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            if rest.len() > 1 && (lower.starts_with("ctrl+") || lower.starts_with("ctrl-")) {
+                ctrl = true;
+                rest = &rest[5..];
+            } else if rest.len() > 1 && (lower.starts_with("alt+") || lower.starts_with("alt-")) {
+                alt = true;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "esc" | "escape" => KeyCode::Esc,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                other => match other.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => bail!("Unknown key '{}'", spec),
+                },
+            },
+        };
+
+        Ok(Self { code, ctrl, alt })
+    }
+
+    /// Build a spec from a terminal key event
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self {
+            code: key.code,
+            ctrl: key.modifiers.contains(KeyModifiers::CONTROL),
+            alt: key.modifiers.contains(KeyModifiers::ALT),
+        }
+    }
+}
+
+impl fmt::Display for KeySpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// On-disk keymap format
+#[derive(Debug, Default, Deserialize)]
+struct KeymapFile {
+    #[serde(default)]
+    bindings: BTreeMap<String, Vec<String>>,
+}
+
+/// Effective key bindings
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: BTreeMap<Action, Vec<KeySpec>>,
+    lookup: HashMap<KeySpec, Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .map(|action| {
+                let keys = action
+                    .default_keys()
+                    .iter()
+                    .map(|k| KeySpec::parse(k).expect("default key specs are valid"))
+                    .collect();
+                (*action, keys)
+            })
+            .collect();
+        Self::from_bindings(bindings).expect("default key bindings don't conflict")
+    }
+}
+
+impl Keymap {
+    /// Load the keymap from the config directory.
+    ///
+    /// Returns the defaults and an error message if the file is invalid.
+    pub fn load() -> (Self, Option<String>) {
+        let path = match ConfigPaths::keymap_file() {
+            Ok(path) => path,
+            Err(_) => return (Self::default(), None),
+        };
+
+        if !path.exists() {
+            return (Self::default(), None);
+        }
+
+        match Self::load_from(&path) {
+            Ok(keymap) => (keymap, None),
+            Err(e) => {
+                tracing::warn!("Invalid keymap {:?}: {:#}", path, e);
+                (Self::default(), Some(format!("Keymap ignored, using defaults: {:#}", e)))
+            },
+        }
+    }
+
+    /// Load a keymap file, applying overrides on top of the defaults
+    pub fn load_from<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read keymap file: {:?}", path))?;
+        Self::from_toml(&content)
+    }
+
+    /// Parse keymap overrides from TOML
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let file: KeymapFile = toml::from_str(content).context("Failed to parse keymap")?;
+
+        let mut bindings = Self::default().bindings;
+        for (name, keys) in file.bindings {
+            let action = Action::from_config_name(&name)
+                .with_context(|| format!("Unknown action '{}'", name))?;
+            let specs = keys
+                .iter()
+                .map(|k| KeySpec::parse(k))
+                .collect::<Result<Vec<_>>>()
+                .with_context(|| format!("Invalid binding for '{}'", name))?;
+            bindings.insert(action, specs);
+        }
+
+        Self::from_bindings(bindings)
+    }
+
+    /// Build the reverse lookup, rejecting keys bound to more than one action
+    fn from_bindings(bindings: BTreeMap<Action, Vec<KeySpec>>) -> Result<Self> {
+        let mut lookup = HashMap::new();
+        for (action, keys) in &bindings {
+            for key in keys {
+                if let Some(existing) = lookup.insert(*key, *action) {
+                    bail!(
+                        "Key '{}' is bound to both '{}' and '{}'",
+                        key,
+                        existing.config_name(),
+                        action.config_name()
+                    );
+                }
+            }
+        }
+        Ok(Self { bindings, lookup })
+    }
+
+    /// Resolve a key event to an action (Shift is implied by the character itself)
+    pub fn action_for(&self, key: &KeyEvent) -> Option<Action> {
+        self.lookup.get(&KeySpec::from_event(key)).copied()
+    }
+
+    /// Keys bound to an action, formatted for display (e.g. `Enter/r`)
+    pub fn keys_label(&self, action: Action) -> String {
+        self.bindings
+            .get(&action)
+            .map(|keys| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>().join("/"))
+            .unwrap_or_default()
+    }
+
+    /// Help text listing all effective bindings
+    pub fn help_lines(&self) -> Vec<String> {
+        Action::ALL
+            .iter()
+            .map(|action| format!("{:<22} {:>14}", action.description(), self.keys_label(*action)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_default_keymap() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.action_for(&key(KeyCode::Enter)), Some(Action::Run));
+        assert_eq!(keymap.action_for(&key(KeyCode::Char('<'))), Some(Action::SidebarShrink));
+        assert_eq!(keymap.action_for(&key(KeyCode::Char('y'))), Some(Action::Copy));
+        assert_eq!(keymap.action_for(&key(KeyCode::Char('w'))), None);
+    }
+
+    #[test]
+    fn test_override_replaces_defaults() {
+        let keymap = Keymap::from_toml("[bindings]\nrun = [\"r\", \"Ctrl+Enter\"]\n").unwrap();
+        assert_eq!(keymap.action_for(&key(KeyCode::Char('r'))), Some(Action::Run));
+        assert_eq!(keymap.action_for(&key(KeyCode::Enter)), None);
+        assert_eq!(
+            keymap.action_for(&KeyEvent::new(KeyCode::Enter, KeyModifiers::CONTROL)),
+            Some(Action::Run)
+        );
+        assert_eq!(keymap.keys_label(Action::Run), "r/Ctrl+Enter");
+    }
+
+    #[test]
+    fn test_conflicting_bindings_rejected() {
+        let err = Keymap::from_toml("[bindings]\nrun = [\"q\"]\n").unwrap_err();
+        assert!(format!("{:#}", err).contains("bound to both"));
+    }
+
+    #[test]
+    fn test_unknown_action_and_key_rejected() {
+        assert!(Keymap::from_toml("[bindings]\nlaunch = [\"r\"]\n").is_err());
+        assert!(Keymap::from_toml("[bindings]\nrun = [\"Hyper\"]\n").is_err());
+    }
+}
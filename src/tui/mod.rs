@@ -1,9 +1,11 @@
+use std::collections::VecDeque;
 use std::io;
 use std::time::Duration;
 
-use anyhow::{Context, Result};
+use anyhow::Result;
+use futures_util::StreamExt;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseEventKind, MouseButton},
+    event::{DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyCode, KeyEventKind, KeyModifiers, MouseEventKind, MouseButton},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -12,60 +14,263 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs, Wrap},
+    widgets::{Block, Borders, ListState, Paragraph, Wrap},
     Terminal,
 };
 
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
 mod flowchart;
 use flowchart::{FlowchartWidget, FlowchartState};
 
-mod preflight;
+pub mod preflight;
 use preflight::{PreflightChecker, PreflightStatus, CheckAction};
 
+mod bulk;
+use bulk::RunQueue;
+
+mod editor;
+
+mod keymap;
+use keymap::{Action, Keymap};
+
+mod state;
+use state::{
+    console_height_at, push_recent, sidebar_percent_at, UiState, CONSOLE_HEIGHT_RANGE,
+    DETAIL_TAB_COUNT, SIDEBAR_PERCENT_RANGE,
+};
+
+mod preview;
+use preview::{DerivativeDownload, DerivativePicker, ResultPreview};
+
+mod results;
+use results::OutputTree;
+
+mod datamgmt;
+use datamgmt::{DataBrowser, DmKind, DmRequest};
+
+mod designauto;
+use designauto::{DaInspector, DaTarget};
+
+mod credentials;
+use credentials::AuthInfo;
+
+mod clipboard;
+use clipboard::{Clipboard, CopyMethod};
+
+pub mod console;
+use console::ConsoleLog;
+
+mod hyperlink;
+use hyperlink::LinkSpot;
+
+pub mod i18n;
+use i18n::{Locale, Text};
+
+pub mod glyphs;
+use glyphs::{GlyphMode, Glyphs};
+
+pub mod doctor;
+
+#[cfg(any(test, feature = "test-driver"))]
+pub mod driver;
+
+mod responsive;
+use responsive::{LayoutMode, PanelSizes};
+
+mod views;
+use views::assets::{AssetsView, RequiredAssets};
+use views::console::ConsoleView;
+use views::detail_tabs::DetailTabs;
+use views::popup::PopupView;
+use views::sidebar::{SidebarRow, SidebarView};
+use views::toast::ToastView;
+use views::Msg;
+
+mod presentation;
+use presentation::PresentationView;
+
+mod tour;
+use tour::TourRun;
+
+mod progress;
+use progress::format_progress_bar;
+
+mod sidebar;
+use sidebar::{SidebarGroup, SidebarItem, SidebarLayout};
+
+mod tabs;
+
+mod timeline;
+use timeline::TimelineEntry;
+
+mod variables;
+use variables::VariableEntry;
+
 use crate::workflow::{
-    ExecutionStatus, ExecutionUpdate, WorkflowDiscovery, WorkflowExecutor, WorkflowMetadata,
-    WorkflowDefinition, RapsCommand,
+    DiscoveredWorkflows, ExecutionHandle, ExecutionOptions, ExecutionStatus, ExecutionUpdate, StepDecision, VariableSource, WorkflowEngine, WorkflowMetadata,
+    RapsCommand, UpdateReceiver, WorkflowCategory, ObjectAction, AuthAction,
 };
+use crate::workflow::client::{CommandProgress, RapsClient};
+use crate::workflow::history::{DurationHistory, SharedHistory};
+use crate::workflow::locks::LockWait;
+use crate::workflow::metrics::WorkflowMetrics;
+use crate::workflow::builtin;
+use crate::workflow::output;
+use crate::workflow::diff::WorkflowDiff;
+use crate::workflow::pack::{self, PackTrust};
+use crate::workflow::tour::{self as tours, TourDefinition, TourItem};
+use crate::workflow::translation;
+use crate::config::types::ConfigPaths;
+use crate::config::watch::ConfigWatcher;
+use crate::assets::{AssetDefinition, AssetRegistry};
+use crate::builder::RapsDemoBuilder;
+use crate::resource::InterruptedWorkflowCleanup;
+use crate::utils::cast::{CastWriter, SessionRecorder};
+use crate::utils::crash;
+use crate::utils::qr;
+use crate::utils::interrupt::InterruptSignal;
+use crate::utils::update::{self, Release, UpdateConfig};
+
+/// Terminal the TUI draws to, optionally recording the session
+type AppTerminal = Terminal<CrosstermBackend<SessionRecorder<io::Stdout>>>;
 
 /// Guard to ensure terminal is restored even on panic
 struct TerminalGuard;
 
+impl TerminalGuard {
+    /// Restore the terminal when dropped, and before a crash report on panic
+    fn new() -> Self {
+        crash::install_panic_hook();
+        crash::set_terminal_restore(Some(restore_terminal));
+        TerminalGuard
+    }
+}
+
 impl Drop for TerminalGuard {
     fn drop(&mut self) {
-        // Attempt to restore terminal state
-        let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+        crash::set_terminal_restore(None);
+        restore_terminal();
     }
 }
 
-/// Sidebar item type for grouped workflow display
-#[derive(Clone, Debug)]
-enum SidebarItem {
-    /// Category header (expandable/collapsible)
-    Category { name: String, count: usize },
-    /// Workflow entry with index into workflows vec
-    Workflow { index: usize },
+/// Attempt to restore terminal state
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+}
+
+/// Run a listing command through `client` in the background and send its
+/// JSON output (or why there is none) back tagged with `key`
+fn spawn_listing<K: Send + 'static>(
+    client: Arc<RapsClient>,
+    sender: mpsc::UnboundedSender<(K, std::result::Result<serde_json::Value, String>)>,
+    key: K,
+    command: RapsCommand,
+) {
+    tokio::spawn(async move {
+        let result = match client.execute_command_async(&command).await {
+            Ok(result) if result.success => result.json_output.ok_or_else(|| "Output was not JSON".to_string()),
+            Ok(result) => Err(result.error_message().unwrap_or_default()),
+            Err(e) => Err(format!("{:#}", e)),
+        };
+        let _ = sender.send((key, result));
+    });
 }
 
+/// Sidebar group listing favorite workflows
+const FAVORITES_CATEGORY: &str = "Favorites";
+/// Sidebar group listing recently executed workflows
+const RECENT_CATEGORY: &str = "Recent";
+/// Sidebar group listing guided tours
+const TOURS_CATEGORY: &str = "Tours";
+/// How often the screen is redrawn without input, for the timeline's
+/// running bars and the auth panel's expiry countdown
+const TICK_INTERVAL: Duration = Duration::from_millis(250);
+/// How often the configuration files are checked for edits
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How long a toast stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+
 pub struct TuiApp {
     /// List of discovered workflows
     workflows: Vec<WorkflowMetadata>,
-    /// Cached workflow definitions for quick access
-    workflow_definitions: std::collections::HashMap<String, WorkflowDefinition>,
+    /// Workflow definitions shared with the engine, by ID
+    workflow_definitions: DiscoveredWorkflows,
+    /// Pack and trust status of workflows from installed packs, by workflow ID
+    pack_labels: std::collections::HashMap<String, String>,
     /// State for the workflow list
     list_state: ListState,
     /// Whether the app should exit
     should_quit: bool,
-    /// Console logs/output
-    logs: Vec<String>,
-    /// Workflow engine executor
-    executor: Arc<WorkflowExecutor>,
+    /// Console logs/output (recent lines; the session transcript has them all)
+    logs: ConsoleLog,
+    /// Workflow engine (discovery, execution, cancellation)
+    engine: WorkflowEngine,
     /// Receiver for execution updates
-    update_receiver: mpsc::UnboundedReceiver<ExecutionUpdate>,
-    /// Current detail tab (0 = Overview, 1 = Steps, 2 = Flowchart, 3 = Assets, 4 = YAML)
+    update_receiver: UpdateReceiver,
+    /// Thumbnail/manifest preview of the last Model Derivative run
+    result_preview: Option<ResultPreview>,
+    /// Channel delivering fetched previews back to the UI loop
+    preview_sender: mpsc::UnboundedSender<ResultPreview>,
+    preview_receiver: mpsc::UnboundedReceiver<ResultPreview>,
+    /// Derivatives of the previewed model being picked for download
+    derivative_picker: Option<DerivativePicker>,
+    /// Channel delivering finished derivative downloads and their folder
+    download_sender: mpsc::UnboundedSender<(PathBuf, Vec<DerivativeDownload>)>,
+    download_receiver: mpsc::UnboundedReceiver<(PathBuf, Vec<DerivativeDownload>)>,
+    /// Names of the sample assets being downloaded
+    asset_downloads: std::collections::HashSet<String>,
+    /// Channel delivering finished sample asset downloads
+    asset_sender: mpsc::UnboundedSender<(AssetDefinition, std::result::Result<PathBuf, String>)>,
+    asset_receiver: mpsc::UnboundedReceiver<(AssetDefinition, std::result::Result<PathBuf, String>)>,
+    /// Hubs, projects, folders and items of the Data tab
+    data_browser: DataBrowser,
+    /// App bundles and activities of the DA tab
+    da_inspector: DaInspector,
+    /// Channel delivering Design Automation listings and definitions
+    da_sender: mpsc::UnboundedSender<(DaTarget, std::result::Result<serde_json::Value, String>)>,
+    da_receiver: mpsc::UnboundedReceiver<(DaTarget, std::result::Result<serde_json::Value, String>)>,
+    /// Credentials shown in the auth panel while it is open
+    auth_panel: Option<AuthInfo>,
+    /// Channel delivering auth action outcomes and refreshed credentials
+    auth_sender: mpsc::UnboundedSender<(Option<String>, AuthInfo)>,
+    auth_receiver: mpsc::UnboundedReceiver<(Option<String>, AuthInfo)>,
+    /// Parameters picked in the Data and DA tabs, passed to every run
+    data_params: std::collections::HashMap<String, String>,
+    /// Channel delivering Data Management listings (parent key, JSON or error)
+    data_sender: mpsc::UnboundedSender<(String, std::result::Result<serde_json::Value, String>)>,
+    data_receiver: mpsc::UnboundedReceiver<(String, std::result::Result<serde_json::Value, String>)>,
+    /// Parsed JSON outputs of the last run's steps, for the Results tab
+    step_outputs: OutputTree,
+    /// Signed URL produced by the last run, shown as a QR code
+    signed_url: Option<String>,
+    /// Clipboard used by the copy action
+    clipboard: Clipboard,
+    /// Whether URLs are emitted as OSC 8 hyperlinks
+    hyperlinks: bool,
+    /// URLs drawn in the last frame, re-printed as hyperlinks
+    link_spots: Vec<LinkSpot>,
+    /// asciinema cast file the session is recorded to
+    record_path: Option<PathBuf>,
+    /// Whether presentation mode (big step banner, no side panels) is active
+    presentation: bool,
+    /// Outcome of the last finished run, shown in the presentation banner
+    last_run_status: Option<ExecutionStatus>,
+    /// Language of the TUI strings
+    locale: Locale,
+    /// Icons for the sidebar, tabs, Overview and Assets views
+    glyphs: &'static Glyphs,
+    /// Workflows stopped by Ctrl-C, reported after the terminal is restored
+    interrupted: Vec<InterruptedWorkflowCleanup>,
+    /// Guided tours found next to the workflows
+    tours: Vec<TourDefinition>,
+    /// Tour currently being presented
+    tour_run: Option<TourRun>,
+    /// Current detail tab (0 = Overview, 1 = Steps, 2 = Flowchart, 3 = Assets, 4 = YAML,
+    /// 5 = Timeline, 6 = Variables, 7 = Results)
     detail_tab: usize,
     /// Scroll offset for steps view
     steps_scroll: usize,
@@ -77,22 +282,43 @@ pub struct TuiApp {
     detail_area: Rect,
     /// Help bar area for click detection
     help_bar_area: Rect,
+    /// Console panel area, for dragging its top divider
+    console_area: Rect,
+    /// Splitter currently being dragged with the mouse
+    dragging: Option<Splitter>,
+    /// Column ranges of the rendered detail tabs, for click hit-testing
+    tab_bounds: Vec<(u16, u16)>,
     /// Current executing workflow ID
     executing_workflow_id: Option<String>,
+    /// Handle of the current execution, for pausing it
+    executing_handle: Option<ExecutionHandle>,
+    /// Whether the current execution is paused or about to pause
+    run_pause: Option<RunPause>,
+    /// Lock the current execution waits for before its first step
+    lock_wait: Option<LockWait>,
     /// Current executing step index (0-based)
     executing_step: Option<usize>,
     /// Completed step indices
     completed_steps: Vec<usize>,
+    /// Steps the last run started here includes (its profile, from its
+    /// start step), less those it skipped; all of them for other runs
+    run_steps: Option<(String, Vec<usize>)>,
     /// Resizable panel percentage for sidebar (30-70%)
     sidebar_percent: u16,
     /// Resizable console height (5-20 lines)
     console_height: u16,
+    /// Layout of the last drawn frame
+    layout_mode: LayoutMode,
+    /// Whether the sidebar drawer of the compact layout is open
+    drawer_open: bool,
     /// Collapsed category names (for expandable groups)
     collapsed_categories: std::collections::HashSet<String>,
-    /// Sidebar display items (for grouped view)
-    sidebar_items: Vec<SidebarItem>,
+    /// Sidebar rows (for grouped view)
+    sidebar: SidebarLayout,
     /// Active popup (URL to display, title)
     popup: Option<PopupState>,
+    /// Popups waiting for the active one to close, oldest first
+    popup_queue: VecDeque<PopupState>,
     /// Flag to trigger workflow run from mouse click (handled in async main loop)
     pending_run: bool,
     /// Last click position and time for double-click detection
@@ -107,65 +333,314 @@ pub struct TuiApp {
     selected_asset: usize,
     /// Pending asset download action
     pending_download: Option<usize>,
+    /// Effective key bindings
+    keymap: Keymap,
+    /// Pending request to open the selected workflow in an external editor
+    pending_edit: bool,
+    /// Workflow of the current or last run, shown in the timeline and variables tabs
+    timeline_workflow_id: Option<String>,
+    /// Step timings for the timeline tab
+    timeline: Vec<TimelineEntry>,
+    /// Placeholder values of the current (or last) run, for the variables tab
+    variables: Vec<VariableEntry>,
+    /// Index of the selected value in the variables tab
+    selected_variable: usize,
+    /// Artifacts folder of the last run that collected any
+    last_artifacts_dir: Option<PathBuf>,
+    /// Full output file of the last step whose output was truncated
+    last_full_output: Option<PathBuf>,
+    /// Run the watchdog reported as stalled, until it makes progress again
+    stalled_run: Option<ExecutionHandle>,
+    /// Workflow whose profile is being chosen, and its profile names
+    profile_picker: Option<(String, Vec<String>)>,
+    /// Profile chosen per workflow ID; workflows without one run every step
+    selected_profiles: std::collections::HashMap<String, String>,
+    /// Recorded workflow and step durations
+    history: SharedHistory,
+    /// IDs of favorite workflows, shown in the "Favorites" group
+    favorites: Vec<String>,
+    /// IDs of recently executed workflows, most recent first
+    recent: Vec<String>,
+    /// IDs of workflows marked for bulk actions
+    marked_workflows: std::collections::BTreeSet<String>,
+    /// Marked workflows being run one after another
+    run_queue: Option<RunQueue>,
+    /// Latest progress reported by the executing step, keyed by step ID
+    step_progress: Option<(String, CommandProgress)>,
+    /// Newer release found by the startup check, shown in the help bar
+    available_release: Option<Release>,
+    /// Channel delivering the result of the startup check
+    release_receiver: Option<mpsc::UnboundedReceiver<Release>>,
+    /// Configuration reloaded when its files are edited
+    config_watcher: Option<ConfigWatcher>,
+    /// Last time the configuration files were checked
+    config_polled_at: std::time::Instant,
+    /// Notice in the top right corner and when it was posted
+    toast: Option<(ToastView, std::time::Instant)>,
+}
+
+/// Pane dividers that can be dragged with the mouse
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Splitter {
+    /// Vertical border between the sidebar and the detail panel
+    Sidebar,
+    /// Horizontal border above the console
+    Console,
+}
+
+/// Pause state of the current execution
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RunPause {
+    /// Pausing once the current step finishes
+    Requested,
+    /// Holding before the next step
+    Paused,
+}
+
+/// What a popup is for, which decides the keys it takes
+#[derive(Clone, Debug, PartialEq)]
+enum PopupKind {
+    /// Information: `o` opens its URL, the copy key copies it and any other
+    /// key closes it
+    Message,
+    /// Tour narration, checkpoint or failed tour workflow
+    Tour,
+    /// Failed step of a run holding until retry, skip or abort is chosen
+    Decision(ExecutionHandle),
+    /// Profile picker of a workflow
+    Profiles,
+    /// Derivatives to download
+    Derivatives,
+    /// Credentials panel
+    Auth,
 }
 
 /// State for a popup dialog
 #[derive(Clone, Debug)]
 struct PopupState {
+    kind: PopupKind,
     title: String,
     message: String,
     url: Option<String>,
+    /// QR code of `url`, drawn below the message
+    qr: Option<Vec<String>>,
 }
 
 impl TuiApp {
     /// Create a new TUI application instance
     pub async fn new() -> Result<Self> {
-        tracing::debug!("Initializing TUI application");
+        RapsDemoBuilder::new().build_tui()
+    }
+
+    /// Record the session (terminal output and timing) to an asciinema cast file
+    pub fn with_recording(mut self, path: Option<PathBuf>) -> Self {
+        self.record_path = path;
+        self
+    }
+
+    /// Start in presentation mode
+    pub fn with_presentation(mut self, presentation: bool) -> Self {
+        self.presentation = presentation;
+        self
+    }
+
+    /// Use the Unicode or ASCII glyph set (detected from the terminal for `Auto`)
+    pub fn with_glyphs(mut self, mode: GlyphMode) -> Self {
+        self.glyphs = Glyphs::for_mode(mode);
+        self
+    }
+
+    /// Look for a newer release in the background, unless `config` turns the
+    /// startup check off
+    pub fn with_update_check(mut self, config: &UpdateConfig) -> Self {
+        if !config.startup_check_enabled() {
+            return self;
+        }
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let config = config.clone();
+        tokio::spawn(async move {
+            match update::check_for_update(&config).await {
+                Ok(Some(release)) => {
+                    let _ = sender.send(release);
+                },
+                Ok(None) => {},
+                Err(e) => tracing::debug!("Update check failed: {:#}", e),
+            }
+        });
+        self.release_receiver = Some(receiver);
+        self
+    }
+
+    /// Reload the configuration when its files are edited, applying it to
+    /// the next runs
+    pub fn with_config_watcher(mut self, watcher: ConfigWatcher) -> Self {
+        self.config_watcher = Some(watcher);
+        self
+    }
+
+    /// Keep at most `lines` console lines in memory
+    pub fn with_console_capacity(mut self, lines: usize) -> Self {
+        self.logs.set_capacity(lines);
+        self
+    }
 
-        // Ensure workflows directory exists
-        let workflows_dir = std::path::Path::new("./workflows");
-        if !workflows_dir.exists() {
-            std::fs::create_dir_all(workflows_dir)
-                .context("Failed to create workflows directory")?;
+    /// Label the workflows of packs installed in `dir` with their pack and
+    /// its trust, warning about packs that weren't verified, and offer the
+    /// assets of their asset manifests
+    pub fn with_installed_packs<P: AsRef<std::path::Path>>(mut self, dir: P) -> Self {
+        let mut packs: std::collections::HashMap<PathBuf, String> = std::collections::HashMap::new();
+        let mut manifests = Vec::new();
+        for workflow in &self.workflows {
+            let Some(installed) = pack::installed_pack_for(&workflow.script_path, dir.as_ref()) else {
+                continue;
+            };
+            let label = packs.entry(installed.path.clone()).or_insert_with(|| {
+                if installed.trust() != PackTrust::Verified {
+                    self.logs.push(format!("Warning: workflows from pack {} were not verified", installed.label()));
+                }
+                manifests.extend(installed.assets_manifest());
+                installed.label()
+            });
+            self.pack_labels.insert(workflow.id.clone(), label.clone());
         }
+        if !manifests.is_empty() {
+            for manifest in manifests {
+                self.preflight_checker = std::mem::take(&mut self.preflight_checker).with_asset_manifest(manifest);
+            }
+            self.update_preflight_cache();
+        }
+        self
+    }
+
+    /// Look up and download sample assets in `dir`
+    pub fn with_assets_dir<P: AsRef<std::path::Path>>(mut self, dir: P) -> Self {
+        self.preflight_checker = std::mem::take(&mut self.preflight_checker).with_assets_dir(dir);
+        self.update_preflight_cache();
+        self
+    }
+
+    /// Offer the assets of `registry` for download instead of the known samples
+    pub fn with_asset_registry(mut self, registry: AssetRegistry) -> Self {
+        self.preflight_checker = std::mem::take(&mut self.preflight_checker).with_registry(registry);
+        self.update_preflight_cache();
+        self
+    }
+
+    /// Show tabs, popups and preflight messages in `locale`
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self.preflight_checker = std::mem::take(&mut self.preflight_checker).with_locale(locale);
+        self.update_preflight_cache();
+        self
+    }
+
+    /// Create the TUI application, recording execution metrics if given
+    pub async fn with_metrics(metrics: Option<Arc<WorkflowMetrics>>) -> Result<Self> {
+        RapsDemoBuilder::new().metrics(metrics).build_tui()
+    }
+
+    /// Create the TUI application on top of an existing workflow engine
+    pub fn with_engine(engine: WorkflowEngine) -> Result<Self> {
+        tracing::debug!("Initializing TUI application");
 
-        // Discover workflows
-        let mut discovery = WorkflowDiscovery::new(workflows_dir)
-            .context("Failed to initialize workflow discovery")?;
-        let workflows = discovery.discover_workflows()?;
+        let workflows = engine.list().to_vec();
 
         // Cache workflow definitions
-        let workflow_definitions = discovery.get_workflows().clone();
+        let workflow_definitions = engine.get_workflows().clone();
+        let mut tours: Vec<TourDefinition> = engine.workflows_dirs().iter().flat_map(tours::discover_tours).collect();
+        tours.sort_by(|a, b| a.name.cmp(&b.name));
 
         let mut list_state = ListState::default();
         if !workflows.is_empty() {
             list_state.select(Some(0));
         }
 
-        let (executor, update_receiver) = WorkflowExecutor::new().with_progress_reporting();
+        let history = engine.history().cloned().unwrap_or_else(|| DurationHistory::default().shared());
+        let update_receiver = engine.subscribe();
+        let (preview_sender, preview_receiver) = mpsc::unbounded_channel();
+        let (download_sender, download_receiver) = mpsc::unbounded_channel();
+        let (asset_sender, asset_receiver) = mpsc::unbounded_channel();
+        let (data_sender, data_receiver) = mpsc::unbounded_channel();
+        let (da_sender, da_receiver) = mpsc::unbounded_channel();
+        let (auth_sender, auth_receiver) = mpsc::unbounded_channel();
+
+        let (keymap, keymap_error) = Keymap::load();
+        let mut logs = ConsoleLog::default();
+        logs.push("Welcome to RAPS CLI Demo Workflows! Press ? for help.".to_string());
+        if let Some(err) = keymap_error {
+            logs.push(format!("Warning: {}", err));
+        }
+        for tour in &tours {
+            for error in tour.validate(workflow_definitions.keys().map(String::as_str)) {
+                logs.push(format!("Warning: {}", error));
+            }
+        }
 
         let mut app = Self {
             workflows,
             workflow_definitions,
+            pack_labels: std::collections::HashMap::new(),
             list_state,
             should_quit: false,
-            logs: vec!["Welcome to RAPS CLI Demo Workflows! Press ? for help.".to_string()],
-            executor: Arc::new(executor),
+            logs,
+            engine,
             update_receiver,
+            result_preview: None,
+            preview_sender,
+            preview_receiver,
+            derivative_picker: None,
+            download_sender,
+            download_receiver,
+            asset_downloads: std::collections::HashSet::new(),
+            asset_sender,
+            asset_receiver,
+            data_browser: DataBrowser::default(),
+            da_inspector: DaInspector::default(),
+            da_sender,
+            da_receiver,
+            auth_panel: None,
+            auth_sender,
+            auth_receiver,
+            data_params: std::collections::HashMap::new(),
+            data_sender,
+            data_receiver,
+            step_outputs: OutputTree::default(),
+            signed_url: None,
+            clipboard: Clipboard::default(),
+            hyperlinks: hyperlink::supports_hyperlinks(),
+            link_spots: Vec::new(),
+            record_path: None,
+            presentation: false,
+            last_run_status: None,
+            locale: Locale::default(),
+            glyphs: Glyphs::for_mode(GlyphMode::Auto),
+            interrupted: Vec::new(),
+            tours,
+            tour_run: None,
             detail_tab: 0,
             steps_scroll: 0,
             flowchart_state: FlowchartState::default(),
             sidebar_area: Rect::default(),
             detail_area: Rect::default(),
             help_bar_area: Rect::default(),
+            console_area: Rect::default(),
+            dragging: None,
+            tab_bounds: Vec::new(),
             executing_workflow_id: None,
+            executing_handle: None,
+            run_pause: None,
+            lock_wait: None,
             executing_step: None,
             completed_steps: Vec::new(),
+            run_steps: None,
             sidebar_percent: 30,
             console_height: 10,
+            layout_mode: LayoutMode::Wide,
+            drawer_open: true,
             collapsed_categories: std::collections::HashSet::new(),
-            sidebar_items: Vec::new(),
+            sidebar: SidebarLayout::default(),
             popup: None,
+            popup_queue: VecDeque::new(),
             pending_run: false,
             last_click: None,
             preflight_checker: PreflightChecker::new(),
@@ -173,10 +648,32 @@ impl TuiApp {
             assets_scroll: 0,
             selected_asset: 0,
             pending_download: None,
+            keymap,
+            pending_edit: false,
+            timeline_workflow_id: None,
+            timeline: Vec::new(),
+            variables: Vec::new(),
+            selected_variable: 0,
+            last_artifacts_dir: None,
+            last_full_output: None,
+            stalled_run: None,
+            profile_picker: None,
+            selected_profiles: std::collections::HashMap::new(),
+            history,
+            favorites: Vec::new(),
+            recent: Vec::new(),
+            marked_workflows: std::collections::BTreeSet::new(),
+            run_queue: None,
+            step_progress: None,
+            available_release: None,
+            release_receiver: None,
+            config_watcher: None,
+            config_polled_at: std::time::Instant::now(),
+            toast: None,
         };
         
-        // Build initial sidebar items
-        app.rebuild_sidebar_items();
+        // Restore layout from the previous session
+        app.apply_ui_state(UiState::load());
         
         // Initialize preflight cache for first workflow
         app.update_preflight_cache();
@@ -184,33 +681,152 @@ impl TuiApp {
         Ok(app)
     }
     
-    /// Rebuild the sidebar items based on workflows and collapsed state
+    /// Apply persisted UI state (pane sizes, collapsed groups, selection, tab)
+    fn apply_ui_state(&mut self, state: UiState) {
+        self.sidebar_percent = state.sidebar_percent;
+        self.console_height = state.console_height;
+        self.detail_tab = state.detail_tab;
+        self.collapsed_categories = state.collapsed_categories.into_iter().collect();
+        self.favorites = state.favorites;
+        self.recent = state.recent;
+        self.rebuild_sidebar_items();
+
+        let restored = state.selected_workflow.and_then(|id| self.sidebar.workflow_row(&id));
+        if let Some(idx) = restored {
+            self.list_state.select(Some(idx));
+        }
+    }
+
+    /// Show `popup`, or queue it behind the open one so neither is lost
+    fn show_popup(&mut self, popup: PopupState) {
+        if self.popup.is_some() {
+            self.popup_queue.push_back(popup);
+        } else {
+            self.popup = Some(popup);
+        }
+    }
+
+    /// Close the open popup and show the next queued one, if any
+    fn close_popup(&mut self) {
+        self.popup = self.popup_queue.pop_front();
+    }
+
+    /// Number of popups open or queued
+    fn popup_count(&self) -> usize {
+        usize::from(self.popup.is_some()) + self.popup_queue.len()
+    }
+
+    /// Drop the most recently shown popup, open or queued
+    fn discard_last_popup(&mut self) {
+        if self.popup_queue.pop_back().is_none() {
+            self.close_popup();
+        }
+    }
+
+    /// Drop every popup of `kind`, open or queued
+    fn drop_popups(&mut self, kind: &PopupKind) {
+        self.popup_queue.retain(|popup| &popup.kind != kind);
+        if self.popup.as_ref().map(|popup| &popup.kind) == Some(kind) {
+            self.close_popup();
+        }
+    }
+
+    /// Show the effective key bindings in a popup
+    fn show_keymap_help(&mut self) {
+        self.show_popup(PopupState {
+            kind: PopupKind::Message,
+            title: format!(" {} ", self.locale.text(Text::KeyBindings)),
+            message: self.keymap.help_lines().join("\n"),
+            url: None,
+            qr: None,
+        });
+    }
+
+    /// Capture the current UI state for persistence
+    fn ui_state(&self) -> UiState {
+        let mut collapsed_categories: Vec<String> =
+            self.collapsed_categories.iter().cloned().collect();
+        collapsed_categories.sort();
+
+        let selected_workflow = self
+            .list_state
+            .selected()
+            .and_then(|selected| match self.sidebar.items().get(selected) {
+                Some(SidebarItem::Workflow { index }) => {
+                    self.workflows.get(*index).map(|w| w.id.clone())
+                },
+                _ => None,
+            });
+
+        UiState {
+            sidebar_percent: self.sidebar_percent,
+            console_height: self.console_height,
+            collapsed_categories,
+            selected_workflow,
+            detail_tab: self.detail_tab,
+            favorites: self.favorites.clone(),
+            recent: self.recent.clone(),
+        }
+    }
+
+    /// Regroup the sidebar after the workflows, favorites or recent runs
+    /// changed, keeping the selected row selected
     fn rebuild_sidebar_items(&mut self) {
         use std::collections::BTreeMap;
         
-        // Group workflows by category
-        let mut categories: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        // Group workflows by category, ordered by the category's sort order
+        let registry = self.engine.categories();
+        let mut grouped: BTreeMap<(i32, String), (Color, Vec<usize>)> = BTreeMap::new();
         for (i, w) in self.workflows.iter().enumerate() {
-            let cat_name = format!("{}", w.category);
-            categories.entry(cat_name).or_default().push(i);
+            let info = registry.info(&w.category);
+            let color = info
+                .color
+                .as_deref()
+                .and_then(|c| c.parse::<Color>().ok())
+                .unwrap_or(Color::Cyan);
+            grouped
+                .entry((info.sort_order, info.display_name))
+                .or_insert_with(|| (color, Vec::new()))
+                .1
+                .push(i);
         }
+        let categories = grouped
+            .into_iter()
+            .map(|((_, name), (color, indices))| (name, color, indices));
         
-        // Build sidebar items
-        self.sidebar_items.clear();
-        for (cat_name, indices) in categories {
-            // Add category header
-            self.sidebar_items.push(SidebarItem::Category { 
-                name: cat_name.clone(), 
-                count: indices.len() 
+        // Tours, Favorites and Recent come first
+        let index_of = |id: &String| self.workflows.iter().position(|w| &w.id == id);
+        let pinned = [
+            (FAVORITES_CATEGORY, self.favorites.iter().filter_map(index_of).collect::<Vec<_>>()),
+            (RECENT_CATEGORY, self.recent.iter().filter_map(index_of).collect()),
+        ];
+        let workflow_groups = pinned
+            .into_iter()
+            .map(|(name, indices)| (name.to_string(), Color::Cyan, indices))
+            .filter(|(_, _, indices)| !indices.is_empty())
+            .chain(categories)
+            .map(|(name, color, indices)| SidebarGroup {
+                name,
+                color,
+                entries: indices
+                    .into_iter()
+                    .map(|index| (self.workflows[index].id.clone(), SidebarItem::Workflow { index }))
+                    .collect(),
             });
-            
-            // Add workflows if not collapsed
-            if !self.collapsed_categories.contains(&cat_name) {
-                for idx in indices {
-                    self.sidebar_items.push(SidebarItem::Workflow { index: idx });
-                }
-            }
-        }
+        let tours = (!self.tours.is_empty()).then(|| SidebarGroup {
+            name: TOURS_CATEGORY.to_string(),
+            color: Color::LightMagenta,
+            entries: self
+                .tours
+                .iter()
+                .enumerate()
+                .map(|(index, tour)| (tour.name.clone(), SidebarItem::Tour { index }))
+                .collect(),
+        });
+        let groups = tours.into_iter().chain(workflow_groups).collect();
+
+        let selected = self.sidebar.set_groups(groups, &self.collapsed_categories, self.list_state.selected());
+        self.list_state.select(selected);
     }
 
     /// Run the TUI application main loop
@@ -218,184 +834,95 @@ impl TuiApp {
         tracing::info!("Starting TUI main loop");
 
         // Create terminal guard to ensure cleanup on panic/error
-        let _guard = TerminalGuard;
+        let _guard = TerminalGuard::new();
 
         // Set up terminal
         enable_raw_mode()?;
         let mut stdout = io::stdout();
         execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-        let backend = CrosstermBackend::new(stdout);
-        let mut terminal = Terminal::new(backend)?;
+        let writer = match &self.record_path {
+            Some(path) => {
+                let (width, height) = crossterm::terminal::size()?;
+                let cast = CastWriter::create(path, width, height, Some("RAPS Demo Workflows"))?;
+                self.logs.push(format!("Recording session to {}", path.display()));
+                SessionRecorder::recording(stdout, cast)
+            },
+            None => SessionRecorder::passthrough(stdout),
+        };
+        let backend = CrosstermBackend::new(writer);
+        let mut terminal: AppTerminal = Terminal::new(backend)?;
 
-        // Move receiver out of self to avoid borrow conflicts in select!
-        let mut receiver =
-            std::mem::replace(&mut self.update_receiver, mpsc::unbounded_channel().1);
+        let interrupt = InterruptSignal::install();
+        let mut events = EventStream::new();
+        let mut tick = tokio::time::interval(TICK_INTERVAL);
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
         // Main event loop
         loop {
+            if interrupt.take() {
+                self.handle_interrupt().await;
+            }
             if self.should_quit {
                 break;
             }
 
             terminal.draw(|f| self.draw(f))?;
-
-            // Poll for events with timeout - simple synchronous approach
-            // This avoids race conditions with spawn_blocking
-            if event::poll(Duration::from_millis(50))? {
-                match event::read()? {
-                    Event::Key(key) => {
-                        // Only handle key press events, not release or repeat
-                        // This is important on Windows where key events include Press/Release/Repeat
-                        if key.kind == KeyEventKind::Press {
-                            // Handle popup keys first
-                            if self.popup.is_some() {
-                                match key.code {
-                                    KeyCode::Char('o') | KeyCode::Char('O') => {
-                                        // Open URL in browser
-                                        if let Some(ref popup) = self.popup {
-                                            if let Some(ref url) = popup.url {
-                                                let _ = open::that(url);
-                                            }
-                                        }
-                                        self.popup = None;
-                                    }
-                                    _ => {
-                                        // Any other key closes the popup
-                                        self.popup = None;
-                                    }
-                                }
-                                continue;
-                            }
-                            
-                            match key.code {
-                                KeyCode::Char('q') => self.should_quit = true,
-                                KeyCode::Up | KeyCode::Char('k') => {
-                                    if (self.detail_tab == 1 || self.detail_tab == 4) && self.steps_scroll > 0 {
-                                        self.steps_scroll -= 1;
-                                    } else if self.detail_tab == 2 {
-                                        self.flowchart_state.scroll_up(1);
-                                    } else if self.detail_tab == 3 {
-                                        // Navigate assets list
-                                        if self.selected_asset > 0 {
-                                            self.selected_asset -= 1;
-                                        }
-                                    } else if self.detail_tab == 0 {
-                                        self.previous_workflow();
-                                        self.update_preflight_cache();
-                                    }
-                                }
-                                KeyCode::Down | KeyCode::Char('j') => {
-                                    if self.detail_tab == 1 || self.detail_tab == 4 {
-                                        self.steps_scroll += 1;
-                                    } else if self.detail_tab == 2 {
-                                        self.flowchart_state.scroll_down(1);
-                                    } else if self.detail_tab == 3 {
-                                        // Navigate assets list
-                                        let assets_count = self.preflight_checker.get_all_assets_with_status().len();
-                                        if self.selected_asset < assets_count.saturating_sub(1) {
-                                            self.selected_asset += 1;
-                                        }
-                                    } else if self.detail_tab == 0 {
-                                        self.next_workflow();
-                                        self.update_preflight_cache();
-                                    }
-                                }
-                                KeyCode::Left | KeyCode::Char('h') => {
-                                    if self.detail_tab > 0 {
-                                        self.detail_tab -= 1;
-                                    }
-                                }
-                                KeyCode::Right | KeyCode::Char('l') => {
-                                    if self.detail_tab < 4 {
-                                        self.detail_tab += 1;
-                                    }
-                                }
-                                KeyCode::Tab => {
-                                    self.detail_tab = (self.detail_tab + 1) % 5;
-                                    self.steps_scroll = 0;
-                                    self.flowchart_state.reset();
-                                }
-                                KeyCode::Enter => self.run_selected_workflow().await?,
-                                KeyCode::Char('1') => { self.detail_tab = 0; self.steps_scroll = 0; self.flowchart_state.reset(); }
-                                KeyCode::Char('2') => { self.detail_tab = 1; self.steps_scroll = 0; }
-                                KeyCode::Char('3') => { self.detail_tab = 2; self.flowchart_state.reset(); }
-                                KeyCode::Char('4') => { self.detail_tab = 3; self.assets_scroll = 0; }
-                                KeyCode::Char('5') => { self.detail_tab = 4; self.steps_scroll = 0; }
-                                KeyCode::Char('d') | KeyCode::Char('D') => {
-                                    // Download selected asset if in Assets tab
-                                    if self.detail_tab == 3 {
-                                        self.pending_download = Some(self.selected_asset);
-                                    }
-                                }
-                                KeyCode::PageUp => {
-                                    if self.detail_tab == 1 || self.detail_tab == 4 { self.steps_scroll = self.steps_scroll.saturating_sub(5); }
-                                    else if self.detail_tab == 2 { self.flowchart_state.scroll_up(5); }
-                                    else if self.detail_tab == 3 { self.selected_asset = self.selected_asset.saturating_sub(5); }
-                                }
-                                KeyCode::PageDown => {
-                                    if self.detail_tab == 1 || self.detail_tab == 4 { self.steps_scroll += 5; }
-                                    else if self.detail_tab == 2 { self.flowchart_state.scroll_down(5); }
-                                    else if self.detail_tab == 3 {
-                                        let assets_count = self.preflight_checker.get_all_assets_with_status().len();
-                                        self.selected_asset = (self.selected_asset + 5).min(assets_count.saturating_sub(1));
-                                    }
-                                }
-                                KeyCode::Home => {
-                                    self.steps_scroll = 0;
-                                    self.assets_scroll = 0;
-                                    self.selected_asset = 0;
-                                    self.flowchart_state.reset();
-                                }
-                                // Resize panels with [ ] for sidebar, { } for console
-                                KeyCode::Char('[') => {
-                                    if self.sidebar_percent > 15 {
-                                        self.sidebar_percent -= 5;
-                                    }
-                                }
-                                KeyCode::Char(']') => {
-                                    if self.sidebar_percent < 60 {
-                                        self.sidebar_percent += 5;
-                                    }
-                                }
-                                KeyCode::Char('-') => {
-                                    if self.console_height > 5 {
-                                        self.console_height -= 2;
-                                    }
-                                }
-                                KeyCode::Char('+') | KeyCode::Char('=') => {
-                                    if self.console_height < 25 {
-                                        self.console_height += 2;
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-                    Event::Mouse(mouse) => {
-                        self.handle_mouse_event(mouse);
-                        // Handle pending run triggered by mouse click
-                        if self.pending_run {
-                            self.pending_run = false;
-                            self.run_selected_workflow().await?;
-                        }
-                    }
-                    _ => {}
-                }
+            if self.hyperlinks && !self.link_spots.is_empty() {
+                hyperlink::write_links(terminal.backend_mut(), &self.link_spots)?;
             }
-            
-            // Handle pending asset download
-            if let Some(asset_idx) = self.pending_download.take() {
-                self.download_asset(asset_idx);
+
+            // Sleep until there is something to show: input, an execution
+            // update, a background result, or the tick for timers and countdowns
+            let event = tokio::select! {
+                event = events.next() => match event {
+                    Some(event) => Some(event?),
+                    None => break,
+                },
+                Some(update) = self.update_receiver.recv() => {
+                    self.handle_execution_update(update);
+                    None
+                },
+                Some(preview) = self.preview_receiver.recv() => {
+                    self.result_preview = Some(preview);
+                    None
+                },
+                Some((dir, downloads)) = self.download_receiver.recv() => {
+                    self.derivatives_downloaded(dir, downloads);
+                    None
+                },
+                Some((parent, result)) = self.data_receiver.recv() => {
+                    self.data_browser.apply(&parent, result);
+                    None
+                },
+                Some((target, result)) = self.da_receiver.recv() => {
+                    self.da_inspector.apply(target, result);
+                    None
+                },
+                Some((outcome, info)) = self.auth_receiver.recv() => {
+                    self.auth_received(outcome, info);
+                    None
+                },
+                _ = tick.tick() => None,
+            };
+            if let Some(event) = event {
+                self.handle_event(event, &interrupt).await?;
             }
 
-            // Check for execution updates (non-blocking)
-            while let Ok(update) = receiver.try_recv() {
-                self.handle_execution_update(update);
+            // Handle pending edit (needs the terminal to suspend the UI)
+            if std::mem::take(&mut self.pending_edit) {
+                // A waiting event stream keeps reading the terminal and would take the editor's keys
+                drop(events);
+                self.edit_selected_workflow(&mut terminal)?;
+                events = EventStream::new();
             }
+
+            self.process_pending().await?;
         }
 
-        // Put receiver back
-        self.update_receiver = receiver;
+        // Remember layout for next session
+        if let Err(e) = self.ui_state().save() {
+            tracing::warn!("Failed to save UI state: {:#}", e);
+        }
 
         // Restore terminal
         disable_raw_mode()?;
@@ -409,116 +936,812 @@ impl TuiApp {
         Ok(())
     }
 
-    /// Handle an update from the execution engine
-    fn handle_execution_update(&mut self, update: ExecutionUpdate) {
-        match update {
-            ExecutionUpdate::Started { workflow_id, .. } => {
-                self.executing_workflow_id = Some(workflow_id.clone());
-                self.executing_step = Some(0);
-                self.completed_steps.clear();
-                self.logs
-                    .push(format!(">>> Started workflow: {}", workflow_id));
-            },
-            ExecutionUpdate::StepStarted { step, .. } => {
-                // Find step index by matching step id with workflow definition
-                if let Some(ref wf_id) = self.executing_workflow_id {
-                    if let Some(def) = self.workflow_definitions.get(wf_id) {
-                        if let Some(idx) = def.steps.iter().position(|s| s.id == step.id) {
-                            self.executing_step = Some(idx);
+    /// Apply a key press or mouse event
+    async fn handle_event(&mut self, event: Event, interrupt: &InterruptSignal) -> Result<()> {
+        match event {
+            // Only handle key press events, not release or repeat
+            // This is important on Windows where key events include Press/Release/Repeat
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                // Raw mode turns Ctrl-C into a key press instead of a signal
+                if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                    interrupt.raise();
+                    return Ok(());
+                }
+
+                // Handle popup keys first, as the open popup's kind says
+                match self.popup.as_ref().map(|popup| popup.kind.clone()) {
+                    Some(PopupKind::Tour) => {
+                        self.handle_tour_popup_key(key.code);
+                        return Ok(());
+                    },
+                    Some(PopupKind::Decision(handle)) => {
+                        self.handle_decision_key(handle, key.code).await;
+                        return Ok(());
+                    },
+                    Some(PopupKind::Profiles) => {
+                        self.handle_profile_key(key.code);
+                        return Ok(());
+                    },
+                    Some(PopupKind::Derivatives) => {
+                        self.handle_derivative_key(key.code);
+                        return Ok(());
+                    },
+                    Some(PopupKind::Auth) => {
+                        self.handle_auth_key(key.code);
+                        return Ok(());
+                    },
+                    Some(PopupKind::Message) | None => {},
+                }
+                if self.popup.is_some() {
+                    if self.keymap.action_for(&key) == Some(Action::Copy) {
+                        // Copy the URL (or message) and keep the popup open
+                        if let Some(text) = self.popup.as_ref().map(|p| p.url.clone().unwrap_or_else(|| p.message.clone())) {
+                            self.copy_to_clipboard(&text);
                         }
+                        return Ok(());
                     }
-                }
-                self.logs.push(format!("  > Step: {}", step.name));
-            },
-            ExecutionUpdate::StepCompleted { result, .. } => {
-                // Find step index by step_id
-                let step_idx = if let Some(ref wf_id) = self.executing_workflow_id {
-                    if let Some(def) = self.workflow_definitions.get(wf_id) {
-                        def.steps.iter().position(|s| s.id == result.step_id)
-                    } else {
-                        None
+                    match key.code {
+                        KeyCode::Char('o') | KeyCode::Char('O') => {
+                            // Open URL in browser
+                            if let Some(ref popup) = self.popup {
+                                if let Some(ref url) = popup.url {
+                                    let _ = open::that(url);
+                                }
+                            }
+                            self.update(Msg::ClosePopup);
+                        }
+                        _ => {
+                            // Any other key closes the popup
+                            self.update(Msg::ClosePopup);
+                        }
                     }
-                } else {
-                    None
-                };
-                
-                if let Some(idx) = step_idx {
-                    self.completed_steps.push(idx);
+                    return Ok(());
                 }
                 
-                if result.status == ExecutionStatus::Completed {
-                    self.logs
-                        .push(format!("  [OK] Step '{}' finished", result.step_id));
-                    // Show stdout if available
-                    if !result.stdout.is_empty() {
-                        // Try to format as JSON
-                        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&result.stdout) {
-                            if let Ok(pretty) = serde_json::to_string_pretty(&json) {
-                                for line in pretty.lines().take(10) {
-                                    self.logs.push(format!("      {}", line));
-                                }
-                                if pretty.lines().count() > 10 {
-                                    self.logs.push("      ... (truncated)".to_string());
-                                }
+                let Some(action) = self.keymap.action_for(&key) else {
+                    return Ok(());
+                };
+
+                match action {
+                    Action::Quit => self.should_quit = true,
+                    Action::Help => self.show_keymap_help(),
+                    Action::Up => {
+                        if self.results_tree_focused() {
+                            self.step_outputs.move_selection(-1);
+                        } else if self.data_focused() {
+                            self.data_browser.move_selection(-1);
+                        } else if self.da_focused() {
+                            self.da_inspector.move_selection(-1);
+                        } else if self.variables_focused() {
+                            self.selected_variable = self.selected_variable.saturating_sub(1);
+                        } else if matches!(self.detail_tab, 1 | 4 | 5 | 6 | 7) && self.steps_scroll > 0 {
+                            self.steps_scroll -= 1;
+                        } else if self.detail_tab == 2 {
+                            self.flowchart_state.scroll_up(1);
+                        } else if self.detail_tab == 3 {
+                            // Navigate assets list
+                            if self.selected_asset > 0 {
+                                self.selected_asset -= 1;
                             }
-                        } else {
-                            // Plain text output
-                            for line in result.stdout.lines().take(5) {
-                                self.logs.push(format!("      {}", line));
+                        } else if self.detail_tab == 0 {
+                            self.previous_item();
+                            self.update_preflight_cache();
+                        }
+                    }
+                    Action::Down => {
+                        if self.results_tree_focused() {
+                            self.step_outputs.move_selection(1);
+                        } else if self.data_focused() {
+                            self.data_browser.move_selection(1);
+                        } else if self.da_focused() {
+                            self.da_inspector.move_selection(1);
+                        } else if self.variables_focused() {
+                            self.selected_variable = (self.selected_variable + 1).min(self.variables.len() - 1);
+                        } else if matches!(self.detail_tab, 1 | 4 | 5 | 6 | 7) {
+                            self.steps_scroll += 1;
+                        } else if self.detail_tab == 2 {
+                            self.flowchart_state.scroll_down(1);
+                        } else if self.detail_tab == 3 {
+                            // Navigate assets list
+                            let assets_count = self.preflight_checker.get_all_assets_with_status().len();
+                            if self.selected_asset < assets_count.saturating_sub(1) {
+                                self.selected_asset += 1;
                             }
+                        } else if self.detail_tab == 0 {
+                            self.next_item();
+                            self.update_preflight_cache();
                         }
                     }
-                } else {
-                    self.logs
-                        .push(format!("  [FAIL] Step '{}' failed", result.step_id));
-                    if !result.stderr.is_empty() {
-                        for line in result.stderr.lines().take(3) {
-                            self.logs.push(format!("      ERR: {}", line));
+                    // On a category header, Left/Right collapse/expand
+                    // and Enter/Space toggle it
+                    Action::PrevTab | Action::NextTab | Action::Run | Action::ToggleMark
+                        if self.selected_category().is_some() =>
+                    {
+                        if let Some(name) = self.selected_category() {
+                            match action {
+                                Action::PrevTab => self.set_category_collapsed(&name, true),
+                                Action::NextTab => self.set_category_collapsed(&name, false),
+                                _ => self.toggle_category(&name),
+                            }
                         }
                     }
-                }
-            },
-            ExecutionUpdate::Completed { result, .. } => {
-                let wf_id = result.workflow_id.clone();
-                self.executing_workflow_id = None;
-                self.executing_step = None;
-                let status = if result.success {
-                    "COMPLETED"
-                } else {
-                    "FAILED"
-                };
-                self.logs.push(format!(
-                    "=== Workflow {} {} ({} steps) ===",
-                    result.workflow_id, status, result.steps_completed
-                ));
-                
-                // Show popup with viewer URL for translation workflows
-                if result.success {
-                    // Check if this is a model derivative workflow
-                    if wf_id.contains("translate") || wf_id.contains("derivative") || wf_id.contains("svf") {
-                        self.popup = Some(PopupState {
-                            title: " Workflow Complete ".to_string(),
-                            message: format!("Model translation '{}' completed successfully!", wf_id),
-                            url: Some("https://aps.autodesk.com/viewer".to_string()),
-                        });
-                    } else {
-                        self.popup = Some(PopupState {
-                            title: " Workflow Complete ".to_string(),
-                            message: format!("Workflow '{}' completed successfully!", wf_id),
-                            url: None,
-                        });
+                    // In the Results tab, Enter/Space collapse/expand the selected node
+                    Action::Run | Action::ToggleMark if self.results_tree_focused() => {
+                        self.step_outputs.toggle_selected();
                     }
-                }
-            },
-            ExecutionUpdate::Failed { error, .. } => {
-                self.executing_workflow_id = None;
-                self.executing_step = None;
-                self.logs.push(format!("!!! Error: {}", error.message));
+                    // In the Data tab, they expand a node, listing its children the first time
+                    Action::Run | Action::ToggleMark if self.data_focused() => {
+                        if let Some(request) = self.data_browser.toggle_selected() {
+                            self.request_data(request);
+                        }
+                    }
+                    // In the DA tab, they show an entry's definition
+                    Action::Run | Action::ToggleMark if self.da_focused() => {
+                        if let Some(request) = self.da_inspector.toggle_selected() {
+                            spawn_listing(self.raps_client(), self.da_sender.clone(), request.target, request.command);
+                        }
+                    }
+                    Action::PrevTab if self.detail_tab > 0 => {
+                        self.detail_tab -= 1;
+                    }
+                    Action::NextTab if self.detail_tab < DETAIL_TAB_COUNT - 1 => {
+                        self.detail_tab += 1;
+                    }
+                    Action::CycleTab => self.update(Msg::SelectTab((self.detail_tab + 1) % DETAIL_TAB_COUNT)),
+                    Action::Run => self.run_selected_workflow().await?,
+                    Action::ToggleFavorite => self.toggle_favorite(),
+                    Action::ToggleMark => self.toggle_mark(),
+                    Action::ClearMarks => self.marked_workflows.clear(),
+                    Action::BulkRun => self.start_bulk_run(),
+                    Action::BulkValidate => self.validate_marked(),
+                    Action::BulkExport => self.export_marked(),
+                    Action::BulkCost => self.estimate_marked(),
+                    Action::TabOverview => self.update(Msg::SelectTab(0)),
+                    Action::TabSteps => self.update(Msg::SelectTab(1)),
+                    Action::TabFlowchart => self.update(Msg::SelectTab(2)),
+                    Action::TabAssets => self.update(Msg::SelectTab(3)),
+                    Action::TabYaml => self.update(Msg::SelectTab(4)),
+                    Action::TabTimeline => self.update(Msg::SelectTab(5)),
+                    Action::TabVariables => self.update(Msg::SelectTab(6)),
+                    Action::TabResults => self.update(Msg::SelectTab(7)),
+                    Action::TabData => self.update(Msg::SelectTab(8)),
+                    Action::TabDesignAutomation => self.update(Msg::SelectTab(9)),
+                    // Download selected asset if in Assets tab
+                    Action::Download if self.detail_tab == 3 => {
+                        self.pending_download = Some(self.selected_asset);
+                    }
+                    Action::Edit => self.pending_edit = true,
+                    Action::FlowchartZoom if self.detail_tab == 2 => {
+                        self.flowchart_state.toggle_zoom();
+                    }
+                    Action::PanLeft if self.detail_tab == 2 => self.flowchart_state.pan_left(4),
+                    Action::PanRight if self.detail_tab == 2 => self.flowchart_state.pan_right(4),
+                    Action::Export if self.detail_tab == 2 => self.export_flowchart(),
+                    Action::OpenArtifacts => self.open_artifacts(),
+                    Action::ViewFullOutput => self.view_full_output(),
+                    Action::PauseResume => self.toggle_pause().await,
+                    Action::RunFromStep if self.detail_tab == 1 => self.run_from_top_step().await,
+                    Action::PickProfile => self.open_profile_picker(),
+                    Action::BrowseDerivatives => self.open_derivative_picker(),
+                    Action::ShowAuth => self.open_auth_panel(),
+                    Action::KillStalled => self.kill_stalled_run(false).await,
+                    Action::FailStalled => self.kill_stalled_run(true).await,
+                    Action::Copy if self.data_focused() => self.use_data_params(),
+                    Action::Copy if self.da_focused() => self.use_da_param(),
+                    Action::Copy => self.copy_selection(),
+                    Action::ShowQrCode => {
+                        let selected = if self.results_tree_focused() {
+                            self.step_outputs.selected_copy_text()
+                        } else if self.variables_focused() {
+                            self.variables.get(self.selected_variable).map(|v| v.value.clone())
+                        } else {
+                            None
+                        };
+                        match selected.filter(|t| t.starts_with("http")).or_else(|| self.signed_url.clone()) {
+                            Some(url) => self.show_qr_code(&url),
+                            None => self.logs.push("No URL to show as a QR code".to_string()),
+                        }
+                    }
+                    Action::Presentation => {
+                        self.presentation = !self.presentation;
+                        self.flowchart_state.reset();
+                    }
+                    Action::CopyConsole => {
+                        let text = self.visible_logs().join("\n");
+                        self.copy_to_clipboard(&text);
+                    }
+                    Action::PageUp => {
+                        if self.results_tree_focused() { self.step_outputs.move_selection(-5); }
+                        else if self.variables_focused() { self.selected_variable = self.selected_variable.saturating_sub(5); }
+                        else if matches!(self.detail_tab, 1 | 4 | 5 | 6 | 7) { self.steps_scroll = self.steps_scroll.saturating_sub(5); }
+                        else if self.detail_tab == 2 { self.flowchart_state.scroll_up(5); }
+                        else if self.detail_tab == 3 { self.selected_asset = self.selected_asset.saturating_sub(5); }
+                    }
+                    Action::PageDown => {
+                        if self.results_tree_focused() { self.step_outputs.move_selection(5); }
+                        else if self.variables_focused() { self.selected_variable = (self.selected_variable + 5).min(self.variables.len() - 1); }
+                        else if matches!(self.detail_tab, 1 | 4 | 5 | 6 | 7) { self.steps_scroll += 5; }
+                        else if self.detail_tab == 2 { self.flowchart_state.scroll_down(5); }
+                        else if self.detail_tab == 3 {
+                            let assets_count = self.preflight_checker.get_all_assets_with_status().len();
+                            self.selected_asset = (self.selected_asset + 5).min(assets_count.saturating_sub(1));
+                        }
+                    }
+                    Action::Home => {
+                        self.steps_scroll = 0;
+                        self.assets_scroll = 0;
+                        self.selected_asset = 0;
+                        self.flowchart_state.reset();
+                    }
+                    // Resize panels (sidebar width, console height)
+                    Action::SidebarShrink => self.update(Msg::ResizeSidebar(self.sidebar_percent.saturating_sub(5))),
+                    Action::SidebarGrow => self.update(Msg::ResizeSidebar(self.sidebar_percent + 5)),
+                    Action::ToggleSidebar => self.update(Msg::ToggleDrawer),
+                    Action::ConsoleShrink => self.update(Msg::ResizeConsole(self.console_height.saturating_sub(2))),
+                    Action::ConsoleGrow => self.update(Msg::ResizeConsole(self.console_height + 2)),
+                    _ => {}
+                }
+            }
+            Event::Mouse(mouse) => {
+                self.handle_mouse_event(mouse);
+                // Handle pending run triggered by mouse click
+                if self.pending_run {
+                    self.pending_run = false;
+                    self.run_selected_workflow().await?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Work queued by input or finished in the background: downloads, results
+    /// of background tasks, the auth countdown and the next queued run
+    async fn process_pending(&mut self) -> Result<()> {
+        // Handle pending asset download
+        if let Some(asset_idx) = self.pending_download.take() {
+            self.download_asset(asset_idx);
+        }
+
+        // Check for execution updates (non-blocking)
+        while let Some(update) = self.update_receiver.try_recv() {
+            self.handle_execution_update(update);
+        }
+
+        // Pick up fetched result previews
+        while let Ok(preview) = self.preview_receiver.try_recv() {
+            self.result_preview = Some(preview);
+        }
+        while let Ok((dir, downloads)) = self.download_receiver.try_recv() {
+            self.derivatives_downloaded(dir, downloads);
+        }
+        while let Ok((asset, result)) = self.asset_receiver.try_recv() {
+            self.asset_downloaded(asset, result);
+        }
+
+        // List the hubs the first time the Data tab is shown
+        if self.detail_tab == 8 {
+            if let Some(request) = self.data_browser.root_request() {
+                self.request_data(request);
+            }
+        }
+        while let Ok((parent, result)) = self.data_receiver.try_recv() {
+            self.data_browser.apply(&parent, result);
+        }
+        if self.detail_tab == 9 {
+            for request in self.da_inspector.list_requests() {
+                spawn_listing(self.raps_client(), self.da_sender.clone(), request.target, request.command);
+            }
+        }
+        while let Ok((target, result)) = self.da_receiver.try_recv() {
+            self.da_inspector.apply(target, result);
+        }
+
+        if let Some(Ok(release)) = self.release_receiver.as_mut().map(|r| r.try_recv()) {
+            self.logs.push(format!(
+                "Version {} is available (running {}): run raps-demo self-update, or see {}",
+                release.version,
+                update::Version::current(),
+                release.url
+            ));
+            self.available_release = Some(release);
+            self.release_receiver = None;
+        }
+
+        self.poll_config().await;
+
+        // Keep the auth panel's expiry countdown ticking
+        while let Ok((outcome, info)) = self.auth_receiver.try_recv() {
+            self.auth_received(outcome, info);
+        }
+        let auth_popup = self
+            .popup
+            .iter_mut()
+            .chain(self.popup_queue.iter_mut())
+            .find(|popup| popup.kind == PopupKind::Auth);
+        if let (Some(info), Some(popup)) = (&self.auth_panel, auth_popup) {
+            popup.message = info.message(chrono::Utc::now());
+        }
+
+        // Start the next queued workflow once the previous one finished
+        self.advance_run_queue().await?;
+        self.advance_tour().await?;
+        Ok(())
+    }
+
+    /// Reload the configuration if its files were edited, and say what changed
+    async fn poll_config(&mut self) {
+        if self.config_polled_at.elapsed() < CONFIG_POLL_INTERVAL {
+            return;
+        }
+        self.config_polled_at = std::time::Instant::now();
+        let Some(watcher) = self.config_watcher.as_mut() else {
+            return;
+        };
+        let changes = match watcher.poll().await {
+            None => return,
+            Some(Ok(changes)) => changes,
+            Some(Err(e)) => {
+                self.logs.push(format!("Configuration not reloaded, keeping the previous one: {:#}", e));
+                let title = format!(" {} ", self.locale.text(Text::Configuration));
+                let line = self.locale.text(Text::ConfigurationNotReloaded).to_string();
+                self.show_toast(&title, vec![line]);
+                return;
+            },
+        };
+        if changes.is_empty() {
+            return;
+        }
+        // Only the edited settings, so command-line overrides (`--seed`,
+        // `--chaos`, `--env`, `--keep-temp-dirs`, ...) stay
+        let config = changes.apply_settings(self.engine.config(), watcher.manager().demo_config());
+        let profiles = watcher.manager().profile_environments();
+
+        // Settings the TUI itself uses, only when edited so command-line
+        // choices (e.g. `--locale`) stay too
+        if changes.settings.contains(&"locale") {
+            self.locale = config.locale;
+            self.preflight_checker = std::mem::take(&mut self.preflight_checker).with_locale(config.locale);
+            self.update_preflight_cache();
+        }
+        if changes.settings.contains(&"glyphs") {
+            self.glyphs = Glyphs::for_mode(config.glyphs);
+        }
+        if changes.settings.contains(&"console_log_lines") {
+            self.logs.set_capacity(config.console_log_lines);
+        }
+        self.engine.reconfigure(config, profiles);
+
+        let summary = changes.summary();
+        for line in &summary {
+            self.logs.push(format!("Configuration reloaded: {}", line));
+        }
+        let title = format!(" {} ", self.locale.text(Text::ConfigurationReloaded));
+        self.show_toast(&title, summary);
+    }
+
+    /// Show a notice in the top right corner for a few seconds
+    fn show_toast(&mut self, title: &str, lines: Vec<String>) {
+        let view = ToastView {
+            title: title.to_string(),
+            lines,
+        };
+        self.toast = Some((view, std::time::Instant::now()));
+    }
+
+    /// Apply a change requested by input on a view
+    fn update(&mut self, message: Msg) {
+        match message {
+            Msg::SelectRow(row) => {
+                self.list_state.select(Some(row));
+                self.update_preflight_cache();
+                self.steps_scroll = 0;
+                self.flowchart_state.reset();
+            },
+            Msg::ToggleCategory(name) => self.toggle_category(&name),
+            Msg::RunSelected => self.pending_run = true,
+            Msg::SelectTab(tab) => {
+                self.detail_tab = tab.min(DETAIL_TAB_COUNT - 1);
+                self.steps_scroll = 0;
+                self.assets_scroll = 0;
+                self.flowchart_state.reset();
+            },
+            Msg::ClosePopup => self.close_popup(),
+            Msg::ToggleDrawer => self.drawer_open = !self.drawer_open,
+            Msg::ResizeSidebar(percent) => {
+                self.sidebar_percent = percent.clamp(SIDEBAR_PERCENT_RANGE.0, SIDEBAR_PERCENT_RANGE.1);
+            },
+            Msg::ResizeConsole(height) => {
+                self.console_height = height.clamp(CONSOLE_HEIGHT_RANGE.0, CONSOLE_HEIGHT_RANGE.1);
+            },
+        }
+    }
+
+    /// Apply the result of a login, logout or token refresh
+    fn auth_received(&mut self, outcome: Option<String>, info: AuthInfo) {
+        if let Some(outcome) = outcome {
+            self.logs.push(format!("    {}", outcome));
+            // Preflight shows whether authentication is available
+            self.update_preflight_cache();
+        }
+        if self.auth_panel.is_some() {
+            self.auth_panel = Some(info);
+        }
+    }
+
+    /// Handle an update from the execution engine
+    fn handle_execution_update(&mut self, update: ExecutionUpdate) {
+        if self.stalled_run.as_ref() == Some(update.handle()) && !matches!(update, ExecutionUpdate::Stalled { .. }) {
+            self.stalled_run = None;
+        }
+        // The run holds its locks once it moves on (or ends)
+        if self.executing_handle.as_ref() == Some(update.handle())
+            && !matches!(
+                update,
+                ExecutionUpdate::WaitingForLock { .. } | ExecutionUpdate::Started { .. } | ExecutionUpdate::VariablesUpdated { .. }
+            )
+        {
+            self.lock_wait = None;
+        }
+        if self.executing_handle.as_ref() == Some(update.handle())
+            && matches!(
+                update,
+                ExecutionUpdate::Completed { .. } | ExecutionUpdate::Failed { .. } | ExecutionUpdate::Cancelled { .. }
+            )
+        {
+            self.executing_handle = None;
+            self.run_pause = None;
+        }
+        if matches!(
+            update,
+            ExecutionUpdate::Completed { .. } | ExecutionUpdate::Failed { .. } | ExecutionUpdate::Cancelled { .. }
+        ) {
+            // The run no longer waits for a decision still on screen or queued
+            self.drop_popups(&PopupKind::Decision(update.handle().clone()));
+        }
+        match update {
+            ExecutionUpdate::Paused { next_step, .. } => {
+                self.run_pause = Some(RunPause::Paused);
+                self.step_progress = None;
+                self.logs.push(format!(
+                    "  [PAUSED] Before step '{}' (press {} to resume)",
+                    next_step.name,
+                    self.keymap.keys_label(Action::PauseResume)
+                ));
+            },
+            ExecutionUpdate::Resumed { .. } => {
+                self.run_pause = None;
+                self.logs.push("  [RESUMED]".to_string());
+            },
+            ExecutionUpdate::StepFailed { handle, step, error } => {
+                self.step_progress = None;
+                self.close_running_timeline_entries(ExecutionStatus::Failed);
+                self.logs.push(format!("  [FAIL] Step '{}' failed: {}", step.id, error.message));
+                let mut message = error.message.clone();
+                for suggestion in &error.recovery_suggestions {
+                    message.push_str(&format!("\n  - {}", suggestion));
+                }
+                message.push_str("\n\n[r] Retry   [s] Skip and continue   [a] Abort");
+                self.show_popup(PopupState {
+                    kind: PopupKind::Decision(handle),
+                    title: format!(" Step '{}' failed ", step.name),
+                    message,
+                    url: None,
+                    qr: None,
+                });
+            },
+            ExecutionUpdate::WaitingForLock { handle, wait } => {
+                self.logs.push(format!(
+                    "  [LOCK] {} waits for lock '{}' held by {}",
+                    handle.workflow_id, wait.lock, wait.holder
+                ));
+                if self.executing_handle.as_ref() == Some(&handle) {
+                    self.lock_wait = Some(wait);
+                }
+            },
+            ExecutionUpdate::StepOutput { line, .. } => {
+                self.logs.push(format!("      {}", line));
+            },
+            ExecutionUpdate::Stalled { handle, report } => {
+                self.logs.push(format!("  [STALLED] {}: {}", handle.workflow_id, report.summary()));
+                self.logs.push(format!(
+                    "      Press {} to kill the command or {} to mark the run failed",
+                    self.keymap.keys_label(Action::KillStalled),
+                    self.keymap.keys_label(Action::FailStalled)
+                ));
+                self.stalled_run = Some(handle);
+            },
+            ExecutionUpdate::Started { handle, workflow_id } => {
+                self.executing_handle = Some(handle);
+                self.run_pause = None;
+                self.executing_workflow_id = Some(workflow_id.clone());
+                self.executing_step = Some(0);
+                self.completed_steps.clear();
+                push_recent(&mut self.recent, &workflow_id);
+                self.rebuild_sidebar_items();
+                self.timeline_workflow_id = Some(workflow_id.clone());
+                self.timeline.clear();
+                self.variables.clear();
+                self.selected_variable = 0;
+                self.step_outputs.reset(&workflow_id);
+                self.signed_url = None;
+                self.last_run_status = None;
+                self.logs
+                    .push(format!(">>> Started workflow: {}", workflow_id));
+            },
+            ExecutionUpdate::StepStarted { step, .. } => {
+                // Find step index by matching step id with workflow definition
+                if let Some(ref wf_id) = self.executing_workflow_id {
+                    if let Some(def) = self.workflow_definitions.get(wf_id) {
+                        if let Some(idx) = def.steps.iter().position(|s| s.id == step.id) {
+                            self.executing_step = Some(idx);
+                        }
+                    }
+                }
+                self.timeline.push(TimelineEntry {
+                    name: step.name.clone(),
+                    start: chrono::Utc::now(),
+                    end: None,
+                    status: ExecutionStatus::Running,
+                });
+                self.step_progress = None;
+                self.logs.push(format!("  > Step: {}", step.name));
+            },
+            ExecutionUpdate::StepProgress { step_id, progress, .. } => {
+                self.step_progress = Some((step_id, progress));
+            },
+            ExecutionUpdate::VariablesUpdated { source, values, .. } => {
+                if let VariableSource::StepOutput(step_id) = &source {
+                    for (name, value) in &values {
+                        self.logs.push(format!("      {{{}}} <- {} ({})", name, value, step_id));
+                    }
+                }
+                variables::upsert_variables(&mut self.variables, source, values);
+            },
+            ExecutionUpdate::ArtifactsCollected { directory, files, .. } => {
+                self.logs.push(format!(
+                    "  [ARTIFACTS] {} file(s) saved to {} (press {} to open)",
+                    files.len(),
+                    directory.display(),
+                    self.keymap.keys_label(Action::OpenArtifacts)
+                ));
+                for file in files.iter().take(5) {
+                    let name = file.strip_prefix(&directory).unwrap_or(file);
+                    self.logs.push(format!("      {}", name.display()));
+                }
+                if files.len() > 5 {
+                    self.logs.push(format!("      ... and {} more", files.len() - 5));
+                }
+                self.last_artifacts_dir = Some(directory);
+            },
+            ExecutionUpdate::CleanupCompleted { results, .. } => {
+                for result in &results {
+                    match &result.error {
+                        None => self.logs.push(format!("  [CLEANUP OK] {}", result.command)),
+                        Some(error) => self.logs.push(format!("  [CLEANUP FAIL] {}: {}", result.command, error)),
+                    }
+                }
+            },
+            ExecutionUpdate::HookCompleted { event, action, success, message, .. } => {
+                let status = if success { "OK" } else { "FAIL" };
+                self.logs
+                    .push(format!("  [HOOK {}] {}: {} ({})", status, event, action, message));
+            },
+            ExecutionUpdate::StepCompleted { result, .. } => {
+                // Find step index by step_id
+                let step_idx = if let Some(ref wf_id) = self.executing_workflow_id {
+                    if let Some(def) = self.workflow_definitions.get(wf_id) {
+                        def.steps.iter().position(|s| s.id == result.step_id)
+                    } else {
+                        None
+                    }
+                } else {
+                    None
+                };
+                
+                if let Some(idx) = step_idx {
+                    self.completed_steps.push(idx);
+                    // A skipped step no longer counts towards the run's progress
+                    if matches!(result.status, ExecutionStatus::Skipped | ExecutionStatus::AlreadyExists) {
+                        if let Some((_, runs)) = &mut self.run_steps {
+                            runs.retain(|&i| i != idx);
+                        }
+                    }
+                }
+                self.record_step_timing(&result);
+                self.step_progress = None;
+                if let Some(path) = result.full_output.first() {
+                    self.logs.push(format!(
+                        "  [OUTPUT] Truncated; full output saved to {} (press {} to view)",
+                        path.display(),
+                        self.keymap.keys_label(Action::ViewFullOutput)
+                    ));
+                    self.last_full_output = Some(path.clone());
+                }
+                
+                if result.status == ExecutionStatus::Completed {
+                    self.logs
+                        .push(format!("  [OK] Step '{}' finished", result.step_id));
+                    // Show stdout if available
+                    if !result.stdout.is_empty() {
+                        // Try to format as JSON
+                        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&result.stdout) {
+                            if let Ok(pretty) = serde_json::to_string_pretty(&json) {
+                                for line in pretty.lines().take(10) {
+                                    self.logs.push(format!("      {}", line));
+                                }
+                                if pretty.lines().count() > 10 {
+                                    self.logs.push("      ... (truncated)".to_string());
+                                }
+                            }
+                            let step_name = step_idx
+                                .and_then(|idx| {
+                                    let wf_id = self.executing_workflow_id.as_ref()?;
+                                    Some(self.workflow_definitions.get(wf_id)?.steps[idx].name.clone())
+                                })
+                                .unwrap_or_else(|| result.step_id.clone());
+                            let signs_url = step_idx
+                                .and_then(|idx| {
+                                    let wf_id = self.executing_workflow_id.as_ref()?;
+                                    let step = self.workflow_definitions.get(wf_id)?.steps.get(idx)?;
+                                    Some(matches!(
+                                        step.command,
+                                        RapsCommand::Object { action: ObjectAction::SignedUrl, .. }
+                                    ))
+                                })
+                                .unwrap_or(false);
+                            let url = result.json_output.as_ref().and_then(results::first_url);
+                            if let Some(url) = url.filter(|_| signs_url) {
+                                self.logs.push(format!(
+                                    "  [URL] Signed URL ready (press {} for a QR code)",
+                                    self.keymap.keys_label(Action::ShowQrCode)
+                                ));
+                                self.signed_url = Some(url);
+                            }
+                            self.step_outputs.push(&result.step_id, &step_name, json);
+                        } else {
+                            // Plain text output
+                            for line in output::displayed_lines(&result.stdout).take(5) {
+                                self.logs.push(format!("      {}", line));
+                            }
+                        }
+                    }
+                } else if result.status == ExecutionStatus::AlreadyExists {
+                    self.logs.push(format!(
+                        "  [SKIP] Step '{}' skipped: already exists (skip_if)",
+                        result.step_id
+                    ));
+                } else if result.status == ExecutionStatus::Skipped {
+                    self.logs
+                        .push(format!("  [SKIP] Step '{}' failed and was skipped", result.step_id));
+                } else {
+                    self.logs
+                        .push(format!("  [FAIL] Step '{}' failed", result.step_id));
+                    if !result.stderr.is_empty() {
+                        for line in output::displayed_lines(&result.stderr).take(3) {
+                            self.logs.push(format!("      ERR: {}", line));
+                        }
+                    }
+                }
+            },
+            ExecutionUpdate::Completed { result, .. } => {
+                let wf_id = result.workflow_id.clone();
+                self.tour_workflow_finished(&wf_id, result.success);
+                self.last_run_status = Some(if result.success {
+                    ExecutionStatus::Completed
+                } else {
+                    ExecutionStatus::Failed
+                });
+                let queued = self.finish_queued_workflow(&wf_id, result.success);
+                self.executing_workflow_id = None;
+                self.executing_step = None;
+                self.step_progress = None;
+                let status = if result.success {
+                    "COMPLETED"
+                } else {
+                    "FAILED"
+                };
+                self.logs.push(format!(
+                    "=== Workflow {} {} ({} steps) ===",
+                    result.workflow_id, status, result.steps_completed
+                ));
+                
+                if result.success {
+                    self.request_result_preview(&wf_id);
+                }
+
+                // Show popup with viewer URL for translation workflows
+                // (a queued run shows a single summary at the end instead)
+                if result.success && !queued {
+                    // Signed URLs are for the audience's phones
+                    if let Some(url) = self.signed_url.clone() {
+                        self.show_qr_code(&url);
+                    } else if wf_id.contains("translate") || wf_id.contains("derivative") || wf_id.contains("svf") {
+                        self.show_popup(PopupState {
+                            kind: PopupKind::Message,
+                            title: format!(" {} ", self.locale.text(Text::WorkflowComplete)),
+                            message: self.locale.format(Text::TranslationCompleted, &[&wf_id]),
+                            url: Some("https://aps.autodesk.com/viewer".to_string()),
+                            qr: None,
+                        });
+                    } else {
+                        self.show_popup(PopupState {
+                            kind: PopupKind::Message,
+                            title: format!(" {} ", self.locale.text(Text::WorkflowComplete)),
+                            message: self.locale.format(Text::WorkflowCompleted, &[&wf_id]),
+                            url: None,
+                            qr: None,
+                        });
+                    }
+                }
+            },
+            ExecutionUpdate::Cancelled { .. } => {
+                if self.run_queue.take().is_some() {
+                    self.logs.push("=== Queued workflows cancelled ===".to_string());
+                }
+                if self.tour_run.take().is_some() {
+                    self.logs.push("=== Tour ended ===".to_string());
+                }
+                self.executing_workflow_id = None;
+                self.executing_step = None;
+                self.step_progress = None;
+                self.close_running_timeline_entries(ExecutionStatus::Cancelled);
+                self.last_run_status = Some(ExecutionStatus::Cancelled);
+                self.logs.push("=== Workflow cancelled ===".to_string());
+            },
+            ExecutionUpdate::Failed { handle, error } => {
+                self.finish_queued_workflow(&handle.workflow_id, false);
+                self.tour_workflow_finished(&handle.workflow_id, false);
+                self.executing_workflow_id = None;
+                self.executing_step = None;
+                self.step_progress = None;
+                self.close_running_timeline_entries(ExecutionStatus::Failed);
+                self.last_run_status = Some(ExecutionStatus::Failed);
+                self.logs.push(format!("!!! Error: {}", error.message));
+                if let Some(kind) = error.kind {
+                    let retry = if kind.is_retryable() { ", retryable" } else { "" };
+                    self.logs.push(format!("    Kind: {}{}", kind, retry));
+                }
                 for suggestion in error.recovery_suggestions {
                     self.logs.push(format!("    Suggestion: {}", suggestion));
                 }
             },
-            _ => {},
+        }
+    }
+
+    /// Update the timeline entry for a finished step with its real timings
+    fn record_step_timing(&mut self, result: &crate::workflow::StepResult) {
+        let end = result.end_time.unwrap_or_else(chrono::Utc::now);
+        match self.timeline.iter_mut().rev().find(|e| e.end.is_none()) {
+            Some(entry) => {
+                entry.start = result.start_time;
+                entry.end = Some(end);
+                entry.status = result.status.clone();
+            },
+            None => {
+                let name = self
+                    .executing_workflow_id
+                    .as_ref()
+                    .and_then(|id| self.workflow_definitions.get(id))
+                    .and_then(|def| def.steps.iter().find(|s| s.id == result.step_id))
+                    .map(|s| s.name.clone())
+                    .unwrap_or_else(|| result.step_id.clone());
+                self.timeline.push(TimelineEntry {
+                    name,
+                    start: result.start_time,
+                    end: Some(end),
+                    status: result.status.clone(),
+                });
+            },
+        }
+    }
+
+    /// Mark any still-running timeline entries as finished with `status`
+    fn close_running_timeline_entries(&mut self, status: ExecutionStatus) {
+        let now = chrono::Utc::now();
+        for entry in self.timeline.iter_mut().filter(|e| e.end.is_none()) {
+            entry.end = Some(now);
+            entry.status = status.clone();
         }
     }
 
@@ -529,68 +1752,45 @@ impl TuiApp {
         
         match mouse.kind {
             MouseEventKind::Down(MouseButton::Left) => {
-                // If popup is open, close it on any click
-                if self.popup.is_some() {
-                    self.popup = None;
+                // A click closes a message popup; the others wait for their keys
+                match self.popup.as_ref().map(|popup| &popup.kind) {
+                    Some(PopupKind::Message) => {
+                        self.update(Msg::ClosePopup);
+                        return;
+                    },
+                    Some(_) => return,
+                    None => {},
+                }
+
+                // Start dragging a pane splitter when its border is grabbed
+                if let Some(splitter) = self.splitter_at(x, y) {
+                    self.dragging = Some(splitter);
                     return;
                 }
                 
                 // Check if click is in sidebar area
-                if x >= self.sidebar_area.x 
-                    && x < self.sidebar_area.x + self.sidebar_area.width
-                    && y >= self.sidebar_area.y + 1  // +1 for border
-                    && y < self.sidebar_area.y + self.sidebar_area.height - 1 
-                {
-                    // Calculate which sidebar item was clicked
-                    let clicked_display_index = (y - self.sidebar_area.y - 1) as usize;
-                    if clicked_display_index < self.sidebar_items.len() {
-                        match &self.sidebar_items[clicked_display_index] {
-                            SidebarItem::Category { name, .. } => {
-                                // Toggle category expansion
-                                let name = name.clone();
-                                if self.collapsed_categories.contains(&name) {
-                                    self.collapsed_categories.remove(&name);
-                                } else {
-                                    self.collapsed_categories.insert(name);
-                                }
-                                self.rebuild_sidebar_items();
-                            }
-                            SidebarItem::Workflow { index } => {
-                                let _workflow_index = *index;
-                                // Check if [Run] button was clicked (last 6 chars "[Run]" + border)
-                                let run_button_x = self.sidebar_area.x + self.sidebar_area.width - 8;
-                                if x >= run_button_x {
-                                    // Run button clicked - select and trigger execution
-                                    self.list_state.select(Some(clicked_display_index));
-                                    self.update_preflight_cache();
-                                    self.pending_run = true;
-                                } else {
-                                    // Check for double-click to run workflow
-                                    let now = std::time::Instant::now();
-                                    let is_double_click = if let Some((lx, ly, lt)) = self.last_click {
-                                        lx == x && ly == y && now.duration_since(lt).as_millis() < 400
-                                    } else {
-                                        false
-                                    };
-                                    
-                                    // Select workflow (use display index for list widget)
-                                    self.list_state.select(Some(clicked_display_index));
-                                    self.update_preflight_cache();
-                                    
-                                    if is_double_click {
-                                        // Double-click triggers run
-                                        self.pending_run = true;
-                                        self.last_click = None;
-                                    } else {
-                                        // Record click for double-click detection
-                                        self.last_click = Some((x, y, now));
-                                    }
-                                }
-                                self.steps_scroll = 0;
-                                self.flowchart_state.reset();
+                if let Some(hit) = self.sidebar_view().hit(self.sidebar_area, self.list_state.offset(), x, y) {
+                    let mut messages = vec![Msg::SelectRow(hit.row)];
+                    match &self.sidebar.items()[hit.row] {
+                        SidebarItem::Category { name, .. } => messages.push(Msg::ToggleCategory(name.clone())),
+                        SidebarItem::Workflow { .. } | SidebarItem::Tour { .. } => {
+                            // The [Run] button or a double click runs it
+                            let now = std::time::Instant::now();
+                            let is_double_click = self.last_click.is_some_and(|(lx, ly, lt)| {
+                                lx == x && ly == y && now.duration_since(lt).as_millis() < 400
+                            });
+                            if hit.run_button || is_double_click {
+                                messages.push(Msg::RunSelected);
+                                self.last_click = None;
+                            } else {
+                                // Record click for double-click detection
+                                self.last_click = Some((x, y, now));
                             }
                         }
                     }
+                    for message in messages {
+                        self.update(message);
+                    }
                 }
                 // Check if click is in detail tabs area (top row of detail panel)
                 else if x >= self.detail_area.x
@@ -598,19 +1798,10 @@ impl TuiApp {
                     && y >= self.detail_area.y
                     && y <= self.detail_area.y + 2
                 {
-                    // Simple tab detection based on x position
-                    let tab_x = x - self.detail_area.x;
-                    if tab_x < 12 {
-                        self.detail_tab = 0;  // Overview
-                    } else if tab_x < 20 {
-                        self.detail_tab = 1;  // Steps
-                    } else if tab_x < 32 {
-                        self.detail_tab = 2;  // Flowchart
-                    } else if tab_x < 40 {
-                        self.detail_tab = 3;  // YAML
+                    // Use the tab boundaries from the last render
+                    if let Some(tab) = tabs::tab_at(&self.tab_bounds, x) {
+                        self.update(Msg::SelectTab(tab));
                     }
-                    self.steps_scroll = 0;
-                    self.flowchart_state.reset();
                 }
                 // Check if click is in help bar area
                 else if y == self.help_bar_area.y {
@@ -618,7 +1809,7 @@ impl TuiApp {
                     // Help bar format: " ^/v  Scroll   </>  Tabs   []  Width   -+  Height   Enter  Run   q  Quit"
                     let help_x = x - self.help_bar_area.x;
                     // Run button is around position 48-58, Quit is around 60-68
-                    if help_x >= 48 && help_x < 58 {
+                    if (48..58).contains(&help_x) {
                         // "Enter Run" clicked - trigger workflow run
                         // We'll set a flag and handle in main loop
                         self.logs.push("Click: Run workflow...".to_string());
@@ -628,6 +1819,19 @@ impl TuiApp {
                     }
                 }
             }
+            MouseEventKind::Drag(MouseButton::Left) => match self.dragging {
+                Some(Splitter::Sidebar) => {
+                    let row_width = self.sidebar_area.width + self.detail_area.width;
+                    self.update(Msg::ResizeSidebar(sidebar_percent_at(x, self.sidebar_area.x, row_width)));
+                },
+                Some(Splitter::Console) => {
+                    self.update(Msg::ResizeConsole(console_height_at(y, self.help_bar_area.y)));
+                },
+                None => {},
+            },
+            MouseEventKind::Up(MouseButton::Left) => {
+                self.dragging = None;
+            }
             MouseEventKind::ScrollUp => {
                 // Scroll in the active view
                 if x >= self.detail_area.x && x < self.detail_area.x + self.detail_area.width {
@@ -637,7 +1841,7 @@ impl TuiApp {
                         self.flowchart_state.scroll_up(2);
                     }
                 } else if x >= self.sidebar_area.x && x < self.sidebar_area.x + self.sidebar_area.width {
-                    self.previous_workflow();
+                    self.previous_item();
                 }
             }
             MouseEventKind::ScrollDown => {
@@ -649,159 +1853,298 @@ impl TuiApp {
                         self.flowchart_state.scroll_down(2);
                     }
                 } else if x >= self.sidebar_area.x && x < self.sidebar_area.x + self.sidebar_area.width {
-                    self.next_workflow();
+                    self.next_item();
                 }
             }
             _ => {}
         }
     }
 
-    fn draw(&mut self, f: &mut ratatui::Frame) {
-        let size = f.size();
-        
-        // Main layout: content + help bar at bottom
-        let main_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Min(0),      // Main content
-                Constraint::Length(1),   // Help bar
-            ])
-            .split(size);
+    /// Which splitter (if any) lies under the given cell
+    fn splitter_at(&self, x: u16, y: u16) -> Option<Splitter> {
+        let sidebar_edge = self.sidebar_area.x + self.sidebar_area.width;
+        let in_panel_rows =
+            y >= self.sidebar_area.y && y < self.sidebar_area.y + self.sidebar_area.height;
+        let side_by_side = self.layout_mode == LayoutMode::Wide;
+        if side_by_side && in_panel_rows && self.sidebar_area.width > 0 && (x + 1 == sidebar_edge || x == sidebar_edge) {
+            return Some(Splitter::Sidebar);
+        }
+        if self.console_area.height > 0 && y == self.console_area.y {
+            return Some(Splitter::Console);
+        }
+        None
+    }
 
-        // Content layout: main area + console output (resizable)
-        let content_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Min(0),                            // Main panels
-                Constraint::Length(self.console_height),       // Console output (resizable)
-            ])
-            .split(main_layout[0]);
+    fn draw(&mut self, f: &mut ratatui::Frame) {
+        let size = f.size();
+        self.link_spots.clear();
 
-        // Horizontal split: sidebar + details (resizable)
-        let panels = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(self.sidebar_percent),      // Sidebar (resizable)
-                Constraint::Percentage(100 - self.sidebar_percent), // Details
-            ])
-            .split(content_layout[0]);
+        if self.presentation {
+            self.draw_presentation(f, size);
+            return;
+        }
+
+        self.layout_mode = LayoutMode::for_size(size);
+        if self.layout_mode == LayoutMode::TooSmall {
+            self.draw_too_small(f, size);
+            return;
+        }
+
+        let panels = responsive::panels(
+            size,
+            self.layout_mode,
+            PanelSizes {
+                sidebar_percent: self.sidebar_percent,
+                console_height: self.console_height,
+                drawer_open: self.drawer_open,
+                sidebar_rows: self.sidebar.items().len() as u16,
+            },
+        );
 
         // Cache layout areas for mouse click detection
-        self.sidebar_area = panels[0];
-        self.detail_area = panels[1];
-        self.help_bar_area = main_layout[1];
+        self.sidebar_area = panels.sidebar;
+        self.detail_area = panels.details;
+        self.help_bar_area = panels.help_bar;
+        self.console_area = panels.console;
 
         // Render Sidebar with workflow list
-        self.render_sidebar(f, panels[0]);
+        self.render_sidebar(f, panels.sidebar);
 
         // Render Details panel with tabs
-        self.render_details(f, panels[1]);
+        self.render_details(f, panels.details);
 
         // Render Console Output
-        self.render_console(f, content_layout[1]);
+        self.render_console(f, panels.console);
 
         // Render Help Bar
-        self.render_help_bar(f, main_layout[1]);
+        self.render_help_bar(f, panels.help_bar);
         
-        // Render popup if active
-        if let Some(ref popup) = self.popup {
-            self.render_popup(f, size, popup);
-        }
+        self.draw_toast(f, size);
+        self.draw_popup(f, size);
     }
-    
-    fn render_popup(&self, f: &mut ratatui::Frame, size: Rect, popup: &PopupState) {
-        // Create centered popup
-        let popup_width = 60.min(size.width.saturating_sub(4));
-        let popup_height = 10.min(size.height.saturating_sub(4));
-        
-        let popup_x = (size.width - popup_width) / 2;
-        let popup_y = (size.height - popup_height) / 2;
-        
-        let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
-        
-        // Clear the popup area
-        use ratatui::widgets::Clear;
-        f.render_widget(Clear, popup_area);
-        
-        // Build popup content
+
+    /// In place of the panels when the terminal is smaller than they need
+    fn draw_too_small(&mut self, f: &mut ratatui::Frame, size: Rect) {
+        // Nothing is laid out, so nothing should react to clicks
+        self.sidebar_area = Rect::default();
+        self.console_area = Rect::default();
+        self.help_bar_area = Rect::default();
+        self.detail_area = Rect::default();
+        self.tab_bounds.clear();
+
+        let current = format!("{}x{}", size.width, size.height);
+        let minimum = format!("{}x{}", doctor::MIN_WIDTH, doctor::MIN_HEIGHT);
         let mut lines = vec![
+            Line::from(Span::styled(
+                self.locale.text(Text::TerminalTooSmall),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
             Line::from(""),
-            Line::from(Span::styled(&popup.message, Style::default().fg(Color::White))),
+            Line::from(self.locale.format(Text::TerminalSizeNeeded, &[&current, &minimum])),
             Line::from(""),
+            Line::from(Span::styled(
+                self.locale.text(Text::TerminalDoctorHint),
+                Style::default().fg(Color::DarkGray),
+            )),
         ];
-        
-        if let Some(ref url) = popup.url {
-            lines.push(Line::from(Span::styled(
-                format!("URL: {}", url),
-                Style::default().fg(Color::Cyan).add_modifier(Modifier::UNDERLINED)
-            )));
-            lines.push(Line::from(""));
-            lines.push(Line::from(Span::styled(
-                "(Press 'o' to open in browser, any key to close)",
-                Style::default().fg(Color::DarkGray)
-            )));
-        } else {
-            lines.push(Line::from(Span::styled(
-                "(Press any key to close)",
-                Style::default().fg(Color::DarkGray)
-            )));
+        let top = size.height.saturating_sub(lines.len() as u16) / 2;
+        lines.splice(0..0, std::iter::repeat(Line::from("")).take(top as usize));
+        f.render_widget(
+            Paragraph::new(lines)
+                .alignment(ratatui::layout::Alignment::Center)
+                .wrap(Wrap { trim: true }),
+            size,
+        );
+    }
+
+    /// Render the toast, if any, until it expires
+    fn draw_toast(&mut self, f: &mut ratatui::Frame, size: Rect) {
+        if self.toast.as_ref().is_some_and(|(_, posted)| posted.elapsed() >= TOAST_DURATION) {
+            self.toast = None;
         }
-        
-        let popup_block = Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Yellow))
-            .title(Span::styled(&popup.title, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
-        
-        let popup_content = Paragraph::new(lines)
-            .block(popup_block)
+        if let Some((toast, _)) = &self.toast {
+            hyperlink::uncover(&mut self.link_spots, toast.area(size));
+            toast.render(f, size);
+        }
+    }
+
+    /// Render the popup, if any, on top of everything else
+    fn draw_popup(&mut self, f: &mut ratatui::Frame, size: Rect) {
+        if let Some(ref popup) = self.popup {
+            let area = self.popup_view(popup).area(size);
+            hyperlink::uncover(&mut self.link_spots, area);
+            let spots = self.render_popup(f, size, popup);
+            self.link_spots.extend(spots);
+        }
+    }
+
+    /// Presentation layout: a big banner with the current step over the flowchart or results
+    fn draw_presentation(&mut self, f: &mut ratatui::Frame, size: Rect) {
+        // Panels are hidden, so nothing should react to clicks on them
+        self.sidebar_area = Rect::default();
+        self.console_area = Rect::default();
+        self.help_bar_area = Rect::default();
+        self.detail_area = Rect::default();
+        self.tab_bounds.clear();
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(presentation::BIG_TEXT_HEIGHT as u16 + 4),
+                Constraint::Min(0),
+            ])
+            .split(size);
+
+        let workflow_id = self.executing_workflow_id.clone().or_else(|| self.selected_workflow_id());
+        let definition = workflow_id.as_ref().and_then(|id| self.workflow_definitions.get(id));
+        let is_executing = self.executing_workflow_id.is_some();
+
+        let (headline, color) = match (&self.executing_step, &self.last_run_status) {
+            _ if is_executing && self.run_pause == Some(RunPause::Paused) => ("Paused".to_string(), Color::Magenta),
+            _ if is_executing && self.lock_wait.is_some() => ("Waiting".to_string(), Color::Magenta),
+            (Some(idx), _) if is_executing => (
+                definition
+                    .and_then(|d| d.steps.get(*idx))
+                    .map_or_else(|| "Running".to_string(), |s| s.name.clone()),
+                Color::Yellow,
+            ),
+            (_, Some(ExecutionStatus::Completed)) => ("Complete".to_string(), Color::Green),
+            (_, Some(ExecutionStatus::Failed)) => ("Failed".to_string(), Color::Red),
+            (_, Some(ExecutionStatus::Cancelled)) => ("Cancelled".to_string(), Color::Gray),
+            _ => ("Ready".to_string(), Color::Cyan),
+        };
+
+        let inner_width = layout[0].width.saturating_sub(2) as usize;
+        let style = Style::default().fg(color).add_modifier(Modifier::BOLD);
+        let mut lines: Vec<Line> = match presentation::big_text(&headline, inner_width) {
+            Some(rows) => rows.into_iter().map(|row| Line::from(Span::styled(row, style))).collect(),
+            None => vec![Line::from(""), Line::from(Span::styled(headline, style)), Line::from("")],
+        };
+
+        let mut status = Vec::new();
+        if let (Some(def), Some(idx), true) = (definition, self.executing_step, is_executing) {
+            status.push(Span::styled(
+                format!("Step {}/{}", idx + 1, def.steps.len()),
+                Style::default().fg(Color::White),
+            ));
+            if let Some((_, progress)) = &self.step_progress {
+                status.push(Span::raw("  "));
+                status.push(Span::styled(
+                    progress::format_progress_bar(progress, 30),
+                    Style::default().fg(Color::Cyan),
+                ));
+            }
+        }
+        lines.push(Line::from(status));
+
+        let title = format!(
+            " {} ({}: exit presentation) ",
+            definition.map_or("RAPS Demo", |d| d.metadata.name.as_str()),
+            self.keymap.keys_label(Action::Presentation)
+        );
+        let banner = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
             .alignment(ratatui::layout::Alignment::Center);
-        
-        f.render_widget(popup_content, popup_area);
+        f.render_widget(banner, layout[0]);
+
+        let has_results = self.has_step_outputs()
+            || self
+                .result_preview
+                .as_ref()
+                .is_some_and(|p| Some(&p.workflow_id) == workflow_id.as_ref());
+        match presentation::presentation_view(is_executing, has_results) {
+            PresentationView::Flowchart => self.render_flowchart(f, layout[1]),
+            PresentationView::Results => self.render_results(f, layout[1]),
+        }
+
+        self.draw_toast(f, size);
+        self.draw_popup(f, size);
+    }
+    
+    /// Render a popup, returning the position of its URL for hyperlinking
+    fn render_popup(&self, f: &mut ratatui::Frame, size: Rect, popup: &PopupState) -> Vec<LinkSpot> {
+        self.popup_view(popup).render(f, size)
     }
 
-    fn render_sidebar(&mut self, f: &mut ratatui::Frame, area: Rect) {
-        // Build list items from sidebar_items (grouped view)
-        let mut items: Vec<ListItem> = Vec::new();
-        
-        for (_display_idx, item) in self.sidebar_items.iter().enumerate() {
-            match item {
-                SidebarItem::Category { name, count } => {
+    /// The dialog showing a popup
+    fn popup_view(&self, popup: &PopupState) -> PopupView {
+        let hint = match popup.url {
+            Some(_) => self.locale.format(Text::PopupUrlHint, &[&self.keymap.keys_label(Action::Copy)]),
+            None => self.locale.text(Text::PopupClose).to_string(),
+        };
+        PopupView {
+            title: popup.title.clone(),
+            message: popup.message.clone(),
+            url: popup.url.clone(),
+            qr: popup.qr.clone(),
+            hint,
+        }
+    }
+
+    /// Rows of the sidebar for its current items
+    fn sidebar_view(&self) -> SidebarView {
+        let rows = self
+            .sidebar
+            .items()
+            .iter()
+            .filter_map(|item| match item {
+                SidebarItem::Category { name, count, color } => {
                     let is_collapsed = self.collapsed_categories.contains(name);
                     let indicator = if is_collapsed { "[+]" } else { "[-]" };
-                    let header = format!("{} {} ({})", indicator, name, count);
-                    let style = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
-                    items.push(ListItem::new(header).style(style));
+                    Some(SidebarRow {
+                        text: format!("{} {} ({})", indicator, name, count),
+                        style: Style::default().fg(*color).add_modifier(Modifier::BOLD),
+                    })
                 }
-                SidebarItem::Workflow { index } => {
-                    if let Some(w) = self.workflows.get(*index) {
-                        let category_icon = match w.category {
-                            crate::workflow::WorkflowCategory::ObjectStorage => "[OSS]",
-                            crate::workflow::WorkflowCategory::ModelDerivative => "[MD]",
-                            crate::workflow::WorkflowCategory::DataManagement => "[DM]",
-                            crate::workflow::WorkflowCategory::DesignAutomation => "[DA]",
-                            crate::workflow::WorkflowCategory::ConstructionCloud => "[ACC]",
-                            crate::workflow::WorkflowCategory::RealityCapture => "[RC]",
-                            crate::workflow::WorkflowCategory::Webhooks => "[WH]",
-                            crate::workflow::WorkflowCategory::EndToEnd => "[E2E]",
-                        };
-                        // Add [Run] button indicator
-                        let text = format!("  {} {} [Run]", category_icon, w.name);
-                        items.push(ListItem::new(text));
-                    }
+                SidebarItem::Tour { index } => {
+                    let tour = &self.tours[*index];
+                    Some(SidebarRow {
+                        text: format!(
+                            "  {} {} ({} workflows) [Tour]",
+                            self.glyphs.play,
+                            tour.name,
+                            tour.workflow_ids().count()
+                        ),
+                        style: Style::default().fg(Color::LightMagenta),
+                    })
                 }
-            }
-        }
+                SidebarItem::Workflow { index } => self.workflows.get(*index).map(|w| {
+                    let category_info = self.engine.categories().info(&w.category);
+                    let category_icon = category_info.icon.unwrap_or_default();
+                    // Add [Run] button indicator, and a marker for bulk selection
+                    let star = if self.favorites.contains(&w.id) {
+                        format!("{} ", self.glyphs.star)
+                    } else {
+                        String::new()
+                    };
+                    if self.marked_workflows.contains(&w.id) {
+                        SidebarRow {
+                            text: format!("* {} {}{} [Run]", category_icon, star, w.name),
+                            style: Style::default().fg(Color::Magenta),
+                        }
+                    } else {
+                        SidebarRow {
+                            text: format!("  {} {}{} [Run]", category_icon, star, w.name),
+                            style: Style::default(),
+                        }
+                    }
+                }),
+            })
+            .collect();
 
-        let list = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title("Workflows"))
-            .highlight_style(
-                Style::default()
-                    .add_modifier(Modifier::BOLD)
-                    .fg(Color::Yellow),
-            )
-            .highlight_symbol("> ");
+        let mut title = match self.marked_workflows.len() {
+            0 => "Workflows".to_string(),
+            n => format!("Workflows ({} marked)", n),
+        };
+        // The drawer of the compact layout shows whether it can expand, like a category
+        if self.layout_mode == LayoutMode::Compact {
+            title = format!("{} {}", if self.drawer_open { "[-]" } else { "[+]" }, title);
+        }
+        SidebarView { title, rows }
+    }
 
-        f.render_stateful_widget(list, area, &mut self.list_state);
+    fn render_sidebar(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        self.sidebar_view().render(f, area, &mut self.list_state);
     }
 
     fn render_details(&mut self, f: &mut ratatui::Frame, area: Rect) {
@@ -819,29 +2162,33 @@ impl TuiApp {
         let auth_ok = preflight.map(|p| p.auth_status().map(|c| c.passed).unwrap_or(true)).unwrap_or(true);
         let assets_ok = preflight.map(|p| p.assets_status().map(|c| c.passed).unwrap_or(true)).unwrap_or(true);
         
-        let overview_title = if auth_ok && assets_ok {
-            "Overview ✓".to_string()
-        } else {
-            "Overview ⚠".to_string()
+        let mark = |ok: bool| if ok { self.glyphs.ok } else { self.glyphs.warn };
+        // Narrow terminals fit all ten tabs only with abbreviated titles
+        let label = |text: Text| match self.layout_mode {
+            LayoutMode::Compact => tabs::abbreviate(self.locale.text(text)),
+            _ => self.locale.text(text).to_string(),
         };
-        
-        let assets_title = if assets_ok {
-            "Assets ✓".to_string()
-        } else {
-            "Assets ⚠".to_string()
+        let overview_title = format!("{} {}", label(Text::TabOverview), mark(auth_ok && assets_ok));
+        let assets_title = format!("{} {}", label(Text::TabAssets), mark(assets_ok));
+
+        let tab_titles = vec![
+            overview_title,
+            label(Text::TabSteps),
+            label(Text::TabFlowchart),
+            assets_title,
+            label(Text::TabYaml),
+            label(Text::TabTimeline),
+            label(Text::TabVariables),
+            label(Text::TabResults),
+            label(Text::TabData),
+            label(Text::TabDesignAutomation),
+        ];
+        let tabs = DetailTabs {
+            title: self.locale.text(Text::Details).to_string(),
+            titles: tab_titles,
+            selected: self.detail_tab,
         };
-        
-        let tab_titles = vec![overview_title, "Steps".to_string(), "Flowchart".to_string(), assets_title, "YAML".to_string()];
-        let tabs = Tabs::new(tab_titles)
-            .block(Block::default().borders(Borders::ALL).title("Details"))
-            .select(self.detail_tab)
-            .style(Style::default().fg(Color::White))
-            .highlight_style(
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            );
-        f.render_widget(tabs, detail_layout[0]);
+        self.tab_bounds = tabs.render(f, detail_layout[0]);
 
         // Render content based on selected tab
         match self.detail_tab {
@@ -850,17 +2197,347 @@ impl TuiApp {
             2 => self.render_flowchart(f, detail_layout[1]),
             3 => self.render_assets(f, detail_layout[1]),
             4 => self.render_yaml(f, detail_layout[1]),
+            5 => self.render_timeline(f, detail_layout[1]),
+            6 => self.render_variables(f, detail_layout[1]),
+            7 => self.render_results(f, detail_layout[1]),
+            8 => self.render_data(f, detail_layout[1]),
+            9 => self.render_design_automation(f, detail_layout[1]),
             _ => {}
         }
     }
 
+    fn render_results(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        let selected_id = self.selected_workflow_id();
+        let preview = self
+            .result_preview
+            .as_ref()
+            .filter(|p| Some(&p.workflow_id) == selected_id.as_ref());
+        let has_outputs = self.has_step_outputs();
+
+        let mut lines = Vec::new();
+        match preview {
+            None if has_outputs => {},
+            None => {
+                lines.push(Line::from("No results yet for this workflow."));
+                lines.push(Line::from(
+                    "Step JSON outputs, and for Model Derivative runs the thumbnail and manifest, show up here.",
+                ));
+            },
+            Some(preview) if preview.loading => {
+                lines.push(Line::from(format!("Fetching thumbnail and manifest for {}...", preview.urn)));
+            },
+            Some(preview) => {
+                lines.push(Line::from(vec![
+                    Span::styled("URN: ", Style::default().fg(Color::DarkGray)),
+                    Span::raw(preview.urn.clone()),
+                ]));
+                lines.push(Line::from(""));
+                if let Some(thumbnail) = &preview.thumbnail {
+                    let max_cols = area.width.saturating_sub(2) as usize;
+                    lines.extend(preview::thumbnail_lines(thumbnail, max_cols.min(64), 16));
+                    lines.push(Line::from(""));
+                }
+                if let Some(manifest) = &preview.manifest {
+                    lines.extend(preview::manifest_lines(manifest));
+                }
+                for error in &preview.errors {
+                    lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red))));
+                }
+                lines.push(Line::from(""));
+            },
+        }
+
+        if has_outputs {
+            lines.push(Line::from(Span::styled(
+                "Step outputs",
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+            // Keep the selected row in view
+            let selected_line = lines.len() + self.step_outputs.selected();
+            let visible = area.height.saturating_sub(2) as usize;
+            if selected_line < self.steps_scroll {
+                self.steps_scroll = selected_line;
+            } else if visible > 0 && selected_line >= self.steps_scroll + visible {
+                self.steps_scroll = selected_line + 1 - visible;
+            }
+            lines.extend(self.step_outputs.lines(true));
+        }
+
+        let title = if has_outputs {
+            format!(
+                "Results (Enter: expand/collapse, {}: copy)",
+                self.keymap.keys_label(Action::Copy)
+            )
+        } else {
+            "Results".to_string()
+        };
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .scroll((self.steps_scroll as u16, 0));
+        f.render_widget(paragraph, area);
+    }
+
+    /// ID of the workflow selected in the sidebar
+    fn selected_workflow_id(&self) -> Option<String> {
+        self.list_state.selected().and_then(|i| match self.sidebar.items().get(i) {
+            Some(SidebarItem::Workflow { index }) => Some(self.workflows[*index].id.clone()),
+            _ => None,
+        })
+    }
+
+    /// Whether step outputs of the selected workflow's last run are available
+    fn has_step_outputs(&self) -> bool {
+        !self.step_outputs.is_empty()
+            && self.step_outputs.workflow_id.is_some()
+            && self.step_outputs.workflow_id == self.selected_workflow_id()
+    }
+
+    /// Whether the Results tab shows the step output tree of the selected workflow
+    fn results_tree_focused(&self) -> bool {
+        self.detail_tab == 7 && self.has_step_outputs()
+    }
+
+    /// Whether the Data tab shows the Data Management tree
+    fn data_focused(&self) -> bool {
+        self.detail_tab == 8 && !self.data_browser.is_empty()
+    }
+
+    /// List the children of a Data tab node in the background
+    fn request_data(&mut self, request: DmRequest) {
+        spawn_listing(self.raps_client(), self.data_sender.clone(), request.parent, request.command);
+    }
+
+    /// Whether the DA tab shows app bundles or activities
+    fn da_focused(&self) -> bool {
+        self.detail_tab == 9 && !self.da_inspector.is_empty()
+    }
+
+    /// Use the selected app bundle or activity as a parameter of the next
+    /// runs, and copy its ID
+    fn use_da_param(&mut self) {
+        let Some((name, id)) = self.da_inspector.selected_param() else {
+            return;
+        };
+        self.data_params.insert(name.to_string(), id.clone());
+        self.copy_to_clipboard(&id);
+        self.logs.push(format!("    Next runs use params.{}", name));
+    }
+
+    fn render_design_automation(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        let mut lines = Vec::new();
+        for name in ["appbundle_id", "activity_id"] {
+            if let Some(value) = self.data_params.get(name) {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("params.{}", name), Style::default().fg(Color::Cyan)),
+                    Span::raw(format!(" = {}", value)),
+                ]));
+            }
+        }
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+
+        let (entries, selected) = self.da_inspector.lines();
+        // Keep the selected entry in view
+        let selected_line = lines.len() + selected;
+        let visible = area.height.saturating_sub(2) as usize;
+        if selected_line < self.steps_scroll {
+            self.steps_scroll = selected_line;
+        } else if visible > 0 && selected_line >= self.steps_scroll + visible {
+            self.steps_scroll = selected_line + 1 - visible;
+        }
+        lines.extend(entries);
+
+        let title = format!(
+            "Design Automation (Enter: definition, {}: use as workflow parameter)",
+            self.keymap.keys_label(Action::Copy)
+        );
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .scroll((self.steps_scroll as u16, 0));
+        f.render_widget(paragraph, area);
+    }
+
+    /// Use the IDs of the selected Data tab node (and its ancestors) as
+    /// parameters of the next runs, and copy the node's own ID
+    fn use_data_params(&mut self) {
+        let params = self.data_browser.selected_params();
+        let Some((_, id)) = params.last().cloned() else {
+            return;
+        };
+        let names: Vec<String> = params.iter().map(|(name, _)| format!("params.{}", name)).collect();
+        // IDs of the previous selection's levels don't carry over
+        for kind in DmKind::ALL {
+            self.data_params.remove(kind.param());
+        }
+        self.data_params
+            .extend(params.into_iter().map(|(name, value)| (name.to_string(), value)));
+        self.copy_to_clipboard(&id);
+        self.logs.push(format!("    Next runs use {}", names.join(", ")));
+    }
+
+    fn render_data(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        let mut lines = Vec::new();
+        if !self.data_params.is_empty() {
+            let mut params: Vec<_> = self.data_params.iter().collect();
+            params.sort();
+            for (name, value) in params {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("params.{}", name), Style::default().fg(Color::Cyan)),
+                    Span::raw(format!(" = {}", value)),
+                ]));
+            }
+            lines.push(Line::from(""));
+        }
+
+        // Keep the selected row in view
+        let selected_line = lines.len() + self.data_browser.selected();
+        let visible = area.height.saturating_sub(2) as usize;
+        if selected_line < self.steps_scroll {
+            self.steps_scroll = selected_line;
+        } else if visible > 0 && selected_line >= self.steps_scroll + visible {
+            self.steps_scroll = selected_line + 1 - visible;
+        }
+        lines.extend(self.data_browser.lines());
+
+        let title = format!(
+            "Data Management (Enter: expand, {}: use as workflow parameters)",
+            self.keymap.keys_label(Action::Copy)
+        );
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .scroll((self.steps_scroll as u16, 0));
+        f.render_widget(paragraph, area);
+    }
+
+    /// Whether the Variables tab shows captured values of the selected workflow
+    fn variables_focused(&self) -> bool {
+        self.detail_tab == 6
+            && !self.variables.is_empty()
+            && self.timeline_workflow_id.is_some()
+            && self.timeline_workflow_id == self.selected_workflow_id()
+    }
+
+    /// Copy the selected Results/Variables value, or else the last console line
+    fn copy_selection(&mut self) {
+        let text = if self.results_tree_focused() {
+            self.step_outputs.selected_copy_text()
+        } else if self.variables_focused() {
+            self.variables.get(self.selected_variable).map(|v| v.value.clone())
+        } else {
+            // Skip our own copy confirmations so repeated presses keep the same line
+            self.logs
+                .iter()
+                .rev()
+                .find(|l| !l.trim().is_empty() && !l.starts_with("Copied"))
+                .map(|l| l.trim().to_string())
+        };
+        match text {
+            Some(text) => self.copy_to_clipboard(&text),
+            None => self.logs.push("Nothing to copy".to_string()),
+        }
+    }
+
+    /// Show a URL as a QR code the audience can scan
+    fn show_qr_code(&mut self, url: &str) {
+        match qr::qr_lines(url) {
+            Ok(lines) => {
+                self.show_popup(PopupState {
+                    kind: PopupKind::Message,
+                    title: " Scan to Open ".to_string(),
+                    message: "Point a phone camera at the code".to_string(),
+                    url: Some(url.to_string()),
+                    qr: Some(lines),
+                });
+            },
+            Err(e) => self.logs.push(format!("Cannot show a QR code: {:#}", e)),
+        }
+    }
+
+    /// Copy text to the clipboard and report it in the console
+    fn copy_to_clipboard(&mut self, text: &str) {
+        match self.clipboard.copy(text) {
+            Ok(method) => {
+                let shown: String = text.lines().next().unwrap_or_default().chars().take(60).collect();
+                let via = match method {
+                    CopyMethod::System => "",
+                    CopyMethod::Osc52 => " (via terminal)",
+                };
+                self.logs.push(format!("Copied{}: {}", via, shown));
+            },
+            Err(e) => self.logs.push(format!("Failed to copy to clipboard: {:#}", e)),
+        }
+    }
+
+    fn render_variables(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        let selected_id = self.list_state.selected().and_then(|i| match self.sidebar.items().get(i) {
+            Some(SidebarItem::Workflow { index }) => Some(self.workflows[*index].id.clone()),
+            _ => None,
+        });
+        let definition = selected_id.as_ref().and_then(|id| self.workflow_definitions.get(id)).map(Arc::as_ref);
+
+        // Captured values only apply to the workflow that produced them
+        let is_last_run = selected_id.is_some() && selected_id == self.timeline_workflow_id;
+        let entries: &[VariableEntry] = if is_last_run { &self.variables } else { &[] };
+
+        let selected = if entries.is_empty() {
+            None
+        } else {
+            self.selected_variable = self.selected_variable.min(entries.len() - 1);
+            Some(self.selected_variable)
+        };
+        if let Some(index) = selected {
+            // Keep the selected value in view
+            let line = variables::entry_line(definition, entries, index);
+            let visible = area.height.saturating_sub(2) as usize;
+            if line < self.steps_scroll {
+                self.steps_scroll = line;
+            } else if visible > 0 && line >= self.steps_scroll + visible {
+                self.steps_scroll = line + 1 - visible;
+            }
+        }
+
+        let lines = match definition {
+            Some(def) => variables::build_variable_lines(Some(def), entries, selected),
+            None => vec![Line::from(format!("{} Select a workflow from the list", self.glyphs.arrow_left))],
+        };
+        let copy_hint = if entries.is_empty() {
+            String::new()
+        } else {
+            format!(" ({}: copy value)", self.keymap.keys_label(Action::Copy))
+        };
+        let title = if is_last_run && self.executing_workflow_id.is_some() {
+            format!("Variables (live){}", copy_hint)
+        } else {
+            format!("Variables{}", copy_hint)
+        };
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .scroll((self.steps_scroll as u16, 0));
+        f.render_widget(paragraph, area);
+    }
+
+    fn render_timeline(&self, f: &mut ratatui::Frame, area: Rect) {
+        let inner_width = area.width.saturating_sub(2) as usize;
+        let lines = timeline::build_timeline_lines(&self.timeline, chrono::Utc::now(), inner_width);
+
+        let title = match &self.timeline_workflow_id {
+            Some(id) => format!("Timeline: {}", id),
+            None => "Timeline".to_string(),
+        };
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .scroll((self.steps_scroll as u16, 0));
+        f.render_widget(paragraph, area);
+    }
+
     fn render_yaml(&self, f: &mut ratatui::Frame, area: Rect) {
         let content = if let Some(selected) = self.list_state.selected() {
-            if let Some(SidebarItem::Workflow { index }) = self.sidebar_items.get(selected) {
+            if let Some(SidebarItem::Workflow { index }) = self.sidebar.items().get(selected) {
                 let w = &self.workflows[*index];
                 if let Some(def) = self.workflow_definitions.get(&w.id) {
                     // Serialize to YAML
-                    match serde_yaml::to_string(def) {
+                    match serde_yaml::to_string(def.as_ref()) {
                         Ok(yaml) => yaml,
                         Err(e) => format!("Error serializing YAML: {}", e),
                     }
@@ -868,14 +2545,15 @@ impl TuiApp {
                     "Workflow definition not found".to_string()
                 }
             } else {
-                "← Select a workflow (not a category)".to_string()
+                format!("{} Select a workflow (not a category)", self.glyphs.arrow_left)
             }
         } else {
-            "<- Select a workflow from the list".to_string()
+            format!("{} Select a workflow from the list", self.glyphs.arrow_left)
         };
 
+        let title = format!("YAML (scroll: ^/v, edit: {})", self.keymap.keys_label(Action::Edit));
         let paragraph = Paragraph::new(content)
-            .block(Block::default().borders(Borders::ALL).title("YAML (scroll: ^/v)"))
+            .block(Block::default().borders(Borders::ALL).title(title))
             .wrap(Wrap { trim: false })
             .scroll((self.steps_scroll as u16, 0));
         f.render_widget(paragraph, area);
@@ -883,12 +2561,13 @@ impl TuiApp {
 
     fn render_overview(&self, f: &mut ratatui::Frame, area: Rect) {
         let content = if let Some(selected) = self.list_state.selected() {
-            if let Some(SidebarItem::Workflow { index }) = self.sidebar_items.get(selected) {
+            if let Some(SidebarItem::Workflow { index }) = self.sidebar.items().get(selected) {
                 let w = &self.workflows[*index];
                 let def = self.workflow_definitions.get(&w.id);
                 let step_count = def.map(|d| d.steps.len()).unwrap_or(0);
+                let glyphs = self.glyphs;
                 let prereqs = w.prerequisites.iter()
-                    .map(|p| format!("  • {}", p.description))
+                    .map(|p| format!("  {} {}", glyphs.bullet, p.description))
                     .collect::<Vec<_>>()
                     .join("\n");
                 let prereqs_section = if prereqs.is_empty() {
@@ -901,61 +2580,116 @@ impl TuiApp {
                 let preflight_section = if let Some(ref preflight) = self.cached_preflight {
                     let mut lines = Vec::new();
                     for check in &preflight.checks {
-                        let icon = if check.passed { "✓" } else { "✗" };
+                        let icon = glyphs.status(check.passed);
                         let color_hint = if check.passed { "" } else { " [!]" };
-                        lines.push(format!("  {} {}: {}{}", icon, check.name, check.message, color_hint));
+                        lines.push(format!(
+                            "  {} {}: {}{}",
+                            icon,
+                            self.locale.check_name(&check.name),
+                            check.message,
+                            color_hint
+                        ));
                     }
+                    if !preflight.blocked_steps.is_empty() {
+                        lines.push(format!(
+                            "  {} {}",
+                            glyphs.warn,
+                            self.locale.format(Text::StepsNeedAssets, &[&preflight.blocked_steps.join(", ")])
+                        ));
+                    }
+                    lines.push(format!("  {}", glyphs.double_rule.repeat(34)));
                     if preflight.all_passed {
-                        lines.push("  ══════════════════════════════════".to_string());
-                        lines.push("  ✓ Ready to run! Press ENTER to execute".to_string());
+                        lines.push(format!("  {} {}", glyphs.ok, self.locale.text(Text::ReadyToRun)));
                     } else {
-                        lines.push("  ══════════════════════════════════".to_string());
-                        lines.push("  ⚠ Missing requirements - see Assets tab".to_string());
+                        lines.push(format!("  {} {}", glyphs.warn, self.locale.text(Text::MissingSeeAssets)));
                     }
                     lines.join("\n")
                 } else {
-                    "  Checking...".to_string()
+                    format!("  {}", self.locale.text(Text::Checking))
                 };
                 
                 // Required assets section
-                let assets_section = if w.required_assets.is_empty() {
-                    "  None".to_string()
-                } else {
-                    w.required_assets.iter()
-                        .map(|a| format!("  • {}", a.display()))
+                let assets_section = match self.workflow_definitions.get(&w.id) {
+                    Some(def) if !def.required_assets().is_empty() => def
+                        .required_assets()
+                        .iter()
+                        .map(|a| format!("  {} {}", glyphs.bullet, def.describe_asset(a)))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    Some(_) => "  None".to_string(),
+                    None if w.required_assets.is_empty() => "  None".to_string(),
+                    None => w.required_assets.iter()
+                        .map(|a| format!("  {} {}", glyphs.bullet, a.display()))
                         .collect::<Vec<_>>()
-                        .join("\n")
+                        .join("\n"),
                 };
                 
+                // Prefer measured timings over the declared estimate
+                let mut duration_line = format!("~{} seconds", w.estimated_duration.num_seconds());
+                if let Some(summary) = self.history.read().ok().and_then(|h| h.summary(&w.id)) {
+                    duration_line.push_str(&format!(", {}", summary));
+                }
+
+                let pack_line = self
+                    .pack_labels
+                    .get(&w.id)
+                    .map(|label| format!("Pack: {}\n", label))
+                    .unwrap_or_default();
+
+                let profile_line = match (def, self.selected_profiles.get(&w.id)) {
+                    (Some(def), Some(profile)) => format!(
+                        "Profile: {} ({} of {} steps)\n",
+                        profile,
+                        def.with_profile(profile).map(|d| d.steps.len()).unwrap_or(0),
+                        step_count
+                    ),
+                    (Some(def), None) if !def.profiles.is_empty() => format!(
+                        "Profiles: {} (press {} to choose)\n",
+                        def.profiles.keys().map(String::as_str).collect::<Vec<_>>().join(", "),
+                        self.keymap.keys_label(Action::PickProfile)
+                    ),
+                    _ => String::new(),
+                };
+
                 format!(
-                    "┌─ {} ─┐\n\n\
+                    "{}\n\n\
                      ID: {}\n\
                      Category: {}\n\
+                     {}\
                      Steps: {}\n\
-                     Duration: ~{} seconds\n\n\
-                     ─── Description ───\n\
+                     {}\
+                     Duration: {}\n\n\
+                     {}\n\
                      {}\n\n\
-                     ─── Prerequisites ───\n\
+                     {}\n\
                      {}\n\n\
-                     ─── Required Assets ───\n\
+                     {}\n\
                      {}\n\n\
-                     ─── Pre-flight Check ───\n\
+                     {}\n\
                      {}",
-                    w.name,
+                    glyphs.boxed(&w.name),
                     w.id,
                     w.category,
+                    pack_line,
                     step_count,
-                    w.estimated_duration.num_seconds(),
+                    profile_line,
+                    duration_line,
+                    glyphs.section("Description"),
                     w.description,
+                    glyphs.section("Prerequisites"),
                     prereqs_section,
+                    glyphs.section("Required Assets"),
                     assets_section,
+                    glyphs.section("Pre-flight Check"),
                     preflight_section
                 )
+            } else if let Some(SidebarItem::Tour { index }) = self.sidebar.items().get(selected) {
+                self.tour_overview(&self.tours[*index])
             } else {
-                "← Select a workflow (not a category)".to_string()
+                format!("{} Select a workflow (not a category)", self.glyphs.arrow_left)
             }
         } else {
-            "← Select a workflow from the list".to_string()
+            format!("{} Select a workflow from the list", self.glyphs.arrow_left)
         };
 
         let paragraph = Paragraph::new(content)
@@ -966,7 +2700,7 @@ impl TuiApp {
 
     fn render_steps(&self, f: &mut ratatui::Frame, area: Rect) {
         let content = if let Some(selected) = self.list_state.selected() {
-            if let Some(SidebarItem::Workflow { index }) = self.sidebar_items.get(selected) {
+            if let Some(SidebarItem::Workflow { index }) = self.sidebar.items().get(selected) {
                 let w = &self.workflows[*index];
                 let is_executing = self.executing_workflow_id.as_ref() == Some(&w.id);
                 
@@ -990,38 +2724,123 @@ impl TuiApp {
                                 "    "
                             };
                             
+                            // Live progress for the executing step
+                            let progress_line = match &self.step_progress {
+                                Some((step_id, progress))
+                                    if is_executing
+                                        && self.executing_step == Some(i)
+                                        && *step_id == step.id =>
+                                {
+                                    format!("| Progress: {}\n", format_progress_bar(progress, 20))
+                                },
+                                _ => String::new(),
+                            };
+
+                            // Countdown against the step's expected duration
+                            let countdown_line = match (step.expected_duration, self.timeline.last()) {
+                                (Some(expected), Some(entry))
+                                    if is_executing && self.executing_step == Some(i) && entry.end.is_none() =>
+                                {
+                                    let elapsed = chrono::Utc::now().signed_duration_since(entry.start);
+                                    let remaining = expected.num_seconds().saturating_sub(elapsed.num_seconds());
+                                    if remaining >= 0 {
+                                        format!("| Remaining: ~{}s of ~{}s\n", remaining, expected.num_seconds())
+                                    } else {
+                                        format!("| Over estimate by {}s\n", remaining.unsigned_abs())
+                                    }
+                                },
+                                _ => String::new(),
+                            };
+
+                            // Asset badge: the files this step needs and whether they are present
+                            let assets_line = if step.requires_assets.is_empty() {
+                                String::new()
+                            } else {
+                                let badges: Vec<String> = step
+                                    .requires_assets
+                                    .iter()
+                                    .map(|asset| {
+                                        let name = asset.file_name().unwrap_or(asset.as_os_str());
+                                        format!("{} {}", self.glyphs.status(asset.exists()), name.to_string_lossy())
+                                    })
+                                    .collect();
+                                format!("| Assets: {}\n", badges.join("  "))
+                            };
+
+                            // Documentation-only annotations
+                            let mut annotations = String::new();
+                            if let Some(expected) = &step.expected_output {
+                                annotations.push_str("|\n| Expected output:\n");
+                                for line in expected.trim_end().lines() {
+                                    annotations.push_str(&format!("|   {}\n", line));
+                                }
+                            }
+                            if let Some(notes) = &step.notes {
+                                annotations.push_str("|\n");
+                                for (j, line) in notes.trim_end().lines().enumerate() {
+                                    let label = if j == 0 { "Note:" } else { "     " };
+                                    annotations.push_str(&format!("| {} {}\n", label, line));
+                                }
+                            }
+
                             format!(
                                 "+-- Step {} {} ----------------------\n\
                                  | Name: {}\n\
                                  | {}\n\
+                                 {}\
+                                 {}\
+                                 {}\
                                  |\n\
                                  | Command:\n\
                                  |   raps {}\n\
+                                 {}\
                                  +------------------------------------",
                                 i + 1,
                                 status,
                                 step.name,
                                 step.description,
-                                cmd_str
+                                assets_line,
+                                progress_line,
+                                countdown_line,
+                                cmd_str,
+                                annotations
                             )
                         })
                         .collect();
                     
+                    // Overall progress, weighted by expected step durations
+                    // over the steps the run includes
+                    let overall = if is_executing {
+                        let runs = match &self.run_steps {
+                            Some((id, runs)) if *id == w.id => runs.clone(),
+                            _ => def.run_steps(None, None),
+                        };
+                        format!(
+                            " | {:.0}% done",
+                            def.run_progress_fraction(&runs, &self.completed_steps) * 100.0
+                        )
+                    } else {
+                        String::new()
+                    };
+
                     if steps.is_empty() {
                         "No steps defined".to_string()
                     } else {
-                        format!("Total: {} steps (scroll with ↑↓)\n\n{}", 
+                        format!("Total: {} steps{} (scroll with {}, {} runs from the top step)\n\n{}", 
                             def.steps.len(),
+                            overall,
+                            self.glyphs.arrows_up_down,
+                            self.keymap.keys_label(Action::RunFromStep),
                             steps.join("\n\n"))
                     }
                 } else {
                     "Workflow definition not found".to_string()
                 }
             } else {
-                "← Select a workflow (not a category)".to_string()
+                format!("{} Select a workflow (not a category)", self.glyphs.arrow_left)
             }
         } else {
-            "← Select a workflow from the list".to_string()
+            format!("{} Select a workflow from the list", self.glyphs.arrow_left)
         };
 
         let paragraph = Paragraph::new(content)
@@ -1033,10 +2852,10 @@ impl TuiApp {
     fn render_flowchart(&mut self, f: &mut ratatui::Frame, area: Rect) {
         // Get the workflow definition for the selected workflow
         let (workflow_def, is_executing) = if let Some(selected) = self.list_state.selected() {
-            if let Some(SidebarItem::Workflow { index }) = self.sidebar_items.get(selected) {
+            if let Some(SidebarItem::Workflow { index }) = self.sidebar.items().get(selected) {
                 let w = &self.workflows[*index];
                 let is_exec = self.executing_workflow_id.as_ref() == Some(&w.id);
-                (self.workflow_definitions.get(&w.id), is_exec)
+                (self.workflow_definitions.get(&w.id).map(Arc::as_ref), is_exec)
             } else {
                 (None, false)
             }
@@ -1052,158 +2871,68 @@ impl TuiApp {
         }
 
         // Create and render the flowchart widget
+        let title = format!(
+            "Flowchart (^/v scroll, {} zoom, {} export)",
+            self.keymap.keys_label(Action::FlowchartZoom),
+            self.keymap.keys_label(Action::Export)
+        );
         let flowchart = FlowchartWidget::new(workflow_def)
             .block(Block::default()
                 .borders(Borders::ALL)
-                .title("Flowchart (^/v scroll)"));
+                .title(title));
         
         f.render_stateful_widget(flowchart, area, &mut self.flowchart_state);
     }
 
     fn render_assets(&self, f: &mut ratatui::Frame, area: Rect) {
-        use crate::assets::AssetCategory as AssetCat;
-        
-        let assets_with_status = self.preflight_checker.get_all_assets_with_status();
-        
-        // Build content
-        let mut lines: Vec<Line> = Vec::new();
-        
-        // Header
-        lines.push(Line::from(vec![
-            Span::styled("═══ ", Style::default().fg(Color::Cyan)),
-            Span::styled("AUTODESK SAMPLE ASSETS", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            Span::styled(" ═══", Style::default().fg(Color::Cyan)),
-        ]));
-        lines.push(Line::from(""));
-        lines.push(Line::from(Span::styled(
-            "© Autodesk, Inc. All rights reserved.",
-            Style::default().fg(Color::DarkGray),
-        )));
-        lines.push(Line::from(""));
-        
-        // Status summary
-        let downloaded = assets_with_status.iter().filter(|(_, d)| *d).count();
-        let total = assets_with_status.len();
-        let status_color = if downloaded == total { Color::Green } else { Color::Yellow };
-        lines.push(Line::from(vec![
-            Span::raw("Status: "),
-            Span::styled(
-                format!("{}/{} downloaded", downloaded, total),
-                Style::default().fg(status_color),
-            ),
-        ]));
-        lines.push(Line::from(""));
-        lines.push(Line::from(Span::styled(
-            "Use ↑↓ to select, D to download selected asset",
-            Style::default().fg(Color::DarkGray),
-        )));
-        lines.push(Line::from(""));
-        
-        // Group by category
-        let mut current_category: Option<AssetCat> = None;
-        
-        for (i, (asset, is_downloaded)) in assets_with_status.iter().enumerate() {
-            // Category header
-            if current_category != Some(asset.category) {
-                current_category = Some(asset.category);
-                lines.push(Line::from(""));
-                lines.push(Line::from(Span::styled(
-                    format!("┌─ {} ─────────────────────────", asset.category.display_name()),
-                    Style::default().fg(Color::Cyan),
-                )));
-            }
-            
-            // Asset entry
-            let status_icon = if *is_downloaded { "✓" } else { "⬇" };
-            let status_color = if *is_downloaded { Color::Green } else { Color::Yellow };
-            let is_selected = i == self.selected_asset;
-            
-            let line_style = if is_selected {
-                Style::default().bg(Color::DarkGray)
-            } else {
-                Style::default()
-            };
-            
-            let prefix = if is_selected { "> " } else { "  " };
-            
-            lines.push(Line::from(vec![
-                Span::styled(prefix, line_style),
-                Span::styled(status_icon, Style::default().fg(status_color)),
-                Span::styled(" ", Style::default()),
-                Span::styled(&asset.name, line_style.add_modifier(if is_selected { Modifier::BOLD } else { Modifier::empty() })),
-                Span::styled(format!(" ({:.1} MB)", asset.estimated_size_mb), Style::default().fg(Color::DarkGray)),
-            ]));
-            
-            if is_selected {
-                lines.push(Line::from(vec![
-                    Span::styled("    ", Style::default()),
-                    Span::styled(&asset.description, Style::default().fg(Color::Gray)),
-                ]));
-                if !*is_downloaded {
-                    lines.push(Line::from(vec![
-                        Span::styled("    ", Style::default()),
-                        Span::styled("[Press D to download]", Style::default().fg(Color::Yellow)),
-                    ]));
-                }
-            }
-        }
-        
-        // Footer with workflow requirements if any
-        if let Some(selected) = self.list_state.selected() {
-            if let Some(SidebarItem::Workflow { index }) = self.sidebar_items.get(selected) {
-                let w = &self.workflows[*index];
-                if !w.required_assets.is_empty() {
-                    lines.push(Line::from(""));
-                    lines.push(Line::from(Span::styled(
-                        format!("─── Required for '{}' ───", w.name),
-                        Style::default().fg(Color::Magenta),
-                    )));
-                    for asset_path in &w.required_assets {
-                        let exists = asset_path.exists();
-                        let icon = if exists { "✓" } else { "✗" };
-                        let color = if exists { Color::Green } else { Color::Red };
-                        lines.push(Line::from(vec![
-                            Span::styled(format!("  {} ", icon), Style::default().fg(color)),
-                            Span::styled(asset_path.display().to_string(), Style::default()),
-                        ]));
-                    }
-                }
-            }
-        }
-        
-        let paragraph = Paragraph::new(lines)
-            .block(Block::default().borders(Borders::ALL).title("Assets (D=download)"))
-            .scroll((self.assets_scroll as u16, 0));
-        f.render_widget(paragraph, area);
+        let required = self
+            .selected_workflow_index()
+            .map(|index| &self.workflows[index])
+            .filter(|w| !w.required_assets.is_empty())
+            .map(|w| RequiredAssets {
+                workflow_name: w.name.clone(),
+                files: w.required_assets.iter().map(|path| (path.display().to_string(), path.exists())).collect(),
+            });
+        let view = AssetsView {
+            assets: self.preflight_checker.get_all_assets_with_status(),
+            selected: self.selected_asset,
+            required,
+            scroll: self.assets_scroll as u16,
+            glyphs: *self.glyphs,
+        };
+        view.render(f, area);
     }
 
-    fn render_console(&self, f: &mut ratatui::Frame, area: Rect) {
-        let logs_text: String = self
-            .logs
-            .iter()
-            .rev()
-            .take(8)
-            .rev()
-            .cloned()
-            .collect::<Vec<_>>()
-            .join("\n");
-        
-        let logs = Paragraph::new(logs_text)
-            .block(Block::default().borders(Borders::ALL).title("Console Output"));
-        f.render_widget(logs, area);
+    /// Console lines currently shown
+    fn visible_logs(&self) -> Vec<String> {
+        self.logs.tail(8)
+    }
+
+    fn render_console(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        let console = ConsoleView {
+            title: self.locale.text(Text::ConsoleOutput).to_string(),
+            lines: self.visible_logs(),
+        };
+        let links = console.render(f, area);
+        self.link_spots.extend(links);
     }
 
     fn render_help_bar(&self, f: &mut ratatui::Frame, area: Rect) {
-        let help_items = vec![
-            ("^/v", "Scroll"),
-            ("</>", "Tabs"),
-            ("[]", "Width"),
-            ("-+", "Height"),
-            ("Enter", "Run"),
-            ("q", "Quit"),
+        // The sidebar width can't be changed in the compact layout, the drawer toggles instead
+        let sidebar_item = match self.layout_mode {
+            LayoutMode::Compact => ("s", Text::HelpDrawer),
+            _ => ("[]", Text::HelpWidth),
+        };
+        let help_items = [
+            ("^/v", Text::HelpScroll),
+            ("</>", Text::HelpTabs),
+            sidebar_item,
+            ("-+", Text::HelpHeight),
+            ("Enter", Text::HelpRun),
+            ("q", Text::HelpQuit),
         ];
         
-        let help_spans: Vec<Span> = help_items
+        let mut help_spans: Vec<Span> = help_items
             .iter()
             .flat_map(|(key, desc)| {
                 vec![
@@ -1212,13 +2941,25 @@ impl TuiApp {
                         Style::default().fg(Color::Black).bg(Color::Cyan),
                     ),
                     Span::styled(
-                        format!(" {} ", desc),
+                        format!(" {} ", self.locale.text(*desc)),
                         Style::default().fg(Color::White),
                     ),
                     Span::raw(" "),
                 ]
             })
             .collect();
+        if let Some(wait) = &self.lock_wait {
+            help_spans.push(Span::styled(
+                format!(" Waiting for lock '{}' held by {} ", wait.lock, wait.holder),
+                Style::default().fg(Color::Black).bg(Color::Magenta),
+            ));
+        }
+        if let Some(release) = &self.available_release {
+            help_spans.push(Span::styled(
+                format!(" v{} available: raps-demo self-update ", release.version),
+                Style::default().fg(Color::Black).bg(Color::Yellow),
+            ));
+        }
         
         let help_line = Line::from(help_spans);
         let help = Paragraph::new(help_line)
@@ -1265,70 +3006,75 @@ impl TuiApp {
         }
     }
 
-    fn next_workflow(&mut self) {
-        if self.sidebar_items.is_empty() {
+    /// Move the sidebar selection down, landing on category headers too
+    fn next_item(&mut self) {
+        if self.sidebar.items().is_empty() {
             self.list_state.select(None);
             return;
         }
 
-        let current = self.list_state.selected().unwrap_or(0);
-        // Find next workflow (skip categories)
-        let mut next = current + 1;
-        loop {
-            if next >= self.sidebar_items.len() {
-                next = 0;
-            }
-            if matches!(self.sidebar_items[next], SidebarItem::Workflow { .. }) {
-                break;
-            }
-            next += 1;
-            if next == current {
-                break; // Wrapped around, no workflows
-            }
-        }
+        let next = match self.list_state.selected() {
+            Some(current) if current + 1 < self.sidebar.items().len() => current + 1,
+            Some(_) => 0,
+            None => 0,
+        };
         self.list_state.select(Some(next));
     }
 
-    fn previous_workflow(&mut self) {
-        if self.sidebar_items.is_empty() {
+    /// Move the sidebar selection up, landing on category headers too
+    fn previous_item(&mut self) {
+        if self.sidebar.items().is_empty() {
             self.list_state.select(None);
             return;
         }
 
-        let current = self.list_state.selected().unwrap_or(0);
-        // Find previous workflow (skip categories)
-        let mut prev = if current == 0 { self.sidebar_items.len() - 1 } else { current - 1 };
-        loop {
-            if matches!(self.sidebar_items[prev], SidebarItem::Workflow { .. }) {
-                break;
-            }
-            if prev == 0 {
-                prev = self.sidebar_items.len() - 1;
-            } else {
-                prev -= 1;
-            }
-            if prev == current {
-                break; // Wrapped around, no workflows
-            }
-        }
+        let prev = match self.list_state.selected() {
+            Some(0) | None => self.sidebar.items().len() - 1,
+            Some(current) => current - 1,
+        };
         self.list_state.select(Some(prev));
     }
 
-    fn get_selected_workflow(&self) -> Option<&crate::workflow::WorkflowMetadata> {
-        if let Some(selected) = self.list_state.selected() {
-            if let Some(SidebarItem::Workflow { index }) = self.sidebar_items.get(selected) {
-                return self.workflows.get(*index);
-            }
+    /// Name of the category header under the sidebar selection, if any
+    fn selected_category(&self) -> Option<String> {
+        match self.sidebar.items().get(self.list_state.selected()?) {
+            Some(SidebarItem::Category { name, .. }) => Some(name.clone()),
+            _ => None,
         }
-        None
     }
-    
+
+    /// Collapse or expand a sidebar category
+    fn set_category_collapsed(&mut self, name: &str, collapsed: bool) {
+        if collapsed {
+            self.collapsed_categories.insert(name.to_string());
+        } else {
+            self.collapsed_categories.remove(name);
+        }
+        let selected = self.sidebar.layout(&self.collapsed_categories, self.list_state.selected());
+        self.list_state.select(selected);
+        self.update_preflight_cache();
+    }
+
+    /// Toggle a sidebar category between collapsed and expanded
+    fn toggle_category(&mut self, name: &str) {
+        let collapsed = self.collapsed_categories.contains(name);
+        self.set_category_collapsed(name, !collapsed);
+    }
+
+    /// Preflight status of the workflow at `index`, step assets included
+    fn preflight(&self, index: usize) -> PreflightStatus {
+        let metadata = &self.workflows[index];
+        match self.workflow_definitions.get(&metadata.id) {
+            Some(definition) => self.preflight_checker.check_definition(definition),
+            None => self.preflight_checker.check(metadata),
+        }
+    }
+
     /// Update the cached preflight status for the selected workflow
     fn update_preflight_cache(&mut self) {
         if let Some(selected) = self.list_state.selected() {
-            if let Some(SidebarItem::Workflow { index }) = self.sidebar_items.get(selected) {
-                let workflow = &self.workflows[*index];
-                self.cached_preflight = Some(self.preflight_checker.check(workflow));
+            if let Some(SidebarItem::Workflow { index }) = self.sidebar.items().get(selected) {
+                self.cached_preflight = Some(self.preflight(*index));
             } else {
                 self.cached_preflight = None;
             }
@@ -1337,6 +3083,237 @@ impl TuiApp {
         }
     }
     
+    /// Open the selected workflow's YAML in `$EDITOR`, then reload and validate it
+    fn edit_selected_workflow(
+        &mut self,
+        terminal: &mut AppTerminal,
+    ) -> Result<()> {
+        let Some(index) = self.selected_workflow_index() else {
+            self.logs.push("Select a workflow to edit".to_string());
+            return Ok(());
+        };
+        let path = self.workflows[index].script_path.clone();
+        if builtin::is_builtin(&path) {
+            self.logs.push(format!(
+                "{} is a read-only built-in workflow; copy it into a workflows directory to edit it",
+                self.workflows[index].id
+            ));
+            return Ok(());
+        }
+
+        // Hand the terminal over to the editor
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
+        let edit_result = editor::open_in_editor(&path);
+        enable_raw_mode()?;
+        execute!(terminal.backend_mut(), EnterAlternateScreen, EnableMouseCapture)?;
+        terminal.clear()?;
+
+        if let Err(e) = edit_result {
+            self.logs.push(format!("Edit failed: {:#}", e));
+            return Ok(());
+        }
+
+        let previous = self.workflow_definitions.get(&self.workflows[index].id).cloned();
+        match self.engine.reload_workflow(&path) {
+            Ok((definition, result)) if result.is_valid => {
+                if let Some(previous) = previous {
+                    let diff = WorkflowDiff::between(&previous, &definition, self.engine.executor().raps_client());
+                    if !diff.is_empty() {
+                        self.show_popup(PopupState {
+                            kind: PopupKind::Message,
+                            title: format!(" Changes to {} ", definition.metadata.id),
+                            message: diff.render().join("\n"),
+                            url: None,
+                            qr: None,
+                        });
+                    }
+                }
+                self.workflows[index] = definition.metadata.clone();
+                self.workflow_definitions = self.engine.get_workflows().clone();
+                self.rebuild_sidebar_items();
+                self.flowchart_state.reset();
+                self.update_preflight_cache();
+                self.logs
+                    .push(format!("Reloaded workflow: {}", definition.metadata.id));
+            },
+            Ok((_, result)) => {
+                self.logs.push(format!(
+                    "Validation failed, keeping previous version of {}:",
+                    path.display()
+                ));
+                for error in result.errors {
+                    self.logs.push(format!("  - {}", error));
+                }
+            },
+            Err(e) => {
+                self.logs.push(format!("Failed to reload {}: {:#}", path.display(), e));
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Fetch the thumbnail and manifest after a Model Derivative run
+    fn request_result_preview(&mut self, workflow_id: &str) {
+        let is_derivative = self
+            .workflows
+            .iter()
+            .any(|w| w.id == workflow_id && w.category == WorkflowCategory::ModelDerivative);
+        if !is_derivative {
+            return;
+        }
+        let Some(urn) = self.variables.iter().find(|v| v.name == "urn").map(|v| v.value.clone()) else {
+            self.logs.push("    No {urn} captured; skipping result preview".to_string());
+            return;
+        };
+
+        self.logs.push(format!(
+            "    Fetching thumbnail and manifest (press {} for Results)",
+            self.keymap.keys_label(Action::TabResults)
+        ));
+        self.result_preview = Some(ResultPreview::loading(workflow_id, &urn));
+        let sender = self.preview_sender.clone();
+        let workflow_id = workflow_id.to_string();
+        let client = self.raps_client();
+        tokio::spawn(async move {
+            let preview = preview::fetch_preview(&client, &workflow_id, &urn).await;
+            let _ = sender.send(preview);
+        });
+    }
+
+    /// Open the last run's artifacts folder in the system file browser
+    fn open_artifacts(&mut self) {
+        match &self.last_artifacts_dir {
+            Some(dir) => {
+                if let Err(e) = open::that(dir) {
+                    self.logs.push(format!("!!! Failed to open {}: {}", dir.display(), e));
+                }
+            },
+            None => self.logs.push("No artifacts collected yet".to_string()),
+        }
+    }
+
+    /// Open the full output of the last truncated step with the system viewer
+    fn view_full_output(&mut self) {
+        match &self.last_full_output {
+            Some(path) => {
+                if let Err(e) = open::that(path) {
+                    self.logs.push(format!("!!! Failed to open {}: {}", path.display(), e));
+                }
+            },
+            None => self.logs.push("No truncated output to view".to_string()),
+        }
+    }
+
+    /// Pause the current execution after its step, or resume it
+    async fn toggle_pause(&mut self) {
+        let Some(handle) = self.executing_handle.clone() else {
+            self.logs.push("No workflow running".to_string());
+            return;
+        };
+        let result = match self.run_pause {
+            None => self.engine.pause(&handle).await,
+            Some(_) => self.engine.resume(&handle).await,
+        };
+        match (result, self.run_pause) {
+            (Err(e), _) => self.logs.push(format!("!!! Failed to pause {}: {:#}", handle.workflow_id, e)),
+            (Ok(()), None) => {
+                self.run_pause = Some(RunPause::Requested);
+                self.logs.push("  [PAUSE] Pausing after the current step".to_string());
+            },
+            (Ok(()), Some(RunPause::Requested)) => {
+                self.run_pause = None;
+                self.logs.push("  [PAUSE] Pause withdrawn".to_string());
+            },
+            // The Resumed update clears the state
+            (Ok(()), Some(RunPause::Paused)) => {},
+        }
+    }
+
+    /// Deal with the stalled run: cancel it, or fail it when `fail` is set
+    async fn kill_stalled_run(&mut self, fail: bool) {
+        let Some(handle) = self.stalled_run.take() else {
+            self.logs.push("No stalled run".to_string());
+            return;
+        };
+        let result = if fail {
+            self.engine.force_fail(&handle, "Stalled: no step progress, failed by the user").await
+        } else {
+            self.engine.cancel(&handle).await
+        };
+        if let Err(e) = result {
+            self.logs.push(format!("!!! Failed to stop {}: {:#}", handle.workflow_id, e));
+        }
+    }
+
+    /// Write the selected workflow's flowchart as ASCII, Mermaid and SVG files
+    fn export_flowchart(&mut self) {
+        use crate::workflow::diagram::{render_diagram, DiagramFormat};
+
+        let Some(index) = self.selected_workflow_index() else {
+            self.logs.push("Select a workflow to export".to_string());
+            return;
+        };
+        let workflow_id = self.workflows[index].id.clone();
+        let Some(definition) = self.workflow_definitions.get(&workflow_id) else {
+            return;
+        };
+
+        let export_dir = std::path::Path::new("./exports");
+        let ascii = FlowchartWidget::new(Some(definition)).to_ascii(self.flowchart_state.zoom);
+        let outputs = [
+            ("txt", ascii),
+            (
+                DiagramFormat::Mermaid.extension(),
+                render_diagram(definition, DiagramFormat::Mermaid),
+            ),
+            (
+                DiagramFormat::Svg.extension(),
+                render_diagram(definition, DiagramFormat::Svg),
+            ),
+            (
+                DiagramFormat::Markdown.extension(),
+                render_diagram(definition, DiagramFormat::Markdown),
+            ),
+        ];
+
+        let result = std::fs::create_dir_all(export_dir).and_then(|_| {
+            outputs.iter().try_for_each(|(ext, content)| {
+                std::fs::write(export_dir.join(format!("{}.{}", workflow_id, ext)), content)
+            })
+        });
+        match result {
+            Ok(()) => self.logs.push(format!(
+                "Exported flowchart to {}/{}.{{txt,mmd,svg,md}}",
+                export_dir.display(),
+                workflow_id
+            )),
+            Err(e) => self.logs.push(format!("Flowchart export failed: {}", e)),
+        }
+    }
+
+    /// Index into `workflows` of the selected sidebar entry, if it is a workflow
+    fn selected_workflow_index(&self) -> Option<usize> {
+        match self.sidebar.items().get(self.list_state.selected()?) {
+            Some(SidebarItem::Workflow { index }) => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Move the sidebar selection to a workflow, expanding its category if needed
+    fn select_workflow(&mut self, workflow_index: usize) {
+        let id = self.workflows[workflow_index].id.clone();
+        if self.sidebar.workflow_row(&id).is_none() {
+            let category = self.engine.categories().info(&self.workflows[workflow_index].category);
+            self.set_category_collapsed(&category.display_name, false);
+        }
+        if let Some(position) = self.sidebar.workflow_row(&id) {
+            self.list_state.select(Some(position));
+            self.update_preflight_cache();
+        }
+    }
+
     /// Download an asset by index
     fn download_asset(&mut self, asset_index: usize) {
         let assets = self.preflight_checker.get_all_assets_with_status();
@@ -1346,83 +3323,787 @@ impl TuiApp {
                 return;
             }
             
+            if self.asset_downloads.contains(&asset.name) {
+                self.logs.push(format!("Already downloading: {}", asset.name));
+                return;
+            }
+            let downloader = match self.preflight_checker.shared_downloader() {
+                Ok(downloader) => downloader,
+                Err(e) => {
+                    self.logs.push(format!("  {} Download failed: {}", self.glyphs.fail, e));
+                    return;
+                },
+            };
             self.logs.push(format!("Downloading: {}...", asset.name));
-            
-            // Clone what we need before the match
-            let asset_clone = asset.clone();
-            
-            match self.preflight_checker.download_asset(&asset_clone) {
-                Ok(path) => {
-                    self.logs.push(format!("  ✓ Downloaded to: {}", path.display()));
-                    // Refresh preflight cache
-                    self.update_preflight_cache();
+            self.asset_downloads.insert(asset.name.clone());
+
+            // The download blocks, so it runs on the blocking pool and the
+            // UI keeps drawing meanwhile
+            let asset = asset.clone();
+            let sender = self.asset_sender.clone();
+            tokio::task::spawn_blocking(move || {
+                let result = downloader.download(&asset).map_err(|e| format!("{:#}", e));
+                let _ = sender.send((asset, result));
+            });
+        }
+    }
+
+    /// Report a finished sample asset download and recheck what it unblocks
+    fn asset_downloaded(&mut self, asset: AssetDefinition, result: std::result::Result<PathBuf, String>) {
+        self.asset_downloads.remove(&asset.name);
+        match result {
+            Ok(path) => {
+                self.logs.push(format!("  {} Downloaded to: {}", self.glyphs.ok, path.display()));
+                self.preflight_checker.invalidate_asset_cache();
+                self.update_preflight_cache();
+            },
+            Err(e) => {
+                self.logs.push(format!("  {} Download failed: {}", self.glyphs.fail, e));
+            },
+        }
+    }
+
+    async fn run_selected_workflow(&mut self) -> Result<()> {
+        // Get the actual workflow index from the sidebar rows
+        if let Some(index) = self.selected_workflow_index() {
+            self.run_workflow(index).await?;
+        } else if let Some(SidebarItem::Tour { index }) =
+            self.list_state.selected().and_then(|i| self.sidebar.items().get(i))
+        {
+            self.start_tour(*index);
+        }
+        Ok(())
+    }
+
+    /// Cancel running workflows, record them as interrupted and quit
+    async fn handle_interrupt(&mut self) {
+        self.logs.push("=== Interrupted ===".to_string());
+        self.run_queue = None;
+        self.tour_run = None;
+        match self.engine.interrupt().await {
+            Ok(records) => self.interrupted = records,
+            Err(e) => tracing::error!("Failed to record interrupted workflows: {:#}", e),
+        }
+        self.should_quit = true;
+    }
+
+    /// Workflows stopped by Ctrl-C during the session
+    pub fn interrupted_workflows(&self) -> &[InterruptedWorkflowCleanup] {
+        &self.interrupted
+    }
+
+    /// Start presenting a guided tour
+    fn start_tour(&mut self, index: usize) {
+        if self.executing_workflow_id.is_some() || self.run_queue.is_some() {
+            self.logs.push("Wait for the current run to finish before starting a tour".to_string());
+            return;
+        }
+        let tour = self.tours[index].clone();
+        self.logs.push(format!(">>> Starting tour: {}", tour.name));
+        self.tour_run = Some(TourRun::new(tour));
+    }
+
+    /// Present the next tour item once nothing else is on screen or running
+    async fn advance_tour(&mut self) -> Result<()> {
+        if self.popup.is_some() || self.executing_workflow_id.is_some() {
+            return Ok(());
+        }
+        let Some(run) = self.tour_run.as_mut() else {
+            return Ok(());
+        };
+        if run.is_finished() {
+            let name = run.tour.name.clone();
+            self.tour_run = None;
+            self.logs.push(format!("=== Tour finished: {} ===", name));
+            self.show_popup(PopupState {
+                kind: PopupKind::Message,
+                title: format!(" {} ", self.locale.text(Text::TourComplete)),
+                message: self.locale.format(Text::TourFinished, &[&name]),
+                url: None,
+                qr: None,
+            });
+            return Ok(());
+        }
+        let Some(item) = run.take_pending() else {
+            return Ok(());
+        };
+
+        match item {
+            TourItem::Narration { title, text } => {
+                let title = run.title(&title);
+                self.show_popup(PopupState {
+                    kind: PopupKind::Tour,
+                    title,
+                    message: format!("{}\n\n[Enter] continue  [Esc] end tour", text),
+                    url: None,
+                    qr: None,
+                });
+            },
+            TourItem::Checkpoint(checkpoint) => {
+                let title = run.title("Checkpoint");
+                self.show_popup(PopupState {
+                    kind: PopupKind::Tour,
+                    title,
+                    message: tour::checkpoint_message(&checkpoint, None),
+                    url: None,
+                    qr: None,
+                });
+            },
+            TourItem::Workflow(id) => {
+                run.wait_for(&id);
+                let started = match self.workflows.iter().position(|w| w.id == id) {
+                    Some(index) => {
+                        self.select_workflow(index);
+                        self.run_workflow(index).await?
+                    },
+                    None => false,
+                };
+                if !started {
+                    // Keep any "Missing Requirements" popup; the tour resumes once it is closed
+                    self.logs.push(format!("!!! Tour skips workflow '{}': not runnable", id));
+                    if let Some(run) = self.tour_run.as_mut() {
+                        run.next();
+                    }
                 }
-                Err(e) => {
-                    self.logs.push(format!("  ✗ Download failed: {}", e));
+            },
+        }
+        Ok(())
+    }
+
+    /// Handle a key while a tour popup is shown
+    fn handle_tour_popup_key(&mut self, code: KeyCode) {
+        let Some(run) = self.tour_run.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => {
+                self.logs.push(format!("=== Tour ended: {} ===", run.tour.name));
+                self.tour_run = None;
+                self.close_popup();
+            },
+            KeyCode::Char(c) if run.open_checkpoint().is_some() && c.is_ascii_digit() => {
+                let choice = c.to_digit(10).unwrap_or_default() as usize;
+                if let (Some(message), Some(popup)) = (run.answer(choice), self.popup.as_mut()) {
+                    popup.message = message;
                 }
+            },
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                run.next();
+                self.close_popup();
+            },
+            _ => {},
+        }
+    }
+
+    /// Retry, skip or abort the failed step of the run holding on it
+    async fn handle_decision_key(&mut self, handle: ExecutionHandle, code: KeyCode) {
+        let decision = match code {
+            KeyCode::Char('r') | KeyCode::Char('R') | KeyCode::Enter => StepDecision::Retry,
+            KeyCode::Char('s') | KeyCode::Char('S') => StepDecision::Skip,
+            KeyCode::Char('a') | KeyCode::Char('A') | KeyCode::Esc => StepDecision::Abort,
+            _ => return,
+        };
+        self.close_popup();
+        match decision {
+            StepDecision::Retry => self.logs.push("  [RETRY] Running the step again".to_string()),
+            StepDecision::Skip => {},
+            StepDecision::Abort => self.logs.push("  [ABORT] Failing the workflow".to_string()),
+        }
+        if let Err(e) = self.engine.decide(&handle, decision).await {
+            self.logs.push(format!("!!! {:#}", e));
+        }
+    }
+
+    /// Offer the selected workflow's profiles in a popup
+    fn open_profile_picker(&mut self) {
+        let Some(index) = self.selected_workflow_index() else {
+            return;
+        };
+        let id = self.workflows[index].id.clone();
+        let Some(def) = self.workflow_definitions.get(&id) else {
+            return;
+        };
+        if def.profiles.is_empty() {
+            self.logs.push(format!("    {} has no profiles", self.workflows[index].name));
+            return;
+        }
+
+        let current = self.selected_profiles.get(&id);
+        let marker = |selected: bool| if selected { "*" } else { " " };
+        let mut message = format!("{} [0] Full workflow ({} steps)\n", marker(current.is_none()), def.steps.len());
+        let names: Vec<String> = def.profiles.keys().take(9).cloned().collect();
+        for (i, name) in names.iter().enumerate() {
+            let steps = def.with_profile(name).map(|d| d.steps.len()).unwrap_or(0);
+            message.push_str(&format!(
+                "{} [{}] {} ({} steps)\n",
+                marker(current == Some(name)),
+                i + 1,
+                name,
+                steps
+            ));
+        }
+        message.push_str("\nPress a number to choose, Esc to keep the current one");
+        self.show_popup(PopupState {
+            kind: PopupKind::Profiles,
+            title: format!(" Profile for {} ", self.workflows[index].name),
+            message,
+            url: None,
+            qr: None,
+        });
+        self.profile_picker = Some((id, names));
+    }
+
+    /// Choose a profile by its number in the picker
+    fn handle_profile_key(&mut self, code: KeyCode) {
+        let choice = match code {
+            KeyCode::Char(c) => match c.to_digit(10) {
+                Some(digit) => digit as usize,
+                None => return,
+            },
+            KeyCode::Esc => {
+                self.profile_picker = None;
+                self.close_popup();
+                return;
+            },
+            _ => return,
+        };
+        let Some((workflow_id, names)) = self.profile_picker.as_ref() else {
+            return;
+        };
+        if choice > names.len() {
+            return;
+        }
+        let workflow_id = workflow_id.clone();
+        if choice == 0 {
+            self.selected_profiles.remove(&workflow_id);
+            self.logs.push(format!("    {} runs every step", workflow_id));
+        } else {
+            let name = names[choice - 1].clone();
+            self.logs.push(format!("    {} runs profile '{}'", workflow_id, name));
+            self.selected_profiles.insert(workflow_id, name);
+        }
+        self.profile_picker = None;
+        self.close_popup();
+    }
+
+    /// Open the auth panel and gather the current credentials
+    fn open_auth_panel(&mut self) {
+        self.show_popup(PopupState {
+            kind: PopupKind::Auth,
+            title: " Credentials ".to_string(),
+            message: "Checking credentials...".to_string(),
+            url: None,
+            qr: None,
+        });
+        self.auth_panel = Some(AuthInfo::default());
+        self.request_auth(None);
+    }
+
+    /// Run an auth action (or none) and then refresh the auth panel
+    fn request_auth(&mut self, action: Option<AuthAction>) {
+        let sender = self.auth_sender.clone();
+        let client = self.raps_client();
+        tokio::spawn(async move {
+            let _ = sender.send(credentials::fetch_auth_info(&client, action).await);
+        });
+    }
+
+    /// Refresh, log in, log out or close from the auth panel
+    fn handle_auth_key(&mut self, code: KeyCode) {
+        let action = match code {
+            KeyCode::Char('r') => AuthAction::Refresh,
+            KeyCode::Char('l') => AuthAction::Login,
+            KeyCode::Char('x') => AuthAction::Logout,
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.auth_panel = None;
+                self.close_popup();
+                return;
+            },
+            _ => return,
+        };
+        self.logs.push(format!("    Running raps auth {}...", format!("{:?}", action).to_lowercase()));
+        self.request_auth(Some(action));
+    }
+
+    /// Offer the derivatives of the previewed model for download in a popup
+    fn open_derivative_picker(&mut self) {
+        let Some(preview) = self.result_preview.as_ref().filter(|p| !p.loading) else {
+            self.logs.push("No translated model yet; run a Model Derivative workflow first".to_string());
+            return;
+        };
+        let files = preview
+            .manifest
+            .as_ref()
+            .map(translation::downloadable_derivatives)
+            .unwrap_or_default();
+        if files.is_empty() {
+            self.logs.push(format!("    No downloadable derivatives in the manifest of {}", preview.urn));
+            return;
+        }
+        let picker = DerivativePicker::new(&preview.workflow_id, &preview.urn, files);
+        self.show_popup(PopupState {
+            kind: PopupKind::Derivatives,
+            title: " Download Derivatives ".to_string(),
+            message: picker.message(),
+            url: None,
+            qr: None,
+        });
+        self.derivative_picker = Some(picker);
+    }
+
+    /// Pick derivatives by number, then download them with Enter
+    fn handle_derivative_key(&mut self, code: KeyCode) {
+        let Some(picker) = self.derivative_picker.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Char('a') => picker.toggle_all(),
+            KeyCode::Char(c) => match c.to_digit(10) {
+                Some(digit) if digit > 0 => picker.toggle(digit as usize - 1),
+                _ => return,
+            },
+            KeyCode::Enter => {
+                self.close_popup();
+                if let Some(picker) = self.derivative_picker.take() {
+                    self.download_derivatives(picker);
+                }
+                return;
+            },
+            KeyCode::Esc => {
+                self.derivative_picker = None;
+                self.close_popup();
+                return;
+            },
+            _ => return,
+        }
+        let message = picker.message();
+        if let Some(popup) = self.popup.as_mut() {
+            popup.message = message;
+        }
+    }
+
+    /// The engine's RAPS client, for commands run outside workflows
+    fn raps_client(&self) -> Arc<RapsClient> {
+        Arc::clone(self.engine.executor().raps_client())
+    }
+
+    /// Download the picked derivatives into the artifacts folder in the background
+    fn download_derivatives(&mut self, picker: DerivativePicker) {
+        let formats = picker.chosen_formats();
+        if formats.is_empty() {
+            self.logs.push("No derivatives picked".to_string());
+            return;
+        }
+        let dir = match ConfigPaths::artifacts_dir() {
+            Ok(root) => root
+                .join(&picker.workflow_id)
+                .join(format!("derivatives-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"))),
+            Err(e) => {
+                self.logs.push(format!("!!! No artifacts folder: {:#}", e));
+                return;
+            },
+        };
+        self.logs.push(format!("    Downloading {} into {}", formats.join(", "), dir.display()));
+        let sender = self.download_sender.clone();
+        let client = self.raps_client();
+        tokio::spawn(async move {
+            let downloads = preview::download_derivatives(&client, &picker.urn, &formats, &dir).await;
+            let _ = sender.send((dir, downloads));
+        });
+    }
+
+    /// Report finished derivative downloads
+    fn derivatives_downloaded(&mut self, dir: PathBuf, downloads: Vec<DerivativeDownload>) {
+        for download in &downloads {
+            match &download.error {
+                None => self.logs.push(format!("  {} Downloaded {}", self.glyphs.ok, download.format)),
+                Some(error) => self.logs.push(format!(
+                    "  {} Download of {} failed: {}",
+                    self.glyphs.fail, download.format, error
+                )),
             }
         }
+        if downloads.iter().any(|d| d.error.is_none()) {
+            self.logs.push(format!(
+                "    Saved to {} (press {} to open)",
+                dir.display(),
+                self.keymap.keys_label(Action::OpenArtifacts)
+            ));
+            self.last_artifacts_dir = Some(dir);
+        }
     }
 
-    async fn run_selected_workflow(&mut self) -> Result<()> {
-        // Get the actual workflow index from sidebar_items
-        if let Some(selected) = self.list_state.selected() {
-            if let Some(SidebarItem::Workflow { index: workflow_index }) = self.sidebar_items.get(selected) {
-                let metadata = &self.workflows[*workflow_index];
-                
-                // Check preflight status before running
-                let preflight = self.preflight_checker.check(metadata);
-                
-                if !preflight.all_passed {
-                    // Show popup with missing requirements
-                    let blockers = preflight.blocking_checks.join(", ");
-                    
-                    // Check if assets can be downloaded
-                    let has_downloadable = preflight.checks.iter().any(|c| {
-                        matches!(&c.action, Some(CheckAction::DownloadAssets(_)))
-                    });
-                    
-                    if has_downloadable {
-                        self.popup = Some(PopupState {
-                            title: " Missing Requirements ".to_string(),
-                            message: format!(
-                                "Cannot run '{}'\n\nMissing: {}\n\nGo to Assets tab (press 4) to download required files.",
-                                metadata.name, blockers
-                            ),
-                            url: None,
-                        });
-                    } else {
-                        self.popup = Some(PopupState {
-                            title: " Missing Requirements ".to_string(),
-                            message: format!(
-                                "Cannot run '{}'\n\nMissing: {}\n\nPlease resolve these requirements first.",
-                                metadata.name, blockers
-                            ),
-                            url: None,
-                        });
+    /// Let the tour continue after the workflow it started finished
+    fn tour_workflow_finished(&mut self, workflow_id: &str, success: bool) {
+        let Some(run) = self.tour_run.as_mut() else {
+            return;
+        };
+        if !run.is_waiting_for(workflow_id) {
+            return;
+        }
+        if success {
+            run.next();
+        } else {
+            let title = run.title("Workflow Failed");
+            self.show_popup(PopupState {
+                kind: PopupKind::Tour,
+                title,
+                message: format!(
+                    "'{}' did not complete.\n\n[Enter] continue the tour  [Esc] end tour",
+                    workflow_id
+                ),
+                url: None,
+                qr: None,
+            });
+        }
+    }
+
+    /// Overview text for a tour
+    fn tour_overview(&self, tour: &TourDefinition) -> String {
+        let glyphs = self.glyphs;
+        let mut text = format!("{}\n\nID: {}\nItems: {}\n", glyphs.boxed(&tour.name), tour.id, tour.items.len());
+        let seconds: i64 = tour
+            .workflow_ids()
+            .filter_map(|id| self.workflows.iter().find(|w| w.id == id))
+            .map(|w| w.estimated_duration.num_seconds())
+            .sum();
+        text.push_str(&format!("Duration: ~{} minutes of workflows\n", (seconds + 59) / 60));
+        if !tour.description.is_empty() {
+            text.push_str(&format!("\n{}\n{}\n", glyphs.section("Description"), tour.description));
+        }
+        text.push_str(&format!("\n{}\n", glyphs.section("Agenda")));
+        for (i, item) in tour.items.iter().enumerate() {
+            let line = match item {
+                TourItem::Narration { title, .. } => format!("{} {}", glyphs.narration, title),
+                TourItem::Workflow(id) => match self.workflows.iter().find(|w| &w.id == id) {
+                    Some(w) => format!("{} {}", glyphs.play, w.name),
+                    None => format!("{} {} (unknown workflow)", glyphs.fail, id),
+                },
+                TourItem::Checkpoint(checkpoint) => format!("? {}", checkpoint.question),
+            };
+            text.push_str(&format!("  {:>2}. {}\n", i + 1, line));
+        }
+        text.push_str("\nPress ENTER to start the tour");
+        text
+    }
+
+
+    /// Run the workflow at `workflow_index`, returning whether it was started
+    async fn run_workflow(&mut self, workflow_index: usize) -> Result<bool> {
+        self.run_workflow_from(workflow_index, None).await
+    }
+
+    /// Run the selected workflow from the step at the top of the Steps tab,
+    /// reusing the outputs the skipped steps produced in the last run
+    async fn run_from_top_step(&mut self) {
+        let Some(index) = self.selected_workflow_index() else {
+            return;
+        };
+        let step_id = self
+            .workflow_definitions
+            .get(&self.workflows[index].id)
+            .and_then(|def| def.steps.get(self.steps_scroll))
+            .map(|step| step.id.clone());
+        if let Err(e) = self.run_workflow_from(index, step_id).await {
+            self.logs.push(format!("!!! {:#}", e));
+        }
+    }
+
+    /// Run a workflow, starting at `start_at_step` if given
+    async fn run_workflow_from(&mut self, workflow_index: usize, start_at_step: Option<String>) -> Result<bool> {
+        // Check preflight status before running
+        let preflight = self.preflight(workflow_index);
+        let metadata = &self.workflows[workflow_index];
+
+        if !preflight.all_passed {
+            // Show popup with missing requirements
+            let locale = self.locale;
+            let blockers = preflight
+                .blocking_checks
+                .iter()
+                .map(|c| locale.check_name(c))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mut reason = format!(
+                "{}\n\n{}",
+                locale.format(Text::CannotRun, &[&metadata.name]),
+                locale.format(Text::Missing, &[&blockers])
+            );
+            if !preflight.blocked_steps.is_empty() {
+                reason.push('\n');
+                reason.push_str(&locale.format(Text::StepsNeedAssets, &[&preflight.blocked_steps.join(", ")]));
+            }
+
+            // Check if assets can be downloaded
+            let has_downloadable = preflight.checks.iter().any(|c| {
+                matches!(&c.action, Some(CheckAction::DownloadAssets(_)))
+            });
+
+            if has_downloadable {
+                self.show_popup(PopupState {
+                    kind: PopupKind::Message,
+                    title: format!(" {} ", locale.text(Text::MissingRequirements)),
+                    message: format!("{}\n\n{}", reason, locale.text(Text::DownloadFromAssetsTab)),
+                    url: None,
+                    qr: None,
+                });
+            } else {
+                // Point at the token guide when authentication is what's missing
+                let auth_missing = preflight.blocking_checks.iter().any(|c| c == "Authentication");
+                self.show_popup(PopupState {
+                    kind: PopupKind::Message,
+                    title: format!(" {} ", locale.text(Text::MissingRequirements)),
+                    message: format!("{}\n\n{}", reason, locale.text(Text::ResolveRequirements)),
+                    url: auth_missing.then(|| crate::config::auth::OAUTH_GUIDE_URL.to_string()),
+                    qr: None,
+                });
+            }
+            return Ok(false);
+        }
+
+        if self.workflow_definitions.contains_key(&metadata.id) {
+            self.logs
+                .push(format!(">>> Executing workflow: {}", metadata.name));
+
+            // The engine spawns the execution in the background; it runs
+            // through its steps unless paused with the pause key, and holds
+            // on a failed step until retry, skip or abort is chosen
+            let mut options = ExecutionOptions {
+                interactive: false,
+                ask_on_failure: true,
+                profile: self.selected_profiles.get(&metadata.id).cloned(),
+                ..self.engine.execution_options()
+            };
+            options.params.extend(self.data_params.clone());
+            if let Some(profile) = &options.profile {
+                self.logs.push(format!("    Profile: {}", profile));
+            }
+            if let Some(step_id) = start_at_step {
+                self.logs.push(format!("    Starting at step '{}'", step_id));
+                // Outputs captured in the last run of this workflow stand in
+                // for skipped steps, under their step and as bare `{key}`s
+                let last_run = self.timeline_workflow_id.as_ref() == Some(&metadata.id);
+                if let Some(definition) = self.workflow_definitions.get(&metadata.id).filter(|_| last_run) {
+                    let declared = &definition.params;
+                    for entry in &self.variables {
+                        if let VariableSource::StepOutput(source_step) = &entry.source {
+                            options
+                                .params
+                                .insert(format!("steps.{}.{}", source_step, entry.name), entry.value.clone());
+                            if !declared.contains_key(&entry.name) {
+                                options.params.insert(entry.name.clone(), entry.value.clone());
+                            }
+                        }
                     }
-                    return Ok(());
                 }
+                options.start_at_step = Some(step_id);
+            }
+            if let Some(definition) = self.workflow_definitions.get(&metadata.id) {
+                let runs = definition.run_steps(options.profile.as_deref(), options.start_at_step.as_deref());
+                self.run_steps = Some((metadata.id.clone(), runs));
+            }
+            self.engine.execute(&metadata.id, options).await?;
+            Ok(true)
+        } else {
+            self.logs.push(format!(
+                "!!! Workflow definition not found: {}",
+                metadata.id
+            ));
+            Ok(false)
+        }
+    }
 
-                // Use cached workflow definition instead of re-discovering
-                if let Some(definition) = self.workflow_definitions.get(&metadata.id) {
-                    let definition = definition.clone();
-                    self.logs
-                        .push(format!(">>> Executing workflow: {}", metadata.name));
+    /// Add or remove the selected workflow from the favorites
+    fn toggle_favorite(&mut self) {
+        let Some(index) = self.selected_workflow_index() else {
+            return;
+        };
+        let id = self.workflows[index].id.clone();
+        if let Some(pos) = self.favorites.iter().position(|f| *f == id) {
+            self.favorites.remove(pos);
+        } else {
+            self.favorites.push(id);
+        }
+        self.rebuild_sidebar_items();
 
-                    let options = crate::workflow::ExecutionOptions::default();
-                    let executor: Arc<WorkflowExecutor> = Arc::clone(&self.executor);
+        // Persist right away so favorites survive a crash
+        if let Err(e) = self.ui_state().save() {
+            tracing::warn!("Failed to save favorites: {:#}", e);
+        }
+    }
 
-                    // execute_workflow spawns in background
-                    executor.execute_workflow(definition, options).await?;
-                } else {
-                    self.logs.push(format!(
-                        "!!! Workflow definition not found: {}",
-                        metadata.id
-                    ));
-                }
+    /// Mark or unmark the selected workflow for bulk actions
+    fn toggle_mark(&mut self) {
+        let Some(index) = self.selected_workflow_index() else {
+            return;
+        };
+        let id = self.workflows[index].id.clone();
+        if !self.marked_workflows.remove(&id) {
+            self.marked_workflows.insert(id);
+        }
+    }
+
+    /// Marked workflows in sidebar order, or the selected one if none are marked
+    fn bulk_targets(&self) -> Vec<usize> {
+        if self.marked_workflows.is_empty() {
+            return self.selected_workflow_index().into_iter().collect();
+        }
+        (0..self.workflows.len())
+            .filter(|&i| self.marked_workflows.contains(&self.workflows[i].id))
+            .collect()
+    }
+
+    /// Queue the marked workflows to run one after another
+    fn start_bulk_run(&mut self) {
+        if self.run_queue.is_some() || self.executing_workflow_id.is_some() {
+            self.logs.push("A workflow is already running".to_string());
+            return;
+        }
+        let ids: Vec<String> = self
+            .bulk_targets()
+            .into_iter()
+            .map(|i| self.workflows[i].id.clone())
+            .collect();
+        if ids.is_empty() {
+            return;
+        }
+        self.logs.push(format!(">>> Queued {} workflows: {}", ids.len(), ids.join(", ")));
+        self.run_queue = Some(RunQueue::new(ids));
+    }
+
+    /// Start the next queued workflow when nothing is running
+    async fn advance_run_queue(&mut self) -> Result<()> {
+        let Some(queue) = self.run_queue.as_mut() else {
+            return Ok(());
+        };
+        if queue.is_done() {
+            let summary = queue.summary();
+            self.run_queue = None;
+            self.logs.push(format!("=== Queued run finished: {} ===", summary.lines().next().unwrap_or_default()));
+            self.show_popup(PopupState {
+                kind: PopupKind::Message,
+                title: format!(" {} ", self.locale.text(Text::QueuedRunComplete)),
+                message: summary,
+                url: None,
+                qr: None,
+            });
+            return Ok(());
+        }
+        let Some(id) = queue.start_next() else {
+            return Ok(());
+        };
+
+        let remaining = queue.remaining();
+        let shown = self.popup_count();
+        let started = match self.workflows.iter().position(|w| w.id == id) {
+            Some(index) => self.run_workflow(index).await?,
+            None => false,
+        };
+        if started {
+            self.logs.push(format!("    ({} more queued)", remaining));
+        } else if self.run_queue.is_some() {
+            // Keep going with the rest of the queue, without its "Missing Requirements" popup
+            if self.popup_count() > shown {
+                self.discard_last_popup();
+            }
+            self.logs.push(format!("!!! Skipping queued workflow '{}': not runnable", id));
+            if let Some(queue) = self.run_queue.as_mut() {
+                queue.finish_current(false);
             }
         }
         Ok(())
     }
+
+    /// Record the outcome of a queued workflow; returns whether it was queued
+    fn finish_queued_workflow(&mut self, workflow_id: &str, success: bool) -> bool {
+        match self.run_queue.as_mut() {
+            Some(queue) if queue.current() == Some(workflow_id) => {
+                queue.finish_current(success);
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Validate the marked workflows and show the results
+    fn validate_marked(&mut self) {
+        let mut lines = Vec::new();
+        for index in self.bulk_targets() {
+            let id = self.workflows[index].id.clone();
+            match self.engine.validate(&id) {
+                Ok(result) if result.is_valid => {
+                    let warnings = match result.warnings.len() {
+                        0 => String::new(),
+                        n => format!(" ({} warnings)", n),
+                    };
+                    lines.push(format!("✓ {}{}", id, warnings));
+                },
+                Ok(result) => {
+                    lines.push(format!("✗ {}", id));
+                    lines.extend(result.errors.iter().map(|e| format!("    {}", e)));
+                },
+                Err(e) => lines.push(format!("✗ {}: {}", id, e)),
+            }
+        }
+        if lines.is_empty() {
+            return;
+        }
+        self.show_popup(PopupState {
+            kind: PopupKind::Message,
+            title: format!(" {} ", self.locale.text(Text::Validation)),
+            message: lines.join("\n"),
+            url: None,
+            qr: None,
+        });
+    }
+
+    /// Export Mermaid and SVG diagrams for the marked workflows
+    fn export_marked(&mut self) {
+        use crate::workflow::diagram::{render_diagram, DiagramFormat};
+
+        let export_dir = std::path::Path::new("./exports");
+        let mut exported = 0;
+        for index in self.bulk_targets() {
+            let id = &self.workflows[index].id;
+            let Some(definition) = self.workflow_definitions.get(id) else {
+                continue;
+            };
+            let result = std::fs::create_dir_all(export_dir).and_then(|_| {
+                [DiagramFormat::Mermaid, DiagramFormat::Svg]
+                    .into_iter()
+                    .try_for_each(|format| {
+                        let path = export_dir.join(format!("{}.{}", id, format.extension()));
+                        std::fs::write(path, render_diagram(definition, format))
+                    })
+            });
+            match result {
+                Ok(()) => exported += 1,
+                Err(e) => self.logs.push(format!("Export of '{}' failed: {}", id, e)),
+            }
+        }
+        self.logs.push(format!(
+            "Exported {} workflow diagrams to {}",
+            exported,
+            export_dir.display()
+        ));
+    }
+
+    /// Show the combined cost estimate of the marked workflows
+    fn estimate_marked(&mut self) {
+        let targets: Vec<&WorkflowMetadata> = self
+            .bulk_targets()
+            .into_iter()
+            .map(|i| &self.workflows[i])
+            .collect();
+        if targets.is_empty() {
+            return;
+        }
+        self.show_popup(PopupState {
+            kind: PopupKind::Message,
+            title: format!(" Estimate ({} workflows) ", targets.len()),
+            message: bulk::combined_estimate(&targets),
+            url: None,
+            qr: None,
+        });
+    }
 }
@@ -2,13 +2,16 @@
 //!
 //! Validates that all prerequisites are met before running a workflow:
 //! - Authentication status
-//! - Required asset files
+//! - Required asset files (workflow-level and per step)
 //! - Other prerequisites (permissions, external tools)
 
 use std::path::{Path, PathBuf};
 use std::cell::RefCell;
+use std::sync::Arc;
+use crate::config::types::ConfigPaths;
 use crate::assets::{AssetCategory, AssetDefinition, AssetDownloader, AssetRegistry, AssetStatus};
-use crate::workflow::{WorkflowMetadata, PrerequisiteType};
+use crate::workflow::{ExecutionStep, PrerequisiteType, WorkflowDefinition, WorkflowMetadata};
+use super::i18n::{Locale, Text};
 
 /// Result of a single pre-flight check
 #[derive(Debug, Clone)]
@@ -38,6 +41,8 @@ pub struct PreflightStatus {
     pub checks: Vec<CheckResult>,
     pub all_passed: bool,
     pub blocking_checks: Vec<String>,
+    /// Steps whose `requires_assets` are missing
+    pub blocked_steps: Vec<String>,
 }
 
 impl PreflightStatus {
@@ -61,6 +66,18 @@ impl PreflightStatus {
     }
 }
 
+/// Known sample assets plus those listed in `./assets.yaml`, if present
+fn local_registry() -> AssetRegistry {
+    let manifest = Path::new(ConfigPaths::DEFAULT_ASSETS_MANIFEST);
+    if !manifest.exists() {
+        return AssetRegistry::new();
+    }
+    AssetRegistry::new().with_manifest(manifest).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring asset manifest: {:#}", e);
+        AssetRegistry::new()
+    })
+}
+
 /// Pre-flight checker for workflow execution
 pub struct PreflightChecker {
     /// Base directory for assets
@@ -68,22 +85,49 @@ pub struct PreflightChecker {
     /// Asset registry for looking up available assets
     registry: AssetRegistry,
     /// Cached downloader to avoid recreating HTTP client on every call
-    cached_downloader: RefCell<Option<AssetDownloader>>,
+    cached_downloader: RefCell<Option<Arc<AssetDownloader>>>,
     /// Cached asset status (asset definitions with download status)
     cached_assets_status: RefCell<Option<Vec<(AssetDefinition, bool)>>>,
+    /// Language of the check messages (check names stay English keys)
+    locale: Locale,
 }
 
 impl PreflightChecker {
     /// Create a new pre-flight checker
     pub fn new() -> Self {
         Self {
-            assets_dir: PathBuf::from("./sample-models/autodesk"),
-            registry: AssetRegistry::new(),
+            assets_dir: PathBuf::from(ConfigPaths::DEFAULT_ASSETS_DIR),
+            registry: local_registry(),
             cached_downloader: RefCell::new(None),
             cached_assets_status: RefCell::new(None),
+            locale: Locale::default(),
         }
     }
+
+    /// Set the language of the check messages
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
     
+    /// Look up missing assets in `registry` instead of the known samples
+    pub fn with_registry(mut self, registry: AssetRegistry) -> Self {
+        self.registry = registry;
+        *self.cached_assets_status.borrow_mut() = None;
+        self
+    }
+
+    /// Also offer the assets listed in the manifest at `path` (e.g. an
+    /// installed pack's `assets.yaml`); an unreadable manifest is skipped
+    pub fn with_asset_manifest<P: AsRef<Path>>(mut self, path: P) -> Self {
+        let path = path.as_ref();
+        match AssetRegistry::from_assets(self.registry.all().to_vec()).with_manifest(path) {
+            Ok(registry) => self = self.with_registry(registry),
+            Err(e) => tracing::warn!("Ignoring asset manifest {}: {:#}", path.display(), e),
+        }
+        self
+    }
+
     /// Set the assets directory
     pub fn with_assets_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
         self.assets_dir = dir.as_ref().to_path_buf();
@@ -95,6 +139,15 @@ impl PreflightChecker {
     
     /// Run all pre-flight checks for a workflow
     pub fn check(&self, workflow: &WorkflowMetadata) -> PreflightStatus {
+        self.check_steps(workflow, &[])
+    }
+
+    /// Run all pre-flight checks for a workflow, including its steps' assets
+    pub fn check_definition(&self, definition: &WorkflowDefinition) -> PreflightStatus {
+        self.check_steps(&definition.metadata, &definition.steps)
+    }
+
+    fn check_steps(&self, workflow: &WorkflowMetadata, steps: &[ExecutionStep]) -> PreflightStatus {
         let mut checks = Vec::new();
         let mut all_passed = true;
         let mut blocking = Vec::new();
@@ -108,7 +161,7 @@ impl PreflightChecker {
         checks.push(auth_check);
         
         // Check required assets
-        let assets_check = self.check_assets(workflow);
+        let assets_check = self.check_assets(workflow, steps);
         if !assets_check.passed {
             all_passed = false;
             blocking.push("Assets".to_string());
@@ -141,10 +194,17 @@ impl PreflightChecker {
             }
         }
         
+        let blocked_steps = steps
+            .iter()
+            .filter(|step| step.requires_assets.iter().any(|asset| !asset.exists()))
+            .map(|step| step.id.clone())
+            .collect();
+
         PreflightStatus {
             checks,
             all_passed,
             blocking_checks: blocking,
+            blocked_steps,
         }
     }
     
@@ -159,7 +219,7 @@ impl PreflightChecker {
             return CheckResult {
                 name: "Authentication".to_string(),
                 passed: true,
-                message: "Not required for this workflow".to_string(),
+                message: self.locale.text(Text::AuthNotRequired).to_string(),
                 action: None,
             };
         }
@@ -174,14 +234,14 @@ impl PreflightChecker {
             CheckResult {
                 name: "Authentication".to_string(),
                 passed: true,
-                message: "APS credentials found".to_string(),
+                message: self.locale.text(Text::AuthFound).to_string(),
                 action: None,
             }
         } else {
             CheckResult {
                 name: "Authentication".to_string(),
                 passed: false,
-                message: "APS authentication required".to_string(),
+                message: self.locale.text(Text::AuthRequired).to_string(),
                 action: Some(CheckAction::Login),
             }
         }
@@ -200,12 +260,20 @@ impl PreflightChecker {
     }
     
     /// Check if required assets are available
-    fn check_assets(&self, workflow: &WorkflowMetadata) -> CheckResult {
-        if workflow.required_assets.is_empty() {
+    fn check_assets(&self, workflow: &WorkflowMetadata, steps: &[ExecutionStep]) -> CheckResult {
+        let mut required: Vec<&PathBuf> = Vec::new();
+        let step_assets = steps.iter().flat_map(|step| &step.requires_assets);
+        for asset_path in workflow.required_assets.iter().chain(step_assets) {
+            if !required.contains(&asset_path) {
+                required.push(asset_path);
+            }
+        }
+
+        if required.is_empty() {
             return CheckResult {
                 name: "Assets".to_string(),
                 passed: true,
-                message: "No assets required".to_string(),
+                message: self.locale.text(Text::NoAssetsRequired).to_string(),
                 action: None,
             };
         }
@@ -213,7 +281,7 @@ impl PreflightChecker {
         let mut missing_files: Vec<&PathBuf> = Vec::new();
         let mut missing_assets: Vec<AssetDefinition> = Vec::new();
         
-        for asset_path in &workflow.required_assets {
+        for asset_path in required.iter().copied() {
             // Check if the file exists
             if !asset_path.exists() {
                 missing_files.push(asset_path);
@@ -231,14 +299,16 @@ impl PreflightChecker {
             CheckResult {
                 name: "Assets".to_string(),
                 passed: true,
-                message: format!("{} asset(s) available", workflow.required_assets.len()),
+                message: self
+                    .locale
+                    .format(Text::AssetsAvailable, &[&required.len().to_string()]),
                 action: None,
             }
         } else {
             let msg = if missing_assets.is_empty() {
-                format!("{} file(s) missing", missing_files.len())
+                self.locale.format(Text::FilesMissing, &[&missing_files.len().to_string()])
             } else {
-                format!("{} asset(s) need download", missing_assets.len())
+                self.locale.format(Text::AssetsNeedDownload, &[&missing_assets.len().to_string()])
             };
             
             CheckResult {
@@ -248,7 +318,7 @@ impl PreflightChecker {
                 action: if missing_assets.is_empty() {
                     Some(CheckAction::Instruction(
                         format!("Missing files: {:?}", missing_files.iter()
-                            .map(|p| p.display().to_string())
+                            .map(|p| describe_missing(p, steps))
                             .collect::<Vec<_>>())
                     ))
                 } else {
@@ -258,6 +328,11 @@ impl PreflightChecker {
         }
     }
     
+    /// Whether a known sample asset provides the missing `asset_path`
+    pub fn is_downloadable(&self, asset_path: &Path) -> bool {
+        self.find_matching_asset(asset_path).is_some()
+    }
+
     /// Find a matching asset definition for a required asset path
     fn find_matching_asset(&self, asset_path: &Path) -> Option<AssetDefinition> {
         let path_str = asset_path.to_string_lossy().to_lowercase();
@@ -289,15 +364,17 @@ impl PreflightChecker {
                 if path_str.contains("stapler") && filename.contains("inventor") && filename.contains("sample") {
                     return Some((*asset).clone());
                 }
-                if path_str.contains("basic") && path_str.contains("revit") {
-                    if asset.name.to_lowercase().contains("basic") {
-                        return Some((*asset).clone());
-                    }
+                if path_str.contains("basic")
+                    && path_str.contains("revit")
+                    && asset.name.to_lowercase().contains("basic")
+                {
+                    return Some((*asset).clone());
                 }
-                if path_str.contains("advanced") && path_str.contains("revit") {
-                    if asset.name.to_lowercase().contains("advanced") {
-                        return Some((*asset).clone());
-                    }
+                if path_str.contains("advanced")
+                    && path_str.contains("revit")
+                    && asset.name.to_lowercase().contains("advanced")
+                {
+                    return Some((*asset).clone());
                 }
             }
             
@@ -315,7 +392,7 @@ impl PreflightChecker {
     fn ensure_downloader(&self) -> anyhow::Result<()> {
         let mut cached = self.cached_downloader.borrow_mut();
         if cached.is_none() {
-            *cached = Some(AssetDownloader::new(&self.assets_dir)?);
+            *cached = Some(Arc::new(AssetDownloader::new(&self.assets_dir)?));
         }
         Ok(())
     }
@@ -324,9 +401,16 @@ impl PreflightChecker {
     pub fn get_downloader(&self) -> anyhow::Result<std::cell::Ref<'_, AssetDownloader>> {
         self.ensure_downloader()?;
         Ok(std::cell::Ref::map(self.cached_downloader.borrow(), |opt| {
-            opt.as_ref().expect("downloader should be initialized")
+            opt.as_deref().expect("downloader should be initialized")
         }))
     }
+
+    /// The asset downloader, for a download running on another thread
+    pub fn shared_downloader(&self) -> anyhow::Result<Arc<AssetDownloader>> {
+        self.ensure_downloader()?;
+        let cached = self.cached_downloader.borrow();
+        Ok(Arc::clone(cached.as_ref().expect("downloader should be initialized")))
+    }
     
     /// Get the current asset status
     pub fn get_asset_status(&self) -> anyhow::Result<AssetStatus> {
@@ -374,12 +458,12 @@ impl PreflightChecker {
         let result = downloader.download(asset);
         // Invalidate cache after download
         self.invalidate_asset_cache();
-        result
+        Ok(result?)
     }
     
     /// Download all missing assets for a workflow
     pub fn download_workflow_assets(&self, workflow: &WorkflowMetadata) -> anyhow::Result<Vec<PathBuf>> {
-        let check = self.check_assets(workflow);
+        let check = self.check_assets(workflow, &[]);
         
         if let Some(CheckAction::DownloadAssets(assets)) = check.action {
             let downloader = self.get_downloader()?;
@@ -397,6 +481,20 @@ impl PreflightChecker {
     }
 }
 
+/// `path` followed by the steps that need it
+fn describe_missing(path: &Path, steps: &[ExecutionStep]) -> String {
+    let needed_by: Vec<&str> = steps
+        .iter()
+        .filter(|step| step.requires_assets.iter().any(|asset| asset == path))
+        .map(|step| step.id.as_str())
+        .collect();
+    if needed_by.is_empty() {
+        path.display().to_string()
+    } else {
+        format!("{} (step {})", path.display(), needed_by.join(", "))
+    }
+}
+
 impl Default for PreflightChecker {
     fn default() -> Self {
         Self::new()
@@ -412,4 +510,20 @@ mod tests {
         let checker = PreflightChecker::new();
         assert!(checker.assets_dir.ends_with("autodesk"));
     }
+
+    #[test]
+    fn test_asset_manifests_add_to_the_registry() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = dir.path().join("assets.yaml");
+        std::fs::write(
+            &manifest,
+            "assets:\n  - name: Bracket\n    description: Pack part\n    url: https://example.com/pack/bracket.ipt\n    category: inventor\n",
+        )
+        .unwrap();
+
+        let checker = PreflightChecker::new().with_registry(AssetRegistry::from_assets(Vec::new()));
+        let checker = checker.with_asset_manifest(&manifest).with_asset_manifest(dir.path().join("missing.yaml"));
+        let names: Vec<&str> = checker.registry.all().iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["Bracket"]);
+    }
 }
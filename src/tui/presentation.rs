@@ -0,0 +1,119 @@
+// Presentation mode helpers
+//
+// Presentation mode hides the sidebar, console and help bar and shows the
+// current step as large box-drawing text above an enlarged flowchart, so a
+// demo stays readable on a projector. Views advance on their own: the
+// flowchart while a workflow runs, its results once it has finished.
+
+/// Height of big text in rows
+pub const BIG_TEXT_HEIGHT: usize = 3;
+
+/// Box-drawing glyph for a character, three columns wide, or `None` if
+/// there is none for it
+fn glyph(c: char) -> Option<[&'static str; BIG_TEXT_HEIGHT]> {
+    let glyph = match c.to_ascii_uppercase() {
+        'A' => ["╭─╮", "├─┤", "╵ ╵"],
+        'B' => ["┌─╮", "├─┤", "└─╯"],
+        'C' => ["╭─╴", "│  ", "╰─╴"],
+        'D' => ["┌─╮", "│ │", "└─╯"],
+        'E' => ["┌─╴", "├─ ", "└─╴"],
+        'F' => ["┌─╴", "├─ ", "╵  "],
+        'G' => ["╭─╴", "│╶╮", "╰─╯"],
+        'H' => ["╷ ╷", "├─┤", "╵ ╵"],
+        'I' => ["╶┬╴", " │ ", "╶┴╴"],
+        'J' => ["  ╷", "  │", "╰─╯"],
+        'K' => ["╷╭╴", "├┤ ", "╵╰╴"],
+        'L' => ["╷  ", "│  ", "└─╴"],
+        'M' => ["╭┬╮", "│││", "╵╵╵"],
+        'N' => ["╭╮╷", "│││", "╵╰╯"],
+        'O' => ["╭─╮", "│ │", "╰─╯"],
+        'P' => ["┌─╮", "├─╯", "╵  "],
+        'Q' => ["╭─╮", "│ │", "╰─╳"],
+        'R' => ["┌─╮", "├┬╯", "╵╰╴"],
+        'S' => ["╭─╴", "╰─╮", "╶─╯"],
+        'T' => ["╶┬╴", " │ ", " ╵ "],
+        'U' => ["╷ ╷", "│ │", "╰─╯"],
+        'V' => ["╷ ╷", "╲ ╱", " ╵ "],
+        'W' => ["╷╷╷", "│││", "╰┴╯"],
+        'X' => ["╲ ╱", " ╳ ", "╱ ╲"],
+        'Y' => ["╷ ╷", "╰┬╯", " ╵ "],
+        'Z' => ["╶─┐", "╭─╯", "└─╴"],
+        '0' => ["╭─╮", "│╱│", "╰─╯"],
+        '1' => ["╶┐ ", " │ ", "╶┴╴"],
+        '2' => ["╶─╮", "╭─╯", "└─╴"],
+        '3' => ["╶─╮", "╶─┤", "╶─╯"],
+        '4' => ["╷ ╷", "└─┤", "  ╵"],
+        '5' => ["┌─╴", "└─╮", "╶─╯"],
+        '6' => ["╭─╴", "├─╮", "╰─╯"],
+        '7' => ["╶─┐", "  │", "  ╵"],
+        '8' => ["╭─╮", "├─┤", "╰─╯"],
+        '9' => ["╭─╮", "╰─┤", "╶─╯"],
+        '-' | '_' => ["   ", "╶─╴", "   "],
+        '.' => ["   ", "   ", " ╷ "],
+        ':' => ["   ", " ╵ ", " ╷ "],
+        '/' => ["  ╱", " ╱ ", "╱  "],
+        ' ' => ["   ", "   ", "   "],
+        _ => return None,
+    };
+    Some(glyph)
+}
+
+/// Columns taken by `text` in big text
+pub fn big_text_width(text: &str) -> usize {
+    let chars = text.chars().count();
+    if chars == 0 {
+        0
+    } else {
+        chars * 4 - 1
+    }
+}
+
+/// Render `text` as big box-drawing text, or `None` if it is wider than
+/// `max_width` or has characters without a glyph (accented letters, CJK,
+/// emoji, ...), which are then shown as plain text
+pub fn big_text(text: &str, max_width: usize) -> Option<Vec<String>> {
+    if big_text_width(text) > max_width {
+        return None;
+    }
+    let glyphs: Vec<_> = text.chars().map(glyph).collect::<Option<_>>()?;
+    Some(
+        (0..BIG_TEXT_HEIGHT)
+            .map(|row| glyphs.iter().map(|g| g[row]).collect::<Vec<_>>().join(" "))
+            .collect(),
+    )
+}
+
+/// What the main area shows in presentation mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresentationView {
+    Flowchart,
+    Results,
+}
+
+/// The flowchart while a workflow runs (or before any run), its results afterwards
+pub fn presentation_view(executing: bool, has_results: bool) -> PresentationView {
+    if !executing && has_results {
+        PresentationView::Results
+    } else {
+        PresentationView::Flowchart
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_big_text() {
+        let lines = big_text("Go 1", 80).unwrap();
+        assert_eq!(lines, vec!["╭─╴ ╭─╮     ╶┐ ", "│╶╮ │ │      │ ", "╰─╯ ╰─╯     ╶┴╴"]);
+        assert!(lines.iter().all(|l| l.chars().count() == big_text_width("Go 1")));
+        assert!(big_text("Upload File", 20).is_none());
+        assert!(big_text("Téléverser", 80).is_none());
+        assert!(big_text("上传", 80).is_none());
+
+        assert_eq!(presentation_view(true, true), PresentationView::Flowchart);
+        assert_eq!(presentation_view(false, true), PresentationView::Results);
+        assert_eq!(presentation_view(false, false), PresentationView::Flowchart);
+    }
+}
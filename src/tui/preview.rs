@@ -0,0 +1,440 @@
+// Model Derivative result preview for the Results tab
+//
+// After a Model Derivative workflow finishes, the thumbnail and manifest of
+// the translated model are fetched through the RAPS CLI. The thumbnail is
+// drawn with half-block characters (two pixels per cell, true color) and the
+// manifest is shown as a derivative tree instead of raw JSON. The derivative
+// browser lists what the manifest offers for download (OBJ, thumbnails, the
+// properties database, ...) and fetches the chosen ones into the artifacts
+// folder.
+
+use anyhow::{Context, Result};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use serde_json::Value;
+
+use crate::workflow::client::RapsClient;
+use crate::workflow::translation::DerivativeFile;
+use crate::workflow::{RapsCommand, TranslateAction, TranslateParams};
+
+/// Background the thumbnail's transparent pixels are blended onto
+const BACKGROUND: [u8; 3] = [0x20, 0x20, 0x20];
+
+/// Decoded thumbnail as rows of RGB pixels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thumbnail {
+    pub width: usize,
+    pub height: usize,
+    /// Row-major RGB pixels
+    pub pixels: Vec<[u8; 3]>,
+}
+
+impl Thumbnail {
+    /// Decode a PNG image (as returned by the Model Derivative thumbnail API)
+    pub fn from_png(bytes: &[u8]) -> Result<Self> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().context("Invalid PNG thumbnail")?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).context("Failed to decode PNG thumbnail")?;
+
+        let channels = info.color_type.samples();
+        let pixels = buffer[..info.buffer_size()]
+            .chunks_exact(channels)
+            .map(|px| match px {
+                [r, g, b, a] => blend([*r, *g, *b], *a),
+                [r, g, b] => [*r, *g, *b],
+                [l, a] => blend([*l, *l, *l], *a),
+                [l] => [*l, *l, *l],
+                _ => BACKGROUND,
+            })
+            .collect();
+
+        Ok(Self {
+            width: info.width as usize,
+            height: info.height as usize,
+            pixels,
+        })
+    }
+
+    /// Pixel at (x, y)
+    fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        self.pixels[y * self.width + x]
+    }
+}
+
+/// Blend a pixel with the given alpha onto the background
+fn blend(rgb: [u8; 3], alpha: u8) -> [u8; 3] {
+    let a = alpha as u16;
+    let mix = |c: u8, bg: u8| ((c as u16 * a + bg as u16 * (255 - a)) / 255) as u8;
+    [
+        mix(rgb[0], BACKGROUND[0]),
+        mix(rgb[1], BACKGROUND[1]),
+        mix(rgb[2], BACKGROUND[2]),
+    ]
+}
+
+/// Render a thumbnail into at most `max_cols` x `max_rows` cells.
+///
+/// Each cell shows two vertically stacked pixels: `▀` in the top pixel's
+/// color over a background of the bottom pixel's color.
+pub fn thumbnail_lines(thumb: &Thumbnail, max_cols: usize, max_rows: usize) -> Vec<Line<'static>> {
+    if thumb.width == 0 || thumb.height == 0 || max_cols == 0 || max_rows == 0 {
+        return Vec::new();
+    }
+
+    // Half blocks give square pixels, so scale uniformly to fit
+    let scale = (max_cols as f64 / thumb.width as f64)
+        .min((max_rows * 2) as f64 / thumb.height as f64)
+        .min(1.0);
+    let cols = ((thumb.width as f64 * scale).round() as usize).max(1);
+    let pixel_rows = ((thumb.height as f64 * scale).round() as usize).max(1);
+
+    let sample = |col: usize, row: usize| {
+        let x = (col * thumb.width / cols).min(thumb.width - 1);
+        let y = (row * thumb.height / pixel_rows).min(thumb.height - 1);
+        let [r, g, b] = thumb.pixel(x, y);
+        Color::Rgb(r, g, b)
+    };
+
+    (0..pixel_rows)
+        .step_by(2)
+        .map(|top| {
+            let spans: Vec<Span> = (0..cols)
+                .map(|col| {
+                    let mut style = Style::default().fg(sample(col, top));
+                    if top + 1 < pixel_rows {
+                        style = style.bg(sample(col, top + 1));
+                    }
+                    Span::styled("▀", style)
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Render a Model Derivative manifest as a tree of derivatives
+pub fn manifest_lines(manifest: &Value) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+
+    let status = manifest.get("status").and_then(Value::as_str).unwrap_or("unknown");
+    let progress = manifest.get("progress").and_then(Value::as_str).unwrap_or("");
+    lines.push(Line::from(vec![
+        Span::styled("Manifest ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::styled(
+            format!("{} {}", status, progress).trim_end().to_string(),
+            Style::default().fg(status_color(status)),
+        ),
+    ]));
+
+    let derivatives = manifest
+        .get("derivatives")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    push_nodes(&mut lines, derivatives, "");
+    lines
+}
+
+/// Append tree lines for `nodes` and their children
+fn push_nodes(lines: &mut Vec<Line<'static>>, nodes: &[Value], prefix: &str) {
+    for (i, node) in nodes.iter().enumerate() {
+        let last = i + 1 == nodes.len();
+        let branch = if last { "└─ " } else { "├─ " };
+        let status = node.get("status").and_then(Value::as_str).unwrap_or("");
+
+        let mut spans = vec![
+            Span::styled(format!("{}{}", prefix, branch), Style::default().fg(Color::DarkGray)),
+            Span::raw(node_label(node)),
+        ];
+        if !status.is_empty() {
+            spans.push(Span::styled(format!("  {}", status), Style::default().fg(status_color(status))));
+        }
+        lines.push(Line::from(spans));
+
+        if let Some(children) = node.get("children").and_then(Value::as_array) {
+            let child_prefix = format!("{}{}", prefix, if last { "   " } else { "│  " });
+            push_nodes(lines, children, &child_prefix);
+        }
+    }
+}
+
+/// Label for a manifest node, e.g. `{3D} [geometry/3d]` or `svf2 (model.rvt)`
+fn node_label(node: &Value) -> String {
+    let text = |key: &str| node.get(key).and_then(Value::as_str);
+
+    if let Some(output_type) = text("outputType") {
+        return match text("name") {
+            Some(name) => format!("{} ({})", output_type, name),
+            None => output_type.to_string(),
+        };
+    }
+
+    let name = text("name").or(text("mime")).unwrap_or("(unnamed)");
+    let kind = [text("type"), text("role")].into_iter().flatten().collect::<Vec<_>>().join("/");
+    if kind.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} [{}]", name, kind)
+    }
+}
+
+/// Color for a manifest status value
+fn status_color(status: &str) -> Color {
+    match status {
+        "success" => Color::Green,
+        "failed" | "timeout" => Color::Red,
+        "inprogress" | "pending" => Color::Yellow,
+        _ => Color::Gray,
+    }
+}
+
+/// Thumbnail and manifest of a translated model
+#[derive(Debug, Clone, Default)]
+pub struct ResultPreview {
+    /// Workflow whose run produced the model
+    pub workflow_id: String,
+    /// URN of the translated model
+    pub urn: String,
+    /// Whether the preview is still being fetched
+    pub loading: bool,
+    pub thumbnail: Option<Thumbnail>,
+    pub manifest: Option<Value>,
+    /// Problems fetching either part
+    pub errors: Vec<String>,
+}
+
+impl ResultPreview {
+    /// Placeholder shown while the preview is fetched
+    pub fn loading(workflow_id: &str, urn: &str) -> Self {
+        Self {
+            workflow_id: workflow_id.to_string(),
+            urn: urn.to_string(),
+            loading: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// Fetch the manifest and thumbnail of `urn` through the RAPS CLI
+pub async fn fetch_preview(client: &RapsClient, workflow_id: &str, urn: &str) -> ResultPreview {
+    let mut preview = ResultPreview {
+        loading: false,
+        ..ResultPreview::loading(workflow_id, urn)
+    };
+
+    let params = |output_dir| TranslateParams {
+        urn: Some(urn.to_string()),
+        format: None,
+        output_dir,
+        wait: None,
+        region: None,
+    };
+
+    let manifest = RapsCommand::Translate {
+        action: TranslateAction::Manifest,
+        params: params(None),
+    };
+    match client.execute_command_async(&manifest).await {
+        Ok(result) if result.success => match result.json_output {
+            Some(json) => preview.manifest = Some(json),
+            None => preview.errors.push("Manifest output was not JSON".to_string()),
+        },
+        Ok(result) => preview.errors.push(format!(
+            "Manifest: {}",
+            result.error_message().unwrap_or_default()
+        )),
+        Err(e) => preview.errors.push(format!("Manifest: {:#}", e)),
+    }
+
+    match fetch_thumbnail(client, params).await {
+        Ok(thumbnail) => preview.thumbnail = Some(thumbnail),
+        Err(e) => preview.errors.push(format!("Thumbnail: {:#}", e)),
+    }
+
+    preview
+}
+
+/// Download the thumbnail into a temporary file and decode it
+async fn fetch_thumbnail<F>(client: &RapsClient, params: F) -> Result<Thumbnail>
+where
+    F: Fn(Option<std::path::PathBuf>) -> TranslateParams,
+{
+    let dir = tempfile::tempdir().context("Failed to create temporary directory")?;
+    let path = dir.path().join("thumbnail.png");
+    let command = RapsCommand::Translate {
+        action: TranslateAction::Thumbnail,
+        params: params(Some(path.clone())),
+    };
+
+    let result = client.execute_command_async(&command).await?;
+    if !result.success {
+        anyhow::bail!("{}", result.error_message().unwrap_or_default());
+    }
+    let bytes = tokio::fs::read(&path)
+        .await
+        .with_context(|| format!("Thumbnail was not written to {:?}", path))?;
+    Thumbnail::from_png(&bytes)
+}
+
+/// Derivatives offered for download, with the ones picked so far
+#[derive(Debug, Clone)]
+pub struct DerivativePicker {
+    /// URN of the translated model
+    pub urn: String,
+    /// Workflow whose run produced the model
+    pub workflow_id: String,
+    pub files: Vec<DerivativeFile>,
+    /// Whether each of `files` will be downloaded
+    pub selected: Vec<bool>,
+}
+
+impl DerivativePicker {
+    /// Picker over `files` with nothing picked
+    pub fn new(workflow_id: &str, urn: &str, files: Vec<DerivativeFile>) -> Self {
+        Self {
+            urn: urn.to_string(),
+            workflow_id: workflow_id.to_string(),
+            selected: vec![false; files.len()],
+            files,
+        }
+    }
+
+    /// Pick or unpick the derivative at `index`
+    pub fn toggle(&mut self, index: usize) {
+        if let Some(selected) = self.selected.get_mut(index) {
+            *selected = !*selected;
+        }
+    }
+
+    /// Pick every derivative, or none if all are picked already
+    pub fn toggle_all(&mut self) {
+        let all = self.selected.iter().all(|s| *s);
+        self.selected.iter_mut().for_each(|s| *s = !all);
+    }
+
+    /// `--format` values of the picked derivatives
+    pub fn chosen_formats(&self) -> Vec<String> {
+        self.files
+            .iter()
+            .zip(&self.selected)
+            .filter(|(_, selected)| **selected)
+            .map(|(file, _)| file.format.clone())
+            .collect()
+    }
+
+    /// Popup text listing the derivatives (at most nine, one per digit)
+    pub fn message(&self) -> String {
+        let mut message = String::new();
+        for (i, (file, selected)) in self.files.iter().zip(&self.selected).take(9).enumerate() {
+            let mark = if *selected { "x" } else { " " };
+            message.push_str(&format!("[{}] {} {}\n", mark, i + 1, file.label));
+        }
+        message.push_str("\nNumber: pick, a: all, Enter: download, Esc: cancel");
+        message
+    }
+}
+
+/// Result of downloading one derivative format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivativeDownload {
+    pub format: String,
+    /// Why the download failed, if it did
+    pub error: Option<String>,
+}
+
+/// Download each of `formats` of `urn` into `dir` through the RAPS CLI
+pub async fn download_derivatives(
+    client: &RapsClient,
+    urn: &str,
+    formats: &[String],
+    dir: &std::path::Path,
+) -> Vec<DerivativeDownload> {
+    let mut downloads = Vec::new();
+    for format in formats {
+        let command = RapsCommand::Translate {
+            action: TranslateAction::Download,
+            params: TranslateParams {
+                urn: Some(urn.to_string()),
+                format: Some(format.clone()),
+                output_dir: Some(dir.to_path_buf()),
+                wait: None,
+                region: None,
+            },
+        };
+        let error = match client.execute_command_async(&command).await {
+            Ok(result) if result.success => None,
+            Ok(result) => Some(result.error_message().unwrap_or_default()),
+            Err(e) => Some(format!("{:#}", e)),
+        };
+        downloads.push(DerivativeDownload {
+            format: format.clone(),
+            error,
+        });
+    }
+    downloads
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumbnail_png_and_half_blocks() {
+        // 2x2 RGB image: red, green / blue, white
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, 2, 2);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer
+                .write_image_data(&[255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255])
+                .unwrap();
+        }
+
+        let thumb = Thumbnail::from_png(&bytes).unwrap();
+        assert_eq!((thumb.width, thumb.height), (2, 2));
+
+        let lines = thumbnail_lines(&thumb, 10, 10);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].spans.len(), 2);
+        assert_eq!(lines[0].spans[0].style.fg, Some(Color::Rgb(255, 0, 0)));
+        assert_eq!(lines[0].spans[0].style.bg, Some(Color::Rgb(0, 0, 255)));
+        assert_eq!(lines[0].spans[1].style.bg, Some(Color::Rgb(255, 255, 255)));
+    }
+
+    #[test]
+    fn test_manifest_tree() {
+        let manifest = serde_json::json!({
+            "status": "success",
+            "progress": "complete",
+            "derivatives": [{
+                "name": "house.rvt",
+                "outputType": "svf2",
+                "status": "success",
+                "children": [
+                    {"name": "{3D}", "type": "geometry", "role": "3d", "status": "success"},
+                    {"type": "resource", "role": "thumbnail", "mime": "image/png"}
+                ]
+            }]
+        });
+
+        let text: Vec<String> = manifest_lines(&manifest)
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(
+            text,
+            vec![
+                "Manifest success complete",
+                "└─ svf2 (house.rvt)  success",
+                "   ├─ {3D} [geometry/3d]  success",
+                "   └─ image/png [resource/thumbnail]",
+            ]
+        );
+    }
+}
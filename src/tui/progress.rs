@@ -0,0 +1,77 @@
+//! Inline progress bars for the executing step
+
+use crate::workflow::client::CommandProgress;
+
+/// Render a text progress bar with percentage, transfer rate, ETA and current
+/// operation, e.g. `[██████░░░░]  60%  12.5 MB/s  ETA 0:12  Uploading part 3/5`
+pub fn format_progress_bar(progress: &CommandProgress, width: usize) -> String {
+    let fraction = progress.progress_percent.clamp(0.0, 1.0);
+    let filled = ((fraction * width as f32).round() as usize).min(width);
+
+    let mut text = format!(
+        "[{}{}] {:>3.0}%",
+        "█".repeat(filled),
+        "░".repeat(width - filled),
+        fraction * 100.0
+    );
+    if let Some(rate) = progress.status_info.get("rate") {
+        text.push_str("  ");
+        text.push_str(rate);
+    }
+    if let Some(eta) = progress.estimated_remaining {
+        let secs = eta.as_secs();
+        text.push_str(&format!("  ETA {}:{:02}", secs / 60, secs % 60));
+    }
+    if !progress.current_operation.is_empty() {
+        text.push_str("  ");
+        text.push_str(&progress.current_operation);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_progress_bar() {
+        let progress = CommandProgress {
+            current_operation: "Uploading".to_string(),
+            progress_percent: 0.6,
+            estimated_remaining: Some(Duration::from_secs(75)),
+            status_info: HashMap::new(),
+        };
+
+        let bar = format_progress_bar(&progress, 10);
+        assert_eq!(bar, "[██████░░░░]  60%  ETA 1:15  Uploading");
+    }
+
+    #[test]
+    fn test_format_progress_bar_clamps_overflow() {
+        let progress = CommandProgress {
+            current_operation: String::new(),
+            progress_percent: 1.7,
+            estimated_remaining: None,
+            status_info: HashMap::new(),
+        };
+
+        assert_eq!(format_progress_bar(&progress, 4), "[████] 100%");
+    }
+
+    #[test]
+    fn test_format_progress_bar_with_rate() {
+        let progress = CommandProgress {
+            current_operation: "Uploading part 3/5".to_string(),
+            progress_percent: 0.6,
+            estimated_remaining: Some(Duration::from_secs(12)),
+            status_info: HashMap::from([("rate".to_string(), "12.5 MB/s".to_string())]),
+        };
+
+        assert_eq!(
+            format_progress_bar(&progress, 10),
+            "[██████░░░░]  60%  12.5 MB/s  ETA 0:12  Uploading part 3/5"
+        );
+    }
+}
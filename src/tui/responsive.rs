@@ -0,0 +1,136 @@
+// Layout of the main screen for the terminal size
+//
+// Side by side, the sidebar and the details need about 100 columns before
+// either is readable. Narrower terminals (a laptop split screen, a projector
+// at a low resolution) get a stacked layout: the sidebar becomes a drawer
+// above the details that `s` opens and closes, and the tab titles are
+// abbreviated. Below the doctor's minimum size nothing is laid out at all and
+// the "terminal too small" notice is shown instead.
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+
+use super::doctor::{MIN_HEIGHT, MIN_WIDTH};
+
+/// Width below which the panels are stacked
+pub const COMPACT_WIDTH: u16 = 100;
+
+/// Rows of the closed drawer: the selected entry between two borders
+const CLOSED_DRAWER_ROWS: u16 = 3;
+
+/// How the main screen is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutMode {
+    /// Sidebar and details side by side
+    Wide,
+    /// Sidebar drawer above the details
+    Compact,
+    /// Smaller than the minimum size
+    TooSmall,
+}
+
+impl LayoutMode {
+    /// Layout for a terminal of `size`
+    pub fn for_size(size: Rect) -> Self {
+        if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+            LayoutMode::TooSmall
+        } else if size.width < COMPACT_WIDTH {
+            LayoutMode::Compact
+        } else {
+            LayoutMode::Wide
+        }
+    }
+}
+
+/// Areas of the main screen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Panels {
+    pub sidebar: Rect,
+    pub details: Rect,
+    pub console: Rect,
+    pub help_bar: Rect,
+}
+
+/// Sizes the user chose for the panels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PanelSizes {
+    /// Width of the sidebar in percent (wide layout)
+    pub sidebar_percent: u16,
+    /// Rows of the console
+    pub console_height: u16,
+    /// Whether the drawer is open (compact layout)
+    pub drawer_open: bool,
+    /// Rows of the sidebar's entries
+    pub sidebar_rows: u16,
+}
+
+/// Areas of a main screen of `size` in `mode`
+pub fn panels(size: Rect, mode: LayoutMode, sizes: PanelSizes) -> Panels {
+    let main = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(size);
+    let content = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(sizes.console_height)])
+        .split(main[0]);
+
+    let split = match mode {
+        LayoutMode::Wide => Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(sizes.sidebar_percent),
+                Constraint::Percentage(100 - sizes.sidebar_percent),
+            ])
+            .split(content[0]),
+        LayoutMode::Compact => {
+            // An open drawer fits its entries but leaves the details at least half
+            let drawer = if sizes.drawer_open {
+                (sizes.sidebar_rows + 2).clamp(CLOSED_DRAWER_ROWS, (content[0].height / 2).max(CLOSED_DRAWER_ROWS))
+            } else {
+                CLOSED_DRAWER_ROWS
+            };
+            Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(drawer), Constraint::Min(0)])
+                .split(content[0])
+        },
+        LayoutMode::TooSmall => return Panels::default(),
+    };
+
+    Panels {
+        sidebar: split[0],
+        details: split[1],
+        console: content[1],
+        help_bar: main[1],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_modes() {
+        assert_eq!(LayoutMode::for_size(Rect::new(0, 0, 120, 40)), LayoutMode::Wide);
+        assert_eq!(LayoutMode::for_size(Rect::new(0, 0, 90, 30)), LayoutMode::Compact);
+        assert_eq!(LayoutMode::for_size(Rect::new(0, 0, 90, 10)), LayoutMode::TooSmall);
+
+        let sizes = PanelSizes {
+            sidebar_percent: 30,
+            console_height: 8,
+            drawer_open: true,
+            sidebar_rows: 40,
+        };
+        let size = Rect::new(0, 0, 90, 30);
+        let open = panels(size, LayoutMode::Compact, sizes);
+        assert_eq!(open.sidebar, Rect::new(0, 0, 90, 10));
+        assert_eq!(open.details, Rect::new(0, 10, 90, 11));
+        assert_eq!(open.console, Rect::new(0, 21, 90, 8));
+        assert_eq!(open.help_bar, Rect::new(0, 29, 90, 1));
+
+        let closed = panels(size, LayoutMode::Compact, PanelSizes { drawer_open: false, ..sizes });
+        assert_eq!(closed.sidebar.height, 3);
+        let wide = panels(Rect::new(0, 0, 100, 30), LayoutMode::Wide, sizes);
+        assert_eq!((wide.sidebar.width, wide.details.x), (30, 30));
+    }
+}
@@ -0,0 +1,260 @@
+// Step output tree for the Results tab
+//
+// Collects the parsed JSON output of every step of the last run and shows it
+// as a collapsible tree, so values such as URNs, bucket keys and signed URLs
+// can be found and copied mid-demo.
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Longest value shown inline before it is shortened
+const MAX_INLINE_VALUE: usize = 80;
+
+/// Parsed output of one step
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepOutput {
+    pub step_id: String,
+    pub step_name: String,
+    pub json: Value,
+}
+
+/// Step outputs of the last run with tree navigation state
+#[derive(Debug, Clone, Default)]
+pub struct OutputTree {
+    /// Workflow whose run produced the outputs
+    pub workflow_id: Option<String>,
+    outputs: Vec<StepOutput>,
+    /// Paths of collapsed nodes
+    collapsed: HashSet<String>,
+    /// Index of the selected visible row
+    selected: usize,
+}
+
+/// A visible row of the tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeRow {
+    /// Unique path such as `upload/objectKey` or `list/items/0`
+    pub path: String,
+    pub depth: usize,
+    pub label: String,
+    /// Scalar value shown after the label (leaves only)
+    pub value: Option<String>,
+    pub has_children: bool,
+    pub collapsed: bool,
+    /// Text copied for this row
+    pub copy_text: String,
+}
+
+impl OutputTree {
+    /// Start collecting outputs for a new run
+    pub fn reset(&mut self, workflow_id: &str) {
+        *self = Self {
+            workflow_id: Some(workflow_id.to_string()),
+            ..Self::default()
+        };
+    }
+
+    /// Add a step's output (ignored unless it is a JSON object or array)
+    pub fn push(&mut self, step_id: &str, step_name: &str, json: Value) {
+        if !(json.is_object() || json.is_array()) {
+            return;
+        }
+        self.outputs.retain(|o| o.step_id != step_id);
+        self.outputs.push(StepOutput {
+            step_id: step_id.to_string(),
+            step_name: step_name.to_string(),
+            json,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+
+    /// Index of the selected row
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Move the selection by `delta` rows, staying within the visible rows
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.rows().len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Collapse or expand the selected node
+    pub fn toggle_selected(&mut self) {
+        let Some(row) = self.rows().into_iter().nth(self.selected) else {
+            return;
+        };
+        if row.has_children && !self.collapsed.remove(&row.path) {
+            self.collapsed.insert(row.path);
+        }
+    }
+
+    /// Text to copy for the selected row
+    pub fn selected_copy_text(&self) -> Option<String> {
+        self.rows().into_iter().nth(self.selected).map(|row| row.copy_text)
+    }
+
+    /// Flatten the tree into its visible rows
+    pub fn rows(&self) -> Vec<TreeRow> {
+        let mut rows = Vec::new();
+        for output in &self.outputs {
+            let path = output.step_id.clone();
+            let collapsed = self.collapsed.contains(&path);
+            rows.push(TreeRow {
+                path: path.clone(),
+                depth: 0,
+                label: output.step_name.clone(),
+                value: None,
+                has_children: true,
+                collapsed,
+                copy_text: serde_json::to_string_pretty(&output.json).unwrap_or_default(),
+            });
+            if !collapsed {
+                self.push_children(&mut rows, &output.json, &path, 1);
+            }
+        }
+        rows
+    }
+
+    /// Append rows for the children of a JSON container
+    fn push_children(&self, rows: &mut Vec<TreeRow>, value: &Value, parent: &str, depth: usize) {
+        let children: Vec<(String, &Value)> = match value {
+            Value::Object(map) => map.iter().map(|(k, v)| (k.clone(), v)).collect(),
+            Value::Array(items) => items.iter().enumerate().map(|(i, v)| (i.to_string(), v)).collect(),
+            _ => return,
+        };
+
+        for (key, child) in children {
+            let path = format!("{}/{}", parent, key);
+            let has_children = matches!(child, Value::Object(m) if !m.is_empty())
+                || matches!(child, Value::Array(a) if !a.is_empty());
+            let collapsed = has_children && self.collapsed.contains(&path);
+            let (value, copy_text) = match child {
+                Value::String(s) => (Some(s.clone()), s.clone()),
+                Value::Object(_) | Value::Array(_) if has_children => {
+                    (None, serde_json::to_string(child).unwrap_or_default())
+                },
+                other => (Some(other.to_string()), other.to_string()),
+            };
+            rows.push(TreeRow {
+                path: path.clone(),
+                depth,
+                label: key,
+                value,
+                has_children,
+                collapsed,
+                copy_text,
+            });
+            if has_children && !collapsed {
+                self.push_children(rows, child, &path, depth + 1);
+            }
+        }
+    }
+
+    /// Render the visible rows, highlighting the selection
+    pub fn lines(&self, focused: bool) -> Vec<Line<'static>> {
+        self.rows()
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let marker = match (row.has_children, row.collapsed) {
+                    (true, true) => "▸ ",
+                    (true, false) => "▾ ",
+                    (false, _) => "  ",
+                };
+                let label_style = if row.depth == 0 {
+                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::Yellow)
+                };
+                let mut spans = vec![
+                    Span::raw("  ".repeat(row.depth)),
+                    Span::styled(marker, Style::default().fg(Color::DarkGray)),
+                    Span::styled(row.label, label_style),
+                ];
+                if let Some(value) = row.value {
+                    spans.push(Span::styled(": ", Style::default().fg(Color::DarkGray)));
+                    spans.push(Span::raw(shorten(&value)));
+                }
+
+                let mut line = Line::from(spans);
+                if focused && i == self.selected {
+                    line.patch_style(Style::default().add_modifier(Modifier::REVERSED));
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+/// Shorten long values for inline display
+fn shorten(value: &str) -> String {
+    if value.chars().count() <= MAX_INLINE_VALUE {
+        value.to_string()
+    } else {
+        let head: String = value.chars().take(MAX_INLINE_VALUE - 1).collect();
+        format!("{}…", head)
+    }
+}
+
+/// First `http(s)://` string in a step's JSON output, e.g. a signed URL
+pub fn first_url(json: &Value) -> Option<String> {
+    match json {
+        Value::String(s) if s.starts_with("https://") || s.starts_with("http://") => Some(s.clone()),
+        Value::Array(items) => items.iter().find_map(first_url),
+        Value::Object(map) => map.values().find_map(first_url),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_tree_navigation_and_copy() {
+        let mut tree = OutputTree::default();
+        tree.reset("oss-demo");
+        tree.push(
+            "upload",
+            "Upload File",
+            serde_json::json!({"objectKey": "house.rvt", "urn": "dXJuOmFkc2s", "size": 42}),
+        );
+        tree.push("list", "List Objects", serde_json::json!({"items": [{"key": "a"}]}));
+        // Plain text output has no tree
+        tree.push("status", "Status", serde_json::json!("ok"));
+
+        let labels: Vec<String> = tree.rows().into_iter().map(|r| r.label).collect();
+        assert_eq!(
+            labels,
+            vec!["Upload File", "objectKey", "size", "urn", "List Objects", "items", "0", "key"]
+        );
+
+        tree.move_selection(3);
+        assert_eq!(tree.selected_copy_text().as_deref(), Some("dXJuOmFkc2s"));
+
+        // Collapse "List Objects" and clamp the selection to the visible rows
+        tree.move_selection(1);
+        tree.toggle_selected();
+        assert_eq!(tree.rows().len(), 5);
+        tree.move_selection(10);
+        assert_eq!(tree.selected(), 4);
+
+        tree.reset("other");
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_first_url() {
+        let json = serde_json::json!({"expiration": 3600, "urls": ["https://cdn.example/house.rvt?sig=1"]});
+        assert_eq!(first_url(&json).as_deref(), Some("https://cdn.example/house.rvt?sig=1"));
+        assert_eq!(first_url(&serde_json::json!({"objectKey": "house.rvt"})), None);
+    }
+}
@@ -0,0 +1,193 @@
+// Rows of the sidebar and the selection across changes to them
+//
+// The sidebar lists groups (tours, favorites, recent runs and the workflow
+// categories), each a header followed by its entries unless collapsed. The
+// grouping only changes when the workflows, favorites or recent runs do;
+// collapsing or expanding a group just lays the cached groups out again.
+// Every row has a key (its group and the ID of its entry) that outlives the
+// layout, so the selection follows the selected row instead of staying on a
+// row number that now shows something else.
+
+use ratatui::style::Color;
+use std::collections::{HashMap, HashSet};
+
+/// Sidebar item type for grouped workflow display
+#[derive(Clone, Debug)]
+pub enum SidebarItem {
+    /// Category header (expandable/collapsible)
+    Category { name: String, count: usize, color: Color },
+    /// Workflow entry with index into workflows vec
+    Workflow { index: usize },
+    /// Guided tour with index into tours vec
+    Tour { index: usize },
+}
+
+/// A group of the sidebar and its entries
+#[derive(Clone, Debug)]
+pub struct SidebarGroup {
+    pub name: String,
+    pub color: Color,
+    /// Entries with the ID that identifies them (workflow ID or tour name)
+    pub entries: Vec<(String, SidebarItem)>,
+}
+
+/// What a row shows, independent of where it is laid out
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum RowKey {
+    Header(String),
+    Entry { group: String, id: String },
+}
+
+/// The laid-out rows of the sidebar
+#[derive(Debug, Default)]
+pub struct SidebarLayout {
+    groups: Vec<SidebarGroup>,
+    items: Vec<SidebarItem>,
+    /// Key of each row of `items`
+    keys: Vec<RowKey>,
+    /// First row showing each workflow, by workflow ID
+    workflow_rows: HashMap<String, usize>,
+}
+
+impl SidebarLayout {
+    /// Replace the groups, laying them out with `collapsed` groups closed;
+    /// returns where the row at `selected` ended up
+    pub fn set_groups(
+        &mut self,
+        groups: Vec<SidebarGroup>,
+        collapsed: &HashSet<String>,
+        selected: Option<usize>,
+    ) -> Option<usize> {
+        self.groups = groups;
+        self.layout(collapsed, selected)
+    }
+
+    /// Lay the groups out again with `collapsed` groups closed; returns
+    /// where the row at `selected` ended up: the same entry, the same
+    /// workflow in another group, the header of its now closed group or,
+    /// failing those, the nearest row
+    pub fn layout(&mut self, collapsed: &HashSet<String>, selected: Option<usize>) -> Option<usize> {
+        let previous = selected.and_then(|row| self.keys.get(row)).cloned();
+        self.items.clear();
+        self.keys.clear();
+        self.workflow_rows.clear();
+        for group in &self.groups {
+            self.items.push(SidebarItem::Category {
+                name: group.name.clone(),
+                count: group.entries.len(),
+                color: group.color,
+            });
+            self.keys.push(RowKey::Header(group.name.clone()));
+            if collapsed.contains(&group.name) {
+                continue;
+            }
+            for (id, item) in &group.entries {
+                if matches!(item, SidebarItem::Workflow { .. }) {
+                    self.workflow_rows.entry(id.clone()).or_insert(self.items.len());
+                }
+                self.items.push(item.clone());
+                self.keys.push(RowKey::Entry {
+                    group: group.name.clone(),
+                    id: id.clone(),
+                });
+            }
+        }
+
+        if self.items.is_empty() {
+            return None;
+        }
+        let found = previous.and_then(|key| {
+            self.keys.iter().position(|k| *k == key).or_else(|| match &key {
+                RowKey::Entry { group, id } => self
+                    .workflow_rows
+                    .get(id)
+                    .copied()
+                    .or_else(|| self.keys.iter().position(|k| *k == RowKey::Header(group.clone()))),
+                RowKey::Header(_) => None,
+            })
+        });
+        found.or_else(|| selected.map(|row| row.min(self.items.len() - 1)))
+    }
+
+    /// The rows, top to bottom
+    pub fn items(&self) -> &[SidebarItem] {
+        &self.items
+    }
+
+    /// First row showing the workflow `id`, unless its groups are collapsed
+    pub fn workflow_row(&self, id: &str) -> Option<usize> {
+        self.workflow_rows.get(id).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(name: &str, ids: &[(&str, usize)]) -> SidebarGroup {
+        SidebarGroup {
+            name: name.to_string(),
+            color: Color::Cyan,
+            entries: ids
+                .iter()
+                .map(|(id, index)| (id.to_string(), SidebarItem::Workflow { index: *index }))
+                .collect(),
+        }
+    }
+
+    fn selected_workflow(layout: &SidebarLayout, row: Option<usize>) -> Option<usize> {
+        match layout.items().get(row?) {
+            Some(SidebarItem::Workflow { index }) => Some(*index),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_selection_follows_collapse_and_expand() {
+        let mut collapsed = HashSet::new();
+        let mut layout = SidebarLayout::default();
+        let groups = vec![group("OSS", &[("a", 0), ("b", 1)]), group("ACC", &[("c", 2), ("d", 3)])];
+        assert_eq!(layout.set_groups(groups, &collapsed, None), None);
+        assert_eq!(layout.items().len(), 6);
+
+        // "d" in the second group stays selected when the first one closes
+        let row = layout.workflow_row("d");
+        collapsed.insert("OSS".to_string());
+        let row = layout.layout(&collapsed, row);
+        assert_eq!(row, Some(3));
+        assert_eq!(selected_workflow(&layout, row), Some(3));
+
+        // Closing its own group moves the selection to the header
+        collapsed.insert("ACC".to_string());
+        let row = layout.layout(&collapsed, row);
+        assert!(matches!(&layout.items()[row.unwrap()], SidebarItem::Category { name, .. } if name == "ACC"));
+        assert_eq!(layout.workflow_row("d"), None);
+
+        // Expanding keeps the header selected
+        collapsed.clear();
+        let row = layout.layout(&collapsed, row);
+        assert_eq!(row, Some(3));
+        assert_eq!(layout.workflow_row("a"), Some(1));
+    }
+
+    #[test]
+    fn test_selection_survives_regrouping() {
+        let collapsed = HashSet::new();
+        let mut layout = SidebarLayout::default();
+        layout.set_groups(vec![group("OSS", &[("a", 0), ("b", 1)])], &collapsed, None);
+        let row = layout.workflow_row("b");
+
+        // A favorites group above, with the workflows in a new order
+        let groups = vec![group("Favorites", &[("a", 1)]), group("OSS", &[("b", 0), ("a", 1)])];
+        let row = layout.set_groups(groups, &collapsed, row);
+        assert_eq!(row, Some(3));
+        assert_eq!(selected_workflow(&layout, row), Some(0));
+
+        // Once its group is gone, the same workflow elsewhere is selected
+        let row = layout.set_groups(vec![group("OSS", &[("b", 0), ("a", 1)])], &collapsed, Some(1));
+        assert_eq!(selected_workflow(&layout, row), Some(1));
+
+        // Without groups there is nothing to select
+        assert_eq!(layout.set_groups(Vec::new(), &collapsed, row), None);
+    }
+}
@@ -0,0 +1,209 @@
+//! Persisted TUI layout and UI state
+//!
+//! Remembers pane sizes, collapsed categories, the selected workflow and the
+//! active detail tab between sessions so the layout doesn't need to be
+//! re-adjusted on every run.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::types::ConfigPaths;
+
+/// Sidebar width bounds (percent of terminal width)
+pub const SIDEBAR_PERCENT_RANGE: (u16, u16) = (15, 60);
+/// Console height bounds (lines)
+pub const CONSOLE_HEIGHT_RANGE: (u16, u16) = (5, 25);
+/// Number of detail tabs
+pub const DETAIL_TAB_COUNT: usize = 10;
+/// Number of workflows kept in the "Recent" list
+pub const RECENT_LIMIT: usize = 5;
+
+/// Sidebar width (percent) for a splitter dragged to column `x` within
+/// the panel row `area`
+pub fn sidebar_percent_at(x: u16, area_x: u16, area_width: u16) -> u16 {
+    if area_width == 0 {
+        return SIDEBAR_PERCENT_RANGE.0;
+    }
+    let columns = u32::from(x.saturating_sub(area_x)) + 1;
+    let percent = (columns * 100 / u32::from(area_width)) as u16;
+    percent.clamp(SIDEBAR_PERCENT_RANGE.0, SIDEBAR_PERCENT_RANGE.1)
+}
+
+/// Console height (lines) for a divider dragged to row `y`, where the
+/// console ends just above row `bottom`
+pub fn console_height_at(y: u16, bottom: u16) -> u16 {
+    bottom
+        .saturating_sub(y)
+        .clamp(CONSOLE_HEIGHT_RANGE.0, CONSOLE_HEIGHT_RANGE.1)
+}
+
+/// UI state saved on exit and restored on startup
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    /// Sidebar width as a percentage of the terminal
+    pub sidebar_percent: u16,
+    /// Console panel height in lines
+    pub console_height: u16,
+    /// Names of collapsed sidebar categories
+    pub collapsed_categories: Vec<String>,
+    /// ID of the last selected workflow
+    pub selected_workflow: Option<String>,
+    /// Active detail tab index
+    pub detail_tab: usize,
+    /// IDs of favorite workflows
+    pub favorites: Vec<String>,
+    /// IDs of recently executed workflows, most recent first
+    pub recent: Vec<String>,
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self {
+            sidebar_percent: 30,
+            console_height: 10,
+            collapsed_categories: Vec::new(),
+            selected_workflow: None,
+            detail_tab: 0,
+            favorites: Vec::new(),
+            recent: Vec::new(),
+        }
+    }
+}
+
+impl UiState {
+    /// Load state from the default state file, falling back to defaults
+    pub fn load() -> Self {
+        let path = match ConfigPaths::ui_state_file() {
+            Ok(path) => path,
+            Err(e) => {
+                tracing::debug!("No UI state location available: {}", e);
+                return Self::default();
+            },
+        };
+
+        if !path.exists() {
+            return Self::default();
+        }
+
+        match Self::load_from(&path) {
+            Ok(state) => state,
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable UI state file {:?}: {:#}", path, e);
+                Self::default()
+            },
+        }
+    }
+
+    /// Save state to the default state file
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&ConfigPaths::ui_state_file()?)
+    }
+
+    /// Load state from a specific file
+    pub fn load_from<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read UI state file: {:?}", path))?;
+        let state: UiState = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse UI state file: {:?}", path))?;
+        Ok(state.clamped())
+    }
+
+    /// Save state to a specific file
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+        }
+        let content = toml::to_string_pretty(self).context("Failed to serialize UI state")?;
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write UI state file: {:?}", path))?;
+        Ok(())
+    }
+
+    /// Clamp values into the ranges the layout supports
+    fn clamped(mut self) -> Self {
+        self.sidebar_percent = self
+            .sidebar_percent
+            .clamp(SIDEBAR_PERCENT_RANGE.0, SIDEBAR_PERCENT_RANGE.1);
+        self.console_height = self
+            .console_height
+            .clamp(CONSOLE_HEIGHT_RANGE.0, CONSOLE_HEIGHT_RANGE.1);
+        if self.detail_tab >= DETAIL_TAB_COUNT {
+            self.detail_tab = 0;
+        }
+        self.recent.truncate(RECENT_LIMIT);
+        self
+    }
+}
+
+/// Move `id` to the front of the recent list, keeping at most `RECENT_LIMIT`
+pub fn push_recent(recent: &mut Vec<String>, id: &str) {
+    recent.retain(|r| r != id);
+    recent.insert(0, id.to_string());
+    recent.truncate(RECENT_LIMIT);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_ui_state_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("nested").join("ui-state.toml");
+
+        let state = UiState {
+            sidebar_percent: 45,
+            console_height: 15,
+            collapsed_categories: vec!["Object Storage".to_string()],
+            selected_workflow: Some("oss-upload".to_string()),
+            detail_tab: 2,
+            favorites: vec!["oss-upload".to_string()],
+            recent: vec!["md-translate".to_string(), "oss-upload".to_string()],
+        };
+        state.save_to(&path).unwrap();
+
+        assert_eq!(UiState::load_from(&path).unwrap(), state);
+    }
+
+    #[test]
+    fn test_ui_state_clamps_out_of_range_values() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ui-state.toml");
+        fs::write(&path, "sidebar_percent = 95\nconsole_height = 1\ndetail_tab = 12\n").unwrap();
+
+        let state = UiState::load_from(&path).unwrap();
+        assert_eq!(state.sidebar_percent, 60);
+        assert_eq!(state.console_height, 5);
+        assert_eq!(state.detail_tab, 0);
+        assert!(state.collapsed_categories.is_empty());
+    }
+
+    #[test]
+    fn test_splitter_positions_are_clamped() {
+        // Dragging to column 39 of a 100-wide row gives a 40% sidebar
+        assert_eq!(sidebar_percent_at(39, 0, 100), 40);
+        assert_eq!(sidebar_percent_at(0, 0, 100), SIDEBAR_PERCENT_RANGE.0);
+        assert_eq!(sidebar_percent_at(99, 0, 100), SIDEBAR_PERCENT_RANGE.1);
+
+        assert_eq!(console_height_at(30, 42), 12);
+        assert_eq!(console_height_at(41, 42), CONSOLE_HEIGHT_RANGE.0);
+        assert_eq!(console_height_at(0, 42), CONSOLE_HEIGHT_RANGE.1);
+    }
+
+    #[test]
+    fn test_push_recent_dedupes_and_limits() {
+        let mut recent = Vec::new();
+        for id in ["a", "b", "c", "d", "e", "f", "c"] {
+            push_recent(&mut recent, id);
+        }
+        assert_eq!(recent, vec!["c", "f", "e", "d", "b"]);
+    }
+}
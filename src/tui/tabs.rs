@@ -0,0 +1,69 @@
+//! Hit-testing for the detail tab bar
+//!
+//! Mirrors how ratatui's `Tabs` widget lays out titles (one space of padding
+//! on each side, a one-column divider between tabs) so mouse clicks map to
+//! the tab actually drawn under the cursor.
+
+use ratatui::text::Line;
+
+/// Columns of padding drawn on each side of a tab title
+const TAB_PADDING: u16 = 1;
+/// Width of the divider drawn between tabs
+const TAB_DIVIDER: u16 = 1;
+/// Characters kept of an abbreviated title
+const ABBREVIATED_CHARS: usize = 4;
+
+/// Compute the `[start, end)` column range of each tab, starting at `x`
+/// and cut off at `right`
+pub fn tab_bounds(titles: &[String], x: u16, right: u16) -> Vec<(u16, u16)> {
+    let mut bounds = Vec::with_capacity(titles.len());
+    let mut start = x;
+    for title in titles {
+        if start >= right {
+            break;
+        }
+        let width = Line::from(title.as_str()).width() as u16 + 2 * TAB_PADDING;
+        let end = start.saturating_add(width).min(right);
+        bounds.push((start, end));
+        start = end.saturating_add(TAB_DIVIDER);
+    }
+    bounds
+}
+
+/// Short form of a tab title for narrow terminals
+pub fn abbreviate(title: &str) -> String {
+    title.chars().take(ABBREVIATED_CHARS).collect()
+}
+
+/// Index of the tab under column `x`, if any
+pub fn tab_at(bounds: &[(u16, u16)], x: u16) -> Option<usize> {
+    bounds.iter().position(|&(start, end)| x >= start && x < end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tab_bounds_follow_title_widths() {
+        let titles = vec!["Overview ✓".to_string(), "Steps".to_string(), "YAML".to_string()];
+        let bounds = tab_bounds(&titles, 1, 80);
+
+        // " Overview ✓ │ Steps │ YAML "
+        assert_eq!(bounds, vec![(1, 13), (14, 21), (22, 28)]);
+        assert_eq!(tab_at(&bounds, 1), Some(0));
+        assert_eq!(tab_at(&bounds, 13), None); // divider
+        assert_eq!(tab_at(&bounds, 20), Some(1));
+        assert_eq!(tab_at(&bounds, 27), Some(2));
+        assert_eq!(tab_at(&bounds, 40), None);
+    }
+
+    #[test]
+    fn test_tab_bounds_truncated_by_width() {
+        let titles = vec!["Overview".to_string(), "Flowchart".to_string()];
+        assert_eq!(tab_bounds(&titles, 0, 14), vec![(0, 10), (11, 14)]);
+        assert_eq!(abbreviate("Flowchart"), "Flow");
+        assert_eq!(abbreviate("タイムライン"), "タイムラ");
+        assert_eq!(abbreviate("DA"), "DA");
+    }
+}
@@ -0,0 +1,181 @@
+//! Execution timeline (Gantt) view
+//!
+//! Draws one bar per step, positioned by its start time and sized by its
+//! duration, so it's easy to see which step dominates a run.
+
+use chrono::{DateTime, Utc};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::workflow::ExecutionStatus;
+
+/// Width reserved for the step name column
+const LABEL_WIDTH: usize = 22;
+/// Width reserved for the duration column
+const DURATION_WIDTH: usize = 9;
+
+/// A single step on the timeline
+#[derive(Debug, Clone)]
+pub struct TimelineEntry {
+    pub name: String,
+    pub start: DateTime<Utc>,
+    /// `None` while the step is still running
+    pub end: Option<DateTime<Utc>>,
+    pub status: ExecutionStatus,
+}
+
+impl TimelineEntry {
+    /// Duration in seconds, measured up to `now` for running steps
+    fn duration_secs(&self, now: DateTime<Utc>) -> f64 {
+        let end = self.end.unwrap_or(now);
+        (end - self.start).num_milliseconds().max(0) as f64 / 1000.0
+    }
+}
+
+/// Build the timeline lines for the given entries and available width
+pub fn build_timeline_lines(
+    entries: &[TimelineEntry],
+    now: DateTime<Utc>,
+    width: usize,
+) -> Vec<Line<'static>> {
+    if entries.is_empty() {
+        return vec![Line::from(Span::styled(
+            "Run a workflow to see its execution timeline",
+            Style::default().fg(Color::DarkGray),
+        ))];
+    }
+
+    let origin = entries.iter().map(|e| e.start).min().unwrap_or(now);
+    let finish = entries
+        .iter()
+        .map(|e| e.end.unwrap_or(now))
+        .max()
+        .unwrap_or(now);
+    let total_secs = ((finish - origin).num_milliseconds().max(1)) as f64 / 1000.0;
+    let bar_width = width.saturating_sub(LABEL_WIDTH + DURATION_WIDTH + 2).max(10);
+
+    let slowest = entries
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.duration_secs(now).total_cmp(&b.duration_secs(now)))
+        .map(|(i, _)| i);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Total: ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                format_secs(total_secs),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("   {} steps", entries.len()),
+                Style::default().fg(Color::Gray),
+            ),
+        ]),
+        Line::from(""),
+    ];
+
+    for (i, entry) in entries.iter().enumerate() {
+        let offset_secs = (entry.start - origin).num_milliseconds().max(0) as f64 / 1000.0;
+        let duration = entry.duration_secs(now);
+
+        let offset = ((offset_secs / total_secs) * bar_width as f64).round() as usize;
+        let offset = offset.min(bar_width.saturating_sub(1));
+        let length = ((duration / total_secs) * bar_width as f64).round() as usize;
+        let length = length.clamp(1, bar_width - offset);
+
+        let color = match entry.status {
+            ExecutionStatus::Completed => Color::Green,
+            ExecutionStatus::Failed => Color::Red,
+            ExecutionStatus::Running => Color::Yellow,
+            ExecutionStatus::Cancelled => Color::Magenta,
+            ExecutionStatus::Skipped | ExecutionStatus::AlreadyExists => Color::Cyan,
+            ExecutionStatus::Pending | ExecutionStatus::Paused => Color::DarkGray,
+        };
+
+        let name: String = entry.name.chars().take(LABEL_WIDTH - 1).collect();
+        let mut label_style = Style::default().fg(Color::White);
+        if Some(i) == slowest && entries.len() > 1 {
+            label_style = label_style.add_modifier(Modifier::BOLD);
+        }
+
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<width$}", name, width = LABEL_WIDTH), label_style),
+            Span::raw(" ".repeat(offset)),
+            Span::styled("█".repeat(length), Style::default().fg(color)),
+            Span::raw(" ".repeat(bar_width - offset - length)),
+            Span::styled(
+                format!(" {:>width$}", format_secs(duration), width = DURATION_WIDTH),
+                Style::default().fg(Color::Gray),
+            ),
+        ]));
+    }
+
+    if let Some(i) = slowest.filter(|_| entries.len() > 1) {
+        let share = entries[i].duration_secs(now) / total_secs * 100.0;
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("Slowest: {} ({:.0}% of total)", entries[i].name, share),
+            Style::default().fg(Color::Cyan),
+        )));
+    }
+
+    lines
+}
+
+/// Format seconds as `1m 05s` / `12.3s`
+fn format_secs(secs: f64) -> String {
+    if secs >= 60.0 {
+        let whole = secs.round() as u64;
+        format!("{}m {:02}s", whole / 60, whole % 60)
+    } else {
+        format!("{:.1}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn line_text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_timeline_bars_scale_with_duration() {
+        let t0 = Utc::now();
+        let entries = vec![
+            TimelineEntry {
+                name: "Upload".to_string(),
+                start: t0,
+                end: Some(t0 + Duration::seconds(10)),
+                status: ExecutionStatus::Completed,
+            },
+            TimelineEntry {
+                name: "Translate".to_string(),
+                start: t0 + Duration::seconds(10),
+                end: Some(t0 + Duration::seconds(40)),
+                status: ExecutionStatus::Completed,
+            },
+        ];
+
+        let lines = build_timeline_lines(&entries, t0 + Duration::seconds(40), 73);
+        let upload = line_text(&lines[2]);
+        let translate = line_text(&lines[3]);
+
+        // 40 columns of bar: 10 for upload, 30 for translate
+        assert_eq!(upload.matches('█').count(), 10);
+        assert_eq!(translate.matches('█').count(), 30);
+        assert!(line_text(&lines[0]).contains("40.0s"));
+        assert!(line_text(lines.last().unwrap()).contains("Slowest: Translate (75% of total)"));
+    }
+
+    #[test]
+    fn test_format_secs() {
+        assert_eq!(format_secs(12.34), "12.3s");
+        assert_eq!(format_secs(65.0), "1m 05s");
+    }
+}
@@ -0,0 +1,174 @@
+//! Progress through a guided tour
+
+use crate::workflow::tour::{Checkpoint, TourDefinition, TourItem};
+
+/// A tour being presented, one item at a time
+#[derive(Debug, Clone)]
+pub struct TourRun {
+    pub tour: TourDefinition,
+    position: usize,
+    /// Whether the current item still has to be presented
+    pending: bool,
+    /// Workflow started for the current item
+    waiting_for: Option<String>,
+    /// Option chosen at the current checkpoint
+    answered: Option<usize>,
+}
+
+impl TourRun {
+    pub fn new(tour: TourDefinition) -> Self {
+        Self {
+            tour,
+            position: 0,
+            pending: true,
+            waiting_for: None,
+            answered: None,
+        }
+    }
+
+    /// Item currently presented
+    pub fn current(&self) -> Option<&TourItem> {
+        self.tour.items.get(self.position)
+    }
+
+    /// Whether every item has been presented
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.tour.items.len()
+    }
+
+    /// Take the current item if it hasn't been presented yet
+    pub fn take_pending(&mut self) -> Option<TourItem> {
+        if !self.pending {
+            return None;
+        }
+        self.pending = false;
+        self.current().cloned()
+    }
+
+    /// Move on to the next item
+    pub fn next(&mut self) {
+        self.position += 1;
+        self.pending = true;
+        self.waiting_for = None;
+        self.answered = None;
+    }
+
+    /// Remember the workflow started for the current item
+    pub fn wait_for(&mut self, workflow_id: &str) {
+        self.waiting_for = Some(workflow_id.to_string());
+    }
+
+    /// Whether the tour is waiting for this workflow to finish
+    pub fn is_waiting_for(&self, workflow_id: &str) -> bool {
+        self.waiting_for.as_deref() == Some(workflow_id)
+    }
+
+    /// Current checkpoint, if it still awaits an answer
+    pub fn open_checkpoint(&self) -> Option<&Checkpoint> {
+        match self.current() {
+            Some(TourItem::Checkpoint(checkpoint)) if self.answered.is_none() && !checkpoint.options.is_empty() => {
+                Some(checkpoint)
+            },
+            _ => None,
+        }
+    }
+
+    /// Answer the current checkpoint with a 1-based option; returns the updated message
+    pub fn answer(&mut self, choice: usize) -> Option<String> {
+        let checkpoint = self.open_checkpoint()?;
+        if choice == 0 || choice > checkpoint.options.len() {
+            return None;
+        }
+        let message = checkpoint_message(checkpoint, Some(choice));
+        self.answered = Some(choice);
+        Some(message)
+    }
+
+    /// Popup title with the tour's progress, e.g. ` Storage · APS Tour (2/7) `
+    pub fn title(&self, heading: &str) -> String {
+        format!(
+            " {} · {} ({}/{}) ",
+            heading,
+            self.tour.name,
+            (self.position + 1).min(self.tour.items.len()),
+            self.tour.items.len()
+        )
+    }
+}
+
+/// Popup text for a checkpoint, with feedback once an option was chosen
+pub fn checkpoint_message(checkpoint: &Checkpoint, chosen: Option<usize>) -> String {
+    let mut text = format!("{}\n", checkpoint.question);
+    for (i, option) in checkpoint.options.iter().enumerate() {
+        let marker = match (chosen, checkpoint.answer) {
+            (Some(_), Some(answer)) if answer == i + 1 => "✓",
+            (Some(choice), _) if choice == i + 1 => "✗",
+            _ => " ",
+        };
+        text.push_str(&format!("\n{} {}. {}", marker, i + 1, option));
+    }
+
+    match chosen {
+        None if checkpoint.options.is_empty() => text.push_str("\n\n[Enter] continue  [Esc] end tour"),
+        None => text.push_str(&format!(
+            "\n\n[1-{}] answer  [Enter] skip  [Esc] end tour",
+            checkpoint.options.len()
+        )),
+        Some(choice) => {
+            let verdict = match checkpoint.answer {
+                Some(answer) if answer == choice => "Correct!",
+                Some(_) => "Not quite.",
+                None => "Thanks!",
+            };
+            text.push_str(&format!("\n\n{}", verdict));
+            if let Some(explanation) = &checkpoint.explanation {
+                text.push_str(&format!(" {}", explanation));
+            }
+            text.push_str("\n\n[Enter] continue  [Esc] end tour");
+        },
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tour_run_progression() {
+        let tour = TourDefinition {
+            id: "t".to_string(),
+            name: "Tour".to_string(),
+            description: String::new(),
+            items: vec![
+                TourItem::Workflow("oss".to_string()),
+                TourItem::Checkpoint(Checkpoint {
+                    question: "Region?".to_string(),
+                    options: vec!["US".to_string(), "EMEA".to_string()],
+                    answer: Some(2),
+                    explanation: Some("Data residency.".to_string()),
+                }),
+            ],
+            path: Default::default(),
+        };
+        let mut run = TourRun::new(tour);
+
+        assert_eq!(run.take_pending(), Some(TourItem::Workflow("oss".to_string())));
+        assert_eq!(run.take_pending(), None);
+        run.wait_for("oss");
+        assert!(run.is_waiting_for("oss"));
+        assert_eq!(run.title("Storage"), " Storage · Tour (1/2) ");
+
+        run.next();
+        assert!(matches!(run.take_pending(), Some(TourItem::Checkpoint(_))));
+        assert!(run.answer(3).is_none());
+        let message = run.answer(1).unwrap();
+        assert!(message.contains("✗ 1. US"));
+        assert!(message.contains("✓ 2. EMEA"));
+        assert!(message.contains("Not quite. Data residency."));
+        assert!(run.open_checkpoint().is_none());
+
+        run.next();
+        assert!(run.is_finished());
+    }
+}
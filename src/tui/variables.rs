@@ -0,0 +1,227 @@
+//! Placeholder/variables view
+//!
+//! Lists the placeholders a workflow uses, where their values came from and
+//! which steps consume them, so it's visible how e.g. an uploaded object's
+//! URN flows into the translate and viewer steps.
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::workflow::{VariableSource, WorkflowDefinition};
+
+/// Width of the name column
+const NAME_WIDTH: usize = 20;
+/// Width of the source column
+const SOURCE_WIDTH: usize = 16;
+
+/// A placeholder value known for the current run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableEntry {
+    pub name: String,
+    pub value: String,
+    pub source: VariableSource,
+    /// Set for values from the most recent update, to highlight them
+    pub fresh: bool,
+}
+
+/// Insert or replace the given values, marking them as the freshest update
+pub fn upsert_variables(
+    entries: &mut Vec<VariableEntry>,
+    source: VariableSource,
+    values: Vec<(String, String)>,
+) {
+    for entry in entries.iter_mut() {
+        entry.fresh = false;
+    }
+    for (name, value) in values {
+        let entry = VariableEntry {
+            name,
+            value,
+            source: source.clone(),
+            fresh: true,
+        };
+        match entries.iter_mut().find(|e| e.name == entry.name) {
+            Some(existing) => *existing = entry,
+            None => entries.push(entry),
+        }
+    }
+}
+
+/// Placeholders referenced by each step's command, as `(placeholder, step IDs)`
+/// in order of first use
+pub fn placeholder_usage(definition: &WorkflowDefinition) -> Vec<(String, Vec<String>)> {
+    let mut usage: Vec<(String, Vec<String>)> = Vec::new();
+    for step in &definition.steps {
+        let command = serde_json::to_string(&step.command).unwrap_or_default();
+        for name in placeholder_names(&command) {
+            // `{step.key}` refers to the same value as `{key}`
+            let name = name.rsplit('.').next().unwrap_or(&name).to_string();
+            match usage.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, steps)) if !steps.contains(&step.id) => steps.push(step.id.clone()),
+                Some(_) => {},
+                None => usage.push((name, vec![step.id.clone()])),
+            }
+        }
+    }
+    usage
+}
+
+/// Extract `{name}` placeholder names from a string
+fn placeholder_names(text: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('{') {
+        rest = &rest[open + 1..];
+        let Some(close) = rest.find('}') else { break };
+        let candidate = &rest[..close];
+        if !candidate.is_empty()
+            && candidate
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        {
+            names.push(candidate.to_string());
+        }
+    }
+    names
+}
+
+/// Line of the table showing `entries[index]` (captured values come first)
+pub fn entry_line(definition: Option<&WorkflowDefinition>, entries: &[VariableEntry], index: usize) -> usize {
+    let usage = definition.map(placeholder_usage).unwrap_or_default();
+    let preceding: usize = entries
+        .iter()
+        .take(index)
+        .map(|e| if usage.iter().any(|(n, _)| *n == e.name) { 2 } else { 1 })
+        .sum();
+    // Header and blank line
+    2 + preceding
+}
+
+/// Build the variables table for a workflow and the values captured so far,
+/// highlighting `entries[selected]`
+pub fn build_variable_lines(
+    definition: Option<&WorkflowDefinition>,
+    entries: &[VariableEntry],
+    selected: Option<usize>,
+) -> Vec<Line<'static>> {
+    let usage = definition.map(placeholder_usage).unwrap_or_default();
+    if usage.is_empty() && entries.is_empty() {
+        return vec![Line::from(Span::styled(
+            "This workflow doesn't use any placeholders",
+            Style::default().fg(Color::DarkGray),
+        ))];
+    }
+
+    let header = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(format!("{:<w$}", "Name", w = NAME_WIDTH), header),
+            Span::styled(format!("{:<w$}", "Source", w = SOURCE_WIDTH), header),
+            Span::styled("Value", header),
+        ]),
+        Line::from(""),
+    ];
+
+    // Known values first (in capture order), then placeholders still pending
+    let mut names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+    for (name, _) in &usage {
+        if !names.contains(&name.as_str()) {
+            names.push(name);
+        }
+    }
+
+    for (i, name) in names.into_iter().enumerate() {
+        let entry = entries.iter().find(|e| e.name == name);
+        let (source, value, value_style) = match entry {
+            Some(entry) => {
+                let source = match &entry.source {
+                    VariableSource::BuiltIn => "built-in".to_string(),
+                    VariableSource::Param => "param".to_string(),
+                    VariableSource::StepOutput(step) => step.clone(),
+                };
+                let mut style = Style::default().fg(Color::Green);
+                if entry.fresh {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+                (source, entry.value.clone(), style)
+            },
+            None => (
+                "-".to_string(),
+                "(pending)".to_string(),
+                Style::default().fg(Color::DarkGray),
+            ),
+        };
+
+        let label: String = name.chars().take(NAME_WIDTH - 1).collect();
+        let source: String = source.chars().take(SOURCE_WIDTH - 1).collect();
+        let mut line = Line::from(vec![
+            Span::styled(
+                format!("{:<w$}", label, w = NAME_WIDTH),
+                Style::default().fg(Color::White),
+            ),
+            Span::styled(
+                format!("{:<w$}", source, w = SOURCE_WIDTH),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(value, value_style),
+        ]);
+        if selected == Some(i) {
+            line.patch_style(Style::default().add_modifier(Modifier::REVERSED));
+        }
+        lines.push(line);
+
+        if let Some((_, steps)) = usage.iter().find(|(n, _)| n == name) {
+            lines.push(Line::from(Span::styled(
+                format!("{:w$}→ used by {}", "", steps.join(", "), w = NAME_WIDTH),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholder_names() {
+        let names = placeholder_names(r#"{"urn":"{upload.urn}","name":"demo-{uuid}","x":"{not valid}"}"#);
+        assert_eq!(names, vec!["upload.urn", "uuid"]);
+    }
+
+    #[test]
+    fn test_upsert_variables_replaces_and_marks_fresh() {
+        let mut entries = Vec::new();
+        upsert_variables(
+            &mut entries,
+            VariableSource::BuiltIn,
+            vec![("uuid".to_string(), "abc".to_string())],
+        );
+        upsert_variables(
+            &mut entries,
+            VariableSource::StepOutput("upload".to_string()),
+            vec![
+                ("urn".to_string(), "dXJu".to_string()),
+                ("uuid".to_string(), "def".to_string()),
+            ],
+        );
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].value, "def");
+        assert_eq!(entries[0].source, VariableSource::StepOutput("upload".to_string()));
+        assert!(entries.iter().all(|e| e.fresh));
+
+        upsert_variables(&mut entries, VariableSource::BuiltIn, Vec::new());
+        assert!(entries.iter().all(|e| !e.fresh));
+
+        // Values are listed after the header in capture order
+        assert_eq!(entry_line(None, &entries, 1), 3);
+        let lines = build_variable_lines(None, &entries, Some(1));
+        assert!(lines[3].spans[0].style.add_modifier.contains(Modifier::REVERSED));
+        assert!(!lines[2].spans[0].style.add_modifier.contains(Modifier::REVERSED));
+    }
+}
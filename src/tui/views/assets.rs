@@ -0,0 +1,192 @@
+// Assets: the Autodesk sample assets and what the selected workflow needs
+//
+// Lists every known sample asset by category with whether it is downloaded,
+// details the selected one, and ends with the files the selected workflow
+// requires and whether each exists.
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::assets::{AssetCategory, AssetDefinition};
+use crate::tui::glyphs::Glyphs;
+
+/// Files a workflow requires
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequiredAssets {
+    pub workflow_name: String,
+    /// Each path with whether it exists
+    pub files: Vec<(String, bool)>,
+}
+
+/// The Assets tab
+#[derive(Debug, Clone)]
+pub struct AssetsView {
+    /// Each asset with whether it is downloaded
+    pub assets: Vec<(AssetDefinition, bool)>,
+    pub selected: usize,
+    /// Requirements of the selected workflow, if it has any
+    pub required: Option<RequiredAssets>,
+    pub scroll: u16,
+    pub glyphs: Glyphs,
+}
+
+impl AssetsView {
+    /// Draw the tab into `area`
+    pub fn render(&self, f: &mut ratatui::Frame, area: Rect) {
+        let paragraph = Paragraph::new(self.lines())
+            .block(Block::default().borders(Borders::ALL).title("Assets (D=download)"))
+            .scroll((self.scroll, 0));
+        f.render_widget(paragraph, area);
+    }
+
+    fn lines(&self) -> Vec<Line<'_>> {
+        let glyphs = self.glyphs;
+        let rule = glyphs.double_rule.repeat(3);
+        let mut lines: Vec<Line> = vec![Line::from(vec![
+            Span::styled(format!("{} ", rule), Style::default().fg(Color::Cyan)),
+            Span::styled("AUTODESK SAMPLE ASSETS", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::styled(format!(" {}", rule), Style::default().fg(Color::Cyan)),
+        ])];
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("{} Autodesk, Inc. All rights reserved.", glyphs.copyright),
+            Style::default().fg(Color::DarkGray),
+        )));
+        lines.push(Line::from(""));
+
+        // Status summary
+        let downloaded = self.assets.iter().filter(|(_, d)| *d).count();
+        let total = self.assets.len();
+        let status_color = if downloaded == total { Color::Green } else { Color::Yellow };
+        lines.push(Line::from(vec![
+            Span::raw("Status: "),
+            Span::styled(format!("{}/{} downloaded", downloaded, total), Style::default().fg(status_color)),
+        ]));
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("Use {} to select, D to download selected asset", glyphs.arrows_up_down),
+            Style::default().fg(Color::DarkGray),
+        )));
+        lines.push(Line::from(""));
+
+        // Group by category
+        let mut current_category: Option<AssetCategory> = None;
+        for (i, (asset, is_downloaded)) in self.assets.iter().enumerate() {
+            if current_category != Some(asset.category) {
+                current_category = Some(asset.category);
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "{}{} {} {}",
+                        glyphs.corner_left,
+                        glyphs.rule,
+                        asset.category.display_name(),
+                        glyphs.rule.repeat(25)
+                    ),
+                    Style::default().fg(Color::Cyan),
+                )));
+            }
+
+            let status_icon = if *is_downloaded { glyphs.ok } else { glyphs.download };
+            let status_color = if *is_downloaded { Color::Green } else { Color::Yellow };
+            let is_selected = i == self.selected;
+            let line_style = if is_selected { Style::default().bg(Color::DarkGray) } else { Style::default() };
+            let prefix = if is_selected { "> " } else { "  " };
+
+            lines.push(Line::from(vec![
+                Span::styled(prefix, line_style),
+                Span::styled(status_icon, Style::default().fg(status_color)),
+                Span::styled(" ", Style::default()),
+                Span::styled(
+                    asset.name.as_str(),
+                    line_style.add_modifier(if is_selected { Modifier::BOLD } else { Modifier::empty() }),
+                ),
+                Span::styled(format!(" ({:.1} MB)", asset.estimated_size_mb), Style::default().fg(Color::DarkGray)),
+            ]));
+
+            if is_selected {
+                lines.push(Line::from(vec![
+                    Span::styled("    ", Style::default()),
+                    Span::styled(asset.description.as_str(), Style::default().fg(Color::Gray)),
+                ]));
+                if !*is_downloaded {
+                    lines.push(Line::from(vec![
+                        Span::styled("    ", Style::default()),
+                        Span::styled("[Press D to download]", Style::default().fg(Color::Yellow)),
+                    ]));
+                }
+            }
+        }
+
+        // Footer with the selected workflow's requirements
+        if let Some(required) = &self.required {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                glyphs.section(&format!("Required for '{}'", required.workflow_name)),
+                Style::default().fg(Color::Magenta),
+            )));
+            for (path, exists) in &required.files {
+                let color = if *exists { Color::Green } else { Color::Red };
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {} ", glyphs.status(*exists)), Style::default().fg(color)),
+                    Span::styled(path.as_str(), Style::default()),
+                ]));
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::glyphs::ASCII;
+    use crate::tui::views::render_lines;
+
+    fn asset(name: &str) -> AssetDefinition {
+        AssetDefinition {
+            name: name.to_string(),
+            description: format!("{} sample", name),
+            url: String::new(),
+            category: AssetCategory::Revit,
+            is_archive: false,
+            estimated_size_mb: 1.5,
+        }
+    }
+
+    #[test]
+    fn test_assets_render() {
+        let view = AssetsView {
+            assets: vec![(asset("House"), true), (asset("Office"), false)],
+            selected: 1,
+            required: Some(RequiredAssets {
+                workflow_name: "Upload".to_string(),
+                files: vec![("assets/office.rvt".to_string(), false)],
+            }),
+            scroll: 4,
+            glyphs: ASCII,
+        };
+        let lines = render_lines(44, 14, |f| view.render(f, f.size()));
+        assert_eq!(
+            lines[1..13].to_vec(),
+            vec![
+                "│Status: 1/2 downloaded                    │",
+                "│                                          │",
+                "│Use Up/Down to select, D to download selec│",
+                "│                                          │",
+                "│                                          │",
+                "│+- Autodesk Revit ------------------------│",
+                "│  + House (1.5 MB)                        │",
+                "│> v Office (1.5 MB)                       │",
+                "│    Office sample                         │",
+                "│    [Press D to download]                 │",
+                "│                                          │",
+                "│--- Required for 'Upload' ---             │",
+            ]
+        );
+    }
+}
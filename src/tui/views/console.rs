@@ -0,0 +1,64 @@
+// Console: the last lines of the session log
+//
+// Besides drawing the lines, the view reports where URLs ended up on screen
+// so they can be turned into terminal hyperlinks.
+
+use ratatui::{
+    layout::Rect,
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::tui::hyperlink::{self, LinkSpot};
+
+/// The console panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsoleView {
+    pub title: String,
+    /// Lines to show, oldest first
+    pub lines: Vec<String>,
+}
+
+impl ConsoleView {
+    /// Draw the panel into `area`, returning the positions of the URLs drawn
+    pub fn render(&self, f: &mut ratatui::Frame, area: Rect) -> Vec<LinkSpot> {
+        let text = self.lines.join("\n");
+        let block = Block::default().borders(Borders::ALL).title(self.title.as_str());
+        let inner = block.inner(area);
+        let links = text
+            .lines()
+            .enumerate()
+            .take(inner.height as usize)
+            .flat_map(|(row, line)| hyperlink::line_links(line, inner.x, inner.y + row as u16, inner.width, None))
+            .collect();
+        f.render_widget(Paragraph::new(text).block(block), area);
+        links
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::views::render_lines;
+
+    #[test]
+    fn test_console_render_and_links() {
+        let view = ConsoleView {
+            title: "Console Output".to_string(),
+            lines: vec!["Starting upload".to_string(), "Viewer: https://example.com/v".to_string()],
+        };
+        let mut links = Vec::new();
+        let lines = render_lines(40, 4, |f| links = view.render(f, f.size()));
+        assert_eq!(
+            lines,
+            vec![
+                "┌Console Output────────────────────────┐",
+                "│Starting upload                       │",
+                "│Viewer: https://example.com/v         │",
+                "└──────────────────────────────────────┘",
+            ]
+        );
+        assert_eq!(links.len(), 1);
+        assert_eq!((links[0].x, links[0].y), (9, 2));
+        assert_eq!(links[0].url, "https://example.com/v");
+    }
+}
@@ -0,0 +1,65 @@
+// Detail tabs: the tab bar above the details panel
+//
+// Titles arrive translated, with their status marks and, on narrow
+// terminals, abbreviated. Rendering returns the column range of each drawn
+// tab so a click can be mapped to the tab under it.
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Tabs},
+};
+
+use crate::tui::tabs;
+
+/// The tab bar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetailTabs {
+    /// Title of the bar's border
+    pub title: String,
+    pub titles: Vec<String>,
+    pub selected: usize,
+}
+
+impl DetailTabs {
+    /// Draw the bar into `area`, returning the `[start, end)` columns of each tab
+    pub fn render(&self, f: &mut ratatui::Frame, area: Rect) -> Vec<(u16, u16)> {
+        let block = Block::default().borders(Borders::ALL).title(self.title.as_str());
+        let inner = block.inner(area);
+        let bounds = tabs::tab_bounds(&self.titles, inner.x, inner.right());
+        let widget = Tabs::new(self.titles.clone())
+            .block(block)
+            .select(self.selected)
+            .style(Style::default().fg(Color::White))
+            .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+        f.render_widget(widget, area);
+        bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::views::render_lines;
+
+    #[test]
+    fn test_detail_tabs_render() {
+        let view = DetailTabs {
+            title: "Details".to_string(),
+            titles: vec!["Over +".to_string(), "Step".to_string(), "Flow".to_string()],
+            selected: 1,
+        };
+        let mut bounds = Vec::new();
+        let lines = render_lines(26, 3, |f| bounds = view.render(f, f.size()));
+        assert_eq!(
+            lines,
+            vec![
+                "┌Details─────────────────┐",
+                "│ Over + │ Step │ Flow   │",
+                "└────────────────────────┘",
+            ]
+        );
+        assert_eq!(bounds, vec![(1, 9), (10, 16), (17, 23)]);
+        assert_eq!(tabs::tab_at(&bounds, 12), Some(1));
+    }
+}
@@ -0,0 +1,52 @@
+// View components of the main screen
+//
+// Each view receives what it draws as plain data, built by `TuiApp` from its
+// state, and renders itself into an area of the frame. That keeps a view free
+// of the rest of the application, so it can be drawn on its own into a
+// `TestBackend` and checked cell by cell. Input on a view is turned into a
+// `Msg` that `TuiApp::update` applies in one place, instead of every key and
+// mouse handler changing the state directly. A new view (Resources, History,
+// Settings) follows the same shape: a data struct, `render`, and the messages
+// its input produces.
+
+pub mod assets;
+pub mod console;
+pub mod detail_tabs;
+pub mod popup;
+pub mod sidebar;
+pub mod toast;
+
+/// A change of the TUI state requested by input on a view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Msg {
+    /// Select a row of the sidebar
+    SelectRow(usize),
+    /// Collapse or expand a sidebar category
+    ToggleCategory(String),
+    /// Run the selected workflow or tour
+    RunSelected,
+    /// Show a detail tab from the top
+    SelectTab(usize),
+    /// Close the open popup
+    ClosePopup,
+    /// Open or close the sidebar drawer of the compact layout
+    ToggleDrawer,
+    /// Set the sidebar width in percent
+    ResizeSidebar(u16),
+    /// Set the console height in rows
+    ResizeConsole(u16),
+}
+
+/// Text of each row of a view rendered into a `width` x `height` test terminal
+#[cfg(test)]
+pub(crate) fn render_lines(width: u16, height: u16, draw: impl FnOnce(&mut ratatui::Frame)) -> Vec<String> {
+    let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, height)).unwrap();
+    terminal.draw(draw).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..height)
+        .map(|y| {
+            let row: String = (0..width).map(|x| buffer.get(x, y).symbol()).collect();
+            row.trim_end().to_string()
+        })
+        .collect()
+}
@@ -0,0 +1,173 @@
+// Popup: a centered dialog over the rest of the screen
+//
+// The dialog shows a message, optionally a URL with its QR code, and a hint
+// on how to close it. It stays inside the frame however small the terminal
+// is: the QR code is dropped when it doesn't fit, and the dialog shrinks with
+// the frame instead of being positioned outside of it.
+
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::tui::hyperlink::{self, LinkSpot};
+
+/// Columns kept free around the dialog
+const MARGIN: u16 = 4;
+/// Preferred width of the dialog
+const PREFERRED_WIDTH: u16 = 60;
+/// Smallest height of the dialog
+const MIN_HEIGHT: u16 = 10;
+
+/// The dialog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PopupView {
+    pub title: String,
+    pub message: String,
+    pub url: Option<String>,
+    /// QR code of `url`, one string per row
+    pub qr: Option<Vec<String>>,
+    /// How to close the dialog (and use its URL)
+    pub hint: String,
+}
+
+impl PopupView {
+    /// Area the dialog covers when centered in `size`
+    pub fn area(&self, size: Rect) -> Rect {
+        self.layout(size).0
+    }
+
+    /// Area of the dialog and the QR code, if it fits
+    fn layout(&self, size: Rect) -> (Rect, Option<&Vec<String>>) {
+        // Wide and tall enough for a QR code that fits
+        let qr = self.qr.as_ref().filter(|qr| {
+            let width = qr.first().map_or(0, |l| l.chars().count()) as u16;
+            width + 4 <= size.width.saturating_sub(MARGIN)
+                && qr.len() as u16 + 12 <= size.height.saturating_sub(MARGIN)
+        });
+        let qr_width = qr.and_then(|qr| qr.first()).map_or(0, |l| l.chars().count() as u16);
+        let qr_height = qr.map_or(0, |qr| qr.len() as u16 + 1);
+        let message_lines = self.message.lines().count() as u16;
+        let popup_width = PREFERRED_WIDTH.max(qr_width + 4).min(size.width.saturating_sub(MARGIN));
+        let popup_height = (message_lines + qr_height + 7).max(MIN_HEIGHT).min(size.height.saturating_sub(MARGIN));
+
+        let popup_x = size.x + size.width.saturating_sub(popup_width) / 2;
+        let popup_y = size.y + size.height.saturating_sub(popup_height) / 2;
+        (Rect::new(popup_x, popup_y, popup_width, popup_height), qr)
+    }
+
+    /// Draw the dialog centered in `size`, returning the position of its URL
+    pub fn render(&self, f: &mut ratatui::Frame, size: Rect) -> Vec<LinkSpot> {
+        let (popup_area, qr) = self.layout(size);
+        let qr_height = qr.map_or(0, |qr| qr.len() as u16 + 1);
+        let message_lines = self.message.lines().count() as u16;
+        f.render_widget(Clear, popup_area);
+
+        let mut lines = vec![Line::from("")];
+        lines.extend(
+            self.message
+                .lines()
+                .map(|line| Line::from(Span::styled(line, Style::default().fg(Color::White)))),
+        );
+        lines.push(Line::from(""));
+        // Light modules are drawn, so the code must be white on black
+        if let Some(qr) = qr {
+            let style = Style::default().fg(Color::White).bg(Color::Black);
+            lines.extend(qr.iter().map(|row| Line::from(Span::styled(row.as_str(), style))));
+            lines.push(Line::from(""));
+        } else if self.qr.is_some() {
+            lines.push(Line::from(Span::styled(
+                "Enlarge the terminal to show the QR code",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        if let Some(url) = &self.url {
+            lines.push(Line::from(Span::styled(
+                format!("URL: {}", url),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::UNDERLINED),
+            )));
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(self.hint.as_str(), Style::default().fg(Color::DarkGray))));
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title(Span::styled(
+                self.title.as_str(),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ));
+        let inner = block.inner(popup_area);
+        f.render_widget(Paragraph::new(lines).block(block).alignment(Alignment::Center), popup_area);
+
+        // The centered "URL: ..." line follows the message, a blank line and
+        // the QR code (or the note that it didn't fit)
+        let url_row = message_lines + 2 + if qr.is_some() { qr_height } else { u16::from(self.qr.is_some()) };
+        match &self.url {
+            Some(url) if url_row < inner.height => {
+                let text = format!("URL: {}", url);
+                let width = text.width() as u16;
+                let x = inner.x + inner.width.saturating_sub(width) / 2;
+                hyperlink::line_links(
+                    &text,
+                    x,
+                    inner.y + url_row,
+                    width.min(inner.width),
+                    Some(crossterm::style::Color::Cyan),
+                )
+            },
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::views::render_lines;
+
+    fn view() -> PopupView {
+        PopupView {
+            title: " Done ".to_string(),
+            message: "Uploaded".to_string(),
+            url: Some("https://a.io".to_string()),
+            qr: None,
+            hint: "(any key)".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_popup_render() {
+        let view = view();
+        let mut links = Vec::new();
+        let lines = render_lines(24, 14, |f| links = view.render(f, f.size()));
+        assert_eq!(
+            lines[2..12].to_vec(),
+            vec![
+                "  ┌ Done ────────────┐",
+                "  │                  │",
+                "  │     Uploaded     │",
+                "  │                  │",
+                "  │ URL: https://a.io│",
+                "  │                  │",
+                "  │     (any key)    │",
+                "  │                  │",
+                "  │                  │",
+                "  └──────────────────┘",
+            ]
+        );
+        assert_eq!(links.len(), 1);
+        assert_eq!((links[0].x, links[0].y), (8, 6));
+
+        // Frames smaller than the margin still draw, as an empty dialog
+        for (width, height) in [(3, 2), (1, 1), (8, 3)] {
+            render_lines(width, height, |f| {
+                view.render(f, f.size());
+            });
+        }
+    }
+}
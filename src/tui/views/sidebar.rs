@@ -0,0 +1,109 @@
+// Sidebar: the workflow list, grouped by category
+//
+// Rows are category headers, tours and workflows, already formatted by
+// `TuiApp`. The view draws them as a list with the selection highlighted and
+// maps a click back to the row under it, taking the list's scroll offset and
+// the `[Run]` button at the end of each row into account.
+
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, List, ListItem, ListState},
+};
+
+/// Columns from the right border where the `[Run]` button starts
+const RUN_BUTTON_COLUMNS: u16 = 8;
+
+/// One row of the list
+#[derive(Debug, Clone, PartialEq)]
+pub struct SidebarRow {
+    pub text: String,
+    pub style: Style,
+}
+
+/// Where a click on the sidebar landed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SidebarHit {
+    /// Row under the click
+    pub row: usize,
+    /// Whether the click was on the row's `[Run]` button
+    pub run_button: bool,
+}
+
+/// The workflow list
+#[derive(Debug, Clone, PartialEq)]
+pub struct SidebarView {
+    pub title: String,
+    pub rows: Vec<SidebarRow>,
+}
+
+impl SidebarView {
+    /// Draw the list into `area`, scrolled to keep the selection of `state` visible
+    pub fn render(&self, f: &mut ratatui::Frame, area: Rect, state: &mut ListState) {
+        let items: Vec<ListItem> =
+            self.rows.iter().map(|row| ListItem::new(row.text.as_str()).style(row.style)).collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(self.title.as_str()))
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD).fg(ratatui::style::Color::Yellow))
+            .highlight_symbol("> ");
+        f.render_stateful_widget(list, area, state);
+    }
+
+    /// Row under the cell (`x`, `y`) of a list drawn in `area` scrolled by `offset` rows
+    pub fn hit(&self, area: Rect, offset: usize, x: u16, y: u16) -> Option<SidebarHit> {
+        let inside = x >= area.x && x < area.right() && y > area.y && y + 1 < area.bottom();
+        if !inside {
+            return None;
+        }
+        let row = offset + (y - area.y - 1) as usize;
+        (row < self.rows.len()).then(|| SidebarHit {
+            row,
+            run_button: x >= area.right().saturating_sub(RUN_BUTTON_COLUMNS),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::views::render_lines;
+
+    fn view() -> SidebarView {
+        let rows = ["[-] Storage (2)", "  Upload [Run]", "  Download [Run]"]
+            .iter()
+            .map(|text| SidebarRow {
+                text: text.to_string(),
+                style: Style::default(),
+            })
+            .collect();
+        SidebarView {
+            title: "Workflows".to_string(),
+            rows,
+        }
+    }
+
+    #[test]
+    fn test_sidebar_render_and_hit() {
+        let view = view();
+        let mut state = ListState::default();
+        state.select(Some(2));
+        let lines = render_lines(24, 4, |f| view.render(f, f.size(), &mut state));
+        // Two rows fit, so the list scrolls to keep the selection visible
+        assert_eq!(
+            lines,
+            vec![
+                "┌Workflows─────────────┐",
+                "│    Upload [Run]      │",
+                "│>   Download [Run]    │",
+                "└──────────────────────┘",
+            ]
+        );
+
+        let area = Rect::new(0, 0, 24, 4);
+        assert_eq!(state.offset(), 1);
+        assert_eq!(view.hit(area, state.offset(), 3, 2), Some(SidebarHit { row: 2, run_button: false }));
+        assert_eq!(view.hit(area, state.offset(), 20, 1), Some(SidebarHit { row: 1, run_button: true }));
+        assert_eq!(view.hit(area, state.offset(), 3, 0), None);
+        assert_eq!(view.hit(area, state.offset(), 3, 3), None);
+    }
+}
@@ -0,0 +1,78 @@
+// Toast: a short notice in the top right corner
+//
+// Unlike the popup, a toast doesn't take input and goes away by itself after
+// a few seconds, so it suits news the presenter may glance at or ignore (the
+// configuration was reloaded). It is drawn over the panels, inside the frame
+// however narrow it is.
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+/// Widest the notice gets
+const MAX_WIDTH: u16 = 48;
+
+/// The notice
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToastView {
+    pub title: String,
+    pub lines: Vec<String>,
+}
+
+impl ToastView {
+    /// Area the notice covers in the top right corner of `size`
+    pub fn area(&self, size: Rect) -> Rect {
+        let text_width = self
+            .lines
+            .iter()
+            .map(|line| line.chars().count())
+            .chain([self.title.chars().count()])
+            .max()
+            .unwrap_or(0) as u16;
+        let width = (text_width + 2).min(MAX_WIDTH).min(size.width);
+        let height = (self.lines.len() as u16 + 2).min(size.height);
+        Rect::new(size.x + size.width - width, size.y, width, height)
+    }
+
+    /// Draw the notice in the top right corner of `size`
+    pub fn render(&self, f: &mut ratatui::Frame, size: Rect) {
+        let area = self.area(size);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Green))
+            .title(self.title.as_str());
+        let lines: Vec<Line> = self.lines.iter().map(|line| Line::from(line.as_str())).collect();
+        f.render_widget(Clear, area);
+        f.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: true }), area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::views::render_lines;
+
+    #[test]
+    fn test_toast_render() {
+        let view = ToastView {
+            title: " Config ".to_string(),
+            lines: vec!["Profiles changed: staging".to_string()],
+        };
+        let lines = render_lines(40, 5, |f| view.render(f, f.size()));
+        assert_eq!(
+            lines[..3].to_vec(),
+            vec![
+                "             ┌ Config ─────────────────┐",
+                "             │Profiles changed: staging│",
+                "             └─────────────────────────┘",
+            ]
+        );
+
+        // A narrow frame keeps it inside
+        let lines = render_lines(12, 3, |f| view.render(f, f.size()));
+        assert!(lines[0].starts_with('┌') && lines[0].ends_with('┐'), "{:?}", lines);
+    }
+}
@@ -0,0 +1,295 @@
+// asciinema v2 recordings for RAPS Demo Workflows
+//
+// `--record session.cast` tees everything the TUI writes to the terminal into
+// an asciinema v2 file (a JSON header line followed by `[time, "o", data]`
+// events), and `raps-demo render` turns a session log into a cast that replays
+// the executed commands, so documentation GIFs can be produced with
+// asciinema/agg instead of screen-recording software.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use super::redact::RedactingWriter;
+
+/// Longest pause kept when rendering a session log, in seconds
+pub const MAX_IDLE_SECS: f64 = 2.0;
+
+/// Delay between typed characters when rendering commands, in seconds
+const TYPING_DELAY_SECS: f64 = 0.03;
+
+/// Header line of an asciinema v2 file
+#[derive(Debug, Clone, Serialize)]
+struct CastHeader<'a> {
+    version: u8,
+    width: u16,
+    height: u16,
+    timestamp: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<&'a str>,
+}
+
+/// Writes asciinema v2 events
+pub struct CastWriter<W: Write> {
+    out: W,
+}
+
+impl CastWriter<BufWriter<File>> {
+    /// Create a cast file at `path`
+    pub fn create<P: AsRef<Path>>(path: P, width: u16, height: u16, title: Option<&str>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path).with_context(|| format!("Failed to create recording: {:?}", path))?;
+        Self::new(BufWriter::new(file), width, height, title)
+    }
+}
+
+impl<W: Write> CastWriter<W> {
+    /// Start a cast by writing its header
+    pub fn new(mut out: W, width: u16, height: u16, title: Option<&str>) -> Result<Self> {
+        let header = CastHeader {
+            version: 2,
+            width,
+            height,
+            timestamp: chrono::Utc::now().timestamp(),
+            title,
+        };
+        writeln!(out, "{}", serde_json::to_string(&header)?)?;
+        Ok(Self { out })
+    }
+
+    /// Append an output event `secs` after the start of the recording
+    pub fn output(&mut self, secs: f64, data: &str) -> io::Result<()> {
+        let event = serde_json::to_string(&(secs, "o", data)).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        writeln!(self.out, "{}", event)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Output events of a cast, timed from when the writer was created
+struct CastEvents {
+    cast: CastWriter<BufWriter<File>>,
+    started: Instant,
+}
+
+impl Write for CastEvents {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.cast.output(self.started.elapsed().as_secs_f64(), &String::from_utf8_lossy(buf))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.cast.flush()
+    }
+}
+
+/// Terminal writer that optionally records everything written to a cast.
+///
+/// The recording is redacted a line at a time, and at each flush (the end of
+/// a drawn frame), rather than per write: a secret the terminal backend
+/// writes in two pieces would otherwise reach the cast unmasked.
+pub struct SessionRecorder<W: Write> {
+    inner: W,
+    cast: Option<RedactingWriter<CastEvents>>,
+}
+
+impl<W: Write> SessionRecorder<W> {
+    /// Pass-through writer without recording
+    pub fn passthrough(inner: W) -> Self {
+        Self { inner, cast: None }
+    }
+
+    /// Writer recording into `cast`
+    pub fn recording(inner: W, cast: CastWriter<BufWriter<File>>) -> Self {
+        let events = CastEvents {
+            cast,
+            started: Instant::now(),
+        };
+        Self {
+            inner,
+            cast: Some(RedactingWriter::new(events)),
+        }
+    }
+}
+
+impl<W: Write> Write for SessionRecorder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if let Some(cast) = self.cast.as_mut() {
+            cast.write_all(&buf[..written])?;
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(cast) = self.cast.as_mut() {
+            cast.flush()?;
+        }
+        self.inner.flush()
+    }
+}
+
+/// Convert a session log (JSON lines) into cast events as `(seconds, data)`.
+///
+/// Commands are "typed" at a prompt when they started and followed by their
+/// outcome; informational messages are shown as comments. Pauses longer than
+/// [`MAX_IDLE_SECS`] are shortened.
+pub fn session_log_events(log: &str) -> Vec<(f64, String)> {
+    let mut raw: Vec<(f64, String)> = Vec::new();
+    let mut origin = None;
+
+    for line in log.lines() {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        let Some(time) = entry
+            .get("timestamp")
+            .and_then(|t| t.as_str())
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        else {
+            continue;
+        };
+        let origin = *origin.get_or_insert(time);
+        let secs = (time - origin).num_milliseconds() as f64 / 1000.0;
+        let fields = entry.get("fields").cloned().unwrap_or_default();
+        let field = |name: &str| fields.get(name).and_then(|v| v.as_str()).map(str::to_string);
+
+        if let Some(command) = field("command") {
+            let duration = fields.get("duration_ms").and_then(|v| v.as_u64()).unwrap_or(0) as f64 / 1000.0;
+            let start = (secs - duration).max(0.0);
+            raw.push((start, "\x1b[1;32m$\x1b[0m ".to_string()));
+            for (i, c) in command.chars().enumerate() {
+                raw.push((start + (i + 1) as f64 * TYPING_DELAY_SECS, c.to_string()));
+            }
+            let typed = start + (command.chars().count() + 1) as f64 * TYPING_DELAY_SECS;
+            raw.push((typed, "\r\n".to_string()));
+
+            let exit_code = fields.get("exit_code").and_then(|v| v.as_i64()).unwrap_or(0);
+            let outcome = if exit_code == 0 {
+                format!("\x1b[32m✓ exit 0 ({:.1}s)\x1b[0m\r\n", duration)
+            } else {
+                let stderr = field("stderr").unwrap_or_default();
+                let mut text = format!("\x1b[31m✗ exit {} ({:.1}s)\x1b[0m\r\n", exit_code, duration);
+                for line in stderr.lines().take(5) {
+                    text.push_str(&format!("  {}\r\n", line));
+                }
+                text
+            };
+            raw.push((secs.max(typed), outcome));
+        } else if entry.get("level").and_then(|l| l.as_str()) == Some("INFO") {
+            if let Some(message) = field("message") {
+                raw.push((secs, format!("\x1b[2m# {}\x1b[0m\r\n", message)));
+            }
+        }
+    }
+
+    // Events must be in order; typed commands start before their log entry
+    raw.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut events = Vec::with_capacity(raw.len());
+    let (mut last_raw, mut clock) = (0.0, 0.0);
+    for (secs, data) in raw {
+        clock += (secs - last_raw).clamp(0.0, MAX_IDLE_SECS);
+        last_raw = secs;
+        events.push((clock, data));
+    }
+    events
+}
+
+/// Render the session log at `log_path` into a cast file at `output`
+pub fn render_session_log(log_path: &Path, output: &Path, width: u16, height: u16) -> Result<usize> {
+    let log = std::fs::read_to_string(log_path)
+        .with_context(|| format!("Failed to read session log: {:?}", log_path))?;
+    let events = session_log_events(&log);
+    if events.is_empty() {
+        anyhow::bail!("Session log {:?} contains no commands or messages to render", log_path);
+    }
+
+    let title = log_path.file_stem().map(|s| s.to_string_lossy().into_owned());
+    let mut cast = CastWriter::create(output, width, height, title.as_deref())?;
+    for (secs, data) in &events {
+        cast.output(*secs, data)?;
+    }
+    cast.flush()?;
+    Ok(events.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_log_events() {
+        let log = [
+            r#"{"timestamp":"2024-05-01T10:00:00.000Z","level":"INFO","fields":{"message":"Executing step: upload - Upload"}}"#,
+            r#"{"timestamp":"2024-05-01T10:00:01.500Z","level":"DEBUG","fields":{"message":"done","command":"raps ls","exit_code":0,"duration_ms":1000}}"#,
+            r#"{"timestamp":"2024-05-01T10:01:00.000Z","level":"WARN","fields":{"message":"failed","command":"raps x","exit_code":2,"duration_ms":0,"stderr":"boom"}}"#,
+            "not json",
+        ]
+        .join("\n");
+
+        let events = session_log_events(&log);
+        assert_eq!(events[0], (0.0, "\x1b[2m# Executing step: upload - Upload\x1b[0m\r\n".to_string()));
+        // Typing starts when the command started, half a second later
+        assert_eq!(events[1].0, 0.5);
+        assert!(events.iter().any(|(_, d)| d.contains("✓ exit 0 (1.0s)")));
+        assert!(events.last().unwrap().1.contains("✗ exit 2"));
+        assert!(events.last().unwrap().1.contains("boom"));
+        // The minute-long gap is shortened and times never go backwards
+        assert!(events.last().unwrap().0 < 5.0);
+        assert!(events.windows(2).all(|w| w[0].0 <= w[1].0));
+    }
+
+    #[test]
+    fn test_session_recorder_writes_cast() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.cast");
+        let cast = CastWriter::create(&path, 80, 24, None).unwrap();
+        let mut terminal = Vec::new();
+        {
+            let mut recorder = SessionRecorder::recording(&mut terminal, cast);
+            // "✓" split across two writes
+            recorder.write_all(&"a✓".as_bytes()[..2]).unwrap();
+            recorder.write_all(&"a✓".as_bytes()[2..]).unwrap();
+            recorder.flush().unwrap();
+        }
+        assert_eq!(terminal, "a✓".as_bytes());
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert!(lines[0].contains("\"version\":2"));
+        assert!(lines[0].contains("\"width\":80"));
+        let data: Vec<String> = lines[1..]
+            .iter()
+            .map(|l| serde_json::from_str::<(f64, String, String)>(l).unwrap().2)
+            .collect();
+        assert_eq!(data.concat(), "a✓");
+    }
+
+    #[test]
+    fn test_session_recorder_redacts_split_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.cast");
+        let cast = CastWriter::create(&path, 80, 24, None).unwrap();
+        let mut terminal = Vec::new();
+        {
+            let mut recorder = SessionRecorder::recording(&mut terminal, cast);
+            recorder.write_all(b"Authorization: Bearer eyJhb").unwrap();
+            recorder.write_all(b"GciOiJIUzI1\r\nnext").unwrap();
+            recorder.flush().unwrap();
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let data: String = content
+            .lines()
+            .skip(1)
+            .map(|l| serde_json::from_str::<(f64, String, String)>(l).unwrap().2)
+            .collect();
+        assert_eq!(data, "Authorization: Bearer [REDACTED]\r\nnext");
+    }
+}
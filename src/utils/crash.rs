@@ -0,0 +1,186 @@
+// Crash reports for RAPS Demo Workflows
+//
+// A panic hook writes a report (panic message, backtrace, the last log lines,
+// the active workflow/step and version information) to the config
+// directory's `crashes/` folder and prints its path. The TUI registers a
+// terminal-restore callback first, so the path is printed to a usable
+// terminal rather than into the alternate screen.
+
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once, OnceLock};
+use tracing::field::{Field, Visit};
+use tracing_subscriber::layer::Context as LayerContext;
+use tracing_subscriber::Layer;
+
+use crate::config::types::ConfigPaths;
+use super::redact::redact_text;
+
+/// Number of log lines kept for crash reports
+pub const MAX_LOG_LINES: usize = 100;
+
+/// What a crash report knows about the session
+#[derive(Debug, Default)]
+struct CrashState {
+    recent_logs: VecDeque<String>,
+    workflow_id: Option<String>,
+    step_id: Option<String>,
+    /// Called before the report is written, e.g. to leave raw mode
+    restore: Option<fn()>,
+}
+
+fn state() -> &'static Mutex<CrashState> {
+    static STATE: OnceLock<Mutex<CrashState>> = OnceLock::new();
+    STATE.get_or_init(Default::default)
+}
+
+/// Remember the workflow step currently running
+pub fn set_active_step(workflow_id: &str, step_id: &str) {
+    if let Ok(mut state) = state().lock() {
+        state.workflow_id = Some(workflow_id.to_string());
+        state.step_id = Some(step_id.to_string());
+    }
+}
+
+/// Register (or clear) a callback that restores the terminal before reporting
+pub fn set_terminal_restore(restore: Option<fn()>) {
+    if let Ok(mut state) = state().lock() {
+        state.restore = restore;
+    }
+}
+
+fn push_log_line(line: String) {
+    if let Ok(mut state) = state().lock() {
+        if state.recent_logs.len() == MAX_LOG_LINES {
+            state.recent_logs.pop_front();
+        }
+        state.recent_logs.push_back(line);
+    }
+}
+
+/// Tracing layer keeping the last [`MAX_LOG_LINES`] events for crash reports
+pub struct CrashLogLayer;
+
+impl<S: tracing::Subscriber> Layer<S> for CrashLogLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: LayerContext<'_, S>) {
+        let mut visitor = LineVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        push_log_line(format!(
+            "{} {:>5} {}: {}{}",
+            chrono::Utc::now().format("%H:%M:%S%.3f"),
+            metadata.level(),
+            metadata.target(),
+            visitor.message,
+            visitor.fields
+        ));
+    }
+}
+
+/// Formats an event's message and fields on one line
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LineVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// Install the crash-reporting panic hook (once; later calls do nothing)
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            // Take what we need without holding the lock while restoring or writing
+            let (restore, report) = match state().lock() {
+                Ok(state) => (
+                    state.restore,
+                    crash_report(&info.to_string(), &std::backtrace::Backtrace::force_capture().to_string(), &state),
+                ),
+                Err(_) => (None, info.to_string()),
+            };
+            if let Some(restore) = restore {
+                restore();
+            }
+            previous(info);
+            match ConfigPaths::crashes_dir().and_then(|dir| write_crash_report(&dir, &report)) {
+                Ok(path) => eprintln!(
+                    "\nraps-demo crashed. A crash report was written to:\n  {}\nPlease attach it when reporting the problem.",
+                    path.display()
+                ),
+                Err(e) => eprintln!("\nraps-demo crashed and the crash report could not be saved: {:#}\n{}", e, report),
+            }
+        }));
+    });
+}
+
+/// Text of a crash report
+fn crash_report(panic: &str, backtrace: &str, state: &CrashState) -> String {
+    let mut report = String::new();
+    let _ = writeln!(report, "raps-demo crash report");
+    let _ = writeln!(report, "Time: {}", chrono::Utc::now().to_rfc3339());
+    let _ = writeln!(report, "Version: {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(report, "Platform: {} {}", std::env::consts::OS, std::env::consts::ARCH);
+    let _ = writeln!(report, "Terminal: {}", std::env::var("TERM").unwrap_or_else(|_| "unknown".to_string()));
+    let _ = writeln!(report, "Workflow: {}", state.workflow_id.as_deref().unwrap_or("none"));
+    let _ = writeln!(report, "Step: {}", state.step_id.as_deref().unwrap_or("none"));
+    let _ = writeln!(report, "\n== Panic ==\n{}", panic);
+    let _ = writeln!(report, "\n== Backtrace ==\n{}", backtrace);
+    let _ = writeln!(report, "== Last {} log lines ==", state.recent_logs.len());
+    for line in &state.recent_logs {
+        let _ = writeln!(report, "{}", line);
+    }
+    report
+}
+
+/// Write a crash report into `dir`, returning its path
+pub fn write_crash_report(dir: &Path, report: &str) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create crash directory: {:?}", dir))?;
+    let path = dir.join(format!("crash-{}.txt", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+    std::fs::write(&path, redact_text(report)).with_context(|| format!("Failed to write crash report: {:?}", path))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crash_report_contents() {
+        let mut state = CrashState {
+            workflow_id: Some("oss-bucket-lifecycle".to_string()),
+            step_id: Some("upload".to_string()),
+            ..Default::default()
+        };
+        state.recent_logs.extend((0..3).map(|i| format!("line {}", i)));
+
+        let report = crash_report("panicked at src/main.rs:1:1:\nboom", "0: main", &state);
+        assert!(report.contains("Workflow: oss-bucket-lifecycle"));
+        assert!(report.contains("Step: upload"));
+        assert!(report.contains("boom"));
+        assert!(report.contains("== Last 3 log lines ==\nline 0\nline 1\nline 2\n"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_crash_report(dir.path(), &report).unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(), report);
+    }
+}
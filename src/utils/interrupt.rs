@@ -0,0 +1,89 @@
+// Ctrl-C handling for RAPS Demo Workflows
+//
+// An interrupt cancels running workflows (killing their RAPS processes) through
+// `WorkflowEngine::interrupt`, which registers each one with the cleanup
+// orchestrator so the APS resources it created are listed with cleanup
+// instructions, and persists that record next to the resource tracker state.
+// This module holds the shared flag and the summary printed on exit. In the
+// TUI, raw mode delivers Ctrl-C as a key press; outside it (and for signals
+// sent by other processes) a handler installed on the runtime raises the same
+// flag.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::resource::InterruptedWorkflowCleanup;
+
+/// Flag raised by Ctrl-C, shared between the signal handler and the UI loop
+#[derive(Debug, Clone, Default)]
+pub struct InterruptSignal {
+    raised: Arc<AtomicBool>,
+}
+
+impl InterruptSignal {
+    /// Create a flag raised whenever the process receives Ctrl-C
+    pub fn install() -> Self {
+        let signal = Self::default();
+        let handler = signal.clone();
+        tokio::spawn(async move {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                handler.raise();
+            }
+        });
+        signal
+    }
+
+    /// Raise the flag (e.g. for a Ctrl-C key press in raw mode)
+    pub fn raise(&self) {
+        self.raised.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether the flag was raised, clearing it
+    pub fn take(&self) -> bool {
+        self.raised.swap(false, Ordering::SeqCst)
+    }
+}
+
+/// Lines describing what an interrupt left behind, for printing after exit
+pub fn interrupted_summary(records: &[InterruptedWorkflowCleanup]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for record in records {
+        lines.push(format!(
+            "Interrupted '{}' ({} resource(s) created)",
+            record.workflow_id,
+            record.created_resources.len()
+        ));
+        lines.extend(record.manual_instructions.iter().map(|i| format!("  - {}", i)));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_interrupt_signal_and_summary() {
+        let signal = InterruptSignal::default();
+        assert!(!signal.take());
+        signal.clone().raise();
+        assert!(signal.take());
+        assert!(!signal.take());
+
+        let record = InterruptedWorkflowCleanup {
+            workflow_id: "oss-bucket-lifecycle".to_string(),
+            interrupted_at: Utc::now(),
+            created_resources: vec![],
+            manual_instructions: vec!["Clean up Bucket 'demo'".to_string()],
+            automated_commands: vec![],
+        };
+        assert_eq!(
+            interrupted_summary(&[record]),
+            vec![
+                "Interrupted 'oss-bucket-lifecycle' (0 resource(s) created)",
+                "  - Clean up Bucket 'demo'"
+            ]
+        );
+    }
+}
@@ -0,0 +1,110 @@
+// Session log files for RAPS Demo Workflows
+//
+// Every run writes a JSON-lines transcript (commands, exit codes, durations,
+// step progress) to the config directory's `logs/` folder, so a failed demo
+// can be investigated after the fact. Old sessions are pruned on startup.
+
+use anyhow::{Context, Result};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use crate::config::types::ConfigPaths;
+
+/// Number of session log files kept in the logs directory
+pub const MAX_SESSION_LOGS: usize = 20;
+
+/// Extension used for session log files
+const SESSION_LOG_EXTENSION: &str = "jsonl";
+
+/// Create a new session log file in the default logs directory
+pub fn create_session_log() -> Result<(PathBuf, File)> {
+    create_session_log_in(&ConfigPaths::logs_dir()?, MAX_SESSION_LOGS)
+}
+
+/// Create a new session log file in `dir`, keeping at most `keep` files
+pub fn create_session_log_in(dir: &Path, keep: usize) -> Result<(PathBuf, File)> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create logs directory: {:?}", dir))?;
+
+    let session = format!(
+        "{}-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        &uuid::Uuid::new_v4().simple().to_string()[..8]
+    );
+    let path = dir.join(format!("{}.{}", session, SESSION_LOG_EXTENSION));
+
+    // Make room for the new session before creating it
+    prune_session_logs(dir, keep.saturating_sub(1), &path)?;
+
+    let file = File::create(&path)
+        .with_context(|| format!("Failed to create session log: {:?}", path))?;
+    Ok((path, file))
+}
+
+/// Delete the oldest session logs so that at most `keep` remain besides
+/// `current`, the log of this session, which is never deleted
+pub fn prune_session_logs(dir: &Path, keep: usize, current: &Path) -> Result<()> {
+    let mut logs: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read logs directory: {:?}", dir))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == SESSION_LOG_EXTENSION))
+        .filter(|path| path != current)
+        .collect();
+
+    if logs.len() <= keep {
+        return Ok(());
+    }
+
+    // Session names start with a timestamp, so name order is age order
+    logs.sort();
+    for old in &logs[..logs.len() - keep] {
+        if let Err(e) = fs::remove_file(old) {
+            tracing::debug!("Failed to remove old session log {:?}: {}", old, e);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_session_logs_are_rotated() {
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..5 {
+            fs::write(temp_dir.path().join(format!("20240101-00000{}-aaaa.jsonl", i)), "").unwrap();
+        }
+        fs::write(temp_dir.path().join("notes.txt"), "").unwrap();
+
+        let (path, _file) = create_session_log_in(temp_dir.path(), 3).unwrap();
+
+        let mut remaining: Vec<String> = fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        remaining.sort();
+
+        assert!(path.exists());
+        assert_eq!(remaining.len(), 4);
+        assert_eq!(remaining[0], "20240101-000003-aaaa.jsonl");
+        assert!(remaining.contains(&"notes.txt".to_string()));
+    }
+
+    #[test]
+    fn test_pruning_keeps_the_current_session() {
+        let temp_dir = TempDir::new().unwrap();
+        // Sorts first, e.g. after the clock was set back
+        let current = temp_dir.path().join("20000101-000000-cccc.jsonl");
+        fs::write(&current, "").unwrap();
+        for i in 0..3 {
+            fs::write(temp_dir.path().join(format!("20240101-00000{}-aaaa.jsonl", i)), "").unwrap();
+        }
+
+        prune_session_logs(temp_dir.path(), 0, &current).unwrap();
+
+        assert!(current.exists());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+}
@@ -1,3 +1,12 @@
 // Shared utility modules for RAPS Demo Workflows
 
+pub mod cast;
+pub mod crash;
+pub mod encoding;
+pub mod interrupt;
+pub mod logging;
+pub mod qr;
+pub mod redact;
 pub mod serde_helpers;
+pub mod telemetry;
+pub mod update;
@@ -0,0 +1,58 @@
+// QR codes for the terminal
+//
+// Signed URLs are long and awkward to type, but an audience can scan a QR code
+// off the projector. Codes are drawn with Unicode half blocks, two module rows
+// per text row, so a typical signed URL fits a normal terminal window. Light
+// modules are the drawn ones: render the lines light-on-dark (e.g. white on
+// black) so the code reads as dark-on-light to a phone camera.
+
+use anyhow::{Context, Result};
+use qrcodegen::{QrCode, QrCodeEcc};
+
+/// Light modules around the code; scanners need a margin to find it
+const QUIET_ZONE: i32 = 2;
+
+/// Text rows of a QR code for `text`, one character per module column
+pub fn qr_lines(text: &str) -> Result<Vec<String>> {
+    let code = QrCode::encode_text(text, QrCodeEcc::Low).context("Text is too long for a QR code")?;
+    let light = |x: i32, y: i32| !code.get_module(x, y);
+    let (start, end) = (-QUIET_ZONE, code.size() + QUIET_ZONE);
+
+    let mut lines = Vec::new();
+    let mut y = start;
+    while y < end {
+        let line = (start..end)
+            .map(|x| match (light(x, y), y + 1 < end && light(x, y + 1)) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            })
+            .collect();
+        lines.push(line);
+        y += 2;
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qr_lines() {
+        let url = "https://developer.api.autodesk.com/oss/v2/signedresources/abc?region=US";
+        let lines = qr_lines(url).unwrap();
+        let width = lines[0].chars().count();
+        // Square: twice as many module rows as text rows (rounded up)
+        assert!(lines.iter().all(|l| l.chars().count() == width));
+        assert_eq!(lines.len(), (width + 1) / 2);
+        // The quiet zone is light all the way round
+        assert!(lines[0].chars().all(|c| c == '█'));
+        let (last, rows) = lines.split_last().unwrap();
+        assert!(rows.iter().all(|l| l.starts_with("██") && l.ends_with("██")));
+        assert!(last.chars().all(|c| c == '▀'));
+
+        assert!(qr_lines(&"x".repeat(8000)).is_err());
+    }
+}
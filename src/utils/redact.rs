@@ -0,0 +1,345 @@
+// Secret redaction for RAPS Demo Workflows
+//
+// Demos are screenshotted, recorded and shared, so nothing the demo shows or
+// keeps should carry a usable credential. Text going to the console, stored
+// step results, session logs and recordings passes through `redact_text`,
+// which masks bearer tokens, values of secret-looking keys (`access_token`,
+// `client_secret`, `--token`, ...), signature parameters of signed URLs and
+// the ID of OSS signed resources. Secrets the demo resolved itself (step
+// `secrets:`) are registered and masked wherever they appear. Formatting is
+// kept, so redacted JSON is still readable. Streams are redacted line by
+// line, so a secret written in two pieces is still masked.
+
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+/// What masked values are replaced with
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Key fragments (lowercase) whose values are credentials
+const SECRET_KEYS: [&str; 6] = ["token", "secret", "password", "authorization", "credential", "cookie"];
+
+/// Key fragments that describe a credential rather than hold one
+const DESCRIPTIVE_KEYS: [&str; 3] = ["type", "expires", "scope"];
+
+/// Signed-URL query parameters that make the URL usable
+const SIGNED_URL_PARAMS: [&str; 5] = ["sig", "signature", "policy", "x-amz-signature", "key-pair-id"];
+
+/// Path segment followed by the (secret) ID of an OSS signed resource
+const SIGNED_RESOURCES: &str = "signedresources";
+
+/// Secret values known to the demo, masked verbatim, oldest first
+static KNOWN_SECRETS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Known secrets kept; a long session resolving fresh secrets for every run
+/// forgets the oldest ones instead of slowing every redaction down
+const MAX_KNOWN_SECRETS: usize = 256;
+
+/// Key fragments whose values are never prose, however short or plain
+const ALWAYS_SECRET_KEYS: [&str; 3] = ["authorization", "password", "secret"];
+
+/// Unquoted `key: value` values shorter than this that are a plain word are
+/// prose ("invalid token: connection refused"), not credentials
+const MIN_PROSE_SECRET_LEN: usize = 16;
+
+/// Whether a lowercase key or variable name looks like it holds a credential
+pub fn is_secret_key(key: &str) -> bool {
+    SECRET_KEYS.iter().any(|s| key.contains(s)) && !DESCRIPTIVE_KEYS.iter().any(|d| key.contains(d))
+}
+
+/// Mask `value` wherever it appears from now on; short values are ignored so
+/// ordinary words aren't masked
+pub fn register_secret(value: &str) {
+    if value.len() < 6 {
+        return;
+    }
+    remember(&mut KNOWN_SECRETS.lock().unwrap_or_else(|e| e.into_inner()), value);
+}
+
+/// Add `value` to `known` as the newest secret, once, forgetting the oldest
+/// one beyond [`MAX_KNOWN_SECRETS`]
+fn remember(known: &mut VecDeque<String>, value: &str) {
+    if let Some(index) = known.iter().position(|k| k == value) {
+        known.remove(index);
+    } else if known.len() == MAX_KNOWN_SECRETS {
+        known.pop_front();
+    }
+    known.push_back(value.to_string());
+}
+
+/// Output with credentials removed, for fixture files: JSON is re-printed
+/// with secret-looking values replaced, other text goes through [`redact_text`]
+pub fn redact(output: &str) -> String {
+    if let Ok(mut json) = serde_json::from_str::<Value>(output) {
+        if json.is_object() || json.is_array() {
+            redact_json(&mut json);
+            return serde_json::to_string_pretty(&json).unwrap_or_default();
+        }
+    }
+    redact_text(output)
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_secret_key(&key.to_lowercase()) && !value.is_object() && !value.is_array() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(value);
+                }
+            }
+        },
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        Value::String(text) => *text = redact_text(text),
+        _ => {},
+    }
+}
+
+/// Text with credentials masked and everything else unchanged
+pub fn redact_text(text: &str) -> String {
+    let mut text = text.to_string();
+    for secret in KNOWN_SECRETS.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+        if text.contains(secret.as_str()) {
+            text = text.replace(secret.as_str(), REDACTED);
+        }
+    }
+    mask_values(&text)
+}
+
+/// Characters of a token or parameter value
+fn is_value_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "-._~+/=%".contains(c)
+}
+
+/// Characters of a key name
+fn is_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Mask the values following bearer keywords, secret keys, signed-URL
+/// parameters and signed resource paths
+fn mask_values(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if !is_key_char(chars[i]) || (i > 0 && is_key_char(chars[i - 1])) {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        let end = (i..chars.len()).find(|&j| !is_key_char(chars[j])).unwrap_or(chars.len());
+        let word: String = chars[i..end].iter().collect::<String>().to_lowercase();
+        out.extend(&chars[i..end]);
+
+        let in_query = i > 0 && matches!(chars[i - 1], '?' | '&');
+        let is_flag = word.starts_with("--");
+        let key = word.trim_start_matches('-');
+        let value_start = if key == "bearer" {
+            separator_end(&chars, end, &[' '])
+        } else if key == SIGNED_RESOURCES && chars.get(end) == Some(&'/') {
+            Some(end + 1)
+        } else if is_secret_key(key) || (in_query && SIGNED_URL_PARAMS.contains(&key)) {
+            separator_end(&chars, end, &[':', '='])
+                .or_else(|| if is_flag { separator_end(&chars, end, &[' ']) } else { None })
+        } else {
+            None
+        };
+        i = end;
+
+        if let Some(start) = value_start {
+            let value_end = (start..chars.len()).find(|&j| !is_value_char(chars[j])).unwrap_or(chars.len());
+            let value: String = chars[start..value_end].iter().collect();
+            // `Authorization: Basic <base64>`: the credentials follow the scheme
+            if value.eq_ignore_ascii_case("basic") && key.contains("authorization") {
+                if let Some(token_start) = separator_end(&chars, value_end, &[' ']) {
+                    let token_end =
+                        (token_start..chars.len()).find(|&j| !is_value_char(chars[j])).unwrap_or(chars.len());
+                    if token_end > token_start {
+                        out.extend(&chars[end..token_start]);
+                        out.push_str(REDACTED);
+                        i = token_end;
+                    }
+                }
+                continue;
+            }
+            let quoted = matches!(chars.get(start.wrapping_sub(1)), Some('"' | '\'' | '\\'));
+            let prose = !quoted
+                && !ALWAYS_SECRET_KEYS.iter().any(|k| key.contains(k))
+                && chars[end..start].contains(&':')
+                && value.len() < MIN_PROSE_SECRET_LEN
+                && value.chars().all(|c| c.is_ascii_alphabetic());
+            // Empty values include already redacted ones
+            let keep = value.is_empty()
+                || prose
+                || value.eq_ignore_ascii_case("bearer")
+                || matches!(value.as_str(), "null" | "true" | "false");
+            if !keep {
+                out.extend(&chars[end..start]);
+                out.push_str(REDACTED);
+                i = value_end;
+            }
+        }
+    }
+    out
+}
+
+/// Index after `key` followed by one of `separators`, with optional quotes
+/// and spaces around it; `None` when no separator follows
+fn separator_end(chars: &[char], mut at: usize, separators: &[char]) -> Option<usize> {
+    let skip = |at: &mut usize, set: &[char]| {
+        while *at < chars.len() && set.contains(&chars[*at]) {
+            *at += 1;
+        }
+    };
+    skip(&mut at, &['"', '\'', '\\']);
+    let spaces_only = separators == [' '];
+    if spaces_only {
+        if chars.get(at) != Some(&' ') {
+            return None;
+        }
+        skip(&mut at, &[' ']);
+    } else {
+        skip(&mut at, &[' ']);
+        if !chars.get(at).is_some_and(|c| separators.contains(c)) {
+            return None;
+        }
+        at += 1;
+        skip(&mut at, &[' ']);
+    }
+    skip(&mut at, &['"', '\'', '\\']);
+    Some(at)
+}
+
+/// Writer that redacts each line before passing it on, and what is left of
+/// a line when flushed; used for log files, the console log output and
+/// recordings
+pub struct RedactingWriter<W: Write> {
+    inner: W,
+    pending: Vec<u8>,
+}
+
+impl<W: Write> RedactingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            pending: Vec::new(),
+        }
+    }
+
+    fn write_lines(&mut self, all: bool) -> io::Result<()> {
+        let end = if all {
+            match std::str::from_utf8(&self.pending) {
+                // Keep an incomplete trailing sequence for the next write
+                Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                _ => self.pending.len(),
+            }
+        } else {
+            match self.pending.iter().rposition(|&b| b == b'\n') {
+                Some(newline) => newline + 1,
+                None => return Ok(()),
+            }
+        };
+        if end == 0 {
+            return Ok(());
+        }
+        let text = String::from_utf8_lossy(&self.pending[..end]).into_owned();
+        self.pending.drain(..end);
+        self.inner.write_all(redact_text(&text).as_bytes())
+    }
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        self.write_lines(false)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_lines(true)?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for RedactingWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_text() {
+        assert_eq!(redact_text("Authorization: Bearer abc.def"), "Authorization: Bearer [REDACTED]");
+        assert_eq!(
+            redact_text(r#"{"access_token": "eyJhbGci", "token_type": "Bearer", "expires_in": 3599}"#),
+            r#"{"access_token": "[REDACTED]", "token_type": "Bearer", "expires_in": 3599}"#
+        );
+        assert_eq!(redact_text("APS_CLIENT_SECRET=s3cr3t raps auth login"), "APS_CLIENT_SECRET=[REDACTED] raps auth login");
+        assert_eq!(redact_text("raps auth login --client-secret s3cr3t"), "raps auth login --client-secret [REDACTED]");
+        assert_eq!(
+            redact_text("https://s3.amazonaws.com/b/k?X-Amz-Credential=AKIA%2F&X-Amz-Signature=f00d&region=US"),
+            "https://s3.amazonaws.com/b/k?X-Amz-Credential=[REDACTED]&X-Amz-Signature=[REDACTED]&region=US"
+        );
+        assert_eq!(
+            redact_text("url: https://developer.api.autodesk.com/oss/v2/signedresources/4f2a-99?region=US"),
+            "url: https://developer.api.autodesk.com/oss/v2/signedresources/[REDACTED]?region=US"
+        );
+        // Ordinary text and bucket policies are left alone
+        assert_eq!(redact_text("bucket create --policy transient"), "bucket create --policy transient");
+        assert_eq!(redact_text(r#""policyKey": "transient""#), r#""policyKey": "transient""#);
+
+        register_secret("keyring-value-42");
+        assert_eq!(redact_text("echo keyring-value-42"), "echo [REDACTED]");
+
+        // Words after a secret-looking key in prose aren't credentials
+        assert_eq!(
+            redact_text("Request failed: invalid token: connection refused"),
+            "Request failed: invalid token: connection refused"
+        );
+        assert_eq!(redact_text("token: eyJhbGci0iJIUzI1"), "token: [REDACTED]");
+        assert_eq!(redact_text("token=expired"), "token=[REDACTED]");
+        // ...but short plain values of these keys are
+        assert_eq!(redact_text("Authorization: Basic dXNlcjpwYXNz"), "Authorization: Basic [REDACTED]");
+        assert_eq!(
+            redact_text(r#"-H "Authorization: Basic dXNlcjpwYXNz""#),
+            r#"-H "Authorization: Basic [REDACTED]""#
+        );
+        assert_eq!(redact_text("password: hunter"), "password: [REDACTED]");
+        assert_eq!(redact_text("APS_CLIENT_SECRET: abcdefghij"), "APS_CLIENT_SECRET: [REDACTED]");
+    }
+
+    #[test]
+    fn test_known_secrets_are_capped() {
+        let mut known = VecDeque::new();
+        for n in 0..MAX_KNOWN_SECRETS + 10 {
+            remember(&mut known, &format!("secret-{}", n));
+        }
+        assert_eq!(known.len(), MAX_KNOWN_SECRETS);
+        assert_eq!(known.front().map(String::as_str), Some("secret-10"));
+
+        // A secret registered again isn't added twice and is forgotten last
+        remember(&mut known, "secret-10");
+        assert_eq!(known.len(), MAX_KNOWN_SECRETS);
+        assert_eq!(known.back().map(String::as_str), Some("secret-10"));
+        assert_eq!(known.front().map(String::as_str), Some("secret-11"));
+    }
+
+    #[test]
+    fn test_redacting_writer() {
+        let mut out = Vec::new();
+        {
+            let mut writer = RedactingWriter::new(&mut out);
+            writer.write_all(b"Authorization: Bearer ab").unwrap();
+            writer.write_all(b"cdef\npartial token=xyz").unwrap();
+        }
+        assert_eq!(String::from_utf8(out).unwrap(), "Authorization: Bearer [REDACTED]\npartial token=[REDACTED]");
+    }
+}
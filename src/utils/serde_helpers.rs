@@ -19,7 +19,26 @@ where
     D: Deserializer<'de>,
 {
     let seconds = i64::deserialize(deserializer)?;
-    Ok(Duration::seconds(seconds))
+    seconds_to_duration(seconds)
+}
+
+/// Longest duration accepted, a year: far beyond any demo, and small enough
+/// that sums and differences of durations can't overflow
+pub const MAX_DURATION_SECONDS: i64 = 366 * 24 * 60 * 60;
+
+/// `seconds` as a Duration, or an error for negative counts and counts over
+/// [`MAX_DURATION_SECONDS`] (hand-edited files may hold anything that fits an
+/// i64)
+fn seconds_to_duration<E: serde::de::Error>(seconds: i64) -> Result<Duration, E> {
+    (0..=MAX_DURATION_SECONDS)
+        .contains(&seconds)
+        .then(|| Duration::seconds(seconds))
+        .ok_or_else(|| {
+            E::custom(format!(
+                "duration of {} seconds is out of range (0 to {})",
+                seconds, MAX_DURATION_SECONDS
+            ))
+        })
 }
 
 /// Module for serializing Duration with serde
@@ -40,7 +59,7 @@ pub mod duration_serde {
         D: Deserializer<'de>,
     {
         let seconds = i64::deserialize(deserializer)?;
-        Ok(Duration::seconds(seconds))
+        super::seconds_to_duration(seconds)
     }
 }
 
@@ -65,7 +84,37 @@ pub mod optional_duration_serde {
         D: Deserializer<'de>,
     {
         let seconds_opt = Option::<i64>::deserialize(deserializer)?;
-        Ok(seconds_opt.map(Duration::seconds))
+        seconds_opt.map(super::seconds_to_duration).transpose()
+    }
+}
+
+/// Module accepting either a single value or a list of values
+/// Use with #[serde(with = "crate::utils::serde_helpers::one_or_many")]
+pub mod one_or_many {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    pub fn serialize<T: Serialize, S>(values: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        values.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de>, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(match OneOrMany::deserialize(deserializer)? {
+            OneOrMany::One(value) => vec![value],
+            OneOrMany::Many(values) => values,
+        })
     }
 }
 
@@ -107,4 +156,36 @@ mod tests {
 
         assert_eq!(test, deserialized);
     }
+
+    #[test]
+    fn test_out_of_range_duration_is_an_error() {
+        let json = format!(r#"{{"duration": {}, "optional_duration": null}}"#, i64::MIN);
+        let error = serde_json::from_str::<TestStruct>(&json).unwrap_err();
+        assert!(error.to_string().contains("out of range"), "{}", error);
+        let json = format!(r#"{{"duration": 1, "optional_duration": {}}}"#, i64::MAX);
+        assert!(serde_json::from_str::<TestStruct>(&json).is_err());
+
+        // Representable, but no demo takes that long
+        for seconds in [-1, MAX_DURATION_SECONDS + 1, i64::MAX / 1000] {
+            let json = format!(r#"{{"duration": {}, "optional_duration": null}}"#, seconds);
+            assert!(serde_json::from_str::<TestStruct>(&json).is_err(), "{}", seconds);
+        }
+        let json = format!(r#"{{"duration": {}, "optional_duration": 0}}"#, MAX_DURATION_SECONDS);
+        let limits: TestStruct = serde_json::from_str(&json).unwrap();
+        assert_eq!(limits.duration, Duration::seconds(MAX_DURATION_SECONDS));
+    }
+
+    #[test]
+    fn test_one_or_many() {
+        #[derive(Deserialize)]
+        struct Paths {
+            #[serde(with = "one_or_many")]
+            paths: Vec<String>,
+        }
+
+        let one: Paths = serde_json::from_str(r#"{"paths": "a"}"#).unwrap();
+        assert_eq!(one.paths, vec!["a"]);
+        let many: Paths = serde_json::from_str(r#"{"paths": ["a", "b"]}"#).unwrap();
+        assert_eq!(many.paths, vec!["a", "b"]);
+    }
 }
@@ -0,0 +1,81 @@
+// OpenTelemetry export for RAPS Demo Workflows
+//
+// Workflow and step execution is instrumented with `tracing` spans carrying
+// workflow_id, step_id and exit_code attributes. When built with the `otlp`
+// feature and given a collector endpoint, those spans are exported over OTLP
+// so demo runs show up in an existing observability stack.
+
+use anyhow::Result;
+use tracing_subscriber::{Layer, Registry};
+
+/// Service name reported to the collector
+pub const SERVICE_NAME: &str = "raps-demo";
+
+/// A tracing layer that can be installed directly on the registry
+pub type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Flushes and shuts down the exporter when dropped
+pub struct TelemetryGuard {
+    #[cfg(feature = "otlp")]
+    provider: opentelemetry_sdk::trace::TracerProvider,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otlp")]
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Warning: failed to flush OpenTelemetry spans: {}", e);
+        }
+    }
+}
+
+/// Build a layer exporting spans to the OTLP (gRPC) collector at `endpoint`.
+///
+/// Must be called from within a Tokio runtime; spans are exported in batches
+/// in the background.
+#[cfg(feature = "otlp")]
+pub fn otlp_layer(endpoint: &str) -> Result<(BoxedLayer, TelemetryGuard)> {
+    use anyhow::Context;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::{runtime, trace::TracerProvider, Resource};
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()
+        .with_context(|| format!("Failed to create OTLP exporter for {}", endpoint))?;
+
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new(vec![
+            KeyValue::new("service.name", SERVICE_NAME),
+            KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+        ]))
+        .build();
+
+    let tracer = provider.tracer(SERVICE_NAME);
+    let layer = tracing_opentelemetry::layer().with_tracer(tracer).boxed();
+    Ok((layer, TelemetryGuard { provider }))
+}
+
+/// Without the `otlp` feature there is no exporter to build
+#[cfg(not(feature = "otlp"))]
+pub fn otlp_layer(endpoint: &str) -> Result<(BoxedLayer, TelemetryGuard)> {
+    anyhow::bail!(
+        "Cannot export spans to {}: raps-demo was built without the `otlp` feature",
+        endpoint
+    )
+}
+
+#[cfg(all(test, not(feature = "otlp")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_otlp_layer_requires_feature() {
+        let error = otlp_layer("http://localhost:4317").err().unwrap();
+        assert!(error.to_string().contains("`otlp` feature"));
+    }
+}
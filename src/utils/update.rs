@@ -0,0 +1,390 @@
+// Version check and self-update for RAPS Demo Workflows
+//
+// Releases are published on GitHub with one binary per platform
+// (`raps-demo-<os>-<arch>`) and an Ed25519 signature of it next to it
+// (`<binary>.sig`, hex). `raps-demo self-update` downloads the binary for this
+// platform, checks the signature against the release key (built in by the
+// release build, or `public_key` in the `[update]` table of `demo.toml`) and
+// swaps it in for the running executable; unsigned or badly signed binaries
+// are never installed. The TUI checks for a newer release in the background at
+// most once per `check_interval_hours` and shows it in the help bar. Managed
+// environments turn the check off with `check_on_startup = false` or
+// `RAPS_DEMO_NO_UPDATE_CHECK=1`.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::config::types::ConfigPaths;
+
+/// GitHub repository releases are published in
+pub const DEFAULT_REPOSITORY: &str = "autodesk-platform-services/raps-demo";
+
+/// Environment variable that turns the startup check off
+pub const DISABLE_CHECK_ENV: &str = "RAPS_DEMO_NO_UPDATE_CHECK";
+
+/// Release signing key (hex) baked in by the release build
+const BUILTIN_PUBLIC_KEY: Option<&str> = option_env!("RAPS_DEMO_UPDATE_PUBLIC_KEY");
+
+/// Extension of release binary signatures
+const SIGNATURE_EXTENSION: &str = "sig";
+
+/// How long to wait for GitHub
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Update settings (the `[update]` table of `demo.toml`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateConfig {
+    /// Whether the TUI looks for a newer release when it starts
+    pub check_on_startup: bool,
+    /// Hours between two startup checks
+    pub check_interval_hours: u64,
+    /// GitHub repository (`owner/name`) releases come from
+    pub repository: String,
+    /// Release signing key (hex Ed25519 public key), overriding the built-in one
+    pub public_key: Option<String>,
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        Self {
+            check_on_startup: true,
+            check_interval_hours: 24,
+            repository: DEFAULT_REPOSITORY.to_string(),
+            public_key: None,
+        }
+    }
+}
+
+impl UpdateConfig {
+    /// Whether to check on startup, honouring [`DISABLE_CHECK_ENV`]
+    pub fn startup_check_enabled(&self) -> bool {
+        let disabled = std::env::var(DISABLE_CHECK_ENV).is_ok_and(|v| !v.is_empty() && v != "0");
+        self.check_on_startup && !disabled
+    }
+
+    fn public_key(&self) -> Option<&str> {
+        self.public_key.as_deref().or(BUILTIN_PUBLIC_KEY)
+    }
+}
+
+/// Release version, `major.minor.patch`; a leading `v` and any pre-release
+/// or build suffix are ignored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    /// Version of this build
+    pub fn current() -> Self {
+        env!("CARGO_PKG_VERSION").parse().expect("package version is major.minor.patch")
+    }
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let core = s.trim().trim_start_matches('v').split(['-', '+']).next().unwrap_or_default();
+        let parts: Vec<u64> = core
+            .split('.')
+            .map(|part| part.parse().map_err(|_| format!("invalid version '{}'", s)))
+            .collect::<Result<_, _>>()?;
+        match parts[..] {
+            [major, minor, patch] => Ok(Self { major, minor, patch }),
+            _ => Err(format!("invalid version '{}' (expected major.minor.patch)", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for Version {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Version> for String {
+    fn from(version: Version) -> Self {
+        version.to_string()
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// A published release
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Release {
+    pub version: Version,
+    /// Release page
+    pub url: String,
+    /// Download URL of the binary for this platform
+    pub binary_url: Option<String>,
+    /// Download URL of the binary's signature
+    pub signature_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Name of the release binary for this platform
+pub fn asset_name() -> String {
+    format!(
+        "raps-demo-{}-{}{}",
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        std::env::consts::EXE_SUFFIX
+    )
+}
+
+impl Release {
+    /// Release described by the GitHub API response `json`
+    fn from_github(json: &str) -> Result<Self> {
+        let release: GithubRelease = serde_json::from_str(json).context("Unexpected GitHub release response")?;
+        let binary = asset_name();
+        let signature = format!("{}.{}", binary, SIGNATURE_EXTENSION);
+        let url_of = |name: &str| {
+            release
+                .assets
+                .iter()
+                .find(|asset| asset.name == name)
+                .map(|asset| asset.browser_download_url.clone())
+        };
+        Ok(Self {
+            version: release.tag_name.parse().map_err(anyhow::Error::msg)?,
+            binary_url: url_of(&binary),
+            signature_url: url_of(&signature),
+            url: release.html_url,
+        })
+    }
+}
+
+fn http_client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(concat!("raps-demo/", env!("CARGO_PKG_VERSION")))
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .context("Failed to create HTTP client")
+}
+
+/// Latest release of `config.repository`
+pub async fn latest_release(config: &UpdateConfig) -> Result<Release> {
+    let url = format!("https://api.github.com/repos/{}/releases/latest", config.repository);
+    let response = http_client()?
+        .get(&url)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("Failed to fetch {}", url))?;
+    Release::from_github(&response.text().await?)
+}
+
+/// Outcome of the last startup check, so it runs at most once per interval
+#[derive(Debug, Serialize, Deserialize)]
+struct CheckRecord {
+    checked_at: DateTime<Utc>,
+    latest: Release,
+}
+
+fn check_record_path() -> Result<PathBuf> {
+    Ok(ConfigPaths::resolved_config_dir()?.join(ConfigPaths::UPDATE_CHECK_FILE))
+}
+
+/// A release newer than this build, looked up at most once per
+/// `check_interval_hours` (the last answer is kept in between)
+pub async fn check_for_update(config: &UpdateConfig) -> Result<Option<Release>> {
+    let path = check_record_path()?;
+    let recent = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| toml::from_str::<CheckRecord>(&content).ok())
+        .filter(|record| {
+            let interval = i64::try_from(config.check_interval_hours)
+                .ok()
+                .and_then(chrono::Duration::try_hours)
+                .unwrap_or(chrono::Duration::MAX);
+            Utc::now() - record.checked_at < interval
+        });
+    let latest = match recent {
+        Some(record) => record.latest,
+        None => {
+            let latest = latest_release(config).await?;
+            let record = CheckRecord {
+                checked_at: Utc::now(),
+                latest: latest.clone(),
+            };
+            if let Err(e) = toml::to_string(&record).map_err(anyhow::Error::from).and_then(|content| {
+                std::fs::create_dir_all(path.parent().unwrap_or(Path::new(".")))?;
+                Ok(std::fs::write(&path, content)?)
+            }) {
+                tracing::debug!("Could not record the update check: {:#}", e);
+            }
+            latest
+        },
+    };
+    Ok((latest.version > Version::current()).then_some(latest))
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    let hex = hex.trim();
+    if let Some(c) = hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+        anyhow::bail!("Invalid hex digit '{}'", c);
+    }
+    if hex.len() % 2 != 0 {
+        anyhow::bail!("Odd number of hex digits");
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).with_context(|| format!("Invalid hex '{}'", &hex[i..i + 2])))
+        .collect()
+}
+
+/// Check the hex Ed25519 `signature` of `binary` against the hex `public_key`
+pub fn verify_signature(binary: &[u8], signature: &str, public_key: &str) -> Result<()> {
+    let public_key = ed25519_compact::PublicKey::from_slice(&decode_hex(public_key).context("Invalid release key")?)
+        .map_err(|e| anyhow::anyhow!("Invalid release key: {}", e))?;
+    let signature = ed25519_compact::Signature::from_slice(&decode_hex(signature).context("Invalid signature")?)
+        .map_err(|e| anyhow::anyhow!("Invalid signature: {}", e))?;
+    public_key
+        .verify(binary, &signature)
+        .map_err(|_| anyhow::anyhow!("Signature does not match the release key"))
+}
+
+/// Result of `self-update`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    /// This build is the latest release (or newer)
+    UpToDate(Version),
+    /// The release was installed at the path
+    Installed(Version, PathBuf),
+}
+
+/// Install the latest release over the running executable, if it is newer
+pub async fn self_update(config: &UpdateConfig) -> Result<UpdateOutcome> {
+    let release = latest_release(config).await?;
+    let current = Version::current();
+    if release.version <= current {
+        return Ok(UpdateOutcome::UpToDate(current));
+    }
+    let public_key = config
+        .public_key()
+        .context("No release signing key: this build has none; set public_key in the [update] table of demo.toml")?;
+    let binary_url = release
+        .binary_url
+        .as_ref()
+        .with_context(|| format!("Release {} has no {} binary", release.version, asset_name()))?;
+    let signature_url = release
+        .signature_url
+        .as_ref()
+        .with_context(|| format!("Release {} has no signature for {}; refusing to install", release.version, asset_name()))?;
+
+    let client = http_client()?;
+    let download = |url: String| {
+        let client = client.clone();
+        async move {
+            let response = client.get(&url).send().await.and_then(|r| r.error_for_status());
+            let bytes = response.with_context(|| format!("Failed to download {}", url))?.bytes().await?;
+            anyhow::Ok(bytes.to_vec())
+        }
+    };
+    let binary = download(binary_url.clone()).await?;
+    let signature = String::from_utf8(download(signature_url.clone()).await?).context("Signature is not text")?;
+    verify_signature(&binary, &signature, public_key)
+        .with_context(|| format!("Release {} failed the signature check; not installed", release.version))?;
+
+    let executable = std::env::current_exe().context("Failed to locate the running executable")?;
+    replace_executable(&executable, &binary)?;
+    Ok(UpdateOutcome::Installed(release.version, executable))
+}
+
+/// Put `binary` in place of `executable`: written next to it, then renamed
+/// over it (the old file is moved aside first, which Windows needs for a
+/// running executable)
+fn replace_executable(executable: &Path, binary: &[u8]) -> Result<()> {
+    let staged = executable.with_extension("new");
+    let previous = executable.with_extension("old");
+    std::fs::write(&staged, binary).with_context(|| format!("Failed to write {}", staged.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+    }
+    let _ = std::fs::remove_file(&previous);
+    std::fs::rename(executable, &previous).with_context(|| format!("Failed to move {} aside", executable.display()))?;
+    if let Err(e) = std::fs::rename(&staged, executable) {
+        let _ = std::fs::rename(&previous, executable);
+        return Err(e).with_context(|| format!("Failed to install {}", executable.display()));
+    }
+    // Still running (and locked) on Windows; removed on the next update
+    let _ = std::fs::remove_file(&previous);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_versions_and_signatures() {
+        let current = Version::current();
+        let next = format!("v{}.{}.0", current.major, current.minor + 1);
+        assert!(next.parse::<Version>().unwrap() > current);
+        assert_eq!("1.10.3-rc.1".parse::<Version>().unwrap().to_string(), "1.10.3");
+        assert!("1.2".parse::<Version>().is_err());
+
+        let json = format!(
+            r#"{{"tag_name": "v9.0.0", "html_url": "https://example.com/r", "assets": [
+                {{"name": "{0}", "browser_download_url": "https://example.com/bin"}},
+                {{"name": "{0}.sig", "browser_download_url": "https://example.com/sig"}}]}}"#,
+            asset_name()
+        );
+        let release = Release::from_github(&json).unwrap();
+        assert_eq!(release.version, Version { major: 9, minor: 0, patch: 0 });
+        assert_eq!(release.signature_url.as_deref(), Some("https://example.com/sig"));
+
+        let key = ed25519_compact::KeyPair::generate();
+        let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+        let signature = hex(key.sk.sign(b"binary", None).as_ref());
+        verify_signature(b"binary", &signature, &hex(key.pk.as_ref())).unwrap();
+        let error = verify_signature(b"tampered", &signature, &hex(key.pk.as_ref())).unwrap_err();
+        assert_eq!(error.to_string(), "Signature does not match the release key");
+        // Downloaded text that isn't hex is an error, not a panic
+        assert!(decode_hex("é0").is_err());
+        assert!(decode_hex("0g").is_err());
+    }
+
+    #[test]
+    fn test_replace_executable() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let executable = dir.path().join("raps-demo");
+        std::fs::write(&executable, b"old").unwrap();
+        replace_executable(&executable, b"new").unwrap();
+        assert_eq!(std::fs::read(&executable).unwrap(), b"new");
+        assert!(!executable.with_extension("old").exists());
+    }
+}
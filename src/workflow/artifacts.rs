@@ -0,0 +1,129 @@
+// Run artifact collection for RAPS Demo Workflows
+//
+// Steps can declare `artifacts:` glob patterns (relative to their working
+// directory). When a run finishes, matching files are copied out of the
+// execution's temporary directory into a per-run folder under the config
+// directory, so downloaded derivatives, thumbnails and manifests survive the
+// temp-dir cleanup and are easy to find.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::types::{ExecutionHandle, StepId};
+use crate::config::types::ConfigPaths;
+
+/// Artifact patterns declared by one executed step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactSpec {
+    /// Step that declared the patterns
+    pub step_id: StepId,
+    /// Directory the patterns are relative to
+    pub base_dir: PathBuf,
+    /// Glob patterns such as `*.svf` or `derivatives/**/*.png`
+    pub patterns: Vec<String>,
+}
+
+/// Folder for the artifacts of one run: `<artifacts>/<workflow>/<time>-<id>`
+pub fn run_artifacts_dir(handle: &ExecutionHandle) -> Result<PathBuf> {
+    let run = format!(
+        "{}-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        &handle.id.simple().to_string()[..8]
+    );
+    Ok(ConfigPaths::artifacts_dir()?.join(&handle.workflow_id).join(run))
+}
+
+/// Copy everything matching `specs` into `dest/<step_id>/...`.
+///
+/// Returns the copied files; `dest` is only created if something matched.
+pub fn collect_artifacts(specs: &[ArtifactSpec], dest: &Path) -> Result<Vec<PathBuf>> {
+    let mut copied = Vec::new();
+
+    for spec in specs {
+        for pattern in &spec.patterns {
+            if !is_relative_pattern(pattern) {
+                tracing::warn!(
+                    "Ignoring artifact pattern '{}' of step '{}': must be relative",
+                    pattern,
+                    spec.step_id
+                );
+                continue;
+            }
+
+            let full_pattern = spec.base_dir.join(pattern);
+            let matches = glob::glob(&full_pattern.to_string_lossy())
+                .with_context(|| format!("Invalid artifact pattern: {}", pattern))?;
+
+            for path in matches.filter_map(|m| m.ok()) {
+                for file in WalkDir::new(&path).into_iter().filter_map(|e| e.ok()) {
+                    if !file.file_type().is_file() {
+                        continue;
+                    }
+                    let relative = file.path().strip_prefix(&spec.base_dir).unwrap_or(file.path());
+                    let target = dest.join(&spec.step_id).join(relative);
+                    if copied.contains(&target) {
+                        continue;
+                    }
+                    if let Some(parent) = target.parent() {
+                        fs::create_dir_all(parent)
+                            .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+                    }
+                    fs::copy(file.path(), &target)
+                        .with_context(|| format!("Failed to copy artifact {:?}", file.path()))?;
+                    copied.push(target);
+                }
+            }
+        }
+    }
+
+    Ok(copied)
+}
+
+/// Whether a pattern stays inside its base directory
+pub fn is_relative_pattern(pattern: &str) -> bool {
+    let path = Path::new(pattern);
+    !pattern.is_empty()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_collect_artifacts() {
+        let work = TempDir::new().unwrap();
+        let dest = TempDir::new().unwrap();
+        fs::create_dir_all(work.path().join("derivatives/thumbs")).unwrap();
+        fs::write(work.path().join("manifest.json"), "{}").unwrap();
+        fs::write(work.path().join("derivatives/thumbs/view.png"), "png").unwrap();
+        fs::write(work.path().join("scratch.tmp"), "").unwrap();
+
+        let spec = ArtifactSpec {
+            step_id: "download".to_string(),
+            base_dir: work.path().to_path_buf(),
+            patterns: vec![
+                "*.json".to_string(),
+                "derivatives".to_string(),
+                "../outside".to_string(),
+            ],
+        };
+        let mut copied = collect_artifacts(&[spec], dest.path()).unwrap();
+        copied.sort();
+
+        assert_eq!(
+            copied,
+            vec![
+                dest.path().join("download/derivatives/thumbs/view.png"),
+                dest.path().join("download/manifest.json"),
+            ]
+        );
+        assert!(is_relative_pattern("out/**/*.png"));
+        assert!(!is_relative_pattern("/etc/passwd"));
+    }
+}
@@ -0,0 +1,110 @@
+// Built-in workflow pack for RAPS Demo Workflows
+//
+// A handful of canonical workflows is compiled into the binary so a fresh
+// install has something to run before any workflows directory is set up.
+// They are listed under a read-only "Built-in" category; a workflow with the
+// same ID in a workflows directory replaces its built-in version, and
+// `extract` writes the pack to disk for editing.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use super::types::CategoryInfo;
+
+/// Category the built-in workflows are listed under
+pub const BUILTIN_CATEGORY: &str = "built-in";
+
+/// Prefix of the pseudo script path of built-in workflows
+pub const BUILTIN_PATH_PREFIX: &str = "builtin:";
+
+/// A workflow definition embedded in the binary
+#[derive(Debug, Clone, Copy)]
+pub struct BuiltinWorkflow {
+    /// Path relative to a workflows directory
+    pub path: &'static str,
+    /// YAML source
+    pub source: &'static str,
+}
+
+impl BuiltinWorkflow {
+    /// Script path shown for the workflow, e.g. `builtin:oss/bucket-lifecycle.yaml`
+    pub fn script_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}{}", BUILTIN_PATH_PREFIX, self.path))
+    }
+}
+
+/// The built-in pack: auth check, bucket lifecycle, upload + translate + view
+/// and a Design Automation tour
+pub const BUILTIN_WORKFLOWS: [BuiltinWorkflow; 4] = [
+    BuiltinWorkflow {
+        path: "oss/auth-management.yaml",
+        source: include_str!("../../workflows/oss/auth-management.yaml"),
+    },
+    BuiltinWorkflow {
+        path: "oss/bucket-lifecycle.yaml",
+        source: include_str!("../../workflows/oss/bucket-lifecycle.yaml"),
+    },
+    BuiltinWorkflow {
+        path: "end-to-end/viewer-pipeline.yaml",
+        source: include_str!("../../workflows/end-to-end/viewer-pipeline.yaml"),
+    },
+    BuiltinWorkflow {
+        path: "design-automation/da-engine-explorer.yaml",
+        source: include_str!("../../workflows/design-automation/da-engine-explorer.yaml"),
+    },
+];
+
+/// Sidebar settings of the "Built-in" category (listed first)
+pub fn category_info() -> CategoryInfo {
+    CategoryInfo {
+        id: BUILTIN_CATEGORY.to_string(),
+        display_name: "Built-in".to_string(),
+        icon: Some("[BI]".to_string()),
+        sort_order: 0,
+        color: Some("white".to_string()),
+    }
+}
+
+/// Whether a script path refers to a built-in (read-only) workflow
+pub fn is_builtin(path: &Path) -> bool {
+    path.to_str().is_some_and(|p| p.starts_with(BUILTIN_PATH_PREFIX))
+}
+
+/// Write the built-in pack into `dir`, keeping existing files unless
+/// `overwrite` is set; returns the files written
+pub fn extract(dir: &Path, overwrite: bool) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for workflow in &BUILTIN_WORKFLOWS {
+        let path = dir.join(workflow.path);
+        if path.exists() && !overwrite {
+            tracing::debug!("Keeping existing workflow file {}", path.display());
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        std::fs::write(&path, workflow.source)
+            .with_context(|| format!("Failed to write workflow: {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_builtin_pack() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(extract(dir.path(), false).unwrap().len(), BUILTIN_WORKFLOWS.len());
+        assert!(dir.path().join("oss/bucket-lifecycle.yaml").is_file());
+        // Existing files are kept unless overwriting
+        assert!(extract(dir.path(), false).unwrap().is_empty());
+        assert_eq!(extract(dir.path(), true).unwrap().len(), BUILTIN_WORKFLOWS.len());
+
+        assert!(is_builtin(&BUILTIN_WORKFLOWS[0].script_path()));
+        assert!(!is_builtin(Path::new("workflows/oss/auth-management.yaml")));
+    }
+}
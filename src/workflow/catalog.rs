@@ -0,0 +1,157 @@
+// Workflow catalog for `--list`
+//
+// The listing of the non-interactive mode is read by people and by scripts
+// (docs generators, CI checks that every workflow is ready to run). Each
+// workflow becomes one catalog entry with its ID, name, category, tags, step
+// count, estimated duration and preflight readiness, printed as an aligned
+// table or as JSON or YAML with the same fields.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::str::FromStr;
+
+use super::discovery::WorkflowDefinition;
+use super::history::format_duration_secs;
+
+/// Output format of the workflow catalog
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListFormat {
+    /// Aligned text table
+    #[default]
+    Table,
+    /// JSON array of entries
+    Json,
+    /// YAML sequence of entries
+    Yaml,
+}
+
+impl FromStr for ListFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "table" | "text" => Ok(ListFormat::Table),
+            "json" => Ok(ListFormat::Json),
+            "yaml" | "yml" => Ok(ListFormat::Yaml),
+            other => Err(anyhow::anyhow!("Unknown list format: {} (expected table, json or yaml)", other)),
+        }
+    }
+}
+
+/// One workflow of the catalog
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CatalogEntry {
+    pub id: String,
+    pub name: String,
+    /// Category ID, as written in the workflow
+    pub category: String,
+    pub tags: Vec<String>,
+    pub steps: usize,
+    pub estimated_duration_secs: i64,
+    pub profiles: Vec<String>,
+    /// Whether the preflight checks pass
+    pub ready: bool,
+    /// Preflight checks that fail
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing: Vec<String>,
+}
+
+impl CatalogEntry {
+    /// Entry of `definition`, whose failing preflight checks are `missing`
+    pub fn new(definition: &WorkflowDefinition, missing: Vec<String>) -> Self {
+        let metadata = &definition.metadata;
+        Self {
+            id: metadata.id.clone(),
+            name: metadata.name.clone(),
+            category: metadata.category.id().to_string(),
+            tags: metadata.tags.clone(),
+            steps: definition.steps.len(),
+            estimated_duration_secs: metadata.estimated_duration.num_seconds(),
+            profiles: definition.profiles.keys().cloned().collect(),
+            ready: missing.is_empty(),
+            missing,
+        }
+    }
+}
+
+/// `entries` in `format`
+pub fn render(entries: &[CatalogEntry], format: ListFormat) -> Result<String> {
+    match format {
+        ListFormat::Json => Ok(serde_json::to_string_pretty(entries)? + "\n"),
+        ListFormat::Yaml => Ok(serde_yaml::to_string(entries)?),
+        ListFormat::Table => Ok(table(entries)),
+    }
+}
+
+/// Aligned table of `entries`, one line each
+fn table(entries: &[CatalogEntry]) -> String {
+    let rows: Vec<[String; 7]> = entries
+        .iter()
+        .map(|entry| {
+            [
+                entry.id.clone(),
+                entry.name.clone(),
+                entry.category.clone(),
+                entry.steps.to_string(),
+                format!("~{}", format_duration_secs(entry.estimated_duration_secs as f64)),
+                if entry.ready { "ready".to_string() } else { format!("missing {}", entry.missing.join(", ")) },
+                entry.tags.join(", "),
+            ]
+        })
+        .collect();
+    let header = ["ID", "NAME", "CATEGORY", "STEPS", "DURATION", "PREFLIGHT", "TAGS"].map(String::from);
+
+    let mut widths = [0; 7];
+    for row in std::iter::once(&header).chain(&rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut out = String::new();
+    for row in std::iter::once(&header).chain(&rows) {
+        let cells: Vec<String> =
+            row.iter().zip(widths).map(|(cell, width)| format!("{:<width$}", cell, width = width)).collect();
+        out.push_str(cells.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKFLOW: &str = r#"
+metadata:
+  id: "upload"
+  name: "Upload"
+  description: "Upload a model"
+  category: "oss"
+  tags: ["beginner", "storage"]
+  estimated_duration: 90
+steps:
+  - id: "create-bucket"
+    name: "Create"
+    description: "Create the bucket"
+    command: {type: bucket, action: create, bucket_name: "demo"}
+"#;
+
+    #[test]
+    fn test_catalog_formats() {
+        let workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        let entries = vec![CatalogEntry::new(&workflow, vec!["Authentication".to_string()])];
+
+        assert_eq!(
+            render(&entries, ListFormat::Table).unwrap(),
+            "ID      NAME    CATEGORY        STEPS  DURATION  PREFLIGHT               TAGS\n\
+             upload  Upload  object-storage  1      ~1m 30s   missing Authentication  beginner, storage\n"
+        );
+        let json: serde_json::Value = serde_json::from_str(&render(&entries, ListFormat::Json).unwrap()).unwrap();
+        assert_eq!(json[0]["steps"], 1);
+        assert_eq!(json[0]["ready"], false);
+        assert_eq!(json[0]["tags"][1], "storage");
+        let yaml = render(&entries, ListFormat::Yaml).unwrap();
+        assert!(yaml.contains("estimated_duration_secs: 90"), "{}", yaml);
+        assert_eq!("YAML".parse::<ListFormat>().unwrap(), ListFormat::Yaml);
+    }
+}
@@ -0,0 +1,196 @@
+// Chaos options for training runs
+//
+// Instructors teaching troubleshooting want failures on demand that look like
+// the real thing. With chaos enabled, each step command may, before it runs,
+// be replaced by an injected failure: a generic server error, a network
+// timeout (after a simulated wait) or a 429 that the rate-limit retries then
+// handle. Faults are drawn from the run's seed, so a fixed `--seed` gives the
+// same faults in the same places every time.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::client::CommandResult;
+use super::placeholders;
+use super::types::StepId;
+
+/// How often faults are injected into step commands (`[chaos]` in
+/// `demo.toml`, or `--chaos failure=10,timeout=5,rate-limit=10`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChaosOptions {
+    /// Percent of step commands failing with a server error
+    pub failure_percent: u8,
+    /// Percent of step commands timing out
+    pub timeout_percent: u8,
+    /// Percent of step commands answered with 429 Too Many Requests
+    pub rate_limit_percent: u8,
+    /// Seconds a simulated timeout waits before failing
+    pub timeout_seconds: u64,
+}
+
+impl Default for ChaosOptions {
+    fn default() -> Self {
+        Self {
+            failure_percent: 0,
+            timeout_percent: 0,
+            rate_limit_percent: 0,
+            timeout_seconds: 5,
+        }
+    }
+}
+
+impl ChaosOptions {
+    /// Whether any fault can be injected
+    pub fn is_enabled(&self) -> bool {
+        self.failure_percent > 0 || self.timeout_percent > 0 || self.rate_limit_percent > 0
+    }
+
+    /// Check that the percentages add up to at most 100
+    pub fn validate(&self) -> Result<()> {
+        let total = self.failure_percent as u32 + self.timeout_percent as u32 + self.rate_limit_percent as u32;
+        if total > 100 {
+            bail!("Chaos percentages add up to {}%, more than 100%", total);
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for ChaosOptions {
+    type Err = anyhow::Error;
+
+    fn from_str(spec: &str) -> Result<Self> {
+        let mut options = Self::default();
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let Some((kind, percent)) = part.split_once('=') else {
+                bail!("Expected kind=percent in chaos option '{}'", part);
+            };
+            let percent: u8 = match percent.trim().trim_end_matches('%').parse() {
+                Ok(percent) if percent <= 100 => percent,
+                _ => bail!("Chaos percentage must be 0-100: {}", part),
+            };
+            match kind.trim() {
+                "failure" => options.failure_percent = percent,
+                "timeout" => options.timeout_percent = percent,
+                "rate-limit" => options.rate_limit_percent = percent,
+                other => bail!("Unknown chaos kind '{}' (expected failure, timeout or rate-limit)", other),
+            }
+        }
+        options.validate()?;
+        Ok(options)
+    }
+}
+
+/// A fault injected in place of a step command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChaosFault {
+    /// 500 Internal Server Error
+    Failure,
+    /// Gateway timeout after a simulated wait
+    Timeout,
+    /// 429 Too Many Requests
+    RateLimit,
+}
+
+impl ChaosFault {
+    /// Short description for progress and logs
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Failure => "server error",
+            Self::Timeout => "network timeout",
+            Self::RateLimit => "rate limit",
+        }
+    }
+
+    /// Failed result with the APS-style JSON error RAPS would print
+    pub fn result(self, step_id: &StepId, waited: Duration) -> CommandResult {
+        let (code, status) = match self {
+            Self::Failure => ("INTERNAL_SERVER_ERROR", 500),
+            Self::Timeout => ("TIMEOUT", 504),
+            Self::RateLimit => ("TOO_MANY_REQUESTS", 429),
+        };
+        let mut error = serde_json::json!({
+            "code": code,
+            "status": status,
+            "message": format!("Chaos: injected {} in step '{}'", self.label(), step_id),
+        });
+        if self == Self::RateLimit {
+            error["retry_after"] = serde_json::json!(1);
+        }
+        let stderr = serde_json::json!({ "error": error }).to_string();
+        CommandResult::new(1, String::new(), stderr, waited)
+    }
+}
+
+/// Seeded fault generator of one run
+#[derive(Debug, Clone)]
+pub struct Chaos {
+    options: ChaosOptions,
+    state: u64,
+}
+
+impl Chaos {
+    /// Generator for a run with `seed`
+    pub fn new(options: ChaosOptions, seed: u64) -> Self {
+        Self {
+            options,
+            // Independent of the names generated from the same seed
+            state: seed ^ 0xC4A0_5C4A_05C4_A05C,
+        }
+    }
+
+    /// Fault to inject into the next step command, if any
+    pub fn roll(&mut self) -> Option<ChaosFault> {
+        let roll = (placeholders::splitmix64(&mut self.state) % 100) as u8;
+        let failure = self.options.failure_percent;
+        let timeout = failure.saturating_add(self.options.timeout_percent);
+        let rate_limit = timeout.saturating_add(self.options.rate_limit_percent);
+        if roll < failure {
+            Some(ChaosFault::Failure)
+        } else if roll < timeout {
+            Some(ChaosFault::Timeout)
+        } else if roll < rate_limit {
+            Some(ChaosFault::RateLimit)
+        } else {
+            None
+        }
+    }
+
+    /// How long a simulated timeout waits
+    pub fn timeout_wait(&self) -> Duration {
+        Duration::from_secs(self.options.timeout_seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::errors::ApsErrorKind;
+    use crate::workflow::rate_limit;
+
+    #[test]
+    fn test_chaos_faults() {
+        let options: ChaosOptions = "failure=20, timeout=10%, rate-limit=30".parse().unwrap();
+        assert_eq!((options.failure_percent, options.timeout_percent, options.rate_limit_percent), (20, 10, 30));
+        assert!("failure=80,timeout=30".parse::<ChaosOptions>().is_err());
+        assert!("meltdown=5".parse::<ChaosOptions>().is_err());
+        assert!(!ChaosOptions::default().is_enabled());
+
+        // Same seed, same faults; roughly the configured share of them
+        let rolls = |seed| {
+            let mut chaos = Chaos::new(options.clone(), seed);
+            (0..1000).map(|_| chaos.roll()).collect::<Vec<_>>()
+        };
+        assert_eq!(rolls(7), rolls(7));
+        let faults = rolls(7).iter().filter(|f| f.is_some()).count();
+        assert!((500..700).contains(&faults), "{} faults", faults);
+
+        let step = "upload".to_string();
+        let limited = ChaosFault::RateLimit.result(&step, Duration::ZERO);
+        assert_eq!(ApsErrorKind::from_result(&limited), Some(ApsErrorKind::RateLimit));
+        assert_eq!(rate_limit::retry_after(&limited), Some(Duration::from_secs(1)));
+        let timed_out = ChaosFault::Timeout.result(&step, Duration::ZERO);
+        assert_eq!(ApsErrorKind::from_result(&timed_out), Some(ApsErrorKind::Network));
+    }
+}
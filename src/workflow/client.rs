@@ -6,13 +6,84 @@
 use anyhow::{Context, Result};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::process::Command as AsyncCommand;
+use tokio::sync::Notify;
 use tokio::time::timeout;
 use tracing::{debug, info, warn};
 
+use super::mock::{FixtureRecorder, MockBackend};
+use super::output::OutputLines;
+use super::rate_limit::{RateLimitConfig, RateLimiter};
+use super::environment::{self, EnvironmentFilter};
+use super::translation::TranslationStatus;
 use super::types::*;
+use crate::config::DemoConfig;
+use crate::utils::redact::redact_text;
+
+/// Per-invocation settings layered over [`RapsClientConfig`]
+#[derive(Debug, Clone, Default)]
+pub struct InvocationOverrides {
+    /// Extra environment variables, taking precedence over the configured ones
+    pub environment: HashMap<String, String>,
+    /// Directory to run the command in
+    pub working_dir: Option<PathBuf>,
+    /// Step the command belongs to, for mock responses and fixtures
+    pub step_id: Option<StepId>,
+    /// RAPS profile (account) whose credentials the command uses
+    pub profile: Option<String>,
+    /// Receives each line the command prints, as it prints it
+    pub output: Option<OutputTap>,
+    /// Stops the command when notified: the RAPS process is killed and
+    /// waited for, and the call fails with [`CommandCancelled`]
+    pub cancel: Option<Arc<Notify>>,
+}
+
+/// Error of a command stopped through [`InvocationOverrides::cancel`]
+#[derive(Debug, thiserror::Error)]
+#[error("RAPS command cancelled")]
+pub struct CommandCancelled;
+
+impl InvocationOverrides {
+    /// Resolves once the command should be stopped; never without a signal
+    async fn cancelled(&self) {
+        match &self.cancel {
+            Some(cancel) => cancel.notified().await,
+            None => std::future::pending().await,
+        }
+    }
+}
+
+/// Callback for the lines a RAPS command prints, with whether the line came
+/// from stderr
+#[derive(Clone)]
+pub struct OutputTap(Arc<OnLine>);
+
+/// Line handler of an [`OutputTap`]
+type OnLine = dyn Fn(&str, bool) + Send + Sync;
+
+impl OutputTap {
+    pub fn new<F: Fn(&str, bool) + Send + Sync + 'static>(on_line: F) -> Self {
+        Self(Arc::new(on_line))
+    }
+
+    fn send(&self, line: &[u8], stderr: bool) {
+        (self.0)(&String::from_utf8_lossy(line), stderr)
+    }
+}
+
+impl fmt::Debug for OutputTap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OutputTap")
+    }
+}
+
+/// Environment variables per RAPS profile name
+pub type ProfileEnvironments = HashMap<String, HashMap<String, String>>;
 
 /// Configuration for RAPS CLI execution
 #[derive(Debug, Clone)]
@@ -25,6 +96,21 @@ pub struct RapsClientConfig {
     pub parse_json_output: bool,
     /// Environment variables to pass to RAPS CLI
     pub environment: HashMap<String, String>,
+    /// Environment of each RAPS profile, applied to commands of workflows
+    /// and steps that name one (`profile:`)
+    pub profiles: ProfileEnvironments,
+    /// Which variables of the parent environment the RAPS CLI inherits
+    pub environment_filter: EnvironmentFilter,
+    /// Limits on how fast commands are sent to APS
+    pub rate_limit: RateLimitConfig,
+    /// Answer commands from this mock instead of running the CLI
+    pub mock: Option<Arc<MockBackend>>,
+    /// Keep the answers of real commands as fixtures for the mock backend
+    pub recorder: Option<Arc<FixtureRecorder>>,
+    /// Time between `translate status` polls of steps waiting for a translation
+    pub translation_poll_interval: Duration,
+    /// Longest time a step waits for a translation to finish
+    pub translation_timeout: Duration,
 }
 
 impl Default for RapsClientConfig {
@@ -34,6 +120,27 @@ impl Default for RapsClientConfig {
             default_timeout: Duration::from_secs(300), // 5 minutes
             parse_json_output: true,
             environment: HashMap::new(),
+            profiles: HashMap::new(),
+            environment_filter: EnvironmentFilter::default(),
+            rate_limit: RateLimitConfig::default(),
+            mock: None,
+            recorder: None,
+            translation_poll_interval: Duration::from_secs(5),
+            translation_timeout: Duration::from_secs(2 * 60 * 60),
+        }
+    }
+}
+
+impl RapsClientConfig {
+    /// The defaults with the demo's rate limits, inherited environment and
+    /// translation polling
+    pub fn for_demo(config: &DemoConfig) -> Self {
+        Self {
+            rate_limit: config.rate_limit.clone(),
+            environment_filter: config.subprocess_env.clone(),
+            translation_poll_interval: Duration::from_secs(config.translation_poll_interval_seconds),
+            translation_timeout: Duration::from_secs(config.translation_timeout_seconds),
+            ..Default::default()
         }
     }
 }
@@ -108,26 +215,30 @@ pub struct CommandProgress {
     pub status_info: HashMap<String, String>,
 }
 
+/// Callback of [`RapsClient::with_progress_callback`]
+type ProgressCallback = dyn Fn(CommandProgress) + Send + Sync;
+
 /// Client for executing RAPS CLI commands
 pub struct RapsClient {
     /// Configuration for the client
     config: RapsClientConfig,
     /// Progress callback for long-running operations
-    progress_callback: Option<Box<dyn Fn(CommandProgress) + Send + Sync>>,
+    progress_callback: Option<Arc<ProgressCallback>>,
+    /// Token bucket shared by all commands sent through this client (and
+    /// its copies with other profiles)
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl RapsClient {
     /// Create a new RAPS client with default configuration
     pub fn new() -> Self {
-        Self {
-            config: RapsClientConfig::default(),
-            progress_callback: None,
-        }
+        Self::with_config(RapsClientConfig::default())
     }
 
     /// Create a new RAPS client with custom configuration
     pub fn with_config(config: RapsClientConfig) -> Self {
         Self {
+            rate_limiter: Arc::new(RateLimiter::new(&config.rate_limit)),
             config,
             progress_callback: None,
         }
@@ -138,26 +249,85 @@ impl RapsClient {
     where
         F: Fn(CommandProgress) + Send + Sync + 'static,
     {
-        self.progress_callback = Some(Box::new(callback));
+        self.progress_callback = Some(Arc::new(callback));
         self
     }
 
+    /// This client with the environments of `profiles` instead, sharing its
+    /// rate limit (e.g. after the profiles were edited)
+    pub fn with_profiles(&self, profiles: ProfileEnvironments) -> Self {
+        Self {
+            config: RapsClientConfig {
+                profiles,
+                ..self.config.clone()
+            },
+            progress_callback: self.progress_callback.clone(),
+            rate_limiter: Arc::clone(&self.rate_limiter),
+        }
+    }
+
+    /// Environment of a RAPS CLI process: the inherited variables the filter
+    /// lets through, then `explicit` (which always passes)
+    fn subprocess_environment(&self, explicit: &HashMap<String, String>) -> Vec<(String, String)> {
+        let mut vars: Vec<(String, String)> = self
+            .config
+            .environment_filter
+            .inherited(std::env::vars())
+            .into_iter()
+            .filter(|(name, _)| !explicit.contains_key(name))
+            .collect();
+        vars.extend(explicit.iter().map(|(k, v)| (k.clone(), v.clone())));
+        debug!("RAPS CLI environment: {}", environment::describe(&vars));
+        vars
+    }
+
+    /// Environment of one RAPS CLI invocation, worked out when it runs:
+    /// configured variables, then those of `profile` (the default account
+    /// if `None`), then `extra`
+    fn invocation_environment(
+        &self,
+        profile: Option<&str>,
+        extra: &HashMap<String, String>,
+    ) -> Result<Vec<(String, String)>> {
+        let mut explicit = self.config.environment.clone();
+        if let Some(profile) = profile {
+            let environment = self.config.profiles.get(profile).with_context(|| {
+                let mut known: Vec<&str> = self.config.profiles.keys().map(String::as_str).collect();
+                known.sort_unstable();
+                if known.is_empty() {
+                    format!("Unknown RAPS profile '{}' (no profiles configured)", profile)
+                } else {
+                    format!("Unknown RAPS profile '{}' (configured: {})", profile, known.join(", "))
+                }
+            })?;
+            explicit.extend(environment.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        explicit.extend(extra.iter().map(|(k, v)| (k.clone(), v.clone())));
+        Ok(self.subprocess_environment(&explicit))
+    }
+
     /// Execute a RAPS command synchronously
+    #[tracing::instrument(
+        name = "raps_command",
+        skip_all,
+        fields(command = tracing::field::Empty, exit_code = tracing::field::Empty)
+    )]
     pub fn execute_command(&self, command: &RapsCommand) -> Result<CommandResult> {
         let args = self.build_command_args(command)?;
+        if let Some(result) = self.mock_result(&args, None) {
+            std::thread::sleep(result.duration);
+            return Ok(result);
+        }
         let start_time = Instant::now();
 
-        info!("Executing RAPS command: {} {}", self.config.raps_binary_path, args.join(" "));
+        info!("Executing RAPS command: {}", self.command_line(&args));
 
         let mut cmd = Command::new(&self.config.raps_binary_path);
         cmd.args(&args)
            .stdout(Stdio::piped())
            .stderr(Stdio::piped());
 
-        // Add environment variables
-        for (key, value) in &self.config.environment {
-            cmd.env(key, value);
-        }
+        cmd.env_clear().envs(self.invocation_environment(None, &HashMap::new())?);
 
         let output = cmd.output()
             .with_context(|| format!("Failed to execute RAPS CLI: {}", self.config.raps_binary_path))?;
@@ -170,31 +340,35 @@ impl RapsClient {
             duration,
         );
 
-        if result.success {
-            debug!("RAPS command completed successfully in {:?}", duration);
-        } else {
-            warn!("RAPS command failed: {}", result.error_message().unwrap_or_default());
-        }
+        self.log_command_result(&args, &result);
+        self.record_fixture(&args, None, &result);
 
         Ok(result)
     }
 
     /// Execute a RAPS command asynchronously with timeout
+    #[tracing::instrument(
+        name = "raps_command",
+        skip_all,
+        fields(command = tracing::field::Empty, exit_code = tracing::field::Empty)
+    )]
     pub async fn execute_command_async(&self, command: &RapsCommand) -> Result<CommandResult> {
         let args = self.build_command_args(command)?;
+        if let Some(result) = self.mock_result(&args, None) {
+            tokio::time::sleep(result.duration).await;
+            return Ok(result);
+        }
+        self.rate_limiter.acquire().await;
         let start_time = Instant::now();
 
-        info!("Executing RAPS command async: {} {}", self.config.raps_binary_path, args.join(" "));
+        info!("Executing RAPS command async: {}", self.command_line(&args));
 
         let mut cmd = AsyncCommand::new(&self.config.raps_binary_path);
         cmd.args(&args)
            .stdout(Stdio::piped())
            .stderr(Stdio::piped());
 
-        // Add environment variables
-        for (key, value) in &self.config.environment {
-            cmd.env(key, value);
-        }
+        cmd.env_clear().envs(self.invocation_environment(None, &HashMap::new())?);
 
         let output = timeout(self.config.default_timeout, cmd.output())
             .await
@@ -209,31 +383,240 @@ impl RapsClient {
             duration,
         );
 
-        if result.success {
-            debug!("RAPS command completed successfully in {:?}", duration);
-        } else {
-            warn!("RAPS command failed: {}", result.error_message().unwrap_or_default());
+        self.log_command_result(&args, &result);
+        self.record_fixture(&args, None, &result);
+
+        Ok(result)
+    }
+
+    /// Execute a RAPS command asynchronously, streaming its output.
+    ///
+    /// `overrides` (extra environment, working directory) apply to this
+    /// invocation only. Each output line (stdout or stderr) is scanned for a percentage such as
+    /// `Translating... 45%`; when found, `on_progress` is called with the parsed
+    /// progress and an ETA extrapolated from the elapsed time.
+    #[tracing::instrument(
+        name = "raps_command",
+        skip_all,
+        fields(command = tracing::field::Empty, exit_code = tracing::field::Empty)
+    )]
+    pub async fn execute_command_streaming<F>(
+        &self,
+        command: &RapsCommand,
+        overrides: &InvocationOverrides,
+        on_progress: F,
+    ) -> Result<CommandResult>
+    where
+        F: Fn(CommandProgress),
+    {
+        let args = self.build_command_args(command)?;
+        if let Some(result) = self.mock_result(&args, overrides.step_id.as_ref()) {
+            tokio::select! {
+                _ = tokio::time::sleep(result.duration) => {},
+                _ = overrides.cancelled() => return Err(CommandCancelled.into()),
+            }
+            // Replayed line by line like a real command's output
+            for (stream, stderr) in [(&result.stdout, false), (&result.stderr, true)] {
+                let mut lines = OutputLines::new(stream.as_bytes());
+                while let Some((line, _)) = lines.next_line().await? {
+                    if let Some(output) = &overrides.output {
+                        output.send(&line, stderr);
+                    }
+                    if let Some(progress) = progress_from_line(&line, result.duration) {
+                        on_progress(progress);
+                    }
+                }
+            }
+            return Ok(result);
+        }
+        let environment = self.invocation_environment(overrides.profile.as_deref(), &overrides.environment)?;
+        tokio::select! {
+            _ = self.rate_limiter.acquire() => {},
+            _ = overrides.cancelled() => return Err(CommandCancelled.into()),
+        }
+        let start_time = Instant::now();
+
+        info!("Executing RAPS command (streaming): {}", self.command_line(&args));
+
+        let mut cmd = AsyncCommand::new(&self.config.raps_binary_path);
+        cmd.args(&args)
+           .stdout(Stdio::piped())
+           .stderr(Stdio::piped())
+           .kill_on_drop(true);
+
+        cmd.env_clear().envs(environment);
+        if let Some(dir) = &overrides.working_dir {
+            cmd.current_dir(dir);
         }
 
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to execute RAPS CLI: {}", self.config.raps_binary_path))?;
+        let stdout = child.stdout.take().context("Failed to capture RAPS CLI stdout")?;
+        let stderr = child.stderr.take().context("Failed to capture RAPS CLI stderr")?;
+
+        let run = async {
+            let mut out_lines = OutputLines::new(stdout);
+            let mut err_lines = OutputLines::new(stderr);
+            let mut out = Vec::new();
+            let mut err = Vec::new();
+            let (mut out_done, mut err_done) = (false, false);
+
+            while !(out_done && err_done) {
+                let (line, from_stdout) = tokio::select! {
+                    line = out_lines.next_line(), if !out_done => (line?, true),
+                    line = err_lines.next_line(), if !err_done => (line?, false),
+                };
+                let buffer = if from_stdout { &mut out } else { &mut err };
+                match line {
+                    Some((line, terminator)) => {
+                        if let Some(output) = &overrides.output {
+                            output.send(&line, !from_stdout);
+                        }
+                        if let Some(progress) = progress_from_line(&line, start_time.elapsed()) {
+                            on_progress(progress);
+                        }
+                        buffer.extend_from_slice(&line);
+                        buffer.extend_from_slice(&terminator);
+                    },
+                    None if from_stdout => out_done = true,
+                    None => err_done = true,
+                }
+            }
+
+            let status = child.wait().await?;
+            Ok::<_, std::io::Error>((status, out, err))
+        };
+
+        // The process is killed and reaped before returning, so nothing it
+        // still writes (e.g. to the run's temporary directory) outlives it
+        let outcome = tokio::select! {
+            outcome = timeout(self.config.default_timeout, run) => Some(outcome),
+            _ = overrides.cancelled() => None,
+        };
+        let Some(outcome) = outcome else {
+            if let Err(e) = child.kill().await {
+                warn!("Failed to stop cancelled RAPS command: {}", e);
+            }
+            return Err(CommandCancelled.into());
+        };
+        let (status, out, err) = outcome
+            .with_context(|| format!("RAPS command timed out after {:?}", self.config.default_timeout))?
+            .with_context(|| format!("Failed to execute RAPS CLI: {}", self.config.raps_binary_path))?;
+
+        let duration = start_time.elapsed();
+        let result = CommandResult::new(
+            status.code().unwrap_or(-1),
+            String::from_utf8_lossy(&out).to_string(),
+            String::from_utf8_lossy(&err).to_string(),
+            duration,
+        );
+
+        self.log_command_result(&args, &result);
+        self.record_fixture(&args, overrides.step_id.as_ref(), &result);
+
         Ok(result)
     }
 
-    /// Execute a command with progress monitoring for long-running operations
+    /// Answer from the mock backend, if the client has one
+    fn mock_result(&self, args: &[String], step: Option<&StepId>) -> Option<CommandResult> {
+        let result = self.config.mock.as_ref()?.respond(args, step);
+        self.log_command_result(args, &result);
+        Some(result)
+    }
+
+    /// Keep a real command's answer as a fixture, if recording
+    fn record_fixture(&self, args: &[String], step: Option<&StepId>, result: &CommandResult) {
+        if let Some(recorder) = &self.config.recorder {
+            recorder.record(args, step, result);
+        }
+    }
+
+    /// Whether commands go to a mock backend rather than the RAPS CLI
+    pub fn is_mock(&self) -> bool {
+        self.config.mock.is_some()
+    }
+
+    /// The command line of `args` with credentials masked; logs and spans
+    /// only ever see this form, since spans may be exported unredacted
+    fn command_line(&self, args: &[String]) -> String {
+        redact_text(&format!("{} {}", self.config.raps_binary_path, args.join(" ")))
+    }
+
+    /// Record a finished command with structured fields for the session log
+    fn log_command_result(&self, args: &[String], result: &CommandResult) {
+        let command = self.command_line(args);
+        let duration_ms = result.duration.as_millis() as u64;
+
+        // Attach the outcome to the enclosing `raps_command` span
+        let span = tracing::Span::current();
+        span.record("command", command.as_str());
+        span.record("exit_code", result.exit_code);
+        if result.success {
+            debug!(
+                command = %command,
+                exit_code = result.exit_code,
+                duration_ms,
+                "RAPS command completed successfully in {:?}",
+                result.duration
+            );
+        } else {
+            warn!(
+                command = %command,
+                exit_code = result.exit_code,
+                duration_ms,
+                stderr = %redact_text(result.stderr.trim()),
+                "RAPS command failed: {}",
+                redact_text(&result.error_message().unwrap_or_default())
+            );
+        }
+    }
+
+    /// Execute a command with progress monitoring for long-running
+    /// operations, reporting to the progress callback
     pub async fn execute_with_progress(&self, command: &RapsCommand) -> Result<CommandResult> {
-        // For commands that support progress monitoring, we can parse their output
-        match command {
-            RapsCommand::Translate { action: TranslateAction::Start, .. } => {
-                self.execute_translation_with_progress(command).await
+        self.execute_command_with_progress(command, &InvocationOverrides::default(), |progress| {
+            if let Some(callback) = &self.progress_callback {
+                callback(progress);
             }
-            _ => {
-                // For other commands, just execute normally
-                self.execute_command_async(command).await
+        })
+        .await
+    }
+
+    /// Execute a command with progress monitoring for long-running operations:
+    /// translations waited for (`wait: true`) are polled until they finish,
+    /// other commands stream their output
+    pub async fn execute_command_with_progress<F>(
+        &self,
+        command: &RapsCommand,
+        overrides: &InvocationOverrides,
+        on_progress: F,
+    ) -> Result<CommandResult>
+    where
+        F: Fn(CommandProgress),
+    {
+        match command {
+            RapsCommand::Translate {
+                action: TranslateAction::Start | TranslateAction::Status,
+                params,
+            } if params.wait.unwrap_or(false) && params.urn.is_some() => {
+                self.execute_translation_with_progress(command, overrides, on_progress).await
             }
+            _ => self.execute_command_streaming(command, overrides, on_progress).await,
+        }
+    }
+
+    /// `raps ...` line of a command for display, or its label when it has
+    /// no command line
+    pub(crate) fn display_command_line(&self, command: &RapsCommand) -> String {
+        match self.build_command_args(command) {
+            Ok(args) => format!("raps {}", args.join(" ")),
+            Err(_) => super::diagram::command_label(command),
         }
     }
 
     /// Build command line arguments from a RapsCommand
-    fn build_command_args(&self, command: &RapsCommand) -> Result<Vec<String>> {
+    pub(crate) fn build_command_args(&self, command: &RapsCommand) -> Result<Vec<String>> {
         let mut args = Vec::new();
 
         match command {
@@ -298,6 +681,12 @@ impl RapsClient {
                         if params.batch.unwrap_or(false) {
                             args.push("--batch".to_string());
                         }
+                        if let Some(chunk_size) = params.chunk_size_mb {
+                            args.extend(["--chunk-size".to_string(), format!("{}MB", chunk_size)]);
+                        }
+                        if let Some(parallel) = params.parallel_parts {
+                            args.extend(["--parallel".to_string(), parallel.to_string()]);
+                        }
                     }
                     ObjectAction::Download => {
                         args.push("download".to_string());
@@ -351,6 +740,9 @@ impl RapsClient {
                         if let Some(format) = &params.format {
                             args.extend(["--format".to_string(), format.clone()]);
                         }
+                        if let Some(region) = &params.region {
+                            args.extend(["--region".to_string(), region.clone()]);
+                        }
                         if params.wait.unwrap_or(false) {
                             args.push("--wait".to_string());
                         }
@@ -366,6 +758,9 @@ impl RapsClient {
                         if let Some(urn) = &params.urn {
                             args.push(urn.clone());
                         }
+                        if let Some(format) = &params.format {
+                            args.extend(["--format".to_string(), format.clone()]);
+                        }
                         if let Some(output_dir) = &params.output_dir {
                             args.extend(["--output".to_string(), output_dir.to_string_lossy().to_string()]);
                         }
@@ -376,6 +771,15 @@ impl RapsClient {
                             args.push(urn.clone());
                         }
                     }
+                    TranslateAction::Thumbnail => {
+                        args.push("thumbnail".to_string());
+                        if let Some(urn) = &params.urn {
+                            args.push(urn.clone());
+                        }
+                        if let Some(output) = &params.output_dir {
+                            args.extend(["--output".to_string(), output.to_string_lossy().to_string()]);
+                        }
+                    }
                 }
             }
 
@@ -482,24 +886,100 @@ impl RapsClient {
         Ok(args)
     }
 
-    /// Execute translation command with progress monitoring
-    async fn execute_translation_with_progress(&self, command: &RapsCommand) -> Result<CommandResult> {
-        // Start the translation
-        let result = self.execute_command_async(command).await?;
-        
-        // If the command included --wait, progress monitoring was handled by RAPS CLI
-        // Otherwise, we could implement polling for status updates
-        if let Some(callback) = &self.progress_callback {
-            let progress = CommandProgress {
-                current_operation: "Translation completed".to_string(),
-                progress_percent: 1.0,
-                estimated_remaining: None,
-                status_info: HashMap::new(),
-            };
-            callback(progress);
+    /// Run a translation command, then poll `translate status` until the job
+    /// finishes, reporting the manifest's progress. A job that fails (or
+    /// never finishes) fails the command, naming the failed derivatives.
+    async fn execute_translation_with_progress<F>(
+        &self,
+        command: &RapsCommand,
+        overrides: &InvocationOverrides,
+        on_progress: F,
+    ) -> Result<CommandResult>
+    where
+        F: Fn(CommandProgress),
+    {
+        let RapsCommand::Translate { action, params } = command else {
+            return self.execute_command_streaming(command, overrides, on_progress).await;
+        };
+        // The CLI must not wait itself, or no progress shows until the end
+        let no_wait = TranslateParams {
+            wait: None,
+            ..params.clone()
+        };
+        let start_time = Instant::now();
+        let first = RapsCommand::Translate {
+            action: action.clone(),
+            params: no_wait.clone(),
+        };
+        let result = self.execute_command_streaming(&first, overrides, &on_progress).await?;
+        if !result.success {
+            return Ok(result);
         }
 
-        Ok(result)
+        // A status command's own output is the first poll; a start command
+        // keeps its output (the URN, for later steps) as the step's result
+        let status_command = RapsCommand::Translate {
+            action: TranslateAction::Status,
+            params: no_wait,
+        };
+        let (mut result, mut next_poll) = match action {
+            TranslateAction::Status => (None, Some(result)),
+            _ => (Some(result), None),
+        };
+        loop {
+            let poll = match next_poll.take() {
+                Some(poll) => poll,
+                None => self.execute_command_streaming(&status_command, overrides, |_| {}).await?,
+            };
+            if !poll.success {
+                return Ok(poll);
+            }
+            // Without a manifest there is nothing to wait for
+            let Some(status) = poll.json_output.as_ref().and_then(TranslationStatus::parse) else {
+                return Ok(result.unwrap_or(poll));
+            };
+
+            let elapsed = start_time.elapsed();
+            let mut status_info = HashMap::from([("status".to_string(), status.status.clone())]);
+            if !status.failed_derivatives.is_empty() {
+                status_info.insert("failed_derivatives".to_string(), status.failed_derivatives.join("\n"));
+            }
+            on_progress(CommandProgress {
+                current_operation: status.summary(),
+                progress_percent: status.progress,
+                estimated_remaining: (status.progress > 0.0 && status.progress < 1.0)
+                    .then(|| elapsed.mul_f32((1.0 - status.progress) / status.progress)),
+                status_info,
+            });
+
+            let failure = if status.is_finished() && !status.is_success() {
+                let details = if status.failed_derivatives.is_empty() {
+                    format!("status {}", status.status)
+                } else {
+                    status.failed_derivatives.join("; ")
+                };
+                Some(format!("Translation failed: {}", details))
+            } else if !status.is_finished() && elapsed >= self.config.translation_timeout {
+                Some(format!(
+                    "Translation timed out after {}s at {:.0}%",
+                    elapsed.as_secs(),
+                    status.progress * 100.0
+                ))
+            } else {
+                None
+            };
+            if status.is_finished() || failure.is_some() {
+                let result = result.take().unwrap_or(poll);
+                return Ok(match failure {
+                    Some(message) => CommandResult::new(1, result.stdout, message, elapsed),
+                    None => result,
+                });
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(self.config.translation_poll_interval) => {},
+                _ = overrides.cancelled() => return Err(CommandCancelled.into()),
+            }
+        }
     }
 
     /// Validate that RAPS CLI is available and working
@@ -536,6 +1016,11 @@ impl RapsClient {
     pub fn config(&self) -> &RapsClientConfig {
         &self.config
     }
+
+    /// Limiter shared by this client's commands
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
 }
 
 impl Default for RapsClient {
@@ -544,9 +1029,156 @@ impl Default for RapsClient {
     }
 }
 
+/// Parse a percentage (e.g. `45%` or `12.5%`) from a line of command output
+pub fn parse_progress_percent(line: &str) -> Option<f32> {
+    line.match_indices('%').find_map(|(idx, _)| {
+        let number: String = line[..idx]
+            .chars()
+            .rev()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        number
+            .parse::<f32>()
+            .ok()
+            .filter(|p| (0.0..=100.0).contains(p))
+            .map(|p| p / 100.0)
+    })
+}
+
+/// Longest remaining time reported for a transfer
+pub const MAX_REMAINING: Duration = Duration::from_secs(99 * 60 * 60);
+
+/// Transfer figures reported by an upload or download progress line, e.g.
+/// `Uploading part 3/10  45%  150.0 MB / 300.0 MB  12.5 MB/s`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TransferStats {
+    /// Bytes per second
+    pub bytes_per_sec: Option<f64>,
+    /// Bytes transferred so far
+    pub done_bytes: Option<f64>,
+    /// Bytes to transfer in total
+    pub total_bytes: Option<f64>,
+}
+
+impl TransferStats {
+    /// Parse the rate (`12.5 MB/s`) and `done / total` sizes from a line;
+    /// `None` if it has neither
+    pub fn parse(line: &str) -> Option<Self> {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let mut stats = Self::default();
+        let mut sizes = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            let (number, unit, used) = match split_quantity(tokens[i]) {
+                Some((number, unit)) if !unit.is_empty() => (number, unit, 1),
+                Some((number, _)) => match tokens.get(i + 1) {
+                    Some(unit) => (number, *unit, 2),
+                    None => break,
+                },
+                None => {
+                    i += 1;
+                    continue;
+                },
+            };
+            let (unit, per_second) = match unit.strip_suffix("/s") {
+                Some(unit) => (unit, true),
+                None => (unit.trim_end_matches(','), false),
+            };
+            match unit_bytes(unit) {
+                Some(scale) if per_second => stats.bytes_per_sec = Some(number * scale),
+                Some(scale) => sizes.push(number * scale),
+                None => {
+                    i += 1;
+                    continue;
+                },
+            }
+            i += used;
+        }
+        if sizes.len() >= 2 {
+            stats.done_bytes = Some(sizes[0]);
+            stats.total_bytes = Some(sizes[1]);
+        }
+        (stats.bytes_per_sec.is_some() || stats.total_bytes.is_some()).then_some(stats)
+    }
+
+    /// Time left at the current rate, when the rate and sizes are known; a
+    /// rate too slow to finish within [`MAX_REMAINING`] reports that
+    pub fn remaining(&self) -> Option<Duration> {
+        let (rate, done, total) = (self.bytes_per_sec?, self.done_bytes?, self.total_bytes?);
+        if rate > 0.0 && total >= done {
+            let secs = (total - done) / rate;
+            Some(Duration::try_from_secs_f64(secs).map_or(MAX_REMAINING, |left| left.min(MAX_REMAINING)))
+        } else {
+            None
+        }
+    }
+}
+
+/// `12.5MB/s` as (12.5, "MB/s"), `12.5` as (12.5, "")
+fn split_quantity(token: &str) -> Option<(f64, &str)> {
+    let end = token.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(token.len());
+    let number = token[..end].parse().ok()?;
+    Some((number, &token[end..]))
+}
+
+/// Bytes in one `unit` (B, KB, MB, GB and their KiB/MiB/GiB forms)
+fn unit_bytes(unit: &str) -> Option<f64> {
+    match unit.to_ascii_uppercase().as_str() {
+        "B" => Some(1.0),
+        "KB" | "KIB" => Some(1024.0),
+        "MB" | "MIB" => Some(1024.0 * 1024.0),
+        "GB" | "GIB" => Some(1024.0 * 1024.0 * 1024.0),
+        _ => None,
+    }
+}
+
+/// `12.5 MB/s` for a rate in bytes per second
+pub fn format_rate(bytes_per_sec: f64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes_per_sec >= MB {
+        format!("{:.1} MB/s", bytes_per_sec / MB)
+    } else {
+        format!("{:.0} KB/s", bytes_per_sec / 1024.0)
+    }
+}
+
+/// Build a progress update from an output line, if it reports a percentage
+fn progress_from_line(line: &[u8], elapsed: Duration) -> Option<CommandProgress> {
+    let text = String::from_utf8_lossy(line);
+    let percent = parse_progress_percent(&text)?;
+    let mut current_operation: String = text.trim().chars().take(80).collect();
+    let mut status_info = HashMap::new();
+
+    // Transfers report their rate and sizes: the ETA follows the bytes left,
+    // otherwise it is extrapolated from the elapsed time
+    let transfer = TransferStats::parse(&text);
+    let estimated_remaining = transfer.and_then(|t| t.remaining()).or_else(|| {
+        (percent > 0.0 && percent < 1.0).then(|| elapsed.mul_f32((1.0 - percent) / percent))
+    });
+    if let Some(transfer) = transfer {
+        if let Some(rate) = transfer.bytes_per_sec {
+            status_info.insert("rate".to_string(), format_rate(rate));
+        }
+        // The bar shows the figures; keep the words before them
+        let words: Vec<&str> = text.split_whitespace().take_while(|w| !w.contains('%')).collect();
+        current_operation = words.join(" ");
+    }
+
+    Some(CommandProgress {
+        current_operation,
+        progress_percent: percent,
+        estimated_remaining,
+        status_info,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::workflow::mock::MockResponse;
     use std::path::PathBuf;
 
     #[test]
@@ -578,6 +1210,13 @@ mod tests {
         assert_eq!(args, vec!["auth", "status", "--non-interactive", "--output", "json"]);
     }
 
+    #[test]
+    fn test_logged_command_line_is_redacted() {
+        let client = RapsClient::new();
+        let args = vec!["auth".to_string(), "login".to_string(), "--client-secret".to_string(), "s3cr3t-v4lue".to_string()];
+        assert_eq!(client.command_line(&args), "raps auth login --client-secret [REDACTED]");
+    }
+
     #[test]
     fn test_build_bucket_create_command_args() {
         let client = RapsClient::new();
@@ -613,6 +1252,8 @@ mod tests {
                 file_path: Some(PathBuf::from("/path/to/file.dwg")),
                 batch: Some(false),
                 expires_in: None,
+                chunk_size_mb: None,
+                parallel_parts: None,
             },
         };
 
@@ -635,6 +1276,7 @@ mod tests {
                 format: Some("svf2".to_string()),
                 output_dir: None,
                 wait: Some(true),
+                region: None,
             },
         };
 
@@ -668,4 +1310,328 @@ mod tests {
         assert!(config.parse_json_output);
         assert!(config.environment.is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_progress_percent() {
+        assert_eq!(parse_progress_percent("Translating... 45%"), Some(0.45));
+        assert_eq!(parse_progress_percent("{\"progress\": \"12.5% complete\"}"), Some(0.125));
+        assert_eq!(parse_progress_percent("done 100%"), Some(1.0));
+        assert_eq!(parse_progress_percent("no percentage here"), None);
+        assert_eq!(parse_progress_percent("bogus 250%"), None);
+    }
+
+    #[test]
+    fn test_progress_eta_extrapolation() {
+        let progress = progress_from_line(b"Uploading 25%", Duration::from_secs(10)).unwrap();
+        assert_eq!(progress.progress_percent, 0.25);
+        assert_eq!(progress.estimated_remaining, Some(Duration::from_secs(30)));
+        assert_eq!(progress.current_operation, "Uploading 25%");
+    }
+
+    #[tokio::test]
+    async fn test_translation_waits_for_manifest() {
+        let mock = Arc::new(MockBackend::new(vec![
+            MockResponse {
+                matches: "translate start".to_string(),
+                stdout: r#"{"urn": "dXJu", "result": "created"}"#.to_string(),
+                ..Default::default()
+            },
+            MockResponse {
+                matches: "translate status".to_string(),
+                stdout: r#"{"status": "failed", "progress": "complete", "derivatives": [
+                    {"outputType": "svf2", "status": "failed", "messages": [{"type": "error", "message": "Corrupt file"}]}
+                ]}"#
+                .to_string(),
+                ..Default::default()
+            },
+        ]));
+        let client = RapsClient::with_config(RapsClientConfig {
+            mock: Some(Arc::clone(&mock)),
+            ..Default::default()
+        });
+        let command = RapsCommand::Translate {
+            action: TranslateAction::Start,
+            params: TranslateParams {
+                urn: Some("dXJu".to_string()),
+                format: Some("svf2".to_string()),
+                output_dir: None,
+                wait: Some(true),
+                region: None,
+            },
+        };
+
+        let seen = std::sync::Mutex::new(Vec::new());
+        let result = client
+            .execute_command_with_progress(&command, &InvocationOverrides::default(), |p| {
+                seen.lock().unwrap().push((p.progress_percent, p.status_info.get("status").cloned()))
+            })
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(result.stderr, "Translation failed: svf2: Corrupt file");
+        // The start command's output stays the step's output
+        assert!(result.stdout.contains("created"));
+        assert_eq!(*seen.lock().unwrap(), vec![(1.0, Some("failed".to_string()))]);
+        let calls = mock.take_calls();
+        assert!(calls[0].starts_with("raps translate start dXJu") && calls[1].starts_with("raps translate status dXJu"));
+        // Polled here, so the CLI isn't asked to wait itself
+        assert!(calls.iter().all(|call| !call.contains("--wait")));
+    }
+
+    #[test]
+    fn test_multipart_upload_progress() {
+        let line = b"Uploading part 3/10  50%  150.0 MB / 300.0 MB  12.5MB/s";
+        let progress = progress_from_line(line, Duration::from_secs(10)).unwrap();
+        assert_eq!(progress.current_operation, "Uploading part 3/10");
+        assert_eq!(progress.status_info.get("rate").map(String::as_str), Some("12.5 MB/s"));
+        // 150 MB left at 12.5 MB/s, not the 10s the elapsed time suggests
+        assert_eq!(progress.estimated_remaining, Some(Duration::from_secs(12)));
+        assert_eq!(TransferStats::parse("Translating... 45%"), None);
+        // A crawling rate doesn't overflow the estimate
+        let crawling = TransferStats {
+            bytes_per_sec: Some(1e-300),
+            done_bytes: Some(0.0),
+            total_bytes: Some(1e10),
+        };
+        assert_eq!(crawling.remaining(), Some(MAX_REMAINING));
+
+        let command = RapsCommand::Object {
+            action: ObjectAction::Upload,
+            params: ObjectParams {
+                bucket_name: "models".to_string(),
+                object_key: None,
+                file_path: Some(PathBuf::from("tower.rvt")),
+                batch: None,
+                expires_in: None,
+                chunk_size_mb: Some(8),
+                parallel_parts: Some(4),
+            },
+        };
+        let args = RapsClient::new().build_command_args(&command).unwrap();
+        assert!(args.join(" ").contains("tower.rvt --chunk-size 8MB --parallel 4"), "{:?}", args);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_command_streaming_reports_progress() {
+        let client = RapsClient::with_config(RapsClientConfig {
+            raps_binary_path: "sh".to_string(),
+            parse_json_output: false,
+            ..RapsClientConfig::default()
+        });
+        let command = RapsCommand::Custom {
+            command: "-c".to_string(),
+            args: vec!["echo 'step 50%'; echo 'warn 75%' >&2; echo done".to_string()],
+        };
+
+        let seen = std::sync::Mutex::new(Vec::new());
+        let result = client
+            .execute_command_streaming(&command, &InvocationOverrides::default(), |p| {
+                seen.lock().unwrap().push(p.progress_percent)
+            })
+            .await
+            .unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.stdout, "step 50%\ndone\n");
+        let mut seen = seen.into_inner().unwrap();
+        seen.sort_by(|a, b| a.total_cmp(b));
+        assert_eq!(seen, vec![0.5, 0.75]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_command_streaming_stops_on_cancel() {
+        let client = RapsClient::with_config(RapsClientConfig {
+            raps_binary_path: "sh".to_string(),
+            parse_json_output: false,
+            ..RapsClientConfig::default()
+        });
+        let command = RapsCommand::Custom {
+            command: "-c".to_string(),
+            args: vec!["echo started; sleep 30".to_string()],
+        };
+        // Cancelled once the process is known to run
+        let cancel = Arc::new(Notify::new());
+        let signal = Arc::clone(&cancel);
+        let overrides = InvocationOverrides {
+            output: Some(OutputTap::new(move |_, _| signal.notify_one())),
+            cancel: Some(cancel),
+            ..Default::default()
+        };
+
+        let started = Instant::now();
+        let error = client
+            .execute_command_streaming(&command, &overrides, |_| {})
+            .await
+            .unwrap_err();
+
+        assert!(error.is::<CommandCancelled>());
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_execute_command_streaming_cancelled_while_throttled() {
+        let client = RapsClient::with_config(RapsClientConfig {
+            raps_binary_path: "sh".to_string(),
+            rate_limit: RateLimitConfig {
+                requests_per_second: 0.001,
+                burst: 1,
+                ..RateLimitConfig::default()
+            },
+            ..RapsClientConfig::default()
+        });
+        // The only token is taken, so the next command waits for minutes
+        client.rate_limiter().acquire().await;
+        let cancel = Arc::new(Notify::new());
+        cancel.notify_one();
+        let overrides = InvocationOverrides {
+            cancel: Some(cancel),
+            ..Default::default()
+        };
+        let command = RapsCommand::Custom {
+            command: "-c".to_string(),
+            args: vec!["true".to_string()],
+        };
+
+        let error = tokio::time::timeout(
+            Duration::from_secs(5),
+            client.execute_command_streaming(&command, &overrides, |_| {}),
+        )
+        .await
+        .unwrap()
+        .unwrap_err();
+        assert!(error.is::<CommandCancelled>());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_command_streaming_applies_overrides() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let client = RapsClient::with_config(RapsClientConfig {
+            raps_binary_path: "sh".to_string(),
+            parse_json_output: false,
+            ..RapsClientConfig::default()
+        });
+        let command = RapsCommand::Custom {
+            command: "-c".to_string(),
+            args: vec!["echo \"$DEMO_VAR\"; echo \"[$CARGO_MANIFEST_DIR]\"; pwd".to_string()],
+        };
+        let overrides = InvocationOverrides {
+            environment: HashMap::from([("DEMO_VAR".to_string(), "scratch".to_string())]),
+            working_dir: Some(temp_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        let result = client
+            .execute_command_streaming(&command, &overrides, |_| {})
+            .await
+            .unwrap();
+
+        let mut lines = result.stdout.lines();
+        assert_eq!(lines.next(), Some("scratch"));
+        // Not in the default allowlist
+        assert_eq!(lines.next(), Some("[]"));
+        let cwd = PathBuf::from(lines.next().unwrap()).canonicalize().unwrap();
+        assert_eq!(cwd, temp_dir.path().canonicalize().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_command_streaming_uses_profile() {
+        let client = RapsClient::with_config(RapsClientConfig {
+            raps_binary_path: "sh".to_string(),
+            parse_json_output: false,
+            profiles: HashMap::from([(
+                "account-b".to_string(),
+                HashMap::from([("APS_CLIENT_ID".to_string(), "client-b".to_string())]),
+            )]),
+            ..RapsClientConfig::default()
+        });
+        let command = RapsCommand::Custom {
+            command: "-c".to_string(),
+            args: vec!["echo \"$APS_CLIENT_ID\"".to_string()],
+        };
+        let mut overrides = InvocationOverrides {
+            profile: Some("account-b".to_string()),
+            ..Default::default()
+        };
+
+        let result = client.execute_command_streaming(&command, &overrides, |_| {}).await.unwrap();
+        assert_eq!(result.stdout.trim(), "client-b");
+
+        overrides.profile = Some("account-c".to_string());
+        let error = client.execute_command_streaming(&command, &overrides, |_| {}).await.unwrap_err();
+        assert!(format!("{:#}", error).contains("Unknown RAPS profile 'account-c' (configured: account-b)"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_command_streaming_taps_output() {
+        let client = RapsClient::with_config(RapsClientConfig {
+            raps_binary_path: "sh".to_string(),
+            parse_json_output: false,
+            ..RapsClientConfig::default()
+        });
+        let command = RapsCommand::Custom {
+            command: "-c".to_string(),
+            args: vec!["echo one; echo two >&2".to_string()],
+        };
+        let lines = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let tapped = lines.clone();
+        let overrides = InvocationOverrides {
+            output: Some(OutputTap::new(move |line, stderr| {
+                tapped.lock().unwrap().push((line.to_string(), stderr))
+            })),
+            ..Default::default()
+        };
+
+        client.execute_command_streaming(&command, &overrides, |_| {}).await.unwrap();
+        let mut lines = lines.lock().unwrap().clone();
+        lines.sort();
+        assert_eq!(lines, vec![("one".to_string(), false), ("two".to_string(), true)]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_command_streaming_splits_carriage_returns() {
+        let client = RapsClient::with_config(RapsClientConfig {
+            raps_binary_path: "sh".to_string(),
+            parse_json_output: false,
+            ..RapsClientConfig::default()
+        });
+        let command = RapsCommand::Custom {
+            command: "-c".to_string(),
+            args: vec![r"printf 'Uploading 10%%\rUploading 60%%\rdone\n'".to_string()],
+        };
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let tapped = seen.clone();
+        let result = client
+            .execute_command_streaming(&command, &InvocationOverrides::default(), move |progress| {
+                tapped.lock().unwrap().push(progress.progress_percent)
+            })
+            .await
+            .unwrap();
+        // Each redraw is reported as it happens; the output is kept as printed
+        assert_eq!(*seen.lock().unwrap(), vec![0.1, 0.6]);
+        assert_eq!(result.stdout, "Uploading 10%\rUploading 60%\rdone\n");
+
+        // Mock output is replayed the same way
+        let client = RapsClient::with_config(RapsClientConfig {
+            mock: Some(Arc::new(MockBackend::new(vec![MockResponse {
+                stdout: "Uploading 10%\rUploading 60%\rdone\n".to_string(),
+                ..Default::default()
+            }]))),
+            ..Default::default()
+        });
+        seen.lock().unwrap().clear();
+        let tapped = seen.clone();
+        client
+            .execute_command_streaming(&command, &InvocationOverrides::default(), move |progress| {
+                tapped.lock().unwrap().push(progress.progress_percent)
+            })
+            .await
+            .unwrap();
+        assert_eq!(*seen.lock().unwrap(), vec![0.1, 0.6]);
+    }
+}
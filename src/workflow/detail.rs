@@ -0,0 +1,284 @@
+// Workflow details for `raps-demo show`
+//
+// Over SSH or in a script there is no TUI to open the Overview and Steps tabs
+// of a workflow. `show` prints the same information: metadata, prerequisites,
+// required assets with whether they are on disk or can be downloaded, each
+// step with its RAPS command resolved as far as it can be without running
+// anything, and the cost estimate. Text is for people; JSON and YAML carry
+// the same fields for tools; Markdown drops straight into docs.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::client::RapsClient;
+use super::discovery::WorkflowDefinition;
+use super::history::format_duration_secs;
+use super::run_summary::cost_label;
+use super::types::CostEstimate;
+use super::watch::resolved_command_lines;
+
+/// Output format of `show`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DetailFormat {
+    /// Plain text for the terminal
+    #[default]
+    Text,
+    Json,
+    Yaml,
+    /// Markdown section
+    Markdown,
+}
+
+impl FromStr for DetailFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(DetailFormat::Text),
+            "json" => Ok(DetailFormat::Json),
+            "yaml" | "yml" => Ok(DetailFormat::Yaml),
+            "md" | "markdown" => Ok(DetailFormat::Markdown),
+            other => Err(anyhow::anyhow!("Unknown format: {} (expected text, json, yaml or md)", other)),
+        }
+    }
+}
+
+/// Whether a required asset can be used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AssetAvailability {
+    /// The file exists
+    Present,
+    /// The file is missing but a known sample asset provides it
+    Downloadable,
+    /// The file is missing
+    Missing,
+}
+
+/// A required asset of the workflow
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AssetDetail {
+    pub path: String,
+    pub status: AssetAvailability,
+    /// Steps that declare the asset; empty when the whole workflow needs it
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub needed_by: Vec<String>,
+}
+
+/// A step with its resolved RAPS command
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StepDetail {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub command: String,
+}
+
+/// Everything `show` prints about a workflow
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkflowDetail {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub category: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub estimated_duration_secs: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_estimate: Option<CostEstimate>,
+    /// Prerequisites as `type: description`
+    pub prerequisites: Vec<String>,
+    pub assets: Vec<AssetDetail>,
+    pub steps: Vec<StepDetail>,
+}
+
+impl WorkflowDetail {
+    /// Details of `workflow`, with its commands built by `client` and the
+    /// availability of each required asset given by `availability`
+    pub fn new(
+        workflow: &WorkflowDefinition,
+        client: &RapsClient,
+        availability: impl Fn(&Path) -> AssetAvailability,
+    ) -> Self {
+        let metadata = &workflow.metadata;
+        let prerequisites = metadata
+            .prerequisites
+            .iter()
+            .map(|prerequisite| {
+                let kind = serde_yaml::to_string(&prerequisite.prerequisite_type).unwrap_or_default();
+                format!("{}: {}", kind.trim(), prerequisite.description)
+            })
+            .collect();
+        let assets = workflow
+            .required_assets()
+            .iter()
+            .map(|asset| AssetDetail {
+                path: asset.display().to_string(),
+                status: availability(asset),
+                needed_by: workflow.steps_requiring(asset).iter().map(|id| id.to_string()).collect(),
+            })
+            .collect();
+        let steps = workflow
+            .steps
+            .iter()
+            .zip(resolved_command_lines(client, workflow, &HashMap::new()))
+            .map(|(step, command)| StepDetail {
+                id: step.id.clone(),
+                name: step.name.clone(),
+                description: step.description.clone(),
+                command,
+            })
+            .collect();
+
+        Self {
+            id: metadata.id.clone(),
+            name: metadata.name.clone(),
+            description: metadata.description.clone(),
+            category: metadata.category.id().to_string(),
+            tags: metadata.tags.clone(),
+            estimated_duration_secs: metadata.estimated_duration.num_seconds(),
+            cost_estimate: metadata.cost_estimate.clone(),
+            prerequisites,
+            assets,
+            steps,
+        }
+    }
+
+    /// The details in `format`
+    pub fn render(&self, format: DetailFormat) -> Result<String> {
+        match format {
+            DetailFormat::Json => Ok(serde_json::to_string_pretty(self)? + "\n"),
+            DetailFormat::Yaml => Ok(serde_yaml::to_string(self)?),
+            DetailFormat::Text => Ok(self.text()),
+            DetailFormat::Markdown => Ok(self.markdown()),
+        }
+    }
+
+    fn cost(&self) -> String {
+        cost_label(self.cost_estimate.as_ref())
+    }
+
+    fn asset_line(asset: &AssetDetail) -> String {
+        let status = match asset.status {
+            AssetAvailability::Present => "present",
+            AssetAvailability::Downloadable => "missing, downloadable",
+            AssetAvailability::Missing => "missing",
+        };
+        let steps = if asset.needed_by.is_empty() {
+            String::new()
+        } else {
+            format!(", needed by step {}", asset.needed_by.join(", "))
+        };
+        format!("{} ({}{})", asset.path, status, steps)
+    }
+
+    fn text(&self) -> String {
+        let mut out = format!("{} - {}\n{}\n\n", self.id, self.name, self.description);
+        out.push_str(&format!("  Category: {}\n", self.category));
+        if !self.tags.is_empty() {
+            out.push_str(&format!("  Tags:     {}\n", self.tags.join(", ")));
+        }
+        out.push_str(&format!("  Duration: ~{}\n", format_duration_secs(self.estimated_duration_secs as f64)));
+        out.push_str(&format!("  Cost:     {}\n", self.cost()));
+        if !self.prerequisites.is_empty() {
+            out.push_str("\nPrerequisites:\n");
+            for prerequisite in &self.prerequisites {
+                out.push_str(&format!("  - {}\n", prerequisite));
+            }
+        }
+        if !self.assets.is_empty() {
+            out.push_str("\nRequired assets:\n");
+            for asset in &self.assets {
+                out.push_str(&format!("  - {}\n", Self::asset_line(asset)));
+            }
+        }
+        out.push_str("\nSteps:\n");
+        for (i, step) in self.steps.iter().enumerate() {
+            out.push_str(&format!("  {}. {} ({})\n     $ {}\n", i + 1, step.name, step.id, step.command));
+        }
+        out
+    }
+
+    fn markdown(&self) -> String {
+        let mut out = format!("# {}\n\n{}\n\n", self.name, self.description);
+        out.push_str(&format!("- **ID:** `{}`\n", self.id));
+        out.push_str(&format!("- **Category:** {}\n", self.category));
+        if !self.tags.is_empty() {
+            out.push_str(&format!("- **Tags:** {}\n", self.tags.join(", ")));
+        }
+        out.push_str(&format!(
+            "- **Estimated duration:** ~{}\n",
+            format_duration_secs(self.estimated_duration_secs as f64)
+        ));
+        out.push_str(&format!("- **Cost:** {}\n", self.cost()));
+        if !self.prerequisites.is_empty() {
+            out.push_str("\n## Prerequisites\n\n");
+            for prerequisite in &self.prerequisites {
+                out.push_str(&format!("- {}\n", prerequisite));
+            }
+        }
+        if !self.assets.is_empty() {
+            out.push_str("\n## Required Assets\n\n");
+            for asset in &self.assets {
+                out.push_str(&format!("- {}\n", Self::asset_line(asset)));
+            }
+        }
+        out.push_str("\n## Steps\n");
+        for (i, step) in self.steps.iter().enumerate() {
+            out.push_str(&format!("\n### {}. {}\n\n", i + 1, step.name));
+            if !step.description.is_empty() {
+                out.push_str(&format!("{}\n\n", step.description));
+            }
+            out.push_str(&format!("```sh\n{}\n```\n", step.command));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKFLOW: &str = r#"
+metadata:
+  id: "upload"
+  name: "Upload"
+  description: "Upload a model"
+  category: "oss"
+  estimated_duration: 90
+  cost_estimate: {description: "Storage", max_cost_usd: 0.05}
+  prerequisites:
+    - {type: authentication, description: "Valid APS credentials"}
+params:
+  bucket: "demo-bucket"
+steps:
+  - id: "create-bucket"
+    name: "Create"
+    description: "Create the bucket"
+    command: {type: bucket, action: create, bucket_name: "{params.bucket}", retention_policy: transient}
+  - id: "upload"
+    name: "Upload"
+    description: "Upload the model"
+    command: {type: object, action: upload, bucket_name: "{params.bucket}", file_path: "assets/model.rvt"}
+    requires_assets: ["assets/model.rvt"]
+"#;
+
+    #[test]
+    fn test_workflow_detail() {
+        let workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        let detail = WorkflowDetail::new(&workflow, &RapsClient::new(), |_| AssetAvailability::Downloadable);
+        assert_eq!(detail.prerequisites, vec!["authentication: Valid APS credentials"]);
+        assert_eq!(detail.assets[0].needed_by, vec!["upload"]);
+        assert!(detail.steps[0].command.contains("demo-bucket"), "{}", detail.steps[0].command);
+
+        let text = detail.render(DetailFormat::Text).unwrap();
+        assert!(text.contains("  Cost:     up to $0.05 (Storage)\n"), "{}", text);
+        assert!(text.contains("  - assets/model.rvt (missing, downloadable, needed by step upload)\n"), "{}", text);
+        let json: serde_json::Value = serde_json::from_str(&detail.render(DetailFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["assets"][0]["status"], "downloadable");
+        assert!(detail.render(DetailFormat::Markdown).unwrap().contains("### 2. Upload\n"));
+    }
+}
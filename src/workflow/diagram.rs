@@ -0,0 +1,455 @@
+// Diagram export for RAPS Demo Workflows
+//
+// Converts a workflow definition into text diagram formats (Mermaid, Graphviz
+// DOT, SVG) so documentation and slides can be generated straight from the YAML.
+// The Markdown format is a full tutorial: the Mermaid diagram followed by
+// every step with its command, expected output and teaching notes.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::discovery::WorkflowDefinition;
+use super::types::RapsCommand;
+
+/// Supported diagram output formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramFormat {
+    /// Mermaid flowchart (`.mmd`)
+    Mermaid,
+    /// Graphviz DOT graph (`.dot`)
+    Dot,
+    /// Standalone SVG image (`.svg`)
+    Svg,
+    /// Markdown tutorial with the diagram and step notes (`.md`)
+    Markdown,
+}
+
+impl DiagramFormat {
+    /// Conventional file extension for the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DiagramFormat::Mermaid => "mmd",
+            DiagramFormat::Dot => "dot",
+            DiagramFormat::Svg => "svg",
+            DiagramFormat::Markdown => "md",
+        }
+    }
+}
+
+impl std::str::FromStr for DiagramFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "mermaid" | "mmd" => Ok(DiagramFormat::Mermaid),
+            "dot" | "graphviz" => Ok(DiagramFormat::Dot),
+            "svg" => Ok(DiagramFormat::Svg),
+            "markdown" | "md" => Ok(DiagramFormat::Markdown),
+            other => Err(anyhow::anyhow!("Unknown diagram format: {}", other)),
+        }
+    }
+}
+
+/// Render a workflow definition in the requested format
+pub fn render_diagram(definition: &WorkflowDefinition, format: DiagramFormat) -> String {
+    match format {
+        DiagramFormat::Mermaid => render_mermaid(definition),
+        DiagramFormat::Dot => render_dot(definition),
+        DiagramFormat::Svg => render_svg(definition),
+        DiagramFormat::Markdown => render_markdown(definition),
+    }
+}
+
+/// Short `raps <group> <action>` label for a command
+pub fn command_label(command: &RapsCommand) -> String {
+    match command {
+        RapsCommand::Auth { action } => format!("raps auth {}", action_name(action)),
+        RapsCommand::Bucket { action, .. } => format!("raps bucket {}", action_name(action)),
+        RapsCommand::Object { action, .. } => format!("raps object {}", action_name(action)),
+        RapsCommand::Translate { action, .. } => {
+            format!("raps translate {}", action_name(action))
+        },
+        RapsCommand::DataManagement { action, .. } => {
+            format!("raps dm {}", action_name(action))
+        },
+        RapsCommand::DesignAutomation { action, .. } => {
+            format!("raps da {}", action_name(action))
+        },
+        RapsCommand::Custom { command, .. } => command.clone(),
+    }
+}
+
+/// Serialized (kebab-case) name of an action enum
+fn action_name<T: Serialize + std::fmt::Debug>(action: &T) -> String {
+    serde_json::to_value(action)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("{:?}", action).to_lowercase())
+}
+
+/// Render as a Markdown tutorial
+fn render_markdown(definition: &WorkflowDefinition) -> String {
+    let metadata = &definition.metadata;
+    let mut out = format!("# {}\n\n{}\n\n", metadata.name, metadata.description);
+    out.push_str(&format!("- **ID:** `{}`\n", metadata.id));
+    out.push_str(&format!("- **Category:** {}\n", metadata.category));
+    out.push_str(&format!(
+        "- **Estimated duration:** ~{} seconds\n",
+        metadata.estimated_duration.num_seconds()
+    ));
+    for dependency in dependencies(definition) {
+        out.push_str(&format!("- **Depends on:** `{}`\n", dependency));
+    }
+    out.push_str(&format!("\n```mermaid\n{}```\n\n## Steps\n", render_mermaid(definition)));
+
+    for (i, step) in definition.steps.iter().enumerate() {
+        out.push_str(&format!("\n### {}. {}\n\n", i + 1, step.name));
+        if !step.description.is_empty() {
+            out.push_str(&format!("{}\n\n", step.description));
+        }
+        out.push_str(&format!("```sh\n{}\n```\n", command_label(&step.command)));
+        if let Some(expected) = &step.expected_output {
+            out.push_str(&format!("\nExpected output:\n\n```\n{}\n```\n", expected.trim_end()));
+        }
+        if let Some(notes) = &step.notes {
+            out.push('\n');
+            for (j, line) in notes.trim_end().lines().enumerate() {
+                let prefix = if j == 0 { "> **Note:** " } else { "> " };
+                out.push_str(&format!("{}{}\n", prefix, line));
+            }
+        }
+    }
+
+    if !definition.cleanup.is_empty() {
+        out.push_str("\n## Cleanup\n\n");
+        for command in &definition.cleanup {
+            out.push_str(&format!("- `{}`\n", command_label(command)));
+        }
+    }
+    out
+}
+
+/// Render as a Mermaid top-down flowchart
+fn render_mermaid(definition: &WorkflowDefinition) -> String {
+    let escape = |s: &str| s.replace('"', "#quot;");
+    let mut out = String::from("flowchart TD\n");
+
+    out.push_str("    start([Start])\n");
+    for (i, dependency) in dependencies(definition).iter().enumerate() {
+        out.push_str(&format!("    dep{}[[\"{}\"]]\n", i + 1, escape(dependency)));
+        out.push_str(&format!("    dep{} -. depends on .-> start\n", i + 1));
+    }
+
+    let mut previous = "start".to_string();
+    for (i, step) in definition.steps.iter().enumerate() {
+        let node = format!("step{}", i + 1);
+        out.push_str(&format!(
+            "    {}[\"{}. {}<br/><code>{}</code>\"]\n",
+            node,
+            i + 1,
+            escape(&step.name),
+            escape(&command_label(&step.command))
+        ));
+        out.push_str(&format!("    {} --> {}\n", previous, node));
+        if !step.cleanup_commands.is_empty() {
+            out.push_str(&format!(
+                "    {}_cleanup>\"Cleanup ({} cmds)\"]\n",
+                node,
+                step.cleanup_commands.len()
+            ));
+            out.push_str(&format!("    {} -. on failure .-> {}_cleanup\n", node, node));
+        }
+        previous = node;
+    }
+
+    if !definition.cleanup.is_empty() {
+        out.push_str(&format!(
+            "    cleanup[/\"Cleanup ({} cmds)\"/]\n",
+            definition.cleanup.len()
+        ));
+        out.push_str(&format!("    {} --> cleanup\n", previous));
+        previous = "cleanup".to_string();
+    }
+
+    // `end` is a reserved word in Mermaid
+    out.push_str("    finish([End])\n");
+    out.push_str(&format!("    {} --> finish\n", previous));
+    out
+}
+
+/// Render as a Graphviz DOT digraph
+fn render_dot(definition: &WorkflowDefinition) -> String {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut out = format!("digraph \"{}\" {{\n", escape(&definition.metadata.id));
+    out.push_str("    rankdir=TB;\n");
+    out.push_str(&format!("    label=\"{}\";\n", escape(&definition.metadata.name)));
+    out.push_str("    node [shape=box, style=\"rounded,filled\", fillcolor=\"#e3f2fd\", fontname=\"monospace\"];\n");
+    out.push_str("    start [label=\"Start\", shape=oval, fillcolor=\"#e8f5e9\"];\n");
+
+    for (i, dependency) in dependencies(definition).iter().enumerate() {
+        out.push_str(&format!(
+            "    dep{} [label=\"{}\", shape=component, fillcolor=\"#fff8e1\"];\n",
+            i + 1,
+            escape(dependency)
+        ));
+        out.push_str(&format!(
+            "    dep{} -> start [style=dotted, label=\"depends on\"];\n",
+            i + 1
+        ));
+    }
+
+    let mut previous = "start".to_string();
+    for (i, step) in definition.steps.iter().enumerate() {
+        let node = format!("step{}", i + 1);
+        out.push_str(&format!(
+            "    {} [label=\"{}. {}\\n{}\"];\n",
+            node,
+            i + 1,
+            escape(&step.name),
+            escape(&command_label(&step.command))
+        ));
+        out.push_str(&format!("    {} -> {};\n", previous, node));
+        if !step.cleanup_commands.is_empty() {
+            out.push_str(&format!(
+                "    {}_cleanup [label=\"Cleanup ({} cmds)\", shape=note, fillcolor=\"#f3e5f5\"];\n",
+                node,
+                step.cleanup_commands.len()
+            ));
+            out.push_str(&format!(
+                "    {} -> {}_cleanup [style=dashed, label=\"on failure\"];\n",
+                node, node
+            ));
+        }
+        previous = node;
+    }
+
+    if !definition.cleanup.is_empty() {
+        out.push_str(&format!(
+            "    cleanup [label=\"Cleanup ({} cmds)\", shape=parallelogram, fillcolor=\"#f3e5f5\"];\n",
+            definition.cleanup.len()
+        ));
+        out.push_str(&format!("    {} -> cleanup;\n", previous));
+        previous = "cleanup".to_string();
+    }
+
+    out.push_str("    finish [label=\"End\", shape=oval, fillcolor=\"#ffebee\"];\n");
+    out.push_str(&format!("    {} -> finish;\n", previous));
+    out.push_str("}\n");
+    out
+}
+
+/// Workflow IDs this workflow depends on
+fn dependencies(definition: &WorkflowDefinition) -> &[String] {
+    definition.dependencies.as_deref().unwrap_or_default()
+}
+
+/// Render as a simple vertical SVG diagram
+fn render_svg(definition: &WorkflowDefinition) -> String {
+    const WIDTH: usize = 420;
+    const NODE_WIDTH: usize = 340;
+    const NODE_HEIGHT: usize = 54;
+    const GAP: usize = 30;
+
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    };
+
+    // (title, subtitle, fill, stroke, rounded)
+    let mut nodes: Vec<(String, String, &str, &str, bool)> = Vec::new();
+    if !dependencies(definition).is_empty() {
+        nodes.push((
+            "Depends on".to_string(),
+            dependencies(definition).join(", "),
+            "#fff8e1",
+            "#f9a825",
+            false,
+        ));
+    }
+    nodes.push(("Start".to_string(), String::new(), "#e8f5e9", "#2e7d32", true));
+    for (i, step) in definition.steps.iter().enumerate() {
+        let mut subtitle = command_label(&step.command);
+        if !step.cleanup_commands.is_empty() {
+            subtitle.push_str(&format!(" (+{} cleanup on failure)", step.cleanup_commands.len()));
+        }
+        nodes.push((
+            format!("{}. {}", i + 1, step.name),
+            subtitle,
+            "#e3f2fd",
+            "#1565c0",
+            false,
+        ));
+    }
+    if !definition.cleanup.is_empty() {
+        nodes.push((
+            format!("Cleanup ({} cmds)", definition.cleanup.len()),
+            String::new(),
+            "#f3e5f5",
+            "#6a1b9a",
+            false,
+        ));
+    }
+    nodes.push(("End".to_string(), String::new(), "#ffebee", "#c62828", true));
+
+    let height = GAP + nodes.len() * (NODE_HEIGHT + GAP);
+    let x = (WIDTH - NODE_WIDTH) / 2;
+    let center = WIDTH / 2;
+
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"monospace\">\n",
+        w = WIDTH,
+        h = height
+    );
+    out.push_str(&format!(
+        "  <title>{}</title>\n",
+        escape(&definition.metadata.name)
+    ));
+    out.push_str("  <defs><marker id=\"arrow\" markerWidth=\"10\" markerHeight=\"10\" refX=\"5\" refY=\"5\" orient=\"auto\"><path d=\"M0,0 L10,5 L0,10 z\" fill=\"#757575\"/></marker></defs>\n");
+
+    for (i, (title, subtitle, fill, stroke, rounded)) in nodes.iter().enumerate() {
+        let y = GAP + i * (NODE_HEIGHT + GAP);
+        let radius = if *rounded { NODE_HEIGHT / 2 } else { 6 };
+        out.push_str(&format!(
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"2\"/>\n",
+            x, y, NODE_WIDTH, NODE_HEIGHT, radius, fill, stroke
+        ));
+        if subtitle.is_empty() {
+            out.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"14\" font-weight=\"bold\">{}</text>\n",
+                center,
+                y + NODE_HEIGHT / 2 + 5,
+                escape(title)
+            ));
+        } else {
+            out.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"14\" font-weight=\"bold\">{}</text>\n",
+                center,
+                y + 22,
+                escape(title)
+            ));
+            out.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"12\" fill=\"#616161\">{}</text>\n",
+                center,
+                y + 42,
+                escape(subtitle)
+            ));
+        }
+
+        if i + 1 < nodes.len() {
+            out.push_str(&format!(
+                "  <line x1=\"{c}\" y1=\"{}\" x2=\"{c}\" y2=\"{}\" stroke=\"#757575\" stroke-width=\"2\" marker-end=\"url(#arrow)\"/>\n",
+                y + NODE_HEIGHT,
+                y + NODE_HEIGHT + GAP - 4,
+                c = center
+            ));
+        }
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_definition() -> WorkflowDefinition {
+        serde_yaml::from_str(
+            r#"
+metadata:
+  id: "diagram-test"
+  name: "Diagram <Test>"
+  description: "Diagram test"
+  category: oss
+  prerequisites: []
+  estimated_duration: 60
+  required_assets: []
+steps:
+  - id: "create"
+    name: "Create \"Bucket\""
+    description: "Create a bucket"
+    command:
+      type: bucket
+      action: create
+      bucket_name: "demo-bucket"
+    expected_output: |
+      Bucket demo-bucket created
+    notes: |
+      Bucket names are global across APS.
+      Use a demo prefix.
+  - id: "sign"
+    name: "Sign URL"
+    description: "Create a signed URL"
+    command:
+      type: object
+      action: signed-url
+      bucket_name: "demo-bucket"
+    cleanup_commands:
+      - type: bucket
+        action: delete
+        bucket_name: "demo-bucket"
+dependencies: ["auth-basics"]
+cleanup:
+  - type: bucket
+    action: delete
+    bucket_name: "demo-bucket"
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mermaid_export() {
+        let mermaid = render_diagram(&sample_definition(), DiagramFormat::Mermaid);
+        assert!(mermaid.starts_with("flowchart TD\n"));
+        assert!(mermaid.contains("step1[\"1. Create #quot;Bucket#quot;<br/><code>raps bucket create</code>\"]"));
+        assert!(mermaid.contains("<code>raps object signed-url</code>"));
+        assert!(mermaid.contains("step2 --> cleanup"));
+        assert!(mermaid.contains("cleanup --> finish"));
+        assert!(mermaid.contains("dep1 -. depends on .-> start"));
+        assert!(mermaid.contains("step2 -. on failure .-> step2_cleanup"));
+    }
+
+    #[test]
+    fn test_dot_export() {
+        let dot = render_diagram(&sample_definition(), DiagramFormat::Dot);
+        assert!(dot.starts_with("digraph \"diagram-test\" {"));
+        assert!(dot.contains("step1 [label=\"1. Create \\\"Bucket\\\"\\nraps bucket create\"];"));
+        assert!(dot.contains("dep1 -> start"));
+        assert!(dot.contains("step2 -> step2_cleanup [style=dashed"));
+        assert!(dot.contains("cleanup -> finish;"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn test_svg_export_escapes_text() {
+        let svg = render_diagram(&sample_definition(), DiagramFormat::Svg);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains("<title>Diagram &lt;Test&gt;</title>"));
+        // Dependencies, start, two steps, cleanup, end
+        assert_eq!(svg.matches("<rect").count(), 6);
+    }
+
+    #[test]
+    fn test_markdown_export() {
+        let markdown = render_diagram(&sample_definition(), DiagramFormat::Markdown);
+        assert!(markdown.starts_with("# Diagram <Test>\n\nDiagram test\n"));
+        assert!(markdown.contains("```mermaid\nflowchart TD\n"));
+        assert!(markdown.contains("### 1. Create \"Bucket\"\n\nCreate a bucket\n\n```sh\nraps bucket create\n```\n"));
+        assert!(markdown.contains("Expected output:\n\n```\nBucket demo-bucket created\n```\n"));
+        assert!(markdown.contains("> **Note:** Bucket names are global across APS.\n> Use a demo prefix.\n"));
+        assert!(markdown.contains("## Cleanup\n\n- `raps bucket delete`\n"));
+    }
+
+    #[test]
+    fn test_format_parsing() {
+        assert_eq!("Mermaid".parse::<DiagramFormat>().unwrap(), DiagramFormat::Mermaid);
+        assert_eq!("svg".parse::<DiagramFormat>().unwrap(), DiagramFormat::Svg);
+        assert_eq!("graphviz".parse::<DiagramFormat>().unwrap(), DiagramFormat::Dot);
+        assert_eq!("md".parse::<DiagramFormat>().unwrap(), DiagramFormat::Markdown);
+        assert!("png".parse::<DiagramFormat>().is_err());
+    }
+}
@@ -0,0 +1,310 @@
+// Structured diff between two versions of a workflow
+//
+// A text diff of the YAML says what was typed; reviewers want to know what the
+// change does. Steps are matched by ID, so the diff lists added, removed,
+// reordered and changed steps, with each step's RAPS command line compared
+// rather than its YAML. Metadata and the remaining top-level sections
+// (parameters, profiles, cleanup, hooks) are compared field by field.
+
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+use std::path::Path;
+use std::process::Command;
+
+use super::client::RapsClient;
+use super::discovery::WorkflowDefinition;
+use super::types::{ExecutionStep, StepId};
+
+/// A field whose value differs between the versions (`None`: absent)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// How a step differs between the versions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepChange {
+    /// Only in the new version
+    Added { id: StepId, name: String, command: String },
+    /// Only in the old version
+    Removed { id: StepId, name: String },
+    /// In both, with different fields
+    Changed { id: StepId, fields: Vec<FieldChange> },
+    /// In both, at another position among the common steps (from 1)
+    Moved { id: StepId, from: usize, to: usize },
+}
+
+/// Differences between two versions of a workflow
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkflowDiff {
+    pub metadata: Vec<FieldChange>,
+    pub steps: Vec<StepChange>,
+    /// Other top-level sections: params, profiles, cleanup, hooks, ...
+    pub other: Vec<FieldChange>,
+}
+
+impl WorkflowDiff {
+    /// Compare `old` with `new`, rendering commands with `client`
+    pub fn between(old: &WorkflowDefinition, new: &WorkflowDefinition, client: &RapsClient) -> Self {
+        let mut diff = Self {
+            metadata: diff_fields(&to_object(&old.metadata), &to_object(&new.metadata), &[]),
+            ..Default::default()
+        };
+
+        let mut old_top = to_object(old);
+        let mut new_top = to_object(new);
+        for key in ["metadata", "steps"] {
+            old_top.remove(key);
+            new_top.remove(key);
+        }
+        diff.other = diff_fields(&old_top, &new_top, &[]);
+
+        let common_old: Vec<&StepId> =
+            old.steps.iter().map(|s| &s.id).filter(|id| new.steps.iter().any(|s| &s.id == *id)).collect();
+        let common_new: Vec<&StepId> =
+            new.steps.iter().map(|s| &s.id).filter(|id| old.steps.iter().any(|s| &s.id == *id)).collect();
+
+        for step in &old.steps {
+            if !new.steps.iter().any(|s| s.id == step.id) {
+                diff.steps.push(StepChange::Removed {
+                    id: step.id.clone(),
+                    name: step.name.clone(),
+                });
+            }
+        }
+        for (index, step) in new.steps.iter().enumerate() {
+            let Some(previous) = old.steps.iter().find(|s| s.id == step.id) else {
+                diff.steps.push(StepChange::Added {
+                    id: step.id.clone(),
+                    name: step.name.clone(),
+                    command: client.display_command_line(&step.command),
+                });
+                continue;
+            };
+            let from = common_old.iter().position(|id| **id == step.id).unwrap_or(index);
+            let to = common_new.iter().position(|id| **id == step.id).unwrap_or(index);
+            if from != to {
+                diff.steps.push(StepChange::Moved {
+                    id: step.id.clone(),
+                    from: from + 1,
+                    to: to + 1,
+                });
+            }
+            let fields = diff_step(client, previous, step);
+            if !fields.is_empty() {
+                diff.steps.push(StepChange::Changed {
+                    id: step.id.clone(),
+                    fields,
+                });
+            }
+        }
+        diff
+    }
+
+    /// Whether the versions behave the same
+    pub fn is_empty(&self) -> bool {
+        self.metadata.is_empty() && self.steps.is_empty() && self.other.is_empty()
+    }
+
+    /// Readable lines: `+` added, `-` removed, `~` changed, `>` moved
+    pub fn render(&self) -> Vec<String> {
+        if self.is_empty() {
+            return vec!["No changes".to_string()];
+        }
+        let mut lines = Vec::new();
+        if !self.metadata.is_empty() {
+            lines.push("Metadata".to_string());
+            lines.extend(self.metadata.iter().map(|c| format!("  {}", render_field(c))));
+        }
+        if !self.steps.is_empty() {
+            lines.push("Steps".to_string());
+            for change in &self.steps {
+                match change {
+                    StepChange::Added { id, name, command } => {
+                        lines.push(format!("  + {} ({})", id, name));
+                        lines.push(format!("      {}", command));
+                    },
+                    StepChange::Removed { id, name } => lines.push(format!("  - {} ({})", id, name)),
+                    StepChange::Moved { id, from, to } => lines.push(format!("  > {}: position {} -> {}", id, from, to)),
+                    StepChange::Changed { id, fields } => {
+                        lines.push(format!("  ~ {}", id));
+                        lines.extend(fields.iter().map(|c| format!("      {}", render_field(c))));
+                    },
+                }
+            }
+        }
+        if !self.other.is_empty() {
+            lines.push("Workflow".to_string());
+            lines.extend(self.other.iter().map(|c| format!("  {}", render_field(c))));
+        }
+        lines
+    }
+}
+
+/// Changed fields of a step; commands compare as RAPS command lines
+fn diff_step(client: &RapsClient, old: &ExecutionStep, new: &ExecutionStep) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    let (old_command, new_command) = (client.display_command_line(&old.command), client.display_command_line(&new.command));
+    if old_command != new_command {
+        changes.push(FieldChange {
+            field: "command".to_string(),
+            old: Some(old_command),
+            new: Some(new_command),
+        });
+    }
+    changes.extend(diff_fields(&to_object(old), &to_object(new), &["id", "command"]));
+    changes
+}
+
+/// Fields of two objects that differ, in key order, skipping `ignore`
+fn diff_fields(old: &Map<String, Value>, new: &Map<String, Value>, ignore: &[&str]) -> Vec<FieldChange> {
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).filter(|k| !ignore.contains(&k.as_str())).collect();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter_map(|key| {
+            let (before, after) = (old.get(key).filter(|v| !is_blank(v)), new.get(key).filter(|v| !is_blank(v)));
+            (before != after).then(|| FieldChange {
+                field: key.clone(),
+                old: before.map(show),
+                new: after.map(show),
+            })
+        })
+        .collect()
+}
+
+/// Empty values are the same as absent ones
+fn is_blank(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(items) => items.is_empty(),
+        Value::Object(map) => map.is_empty(),
+        _ => false,
+    }
+}
+
+fn show(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn to_object<T: serde::Serialize>(value: &T) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+fn render_field(change: &FieldChange) -> String {
+    match (&change.old, &change.new) {
+        (None, Some(new)) => format!("+ {}: {}", change.field, new),
+        (Some(old), None) => format!("- {}: {}", change.field, old),
+        (old, new) => format!(
+            "~ {}: {} -> {}",
+            change.field,
+            old.as_deref().unwrap_or_default(),
+            new.as_deref().unwrap_or_default()
+        ),
+    }
+}
+
+/// Load the version to compare against: `git:<rev>` for the workflow file as
+/// committed at that revision, otherwise a path to another workflow file
+pub fn load_version(against: &str, workflow_path: &Path) -> Result<WorkflowDefinition> {
+    let content = match against.strip_prefix("git:") {
+        Some(rev) => git_show(rev, workflow_path)?,
+        None => std::fs::read_to_string(against).with_context(|| format!("Failed to read workflow file: {}", against))?,
+    };
+    serde_yaml::from_str(&content).with_context(|| format!("Failed to parse workflow YAML from {}", against))
+}
+
+/// Contents of `path` at git revision `rev`
+fn git_show(rev: &str, path: &Path) -> Result<String> {
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let file = path.file_name().context("Workflow path has no file name")?;
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .arg("show")
+        .arg(format!("{}:./{}", rev, file.to_string_lossy()))
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git show {} failed: {}",
+            rev,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = r#"
+metadata:
+  id: "diff-demo"
+  name: "Diff Demo"
+  description: "Before"
+  category: "ObjectStorage"
+  estimated_duration: 60
+steps:
+  - id: "create"
+    name: "Create Bucket"
+    description: "Create it"
+    command: { type: "bucket", action: "create", bucket_name: "demo", retention_policy: "transient" }
+  - id: "status"
+    name: "Auth Status"
+    description: "Check auth"
+    command: { type: "auth", action: "status" }
+  - id: "old"
+    name: "Old Step"
+    description: "Going away"
+    command: { type: "auth", action: "refresh" }
+cleanup: []
+"#;
+
+    #[test]
+    fn test_structured_diff() {
+        let old: WorkflowDefinition = serde_yaml::from_str(OLD).unwrap();
+        let new_yaml = OLD
+            .replace("Before", "After")
+            .replace("transient", "temporary")
+            .replace(
+                "  - id: \"old\"\n    name: \"Old Step\"\n    description: \"Going away\"\n    command: { type: \"auth\", action: \"refresh\" }\n",
+                "",
+            )
+            .replace("steps:\n", "steps:\n  - id: \"login\"\n    name: \"Login\"\n    description: \"Log in\"\n    command: { type: \"auth\", action: \"login\" }\n");
+        let new: WorkflowDefinition = serde_yaml::from_str(&new_yaml).unwrap();
+
+        let client = RapsClient::new();
+        let diff = WorkflowDiff::between(&old, &new, &client);
+        assert_eq!(
+            diff.metadata,
+            vec![FieldChange {
+                field: "description".to_string(),
+                old: Some("Before".to_string()),
+                new: Some("After".to_string()),
+            }]
+        );
+        assert!(diff.other.is_empty());
+        assert!(matches!(&diff.steps[0], StepChange::Removed { id, .. } if id == "old"));
+        assert!(matches!(&diff.steps[1], StepChange::Added { id, command, .. } if id == "login" && command.starts_with("raps auth login")));
+        let StepChange::Changed { id, fields } = &diff.steps[2] else {
+            panic!("expected a changed step: {:?}", diff.steps);
+        };
+        assert_eq!(id, "create");
+        assert_eq!(fields.len(), 1);
+        assert!(fields[0].new.as_deref().unwrap().contains("--policy temporary"));
+
+        let rendered = diff.render().join("\n");
+        assert!(rendered.contains("  - old (Old Step)"), "{}", rendered);
+        assert!(WorkflowDiff::between(&old, &old, &client).is_empty());
+    }
+}
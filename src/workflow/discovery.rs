@@ -1,16 +1,29 @@
 // Workflow discovery and metadata parsing for RAPS Demo Workflows
 //
 // This module handles discovering workflow definition files, parsing their metadata,
-// and resolving dependencies between workflows.
+// and resolving dependencies between workflows. Discovery only parses the header
+// of each file (metadata, dependencies, categories); the steps are parsed from the
+// same source the first time the workflow is used, so listing a large pack
+// doesn't pay for the steps of workflows nobody opens.
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
+use rayon::prelude::*;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 use walkdir::WalkDir;
 
+use super::artifacts::is_relative_pattern;
+use super::builtin;
+use super::hooks::{HookAction, WorkflowHooks};
+use super::parse_cache::ParseCache;
+use super::placeholders;
+use super::policy::StepPolicy;
+use super::region::{self, Region};
 use super::types::*;
+use crate::error::{DiscoveryError, RapsDemoError};
 
 /// Workflow definition as stored in YAML files
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -25,6 +38,368 @@ pub struct WorkflowDefinition {
     /// Dependencies on other workflows (optional)
     #[serde(default)]
     pub dependencies: Option<Vec<WorkflowId>>,
+    /// Custom category declarations (optional)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<CategoryInfo>,
+    /// Lifecycle hooks (optional)
+    #[serde(default, skip_serializing_if = "WorkflowHooks::is_empty")]
+    pub hooks: WorkflowHooks,
+    /// RAPS profile (account) the steps run as, unless a step names its own;
+    /// not to be confused with `profiles:`, the step subsets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Named locks (`bucket:{params.bucket_key}`) held while the workflow
+    /// runs, so concurrent runs don't interfere on shared resources
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locks: Vec<String>,
+    /// Parameters and their defaults, used as `{params.<name>}`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub params: HashMap<String, String>,
+    /// Values recorded when the workflow succeeds (`urn: "{steps.upload.urn}"`),
+    /// which later runs of other workflows can consume
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub outputs: BTreeMap<String, String>,
+    /// Earlier runs (`upload:latest`) whose recorded outputs this workflow
+    /// takes as parameters of the same names
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub uses_outputs: Vec<String>,
+    /// Shared variables of the workflows directory (`vars.yaml`), used as
+    /// `{vars.<name>}`; set by discovery
+    #[serde(skip)]
+    pub vars: BTreeMap<String, String>,
+    /// Named step subsets (`quick: [login, upload]`, `full: all`), so one
+    /// workflow serves both a short teaser and the full walkthrough
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileSteps>,
+}
+
+/// Steps run by an execution profile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileSteps {
+    /// Every step (`all` in YAML)
+    All,
+    /// These steps, in workflow order
+    Only(Vec<StepId>),
+}
+
+impl ProfileSteps {
+    /// Whether the profile runs the step `step_id`
+    pub fn includes(&self, step_id: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Only(steps) => steps.iter().any(|id| id == step_id),
+        }
+    }
+}
+
+impl Serialize for ProfileSteps {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Self::All => serializer.serialize_str("all"),
+            Self::Only(steps) => steps.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ProfileSteps {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Keyword(String),
+            Steps(Vec<StepId>),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Keyword(keyword) if keyword == "all" => Ok(Self::All),
+            Raw::Keyword(other) => Err(serde::de::Error::custom(format!(
+                "invalid profile '{}': expected a list of step IDs or 'all'",
+                other
+            ))),
+            Raw::Steps(steps) => Ok(Self::Only(steps)),
+        }
+    }
+}
+
+impl WorkflowDefinition {
+    /// Assets needed by the workflow and any of its steps, without duplicates
+    pub fn required_assets(&self) -> Vec<AssetPath> {
+        let mut assets: Vec<AssetPath> = Vec::new();
+        let step_assets = self.steps.iter().flat_map(|step| &step.requires_assets);
+        for asset in self.metadata.required_assets.iter().chain(step_assets) {
+            if !assets.contains(asset) {
+                assets.push(asset.clone());
+            }
+        }
+        assets
+    }
+
+    /// IDs of the steps that declare `asset` in `requires_assets`
+    pub fn steps_requiring(&self, asset: &Path) -> Vec<&str> {
+        self.steps
+            .iter()
+            .filter(|step| step.requires_assets.iter().any(|a| a == asset))
+            .map(|step| step.id.as_str())
+            .collect()
+    }
+
+    /// Relative weight of each step in the overall progress: its expected
+    /// duration in seconds, or the average of the declared ones when unset
+    pub fn step_weights(&self) -> Vec<f64> {
+        let declared: Vec<f64> = self
+            .steps
+            .iter()
+            .filter_map(|step| step.expected_duration)
+            .map(|d| d.num_milliseconds().max(1000) as f64 / 1000.0)
+            .collect();
+        let fallback = if declared.is_empty() {
+            1.0
+        } else {
+            declared.iter().sum::<f64>() / declared.len() as f64
+        };
+        self.steps
+            .iter()
+            .map(|step| {
+                step.expected_duration
+                    .map_or(fallback, |d| d.num_milliseconds().max(1000) as f64 / 1000.0)
+            })
+            .collect()
+    }
+
+    /// Fraction of the workflow done once its first `completed` steps have
+    /// finished, weighted by [`step_weights`](Self::step_weights)
+    pub fn progress_fraction(&self, completed: usize) -> f32 {
+        let weights = self.step_weights();
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            return 1.0;
+        }
+        let done: f64 = weights.iter().take(completed).sum();
+        (done / total) as f32
+    }
+
+    /// Indices of the steps a run includes: those of `profile` (all without
+    /// one) from step `start_at` on
+    pub fn run_steps(&self, profile: Option<&str>, start_at: Option<&str>) -> Vec<usize> {
+        let start = start_at
+            .and_then(|id| self.steps.iter().position(|step| step.id == id))
+            .unwrap_or(0);
+        let profile = profile.and_then(|name| self.profiles.get(name));
+        (start..self.steps.len())
+            .filter(|&index| profile.map_or(true, |profile| profile.includes(&self.steps[index].id)))
+            .collect()
+    }
+
+    /// Fraction of a run done once the steps `done` have finished, weighted
+    /// by [`step_weights`](Self::step_weights) over the steps it runs (`runs`,
+    /// see [`run_steps`](Self::run_steps), less any it skipped)
+    pub fn run_progress_fraction(&self, runs: &[usize], done: &[usize]) -> f32 {
+        let weights = self.step_weights();
+        let total: f64 = runs.iter().filter_map(|&index| weights.get(index)).sum();
+        if total <= 0.0 {
+            return 1.0;
+        }
+        let done: f64 = done
+            .iter()
+            .filter(|index| runs.contains(index))
+            .filter_map(|&index| weights.get(index))
+            .sum();
+        (done / total) as f32
+    }
+
+    /// The workflow reduced to the steps of profile `name`
+    pub fn with_profile(&self, name: &str) -> Result<WorkflowDefinition, RapsDemoError> {
+        let profile = self.profiles.get(name).ok_or_else(|| DiscoveryError::UnknownProfile {
+            workflow: self.metadata.id.clone(),
+            profile: name.to_string(),
+            available: self.profiles.keys().cloned().collect(),
+        })?;
+        let mut workflow = self.clone();
+        workflow.steps.retain(|step| profile.includes(&step.id));
+        Ok(workflow)
+    }
+
+    /// `asset` followed by the steps that need it, for error messages
+    pub fn describe_asset(&self, asset: &Path) -> String {
+        let steps = self.steps_requiring(asset);
+        if steps.is_empty() {
+            asset.display().to_string()
+        } else {
+            format!("{} (needed by step {})", asset.display(), steps.join(", "))
+        }
+    }
+}
+
+/// The part of a workflow file read on discovery: enough to list the
+/// workflow and resolve its dependencies
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowHeader {
+    /// Metadata about the workflow
+    pub metadata: WorkflowMetadata,
+    /// Dependencies on other workflows (optional)
+    #[serde(default)]
+    pub dependencies: Option<Vec<WorkflowId>>,
+    /// Custom category declarations (optional)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<CategoryInfo>,
+}
+
+/// Where the YAML of a discovered workflow comes from
+#[derive(Debug, Clone)]
+enum WorkflowSource {
+    /// Already read (embedded, or read to parse the header)
+    Text(Arc<str>),
+    /// Not read yet: the header came from the parse cache
+    File(PathBuf),
+}
+
+impl From<Arc<str>> for WorkflowSource {
+    fn from(text: Arc<str>) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&'static str> for WorkflowSource {
+    fn from(text: &'static str) -> Self {
+        Self::Text(text.into())
+    }
+}
+
+impl From<String> for WorkflowSource {
+    fn from(text: String) -> Self {
+        Self::Text(text.into())
+    }
+}
+
+/// A discovered workflow: its header, and its full definition once parsed
+#[derive(Debug, Clone)]
+struct LazyWorkflow {
+    header: WorkflowHeader,
+    /// YAML the header was parsed from, and the steps will be
+    source: WorkflowSource,
+    /// Shared variables handed to the definition
+    vars: BTreeMap<String, String>,
+    /// The full definition, or why it doesn't parse
+    definition: OnceLock<Result<Arc<WorkflowDefinition>, String>>,
+}
+
+impl LazyWorkflow {
+    fn new(header: WorkflowHeader, source: WorkflowSource) -> Self {
+        Self {
+            header,
+            source,
+            vars: BTreeMap::new(),
+            definition: OnceLock::new(),
+        }
+    }
+
+    /// A workflow already parsed in full
+    fn parsed(definition: WorkflowDefinition, source: WorkflowSource) -> Self {
+        Self {
+            header: WorkflowHeader {
+                metadata: definition.metadata.clone(),
+                dependencies: definition.dependencies.clone(),
+                categories: definition.categories.clone(),
+            },
+            source,
+            vars: definition.vars.clone(),
+            definition: OnceLock::from(Ok(Arc::new(definition))),
+        }
+    }
+
+    /// The full definition, parsing the source on first use
+    fn definition(&self) -> Result<&Arc<WorkflowDefinition>, DiscoveryError> {
+        let path = &self.header.metadata.script_path;
+        self.definition
+            .get_or_init(|| {
+                tracing::debug!("Parsing steps of workflow {}", self.header.metadata.id);
+                let source: Arc<str> = match &self.source {
+                    WorkflowSource::Text(text) => Arc::clone(text),
+                    WorkflowSource::File(file) => fs::read_to_string(file)
+                        .map_err(|e| {
+                            tracing::error!("Failed to read workflow file: {}: {}", file.display(), e);
+                            e.to_string()
+                        })?
+                        .into(),
+                };
+                let mut definition: WorkflowDefinition = serde_yaml::from_str(&source).map_err(|e| {
+                    tracing::error!("Failed to parse workflow YAML: {}: {}", path.display(), e);
+                    e.to_string()
+                })?;
+                // Discovery may have adjusted the metadata (path, category)
+                definition.metadata = self.header.metadata.clone();
+                definition.vars = self.vars.clone();
+                Ok(Arc::new(definition))
+            })
+            .as_ref()
+            .map_err(|message| DiscoveryError::InvalidWorkflow {
+                path: path.clone(),
+                message: message.clone(),
+            })
+    }
+
+    fn set_vars(&mut self, vars: BTreeMap<String, String>) {
+        if let Some(Ok(definition)) = self.definition.get_mut() {
+            Arc::make_mut(definition).vars = vars.clone();
+        }
+        self.vars = vars;
+    }
+}
+
+/// Discovered workflows by ID, whose steps are parsed on first use; cloning
+/// only copies pointers, and clones share what was parsed
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveredWorkflows(HashMap<WorkflowId, Arc<LazyWorkflow>>);
+
+impl DiscoveredWorkflows {
+    /// The definition of workflow `id`, parsing its steps if not done yet;
+    /// `None` if there is no such workflow or its steps don't parse
+    pub fn get(&self, id: &str) -> Option<&Arc<WorkflowDefinition>> {
+        self.load(id).ok()
+    }
+
+    /// Like [`get`](Self::get), telling a missing workflow from one whose
+    /// steps don't parse
+    pub fn load(&self, id: &str) -> Result<&Arc<WorkflowDefinition>, RapsDemoError> {
+        let workflow = self.0.get(id).ok_or_else(|| DiscoveryError::WorkflowNotFound(id.to_string()))?;
+        Ok(workflow.definition()?)
+    }
+
+    /// Metadata of workflow `id`, without parsing its steps
+    pub fn metadata(&self, id: &str) -> Option<&WorkflowMetadata> {
+        self.0.get(id).map(|workflow| &workflow.header.metadata)
+    }
+
+    /// Whether the steps of workflow `id` have been parsed
+    pub fn is_parsed(&self, id: &str) -> bool {
+        self.0.get(id).is_some_and(|workflow| workflow.definition.get().is_some())
+    }
+
+    /// Whether there is a workflow `id`
+    pub fn contains_key(&self, id: &str) -> bool {
+        self.0.contains_key(id)
+    }
+
+    /// IDs of the workflows
+    pub fn keys(&self) -> impl Iterator<Item = &WorkflowId> + Clone {
+        self.0.keys()
+    }
+
+    /// Number of workflows
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether no workflow was discovered
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Every workflow whose steps parse, parsing all of them
+    pub fn iter(&self) -> impl Iterator<Item = (&WorkflowId, &Arc<WorkflowDefinition>)> {
+        self.0
+            .iter()
+            .filter_map(|(id, workflow)| Some((id, workflow.definition().ok()?)))
+    }
 }
 
 /// Result of workflow validation
@@ -66,74 +441,106 @@ impl ValidationResult {
 
 /// Workflow discovery and management
 pub struct WorkflowDiscovery {
-    /// Base directory for workflow definitions
-    workflows_dir: PathBuf,
-    /// Discovered workflows indexed by ID
-    workflows: HashMap<WorkflowId, WorkflowDefinition>,
+    /// Directories of workflow definitions, later ones overriding earlier ones
+    workflows_dirs: Vec<PathBuf>,
+    /// Discovered workflows indexed by ID, shared with the TUI and runs
+    workflows: DiscoveredWorkflows,
     /// Dependency graph for workflow resolution
     pub dependency_graph: HashMap<WorkflowId, Vec<WorkflowId>>,
+    /// Built-in and declared workflow categories
+    categories: CategoryRegistry,
+    /// Whether the embedded workflow pack is listed before the directories
+    include_builtin: bool,
+    /// Step policy workflows are validated against
+    policy: StepPolicy,
+    /// Whether undefined placeholders are errors rather than warnings
+    strict_placeholders: bool,
+    /// Environment whose `vars.<env>.yaml` overlays the shared variables
+    vars_env: Option<String>,
+    /// Shared variables given to every workflow
+    vars: BTreeMap<String, String>,
+    /// Region of steps that name none, for the region checks
+    region: Option<Region>,
+    /// Headers parsed before, reused while their files are unchanged
+    cache: ParseCache,
 }
 
 impl WorkflowDiscovery {
     /// Create a new workflow discovery instance
-    pub fn new<P: AsRef<Path>>(workflows_dir: P) -> Result<Self> {
-        let workflows_dir = workflows_dir.as_ref().to_path_buf();
+    pub fn new<P: AsRef<Path>>(workflows_dir: P) -> Result<Self, RapsDemoError> {
+        Self::with_dirs([workflows_dir.as_ref().to_path_buf()])
+    }
 
-        if !workflows_dir.exists() {
-            return Err(anyhow::anyhow!(
-                "Workflows directory does not exist: {}",
-                workflows_dir.display()
-            ));
+    /// Discover workflows in several directories (e.g. a built-in pack and a
+    /// user pack); a workflow in a later directory replaces one with the same
+    /// ID from an earlier directory
+    pub fn with_dirs<I: IntoIterator<Item = PathBuf>>(workflows_dirs: I) -> Result<Self, RapsDemoError> {
+        Self::with_parse_cache(workflows_dirs, ParseCache::default())
+    }
+
+    /// Like [`with_dirs`](Self::with_dirs), reusing the headers of `cache`
+    /// whose files haven't changed and saving it after each scan
+    pub fn with_parse_cache<I: IntoIterator<Item = PathBuf>>(
+        workflows_dirs: I,
+        cache: ParseCache,
+    ) -> Result<Self, RapsDemoError> {
+        Self::open(workflows_dirs, cache, false).map(|(discovery, _)| discovery)
+    }
+
+    /// Like [`with_parse_cache`](Self::with_parse_cache), also listing the
+    /// embedded pack if `include_builtin`, with the metadata of the
+    /// workflows found in discovery order, from a single scan
+    pub(crate) fn open<I: IntoIterator<Item = PathBuf>>(
+        workflows_dirs: I,
+        cache: ParseCache,
+        include_builtin: bool,
+    ) -> Result<(Self, Vec<WorkflowMetadata>), RapsDemoError> {
+        let workflows_dirs: Vec<PathBuf> = workflows_dirs.into_iter().collect();
+        if workflows_dirs.is_empty() {
+            return Err(DiscoveryError::NoDirectory.into());
+        }
+        for workflows_dir in &workflows_dirs {
+            if !workflows_dir.exists() {
+                return Err(DiscoveryError::MissingDirectory(workflows_dir.clone()).into());
+            }
         }
 
         let mut discovery = Self {
-            workflows_dir,
-            workflows: HashMap::new(),
+            workflows_dirs,
+            workflows: DiscoveredWorkflows::default(),
             dependency_graph: HashMap::new(),
+            categories: CategoryRegistry::default(),
+            include_builtin,
+            policy: StepPolicy::default(),
+            strict_placeholders: true,
+            vars_env: None,
+            vars: BTreeMap::new(),
+            region: None,
+            cache,
         };
 
-        discovery.discover_workflows()?;
+        let workflows = discovery.discover_workflows()?;
 
-        Ok(discovery)
+        Ok((discovery, workflows))
     }
 
     /// Discover all workflow definition files
-    pub fn discover_workflows(&mut self) -> Result<Vec<WorkflowMetadata>> {
-        tracing::info!("Discovering workflows in {}", self.workflows_dir.display());
-
-        self.workflows.clear();
+    pub fn discover_workflows(&mut self) -> Result<Vec<WorkflowMetadata>, RapsDemoError> {
+        self.workflows = DiscoveredWorkflows::default();
+        self.categories = CategoryRegistry::default();
+        self.load_vars();
         let mut discovered_metadata = Vec::new();
 
-        // Walk through the workflows directory looking for YAML files
-        for entry in WalkDir::new(&self.workflows_dir)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
+        if self.include_builtin {
+            self.discover_builtin(&mut discovered_metadata);
+        }
+        for workflows_dir in self.workflows_dirs.clone() {
+            tracing::info!("Discovering workflows in {}", workflows_dir.display());
+            self.discover_dir(&workflows_dir, &mut discovered_metadata);
+        }
 
-            // Only process YAML files
-            if path.is_file()
-                && (path.extension().map_or(false, |ext| ext == "yaml")
-                    || path.extension().map_or(false, |ext| ext == "yml"))
-            {
-                match self.load_workflow_definition(path) {
-                    Ok(mut definition) => {
-                        // Set the script path in metadata
-                        definition.metadata.script_path = path.to_path_buf();
-
-                        let workflow_id = definition.metadata.id.clone();
-                        tracing::debug!("Discovered workflow: {}", workflow_id);
-
-                        discovered_metadata.push(definition.metadata.clone());
-                        self.workflows.insert(workflow_id, definition);
-                    },
-                    Err(e) => {
-                        tracing::error!("Failed to load workflow from {}: {:?}", path.display(), e);
-                        eprintln!("ERROR loading workflow {}: {:?}", path.display(), e);
-                    },
-                }
-            }
+        if let Err(e) = self.cache.save() {
+            tracing::warn!("Failed to save parse cache: {:#}", e);
         }
 
         // Build dependency graph after all workflows are loaded
@@ -143,25 +550,234 @@ impl WorkflowDiscovery {
         Ok(discovered_metadata)
     }
 
-    /// Load and parse a workflow definition from a YAML file
-    fn load_workflow_definition<P: AsRef<Path>>(&self, path: P) -> Result<WorkflowDefinition> {
+    /// Load the workflows of one directory, replacing same-ID workflows found earlier
+    fn discover_dir(&mut self, workflows_dir: &Path, discovered_metadata: &mut Vec<WorkflowMetadata>) {
+        // Walk through the workflows directory looking for YAML files (tours
+        // are loaded separately), in name order so overrides don't depend on
+        // the filesystem
+        let paths: Vec<PathBuf> = WalkDir::new(workflows_dir)
+            .follow_links(true)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|entry| {
+                let path = entry.path();
+                entry.file_type().is_file()
+                    && path.extension().is_some_and(|ext| ext == "yaml" || ext == "yml")
+                    && !super::tour::is_tour_file(path)
+                    && !super::vars::is_vars_file(path)
+            })
+            .map(|entry| entry.into_path())
+            .collect();
+
+        // Files are read and parsed concurrently, which matters where each
+        // read is slow (network filesystems); results keep the walk order
+        let cache = &self.cache;
+        let loaded: Vec<_> = paths
+            .into_par_iter()
+            .map(|path| {
+                let result = Self::read_header(cache, &path);
+                (path, result)
+            })
+            .collect();
+
+        for (path, result) in loaded {
+            match result {
+                Ok((mut header, content)) => {
+                    let source = match content {
+                        Some(content) => {
+                            self.cache.insert(&path, &content, &header);
+                            WorkflowSource::from(content)
+                        },
+                        None => WorkflowSource::File(path.clone()),
+                    };
+                    // Set the script path in metadata
+                    header.metadata.script_path = path;
+                    self.add_discovered(LazyWorkflow::new(header, source), discovered_metadata);
+                },
+                Err(e) => {
+                    tracing::error!("Failed to load workflow from {}: {:?}", path.display(), e);
+                    eprintln!("ERROR loading workflow {}: {:?}", path.display(), e);
+                },
+            }
+        }
+    }
+
+    /// Load the embedded workflow pack under the "Built-in" category
+    fn discover_builtin(&mut self, discovered_metadata: &mut Vec<WorkflowMetadata>) {
+        self.categories.register(builtin::category_info());
+        for workflow in &builtin::BUILTIN_WORKFLOWS {
+            match serde_yaml::from_str::<WorkflowHeader>(workflow.source) {
+                Ok(mut header) => {
+                    header.metadata.script_path = workflow.script_path();
+                    header.metadata.category = WorkflowCategory::from_id(builtin::BUILTIN_CATEGORY);
+                    self.add_discovered(LazyWorkflow::new(header, workflow.source.into()), discovered_metadata);
+                },
+                Err(e) => tracing::error!("Failed to parse built-in workflow {}: {}", workflow.path, e),
+            }
+        }
+    }
+
+    /// Add a loaded workflow, replacing a same-ID workflow found earlier
+    fn add_discovered(&mut self, mut workflow: LazyWorkflow, discovered_metadata: &mut Vec<WorkflowMetadata>) {
+        let metadata = &workflow.header.metadata;
+        let workflow_id = metadata.id.clone();
+        workflow.vars = self.vars.clone();
+        tracing::debug!("Discovered workflow: {}", workflow_id);
+
+        for category in &workflow.header.categories {
+            self.categories.register(category.clone());
+        }
+        if let Some(replaced) = self.workflows.metadata(&workflow_id) {
+            tracing::info!(
+                "Workflow {} from {} overrides {}",
+                workflow_id,
+                metadata.script_path.display(),
+                replaced.script_path.display()
+            );
+            discovered_metadata.retain(|m| m.id != workflow_id);
+        }
+        discovered_metadata.push(metadata.clone());
+        self.workflows.0.insert(workflow_id, Arc::new(workflow));
+    }
+
+    /// Also list the embedded workflow pack (overridden by same-ID workflows
+    /// in the directories) and rediscover
+    pub fn include_builtin(&mut self) -> Result<Vec<WorkflowMetadata>, RapsDemoError> {
+        self.include_builtin = true;
+        self.discover_workflows()
+    }
+
+    /// Validate workflows against `policy`
+    pub fn set_policy(&mut self, policy: StepPolicy) {
+        self.policy = policy;
+    }
+
+    /// Report undefined placeholders as errors (`true`) or warnings
+    pub fn set_strict_placeholders(&mut self, strict: bool) {
+        self.strict_placeholders = strict;
+    }
+
+    /// Check workflows as if steps naming no region ran in `region`
+    pub fn set_region(&mut self, region: Option<Region>) {
+        self.region = region;
+    }
+
+    /// Overlay the shared variables with `vars.<env>.yaml` and hand them to
+    /// the loaded workflows
+    pub fn set_vars_env(&mut self, env: Option<String>) {
+        self.vars_env = env;
+        self.load_vars();
+        for workflow in self.workflows.0.values_mut() {
+            Arc::make_mut(workflow).set_vars(self.vars.clone());
+        }
+    }
+
+    /// Shared variables given to every workflow
+    pub fn vars(&self) -> &BTreeMap<String, String> {
+        &self.vars
+    }
+
+    /// Re-read the shared variables; unreadable files leave none
+    fn load_vars(&mut self) {
+        self.vars = super::vars::load_vars(&self.workflows_dirs, self.vars_env.as_deref()).unwrap_or_else(|e| {
+            tracing::error!("Ignoring shared variables: {:#}", e);
+            BTreeMap::new()
+        });
+    }
+
+    /// Load and parse a workflow definition from a YAML file, steps
+    /// included, with the file's content
+    fn load_workflow_definition<P: AsRef<Path>>(&mut self, path: P) -> Result<(WorkflowDefinition, String)> {
         let path = path.as_ref();
         let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read workflow file: {}", path.display()))?;
 
         let definition: WorkflowDefinition = serde_yaml::from_str(&content)
             .with_context(|| format!("Failed to parse workflow YAML: {}", path.display()))?;
+        let header = WorkflowHeader {
+            metadata: definition.metadata.clone(),
+            dependencies: definition.dependencies.clone(),
+            categories: definition.categories.clone(),
+        };
+        self.cache.insert(path, &content, &header);
 
-        Ok(definition)
+        Ok((definition, content))
+    }
+
+    /// The header of `path` from `cache` if the file is unchanged, else
+    /// parsed from the file, with the file's content when it was read (and
+    /// is worth caching again)
+    fn read_header(cache: &ParseCache, path: &Path) -> Result<(WorkflowHeader, Option<String>)> {
+        if let Some(header) = cache.get_unchanged(path) {
+            return Ok((header.clone(), None));
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read workflow file: {}", path.display()))?;
+        let header = match cache.get(path, &content) {
+            Some(header) => header.clone(),
+            None => serde_yaml::from_str(&content)
+                .with_context(|| format!("Failed to parse workflow YAML: {}", path.display()))?,
+        };
+
+        Ok((header, Some(content)))
+    }
+
+    /// Re-read a single workflow file after it was edited.
+    ///
+    /// The file is parsed and validated first, and the new set of workflows
+    /// and its dependency graph are built on a copy; the cached definitions
+    /// are only replaced when all of that succeeds, so a broken edit never
+    /// clobbers a working workflow. An edit taking the ID of another file's
+    /// workflow is reported as invalid.
+    pub fn reload_workflow<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<(WorkflowDefinition, ValidationResult), RapsDemoError> {
+        let path = path.as_ref();
+        let (mut definition, content) = self.load_workflow_definition(path).map_err(DiscoveryError::Other)?;
+        definition.metadata.script_path = path.to_path_buf();
+        definition.vars = self.vars.clone();
+
+        let mut result = self.validate_definition(&definition);
+        let id = &definition.metadata.id;
+        // Directories override the built-in pack, as on discovery
+        if let Some(existing) = self.workflows.metadata(id).filter(|existing| {
+            existing.script_path != path && !builtin::is_builtin(&existing.script_path)
+        }) {
+            result.is_valid = false;
+            result.errors.push(format!(
+                "Workflow ID '{}' is already used by {}",
+                id,
+                existing.script_path.display()
+            ));
+        }
+        if result.is_valid {
+            // Drop the old entry if the workflow ID was changed in the edit
+            let mut workflows = self.workflows.clone();
+            workflows.0.retain(|_, existing| existing.header.metadata.script_path != path);
+            workflows.0.insert(id.clone(), Arc::new(LazyWorkflow::parsed(definition.clone(), content.into())));
+            let dependency_graph = Self::dependency_graph_of(&workflows)?;
+
+            for category in &definition.categories {
+                self.categories.register(category.clone());
+            }
+            self.workflows = workflows;
+            self.dependency_graph = dependency_graph;
+        }
+
+        Ok((definition, result))
     }
 
     /// Validate a workflow definition
-    pub fn validate_workflow(&self, workflow_id: &WorkflowId) -> Result<ValidationResult> {
-        let workflow = self
-            .workflows
-            .get(workflow_id)
-            .ok_or_else(|| anyhow::anyhow!("Workflow not found: {}", workflow_id))?;
+    pub fn validate_workflow(&self, workflow_id: &WorkflowId) -> Result<ValidationResult, RapsDemoError> {
+        let workflow = self.workflows.load(workflow_id)?;
+
+        Ok(self.validate_definition(workflow))
+    }
 
+    /// Validate a workflow definition against the currently known workflows
+    fn validate_definition(&self, workflow: &WorkflowDefinition) -> ValidationResult {
         let mut errors = Vec::new();
         let mut warnings = Vec::new();
 
@@ -178,6 +794,18 @@ impl WorkflowDiscovery {
             warnings.push("Workflow description is empty".to_string());
         }
 
+        let category = &workflow.metadata.category;
+        let declared_here = workflow
+            .categories
+            .iter()
+            .any(|c| WorkflowCategory::from_id(&c.id) == *category);
+        if !declared_here && !self.categories.is_declared(category) {
+            warnings.push(format!(
+                "Category '{}' is not declared; default display settings will be used",
+                category.id()
+            ));
+        }
+
         // Validate steps
         if workflow.steps.is_empty() {
             errors.push("Workflow must have at least one step".to_string());
@@ -199,14 +827,77 @@ impl WorkflowDiscovery {
             if let Err(e) = self.validate_command(&step.command) {
                 errors.push(format!("Invalid command in step '{}': {}", step.id, e));
             }
+
+            for name in step.env.keys().chain(step.secrets.keys()) {
+                if name.is_empty() || name.contains('=') {
+                    errors.push(format!(
+                        "Invalid environment variable name in step '{}': '{}'",
+                        step.id, name
+                    ));
+                }
+            }
+
+            for pattern in &step.artifacts {
+                if !is_relative_pattern(pattern) {
+                    errors.push(format!(
+                        "Artifact pattern in step '{}' must be a relative path: '{}'",
+                        step.id, pattern
+                    ));
+                }
+            }
+
+            for hook in step.hooks.all() {
+                if let Err(e) = self.validate_hook(hook) {
+                    errors.push(format!("Invalid hook in step '{}': {}", step.id, e));
+                }
+            }
+        }
+
+        // Validate workflow hooks
+        for hook in workflow.hooks.all() {
+            if let Err(e) = self.validate_hook(hook) {
+                errors.push(format!("Invalid workflow hook: {}", e));
+            }
+        }
+
+        errors.extend(self.policy.violations(workflow).into_iter().map(|v| format!("Policy: {}", v)));
+
+        // Profiles name existing steps and run without the outputs of the steps they leave out
+        for (name, profile) in &workflow.profiles {
+            if let ProfileSteps::Only(steps) = profile {
+                for step_id in steps.iter().filter(|id| !workflow.steps.iter().any(|s| &s.id == *id)) {
+                    errors.push(format!("Profile '{}' names unknown step '{}'", name, step_id));
+                }
+            }
+            if let Ok(reduced) = workflow.with_profile(name) {
+                let (undefined, _) = placeholders::check_workflow(&reduced);
+                warnings.extend(undefined.into_iter().map(|e| format!("Profile '{}': {}", name, e)));
+            }
+        }
+
+        let (region_errors, region_warnings) = region::check_workflow(workflow, self.region);
+        errors.extend(region_errors);
+        warnings.extend(region_warnings);
+
+        let (undefined, bare) = placeholders::check_workflow(workflow);
+        if self.strict_placeholders {
+            errors.extend(undefined);
+        } else {
+            warnings.extend(undefined);
+        }
+        for name in bare {
+            warnings.push(format!(
+                "Placeholder {} is not defined before the run; use steps.<id>.<key> for step outputs",
+                name
+            ));
         }
 
         // Validate required assets exist
-        for asset_path in &workflow.metadata.required_assets {
+        for asset_path in workflow.required_assets() {
             if !asset_path.exists() {
                 warnings.push(format!(
                     "Required asset not found: {}",
-                    asset_path.display()
+                    workflow.describe_asset(&asset_path)
                 ));
             }
         }
@@ -226,26 +917,33 @@ impl WorkflowDiscovery {
             ValidationResult::with_errors(errors)
         };
 
-        Ok(result.with_warning(warnings.join("; ")))
+        result.with_warning(warnings.join("; "))
+    }
+
+    /// Validate a hook action
+    fn validate_hook(&self, hook: &HookAction) -> Result<()> {
+        match hook {
+            HookAction::Http(http)
+                if !(http.url.starts_with("http://") || http.url.starts_with("https://")) =>
+            {
+                Err(anyhow::anyhow!("Hook URL must start with http:// or https://: {}", http.url))
+            },
+            HookAction::Http(_) => Ok(()),
+            HookAction::Command(command) => self.validate_command(command),
+        }
     }
 
     /// Validate a RAPS command structure
     fn validate_command(&self, command: &RapsCommand) -> Result<()> {
         match command {
-            RapsCommand::Bucket { params, .. } => {
-                if params.bucket_name.is_none() {
-                    return Err(anyhow::anyhow!("Bucket command requires bucket_name"));
-                }
+            RapsCommand::Bucket { params, .. } if params.bucket_name.is_none() => {
+                return Err(anyhow::anyhow!("Bucket command requires bucket_name"));
             },
-            RapsCommand::Object { params, .. } => {
-                if params.bucket_name.is_empty() {
-                    return Err(anyhow::anyhow!("Object command requires bucket_name"));
-                }
+            RapsCommand::Object { params, .. } if params.bucket_name.is_empty() => {
+                return Err(anyhow::anyhow!("Object command requires bucket_name"));
             },
-            RapsCommand::Custom { command, .. } => {
-                if command.is_empty() {
-                    return Err(anyhow::anyhow!("Custom command cannot be empty"));
-                }
+            RapsCommand::Custom { command, .. } if command.is_empty() => {
+                return Err(anyhow::anyhow!("Custom command cannot be empty"));
             },
             _ => {}, // Other commands are valid by structure
         }
@@ -253,31 +951,34 @@ impl WorkflowDiscovery {
     }
 
     /// Build dependency graph for workflow resolution
-    fn build_dependency_graph(&mut self) -> Result<()> {
-        self.dependency_graph.clear();
+    fn build_dependency_graph(&mut self) -> Result<(), DiscoveryError> {
+        self.dependency_graph = Self::dependency_graph_of(&self.workflows)?;
+        Ok(())
+    }
 
-        for (workflow_id, definition) in &self.workflows {
-            let dependencies = definition.dependencies.clone().unwrap_or_default();
-            self.dependency_graph
-                .insert(workflow_id.clone(), dependencies);
+    /// Dependency graph of `workflows`, unless it has a cycle
+    fn dependency_graph_of(
+        workflows: &DiscoveredWorkflows,
+    ) -> Result<HashMap<WorkflowId, Vec<WorkflowId>>, DiscoveryError> {
+        let mut dependency_graph = HashMap::new();
+        for (workflow_id, workflow) in &workflows.0 {
+            let dependencies = workflow.header.dependencies.clone().unwrap_or_default();
+            dependency_graph.insert(workflow_id.clone(), dependencies);
         }
 
         // Validate no circular dependencies
-        for workflow_id in self.workflows.keys() {
-            if self.has_circular_dependency(workflow_id, &mut HashSet::new())? {
-                return Err(anyhow::anyhow!(
-                    "Circular dependency detected involving workflow: {}",
-                    workflow_id
-                ));
+        for workflow_id in workflows.keys() {
+            if Self::has_circular_dependency(&dependency_graph, workflow_id, &mut HashSet::new())? {
+                return Err(DiscoveryError::CircularDependency(workflow_id.clone()));
             }
         }
 
-        Ok(())
+        Ok(dependency_graph)
     }
 
     /// Check for circular dependencies using DFS
     fn has_circular_dependency(
-        &self,
+        dependency_graph: &HashMap<WorkflowId, Vec<WorkflowId>>,
         workflow_id: &WorkflowId,
         visited: &mut HashSet<WorkflowId>,
     ) -> Result<bool> {
@@ -287,9 +988,9 @@ impl WorkflowDiscovery {
 
         visited.insert(workflow_id.clone());
 
-        if let Some(dependencies) = self.dependency_graph.get(workflow_id) {
+        if let Some(dependencies) = dependency_graph.get(workflow_id) {
             for dep_id in dependencies {
-                if self.has_circular_dependency(dep_id, visited)? {
+                if Self::has_circular_dependency(dependency_graph, dep_id, visited)? {
                     return Ok(true);
                 }
             }
@@ -300,11 +1001,12 @@ impl WorkflowDiscovery {
     }
 
     /// Get workflow dependencies in execution order
-    pub fn get_workflow_dependencies(&self, workflow_id: &WorkflowId) -> Result<Vec<WorkflowId>> {
+    pub fn get_workflow_dependencies(&self, workflow_id: &WorkflowId) -> Result<Vec<WorkflowId>, RapsDemoError> {
         let mut resolved = Vec::new();
         let mut visited = HashSet::new();
 
-        self.resolve_dependencies_recursive(workflow_id, &mut resolved, &mut visited)?;
+        self.resolve_dependencies_recursive(workflow_id, &mut resolved, &mut visited)
+            .map_err(DiscoveryError::Other)?;
 
         Ok(resolved)
     }
@@ -334,14 +1036,24 @@ impl WorkflowDiscovery {
         Ok(())
     }
 
-    /// Get all discovered workflows
-    pub fn get_workflows(&self) -> &HashMap<WorkflowId, WorkflowDefinition> {
+    /// Directories the workflows are discovered in
+    pub fn workflows_dirs(&self) -> &[PathBuf] {
+        &self.workflows_dirs
+    }
+
+    /// Get all discovered workflows; cloning them only copies pointers
+    pub fn get_workflows(&self) -> &DiscoveredWorkflows {
         &self.workflows
     }
 
     /// Get a specific workflow definition
     pub fn get_workflow(&self, workflow_id: &WorkflowId) -> Option<&WorkflowDefinition> {
-        self.workflows.get(workflow_id)
+        self.workflows.get(workflow_id).map(Arc::as_ref)
+    }
+
+    /// Get a specific workflow definition, shared rather than copied
+    pub fn get_shared_workflow(&self, workflow_id: &WorkflowId) -> Option<Arc<WorkflowDefinition>> {
+        self.workflows.get(workflow_id).cloned()
     }
 
     /// Get workflows by category
@@ -350,13 +1062,21 @@ impl WorkflowDiscovery {
         category: &WorkflowCategory,
     ) -> Vec<&WorkflowDefinition> {
         self.workflows
+            .0
             .values()
-            .filter(|w| w.metadata.category == *category)
+            .filter(|w| w.header.metadata.category == *category)
+            .filter_map(|w| w.definition().ok())
+            .map(Arc::as_ref)
             .collect()
     }
 
+    /// Built-in and declared workflow categories
+    pub fn categories(&self) -> &CategoryRegistry {
+        &self.categories
+    }
+
     /// Refresh workflow discovery (re-scan directory)
-    pub fn refresh(&mut self) -> Result<Vec<WorkflowMetadata>> {
+    pub fn refresh(&mut self) -> Result<Vec<WorkflowMetadata>, RapsDemoError> {
         self.discover_workflows()
     }
 }
@@ -451,6 +1171,48 @@ cleanup:
         assert!(result.errors.is_empty());
     }
 
+    #[test]
+    fn test_profiles() {
+        let yaml = create_test_workflow_yaml().replace(
+            "\ncleanup:\n",
+            r#"  - id: "step2"
+    name: "Create Second Bucket"
+    description: "Create another bucket"
+    command:
+      type: "bucket"
+      action: "create"
+      bucket_name: "second-bucket"
+    expected_duration: 10
+
+profiles:
+  quick: [step2]
+  full: all
+  broken: [step2, missing]
+
+cleanup:
+"#,
+        );
+        let definition: WorkflowDefinition = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(definition.profiles["full"], ProfileSteps::All);
+
+        let quick = definition.with_profile("quick").unwrap();
+        assert_eq!(quick.steps.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), vec!["step2"]);
+        assert_eq!(definition.with_profile("full").unwrap().steps.len(), 2);
+        let err = definition.with_profile("teaser").unwrap_err().to_string();
+        assert!(err.contains("available: broken, full, quick"), "{}", err);
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("test-workflow.yaml"), &yaml).unwrap();
+        let mut discovery = WorkflowDiscovery::new(temp_dir.path()).unwrap();
+        discovery.discover_workflows().unwrap();
+        let result = discovery.validate_workflow(&"test-workflow".to_string()).unwrap();
+        assert_eq!(result.errors, vec!["Profile 'broken' names unknown step 'missing'"]);
+
+        // Anything but a step list or `all` is rejected
+        let invalid = yaml.replace("full: all", "full: everything");
+        assert!(serde_yaml::from_str::<WorkflowDefinition>(&invalid).is_err());
+    }
+
     #[test]
     fn test_invalid_workflow_validation() {
         let invalid_yaml = r#"
@@ -483,6 +1245,54 @@ cleanup: []
         assert!(!result.errors.is_empty());
     }
 
+    #[test]
+    fn test_step_required_assets() {
+        let yaml = create_test_workflow_yaml()
+            .replace("required_assets: []", "required_assets: [\"missing/model.rvt\"]")
+            .replace(
+                "    cleanup_commands: []",
+                "    cleanup_commands: []\n    requires_assets: [\"missing/model.rvt\", \"missing/drawing.dwg\"]",
+            );
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("test-workflow.yaml"), yaml).unwrap();
+
+        let mut discovery = WorkflowDiscovery::new(temp_dir.path()).unwrap();
+        discovery.discover_workflows().unwrap();
+        let definition = discovery.get_workflows().get("test-workflow").unwrap();
+        assert_eq!(
+            definition.required_assets(),
+            vec![PathBuf::from("missing/model.rvt"), PathBuf::from("missing/drawing.dwg")]
+        );
+        assert_eq!(definition.steps_requiring(Path::new("missing/drawing.dwg")), vec!["step1"]);
+
+        // Missing files are reported with the steps that need them
+        let result = discovery.validate_workflow(&"test-workflow".to_string()).unwrap();
+        assert!(result.warnings.join("; ").contains("Required asset not found: missing/drawing.dwg (needed by step step1)"));
+    }
+
+    #[test]
+    fn test_progress_weighted_by_step_duration() {
+        let yaml = create_test_workflow_yaml().replace(
+            "cleanup:\n",
+            "  - id: \"translate\"\n    name: \"Translate\"\n    description: \"Translate a model\"\n    command:\n      type: \"auth\"\n      action: \"status\"\n    estimated_duration: 270\n  - id: \"status\"\n    name: \"Status\"\n    description: \"No estimate\"\n    command:\n      type: \"auth\"\n      action: \"status\"\n\ncleanup:\n",
+        );
+        let definition: WorkflowDefinition = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(definition.steps[1].expected_duration, Some(chrono::Duration::seconds(270)));
+
+        // 30s and 270s declared, the undeclared step weighs their average
+        assert_eq!(definition.step_weights(), vec![30.0, 270.0, 150.0]);
+        assert!((definition.progress_fraction(1) - 30.0 / 450.0).abs() < 1e-6);
+        assert!((definition.progress_fraction(2) - 300.0 / 450.0).abs() < 1e-6);
+        assert_eq!(definition.progress_fraction(3), 1.0);
+
+        // Started at the second step: the first one doesn't count
+        let runs = definition.run_steps(None, Some("translate"));
+        assert_eq!(runs, vec![1, 2]);
+        assert!((definition.run_progress_fraction(&runs, &[1]) - 270.0 / 420.0).abs() < 1e-6);
+        // The last step skipped: the run is done after the second
+        assert_eq!(definition.run_progress_fraction(&[1], &[1]), 1.0);
+    }
+
     #[test]
     fn test_dependency_resolution() {
         let temp_dir = TempDir::new().unwrap();
@@ -540,7 +1350,7 @@ cleanup: []
         fs::write(temp_dir.path().join("workflow-a.yaml"), workflow_a).unwrap();
         fs::write(temp_dir.path().join("workflow-b.yaml"), workflow_b).unwrap();
 
-        let mut discovery = WorkflowDiscovery::new(temp_dir.path()).unwrap();
+        let discovery = WorkflowDiscovery::new(temp_dir.path()).unwrap();
         let deps = discovery
             .get_workflow_dependencies(&"workflow-a".to_string())
             .unwrap();
@@ -551,4 +1361,220 @@ cleanup: []
             vec!["workflow-b".to_string(), "workflow-a".to_string()]
         );
     }
+
+    #[test]
+    fn test_later_directory_overrides_workflow() {
+        let builtin = TempDir::new().unwrap();
+        let user = TempDir::new().unwrap();
+        fs::write(builtin.path().join("test.yaml"), create_test_workflow_yaml()).unwrap();
+        fs::write(
+            user.path().join("custom.yaml"),
+            create_test_workflow_yaml().replace("Test Workflow", "Customized Workflow"),
+        )
+        .unwrap();
+
+        let discovery =
+            WorkflowDiscovery::with_dirs([builtin.path().to_path_buf(), user.path().to_path_buf()]).unwrap();
+        let workflow = discovery.get_workflow(&"test-workflow".to_string()).unwrap();
+        assert_eq!(workflow.metadata.name, "Customized Workflow");
+        assert_eq!(discovery.get_workflows().len(), 1);
+
+        let missing = builtin.path().join("missing");
+        assert!(WorkflowDiscovery::with_dirs([builtin.path().to_path_buf(), missing]).is_err());
+    }
+
+    #[test]
+    fn test_parallel_discovery_keeps_name_order() {
+        let temp_dir = TempDir::new().unwrap();
+        for i in (0..40).rev() {
+            let dir = temp_dir.path().join(format!("group-{}", i % 3));
+            fs::create_dir_all(&dir).unwrap();
+            let yaml = create_test_workflow_yaml().replace("test-workflow", &format!("workflow-{:02}", i));
+            fs::write(dir.join(format!("w{:02}.yaml", i)), yaml).unwrap();
+        }
+        // Same ID twice in one directory: the later file by name wins
+        fs::write(temp_dir.path().join("group-0").join("z.yaml"), create_test_workflow_yaml().replace("test-workflow", "workflow-00").replace("Test Workflow", "Last")).unwrap();
+
+        let mut discovery = WorkflowDiscovery::new(temp_dir.path()).unwrap();
+        let ids: Vec<String> = discovery.discover_workflows().unwrap().into_iter().map(|m| m.id).collect();
+        // Group by group, files by name; z.yaml comes last in group-0 and
+        // moves workflow-00 there
+        let group = |g: usize| (g..40).step_by(3).map(|i| format!("workflow-{:02}", i));
+        let mut expected: Vec<String> = group(0).skip(1).collect();
+        expected.push("workflow-00".to_string());
+        expected.extend(group(1));
+        expected.extend(group(2));
+        assert_eq!(ids, expected);
+        assert_eq!(discovery.get_workflow(&"workflow-00".to_string()).unwrap().metadata.name, "Last");
+    }
+
+    #[test]
+    fn test_shared_vars_reach_workflows() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("vars.yaml"), "bucket_prefix: demo\n").unwrap();
+        fs::write(temp_dir.path().join("vars.emea.yaml"), "bucket_prefix: demo-emea\n").unwrap();
+        fs::write(
+            temp_dir.path().join("test.yaml"),
+            create_test_workflow_yaml().replace("\"test-bucket\"", "\"{vars.bucket_prefix}-bucket\""),
+        )
+        .unwrap();
+
+        // The variables files are not taken for workflows
+        let mut discovery = WorkflowDiscovery::new(temp_dir.path()).unwrap();
+        assert_eq!(discovery.get_workflows().len(), 1);
+        let id = "test-workflow".to_string();
+        assert_eq!(discovery.get_workflow(&id).unwrap().vars["bucket_prefix"], "demo");
+        assert!(discovery.validate_workflow(&id).unwrap().is_valid);
+
+        discovery.set_vars_env(Some("emea".to_string()));
+        assert_eq!(discovery.get_workflow(&id).unwrap().vars["bucket_prefix"], "demo-emea");
+
+        fs::write(temp_dir.path().join("vars.yaml"), "region: US\n").unwrap();
+        fs::write(temp_dir.path().join("vars.emea.yaml"), "").unwrap();
+        discovery.refresh().unwrap();
+        let result = discovery.validate_workflow(&id).unwrap();
+        assert!(!result.is_valid);
+        assert!(result.errors.iter().any(|e| e.contains("vars.bucket_prefix")), "{:?}", result.errors);
+    }
+
+    #[test]
+    fn test_definitions_are_shared_not_copied() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("test.yaml"), create_test_workflow_yaml()).unwrap();
+        fs::write(temp_dir.path().join("vars.emea.yaml"), "bucket_prefix: demo-emea\n").unwrap();
+        let mut discovery = WorkflowDiscovery::new(temp_dir.path()).unwrap();
+        let id = "test-workflow".to_string();
+
+        // Handing out the definitions copies pointers, not step lists
+        let shown = discovery.get_workflows().clone();
+        let running = discovery.get_shared_workflow(&id).unwrap();
+        assert!(Arc::ptr_eq(shown.get(&id).unwrap(), &running));
+
+        // Changing a definition in use leaves the copies handed out intact
+        discovery.set_vars_env(Some("emea".to_string()));
+        assert_eq!(discovery.get_workflow(&id).unwrap().vars["bucket_prefix"], "demo-emea");
+        assert!(running.vars.is_empty());
+    }
+
+    #[test]
+    fn test_steps_are_parsed_on_first_use() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test.yaml");
+        fs::write(&path, create_test_workflow_yaml()).unwrap();
+        // The header is fine, the step is not
+        let broken = create_test_workflow_yaml()
+            .replace("id: \"test-workflow\"", "id: \"broken-workflow\"")
+            .replace("action: \"create\"", "action: \"explode\"");
+        fs::write(temp_dir.path().join("broken.yaml"), broken).unwrap();
+
+        let discovery = WorkflowDiscovery::new(temp_dir.path()).unwrap();
+        let shown = discovery.get_workflows().clone();
+        assert_eq!(shown.len(), 2);
+        assert_eq!(shown.metadata("test-workflow").unwrap().script_path, path);
+        assert!(!shown.is_parsed("test-workflow"));
+
+        // Parsed once, for every copy of the workflows
+        let definition = shown.get("test-workflow").unwrap();
+        assert_eq!(definition.steps.len(), 1);
+        assert_eq!(definition.metadata.script_path, path);
+        assert!(discovery.get_workflows().is_parsed("test-workflow"));
+        let id = "test-workflow".to_string();
+        assert!(Arc::ptr_eq(definition, &discovery.get_shared_workflow(&id).unwrap()));
+
+        // Steps that don't parse fail validation rather than discovery
+        let id = "broken-workflow".to_string();
+        assert!(discovery.get_workflow(&id).is_none());
+        let error = discovery.validate_workflow(&id).unwrap_err();
+        assert!(error.to_string().contains("Failed to parse workflow YAML"), "{}", error);
+    }
+
+    #[test]
+    fn test_reload_workflow() {
+        let temp_dir = TempDir::new().unwrap();
+        let workflow_file = temp_dir.path().join("test-workflow.yaml");
+        fs::write(&workflow_file, create_test_workflow_yaml()).unwrap();
+
+        let mut discovery = WorkflowDiscovery::new(temp_dir.path()).unwrap();
+
+        // A valid edit replaces the cached definition
+        let edited = create_test_workflow_yaml().replace("Create Bucket", "Make Bucket");
+        fs::write(&workflow_file, edited).unwrap();
+        let (definition, result) = discovery.reload_workflow(&workflow_file).unwrap();
+        assert!(result.is_valid);
+        assert_eq!(definition.steps[0].name, "Make Bucket");
+        assert_eq!(
+            discovery.get_workflow(&"test-workflow".to_string()).unwrap().steps[0].name,
+            "Make Bucket"
+        );
+
+        // An invalid edit is reported and leaves the cache untouched
+        let broken = create_test_workflow_yaml().replace("name: \"Test Workflow\"", "name: \"\"");
+        fs::write(&workflow_file, broken).unwrap();
+        let (_, result) = discovery.reload_workflow(&workflow_file).unwrap();
+        assert!(!result.is_valid);
+        assert_eq!(
+            discovery.get_workflow(&"test-workflow".to_string()).unwrap().metadata.name,
+            "Test Workflow"
+        );
+
+        // Another file can't take over the ID of this one
+        let other_file = temp_dir.path().join("other.yaml");
+        fs::write(&other_file, create_test_workflow_yaml().replace("Create Bucket", "Stolen")).unwrap();
+        let (_, result) = discovery.reload_workflow(&other_file).unwrap();
+        assert!(!result.is_valid);
+        assert!(result.errors[0].contains("already used by"), "{:?}", result.errors);
+        assert_eq!(discovery.get_workflow(&"test-workflow".to_string()).unwrap().steps[0].name, "Make Bucket");
+
+        // A cycle is an error and leaves the cached workflows as they were
+        fs::write(&workflow_file, create_test_workflow_yaml() + "dependencies: [\"test-workflow\"]\n").unwrap();
+        assert!(discovery.reload_workflow(&workflow_file).is_err());
+        assert!(discovery.get_workflow(&"test-workflow".to_string()).unwrap().dependencies.is_none());
+    }
+
+    #[test]
+    fn test_custom_category_declaration() {
+        let temp_dir = TempDir::new().unwrap();
+        let yaml = create_test_workflow_yaml().replace(
+            "category: \"ObjectStorage\"",
+            "category: \"tandem\"",
+        ) + r#"
+categories:
+  - id: "tandem"
+    display_name: "Tandem Digital Twins"
+    icon: "[TDM]"
+    sort_order: 5
+    color: "magenta"
+"#;
+        fs::write(temp_dir.path().join("tandem.yaml"), yaml).unwrap();
+
+        let discovery = WorkflowDiscovery::new(temp_dir.path()).unwrap();
+        let definition = discovery.get_workflow(&"test-workflow".to_string()).unwrap();
+        let category = &definition.metadata.category;
+        assert_eq!(*category, WorkflowCategory::Custom("tandem".to_string()));
+
+        let info = discovery.categories().info(category);
+        assert_eq!(info.display_name, "Tandem Digital Twins");
+        assert_eq!(info.icon.as_deref(), Some("[TDM]"));
+        assert_eq!(info.sort_order, 5);
+
+        let validation = discovery.validate_workflow(&"test-workflow".to_string()).unwrap();
+        assert!(validation.is_valid);
+        assert!(!validation.warnings.iter().any(|w| w.contains("not declared")));
+    }
+
+    #[test]
+    fn test_category_aliases_and_undeclared_custom() {
+        assert_eq!(WorkflowCategory::from_id("oss"), WorkflowCategory::ObjectStorage);
+        assert_eq!(WorkflowCategory::from_id("Model_Derivative"), WorkflowCategory::ModelDerivative);
+        assert_eq!(WorkflowCategory::from_id("E2E"), WorkflowCategory::EndToEnd);
+
+        let custom = WorkflowCategory::from_id("iot-sensors");
+        assert_eq!(custom.id(), "iot-sensors");
+        let info = CategoryRegistry::default().info(&custom);
+        assert_eq!(info.icon.as_deref(), Some("[IOT]"));
+        assert_eq!(info.sort_order, CategoryInfo::DEFAULT_SORT_ORDER);
+
+        let yaml = serde_yaml::to_string(&WorkflowCategory::DesignAutomation).unwrap();
+        assert_eq!(yaml.trim(), "design-automation");
+    }
 }
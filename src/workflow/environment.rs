@@ -0,0 +1,130 @@
+// Environment passed to RAPS CLI subprocesses
+//
+// A presenter's shell tends to carry credentials that have nothing to do with
+// the demo: cloud provider keys, GitHub tokens, database passwords. Instead
+// of inheriting all of it, RAPS commands only see inherited variables whose
+// names match the allowlist (and none of the denylist). Variables the demo
+// sets itself (client environment, RAPS profiles, step `env:` and secrets)
+// are always passed. `RAPS_SECRET_*` variables, where the demo's own secrets
+// live, are never inherited: a step only sees the secrets it declares. In verbose mode the variables passed to each command are
+// logged, with secret-looking values redacted.
+
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+
+use crate::config::secrets::SECRET_ENV_PREFIX;
+use crate::utils::redact::is_secret_key;
+
+/// Inherited variables passed by default: what a CLI needs to find its
+/// files, talk through a proxy and print in the right language, plus APS and
+/// RAPS settings
+const DEFAULT_ALLOW: &[&str] = &[
+    "PATH", "HOME", "USER", "USERNAME", "LOGNAME", "SHELL", "LANG", "LANGUAGE", "LC_*", "TZ", "TERM",
+    "COLORTERM", "NO_COLOR", "TMPDIR", "TEMP", "TMP", "XDG_*", "HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY",
+    "NO_PROXY", "SSL_CERT_FILE", "SSL_CERT_DIR", "RUST_LOG", "RUST_BACKTRACE", "APS_*", "RAPS_*",
+    "SYSTEMROOT", "SYSTEMDRIVE", "WINDIR", "COMSPEC", "PATHEXT", "APPDATA", "LOCALAPPDATA", "USERPROFILE",
+    "HOMEDRIVE", "HOMEPATH", "PROGRAMDATA", "PROGRAMFILES*",
+];
+
+/// Which inherited variables reach RAPS commands (`[subprocess_env]` in `demo.toml`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvironmentFilter {
+    /// Pass the whole parent environment (minus `deny`) instead of `allow`
+    pub inherit_all: bool,
+    /// Names (glob patterns such as `AWS_*`, case-insensitive) to pass on
+    pub allow: Vec<String>,
+    /// Names never passed on, even when allowed
+    pub deny: Vec<String>,
+}
+
+impl Default for EnvironmentFilter {
+    fn default() -> Self {
+        Self {
+            inherit_all: false,
+            allow: DEFAULT_ALLOW.iter().map(|name| name.to_string()).collect(),
+            deny: Vec::new(),
+        }
+    }
+}
+
+impl EnvironmentFilter {
+    /// Whether an inherited variable named `name` is passed on; demo secrets
+    /// never are, whatever the lists say
+    pub fn passes(&self, name: &str) -> bool {
+        let is_demo_secret = name
+            .get(..SECRET_ENV_PREFIX.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(SECRET_ENV_PREFIX));
+        !is_demo_secret
+            && (self.inherit_all || matches_any(&self.allow, name)) && !matches_any(&self.deny, name)
+    }
+
+    /// Inherited variables that are passed on
+    pub fn inherited<I>(&self, vars: I) -> Vec<(String, String)>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        vars.into_iter().filter(|(name, _)| self.passes(name)).collect()
+    }
+}
+
+fn matches_any(patterns: &[String], name: &str) -> bool {
+    let options = MatchOptions {
+        case_sensitive: false,
+        ..MatchOptions::new()
+    };
+    patterns.iter().any(|pattern| match Pattern::new(pattern) {
+        Ok(pattern) => pattern.matches_with(name, options),
+        Err(_) => pattern.eq_ignore_ascii_case(name),
+    })
+}
+
+/// `NAME=value` list for the log, values of secret-looking names redacted
+pub fn describe(vars: &[(String, String)]) -> String {
+    let mut entries: Vec<String> = vars
+        .iter()
+        .map(|(name, value)| {
+            let lower = name.to_lowercase();
+            if is_secret_key(&lower) || lower.ends_with("key") {
+                format!("{}=[REDACTED]", name)
+            } else {
+                format!("{}={}", name, value)
+            }
+        })
+        .collect();
+    entries.sort();
+    entries.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_environment_filter() {
+        let vars = |names: &[&str]| names.iter().map(|n| (n.to_string(), "x".to_string())).collect::<Vec<_>>();
+        let filter = EnvironmentFilter::default();
+        let passed = filter.inherited(vars(&["PATH", "APS_CLIENT_ID", "lc_all", "AWS_SECRET_ACCESS_KEY", "GITHUB_TOKEN"]));
+        let names: Vec<&str> = passed.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["PATH", "APS_CLIENT_ID", "lc_all"]);
+
+        let filter = EnvironmentFilter {
+            inherit_all: true,
+            deny: vec!["AWS_*".to_string()],
+            ..EnvironmentFilter::default()
+        };
+        assert!(filter.passes("GITHUB_TOKEN"));
+        assert!(!filter.passes("aws_secret_access_key"));
+
+        // Demo secrets are only passed when a step declares them
+        assert!(filter.passes("RAPS_PROFILE"));
+        assert!(!filter.passes("RAPS_SECRET_DA_TOKEN"));
+        assert!(!EnvironmentFilter::default().passes("raps_secret_da_token"));
+
+        let log = describe(&[
+            ("APS_CLIENT_SECRET".to_string(), "hunter2".to_string()),
+            ("APS_CLIENT_ID".to_string(), "abc".to_string()),
+        ]);
+        assert_eq!(log, "APS_CLIENT_ID=abc APS_CLIENT_SECRET=[REDACTED]");
+    }
+}
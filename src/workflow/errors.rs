@@ -0,0 +1,308 @@
+// Structured classification of RAPS CLI failures
+//
+// RAPS reports API errors as JSON (`{"error": {"code": "...", "status": 409,
+// "message": "..."}}`) when it can. `ApsErrorKind` is parsed from that output,
+// falling back to HTTP status codes and well-known phrases in plain stderr, so
+// recovery suggestions and retry decisions no longer depend on ad-hoc string
+// matching at each call site.
+
+use serde_json::Value;
+use std::fmt;
+
+use super::client::CommandResult;
+use super::types::RapsCommand;
+
+/// Kind of APS failure behind a failed RAPS command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApsErrorKind {
+    /// Missing, invalid or expired credentials, or insufficient scopes
+    Auth,
+    /// Account or storage quota exceeded
+    Quota,
+    /// The resource already exists (e.g. a bucket name is taken)
+    Conflict,
+    /// The bucket, object, URN or item does not exist
+    NotFound,
+    /// Connection problems, timeouts and server-side unavailability
+    Network,
+    /// Too many requests; APS asked the client to slow down
+    RateLimit,
+}
+
+impl ApsErrorKind {
+    /// Classify a failed command from its JSON error output, falling back to
+    /// plain-text stderr; `None` for successes and unrecognised failures
+    pub fn from_result(result: &CommandResult) -> Option<Self> {
+        if result.success {
+            return None;
+        }
+        [&result.stderr, &result.stdout]
+            .into_iter()
+            .find_map(|output| json_error(output).and_then(|error| Self::from_json(&error)))
+            .or_else(|| Self::from_text(&result.stderr))
+    }
+
+    /// Classify a JSON error object (`{"error": {...}}` or the inner object)
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let (code, status) = error_fields(value);
+        code.and_then(Self::from_code).or_else(|| status.and_then(Self::from_status))
+    }
+
+    /// Whether a failed command was refused because its token is missing,
+    /// invalid or expired (HTTP 401), so refreshing the token may let it
+    /// succeed; a permission failure (403) is not
+    pub fn is_token_failure(result: &CommandResult) -> bool {
+        if Self::from_result(result) != Some(Self::Auth) {
+            return false;
+        }
+        let json = [&result.stderr, &result.stdout].into_iter().find_map(|output| json_error(output));
+        match json {
+            Some(json) => {
+                let (code, status) = error_fields(&json);
+                match code.map(normalize_code).filter(|code| Self::from_code(code).is_some()) {
+                    Some(code) => TOKEN_CODES.contains(&code.as_str()),
+                    None => status == Some(401),
+                }
+            },
+            None => {
+                let text = result.stderr.to_lowercase();
+                status_in_text(&text) == Some(401) || TOKEN_PHRASES.iter().any(|phrase| text.contains(phrase))
+            },
+        }
+    }
+
+    /// Classify an HTTP status code
+    pub fn from_status(status: u16) -> Option<Self> {
+        match status {
+            401 | 403 => Some(Self::Auth),
+            402 | 507 => Some(Self::Quota),
+            404 | 410 => Some(Self::NotFound),
+            409 => Some(Self::Conflict),
+            429 => Some(Self::RateLimit),
+            408 | 502 | 503 | 504 => Some(Self::Network),
+            _ => None,
+        }
+    }
+
+    /// Classify an error code such as `BUCKET_ALREADY_EXISTS` or `rate-limit`
+    pub fn from_code(code: &str) -> Option<Self> {
+        match normalize_code(code).as_str() {
+            "auth" | "authentication" | "unauthorized" | "forbidden" | "invalidtoken" | "tokenexpired" => {
+                Some(Self::Auth)
+            },
+            "quota" | "quotaexceeded" | "limitexceeded" | "paymentrequired" => Some(Self::Quota),
+            "conflict" | "alreadyexists" | "bucketalreadyexists" => Some(Self::Conflict),
+            "notfound" | "bucketnotfound" | "objectnotfound" => Some(Self::NotFound),
+            "network" | "timeout" | "connection" | "unavailable" => Some(Self::Network),
+            "ratelimit" | "ratelimited" | "toomanyrequests" | "throttled" => Some(Self::RateLimit),
+            _ => None,
+        }
+    }
+
+    /// Classify plain-text error output by the HTTP status it names, or
+    /// else by well-known phrases
+    fn from_text(text: &str) -> Option<Self> {
+        let text = text.to_lowercase();
+        if let Some(kind) = status_in_text(&text).and_then(Self::from_status) {
+            return Some(kind);
+        }
+        let has = |phrases: &[&str]| phrases.iter().any(|p| text.contains(p));
+        if has(&["too many requests", "rate limit"]) {
+            Some(Self::RateLimit)
+        } else if has(&["unauthorized", "forbidden", "permission", "invalid token", "token expired"]) {
+            Some(Self::Auth)
+        } else if has(&["already exists", "conflict"]) {
+            Some(Self::Conflict)
+        } else if has(&["quota"]) {
+            Some(Self::Quota)
+        } else if has(&["not found"]) {
+            Some(Self::NotFound)
+        } else if has(&["network", "timeout", "timed out", "connection"]) {
+            Some(Self::Network)
+        } else {
+            None
+        }
+    }
+
+    /// Whether running the same command again may succeed
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::Network | Self::RateLimit)
+    }
+
+    /// Short lowercase label (`auth`, `rate-limit`, ...)
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Auth => "auth",
+            Self::Quota => "quota",
+            Self::Conflict => "conflict",
+            Self::NotFound => "not-found",
+            Self::Network => "network",
+            Self::RateLimit => "rate-limit",
+        }
+    }
+
+    /// Recovery suggestions for this kind of failure of `command`
+    pub fn recovery_suggestions(self, command: &RapsCommand) -> Vec<String> {
+        let suggestions: &[&str] = match (self, command) {
+            (Self::Auth, RapsCommand::Bucket { .. } | RapsCommand::Object { .. }) => &[
+                "Check your APS credentials and try 'raps auth login'",
+                "Check that you have OSS permissions in your APS app",
+            ],
+            (Self::Auth, _) => &[
+                "Check your APS credentials and try 'raps auth login'",
+                "Verify your client ID and client secret are correct",
+            ],
+            (Self::Conflict, RapsCommand::Bucket { .. }) => {
+                &["Bucket name already exists, try a different name"]
+            },
+            (Self::Conflict, _) => &["The resource already exists; use a different name or delete it first"],
+            (Self::NotFound, RapsCommand::Object { .. }) => {
+                &["Verify the bucket exists and the object key is correct"]
+            },
+            (Self::NotFound, RapsCommand::Translate { .. }) => {
+                &["Verify the URN is valid and the file was uploaded successfully"]
+            },
+            (Self::NotFound, _) => &["Verify the resource exists and its name or ID is correct"],
+            (Self::Quota, _) => &["Your APS quota is exhausted; delete unused resources or wait for it to reset"],
+            (Self::Network, _) => &["Check your internet connection and try again"],
+            (Self::RateLimit, _) => &["APS is rate limiting requests; wait a moment and try again"],
+        };
+        suggestions.iter().map(|s| s.to_string()).collect()
+    }
+}
+
+impl fmt::Display for ApsErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Normalized error codes of a missing, invalid or expired token
+const TOKEN_CODES: [&str; 3] = ["unauthorized", "invalidtoken", "tokenexpired"];
+
+/// Phrases of a missing, invalid or expired token in plain-text errors
+const TOKEN_PHRASES: [&str; 4] = ["unauthorized", "invalid token", "token expired", "expired token"];
+
+/// Words introducing an HTTP status in plain-text errors (`HTTP/1.1 404`,
+/// `status: 409`, `error code 401`)
+const STATUS_PREFIXES: [&str; 3] = ["http", "status", "code"];
+
+/// First words of HTTP reason phrases (`401 Unauthorized`, `404 Not Found`)
+const STATUS_REASONS: [&str; 10] = [
+    "unauthorized", "forbidden", "payment", "not", "timeout", "conflict", "gone", "too", "bad", "service",
+];
+
+/// HTTP error status named in lowercased plain-text output: a three-digit
+/// 4xx or 5xx number following `http`, `status` or `code`, or followed by
+/// its reason phrase. Numbers inside IDs, names or sizes aren't statuses.
+fn status_in_text(text: &str) -> Option<u16> {
+    let words: Vec<&str> = text
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    words.iter().enumerate().find_map(|(i, word)| {
+        let status = word.parse::<u16>().ok().filter(|status| word.len() == 3 && (400..600).contains(status))?;
+        // `http/1.1 404` puts the version between the prefix and the status
+        let prefixed = words[i.saturating_sub(3)..i].iter().any(|w| STATUS_PREFIXES.contains(w));
+        let reasoned = words.get(i + 1).is_some_and(|w| STATUS_REASONS.contains(w));
+        (prefixed || reasoned).then_some(status)
+    })
+}
+
+/// Error code lowercased with everything but letters and digits removed,
+/// so `BUCKET_ALREADY_EXISTS` and `bucket-already-exists` compare equal
+fn normalize_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Error code and HTTP status of a JSON error object (`{"error": {...}}`
+/// or the inner object)
+fn error_fields(value: &Value) -> (Option<&str>, Option<u16>) {
+    let error = value.get("error").filter(|e| e.is_object()).unwrap_or(value);
+    let code = ["code", "kind", "error_code", "type"]
+        .iter()
+        .find_map(|key| error.get(key).and_then(Value::as_str));
+    let status = ["status", "status_code", "statusCode", "http_status"]
+        .iter()
+        .find_map(|key| {
+            let field = error.get(key)?;
+            field.as_u64().or_else(|| field.as_str()?.parse().ok())
+        })
+        .and_then(|status| u16::try_from(status).ok());
+    (code, status)
+}
+
+/// JSON error object in `output`: the whole output, or its first line that parses
+fn json_error(output: &str) -> Option<Value> {
+    let trimmed = output.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    serde_json::from_str::<Value>(trimmed)
+        .ok()
+        .or_else(|| {
+            trimmed
+                .lines()
+                .map(str::trim)
+                .filter(|line| line.starts_with('{'))
+                .find_map(|line| serde_json::from_str(line).ok())
+        })
+        .filter(Value::is_object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn failed(stderr: &str) -> CommandResult {
+        CommandResult::new(1, String::new(), stderr.to_string(), Duration::from_millis(1))
+    }
+
+    #[test]
+    fn test_classify_json_and_text_errors() {
+        let json = r#"{"error": {"code": "BUCKET_ALREADY_EXISTS", "status": 409, "message": "taken"}}"#;
+        assert_eq!(ApsErrorKind::from_result(&failed(json)), Some(ApsErrorKind::Conflict));
+        // The status is used when the code is unknown
+        let json = "warning: retrying\n{\"status\": \"429\", \"code\": \"E_SLOW\"}";
+        assert_eq!(ApsErrorKind::from_result(&failed(json)), Some(ApsErrorKind::RateLimit));
+        assert_eq!(
+            ApsErrorKind::from_result(&failed("Error: bucket not found")),
+            Some(ApsErrorKind::NotFound)
+        );
+        assert_eq!(ApsErrorKind::from_result(&failed("something odd")), None);
+        // Statuses are read where the text names one, not from IDs
+        let http = "HTTP/1.1 409 Conflict";
+        assert_eq!(ApsErrorKind::from_result(&failed(http)), Some(ApsErrorKind::Conflict));
+        let status = "Error: request failed (status: 429)";
+        assert_eq!(ApsErrorKind::from_result(&failed(status)), Some(ApsErrorKind::RateLimit));
+        let id = "Error: object urn-401-abc4039 not found";
+        assert_eq!(ApsErrorKind::from_result(&failed(id)), Some(ApsErrorKind::NotFound));
+        assert_eq!(ApsErrorKind::from_result(&failed("Error: job 4290 failed")), None);
+
+        assert!(ApsErrorKind::RateLimit.is_retryable());
+        assert!(!ApsErrorKind::Auth.is_retryable());
+        assert_eq!(ApsErrorKind::NotFound.to_string(), "not-found");
+    }
+
+    #[test]
+    fn test_token_failures_but_not_permission_failures() {
+        let expired = r#"{"error": {"code": "UNAUTHORIZED", "status": 401}}"#;
+        assert!(ApsErrorKind::is_token_failure(&failed(expired)));
+        assert!(ApsErrorKind::is_token_failure(&failed(r#"{"error": {"status": 401}}"#)));
+        assert!(ApsErrorKind::is_token_failure(&failed("Error: token expired")));
+        assert!(ApsErrorKind::is_token_failure(&failed("Error: HTTP 401")));
+
+        // Refreshing the token doesn't grant a missing permission
+        let forbidden = r#"{"error": {"code": "FORBIDDEN", "status": 403}}"#;
+        assert_eq!(ApsErrorKind::from_result(&failed(forbidden)), Some(ApsErrorKind::Auth));
+        assert!(!ApsErrorKind::is_token_failure(&failed(forbidden)));
+        assert!(!ApsErrorKind::is_token_failure(&failed(r#"{"error": {"status": "403"}}"#)));
+        assert!(!ApsErrorKind::is_token_failure(&failed("Error: permission denied for bucket")));
+        assert!(!ApsErrorKind::is_token_failure(&failed("Error: bucket not found")));
+        assert!(!ApsErrorKind::is_token_failure(&failed("Error: 403 Forbidden for token 401")));
+    }
+}
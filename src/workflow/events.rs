@@ -0,0 +1,140 @@
+// Execution update event bus
+//
+// The executor publishes every `ExecutionUpdate` once on a broadcast channel,
+// and any number of consumers (the TUI, the CLI progress printer, history and
+// report writers, hooks, embedding tools) subscribe independently. A
+// subscriber that falls behind skips the updates it missed rather than
+// holding up the run or the other subscribers.
+
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc};
+
+use super::executor::ExecutionUpdate;
+
+/// Updates buffered per subscriber before a slow one starts missing some
+pub const DEFAULT_EVENT_CAPACITY: usize = 1024;
+
+/// Broadcast channel for execution updates
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<ExecutionUpdate>,
+    /// Unbounded channels that get every update as well
+    sinks: Arc<Mutex<Vec<mpsc::UnboundedSender<ExecutionUpdate>>>>,
+}
+
+impl EventBus {
+    /// Bus buffering up to `capacity` updates per subscriber
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self {
+            sender,
+            sinks: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Publish `update` to every subscriber and sink
+    pub fn send(&self, update: ExecutionUpdate) {
+        let mut sinks = self.sinks.lock().unwrap_or_else(|e| e.into_inner());
+        // Forget sinks whose receiver was dropped
+        sinks.retain(|sink| sink.send(update.clone()).is_ok());
+        // Failing only means there are no subscribers right now
+        let _ = self.sender.send(update);
+    }
+
+    /// New subscription receiving the updates published from now on
+    pub fn subscribe(&self) -> UpdateReceiver {
+        UpdateReceiver {
+            receiver: self.sender.subscribe(),
+        }
+    }
+
+    /// Also forward every update to `sink`, which never misses any
+    pub fn forward_to(&self, sink: mpsc::UnboundedSender<ExecutionUpdate>) {
+        self.sinks.lock().unwrap_or_else(|e| e.into_inner()).push(sink);
+    }
+
+    /// Number of live subscriptions, sinks not included
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_CAPACITY)
+    }
+}
+
+/// One subscriber's view of the execution updates
+#[derive(Debug)]
+pub struct UpdateReceiver {
+    receiver: broadcast::Receiver<ExecutionUpdate>,
+}
+
+impl UpdateReceiver {
+    /// Wait for the next update; `None` once the bus is gone
+    pub async fn recv(&mut self) -> Option<ExecutionUpdate> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(update) => return Some(update),
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    tracing::warn!("Execution update subscriber fell behind, skipped {} update(s)", missed);
+                },
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Next update if one is waiting, without blocking
+    pub fn try_recv(&mut self) -> Option<ExecutionUpdate> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(update) => return Some(update),
+                Err(broadcast::error::TryRecvError::Lagged(missed)) => {
+                    tracing::warn!("Execution update subscriber fell behind, skipped {} update(s)", missed);
+                },
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::types::ExecutionHandle;
+
+    fn cancelled() -> ExecutionUpdate {
+        ExecutionUpdate::Cancelled {
+            handle: ExecutionHandle::new("bus-test".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_every_subscriber_gets_updates() {
+        let bus = EventBus::new(2);
+        let mut first = bus.subscribe();
+        let mut second = bus.subscribe();
+        let (sink, mut sink_receiver) = mpsc::unbounded_channel();
+        bus.forward_to(sink);
+        assert_eq!(bus.subscriber_count(), 2);
+
+        bus.send(cancelled());
+        assert!(matches!(first.recv().await, Some(ExecutionUpdate::Cancelled { .. })));
+        assert!(matches!(second.try_recv(), Some(ExecutionUpdate::Cancelled { .. })));
+        assert!(sink_receiver.try_recv().is_ok());
+        assert!(first.try_recv().is_none());
+
+        // A slow subscriber skips what it missed instead of blocking the bus
+        for _ in 0..5 {
+            bus.send(cancelled());
+        }
+        assert!(first.try_recv().is_some());
+        assert!(first.try_recv().is_some());
+        assert!(first.try_recv().is_none());
+        assert_eq!(std::iter::from_fn(|| sink_receiver.try_recv().ok()).count(), 5);
+
+        drop(bus);
+        assert!(second.recv().await.is_some());
+    }
+}
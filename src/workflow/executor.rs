@@ -3,17 +3,37 @@
 // This module provides the core execution engine for running workflow steps,
 // handling errors, and providing progress reporting and recovery suggestions.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
-use tracing::{error, info};
+use tokio::sync::{Mutex, Notify, OwnedMutexGuard, RwLock};
+use tracing::{error, info, info_span, warn, Instrument};
 use uuid::Uuid;
 
-use super::client::{CommandProgress, CommandResult, RapsClient, RapsClientConfig};
+use super::artifacts::{collect_artifacts, run_artifacts_dir, ArtifactSpec};
+use super::chaos::{Chaos, ChaosFault};
+use super::client::{
+    CommandCancelled, CommandProgress, CommandResult, InvocationOverrides, OutputTap, ProfileEnvironments, RapsClient, RapsClientConfig,
+};
 use super::discovery::WorkflowDefinition;
+use super::errors::ApsErrorKind;
+use super::events::{EventBus, UpdateReceiver};
+use super::history::{OutputsRef, SharedHistory};
+use crate::config::secrets::SecretStore;
+use crate::utils::redact;
+use super::hooks::{send_http_hook, HookAction, HookEvent, WorkflowHooks};
+use super::locks::{LockGuard, LockTable, LockWait};
+use super::metrics::{RunOutcome, WorkflowMetrics};
+use super::placeholders;
+use super::output;
+use super::policy::{NameRewrites, StepPolicy};
+use super::rate_limit;
+use super::region::{self, Region};
 use super::types::*;
+use super::watchdog::{ActivityClock, HealthCheck, StallReport, WatchdogConfig};
 
 /// Execution engine for running workflows step by step
 pub struct WorkflowExecutor {
@@ -21,15 +41,40 @@ pub struct WorkflowExecutor {
     raps_client: Arc<RapsClient>,
     /// Active executions indexed by handle
     active_executions: Arc<RwLock<HashMap<ExecutionHandle, ExecutionState>>>,
-    /// Progress sender for reporting execution updates
-    progress_sender: Option<mpsc::UnboundedSender<ExecutionUpdate>>,
+    /// Bus every execution update is published on
+    events: EventBus,
+    /// Duration history used for ETAs and updated after each run
+    history: Option<SharedHistory>,
+    /// Optional metrics registry for run outcomes and durations
+    metrics: Option<Arc<WorkflowMetrics>>,
+    /// Source of step secrets
+    secrets: SecretStore,
+    /// Restrictions checked before each command and hook runs
+    policy: StepPolicy,
+    /// Fail steps whose placeholders cannot be resolved
+    strict_placeholders: bool,
+    /// Region of bucket creations and translations that name none
+    region: Option<Region>,
+    /// Send every line step commands print as a `StepOutput` update
+    stream_output: bool,
+    /// Refresh the token and retry once on authentication failures
+    auto_reauth: bool,
+    /// Bytes of each output stream kept in step results (0 = unlimited)
+    max_output_bytes: usize,
+    /// Detection of executions stuck without progress
+    watchdog: WatchdogConfig,
+    /// Named locks held by running executions
+    locks: Arc<LockTable>,
 }
 
+/// Number of runs started in this process, for `{seq}`
+static RUN_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
 /// Internal state for an active execution
 #[derive(Debug, Clone)]
 struct ExecutionState {
-    /// Workflow definition being executed
-    workflow: WorkflowDefinition,
+    /// Workflow definition being executed, shared with discovery
+    workflow: Arc<WorkflowDefinition>,
     /// Execution context
     context: ExecutionContext,
     /// Current step index
@@ -44,6 +89,38 @@ struct ExecutionState {
     status: ExecutionStatus,
     /// Generated placeholders (e.g., {uuid}, {timestamp})
     placeholders: HashMap<String, String>,
+    /// Resource names rewritten by the naming policy so far
+    name_rewrites: NameRewrites,
+    /// Artifact patterns of executed steps, collected when the run ends
+    artifact_specs: Vec<ArtifactSpec>,
+    /// Signalled on cancellation to abort the running command
+    cancel: Arc<Notify>,
+    /// Held by the task running the steps, so cancelling can wait until its
+    /// command was stopped
+    run_task: Arc<Mutex<()>>,
+    /// Last time a step started, reported progress or finished
+    activity: ActivityClock,
+    /// Command line of the step being run, for stall diagnostics
+    current_command: Option<String>,
+    /// Whether the watchdog reported the execution as stalled
+    stalled: bool,
+    /// Hold before the next step once the current one finishes
+    pause_requested: bool,
+    /// Index of the step the execution last paused before
+    paused_at: Option<usize>,
+    /// Failed step waiting for a retry/skip/abort decision
+    pending_failure: Option<PendingFailure>,
+    /// Fault generator of a training run with chaos enabled
+    chaos: Option<Chaos>,
+}
+
+/// A failed step held until the user decides what to do
+#[derive(Debug, Clone)]
+struct PendingFailure {
+    /// The step as it ran, placeholders resolved
+    step: ExecutionStep,
+    result: StepResult,
+    error: ExecutionError,
 }
 
 /// Update message for execution progress
@@ -70,11 +147,19 @@ pub enum ExecutionUpdate {
         handle: ExecutionHandle,
         result: StepResult,
     },
-    /// Execution paused (interactive mode)
+    /// Execution paused before `next_step` (interactive mode or on request)
     Paused {
         handle: ExecutionHandle,
         next_step: ExecutionStep,
     },
+    /// Paused execution resumed
+    Resumed { handle: ExecutionHandle },
+    /// A step failed and the execution waits for a [`StepDecision`]
+    StepFailed {
+        handle: ExecutionHandle,
+        step: ExecutionStep,
+        error: ExecutionError,
+    },
     /// Execution completed
     Completed {
         handle: ExecutionHandle,
@@ -87,8 +172,104 @@ pub enum ExecutionUpdate {
     },
     /// Execution cancelled
     Cancelled { handle: ExecutionHandle },
+    /// Placeholder values became available (built-ins or captured outputs)
+    VariablesUpdated {
+        handle: ExecutionHandle,
+        source: VariableSource,
+        values: Vec<(String, String)>,
+    },
+    /// Step artifacts were copied into the run's artifacts folder
+    ArtifactsCollected {
+        handle: ExecutionHandle,
+        directory: PathBuf,
+        files: Vec<PathBuf>,
+    },
+    /// Cleanup commands ran after the steps (see [`ExecutionOptions::auto_cleanup`])
+    CleanupCompleted {
+        handle: ExecutionHandle,
+        results: Vec<CleanupResult>,
+    },
+    /// A hook action finished
+    HookCompleted {
+        handle: ExecutionHandle,
+        event: HookEvent,
+        action: String,
+        success: bool,
+        message: String,
+    },
+    /// Execution waits for a lock held by another run before starting
+    WaitingForLock {
+        handle: ExecutionHandle,
+        wait: LockWait,
+    },
+    /// A line printed by a step's RAPS command (only when output streaming
+    /// is enabled), with secrets redacted
+    StepOutput {
+        handle: ExecutionHandle,
+        step_id: StepId,
+        line: String,
+        stderr: bool,
+    },
+    /// No step progress for longer than the watchdog threshold
+    Stalled {
+        handle: ExecutionHandle,
+        report: StallReport,
+    },
+}
+
+impl ExecutionUpdate {
+    /// Execution the update belongs to
+    pub fn handle(&self) -> &ExecutionHandle {
+        match self {
+            Self::Started { handle, .. }
+            | Self::StepStarted { handle, .. }
+            | Self::StepProgress { handle, .. }
+            | Self::StepCompleted { handle, .. }
+            | Self::Paused { handle, .. }
+            | Self::Completed { handle, .. }
+            | Self::Failed { handle, .. }
+            | Self::Cancelled { handle }
+            | Self::Resumed { handle }
+            | Self::StepFailed { handle, .. }
+            | Self::VariablesUpdated { handle, .. }
+            | Self::ArtifactsCollected { handle, .. }
+            | Self::CleanupCompleted { handle, .. }
+            | Self::HookCompleted { handle, .. }
+            | Self::WaitingForLock { handle, .. }
+            | Self::StepOutput { handle, .. }
+            | Self::Stalled { handle, .. } => handle,
+        }
+    }
+}
+
+/// Where a placeholder value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VariableSource {
+    /// Generated by the executor (e.g. `{uuid}`, `{timestamp}`)
+    BuiltIn,
+    /// Workflow parameter (`{params.<name>}`)
+    Param,
+    /// Captured from the JSON output of a step
+    StepOutput(StepId),
+}
+
+/// Prerequisites a workflow needs that are missing (RAPS CLI, login,
+/// required assets); why [`WorkflowExecutor::execute_workflow`] refused to
+/// start a run
+#[derive(Debug, Clone)]
+pub struct PrerequisiteError {
+    /// One line per missing prerequisite
+    pub problems: Vec<String>,
 }
 
+impl std::fmt::Display for PrerequisiteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Prerequisite validation failed:\n{}", self.problems.join("\n"))
+    }
+}
+
+impl std::error::Error for PrerequisiteError {}
+
 /// Detailed error information for execution failures
 #[derive(Debug, Clone)]
 pub struct ExecutionError {
@@ -102,6 +283,8 @@ pub struct ExecutionError {
     pub recovery_suggestions: Vec<String>,
     /// Whether the error is recoverable
     pub is_recoverable: bool,
+    /// Kind of APS failure, when the command output identifies one
+    pub kind: Option<ApsErrorKind>,
 }
 
 impl ExecutionError {
@@ -113,6 +296,7 @@ impl ExecutionError {
             command_result: None,
             recovery_suggestions: Vec::new(),
             is_recoverable: false,
+            kind: None,
         }
     }
 
@@ -127,12 +311,18 @@ impl ExecutionError {
                 .error_message()
                 .unwrap_or("Command failed".to_string()),
             failed_step: Some(step_id),
+            kind: ApsErrorKind::from_result(&command_result),
             command_result: Some(command_result),
             recovery_suggestions,
             is_recoverable: true,
         }
     }
 
+    /// Whether running the failed command again may succeed
+    pub fn is_retryable(&self) -> bool {
+        self.kind.is_some_and(ApsErrorKind::is_retryable)
+    }
+
     /// Add a recovery suggestion
     pub fn with_suggestion(mut self, suggestion: String) -> Self {
         self.recovery_suggestions.push(suggestion);
@@ -154,7 +344,18 @@ impl WorkflowExecutor {
         Self {
             raps_client,
             active_executions: Arc::new(RwLock::new(HashMap::new())),
-            progress_sender: None,
+            events: EventBus::default(),
+            history: None,
+            metrics: None,
+            secrets: SecretStore::new(),
+            policy: StepPolicy::default(),
+            strict_placeholders: true,
+            region: None,
+            stream_output: false,
+            auto_reauth: true,
+            max_output_bytes: output::DEFAULT_MAX_OUTPUT_BYTES,
+            watchdog: WatchdogConfig::default(),
+            locks: Arc::new(LockTable::default()),
         }
     }
 
@@ -165,15 +366,112 @@ impl WorkflowExecutor {
         Self {
             raps_client,
             active_executions: Arc::new(RwLock::new(HashMap::new())),
-            progress_sender: None,
+            events: EventBus::default(),
+            history: None,
+            metrics: None,
+            secrets: SecretStore::new(),
+            policy: StepPolicy::default(),
+            strict_placeholders: true,
+            region: None,
+            stream_output: false,
+            auto_reauth: true,
+            max_output_bytes: output::DEFAULT_MAX_OUTPUT_BYTES,
+            watchdog: WatchdogConfig::default(),
+            locks: Arc::new(LockTable::default()),
         }
     }
 
-    /// Set up progress reporting
-    pub fn with_progress_reporting(mut self) -> (Self, mpsc::UnboundedReceiver<ExecutionUpdate>) {
-        let (sender, receiver) = mpsc::unbounded_channel();
-        self.progress_sender = Some(sender);
-        (self, receiver)
+    /// Publish execution updates on `events` instead of a bus of its own
+    pub fn with_event_bus(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Bus execution updates are published on
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// New subscription to the execution updates published from now on
+    pub fn subscribe(&self) -> UpdateReceiver {
+        self.events.subscribe()
+    }
+
+    /// Record run durations into `history` and use it for time estimates
+    pub fn with_history(mut self, history: SharedHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Resolve step secrets from a specific store
+    pub fn with_secrets(mut self, secrets: SecretStore) -> Self {
+        self.secrets = secrets;
+        self
+    }
+
+    /// Record run outcomes, step failures and durations into `metrics`
+    pub fn with_metrics(mut self, metrics: Arc<WorkflowMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Refuse commands and hooks that `policy` does not allow
+    pub fn with_policy(mut self, policy: StepPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Run commands of workflows and steps naming a profile with `profiles`
+    pub fn with_profiles(mut self, profiles: ProfileEnvironments) -> Self {
+        self.raps_client = Arc::new(self.raps_client.with_profiles(profiles));
+        self
+    }
+
+    /// Client running the RAPS CLI for this executor, shared with anything
+    /// that runs RAPS on its behalf (configuration, profiles, mock mode and
+    /// rate limit included)
+    pub fn raps_client(&self) -> &Arc<RapsClient> {
+        &self.raps_client
+    }
+
+    /// Fail steps with undefined placeholders (`true`, the default) or pass
+    /// them through with a warning
+    pub fn with_strict_placeholders(mut self, strict: bool) -> Self {
+        self.strict_placeholders = strict;
+        self
+    }
+
+    /// Create buckets and translate in `region` when a step names no region
+    pub fn with_region(mut self, region: Option<Region>) -> Self {
+        self.region = region;
+        self
+    }
+
+    /// Send each line printed by step commands as a `StepOutput` update,
+    /// for runners that show the full command output
+    pub fn with_output_streaming(mut self, stream: bool) -> Self {
+        self.stream_output = stream;
+        self
+    }
+
+    /// Refresh the APS token and retry a step once when it fails with an
+    /// authentication error (`true`, the default)
+    pub fn with_auto_reauth(mut self, enabled: bool) -> Self {
+        self.auto_reauth = enabled;
+        self
+    }
+
+    /// Keep at most `max_bytes` of each output stream in step results, saving
+    /// longer output in full under the outputs directory (0 keeps everything)
+    pub fn with_max_output_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_output_bytes = max_bytes;
+        self
+    }
+
+    /// Watch running executions for stalls with these settings
+    pub fn with_watchdog(mut self, watchdog: WatchdogConfig) -> Self {
+        self.watchdog = watchdog;
+        self
     }
 
     /// Validate prerequisites for a workflow
@@ -195,11 +493,11 @@ impl WorkflowExecutor {
         }
 
         // Check required assets exist
-        for asset_path in &workflow.metadata.required_assets {
+        for asset_path in workflow.required_assets() {
             if !asset_path.exists() {
                 validation_errors.push(format!(
                     "Required asset not found: {}",
-                    asset_path.display()
+                    workflow.describe_asset(&asset_path)
                 ));
             }
         }
@@ -207,48 +505,169 @@ impl WorkflowExecutor {
         Ok(validation_errors)
     }
 
-    /// Start executing a workflow
+    /// Start executing a workflow; a shared definition is only copied when
+    /// a profile changes it
     pub async fn execute_workflow(
         &self,
-        workflow: WorkflowDefinition,
+        workflow: impl Into<Arc<WorkflowDefinition>>,
         options: ExecutionOptions,
     ) -> Result<ExecutionHandle> {
+        let workflow = workflow.into();
+        let workflow = match &options.profile {
+            Some(profile) => Arc::new(workflow.with_profile(profile)?),
+            None => workflow,
+        };
+
         // Validate prerequisites
         let validation_errors = self.validate_prerequisites(&workflow).await?;
         if !validation_errors.is_empty() {
-            return Err(anyhow::anyhow!(
-                "Prerequisite validation failed:\n{}",
-                validation_errors.join("\n")
-            ));
+            return Err(PrerequisiteError {
+                problems: validation_errors,
+            }
+            .into());
         }
 
-        // Create execution context
+        // A run may start at a later step if it is given what the skipped steps would produce
+        let start_index = match &options.start_at_step {
+            Some(step_id) => {
+                let index = workflow
+                    .steps
+                    .iter()
+                    .position(|step| &step.id == step_id)
+                    .ok_or_else(|| anyhow::anyhow!("Workflow {} has no step '{}'", workflow.metadata.id, step_id))?;
+                let missing: Vec<String> = placeholders::skipped_step_outputs(&workflow, index)
+                    .into_iter()
+                    .filter(|name| !options.params.contains_key(name))
+                    .collect();
+                if !missing.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "Starting at step '{}' skips the steps producing {}; pass them as parameters",
+                        step_id,
+                        missing.join(", ")
+                    ));
+                }
+                index
+            },
+            None => 0,
+        };
+
+        options.chaos.validate()?;
+        let imported_outputs = self.imported_outputs(&workflow, &options)?;
+
+        // Create execution context with its own scratch directory
+        let temp_dir = std::env::temp_dir().join(format!("raps-demo-{}", Uuid::new_v4()));
+        tokio::fs::create_dir_all(&temp_dir)
+            .await
+            .with_context(|| format!("Failed to create temporary directory: {:?}", temp_dir))?;
         let context = ExecutionContext {
             workflow_id: workflow.metadata.id.clone(),
             options,
             environment: HashMap::new(),
-            temp_dir: std::env::temp_dir().join(format!("raps-demo-{}", Uuid::new_v4())),
+            temp_dir: temp_dir.clone(),
             start_time: Utc::now(),
         };
 
         // Create execution handle
         let handle = ExecutionHandle::new(workflow.metadata.id.clone());
 
+        // Built-in placeholders available to every step; generated names
+        // derive from the seed so a fixed seed reproduces them
+        let seed = context.options.seed.unwrap_or_else(|| Uuid::new_v4().as_u128() as u64);
+        let seq = RUN_SEQUENCE.fetch_add(1, Ordering::Relaxed) + 1;
+        let context_chaos = context
+            .options
+            .chaos
+            .is_enabled()
+            .then(|| Chaos::new(context.options.chaos.clone(), seed));
+        let builtins = {
+            let mut map = HashMap::new();
+            map.insert("uuid".to_string(), placeholders::seeded_uuid(seed).to_string());
+            map.insert("seed".to_string(), seed.to_string());
+            map.insert("seq".to_string(), seq.to_string());
+            map.insert("username".to_string(), placeholders::username());
+            map.insert("timestamp".to_string(), Utc::now().timestamp().to_string());
+            map.insert("temp_dir".to_string(), temp_dir.display().to_string());
+            map
+        };
+
+        // Shared variables come first, so parameter defaults may use them
+        let mut placeholders = builtins.clone();
+        for (name, value) in &workflow.vars {
+            placeholders.insert(format!("{}{}", placeholders::VARS_PREFIX, name), value.clone());
+        }
+
+        // Workflow parameters: the run's values over outputs of earlier runs
+        // over the declared defaults
+        let mut params: HashMap<String, String> = workflow
+            .params
+            .iter()
+            .map(|(name, default)| (name.clone(), placeholders::resolve(default, &placeholders, &mut Vec::new())))
+            .collect();
+        params.extend(imported_outputs);
+        params.extend(context.options.params.clone());
+        for (name, value) in &params {
+            placeholders.insert(format!("{}{}", placeholders::PARAMS_PREFIX, name), value.clone());
+            // Stand-ins for outputs of skipped steps, also under their older
+            // and bare keys; bare outputs are given as they are
+            if let Some((step, key)) = name.strip_prefix(placeholders::STEPS_PREFIX).and_then(|o| o.split_once('.')) {
+                placeholders.insert(name.clone(), value.clone());
+                placeholders.insert(format!("{}.{}", step, key), value.clone());
+                placeholders.entry(key.to_string()).or_insert_with(|| value.clone());
+            } else if context.options.params.contains_key(name)
+                && !workflow.params.contains_key(name)
+                && !name.contains('.')
+            {
+                placeholders.insert(name.clone(), value.clone());
+            }
+        }
+
+        // Lock names may use the run's parameters
+        let mut undefined = Vec::new();
+        let mut lock_names: Vec<String> = workflow
+            .locks
+            .iter()
+            .map(|lock| placeholders::resolve(lock, &placeholders, &mut undefined))
+            .collect();
+        if !undefined.is_empty() {
+            // The run never starts, so nothing else would remove its scratch directory
+            let _ = tokio::fs::remove_dir_all(&temp_dir).await;
+            return Err(anyhow::anyhow!("Lock names use undefined placeholders: {}", undefined.join(", ")));
+        }
+        lock_names.sort();
+        lock_names.dedup();
+
+        // Claimed before the task is spawned, so a cancel right after the
+        // start already waits for it
+        let run_task = Arc::new(Mutex::new(()));
+        let running = Arc::clone(&run_task).try_lock_owned().ok();
+
         // Create execution state
         let execution_state = ExecutionState {
-            workflow: workflow.clone(),
+            workflow: Arc::clone(&workflow),
             context,
-            current_step_index: 0,
+            current_step_index: start_index,
             completed_steps: Vec::new(),
             created_resources: Vec::new(),
             start_time: Utc::now(),
-            status: ExecutionStatus::Running,
-            placeholders: {
-                let mut map = HashMap::new();
-                map.insert("uuid".to_string(), Uuid::new_v4().to_string());
-                map.insert("timestamp".to_string(), Utc::now().timestamp().to_string());
-                map
+            // Runs needing locks are pending until they hold them
+            status: if lock_names.is_empty() {
+                ExecutionStatus::Running
+            } else {
+                ExecutionStatus::Pending
             },
+            placeholders,
+            name_rewrites: NameRewrites::default(),
+            artifact_specs: Vec::new(),
+            cancel: Arc::new(Notify::new()),
+            run_task: Arc::clone(&run_task),
+            activity: ActivityClock::new(),
+            current_command: None,
+            stalled: false,
+            pause_requested: false,
+            // Interactive runs don't pause before the step they start at
+            paused_at: Some(start_index),
+            pending_failure: None,
+            chaos: context_chaos,
         };
 
         // Store execution state
@@ -257,35 +676,259 @@ impl WorkflowExecutor {
             executions.insert(handle.clone(), execution_state);
         }
 
-        // Send started update
-        if let Some(sender) = &self.progress_sender {
-            let _ = sender.send(ExecutionUpdate::Started {
+        if let Some(metrics) = &self.metrics {
+            let cost = workflow.metadata.cost_estimate.as_ref().map_or(0.0, |c| c.max_cost_usd);
+            metrics.record_cost_estimate(&workflow.metadata.id, cost);
+        }
+
+        // Send started update, followed by the built-in placeholders
+        self.events.send(ExecutionUpdate::Started {
+            handle: handle.clone(),
+            workflow_id: workflow.metadata.id.clone(),
+        });
+        self.events.send(ExecutionUpdate::VariablesUpdated {
+            handle: handle.clone(),
+            source: VariableSource::BuiltIn,
+            values: sorted_pairs(&builtins),
+        });
+        if !params.is_empty() {
+            self.events.send(ExecutionUpdate::VariablesUpdated {
                 handle: handle.clone(),
-                workflow_id: workflow.metadata.id.clone(),
+                source: VariableSource::Param,
+                values: sorted_pairs(&params),
             });
         }
 
         // Start execution in background
         let executor = self.clone();
         let execution_handle = handle.clone();
+        let span = workflow_span(&handle);
+        let start_hooks = workflow.hooks.on_start.clone();
         tokio::spawn(async move {
+            let _running = running;
+            let locks = LockGuard::new(Arc::clone(&executor.locks), execution_handle.clone());
+            if !lock_names.is_empty() && !executor.acquire_locks(&execution_handle, &lock_names).await {
+                return;
+            }
+            executor
+                .run_hooks(&execution_handle, HookEvent::WorkflowStart, &start_hooks, &[])
+                .await;
             if let Err(e) = executor
                 .run_workflow_execution(execution_handle.clone())
                 .await
             {
                 error!("Workflow execution failed: {}", e);
-                if let Some(sender) = &executor.progress_sender {
-                    let _ = sender.send(ExecutionUpdate::Failed {
-                        handle: execution_handle,
-                        error: ExecutionError::new(e.to_string()),
-                    });
+                // Step failures already ran the failure hooks
+                if !executor.has_status(&execution_handle, ExecutionStatus::Failed).await {
+                    let error = e.to_string();
+                    executor.run_failure_hooks(&execution_handle, None, &error).await;
+                    executor.run_cleanup(&execution_handle, false).await;
                 }
+                executor.finalize_temp_dir(&execution_handle).await;
+                if let Some(metrics) = &executor.metrics {
+                    metrics.record_run(&execution_handle.workflow_id, RunOutcome::Failure, None);
+                }
+                executor.events.send(ExecutionUpdate::Failed {
+                    handle: execution_handle.clone(),
+                    error: ExecutionError::new(e.to_string()),
+                });
             }
-        });
+            executor.end_run_task(&execution_handle, locks).await;
+        }.instrument(span));
+
+        if self.watchdog.enabled {
+            let executor = self.clone();
+            let watched = handle.clone();
+            tokio::spawn(async move { executor.watch_execution(&watched).await });
+        }
 
         Ok(handle)
     }
 
+    /// Wait until the execution holds every lock in `names`, reporting what
+    /// it waits for; `false` when it was cancelled meanwhile
+    async fn acquire_locks(&self, handle: &ExecutionHandle, names: &[String]) -> bool {
+        let mut reported: Option<LockWait> = None;
+        loop {
+            let released = self.locks.released();
+            let wait = match self.locks.try_acquire(handle, names) {
+                Ok(()) => break,
+                Err(wait) => wait,
+            };
+            if reported.as_ref() != Some(&wait) {
+                info!("Execution {} of {} waits for lock '{}' held by {}", handle.id, handle.workflow_id, wait.lock, wait.holder);
+                self.events.send(ExecutionUpdate::WaitingForLock {
+                    handle: handle.clone(),
+                    wait: wait.clone(),
+                });
+                reported = Some(wait);
+            }
+
+            let cancel = {
+                let executions = self.active_executions.read().await;
+                match executions.get(handle) {
+                    Some(state) if state.status == ExecutionStatus::Pending => Arc::clone(&state.cancel),
+                    _ => return false,
+                }
+            };
+            tokio::select! {
+                _ = released => {},
+                _ = cancel.notified() => {},
+            }
+        }
+
+        let mut executions = self.active_executions.write().await;
+        match executions.get_mut(handle) {
+            Some(state) if state.status == ExecutionStatus::Pending => {
+                state.status = ExecutionStatus::Running;
+                state.activity.touch();
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Mark the calling task as the one running the steps of `handle`
+    async fn claim_run_task(&self, handle: &ExecutionHandle) -> Option<OwnedMutexGuard<()>> {
+        let run_task = Arc::clone(&self.active_executions.read().await.get(handle)?.run_task);
+        Some(run_task.lock_owned().await)
+    }
+
+    /// Wait until no task runs the steps of `handle`, so its RAPS process
+    /// was killed and reaped and nothing writes to its temporary directory
+    async fn wait_for_run_task(&self, handle: &ExecutionHandle) {
+        drop(self.claim_run_task(handle).await);
+    }
+
+    /// Finish a task running `handle`: its locks stay held while the run is
+    /// paused for a later task to resume, and are released otherwise
+    async fn end_run_task(&self, handle: &ExecutionHandle, locks: LockGuard) {
+        if self.has_status(handle, ExecutionStatus::Paused).await {
+            locks.keep();
+        }
+    }
+
+    /// Fail an execution on a failed step: record the step, run the failure
+    /// hooks and report the failure
+    async fn fail_execution(
+        &self,
+        handle: &ExecutionHandle,
+        step: &ExecutionStep,
+        step_result: StepResult,
+        error: ExecutionError,
+    ) {
+        {
+            let mut executions = self.active_executions.write().await;
+            if let Some(execution_state) = executions.get_mut(handle) {
+                execution_state.status = ExecutionStatus::Failed;
+                execution_state.completed_steps.push(step_result);
+            }
+        }
+
+        // Hooks run before the failure is reported, so a CLI run that
+        // exits on the update doesn't cut them short
+        self.run_failure_hooks(handle, Some(step), &error.message).await;
+        self.run_cleanup(handle, false).await;
+        self.finalize_temp_dir(handle).await;
+
+        self.events.send(ExecutionUpdate::Failed {
+            handle: handle.clone(),
+            error,
+        });
+    }
+
+    /// Check an execution every watchdog interval until it finishes,
+    /// publishing a `Stalled` update when it stops making progress
+    async fn watch_execution(&self, handle: &ExecutionHandle) {
+        loop {
+            tokio::time::sleep(self.watchdog.interval()).await;
+            match self.check_health(handle, self.watchdog.threshold()).await {
+                HealthCheck::Finished => return,
+                HealthCheck::Healthy => {},
+                HealthCheck::Stalled(report) => {
+                    warn!("Execution {} of {} stalled: {}", handle.id, handle.workflow_id, report.summary());
+                    self.events.send(ExecutionUpdate::Stalled {
+                        handle: handle.clone(),
+                        report,
+                    });
+                },
+            }
+        }
+    }
+
+    /// Check whether a running execution went `threshold` without progress
+    ///
+    /// A stall is reported once; the execution is reported again only if it
+    /// makes progress and then stalls anew.
+    pub async fn check_health(&self, handle: &ExecutionHandle, threshold: std::time::Duration) -> HealthCheck {
+        let mut executions = self.active_executions.write().await;
+        let Some(state) = executions.get_mut(handle) else {
+            return HealthCheck::Finished;
+        };
+        match state.status {
+            ExecutionStatus::Running => {},
+            ExecutionStatus::Pending | ExecutionStatus::Paused => return HealthCheck::Healthy,
+            _ => return HealthCheck::Finished,
+        }
+
+        let idle_for = state.activity.idle_for();
+        if idle_for < threshold {
+            state.stalled = false;
+            return HealthCheck::Healthy;
+        }
+        if state.stalled {
+            return HealthCheck::Healthy;
+        }
+        state.stalled = true;
+        HealthCheck::Stalled(StallReport {
+            step_id: state.workflow.steps.get(state.current_step_index).map(|step| step.id.clone()),
+            command: state.current_command.clone(),
+            running_for: (Utc::now() - state.start_time).to_std().unwrap_or_default(),
+            idle_for,
+        })
+    }
+
+    /// Fail a (stalled) execution: kill its running command, clean up after
+    /// it and report it failed with `reason`
+    pub async fn force_fail(&self, handle: &ExecutionHandle, reason: &str) -> Result<()> {
+        let (previous, step) = {
+            let mut executions = self.active_executions.write().await;
+            let state = executions
+                .get_mut(handle)
+                .ok_or_else(|| anyhow::anyhow!("Execution not found"))?;
+            if !matches!(
+                state.status,
+                ExecutionStatus::Pending | ExecutionStatus::Running | ExecutionStatus::Paused
+            ) {
+                anyhow::bail!("Execution already finished");
+            }
+            let previous = std::mem::replace(&mut state.status, ExecutionStatus::Failed);
+            state.cancel.notify_one();
+            (previous, state.workflow.steps.get(state.current_step_index).cloned())
+        };
+
+        warn!("Execution {} of {} force-failed: {}", handle.id, handle.workflow_id, reason);
+        self.run_failure_hooks(handle, step.as_ref(), reason).await;
+        self.wait_for_run_task(handle).await;
+        // A paused run has no task left to release its locks; a running one
+        // released them when its task ended
+        if previous == ExecutionStatus::Paused {
+            self.locks.release(handle);
+        }
+        self.run_cleanup(handle, false).await;
+        self.finalize_temp_dir(handle).await;
+        if let Some(metrics) = &self.metrics {
+            metrics.record_run(&handle.workflow_id, RunOutcome::Failure, None);
+        }
+        let mut error = ExecutionError::new(reason.to_string());
+        error.failed_step = step.map(|step| step.id);
+        self.events.send(ExecutionUpdate::Failed {
+            handle: handle.clone(),
+            error,
+        });
+        Ok(())
+    }
+
     /// Get execution progress for a workflow
     pub async fn get_execution_progress(
         &self,
@@ -307,12 +950,17 @@ impl WorkflowExecutor {
                 None
             };
 
-        let progress_percent = if execution_state.workflow.steps.is_empty() {
-            1.0
-        } else {
-            execution_state.completed_steps.len() as f32
-                / execution_state.workflow.steps.len() as f32
-        };
+        // Weighted by expected step durations, so long steps count for more,
+        // over the steps this run includes and hasn't skipped
+        let workflow = &execution_state.workflow;
+        let index_of = |result: &StepResult| workflow.steps.iter().position(|step| step.id == result.step_id);
+        let is_skipped =
+            |result: &&StepResult| matches!(result.status, ExecutionStatus::Skipped | ExecutionStatus::AlreadyExists);
+        let skipped: Vec<usize> = execution_state.completed_steps.iter().filter(is_skipped).filter_map(index_of).collect();
+        let done: Vec<usize> = execution_state.completed_steps.iter().filter_map(index_of).collect();
+        let mut runs = workflow.run_steps(None, execution_state.context.options.start_at_step.as_deref());
+        runs.retain(|index| !skipped.contains(index));
+        let progress_percent = workflow.run_progress_fraction(&runs, &done);
 
         // Estimate remaining time based on completed steps and their durations
         let estimated_remaining = self.estimate_remaining_time(execution_state);
@@ -330,42 +978,154 @@ impl WorkflowExecutor {
 
     /// Cancel a workflow execution
     pub async fn cancel_execution(&self, handle: &ExecutionHandle) -> Result<()> {
-        let mut executions = self.active_executions.write().await;
-        if let Some(execution_state) = executions.get_mut(handle) {
-            execution_state.status = ExecutionStatus::Cancelled;
+        let previous = {
+            let mut executions = self.active_executions.write().await;
+            executions.get_mut(handle).map(|execution_state| {
+                // Stores a permit if no command is running yet
+                execution_state.cancel.notify_one();
+                std::mem::replace(&mut execution_state.status, ExecutionStatus::Cancelled)
+            })
+        };
 
-            if let Some(sender) = &self.progress_sender {
-                let _ = sender.send(ExecutionUpdate::Cancelled {
-                    handle: handle.clone(),
-                });
+        if let Some(previous) = previous {
+            // A paused run has no task left to release its locks; a running
+            // one releases them once its command was stopped
+            if previous == ExecutionStatus::Paused {
+                self.locks.release(handle);
+            }
+            self.wait_for_run_task(handle).await;
+            self.finalize_temp_dir(handle).await;
+
+            if let Some(metrics) = &self.metrics {
+                metrics.record_run(&handle.workflow_id, RunOutcome::Cancelled, None);
             }
+
+            self.events.send(ExecutionUpdate::Cancelled {
+                handle: handle.clone(),
+            });
         }
         Ok(())
     }
 
-    /// Resume a paused execution (interactive mode)
+    /// Cancel every execution that hasn't finished, returning their handles
+    pub async fn cancel_all(&self) -> Result<Vec<ExecutionHandle>> {
+        let unfinished: Vec<ExecutionHandle> = {
+            let executions = self.active_executions.read().await;
+            executions
+                .iter()
+                .filter(|(_, state)| {
+                    matches!(
+                        state.status,
+                        ExecutionStatus::Pending | ExecutionStatus::Running | ExecutionStatus::Paused
+                    )
+                })
+                .map(|(handle, _)| handle.clone())
+                .collect()
+        };
+        for handle in &unfinished {
+            self.cancel_execution(handle).await?;
+        }
+        Ok(unfinished)
+    }
+
+    /// Pause an execution once its current step finishes, whether or not it
+    /// runs in interactive mode; [`WorkflowExecutor::resume_execution`]
+    /// continues it
+    pub async fn pause_execution(&self, handle: &ExecutionHandle) -> Result<()> {
+        let mut executions = self.active_executions.write().await;
+        let execution_state = executions
+            .get_mut(handle)
+            .ok_or_else(|| anyhow::anyhow!("Execution not found"))?;
+        match execution_state.status {
+            ExecutionStatus::Pending | ExecutionStatus::Running => {
+                execution_state.pause_requested = true;
+                Ok(())
+            },
+            ExecutionStatus::Paused => Ok(()),
+            _ => anyhow::bail!("Execution already finished"),
+        }
+    }
+
+    /// Resume a paused execution
     pub async fn resume_execution(&self, handle: &ExecutionHandle) -> Result<()> {
         let mut executions = self.active_executions.write().await;
         if let Some(execution_state) = executions.get_mut(handle) {
+            if execution_state.pending_failure.is_some() {
+                anyhow::bail!("A failed step is waiting for a retry, skip or abort decision");
+            }
+            // A pause requested while the step ran is withdrawn
+            execution_state.pause_requested = false;
             if execution_state.status == ExecutionStatus::Paused {
                 execution_state.status = ExecutionStatus::Running;
+                // Time spent paused is not a stall
+                execution_state.activity.touch();
+                self.events.send(ExecutionUpdate::Resumed { handle: handle.clone() });
+                self.continue_execution(handle);
+            }
+        }
+        Ok(())
+    }
 
-                // Continue execution in background
-                let executor = self.clone();
-                let execution_handle = handle.clone();
-                tokio::spawn(async move {
-                    if let Err(e) = executor
-                        .run_workflow_execution(execution_handle.clone())
-                        .await
-                    {
-                        error!("Workflow execution failed after resume: {}", e);
-                    }
-                });
+    /// Retry, skip or abort the failed step an execution is holding on
+    pub async fn decide_step_failure(&self, handle: &ExecutionHandle, decision: StepDecision) -> Result<()> {
+        let pending = {
+            let mut executions = self.active_executions.write().await;
+            let execution_state = executions
+                .get_mut(handle)
+                .ok_or_else(|| anyhow::anyhow!("Execution not found"))?;
+            let mut pending = execution_state
+                .pending_failure
+                .take()
+                .ok_or_else(|| anyhow::anyhow!("No failed step is waiting for a decision"))?;
+            match decision {
+                StepDecision::Retry => {},
+                StepDecision::Skip => {
+                    pending.result.status = ExecutionStatus::Skipped;
+                    execution_state.completed_steps.push(pending.result.clone());
+                    execution_state.current_step_index += 1;
+                },
+                StepDecision::Abort => {
+                    drop(executions);
+                    self.fail_execution(handle, &pending.step, pending.result, pending.error).await;
+                    // The held run has no task left to release its locks
+                    self.locks.release(handle);
+                    return Ok(());
+                },
             }
+            execution_state.status = ExecutionStatus::Running;
+            execution_state.activity.touch();
+            pending
+        };
+
+        info!("Step {} of {}: {:?} after failure", pending.step.id, handle.workflow_id, decision);
+        if decision == StepDecision::Skip {
+            self.events.send(ExecutionUpdate::StepCompleted {
+                handle: handle.clone(),
+                result: pending.result,
+            });
         }
+        self.events.send(ExecutionUpdate::Resumed { handle: handle.clone() });
+        self.continue_execution(handle);
         Ok(())
     }
 
+    /// Run the rest of an execution in the background
+    fn continue_execution(&self, handle: &ExecutionHandle) {
+        let executor = self.clone();
+        let execution_handle = handle.clone();
+        tokio::spawn(async move {
+            let _running = executor.claim_run_task(&execution_handle).await;
+            let locks = LockGuard::new(Arc::clone(&executor.locks), execution_handle.clone());
+            if let Err(e) = executor
+                .run_workflow_execution(execution_handle.clone())
+                .await
+            {
+                error!("Workflow execution failed after resume: {}", e);
+            }
+            executor.end_run_task(&execution_handle, locks).await;
+        }.instrument(workflow_span(handle)));
+    }
+
     /// Run the workflow execution loop
     async fn run_workflow_execution(&self, handle: ExecutionHandle) -> Result<()> {
         loop {
@@ -378,7 +1138,12 @@ impl WorkflowExecutor {
                 match execution_state.status {
                     ExecutionStatus::Cancelled => return Ok(()),
                     ExecutionStatus::Paused => return Ok(()),
-                    ExecutionStatus::Completed | ExecutionStatus::Failed => return Ok(()),
+                    ExecutionStatus::Completed
+                    | ExecutionStatus::Failed
+                    | ExecutionStatus::Skipped
+                    | ExecutionStatus::AlreadyExists => {
+                        return Ok(())
+                    },
                     ExecutionStatus::Running => {
                         if execution_state.current_step_index
                             >= execution_state.workflow.steps.len()
@@ -403,28 +1168,30 @@ impl WorkflowExecutor {
             }
 
             if let Some(step) = next_step {
-                // Check if we should pause in interactive mode
+                // Pause between steps in interactive mode, or when asked to;
+                // a resumed execution doesn't pause again before the same step
                 let should_pause = {
-                    let executions = self.active_executions.read().await;
-                    let execution_state = executions.get(&handle).unwrap();
-                    execution_state.context.options.interactive
-                        && execution_state.current_step_index > 0
+                    let mut executions = self.active_executions.write().await;
+                    let execution_state = executions.get_mut(&handle).unwrap();
+                    let index = execution_state.current_step_index;
+                    let pause = execution_state.pause_requested
+                        || (execution_state.context.options.interactive
+                            && index > 0
+                            && execution_state.paused_at != Some(index));
+                    if pause {
+                        execution_state.status = ExecutionStatus::Paused;
+                        execution_state.pause_requested = false;
+                        execution_state.paused_at = Some(index);
+                    }
+                    pause
                 };
 
                 if should_pause {
-                    // Pause for user confirmation
-                    {
-                        let mut executions = self.active_executions.write().await;
-                        let execution_state = executions.get_mut(&handle).unwrap();
-                        execution_state.status = ExecutionStatus::Paused;
-                    }
 
-                    if let Some(sender) = &self.progress_sender {
-                        let _ = sender.send(ExecutionUpdate::Paused {
-                            handle: handle.clone(),
-                            next_step: step,
-                        });
-                    }
+                    self.events.send(ExecutionUpdate::Paused {
+                        handle: handle.clone(),
+                        next_step: step,
+                    });
                     return Ok(());
                 }
 
@@ -435,55 +1202,241 @@ impl WorkflowExecutor {
     }
 
     /// Execute a single workflow step
+    #[tracing::instrument(
+        name = "step",
+        skip_all,
+        fields(
+            workflow_id = %handle.workflow_id,
+            step_id = %step.id,
+            exit_code = tracing::field::Empty,
+        )
+    )]
     async fn execute_step(&self, handle: &ExecutionHandle, step: &ExecutionStep) -> Result<()> {
         let mut step = step.clone();
 
-        // Resolve placeholders in command
+        // Resolve placeholders in command, environment and working directory
+        let mut overrides = InvocationOverrides {
+            step_id: Some(step.id.clone()),
+            ..Default::default()
+        };
+        if self.stream_output {
+            let (events, handle, step_id) = (self.events.clone(), handle.clone(), step.id.clone());
+            overrides.output = Some(OutputTap::new(move |line, stderr| {
+                events.send(ExecutionUpdate::StepOutput {
+                    handle: handle.clone(),
+                    step_id: step_id.clone(),
+                    line: redact::redact_text(line),
+                    stderr,
+                })
+            }));
+        }
+        let mut undefined = Vec::new();
+        let mut rewrites = NameRewrites::default();
         {
             let mut executions = self.active_executions.write().await;
             if let Some(state) = executions.get_mut(handle) {
-                self.resolve_command_placeholders(&mut step.command, &state.placeholders)?;
-                for cleanup in &mut step.cleanup_commands {
-                    self.resolve_command_placeholders(cleanup, &state.placeholders)?;
+                rewrites = state.name_rewrites.clone();
+                overrides.profile = step.profile.clone().or_else(|| state.workflow.profile.clone());
+                self.resolve_command_placeholders(&mut step.command, &state.placeholders, &mut undefined)?;
+                if let Some(region) = self.region {
+                    region::apply_default(&mut step.command, region);
+                }
+                for (name, value) in &step.env {
+                    overrides.environment.insert(
+                        name.clone(),
+                        placeholders::resolve(value, &state.placeholders, &mut undefined),
+                    );
+                }
+                if let Some(dir) = &step.working_dir {
+                    let dir = placeholders::resolve(&dir.to_string_lossy(), &state.placeholders, &mut undefined);
+                    overrides.working_dir = Some(state.context.temp_dir.join(dir));
                 }
             }
         }
+        if let Some(name) = undefined.first() {
+            if self.strict_placeholders {
+                anyhow::bail!("Undefined placeholder '{}' in step {}", name, step.id);
+            }
+            warn!("Undefined placeholders in step {}: {}", step.id, undefined.join(", "));
+        }
 
-        info!("Executing step: {} - {}", step.id, step.name);
-
-        // Send step started update
-        if let Some(sender) = &self.progress_sender {
-            let _ = sender.send(ExecutionUpdate::StepStarted {
-                handle: handle.clone(),
-                step: step.clone(),
-            });
+        // Placeholders may have produced names the policy does not allow;
+        // cleanup commands are resolved and checked when cleanup runs
+        let notes = self
+            .policy
+            .enforce_naming(&mut step.command, &mut rewrites)
+            .with_context(|| format!("Step '{}' blocked by naming policy", step.id))?;
+        for note in notes {
+            warn!("Step {}: {}", step.id, note);
+        }
+        self.policy
+            .check_command(&step.command)
+            .with_context(|| format!("Step '{}' blocked by policy", step.id))?;
+        if !rewrites.is_empty() {
+            self.record_rewrites(handle, rewrites).await;
         }
 
-        let start_time = Utc::now();
+        // Secrets come from the environment, keyring or secrets file, never the YAML
+        let secrets = self
+            .secrets
+            .resolve_all(&step.secrets)
+            .with_context(|| format!("Failed to resolve secrets for step '{}'", step.id))?;
+        secrets.values().for_each(|value| redact::register_secret(value));
+        overrides.environment.extend(secrets);
 
-        // Execute the RAPS command
-        let command_result = self
-            .raps_client
-            .execute_command_async(&step.command)
-            .await?;
+        if let Some(dir) = &overrides.working_dir {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .with_context(|| format!("Failed to create working directory: {:?}", dir))?;
+        }
+
+        info!("Executing step: {} - {}", step.id, step.name);
+        crate::utils::crash::set_active_step(&handle.workflow_id, &step.id);
+
+        // Send step started update
+        self.events.send(ExecutionUpdate::StepStarted {
+            handle: handle.clone(),
+            step: step.clone(),
+        });
+
+        let step_vars = [("step_id", step.id.as_str())];
+        self.run_hooks(handle, HookEvent::StepStart(step.id.clone()), &step.hooks.on_start, &step_vars)
+            .await;
+
+        let start_time = Utc::now();
+        let cancel = {
+            let mut executions = self.active_executions.write().await;
+            executions.get_mut(handle).map(|state| {
+                state.activity.touch();
+                state.current_command = Some(self.raps_client.display_command_line(&step.command));
+                Arc::clone(&state.cancel)
+            })
+        }
+        .unwrap_or_default();
+
+        // Idempotent steps: the check's output stands in for the command's
+        let already_exists = self.check_skip_condition(handle, &step, &overrides).await;
+        let skipped = already_exists.is_some();
+        let command_result = match already_exists {
+            Some(result) => Some(result),
+            None => self.run_step_command(handle, &step, &overrides, &cancel).await?,
+        };
+        let Some(mut command_result) = command_result else {
+            return Ok(());
+        };
+
+        // Tokens expiring mid-demo are the most common failure: refresh and
+        // retry once. A permission failure would fail again, after a create
+        // or upload had run twice.
+        if self.auto_reauth
+            && !matches!(step.command, RapsCommand::Auth { .. })
+            && ApsErrorKind::is_token_failure(&command_result)
+        {
+            warn!("Step {} failed with an expired or invalid token, refreshing it", step.id);
+            self.send_step_progress(handle, &step.id, "Token expired, refreshing and retrying");
+            if self.refresh_auth(overrides.profile.as_deref()).await {
+                let Some(retried) = self.run_step_command(handle, &step, &overrides, &cancel).await? else {
+                    return Ok(());
+                };
+                command_result = retried;
+            }
+        }
+
+        // APS throttles bursts: wait as long as it asks (plus jitter) and retry
+        let limits = &self.raps_client.config().rate_limit;
+        let mut attempt = 0;
+        while attempt < limits.max_retries
+            && ApsErrorKind::from_result(&command_result) == Some(ApsErrorKind::RateLimit)
+        {
+            attempt += 1;
+            let delay = limits.retry_delay(rate_limit::retry_after(&command_result), attempt);
+            self.raps_client.rate_limiter().pause_for(delay);
+            warn!("Step {} was rate limited, retrying in {:?}", step.id, delay);
+            self.send_step_progress(
+                handle,
+                &step.id,
+                &format!(
+                    "Waiting for rate limit ({}s, retry {}/{})",
+                    delay.as_secs(),
+                    attempt,
+                    limits.max_retries
+                ),
+            );
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {},
+                _ = cancel.notified() => {
+                    warn!("Step {} cancelled while waiting for the rate limit", step.id);
+                    return Ok(());
+                },
+            }
+            let Some(retried) = self.run_step_command(handle, &step, &overrides, &cancel).await? else {
+                return Ok(());
+            };
+            command_result = retried;
+        }
 
         let end_time = Utc::now();
         let _duration = end_time.signed_duration_since(start_time);
+        tracing::Span::current().record("exit_code", command_result.exit_code);
+
+        // Remember where this step's artifacts will be collected from
+        if !step.artifacts.is_empty() {
+            let mut executions = self.active_executions.write().await;
+            if let Some(state) = executions.get_mut(handle) {
+                let base_dir = overrides
+                    .working_dir
+                    .clone()
+                    .unwrap_or_else(|| state.context.temp_dir.clone());
+                state.artifact_specs.push(ArtifactSpec {
+                    step_id: step.id.clone(),
+                    base_dir,
+                    patterns: step.artifacts.clone(),
+                });
+            }
+        }
+
+        // Nothing kept or shown carries credentials; outputs were taken from the JSON
+        command_result.stdout = redact::redact_text(&command_result.stdout);
+        command_result.stderr = redact::redact_text(&command_result.stderr);
+
+        // Large outputs keep their head and tail; the rest goes to a file
+        let (stdout, stderr, full_output) = output::run_outputs_dir(handle)
+            .and_then(|dir| {
+                output::limit_step_output(
+                    &dir,
+                    &step.id,
+                    &command_result.stdout,
+                    &command_result.stderr,
+                    self.max_output_bytes,
+                )
+            })
+            .unwrap_or_else(|e| {
+                warn!("Failed to save the full output of step {}: {:#}", step.id, e);
+                (
+                    output::truncate_head_tail(&command_result.stdout, self.max_output_bytes, None),
+                    output::truncate_head_tail(&command_result.stderr, self.max_output_bytes, None),
+                    Vec::new(),
+                )
+            });
 
         // Create step result
         let step_result = StepResult {
             step_id: step.id.clone(),
-            status: if command_result.success {
+            status: if skipped {
+                ExecutionStatus::AlreadyExists
+            } else if command_result.success {
                 ExecutionStatus::Completed
             } else {
                 ExecutionStatus::Failed
             },
             start_time,
             end_time: Some(end_time),
-            stdout: command_result.stdout.clone(),
-            stderr: command_result.stderr.clone(),
+            stdout,
+            stderr,
             exit_code: Some(command_result.exit_code),
             created_resources: Vec::new(), // TODO: Parse resources from command output
+            full_output,
+            json_output: command_result.json_output.clone(),
         };
 
         // Handle command failure
@@ -496,52 +1449,123 @@ impl WorkflowExecutor {
                 recovery_suggestions,
             );
 
-            // Update execution state to failed
-            {
-                let mut executions = self.active_executions.write().await;
-                if let Some(execution_state) = executions.get_mut(handle) {
-                    execution_state.status = ExecutionStatus::Failed;
-                    execution_state.completed_steps.push(step_result.clone());
-                }
+            if let Some(metrics) = &self.metrics {
+                metrics.record_step_failure(&handle.workflow_id, &step.id);
             }
 
-            if let Some(sender) = &self.progress_sender {
-                let _ = sender.send(ExecutionUpdate::Failed {
+            // In interactive (or ask-on-failure) runs the user decides what
+            // happens next; the execution holds until then
+            let held = {
+                let mut executions = self.active_executions.write().await;
+                match executions.get_mut(handle) {
+                    Some(execution_state)
+                        if execution_state.context.options.interactive
+                            || execution_state.context.options.ask_on_failure =>
+                    {
+                        execution_state.status = ExecutionStatus::Paused;
+                        execution_state.pending_failure = Some(PendingFailure {
+                            step: step.clone(),
+                            result: step_result.clone(),
+                            error: error.clone(),
+                        });
+                        true
+                    },
+                    _ => false,
+                }
+            };
+            if held {
+                warn!("Step {} failed, waiting for a retry/skip/abort decision", step.id);
+                self.events.send(ExecutionUpdate::StepFailed {
                     handle: handle.clone(),
+                    step,
                     error,
                 });
+                return Ok(());
             }
 
+            self.fail_execution(handle, &step, step_result, error).await;
             return Err(anyhow::anyhow!("Step failed: {}", step.id));
         }
 
         // Update execution state
-        {
+        let captured = {
             let mut executions = self.active_executions.write().await;
+            let mut captured = Vec::new();
             if let Some(execution_state) = executions.get_mut(handle) {
                 // Capture JSON outputs into placeholders
                 if let Some(json) = &command_result.json_output {
-                    self.capture_json_outputs(json, &step.id, &mut execution_state.placeholders);
+                    captured = self.capture_json_outputs(json, &step.id, &mut execution_state.placeholders);
                 }
 
                 execution_state.completed_steps.push(step_result.clone());
                 execution_state.current_step_index += 1;
+                execution_state.activity.touch();
+                execution_state.current_command = None;
             }
-        }
+            captured
+        };
 
-        // Send step completed update
-        if let Some(sender) = &self.progress_sender {
-            let _ = sender.send(ExecutionUpdate::StepCompleted {
+        if !captured.is_empty() {
+            self.events.send(ExecutionUpdate::VariablesUpdated {
                 handle: handle.clone(),
-                result: step_result,
+                source: VariableSource::StepOutput(step.id.clone()),
+                values: captured,
             });
         }
 
+        // Send step completed update
+        self.events.send(ExecutionUpdate::StepCompleted {
+            handle: handle.clone(),
+            result: step_result,
+        });
+
+        let event = HookEvent::StepComplete(step.id.clone());
+        self.run_hooks(handle, event.clone(), &step.hooks.on_complete, &step_vars).await;
+        let workflow_hooks = self.workflow_hooks(handle, |h| h.on_step_complete.clone()).await;
+        self.run_hooks(handle, event, &workflow_hooks, &step_vars).await;
+
         Ok(())
     }
 
+    /// Recorded outputs of the runs named by the workflow's `uses_outputs:`
+    /// and the run's `from_outputs`, later references overriding earlier ones
+    fn imported_outputs(&self, workflow: &WorkflowDefinition, options: &ExecutionOptions) -> Result<HashMap<String, String>> {
+        let mut imported = HashMap::new();
+        for reference in workflow.uses_outputs.iter().chain(&options.from_outputs) {
+            let reference: OutputsRef = reference.parse()?;
+            let history = self
+                .history
+                .as_ref()
+                .and_then(|history| history.read().ok())
+                .ok_or_else(|| anyhow::anyhow!("No run history to take the outputs of {} from", reference))?;
+            let recorded = history.outputs(&reference).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No recorded outputs for {}; run {} successfully first",
+                    reference,
+                    reference.workflow_id
+                )
+            })?;
+            imported.extend(recorded.values.clone());
+        }
+        Ok(imported)
+    }
+
     /// Complete workflow execution
     async fn complete_workflow_execution(&self, handle: &ExecutionHandle) -> Result<()> {
+        let success = {
+            let executions = self.active_executions.read().await;
+            let execution_state = executions
+                .get(handle)
+                .ok_or_else(|| anyhow::anyhow!("Execution not found"))?;
+            execution_state.completed_steps.iter().all(|s| {
+                matches!(
+                    s.status,
+                    ExecutionStatus::Completed | ExecutionStatus::Skipped | ExecutionStatus::AlreadyExists
+                )
+            })
+        };
+        let cleanup_results = self.run_cleanup(handle, success).await;
+
         let execution_result = {
             let mut executions = self.active_executions.write().await;
             let execution_state = executions
@@ -553,30 +1577,413 @@ impl WorkflowExecutor {
             let end_time = Utc::now();
             let duration = end_time.signed_duration_since(execution_state.start_time);
 
+            // Declared outputs, from the run's final placeholders; outputs
+            // using undefined placeholders are left out
+            let mut outputs = BTreeMap::new();
+            for (name, template) in &execution_state.workflow.outputs {
+                let mut undefined = Vec::new();
+                let value = placeholders::resolve(template, &execution_state.placeholders, &mut undefined);
+                if undefined.is_empty() {
+                    outputs.insert(name.clone(), value);
+                } else {
+                    warn!("Output '{}' uses undefined placeholders: {}", name, undefined.join(", "));
+                }
+            }
+
             ExecutionResult {
                 workflow_id: execution_state.workflow.metadata.id.clone(),
-                success: execution_state
-                    .completed_steps
-                    .iter()
-                    .all(|s| s.status == ExecutionStatus::Completed),
+                success,
                 duration: chrono::Duration::from_std(duration.to_std().unwrap_or_default())
                     .unwrap_or_default(),
                 steps_completed: execution_state.completed_steps.len(),
                 total_steps: execution_state.workflow.steps.len(),
                 resources_created: execution_state.created_resources.clone(),
-                cleanup_performed: false, // TODO: Implement cleanup
+                cleanup_performed: !cleanup_results.is_empty(),
+                cleanup_results,
                 step_results: execution_state.completed_steps.clone(),
+                outputs,
+            }
+        };
+
+        if let Some(metrics) = &self.metrics {
+            let outcome = if execution_result.success {
+                RunOutcome::Success
+            } else {
+                RunOutcome::Failure
+            };
+            let secs = execution_result.duration.num_milliseconds() as f64 / 1000.0;
+            metrics.record_run(&execution_result.workflow_id, outcome, Some(secs));
+            if execution_result.cleanup_performed {
+                metrics.record_cleanup(&execution_result.workflow_id);
+            }
+        }
+
+        // Mock runs say nothing about how long the real commands take
+        if let Some(history) = self.history.as_ref().filter(|_| !self.raps_client.is_mock()) {
+            if let Ok(mut history) = history.write() {
+                history.record(&execution_result);
+                if let Err(e) = history.save() {
+                    warn!("Failed to save duration history: {:#}", e);
+                }
+            }
+        }
+
+        if execution_result.success {
+            let hooks = self.workflow_hooks(handle, |h| h.on_success.clone()).await;
+            self.run_hooks(handle, HookEvent::WorkflowSuccess, &hooks, &[]).await;
+        }
+        self.finalize_temp_dir(handle).await;
+
+        self.events.send(ExecutionUpdate::Completed {
+            handle: handle.clone(),
+            result: execution_result,
+        });
+
+        Ok(())
+    }
+
+    /// Run the cleanup of a finished execution if its options ask for it:
+    /// the workflow's `cleanup` after a successful run, or the cleanup
+    /// commands of the steps that completed, newest first, after a failed
+    /// one. Commands are resolved only now, so they can use the outputs of
+    /// any step that ran; a failing command doesn't stop the others.
+    async fn run_cleanup(&self, handle: &ExecutionHandle, succeeded: bool) -> Vec<CleanupResult> {
+        let (commands, placeholders, rewrites) = {
+            let executions = self.active_executions.read().await;
+            let Some(state) = executions.get(handle).filter(|state| state.context.options.auto_cleanup) else {
+                return Vec::new();
+            };
+            let workflow = &state.workflow;
+            let commands: Vec<(Option<&ExecutionStep>, RapsCommand)> = if succeeded {
+                workflow.cleanup.iter().map(|command| (None, command.clone())).collect()
+            } else {
+                state
+                    .completed_steps
+                    .iter()
+                    .rev()
+                    .filter(|result| result.status == ExecutionStatus::Completed)
+                    .filter_map(|result| workflow.steps.iter().find(|step| step.id == result.step_id))
+                    .flat_map(|step| step.cleanup_commands.iter().map(move |command| (Some(step), command.clone())))
+                    .collect()
+            };
+            let commands: Vec<_> = commands
+                .into_iter()
+                .map(|(step, command)| {
+                    let overrides = InvocationOverrides {
+                        profile: step.and_then(|step| step.profile.clone()).or_else(|| workflow.profile.clone()),
+                        ..Default::default()
+                    };
+                    (step.map(|step| step.id.clone()), overrides, command)
+                })
+                .collect();
+            (commands, state.placeholders.clone(), state.name_rewrites.clone())
+        };
+        if commands.is_empty() {
+            return Vec::new();
+        }
+
+        info!("Running {} cleanup commands for {}", commands.len(), handle.workflow_id);
+        let mut results = Vec::new();
+        for (step_id, overrides, mut command) in commands {
+            let outcome = self.run_extra_command(&mut command, &placeholders, &rewrites, &overrides).await;
+            let error = match outcome {
+                Ok(result) if result.success => None,
+                Ok(result) => Some(format!(
+                    "exit code {}: {}",
+                    result.exit_code,
+                    result.error_message().unwrap_or_default()
+                )),
+                Err(e) => Some(format!("{:#}", e)),
+            };
+            let command = self.raps_client.display_command_line(&command);
+            match &error {
+                None => info!("Cleanup succeeded: {}", command),
+                Some(error) => warn!("Cleanup failed: {}: {}", command, error),
             }
+            results.push(CleanupResult {
+                step_id,
+                command,
+                success: error.is_none(),
+                error,
+            });
+        }
+
+        self.events.send(ExecutionUpdate::CleanupCompleted {
+            handle: handle.clone(),
+            results: results.clone(),
+        });
+        results
+    }
+
+    /// Collect the run's artifacts, then remove the execution's temporary
+    /// directory unless it should be kept
+    async fn finalize_temp_dir(&self, handle: &ExecutionHandle) {
+        let (specs, temp_dir, keep) = {
+            let mut executions = self.active_executions.write().await;
+            let Some(state) = executions.get_mut(handle) else {
+                return;
+            };
+            (
+                std::mem::take(&mut state.artifact_specs),
+                state.context.temp_dir.clone(),
+                state.context.options.keep_temp_dir,
+            )
         };
 
-        if let Some(sender) = &self.progress_sender {
-            let _ = sender.send(ExecutionUpdate::Completed {
+        if !specs.is_empty() {
+            self.collect_run_artifacts(handle, specs).await;
+        }
+        if keep {
+            return;
+        }
+
+        match tokio::fs::remove_dir_all(&temp_dir).await {
+            Ok(()) => tracing::debug!("Removed temporary directory {:?}", temp_dir),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {},
+            Err(e) => warn!("Failed to remove temporary directory {:?}: {}", temp_dir, e),
+        }
+    }
+
+    /// Copy declared artifacts into the run's artifacts folder and report them
+    async fn collect_run_artifacts(&self, handle: &ExecutionHandle, specs: Vec<ArtifactSpec>) {
+        let directory = match run_artifacts_dir(handle) {
+            Ok(directory) => directory,
+            Err(e) => {
+                warn!("Cannot collect artifacts: {:#}", e);
+                return;
+            },
+        };
+
+        let dest = directory.clone();
+        let collected = tokio::task::spawn_blocking(move || collect_artifacts(&specs, &dest)).await;
+        match collected {
+            Ok(Ok(files)) if files.is_empty() => info!("No artifacts matched for {}", handle.workflow_id),
+            Ok(Ok(files)) => {
+                info!("Collected {} artifacts into {:?}", files.len(), directory);
+                self.events.send(ExecutionUpdate::ArtifactsCollected {
+                    handle: handle.clone(),
+                    directory,
+                    files,
+                });
+            },
+            Ok(Err(e)) => warn!("Failed to collect artifacts: {:#}", e),
+            Err(e) => warn!("Artifact collection task failed: {}", e),
+        }
+    }
+
+    /// Whether an execution currently has the given status
+    async fn has_status(&self, handle: &ExecutionHandle, status: ExecutionStatus) -> bool {
+        let executions = self.active_executions.read().await;
+        executions.get(handle).is_some_and(|state| state.status == status)
+    }
+
+    /// Select hook actions from the workflow being executed
+    async fn workflow_hooks<F>(&self, handle: &ExecutionHandle, select: F) -> Vec<HookAction>
+    where
+        F: FnOnce(&WorkflowHooks) -> Vec<HookAction>,
+    {
+        let executions = self.active_executions.read().await;
+        executions
+            .get(handle)
+            .map(|state| select(&state.workflow.hooks))
+            .unwrap_or_default()
+    }
+
+    /// Run the step-level and workflow-level failure hooks
+    async fn run_failure_hooks(&self, handle: &ExecutionHandle, step: Option<&ExecutionStep>, error: &str) {
+        let step_id = step.map(|s| s.id.as_str()).unwrap_or_default();
+        let vars = [("step_id", step_id), ("error", error)];
+        if let Some(step) = step {
+            let event = HookEvent::StepFailure(step.id.clone());
+            self.run_hooks(handle, event, &step.hooks.on_failure, &vars).await;
+        }
+        let hooks = self.workflow_hooks(handle, |h| h.on_failure.clone()).await;
+        self.run_hooks(handle, HookEvent::WorkflowFailure, &hooks, &vars).await;
+    }
+
+    /// Run hook actions in order, reporting each outcome.
+    ///
+    /// Hooks see the execution's placeholders plus `{workflow_id}`,
+    /// `{execution_id}`, `{event}` and the event-specific `extra` values.
+    /// A failing hook is logged and reported but does not stop the workflow.
+    async fn run_hooks(
+        &self,
+        handle: &ExecutionHandle,
+        event: HookEvent,
+        actions: &[HookAction],
+        extra: &[(&str, &str)],
+    ) {
+        if actions.is_empty() {
+            return;
+        }
+
+        // Command hooks of a step run as the step's profile, the others as the workflow's
+        let step_id = match &event {
+            HookEvent::StepStart(id) | HookEvent::StepComplete(id) | HookEvent::StepFailure(id) => Some(id),
+            HookEvent::WorkflowStart | HookEvent::WorkflowSuccess | HookEvent::WorkflowFailure => None,
+        };
+        let (mut placeholders, rewrites, profile) = {
+            let executions = self.active_executions.read().await;
+            executions
+                .get(handle)
+                .map(|state| {
+                    let step = step_id.and_then(|id| state.workflow.steps.iter().find(|step| &step.id == id));
+                    let profile = step
+                        .and_then(|step| step.profile.clone())
+                        .or_else(|| state.workflow.profile.clone());
+                    (state.placeholders.clone(), state.name_rewrites.clone(), profile)
+                })
+                .unwrap_or_default()
+        };
+        placeholders.insert("workflow_id".to_string(), handle.workflow_id.clone());
+        placeholders.insert("execution_id".to_string(), handle.id.to_string());
+        placeholders.insert("event".to_string(), event.to_string());
+        for (key, value) in extra {
+            placeholders.insert(key.to_string(), value.to_string());
+        }
+
+        for action in actions {
+            let outcome = match action {
+                HookAction::Http(hook) => match self.policy.check_hook(action) {
+                    Ok(()) => send_http_hook(hook, &placeholders).await,
+                    Err(e) => Err(e),
+                },
+                HookAction::Command(command) => {
+                    self.run_hook_command(command, &placeholders, &rewrites, profile.as_deref()).await
+                },
+            };
+            let description = action.describe();
+            let (success, message) = match outcome {
+                Ok(message) => {
+                    info!("Hook {} ({}) succeeded: {}", event, description, message);
+                    (true, message)
+                },
+                Err(e) => {
+                    warn!("Hook {} ({}) failed: {:#}", event, description, e);
+                    (false, format!("{:#}", e))
+                },
+            };
+
+            self.events.send(ExecutionUpdate::HookCompleted {
                 handle: handle.clone(),
-                result: execution_result,
+                event: event.clone(),
+                action: description,
+                success,
+                message,
             });
         }
+    }
 
-        Ok(())
+    /// Run the step's `skip_if` check; its result when it passes, meaning
+    /// the step's result is already in place. A failing or broken check lets
+    /// the step run.
+    async fn check_skip_condition(
+        &self,
+        handle: &ExecutionHandle,
+        step: &ExecutionStep,
+        overrides: &InvocationOverrides,
+    ) -> Option<CommandResult> {
+        let check = step.skip_if.as_ref()?;
+        let (placeholders, rewrites) = {
+            let executions = self.active_executions.read().await;
+            executions
+                .get(handle)
+                .map(|state| (state.placeholders.clone(), state.name_rewrites.clone()))
+                .unwrap_or_default()
+        };
+        let outcome = match check {
+            HookAction::Http(hook) => match self.policy.check_hook(check) {
+                Ok(()) => send_http_hook(hook, &placeholders)
+                    .await
+                    .map(|status| CommandResult::new(0, status, String::new(), std::time::Duration::ZERO)),
+                Err(e) => Err(e),
+            },
+            HookAction::Command(command) => {
+                let mut command = command.clone();
+                let mut undefined = Vec::new();
+                let resolved = self.resolve_command_placeholders(&mut command, &placeholders, &mut undefined);
+                rewrites.apply(&mut command);
+                match resolved {
+                    Ok(()) if !undefined.is_empty() => Err(anyhow::anyhow!("Undefined placeholder '{}'", undefined[0])),
+                    Ok(()) => match self.policy.check_command(&command) {
+                        Ok(()) => self.raps_client.execute_command_streaming(&command, overrides, |_| {}).await,
+                        Err(e) => Err(e),
+                    },
+                    Err(e) => Err(e),
+                }
+            },
+        };
+        match outcome {
+            Ok(result) if result.success => {
+                info!("Step {} skipped: skip_if check ({}) passed", step.id, check.describe());
+                Some(result)
+            },
+            Ok(_) => None,
+            Err(e) => {
+                warn!("skip_if check of step {} failed, running the step: {:#}", step.id, e);
+                None
+            },
+        }
+    }
+
+    /// Keep the names rewritten by a step for the rest of the run: later
+    /// commands naming the same resources are rewritten alike, and so are
+    /// placeholders holding an original name
+    async fn record_rewrites(&self, handle: &ExecutionHandle, rewrites: NameRewrites) {
+        let mut executions = self.active_executions.write().await;
+        if let Some(state) = executions.get_mut(handle) {
+            for (original, rewritten) in rewrites.iter() {
+                for value in state.placeholders.values_mut().filter(|value| *value == original) {
+                    *value = rewritten.to_string();
+                }
+            }
+            state.name_rewrites = rewrites;
+        }
+    }
+
+    /// Run a command hook through the RAPS client, as `profile`
+    async fn run_hook_command(
+        &self,
+        command: &RapsCommand,
+        placeholders: &HashMap<String, String>,
+        rewrites: &NameRewrites,
+        profile: Option<&str>,
+    ) -> Result<String> {
+        let mut command = command.clone();
+        let overrides = InvocationOverrides {
+            profile: profile.map(str::to_string),
+            ..Default::default()
+        };
+        let result = self.run_extra_command(&mut command, placeholders, rewrites, &overrides).await?;
+        if !result.success {
+            anyhow::bail!(
+                "exit code {}: {}",
+                result.exit_code,
+                result.error_message().unwrap_or_default()
+            );
+        }
+        Ok(format!("exit code {}", result.exit_code))
+    }
+
+    /// Resolve a hook or cleanup command in place, put it through the same
+    /// naming and policy checks as a step's command and run it
+    async fn run_extra_command(
+        &self,
+        command: &mut RapsCommand,
+        placeholders: &HashMap<String, String>,
+        rewrites: &NameRewrites,
+        overrides: &InvocationOverrides,
+    ) -> Result<CommandResult> {
+        let mut undefined = Vec::new();
+        self.resolve_command_placeholders(command, placeholders, &mut undefined)?;
+        if let (Some(name), true) = (undefined.first(), self.strict_placeholders) {
+            anyhow::bail!("Undefined placeholder '{}'", name);
+        }
+        for note in self.policy.enforce_naming(command, &mut rewrites.clone())? {
+            warn!("{}", note);
+        }
+        self.policy.check_command(command)?;
+        self.raps_client.execute_command_streaming(command, overrides, |_| {}).await
     }
 
     /// Resolve placeholders in a RAPS command
@@ -584,147 +1991,216 @@ impl WorkflowExecutor {
         &self,
         command: &mut RapsCommand,
         placeholders: &HashMap<String, String>,
+        undefined: &mut Vec<String>,
     ) -> Result<()> {
         let json = serde_json::to_value(&command)?;
-        let resolved_json = self.resolve_json_placeholders(json, placeholders);
+        let resolved_json = placeholders::resolve_json(json, placeholders, undefined);
         *command = serde_json::from_value(resolved_json)?;
         Ok(())
     }
 
-    /// Recursively resolve placeholders in JSON value
-    fn resolve_json_placeholders(
-        &self,
-        value: serde_json::Value,
-        placeholders: &HashMap<String, String>,
-    ) -> serde_json::Value {
-        match value {
-            serde_json::Value::String(s) => {
-                let mut resolved = s;
-                for (key, val) in placeholders {
-                    let pattern = format!("{{{}}}", key);
-                    resolved = resolved.replace(&pattern, val);
-                }
-                serde_json::Value::String(resolved)
-            },
-            serde_json::Value::Array(arr) => serde_json::Value::Array(
-                arr.into_iter()
-                    .map(|v| self.resolve_json_placeholders(v, placeholders))
-                    .collect(),
-            ),
-            serde_json::Value::Object(obj) => serde_json::Value::Object(
-                obj.into_iter()
-                    .map(|(k, v)| (k, self.resolve_json_placeholders(v, placeholders)))
-                    .collect(),
-            ),
-            _ => value,
-        }
-    }
-
-    /// Capture outputs from a JSON value into placeholders
+    /// Capture outputs from a JSON value into placeholders, returning the
+    /// captured `(key, value)` pairs
     fn capture_json_outputs(
         &self,
         json: &serde_json::Value,
         step_id: &str,
         placeholders: &mut HashMap<String, String>,
-    ) {
+    ) -> Vec<(String, String)> {
+        let mut captured = Vec::new();
         if let serde_json::Value::Object(map) = json {
             for (key, val) in map {
-                if let Some(s) = val.as_str() {
-                    // Store as global (last one wins)
-                    placeholders.insert(key.clone(), s.to_string());
-                    // Store as step-specific
-                    placeholders.insert(format!("{}.{}", step_id, key), s.to_string());
+                let value = if let Some(s) = val.as_str() {
+                    s.to_string()
                 } else if let Some(n) = val.as_f64() {
-                    placeholders.insert(key.clone(), n.to_string());
-                    placeholders.insert(format!("{}.{}", step_id, key), n.to_string());
+                    n.to_string()
                 } else if let Some(i) = val.as_i64() {
-                    placeholders.insert(key.clone(), i.to_string());
-                    placeholders.insert(format!("{}.{}", step_id, key), i.to_string());
-                }
+                    i.to_string()
+                } else {
+                    continue;
+                };
+                // Store as global (last one wins) and as step-specific
+                placeholders.insert(key.clone(), value.clone());
+                placeholders.insert(format!("{}.{}", step_id, key), value.clone());
+                placeholders.insert(format!("{}{}.{}", placeholders::STEPS_PREFIX, step_id, key), value.clone());
+                captured.push((key.clone(), value));
             }
         }
+        captured
     }
 
-    /// Generate recovery suggestions for failed commands
-    fn generate_recovery_suggestions(
+    /// Run a step's RAPS command, forwarding progress reported on its output;
+    /// `None` when the run is cancelled, which kills the RAPS process
+    async fn run_step_command(
         &self,
-        command: &RapsCommand,
-        result: &CommandResult,
-    ) -> Vec<String> {
-        let mut suggestions = Vec::new();
+        handle: &ExecutionHandle,
+        step: &ExecutionStep,
+        overrides: &InvocationOverrides,
+        cancel: &Arc<Notify>,
+    ) -> Result<Option<CommandResult>> {
+        let (activity, chaos) = {
+            let mut executions = self.active_executions.write().await;
+            executions
+                .get_mut(handle)
+                .map(|state| {
+                    let fault = state.chaos.as_mut().and_then(|chaos| Some((chaos.roll()?, chaos.timeout_wait())));
+                    (state.activity.clone(), fault)
+                })
+                .unwrap_or_default()
+        };
 
-        match command {
-            RapsCommand::Auth { .. } => {
-                suggestions
-                    .push("Check your APS credentials and try 'raps auth login'".to_string());
-                suggestions.push("Verify your client ID and client secret are correct".to_string());
+        // Training runs: replace the command with an injected fault
+        if let Some((fault, wait)) = chaos {
+            warn!("Chaos: injecting {} into step {}", fault.label(), step.id);
+            self.send_step_progress(handle, &step.id, &format!("Chaos: injecting {}", fault.label()));
+            let waited = if fault == ChaosFault::Timeout { wait } else { std::time::Duration::ZERO };
+            tokio::select! {
+                _ = tokio::time::sleep(waited) => {},
+                _ = cancel.notified() => {
+                    warn!("Step {} cancelled while running", step.id);
+                    return Ok(None);
+                },
+            }
+            return Ok(Some(fault.result(&step.id, waited)));
+        }
+
+        // The client stops the command on cancellation and waits for it
+        let overrides = InvocationOverrides {
+            cancel: Some(Arc::clone(cancel)),
+            ..overrides.clone()
+        };
+        let events = self.events.clone();
+        let progress_handle = handle.clone();
+        let progress_step_id = step.id.clone();
+        let result = self
+            .raps_client
+            .execute_command_with_progress(&step.command, &overrides, move |progress| {
+                activity.touch();
+                events.send(ExecutionUpdate::StepProgress {
+                    handle: progress_handle.clone(),
+                    step_id: progress_step_id.clone(),
+                    progress,
+                });
+            })
+            .await;
+        match result {
+            Err(e) if e.is::<CommandCancelled>() => {
+                warn!("Step {} cancelled while running", step.id);
+                Ok(None)
             },
-            RapsCommand::Bucket { .. } => {
-                if result.stderr.contains("already exists") {
-                    suggestions
-                        .push("Bucket name already exists, try a different name".to_string());
-                } else if result.stderr.contains("permission") {
-                    suggestions
-                        .push("Check that you have OSS permissions in your APS app".to_string());
-                }
+            result => result.map(Some),
+        }
+    }
+
+    /// Report what a step is doing outside its command's own progress
+    fn send_step_progress(&self, handle: &ExecutionHandle, step_id: &StepId, operation: &str) {
+        self.events.send(ExecutionUpdate::StepProgress {
+            handle: handle.clone(),
+            step_id: step_id.clone(),
+            progress: CommandProgress {
+                current_operation: operation.to_string(),
+                progress_percent: 0.0,
+                estimated_remaining: None,
+                status_info: HashMap::new(),
             },
-            RapsCommand::Object { .. } => {
-                if result.stderr.contains("not found") {
-                    suggestions
-                        .push("Verify the bucket exists and the object key is correct".to_string());
-                } else if result.stderr.contains("file") {
-                    suggestions.push("Check that the file path exists and is readable".to_string());
-                }
+        });
+    }
+
+    /// Refresh the APS token of `profile` (the default account if `None`)
+    /// with `raps auth refresh`, returning whether it worked
+    async fn refresh_auth(&self, profile: Option<&str>) -> bool {
+        let refresh = RapsCommand::Auth {
+            action: AuthAction::Refresh,
+        };
+        let overrides = InvocationOverrides {
+            profile: profile.map(str::to_string),
+            ..Default::default()
+        };
+        match self.raps_client.execute_command_streaming(&refresh, &overrides, |_| {}).await {
+            Ok(result) if result.success => {
+                info!("Refreshed the APS token");
+                true
             },
-            RapsCommand::Translate { .. } => {
-                if result.stderr.contains("urn") {
-                    suggestions.push(
-                        "Verify the URN is valid and the file was uploaded successfully"
-                            .to_string(),
-                    );
-                } else if result.stderr.contains("format") {
-                    suggestions
-                        .push("Check that the requested output format is supported".to_string());
-                }
+            Ok(result) => {
+                warn!("Token refresh failed: {}", result.stderr.trim());
+                false
             },
-            _ => {
-                suggestions.push("Check the RAPS CLI documentation for this command".to_string());
-                suggestions.push("Verify your APS permissions and authentication".to_string());
+            Err(e) => {
+                warn!("Token refresh failed: {:#}", e);
+                false
             },
         }
+    }
 
-        // Add general suggestions
-        if result.stderr.contains("network") || result.stderr.contains("timeout") {
-            suggestions.push("Check your internet connection and try again".to_string());
+    /// Generate recovery suggestions for failed commands
+    fn generate_recovery_suggestions(
+        &self,
+        command: &RapsCommand,
+        result: &CommandResult,
+    ) -> Vec<String> {
+        if let Some(kind) = ApsErrorKind::from_result(result) {
+            return kind.recovery_suggestions(command);
         }
 
-        suggestions
+        // Unclassified failures get command-specific hints
+        let suggestions: &[&str] = match command {
+            RapsCommand::Auth { .. } => &[
+                "Check your APS credentials and try 'raps auth login'",
+                "Verify your client ID and client secret are correct",
+            ],
+            RapsCommand::Object { .. } => &["Check that the file path exists and is readable"],
+            RapsCommand::Translate { .. } => &["Check that the requested output format is supported"],
+            RapsCommand::Bucket { .. } => &[],
+            _ => &[
+                "Check the RAPS CLI documentation for this command",
+                "Verify your APS permissions and authentication",
+            ],
+        };
+        suggestions.iter().map(|s| s.to_string()).collect()
     }
 
-    /// Estimate remaining execution time
+    /// Estimate remaining execution time.
+    ///
+    /// Each remaining step uses its historical median when known, then its
+    /// declared `expected_duration`, and finally the average duration of the
+    /// steps completed so far in this run.
     fn estimate_remaining_time(
         &self,
         execution_state: &ExecutionState,
     ) -> Option<chrono::Duration> {
-        if execution_state.completed_steps.is_empty() {
-            return None;
-        }
-
-        // Calculate average step duration
-        let total_duration: chrono::Duration = execution_state
+        let completed: Vec<chrono::Duration> = execution_state
             .completed_steps
             .iter()
             .filter_map(|step| {
                 step.end_time
                     .map(|end| end.signed_duration_since(step.start_time))
             })
-            .sum();
-
-        let avg_duration = total_duration / execution_state.completed_steps.len() as i32;
-        let remaining_steps =
-            execution_state.workflow.steps.len() - execution_state.completed_steps.len();
-
-        Some(avg_duration * remaining_steps as i32)
+            .collect();
+        let run_average = (!completed.is_empty())
+            .then(|| completed.iter().copied().sum::<chrono::Duration>() / completed.len() as i32);
+
+        let history = self.history.as_ref().and_then(|h| h.read().ok());
+        let workflow = &execution_state.workflow;
+        let workflow_id = &workflow.metadata.id;
+
+        // The steps this run includes (from `start_at_step` on) that haven't finished
+        let finished = &execution_state.completed_steps;
+        workflow
+            .run_steps(None, execution_state.context.options.start_at_step.as_deref())
+            .into_iter()
+            .map(|index| &workflow.steps[index])
+            .filter(|step| !finished.iter().any(|result| result.step_id == step.id))
+            .map(|step| {
+                history
+                    .as_ref()
+                    .and_then(|h| h.typical_step_duration(workflow_id, &step.id))
+                    .map(|secs| chrono::Duration::milliseconds((secs * 1000.0) as i64))
+                    .or(step.expected_duration)
+                    .or(run_average)
+            })
+            .try_fold(chrono::Duration::zero(), |total, step| {
+                step.map(|step| total.checked_add(&step).unwrap_or(chrono::Duration::MAX))
+            })
     }
 }
 
@@ -733,7 +2209,18 @@ impl Clone for WorkflowExecutor {
         Self {
             raps_client: Arc::clone(&self.raps_client),
             active_executions: Arc::clone(&self.active_executions),
-            progress_sender: self.progress_sender.clone(),
+            events: self.events.clone(),
+            history: self.history.clone(),
+            metrics: self.metrics.clone(),
+            secrets: self.secrets.clone(),
+            policy: self.policy.clone(),
+            strict_placeholders: self.strict_placeholders,
+            region: self.region,
+            stream_output: self.stream_output,
+            auto_reauth: self.auto_reauth,
+            max_output_bytes: self.max_output_bytes,
+            watchdog: self.watchdog.clone(),
+            locks: Arc::clone(&self.locks),
         }
     }
 }
@@ -743,3 +2230,687 @@ impl Default for WorkflowExecutor {
         Self::new()
     }
 }
+
+/// Span covering one execution of a workflow
+fn workflow_span(handle: &ExecutionHandle) -> tracing::Span {
+    info_span!("workflow", workflow_id = %handle.workflow_id, execution_id = %handle.id)
+}
+
+/// Placeholder map as name-sorted pairs
+fn sorted_pairs(map: &HashMap<String, String>) -> Vec<(String, String)> {
+    let mut pairs: Vec<_> = map.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    pairs.sort();
+    pairs
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// Fake RAPS that rejects commands with a 401 until `auth refresh` runs
+    const FAKE_RAPS: &str = r#"#!/bin/sh
+state="$(dirname "$0")/refreshed"
+case "$1 $2" in
+  "auth refresh") touch "$state"; echo '{"access_token": "fresh"}'; exit 0;;
+  "auth status"|"--version "*) echo ok; exit 0;;
+esac
+if [ -f "$state" ]; then echo '{"buckets": []}'; exit 0; fi
+echo '{"error": {"code": "UNAUTHORIZED", "status": 401}}' >&2
+exit 1
+"#;
+
+    const WORKFLOW: &str = r#"
+metadata:
+  id: "reauth-test"
+  name: "Re-auth Test"
+  description: "Fails with a 401 until the token is refreshed"
+  category: "ObjectStorage"
+  estimated_duration: 10
+steps:
+  - id: "list"
+    name: "List Buckets"
+    description: "List buckets"
+    command:
+      type: "bucket"
+      action: "list"
+      bucket_name: "raps-demo-test"
+"#;
+
+    /// Fake RAPS that answers the first list with a 429 asking for no delay
+    const RATE_LIMITED_RAPS: &str = r#"#!/bin/sh
+state="$(dirname "$0")/throttled"
+case "$1 $2" in
+  "auth status"|"--version "*) echo ok; exit 0;;
+esac
+if [ -f "$state" ]; then echo '{"buckets": []}'; exit 0; fi
+touch "$state"
+echo '{"error": {"code": "TOO_MANY_REQUESTS", "status": 429, "retry_after": 0}}' >&2
+exit 1
+"#;
+
+    /// Install `script` as the RAPS binary in `dir` and return an executor
+    /// running it
+    fn fake_executor(dir: &std::path::Path, script: &str) -> WorkflowExecutor {
+        let raps = dir.join("raps");
+        std::fs::write(&raps, script).unwrap();
+        std::fs::set_permissions(&raps, std::fs::Permissions::from_mode(0o755)).unwrap();
+        WorkflowExecutor::with_config(RapsClientConfig {
+            raps_binary_path: raps.display().to_string(),
+            ..Default::default()
+        })
+    }
+
+    /// Fake RAPS appending each command line to `calls` next to it and
+    /// answering every command with `output`
+    fn recording_raps(output: &str) -> String {
+        format!("#!/bin/sh\necho \"$@\" >> \"$(dirname \"$0\")/calls\"\necho '{}'\n", output)
+    }
+
+    /// Command lines the recording fake RAPS in `dir` was run with
+    fn recorded_calls(dir: &std::path::Path) -> String {
+        std::fs::read_to_string(dir.join("calls")).unwrap()
+    }
+
+    /// Result of the next run to complete, failing the test if one fails
+    async fn next_result(updates: &mut UpdateReceiver) -> ExecutionResult {
+        loop {
+            match updates.recv().await.unwrap() {
+                ExecutionUpdate::Completed { result, .. } => return result,
+                ExecutionUpdate::Failed { error, .. } => panic!("run failed: {}", error.message),
+                _ => {},
+            }
+        }
+    }
+
+    /// Run the test workflow against a fake RAPS script, returning the
+    /// progress operations reported along the way
+    async fn run_with_fake_raps(dir: &std::path::Path, script: &str) -> Vec<String> {
+        let executor = fake_executor(dir, script);
+        let mut updates = executor.subscribe();
+        let workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        executor.execute_workflow(workflow, ExecutionOptions::default()).await.unwrap();
+
+        let mut operations = Vec::new();
+        loop {
+            match updates.recv().await.unwrap() {
+                ExecutionUpdate::StepProgress { progress, .. } => operations.push(progress.current_operation),
+                ExecutionUpdate::Completed { result, .. } => {
+                    assert!(result.success);
+                    return operations;
+                },
+                ExecutionUpdate::Failed { error, .. } => panic!("run failed: {}", error.message),
+                _ => {},
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_step_retried_after_token_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let operations = run_with_fake_raps(dir.path(), FAKE_RAPS).await;
+        assert!(operations.iter().any(|op| op.contains("refreshing")));
+        assert!(dir.path().join("refreshed").exists());
+    }
+
+    #[tokio::test]
+    async fn test_token_refreshed_for_the_runs_profile_only_on_401() {
+        // Records the profile's account with each call; `list` answers with
+        // the status in `status` next to the script
+        let script = r#"#!/bin/sh
+dir="$(dirname "$0")"
+echo "$ACCOUNT $@" >> "$dir/calls"
+case "$1 $2" in
+  "auth refresh") echo '{}'; exit 0;;
+  "bucket list") echo "{\"error\": {\"status\": $(cat "$dir/status")}}" >&2; exit 1;;
+esac
+echo ok
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let profiles = HashMap::from([("b".to_string(), HashMap::from([("ACCOUNT".to_string(), "b".to_string())]))]);
+        let executor = fake_executor(dir.path(), script).with_profiles(profiles);
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        workflow.profile = Some("b".to_string());
+        let options = ExecutionOptions {
+            interactive: false,
+            ..Default::default()
+        };
+
+        let mut updates = executor.subscribe();
+        for status in ["401", "403"] {
+            std::fs::write(dir.path().join("status"), status).unwrap();
+            let handle = executor.execute_workflow(workflow.clone(), options.clone()).await.unwrap();
+            loop {
+                match updates.recv().await.unwrap() {
+                    ExecutionUpdate::Failed { handle: failed, .. } if failed == handle => break,
+                    _ => {},
+                }
+            }
+        }
+
+        // A 401 refreshes the profile's token and retries once; a 403 doesn't
+        let calls = recorded_calls(dir.path());
+        let calls: Vec<_> = calls.lines().filter(|line| !line.contains("status") && !line.contains("--version")).collect();
+        let expected = ["b bucket list", "b auth refresh", "b bucket list", "b bucket list"];
+        assert_eq!(calls.len(), expected.len(), "{:?}", calls);
+        for (call, expected) in calls.iter().zip(expected) {
+            assert!(call.starts_with(expected), "{:?}", calls);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hooks_run_as_the_profile_of_their_step_or_workflow() {
+        let script = "#!/bin/sh\necho \"$ACCOUNT $@\" >> \"$(dirname \"$0\")/calls\"\necho '{}'\n";
+        let dir = tempfile::tempdir().unwrap();
+        let account = |name: &str| (name.to_string(), HashMap::from([("ACCOUNT".to_string(), name.to_string())]));
+        let executor = fake_executor(dir.path(), script).with_profiles(HashMap::from([account("a"), account("b")]));
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        workflow.profile = Some("a".to_string());
+        workflow.hooks = serde_yaml::from_str("on_success:\n  - type: custom\n    command: auth\n    args: [status]\n").unwrap();
+        workflow.steps[0].profile = Some("b".to_string());
+        workflow.steps[0].hooks = serde_yaml::from_str("on_start:\n  - type: custom\n    command: auth\n    args: [whoami]\n").unwrap();
+        let options = ExecutionOptions {
+            interactive: false,
+            ..Default::default()
+        };
+
+        let mut updates = executor.subscribe();
+        let handle = executor.execute_workflow(workflow, options).await.unwrap();
+        loop {
+            match updates.recv().await.unwrap() {
+                ExecutionUpdate::Completed { handle: done, .. } if done == handle => break,
+                ExecutionUpdate::Failed { error, .. } => panic!("run failed: {}", error.message),
+                _ => {},
+            }
+        }
+
+        let calls = recorded_calls(dir.path());
+        for expected in ["b auth whoami", "b bucket list", "a auth status"] {
+            assert!(calls.lines().any(|call| call.starts_with(expected)), "{}", calls);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_step_waits_and_retries() {
+        let dir = tempfile::tempdir().unwrap();
+        let operations = run_with_fake_raps(dir.path(), RATE_LIMITED_RAPS).await;
+        assert_eq!(operations, vec!["Waiting for rate limit (0s, retry 1/3)".to_string()]);
+    }
+    #[tokio::test]
+    async fn test_pause_between_steps_on_request() {
+        let dir = tempfile::tempdir().unwrap();
+        let executor = fake_executor(dir.path(), "#!/bin/sh\nsleep 0.3\necho '{}'\n");
+        let mut updates = executor.subscribe();
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        let mut second = workflow.steps[0].clone();
+        second.id = "second".to_string();
+        workflow.steps.push(second);
+        let options = ExecutionOptions {
+            interactive: false,
+            ..Default::default()
+        };
+        let handle = executor.execute_workflow(workflow, options).await.unwrap();
+
+        let mut events = Vec::new();
+        let wait = async {
+            loop {
+                match updates.recv().await.unwrap() {
+                    // Pause while the first step runs
+                    ExecutionUpdate::StepStarted { step, .. } if step.id == "list" => {
+                        executor.pause_execution(&handle).await.unwrap();
+                    },
+                    ExecutionUpdate::StepCompleted { result, .. } => events.push(result.step_id),
+                    ExecutionUpdate::Paused { next_step, .. } => {
+                        events.push(format!("paused before {}", next_step.id));
+                        executor.resume_execution(&handle).await.unwrap();
+                    },
+                    ExecutionUpdate::Resumed { .. } => events.push("resumed".to_string()),
+                    ExecutionUpdate::Completed { result, .. } => return result,
+                    ExecutionUpdate::Failed { error, .. } => panic!("run failed: {}", error.message),
+                    _ => {},
+                }
+            }
+        };
+        let result = tokio::time::timeout(std::time::Duration::from_secs(10), wait).await.unwrap();
+        assert!(result.success);
+        assert_eq!(events, vec!["list", "paused before second", "resumed", "second"]);
+    }
+
+    /// Fake RAPS failing its first and third commands
+    const FLAKY_RAPS: &str = r#"#!/bin/sh
+calls="$(dirname "$0")/calls"
+case "$1 $2" in
+  "auth status"|"--version "*) echo ok; exit 0;;
+esac
+echo x >> "$calls"
+case "$(wc -l < "$calls" | tr -d ' ')" in
+  1|3) echo '{"error": {"code": "CONFLICT", "status": 409}}' >&2; exit 1;;
+esac
+echo '{"buckets": []}'
+"#;
+
+    #[tokio::test]
+    async fn test_failed_steps_retried_and_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let executor = fake_executor(dir.path(), FLAKY_RAPS);
+        let mut updates = executor.subscribe();
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        let mut second = workflow.steps[0].clone();
+        second.id = "second".to_string();
+        workflow.steps.push(second);
+        let options = ExecutionOptions {
+            interactive: false,
+            ask_on_failure: true,
+            ..Default::default()
+        };
+        let handle = executor.execute_workflow(workflow, options).await.unwrap();
+
+        let mut decisions = vec![StepDecision::Skip, StepDecision::Retry];
+        let wait = async {
+            loop {
+                match updates.recv().await.unwrap() {
+                    ExecutionUpdate::StepFailed { step, error, .. } => {
+                        assert_eq!(error.kind, Some(ApsErrorKind::Conflict));
+                        let decision = decisions.pop().unwrap();
+                        assert_eq!(step.id, if decision == StepDecision::Retry { "list" } else { "second" });
+                        executor.decide_step_failure(&handle, decision).await.unwrap();
+                    },
+                    ExecutionUpdate::Completed { result, .. } => return result,
+                    ExecutionUpdate::Failed { error, .. } => panic!("run failed: {}", error.message),
+                    _ => {},
+                }
+            }
+        };
+        let result = tokio::time::timeout(std::time::Duration::from_secs(10), wait).await.unwrap();
+        assert!(result.success);
+        let statuses: Vec<_> = result.step_results.iter().map(|r| r.status.clone()).collect();
+        assert_eq!(statuses, vec![ExecutionStatus::Completed, ExecutionStatus::Skipped]);
+        assert!(executor.decide_step_failure(&handle, StepDecision::Retry).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_aborted_run_releases_its_locks() {
+        let dir = tempfile::tempdir().unwrap();
+        let executor = fake_executor(dir.path(), FLAKY_RAPS);
+        let mut updates = executor.subscribe();
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        workflow.locks = vec!["bucket:demo".to_string()];
+        let options = ExecutionOptions {
+            interactive: false,
+            ask_on_failure: true,
+            ..Default::default()
+        };
+        let handle = executor.execute_workflow(workflow.clone(), options.clone()).await.unwrap();
+
+        let wait = async {
+            loop {
+                match updates.recv().await.unwrap() {
+                    ExecutionUpdate::StepFailed { handle: failed, .. } if failed == handle => {
+                        executor.decide_step_failure(&handle, StepDecision::Abort).await.unwrap();
+                    },
+                    ExecutionUpdate::Failed { handle: failed, .. } if failed == handle => break,
+                    ExecutionUpdate::Completed { .. } => panic!("aborted run completed"),
+                    _ => {},
+                }
+            }
+            // A second run needing the lock gets it
+            let second = executor.execute_workflow(workflow, options).await.unwrap();
+            loop {
+                match updates.recv().await.unwrap() {
+                    ExecutionUpdate::WaitingForLock { .. } => panic!("lock still held by the aborted run"),
+                    ExecutionUpdate::Completed { handle, .. } if handle == second => break,
+                    _ => {},
+                }
+            }
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(10), wait).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_from_step() {
+        let dir = tempfile::tempdir().unwrap();
+        let executor = fake_executor(dir.path(), &recording_raps("{}"));
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        let mut second = workflow.steps[0].clone();
+        second.id = "second".to_string();
+        second.command = RapsCommand::Bucket {
+            action: BucketAction::Details,
+            params: BucketParams {
+                bucket_name: Some("{steps.list.bucket}".to_string()),
+                retention_policy: None,
+                region: None,
+                force: None,
+            },
+        };
+        workflow.steps.push(second);
+
+        // The skipped step's output must be supplied
+        let mut options = ExecutionOptions {
+            interactive: false,
+            start_at_step: Some("second".to_string()),
+            ..Default::default()
+        };
+        let err = executor.execute_workflow(workflow.clone(), options.clone()).await.unwrap_err();
+        assert!(err.to_string().contains("steps.list.bucket"), "{}", err);
+        options.start_at_step = Some("nope".to_string());
+        assert!(executor.execute_workflow(workflow.clone(), options.clone()).await.is_err());
+
+        options.start_at_step = Some("second".to_string());
+        options.params.insert("steps.list.bucket".to_string(), "kept-bucket".to_string());
+        let mut updates = executor.subscribe();
+        executor.execute_workflow(workflow, options).await.unwrap();
+        let result = next_result(&mut updates).await;
+        let ran: Vec<_> = result.step_results.iter().map(|r| r.step_id.as_str()).collect();
+        assert_eq!(ran, vec!["second"]);
+        let calls = recorded_calls(dir.path());
+        assert!(calls.contains("kept-bucket"), "{}", calls);
+        assert!(!calls.contains("bucket list"), "{}", calls);
+    }
+
+    #[tokio::test]
+    async fn test_remaining_time_leaves_out_steps_before_the_start() {
+        let dir = tempfile::tempdir().unwrap();
+        let executor = fake_executor(dir.path(), "#!/bin/sh\nsleep 0.5\necho '{}'\n");
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        workflow.steps[0].expected_duration = Some(chrono::Duration::seconds(100));
+        let mut second = workflow.steps[0].clone();
+        second.id = "second".to_string();
+        second.expected_duration = Some(chrono::Duration::seconds(30));
+        workflow.steps.push(second);
+
+        let options = ExecutionOptions {
+            interactive: false,
+            start_at_step: Some("second".to_string()),
+            ..Default::default()
+        };
+        let mut updates = executor.subscribe();
+        let handle = executor.execute_workflow(workflow, options).await.unwrap();
+        while !matches!(updates.recv().await.unwrap(), ExecutionUpdate::StepStarted { .. }) {}
+        let progress = executor.get_execution_progress(&handle).await.unwrap();
+        assert_eq!(progress.estimated_remaining, Some(chrono::Duration::seconds(30)));
+    }
+
+    #[tokio::test]
+    async fn test_run_from_step_given_bare_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let executor = fake_executor(dir.path(), &recording_raps("{}"));
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        let mut details = workflow.steps[0].clone();
+        details.id = "details".to_string();
+        details.command = serde_yaml::from_str("{type: bucket, action: details, bucket_name: '{bucket}'}").unwrap();
+        workflow.steps.push(details);
+
+        // `{bucket}` would come from the skipped `list` step
+        let mut options = ExecutionOptions {
+            interactive: false,
+            start_at_step: Some("details".to_string()),
+            ..Default::default()
+        };
+        let err = executor.execute_workflow(workflow.clone(), options.clone()).await.unwrap_err();
+        assert!(err.to_string().contains("producing bucket;"), "{}", err);
+
+        options.params.insert("bucket".to_string(), "given-bucket".to_string());
+        let mut updates = executor.subscribe();
+        executor.execute_workflow(workflow, options).await.unwrap();
+        assert!(next_result(&mut updates).await.success);
+        let calls = recorded_calls(dir.path());
+        assert!(calls.contains("given-bucket"), "{}", calls);
+    }
+
+    #[tokio::test]
+    async fn test_runs_sharing_a_lock_run_one_after_another() {
+        let dir = tempfile::tempdir().unwrap();
+        let executor = fake_executor(dir.path(), "#!/bin/sh\nsleep 0.2\necho '{}'\n");
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        workflow.locks = vec!["bucket:{params.bucket}".to_string()];
+        let options = ExecutionOptions {
+            interactive: false,
+            params: HashMap::from([("bucket".to_string(), "demo".to_string())]),
+            ..Default::default()
+        };
+
+        let mut updates = executor.subscribe();
+        executor.execute_workflow(workflow.clone(), options.clone()).await.unwrap();
+        let second = executor.execute_workflow(workflow, options).await.unwrap();
+        let name = |handle: &ExecutionHandle| if *handle == second { "second" } else { "first" };
+        let mut events = Vec::new();
+        while events.iter().filter(|e: &&String| e.ends_with("finished")).count() < 2 {
+            match updates.recv().await.unwrap() {
+                ExecutionUpdate::WaitingForLock { handle, wait } => {
+                    assert_eq!(wait.lock, "bucket:demo");
+                    events.push(format!("{} waits", name(&handle)));
+                },
+                ExecutionUpdate::StepStarted { handle, .. } => events.push(format!("{} started", name(&handle))),
+                ExecutionUpdate::StepCompleted { handle, .. } => events.push(format!("{} step done", name(&handle))),
+                ExecutionUpdate::Completed { handle, .. } => events.push(format!("{} finished", name(&handle))),
+                ExecutionUpdate::Failed { error, .. } => panic!("run failed: {}", error.message),
+                _ => {},
+            }
+        }
+        let position = |event: &str| events.iter().position(|e| e == event).unwrap();
+        assert!(position("second waits") < position("first step done"), "{:?}", events);
+        assert!(position("first step done") < position("second started"), "{:?}", events);
+        // Released once the second run's task is done
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while !executor.locks.held().is_empty() {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_step_skipped_when_result_exists() {
+        let dir = tempfile::tempdir().unwrap();
+        let executor = fake_executor(dir.path(), &recording_raps(r#"{"bucketKey": "kept"}"#));
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        workflow.steps[0].skip_if = Some(serde_yaml::from_str("{type: custom, command: bucket, args: [details, kept]}").unwrap());
+        let options = ExecutionOptions {
+            interactive: false,
+            ..Default::default()
+        };
+
+        let mut updates = executor.subscribe();
+        executor.execute_workflow(workflow, options).await.unwrap();
+        let result = next_result(&mut updates).await;
+        assert!(result.success);
+        assert_eq!(result.step_results[0].status, ExecutionStatus::AlreadyExists);
+        let calls = recorded_calls(dir.path());
+        assert!(calls.contains("bucket details kept"), "{}", calls);
+        assert!(!calls.contains("bucket list"), "{}", calls);
+    }
+
+    #[tokio::test]
+    async fn test_rewritten_names_used_by_later_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        let executor = fake_executor(dir.path(), &recording_raps("{}")).with_policy(StepPolicy {
+            naming: crate::workflow::policy::NamingMode::Rewrite,
+            ..Default::default()
+        });
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        workflow.params.insert("bucket".to_string(), "shared".to_string());
+        let step = |id: &str, command: &str| {
+            let mut step = workflow.steps[0].clone();
+            step.id = id.to_string();
+            step.command = serde_yaml::from_str(command).unwrap();
+            step
+        };
+        // Created through a placeholder, then named directly and through it
+        workflow.steps = vec![
+            step("create", "{type: bucket, action: create, bucket_name: '{params.bucket}'}"),
+            step("details", "{type: bucket, action: details, bucket_name: shared}"),
+            step("delete", "{type: bucket, action: delete, bucket_name: '{params.bucket}'}"),
+        ];
+        let options = ExecutionOptions {
+            interactive: false,
+            ..Default::default()
+        };
+
+        let mut updates = executor.subscribe();
+        executor.execute_workflow(workflow, options).await.unwrap();
+        assert!(next_result(&mut updates).await.success);
+        let calls = recorded_calls(dir.path());
+        let buckets: Vec<_> = calls
+            .lines()
+            .filter(|line| line.starts_with("bucket"))
+            .filter_map(|line| line.split_whitespace().nth(3))
+            .collect();
+        assert_eq!(buckets, vec!["raps-demo-shared"; 3], "{}", calls);
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_resolved_after_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = "#!/bin/sh\necho \"$@\" >> \"$(dirname \"$0\")/calls\"\n\
+                      if [ \"$1\" = fail ]; then exit 1; fi\necho '{\"bucketKey\": \"created-key\"}'\n";
+        let executor = fake_executor(dir.path(), script);
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        workflow.steps[0].command = serde_yaml::from_str("{type: bucket, action: create, bucket_name: demo}").unwrap();
+        // Both use the key the step's own output reports
+        let delete: RapsCommand =
+            serde_yaml::from_str("{type: bucket, action: delete, bucket_name: '{steps.list.bucketKey}'}").unwrap();
+        workflow.steps[0].cleanup_commands = vec![delete.clone()];
+        workflow.cleanup = vec![delete];
+        let options = ExecutionOptions {
+            interactive: false,
+            ..Default::default()
+        };
+
+        // A successful run runs the workflow's cleanup
+        let mut updates = executor.subscribe();
+        executor.execute_workflow(workflow.clone(), options.clone()).await.unwrap();
+        let result = next_result(&mut updates).await;
+        assert!(result.cleanup_performed);
+        assert_eq!(result.cleanup_results.len(), 1);
+        assert!(result.cleanup_results[0].success, "{:?}", result.cleanup_results);
+        assert_eq!(result.cleanup_results[0].step_id, None);
+
+        // A failed one the cleanup of the steps that completed
+        let mut failing = workflow.clone();
+        let mut boom = failing.steps[0].clone();
+        boom.id = "boom".to_string();
+        boom.command = serde_yaml::from_str("{type: custom, command: fail, args: []}").unwrap();
+        boom.cleanup_commands.clear();
+        failing.steps.push(boom);
+        executor.execute_workflow(failing, options).await.unwrap();
+        let results = loop {
+            match updates.recv().await.unwrap() {
+                ExecutionUpdate::CleanupCompleted { results, .. } => break results,
+                ExecutionUpdate::Failed { .. } => panic!("no cleanup before the failure was reported"),
+                _ => {},
+            }
+        };
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].step_id.as_deref(), Some("list"));
+
+        let calls = recorded_calls(dir.path());
+        let deletes = calls.lines().filter(|line| line.starts_with("bucket delete --key created-key")).count();
+        assert_eq!(deletes, 2, "{}", calls);
+    }
+
+    #[tokio::test]
+    async fn test_outputs_piped_to_later_workflow() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = crate::workflow::history::DurationHistory::default().shared();
+        let executor =
+            fake_executor(dir.path(), &recording_raps(r#"{"urn": "urn:demo"}"#)).with_history(Arc::clone(&history));
+        let mut updates = executor.subscribe();
+        let options = ExecutionOptions {
+            interactive: false,
+            ..Default::default()
+        };
+
+        let mut upload: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        upload.outputs.insert("urn".to_string(), "{steps.list.urn}".to_string());
+        let mut translate = upload.clone();
+        translate.metadata.id = "translate".to_string();
+        translate.outputs.clear();
+        translate.params.insert("urn".to_string(), String::new());
+        translate.uses_outputs = vec!["reauth-test:latest".to_string()];
+        translate.steps[0].command = serde_yaml::from_str("{type: custom, command: translate, args: ['{params.urn}']}").unwrap();
+
+        // Nothing recorded yet
+        assert!(executor.execute_workflow(translate.clone(), options.clone()).await.is_err());
+
+        for workflow in [upload, translate] {
+            executor.execute_workflow(workflow, options.clone()).await.unwrap();
+            assert!(next_result(&mut updates).await.success);
+        }
+        let recorded = history.read().unwrap().outputs(&"reauth-test".parse().unwrap()).cloned().unwrap();
+        assert_eq!(recorded.values["urn"], "urn:demo");
+        let calls = recorded_calls(dir.path());
+        assert!(calls.contains("translate urn:demo"), "{}", calls);
+    }
+
+    #[tokio::test]
+    async fn test_stalled_run_reported_and_force_failed() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = "#!/bin/sh\ncase \"$1 $2\" in \"auth status\"|\"--version \"*) echo ok; exit 0;; esac\n\
+                      echo \"$@\" >> \"$(dirname \"$0\")/calls\"\n\
+                      case \"$1 $2\" in \"bucket create\"|\"bucket delete\") echo '{}'; exit 0;; fail*) exit 1;; esac\n\
+                      sleep 30\n";
+        let executor = fake_executor(dir.path(), script).with_watchdog(WatchdogConfig {
+            enabled: true,
+            stall_threshold_seconds: 1,
+            check_interval_seconds: 0,
+        });
+        let mut updates = executor.subscribe();
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        workflow.locks = vec!["bucket:demo".to_string()];
+        let mut create = workflow.steps[0].clone();
+        create.id = "create".to_string();
+        create.command = serde_yaml::from_str("{type: bucket, action: create, bucket_name: demo}").unwrap();
+        create.cleanup_commands = vec![serde_yaml::from_str("{type: bucket, action: delete, bucket_name: demo}").unwrap()];
+        workflow.steps.insert(0, create);
+        let options = ExecutionOptions {
+            interactive: false,
+            ..Default::default()
+        };
+        let handle = executor.execute_workflow(workflow.clone(), options).await.unwrap();
+
+        let wait = async {
+            loop {
+                match updates.recv().await.unwrap() {
+                    ExecutionUpdate::Stalled { report, .. } => {
+                        assert_eq!(report.step_id.as_deref(), Some("list"));
+                        assert!(report.command.unwrap().starts_with("raps bucket list"));
+                        executor.force_fail(&handle, "stuck").await.unwrap();
+                    },
+                    ExecutionUpdate::Failed { error, .. } => return error,
+                    ExecutionUpdate::Completed { .. } => panic!("stalled run completed"),
+                    _ => {},
+                }
+            }
+        };
+        let error = tokio::time::timeout(std::time::Duration::from_secs(10), wait).await.unwrap();
+        assert_eq!(error.message, "stuck");
+        assert_eq!(error.failed_step.as_deref(), Some("list"));
+        assert!(matches!(executor.check_health(&handle, Default::default()).await, HealthCheck::Finished));
+        // The completed step was cleaned up before the failure was reported
+        assert!(recorded_calls(dir.path()).contains("bucket delete"), "{}", recorded_calls(dir.path()));
+
+        // A run held on a failed step releases its locks when force-failed
+        workflow.steps.truncate(1);
+        workflow.steps[0].command = serde_yaml::from_str("{type: custom, command: fail, args: []}").unwrap();
+        let options = ExecutionOptions {
+            interactive: false,
+            ask_on_failure: true,
+            ..Default::default()
+        };
+        let held = executor.execute_workflow(workflow, options).await.unwrap();
+        let wait = async {
+            loop {
+                match updates.recv().await.unwrap() {
+                    ExecutionUpdate::StepFailed { .. } => executor.force_fail(&held, "stuck").await.unwrap(),
+                    ExecutionUpdate::Failed { .. } => break,
+                    _ => {},
+                }
+            }
+            while !executor.locks.held().is_empty() {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(10), wait).await.unwrap();
+    }
+}
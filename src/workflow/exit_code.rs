@@ -0,0 +1,120 @@
+// Exit codes of non-interactive runs
+//
+// Wrappers and CI jobs branch on how a `--no-tui` run ended rather than
+// parsing its output. A run that never started is either invalid (unknown
+// workflow, policy violation, bad profile or parameters) or refused by the
+// prerequisite checks (RAPS CLI missing, not logged in, assets missing). A run
+// that started fails in a step, or its steps succeed and one of the cleanup
+// commands run afterwards fails: the demo itself worked and the failure is
+// reported as a cleanup failure, since resources may have been left behind.
+// Errors outside a run (unreadable configuration and the like) keep the usual
+// exit code 1.
+
+use super::types::ExecutionResult;
+use crate::error::{RapsDemoError, RunError};
+
+/// How a non-interactive run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunExit {
+    /// Every step succeeded
+    Success,
+    /// The workflow, its profile or parameters are invalid
+    Validation,
+    /// A prerequisite of the workflow is missing
+    Preflight,
+    /// A step failed
+    StepFailure,
+    /// Every step succeeded, but a cleanup command failed
+    CleanupFailure,
+    /// Ctrl-C cancelled the run
+    Interrupted,
+}
+
+impl RunExit {
+    /// Process exit code
+    pub fn code(self) -> i32 {
+        match self {
+            Self::Success => 0,
+            Self::Validation => 2,
+            Self::Preflight => 3,
+            Self::StepFailure => 4,
+            Self::CleanupFailure => 5,
+            Self::Interrupted => 130,
+        }
+    }
+
+    /// Exit of a run that could not be started because of `error`
+    pub fn not_started(error: &RapsDemoError) -> Self {
+        match error {
+            RapsDemoError::Execution(RunError::Prerequisites(_)) => Self::Preflight,
+            _ => Self::Validation,
+        }
+    }
+
+    /// Exit of a run that finished with `result`: the steps decide, and
+    /// when they all succeeded a failed cleanup command still fails the
+    /// run, since resources may have been left behind
+    pub fn finished(result: &ExecutionResult) -> Self {
+        if !result.success {
+            Self::StepFailure
+        } else if result.cleanup_results.iter().any(|cleanup| !cleanup.success) {
+            Self::CleanupFailure
+        } else {
+            Self::Success
+        }
+    }
+}
+
+impl From<RunExit> for std::process::ExitCode {
+    fn from(exit: RunExit) -> Self {
+        Self::from(exit.code() as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DiscoveryError;
+    use crate::workflow::executor::PrerequisiteError;
+    use crate::workflow::types::CleanupResult;
+
+    #[test]
+    fn test_run_exit_categories() {
+        let cleanup = |success: bool| CleanupResult {
+            step_id: None,
+            command: "raps bucket delete demo".to_string(),
+            success,
+            error: (!success).then(|| "exit code 1".to_string()),
+        };
+        let mut result = ExecutionResult {
+            workflow_id: "lifecycle".to_string(),
+            success: true,
+            duration: chrono::Duration::seconds(3),
+            steps_completed: 2,
+            total_steps: 2,
+            resources_created: Vec::new(),
+            cleanup_performed: true,
+            cleanup_results: vec![cleanup(true)],
+            step_results: Vec::new(),
+            outputs: Default::default(),
+        };
+        assert_eq!(RunExit::finished(&result), RunExit::Success);
+        result.cleanup_results.push(cleanup(false));
+        assert_eq!(RunExit::finished(&result).code(), 5);
+        result.success = false;
+        assert_eq!(RunExit::finished(&result).code(), 4);
+
+        let preflight: RapsDemoError = RunError::Prerequisites(PrerequisiteError {
+            problems: vec!["APS authentication required.".to_string()],
+        })
+        .into();
+        assert_eq!(RunExit::not_started(&preflight).code(), 3);
+        let unknown: RapsDemoError = DiscoveryError::UnknownProfile {
+            workflow: "lifecycle".to_string(),
+            profile: "x".to_string(),
+            available: Vec::new(),
+        }
+        .into();
+        assert_eq!(RunExit::not_started(&unknown).code(), 2);
+    }
+}
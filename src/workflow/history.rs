@@ -0,0 +1,356 @@
+// Execution duration history for RAPS Demo Workflows
+//
+// Records how long workflows and their steps actually took, so the UI can
+// show realistic timings and the executor can estimate remaining time from
+// historical medians rather than the current run alone.
+//
+// Successful runs also record the workflow's declared `outputs:`, so a later
+// run of another workflow can consume them (`uses_outputs: [upload:latest]`
+// or `--from-outputs upload:latest`), e.g. translating yesterday's upload.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use super::types::{ExecutionResult, ExecutionStatus, StepId, WorkflowId};
+use crate::config::types::ConfigPaths;
+
+/// Number of samples kept per workflow and per step
+const MAX_SAMPLES: usize = 20;
+
+/// History shared between the executor (which records) and the UI (which reads)
+pub type SharedHistory = Arc<RwLock<DurationHistory>>;
+
+/// Recorded durations (in seconds) for one workflow
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkflowHistory {
+    /// Durations of successful runs, oldest first
+    pub runs: Vec<f64>,
+    /// Durations of successful steps, oldest first
+    pub steps: BTreeMap<StepId, Vec<f64>>,
+    /// Declared outputs of successful runs, oldest first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<RecordedOutputs>,
+}
+
+/// Output values of one successful run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedOutputs {
+    /// When the run finished
+    pub finished_at: DateTime<Utc>,
+    /// Output name to value
+    pub values: BTreeMap<String, String>,
+}
+
+/// Reference to the outputs of an earlier run: `upload`, `upload:latest`,
+/// or `upload:latest~1` for the run before the latest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputsRef {
+    pub workflow_id: WorkflowId,
+    /// Runs before the latest one (0 for the latest)
+    pub runs_back: usize,
+}
+
+impl FromStr for OutputsRef {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (workflow_id, run) = s.split_once(':').unwrap_or((s, "latest"));
+        let runs_back = match run.strip_prefix("latest") {
+            Some("") => 0,
+            Some(back) => back
+                .strip_prefix('~')
+                .and_then(|n| n.parse().ok())
+                .with_context(|| format!("Invalid run '{}' in outputs reference '{}'", run, s))?,
+            None => anyhow::bail!("Invalid run '{}' in outputs reference '{}' (expected latest or latest~N)", run, s),
+        };
+        if workflow_id.is_empty() {
+            anyhow::bail!("Outputs reference '{}' names no workflow", s);
+        }
+        Ok(Self {
+            workflow_id: workflow_id.to_string(),
+            runs_back,
+        })
+    }
+}
+
+impl fmt::Display for OutputsRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.runs_back {
+            0 => write!(f, "{}:latest", self.workflow_id),
+            back => write!(f, "{}:latest~{}", self.workflow_id, back),
+        }
+    }
+}
+
+/// Duration history for all workflows
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DurationHistory {
+    pub workflows: BTreeMap<WorkflowId, WorkflowHistory>,
+    /// File the history is persisted to, if any
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl DurationHistory {
+    /// Load history from the default file, falling back to an empty history
+    pub fn load() -> Self {
+        let path = match ConfigPaths::history_file() {
+            Ok(path) => path,
+            Err(e) => {
+                tracing::debug!("No history location available: {}", e);
+                return Self::default();
+            },
+        };
+
+        let mut history = if path.exists() {
+            Self::load_from(&path).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring unreadable history file {:?}: {:#}", path, e);
+                Self::default()
+            })
+        } else {
+            Self::default()
+        };
+        history.path = Some(path);
+        history
+    }
+
+    /// Load history from a specific file
+    pub fn load_from<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read history file: {:?}", path))?;
+        let mut history: DurationHistory = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse history file: {:?}", path))?;
+        history.path = Some(path.to_path_buf());
+        Ok(history)
+    }
+
+    /// Save history to the file it was loaded from (no-op for in-memory history)
+    pub fn save(&self) -> Result<()> {
+        match &self.path {
+            Some(path) => self.save_to(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Save history to a specific file
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+        }
+        let content = toml::to_string_pretty(self).context("Failed to serialize history")?;
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write history file: {:?}", path))?;
+        Ok(())
+    }
+
+    /// Wrap the history for sharing between the executor and the UI
+    pub fn shared(self) -> SharedHistory {
+        Arc::new(RwLock::new(self))
+    }
+
+    /// Record the durations of a finished execution.
+    ///
+    /// Completed steps are always recorded; the overall duration only for
+    /// successful runs, so aborted runs don't skew the typical time.
+    pub fn record(&mut self, result: &ExecutionResult) {
+        let entry = self.workflows.entry(result.workflow_id.clone()).or_default();
+
+        for step in &result.step_results {
+            if step.status != ExecutionStatus::Completed {
+                continue;
+            }
+            if let Some(end) = step.end_time {
+                let secs = (end - step.start_time).num_milliseconds().max(0) as f64 / 1000.0;
+                push_sample(entry.steps.entry(step.step_id.clone()).or_default(), secs);
+            }
+        }
+
+        if result.success {
+            let secs = result.duration.num_milliseconds().max(0) as f64 / 1000.0;
+            push_sample(&mut entry.runs, secs);
+            if !result.outputs.is_empty() {
+                entry.outputs.push(RecordedOutputs {
+                    finished_at: Utc::now(),
+                    values: result.outputs.clone(),
+                });
+                if entry.outputs.len() > MAX_SAMPLES {
+                    entry.outputs.drain(..entry.outputs.len() - MAX_SAMPLES);
+                }
+            }
+        }
+    }
+
+    /// Recorded outputs of the run `reference` points to
+    pub fn outputs(&self, reference: &OutputsRef) -> Option<&RecordedOutputs> {
+        self.workflows.get(&reference.workflow_id)?.outputs.iter().rev().nth(reference.runs_back)
+    }
+
+    /// Median duration of successful runs of a workflow
+    pub fn typical_duration(&self, workflow_id: &str) -> Option<f64> {
+        median(&self.workflows.get(workflow_id)?.runs)
+    }
+
+    /// Duration of the most recent successful run of a workflow
+    pub fn last_duration(&self, workflow_id: &str) -> Option<f64> {
+        self.workflows.get(workflow_id)?.runs.last().copied()
+    }
+
+    /// Median duration of a step
+    pub fn typical_step_duration(&self, workflow_id: &str, step_id: &str) -> Option<f64> {
+        median(self.workflows.get(workflow_id)?.steps.get(step_id)?)
+    }
+
+    /// Summary like `typically takes 3m 40s (last run 3m 12s)`
+    pub fn summary(&self, workflow_id: &str) -> Option<String> {
+        let typical = self.typical_duration(workflow_id)?;
+        let mut text = format!("typically takes {}", format_duration_secs(typical));
+        if let Some(last) = self.last_duration(workflow_id) {
+            text.push_str(&format!(" (last run {})", format_duration_secs(last)));
+        }
+        Some(text)
+    }
+}
+
+/// Append a sample, keeping only the most recent `MAX_SAMPLES`
+fn push_sample(samples: &mut Vec<f64>, secs: f64) {
+    samples.push(secs);
+    if samples.len() > MAX_SAMPLES {
+        samples.drain(..samples.len() - MAX_SAMPLES);
+    }
+}
+
+/// Median of a set of samples
+fn median(samples: &[f64]) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    Some(if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    })
+}
+
+/// Format seconds as `3m 40s` / `45s`
+pub fn format_duration_secs(secs: f64) -> String {
+    let whole = secs.round() as u64;
+    if whole >= 60 {
+        format!("{}m {:02}s", whole / 60, whole % 60)
+    } else {
+        format!("{}s", whole)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::StepResult;
+    use chrono::{Duration, Utc};
+    use tempfile::TempDir;
+
+    fn result(workflow_id: &str, secs: i64, step_secs: i64, success: bool) -> ExecutionResult {
+        let start = Utc::now();
+        ExecutionResult {
+            workflow_id: workflow_id.to_string(),
+            success,
+            duration: Duration::seconds(secs),
+            steps_completed: 1,
+            total_steps: 1,
+            resources_created: Vec::new(),
+            cleanup_performed: false,
+            cleanup_results: Vec::new(),
+            step_results: vec![StepResult {
+                step_id: "upload".to_string(),
+                status: ExecutionStatus::Completed,
+                start_time: start,
+                end_time: Some(start + Duration::seconds(step_secs)),
+                stdout: String::new(),
+                stderr: String::new(),
+                exit_code: Some(0),
+                created_resources: Vec::new(),
+                full_output: Vec::new(),
+                json_output: None,
+            }],
+            outputs: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_history_medians_and_summary() {
+        let mut history = DurationHistory::default();
+        history.record(&result("demo", 200, 10, true));
+        history.record(&result("demo", 240, 30, true));
+        history.record(&result("demo", 220, 20, true));
+        // Failed runs only contribute step timings
+        history.record(&result("demo", 5, 40, false));
+
+        assert_eq!(history.typical_duration("demo"), Some(220.0));
+        assert_eq!(history.last_duration("demo"), Some(220.0));
+        assert_eq!(history.typical_step_duration("demo", "upload"), Some(25.0));
+        assert_eq!(
+            history.summary("demo").as_deref(),
+            Some("typically takes 3m 40s (last run 3m 40s)")
+        );
+        assert_eq!(history.summary("other"), None);
+    }
+
+    #[test]
+    fn test_history_roundtrip_and_sample_limit() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("history.toml");
+
+        let mut history = DurationHistory::default();
+        for secs in 0..(MAX_SAMPLES as i64 + 5) {
+            history.record(&result("demo", secs, 1, true));
+        }
+        assert_eq!(history.workflows["demo"].runs.len(), MAX_SAMPLES);
+        assert_eq!(history.workflows["demo"].runs[0], 5.0);
+
+        history.save_to(&path).unwrap();
+        let loaded = DurationHistory::load_from(&path).unwrap();
+        assert_eq!(loaded.workflows, history.workflows);
+    }
+
+    #[test]
+    fn test_history_outputs() {
+        let mut history = DurationHistory::default();
+        for urn in ["urn:first", "urn:second"] {
+            let mut run = result("upload", 10, 1, true);
+            run.outputs.insert("urn".to_string(), urn.to_string());
+            history.record(&run);
+        }
+        // Failed runs record no outputs
+        let mut failed = result("upload", 10, 1, false);
+        failed.outputs.insert("urn".to_string(), "urn:failed".to_string());
+        history.record(&failed);
+
+        let latest: OutputsRef = "upload:latest".parse().unwrap();
+        assert_eq!(latest, "upload".parse().unwrap());
+        assert_eq!(history.outputs(&latest).unwrap().values["urn"], "urn:second");
+        let previous: OutputsRef = "upload:latest~1".parse().unwrap();
+        assert_eq!(previous.to_string(), "upload:latest~1");
+        assert_eq!(history.outputs(&previous).unwrap().values["urn"], "urn:first");
+        assert!(history.outputs(&"upload:latest~2".parse().unwrap()).is_none());
+        assert!("upload:yesterday".parse::<OutputsRef>().is_err());
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("history.toml");
+        history.save_to(&path).unwrap();
+        assert_eq!(DurationHistory::load_from(&path).unwrap().workflows, history.workflows);
+    }
+}
@@ -0,0 +1,301 @@
+// Workflow and step hooks for RAPS Demo Workflows
+//
+// Hooks let a workflow react to its own lifecycle, e.g. post to a chat
+// webhook when the nightly demo regression fails. A hook is either a RAPS
+// command or an HTTP callback; both run through the executor and report
+// their outcome as execution updates. Hook failures are reported but never
+// change the outcome of the workflow itself.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use super::types::{RapsCommand, StepId};
+
+/// Timeout for HTTP callbacks
+const HTTP_HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Workflow-level hooks (`hooks:` in workflow YAML)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkflowHooks {
+    /// Run before the first step
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_start: Vec<HookAction>,
+    /// Run after every successful step
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_step_complete: Vec<HookAction>,
+    /// Run when the workflow fails
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_failure: Vec<HookAction>,
+    /// Run when the workflow completes successfully
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_success: Vec<HookAction>,
+}
+
+impl WorkflowHooks {
+    /// Whether no hooks are declared
+    pub fn is_empty(&self) -> bool {
+        self.all().next().is_none()
+    }
+
+    /// All declared hook actions
+    pub fn all(&self) -> impl Iterator<Item = &HookAction> {
+        self.on_start
+            .iter()
+            .chain(&self.on_step_complete)
+            .chain(&self.on_failure)
+            .chain(&self.on_success)
+    }
+}
+
+/// Step-level hooks (`hooks:` on a step)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StepHooks {
+    /// Run before the step's command
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_start: Vec<HookAction>,
+    /// Run after the step succeeds
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_complete: Vec<HookAction>,
+    /// Run when the step fails
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub on_failure: Vec<HookAction>,
+}
+
+impl StepHooks {
+    /// Whether no hooks are declared
+    pub fn is_empty(&self) -> bool {
+        self.all().next().is_none()
+    }
+
+    /// All declared hook actions
+    pub fn all(&self) -> impl Iterator<Item = &HookAction> {
+        self.on_start.iter().chain(&self.on_complete).chain(&self.on_failure)
+    }
+}
+
+/// A single hook action.
+///
+/// Entries with a `url` are HTTP callbacks; anything else is parsed as a
+/// RAPS command (`type: custom`, `type: bucket`, ...).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum HookAction {
+    /// HTTP callback
+    Http(HttpHook),
+    /// RAPS command run like a workflow step
+    Command(RapsCommand),
+}
+
+impl HookAction {
+    /// Short description for logs and updates
+    pub fn describe(&self) -> String {
+        match self {
+            HookAction::Http(hook) => format!("{} {}", hook.method, hook.url),
+            HookAction::Command(RapsCommand::Custom { command, args }) => {
+                format!("raps {} {}", command, args.join(" ")).trim_end().to_string()
+            },
+            HookAction::Command(command) => format!("{:?}", command),
+        }
+    }
+}
+
+/// HTTP callback fired by a hook
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpHook {
+    /// Target URL (placeholders allowed)
+    pub url: String,
+    /// HTTP method
+    #[serde(default)]
+    pub method: HttpMethod,
+    /// Request body (placeholders allowed); sent as JSON when it is JSON,
+    /// see [`resolve_body`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Extra request headers
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+}
+
+/// HTTP method of a callback
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    Get,
+    #[default]
+    Post,
+    Put,
+}
+
+impl fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpMethod::Get => write!(f, "GET"),
+            HttpMethod::Post => write!(f, "POST"),
+            HttpMethod::Put => write!(f, "PUT"),
+        }
+    }
+}
+
+/// Lifecycle event that triggered a hook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookEvent {
+    /// Workflow is starting
+    WorkflowStart,
+    /// Workflow finished successfully
+    WorkflowSuccess,
+    /// Workflow failed
+    WorkflowFailure,
+    /// A step is starting
+    StepStart(StepId),
+    /// A step finished successfully
+    StepComplete(StepId),
+    /// A step failed
+    StepFailure(StepId),
+}
+
+impl fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HookEvent::WorkflowStart => write!(f, "on_start"),
+            HookEvent::WorkflowSuccess => write!(f, "on_success"),
+            HookEvent::WorkflowFailure => write!(f, "on_failure"),
+            HookEvent::StepStart(step) => write!(f, "{}.on_start", step),
+            HookEvent::StepComplete(step) => write!(f, "on_step_complete ({})", step),
+            HookEvent::StepFailure(step) => write!(f, "{}.on_failure", step),
+        }
+    }
+}
+
+/// Replace `{name}` placeholders in a string
+pub fn resolve_template(template: &str, placeholders: &HashMap<String, String>) -> String {
+    super::placeholders::resolve(template, placeholders, &mut Vec::new())
+}
+
+/// Request body of an HTTP callback
+#[derive(Debug, Clone, PartialEq)]
+pub enum HookBody {
+    Json(serde_json::Value),
+    Text(String),
+}
+
+/// Resolve the placeholders of a callback body. A JSON template has them
+/// filled into its string values, so quotes and newlines in the values stay
+/// escaped; other templates are resolved as text, and sent as JSON if the
+/// result is (e.g. `{"count": {n}}`).
+pub fn resolve_body(template: &str, placeholders: &HashMap<String, String>) -> HookBody {
+    fn resolve_strings(value: &mut serde_json::Value, placeholders: &HashMap<String, String>) {
+        match value {
+            serde_json::Value::String(text) => *text = resolve_template(text, placeholders),
+            serde_json::Value::Array(items) => items.iter_mut().for_each(|item| resolve_strings(item, placeholders)),
+            serde_json::Value::Object(map) => map.values_mut().for_each(|item| resolve_strings(item, placeholders)),
+            _ => {},
+        }
+    }
+
+    if let Ok(mut json) = serde_json::from_str::<serde_json::Value>(template) {
+        resolve_strings(&mut json, placeholders);
+        return HookBody::Json(json);
+    }
+    let body = resolve_template(template, placeholders);
+    match serde_json::from_str(&body) {
+        Ok(json) => HookBody::Json(json),
+        Err(_) => HookBody::Text(body),
+    }
+}
+
+/// Fire an HTTP callback, returning the response status line
+pub async fn send_http_hook(hook: &HttpHook, placeholders: &HashMap<String, String>) -> Result<String> {
+    let url = resolve_template(&hook.url, placeholders);
+    let client = reqwest::Client::builder()
+        .timeout(HTTP_HOOK_TIMEOUT)
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let mut request = match hook.method {
+        HttpMethod::Get => client.get(&url),
+        HttpMethod::Post => client.post(&url),
+        HttpMethod::Put => client.put(&url),
+    };
+    for (name, value) in &hook.headers {
+        request = request.header(name, resolve_template(value, placeholders));
+    }
+    if let Some(body) = &hook.body {
+        request = match resolve_body(body, placeholders) {
+            HookBody::Json(json) => request.json(&json),
+            HookBody::Text(body) => request.body(body),
+        };
+    }
+
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Hook request to {} failed", url))?;
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("Hook request to {} returned {}", url, status);
+    }
+    Ok(status.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hooks_parse_http_and_commands() {
+        let yaml = r#"
+on_start:
+  - type: custom
+    command: auth
+    args: [status]
+on_failure:
+  - url: https://hooks.example.com/{workflow_id}
+    body: '{"text": "Demo {workflow_id} failed: {error}"}'
+"#;
+        let hooks: WorkflowHooks = serde_yaml::from_str(yaml).unwrap();
+        assert!(matches!(&hooks.on_start[0], HookAction::Command(RapsCommand::Custom { command, .. }) if command == "auth"));
+        let HookAction::Http(http) = &hooks.on_failure[0] else {
+            panic!("expected an HTTP hook");
+        };
+        assert_eq!(http.method, HttpMethod::Post);
+        assert_eq!(hooks.on_start[0].describe(), "raps auth status");
+        assert!(hooks.on_success.is_empty());
+
+        let placeholders = HashMap::from([
+            ("workflow_id".to_string(), "nightly".to_string()),
+            ("error".to_string(), "boom".to_string()),
+        ]);
+        assert_eq!(
+            resolve_body(http.body.as_deref().unwrap(), &placeholders),
+            HookBody::Json(serde_json::json!({"text": "Demo nightly failed: boom"}))
+        );
+    }
+
+    #[test]
+    fn test_json_bodies_escape_values() {
+        let placeholders = HashMap::from([
+            ("error".to_string(), "bad \"name\"\n\"}, \"admin\": true".to_string()),
+            ("count".to_string(), "3".to_string()),
+        ]);
+        let body = resolve_body(r#"{"text": "failed: {error}", "tags": ["{count}"]}"#, &placeholders);
+        assert_eq!(
+            body,
+            HookBody::Json(serde_json::json!({
+                "text": "failed: bad \"name\"\n\"}, \"admin\": true",
+                "tags": ["3"],
+            }))
+        );
+
+        // Templates that are only JSON once resolved, and plain text
+        assert_eq!(resolve_body(r#"{"count": {count}}"#, &placeholders), HookBody::Json(serde_json::json!({"count": 3})));
+        assert_eq!(
+            resolve_body("failed: {error}", &placeholders),
+            HookBody::Text("failed: bad \"name\"\n\"}, \"admin\": true".to_string())
+        );
+    }
+}
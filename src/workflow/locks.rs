@@ -0,0 +1,187 @@
+// Named locks between concurrently running workflows
+//
+// Two runs that use the same demo bucket (or any other shared resource)
+// must not interleave. Workflows declare the locks they need, e.g.
+// `locks: ["bucket:{params.bucket_key}"]`; the executor resolves the names
+// against the run's placeholders and takes all of them before the first step
+// runs, releasing them when the run finishes, fails or is cancelled. Runs
+// asking for a taken lock wait in arrival order and are reported as waiting.
+// The task running a workflow holds a `LockGuard`, so the locks are released
+// once that task is done with the run's commands, even if it panics.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::futures::Notified;
+use tokio::sync::Notify;
+
+use super::types::{ExecutionHandle, WorkflowId};
+
+/// Why a run cannot take its locks yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockWait {
+    /// Lock that is taken
+    pub lock: String,
+    /// Workflow holding it, or queued for it ahead of this run
+    pub holder: WorkflowId,
+}
+
+#[derive(Debug, Default)]
+struct LockState {
+    /// Lock name to the run holding it
+    held: HashMap<String, ExecutionHandle>,
+    /// Runs waiting for locks, in arrival order
+    queue: Vec<(ExecutionHandle, Vec<String>)>,
+}
+
+/// Locks held by the runs of one executor
+#[derive(Debug, Default)]
+pub struct LockTable {
+    state: Mutex<LockState>,
+    released: Notify,
+}
+
+impl LockTable {
+    /// Take every lock in `names` for `handle`, or none of them: when one is
+    /// held by another run (or wanted by a run that asked earlier), `handle`
+    /// joins the queue and the blocking lock is returned
+    pub fn try_acquire(&self, handle: &ExecutionHandle, names: &[String]) -> Result<(), LockWait> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let blocked = names.iter().find_map(|name| {
+            let holder = state.held.get(name).filter(|holder| *holder != handle).or_else(|| {
+                state
+                    .queue
+                    .iter()
+                    .take_while(|(queued, _)| queued != handle)
+                    .find(|(_, wanted)| wanted.contains(name))
+                    .map(|(queued, _)| queued)
+            })?;
+            Some(LockWait {
+                lock: name.clone(),
+                holder: holder.workflow_id.clone(),
+            })
+        });
+
+        state.queue.retain(|(queued, _)| queued != handle || blocked.is_some());
+        match blocked {
+            Some(wait) => {
+                if !state.queue.iter().any(|(queued, _)| queued == handle) {
+                    state.queue.push((handle.clone(), names.to_vec()));
+                }
+                Err(wait)
+            },
+            None => {
+                for name in names {
+                    state.held.insert(name.clone(), handle.clone());
+                }
+                Ok(())
+            },
+        }
+    }
+
+    /// Resolves once locks are released after this call; create it before
+    /// [`Self::try_acquire`] so no release is missed
+    pub fn released(&self) -> Notified<'_> {
+        self.released.notified()
+    }
+
+    /// Release the locks of `handle` and take it out of the queue
+    pub fn release(&self, handle: &ExecutionHandle) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let before = (state.held.len(), state.queue.len());
+        state.held.retain(|_, holder| holder != handle);
+        state.queue.retain(|(queued, _)| queued != handle);
+        if before != (state.held.len(), state.queue.len()) {
+            self.released.notify_waiters();
+        }
+    }
+
+    /// Locks held right now, with the workflow holding each, sorted by name
+    pub fn held(&self) -> Vec<(String, WorkflowId)> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut held: Vec<(String, WorkflowId)> =
+            state.held.iter().map(|(name, holder)| (name.clone(), holder.workflow_id.clone())).collect();
+        held.sort();
+        held
+    }
+}
+
+/// Releases the locks of a run when dropped, unless they are kept for a
+/// later task of the same run (e.g. after a pause)
+#[derive(Debug)]
+pub struct LockGuard {
+    table: Arc<LockTable>,
+    handle: Option<ExecutionHandle>,
+}
+
+impl LockGuard {
+    /// Guard the locks `handle` holds or waits for in `table`
+    pub fn new(table: Arc<LockTable>, handle: ExecutionHandle) -> Self {
+        Self {
+            table,
+            handle: Some(handle),
+        }
+    }
+
+    /// Keep the locks held after this guard is gone
+    pub fn keep(mut self) {
+        self.handle = None;
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            self.table.release(&handle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locks_are_exclusive_and_queued() {
+        let table = LockTable::default();
+        let bucket = vec!["bucket:demo".to_string()];
+        let (first, second, third) = (
+            ExecutionHandle::new("upload".to_string()),
+            ExecutionHandle::new("translate".to_string()),
+            ExecutionHandle::new("cleanup".to_string()),
+        );
+
+        table.try_acquire(&first, &bucket).unwrap();
+        let wait = table.try_acquire(&second, &bucket).unwrap_err();
+        assert_eq!(wait, LockWait { lock: "bucket:demo".to_string(), holder: "upload".to_string() });
+        // Unrelated locks are free
+        table.try_acquire(&third, &["bucket:other".to_string()]).unwrap();
+        table.release(&third);
+
+        // The third run queues behind the second once the first is done
+        table.release(&first);
+        assert_eq!(table.try_acquire(&third, &bucket).unwrap_err().holder, "translate");
+        table.try_acquire(&second, &bucket).unwrap();
+        assert_eq!(table.held(), vec![("bucket:demo".to_string(), "translate".to_string())]);
+        table.release(&second);
+        table.try_acquire(&third, &bucket).unwrap();
+    }
+
+    #[test]
+    fn test_guard_releases_unless_kept() {
+        let table = Arc::new(LockTable::default());
+        let bucket = vec!["bucket:demo".to_string()];
+        let run = ExecutionHandle::new("upload".to_string());
+        table.try_acquire(&run, &bucket).unwrap();
+
+        LockGuard::new(Arc::clone(&table), run.clone()).keep();
+        assert_eq!(table.held().len(), 1);
+
+        // Also when the task holding it panics
+        let guarded = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _guard = LockGuard::new(Arc::clone(&table), run.clone());
+            panic!("step crashed");
+        }));
+        assert!(guarded.is_err());
+        assert!(table.held().is_empty());
+    }
+}
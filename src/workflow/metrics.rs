@@ -0,0 +1,316 @@
+// Prometheus/OpenMetrics export for RAPS Demo Workflows
+//
+// When demo workflows run as scheduled smoke tests, the executor records run
+// outcomes, step failures, durations, cost estimates and cleanups here. The
+// metrics can be scraped from a small localhost HTTP endpoint or pushed to a
+// Prometheus Pushgateway.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use super::types::{StepId, WorkflowId};
+
+/// Content type of the Prometheus text exposition format
+pub const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Metrics exporter settings (the `[metrics]` table of `demo.toml`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// Whether metrics are collected at all
+    pub enabled: bool,
+    /// Address to serve `/metrics` on, e.g. `127.0.0.1:9464`
+    pub listen_address: Option<String>,
+    /// Pushgateway base URL, e.g. `http://localhost:9091`
+    pub pushgateway_url: Option<String>,
+    /// Job name used when pushing
+    pub job_name: String,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_address: None,
+            pushgateway_url: None,
+            job_name: "raps-demo".to_string(),
+        }
+    }
+}
+
+/// Outcome label for a finished workflow run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RunOutcome {
+    Success,
+    Failure,
+    Cancelled,
+}
+
+impl RunOutcome {
+    fn label(&self) -> &'static str {
+        match self {
+            RunOutcome::Success => "success",
+            RunOutcome::Failure => "failure",
+            RunOutcome::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct MetricsData {
+    runs: BTreeMap<(WorkflowId, RunOutcome), u64>,
+    step_failures: BTreeMap<(WorkflowId, StepId), u64>,
+    duration_sum: BTreeMap<WorkflowId, f64>,
+    duration_count: BTreeMap<WorkflowId, u64>,
+    last_duration: BTreeMap<WorkflowId, f64>,
+    cost_estimate: BTreeMap<WorkflowId, f64>,
+    cleanups: BTreeMap<WorkflowId, u64>,
+}
+
+/// Thread-safe collection of workflow metrics
+#[derive(Debug, Default)]
+pub struct WorkflowMetrics {
+    data: Mutex<MetricsData>,
+}
+
+impl WorkflowMetrics {
+    /// Create an empty, shareable metrics registry
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Record the cost estimate of a workflow that is about to run
+    pub fn record_cost_estimate(&self, workflow_id: &str, max_cost_usd: f64) {
+        if let Ok(mut data) = self.data.lock() {
+            data.cost_estimate.insert(workflow_id.to_string(), max_cost_usd);
+        }
+    }
+
+    /// Record a failed step
+    pub fn record_step_failure(&self, workflow_id: &str, step_id: &str) {
+        if let Ok(mut data) = self.data.lock() {
+            *data
+                .step_failures
+                .entry((workflow_id.to_string(), step_id.to_string()))
+                .or_default() += 1;
+        }
+    }
+
+    /// Record a finished run with its duration (if it ran to completion)
+    pub fn record_run(&self, workflow_id: &str, outcome: RunOutcome, duration_secs: Option<f64>) {
+        if let Ok(mut data) = self.data.lock() {
+            *data.runs.entry((workflow_id.to_string(), outcome)).or_default() += 1;
+            if let Some(secs) = duration_secs {
+                *data.duration_sum.entry(workflow_id.to_string()).or_default() += secs;
+                *data.duration_count.entry(workflow_id.to_string()).or_default() += 1;
+                data.last_duration.insert(workflow_id.to_string(), secs);
+            }
+        }
+    }
+
+    /// Record a cleanup performed after a run
+    pub fn record_cleanup(&self, workflow_id: &str) {
+        if let Ok(mut data) = self.data.lock() {
+            *data.cleanups.entry(workflow_id.to_string()).or_default() += 1;
+        }
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let Ok(data) = self.data.lock() else {
+            return String::new();
+        };
+        let mut out = String::new();
+
+        write_family(
+            &mut out,
+            "raps_demo_workflow_runs_total",
+            "counter",
+            "Workflow runs by outcome",
+            data.runs.iter().map(|((workflow, outcome), count)| {
+                (
+                    format!("workflow=\"{}\",outcome=\"{}\"", escape(workflow), outcome.label()),
+                    *count as f64,
+                )
+            }),
+        );
+        write_family(
+            &mut out,
+            "raps_demo_step_failures_total",
+            "counter",
+            "Failed workflow steps",
+            data.step_failures.iter().map(|((workflow, step), count)| {
+                (
+                    format!("workflow=\"{}\",step=\"{}\"", escape(workflow), escape(step)),
+                    *count as f64,
+                )
+            }),
+        );
+
+        // Summary without quantiles: _sum and _count series
+        let _ = writeln!(out, "# HELP raps_demo_workflow_duration_seconds Duration of completed workflow runs");
+        let _ = writeln!(out, "# TYPE raps_demo_workflow_duration_seconds summary");
+        for (workflow, sum) in &data.duration_sum {
+            let count = data.duration_count.get(workflow).copied().unwrap_or_default();
+            let labels = format!("workflow=\"{}\"", escape(workflow));
+            let _ = writeln!(out, "raps_demo_workflow_duration_seconds_sum{{{}}} {}", labels, sum);
+            let _ = writeln!(out, "raps_demo_workflow_duration_seconds_count{{{}}} {}", labels, count);
+        }
+
+        write_family(
+            &mut out,
+            "raps_demo_workflow_last_duration_seconds",
+            "gauge",
+            "Duration of the most recent completed run",
+            data.last_duration
+                .iter()
+                .map(|(workflow, secs)| (format!("workflow=\"{}\"", escape(workflow)), *secs)),
+        );
+        write_family(
+            &mut out,
+            "raps_demo_workflow_cost_estimate_usd",
+            "gauge",
+            "Declared maximum cost estimate of the workflow",
+            data.cost_estimate
+                .iter()
+                .map(|(workflow, usd)| (format!("workflow=\"{}\"", escape(workflow)), *usd)),
+        );
+        write_family(
+            &mut out,
+            "raps_demo_cleanups_total",
+            "counter",
+            "Cleanups performed after workflow runs",
+            data.cleanups
+                .iter()
+                .map(|(workflow, count)| (format!("workflow=\"{}\"", escape(workflow)), *count as f64)),
+        );
+
+        out
+    }
+}
+
+/// Write one metric family with its HELP/TYPE header
+fn write_family<I: Iterator<Item = (String, f64)>>(
+    out: &mut String,
+    name: &str,
+    kind: &str,
+    help: &str,
+    samples: I,
+) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+    }
+}
+
+/// Escape a label value
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Serve `GET /metrics` on `address` in the background
+pub async fn serve_metrics(metrics: Arc<WorkflowMetrics>, address: &str) -> Result<JoinHandle<()>> {
+    let listener = TcpListener::bind(address)
+        .await
+        .with_context(|| format!("Failed to bind metrics endpoint on {}", address))?;
+    tracing::info!("Serving metrics on http://{}/metrics", address);
+
+    Ok(tokio::spawn(async move {
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                continue;
+            };
+            let metrics = Arc::clone(&metrics);
+            tokio::spawn(async move {
+                let mut buffer = [0u8; 1024];
+                let read = stream.read(&mut buffer).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buffer[..read]);
+                let path = request.split_whitespace().nth(1).unwrap_or("");
+
+                let response = if request.starts_with("GET ") && path == "/metrics" {
+                    let body = metrics.render();
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        METRICS_CONTENT_TYPE,
+                        body.len(),
+                        body
+                    )
+                } else {
+                    "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+                };
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    }))
+}
+
+/// Push the current metrics to a Prometheus Pushgateway
+pub async fn push_metrics(metrics: &WorkflowMetrics, gateway_url: &str, job: &str) -> Result<()> {
+    let url = format!("{}/metrics/job/{}", gateway_url.trim_end_matches('/'), job);
+    let response = reqwest::Client::new()
+        .put(&url)
+        .header(reqwest::header::CONTENT_TYPE, METRICS_CONTENT_TYPE)
+        .body(metrics.render())
+        .send()
+        .await
+        .with_context(|| format!("Failed to push metrics to {}", url))?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Pushgateway returned {} for {}", response.status(), url);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_render() {
+        let metrics = WorkflowMetrics::new();
+        metrics.record_cost_estimate("md-translate", 0.5);
+        metrics.record_run("md-translate", RunOutcome::Success, Some(12.5));
+        metrics.record_run("md-translate", RunOutcome::Success, Some(7.5));
+        metrics.record_step_failure("oss-upload", "upload");
+        metrics.record_run("oss-upload", RunOutcome::Failure, None);
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE raps_demo_workflow_runs_total counter"));
+        assert!(text.contains(r#"raps_demo_workflow_runs_total{workflow="md-translate",outcome="success"} 2"#));
+        assert!(text.contains(r#"raps_demo_workflow_runs_total{workflow="oss-upload",outcome="failure"} 1"#));
+        assert!(text.contains(r#"raps_demo_step_failures_total{workflow="oss-upload",step="upload"} 1"#));
+        assert!(text.contains(r#"raps_demo_workflow_duration_seconds_sum{workflow="md-translate"} 20"#));
+        assert!(text.contains(r#"raps_demo_workflow_duration_seconds_count{workflow="md-translate"} 2"#));
+        assert!(text.contains(r#"raps_demo_workflow_cost_estimate_usd{workflow="md-translate"} 0.5"#));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        drop(listener);
+
+        let metrics = WorkflowMetrics::new();
+        metrics.record_run("demo", RunOutcome::Cancelled, None);
+        let server = serve_metrics(Arc::clone(&metrics), &address).await.unwrap();
+
+        let mut stream = tokio::net::TcpStream::connect(&address).await.unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: x\r\n\r\n").await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        server.abort();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains(r#"outcome="cancelled"} 1"#));
+    }
+}
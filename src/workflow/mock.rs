@@ -0,0 +1,267 @@
+// Mock RAPS backend
+//
+// Answers RAPS commands with canned output instead of running the CLI, so
+// workflows can be exercised in CI and in tests without credentials or APS.
+// Each response applies to commands whose arguments contain its `match` text
+// (and, when it names a step, only to that step's command); commands without a
+// matching response succeed with an empty JSON object. Every command is
+// recorded, which is what snapshot tests compare.
+//
+// Responses can also come from fixture files recorded from real runs
+// (`--record-fixtures`), with credentials redacted and each command's duration
+// kept as simulated latency. Failure injection (`--inject-failure step-id`)
+// makes a step's command fail with an APS-style error, so failure handling
+// and recovery suggestions can be demoed and tested on purpose.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::client::CommandResult;
+use super::errors::ApsErrorKind;
+use super::types::StepId;
+use crate::utils::redact::redact;
+
+/// Canned answer for the commands containing `matches`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MockResponse {
+    /// Text the command's arguments must contain, e.g. `bucket create`
+    /// (empty: any command)
+    #[serde(rename = "match", default)]
+    pub matches: String,
+    /// Only answer the command of this step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<StepId>,
+    /// Exit code to report
+    #[serde(default)]
+    pub exit_code: i32,
+    /// Standard output
+    #[serde(default)]
+    pub stdout: String,
+    /// Standard error
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub stderr: String,
+    /// Simulated time the command takes, in milliseconds
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub latency_ms: u64,
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+impl MockResponse {
+    fn applies_to(&self, line: &str, step: Option<&StepId>) -> bool {
+        line.contains(&self.matches) && self.step.as_ref().map_or(true, |s| Some(s) == step)
+    }
+}
+
+/// Load canned responses from a fixture file (a YAML list of responses)
+pub fn load_fixtures(path: &Path) -> Result<Vec<MockResponse>> {
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read fixtures: {}", path.display()))?;
+    serde_yaml::from_str(&content).with_context(|| format!("Failed to parse fixtures: {}", path.display()))
+}
+
+/// A step whose command should fail, written `step-id` or `step-id=kind`
+/// (kind: auth, quota, conflict, not-found, network or rate-limit)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailureInjection {
+    pub step: StepId,
+    pub kind: ApsErrorKind,
+}
+
+impl std::str::FromStr for FailureInjection {
+    type Err = anyhow::Error;
+
+    fn from_str(spec: &str) -> Result<Self> {
+        let (step, kind) = match spec.split_once('=') {
+            Some((step, kind)) => match ApsErrorKind::from_code(kind) {
+                Some(kind) => (step, kind),
+                None => bail!(
+                    "Unknown failure kind '{}' (expected auth, quota, conflict, not-found, network or rate-limit)",
+                    kind
+                ),
+            },
+            None => (spec, ApsErrorKind::Network),
+        };
+        if step.is_empty() {
+            bail!("Failure injection needs a step ID: {}", spec);
+        }
+        Ok(Self {
+            step: step.to_string(),
+            kind,
+        })
+    }
+}
+
+impl FailureInjection {
+    /// Failed result carrying an APS-style JSON error of the injected kind
+    fn result(&self) -> CommandResult {
+        let (code, status) = match self.kind {
+            ApsErrorKind::Auth => ("UNAUTHORIZED", 401),
+            ApsErrorKind::Quota => ("QUOTA_EXCEEDED", 402),
+            ApsErrorKind::Conflict => ("CONFLICT", 409),
+            ApsErrorKind::NotFound => ("NOT_FOUND", 404),
+            ApsErrorKind::Network => ("UNAVAILABLE", 503),
+            ApsErrorKind::RateLimit => ("TOO_MANY_REQUESTS", 429),
+        };
+        let error = serde_json::json!({
+            "error": {
+                "code": code,
+                "status": status,
+                "message": format!("Injected failure in step '{}'", self.step),
+            }
+        });
+        CommandResult::new(1, String::new(), error.to_string(), Duration::ZERO)
+    }
+}
+
+/// Stand-in for the RAPS CLI, shared by every command of a client
+#[derive(Debug, Default)]
+pub struct MockBackend {
+    responses: Mutex<Vec<MockResponse>>,
+    /// Steps whose commands fail
+    failures: Mutex<Vec<FailureInjection>>,
+    /// Command lines received, oldest first
+    calls: Mutex<Vec<String>>,
+}
+
+impl MockBackend {
+    /// Backend answering with `responses`
+    pub fn new(responses: Vec<MockResponse>) -> Self {
+        Self {
+            responses: Mutex::new(responses),
+            ..Default::default()
+        }
+    }
+
+    /// Replace the canned responses
+    pub fn set_responses(&self, responses: Vec<MockResponse>) {
+        *self.responses.lock().unwrap_or_else(|e| e.into_inner()) = responses;
+    }
+
+    /// Replace the steps whose commands fail
+    pub fn set_failures(&self, failures: Vec<FailureInjection>) {
+        *self.failures.lock().unwrap_or_else(|e| e.into_inner()) = failures;
+    }
+
+    /// Record a command run for `step` (`None` outside steps, e.g. the
+    /// prerequisite checks) and answer it: an injected failure, else the first
+    /// matching response
+    pub fn respond(&self, args: &[String], step: Option<&StepId>) -> CommandResult {
+        let line = args.join(" ");
+        self.calls.lock().unwrap_or_else(|e| e.into_inner()).push(format!("raps {}", line));
+        let failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(failure) = failures.iter().find(|f| Some(&f.step) == step) {
+            return failure.result();
+        }
+        let responses = self.responses.lock().unwrap_or_else(|e| e.into_inner());
+        match responses.iter().find(|r| r.applies_to(&line, step)) {
+            Some(response) => CommandResult::new(
+                response.exit_code,
+                response.stdout.clone(),
+                response.stderr.clone(),
+                Duration::from_millis(response.latency_ms),
+            ),
+            None => CommandResult::new(0, "{}".to_string(), String::new(), Duration::ZERO),
+        }
+    }
+
+    /// Command lines received since the last call, oldest first
+    pub fn take_calls(&self) -> Vec<String> {
+        std::mem::take(&mut *self.calls.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// Collects the answers of a real RAPS CLI as fixtures for the mock backend
+#[derive(Debug, Default)]
+pub struct FixtureRecorder {
+    responses: Mutex<Vec<MockResponse>>,
+}
+
+impl FixtureRecorder {
+    /// Record the result of a command run for `step`, redacted
+    pub fn record(&self, args: &[String], step: Option<&StepId>, result: &CommandResult) {
+        // Subcommand and action, so replays match despite generated names
+        let matches = args.iter().take_while(|a| !a.starts_with('-')).take(2).cloned().collect::<Vec<_>>();
+        self.responses.lock().unwrap_or_else(|e| e.into_inner()).push(MockResponse {
+            matches: matches.join(" "),
+            step: step.cloned(),
+            exit_code: result.exit_code,
+            stdout: redact(&result.stdout),
+            stderr: redact(&result.stderr),
+            latency_ms: result.duration.as_millis() as u64,
+        });
+    }
+
+    /// Responses recorded so far, oldest first
+    pub fn responses(&self) -> Vec<MockResponse> {
+        self.responses.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Write the recorded responses as a fixture file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_yaml::to_string(&self.responses()).context("Failed to serialize fixtures")?;
+        fs::write(path, content).with_context(|| format!("Failed to write fixtures: {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_responses() {
+        let mock = MockBackend::new(vec![MockResponse {
+            matches: "bucket create".to_string(),
+            stdout: r#"{"bucketKey": "demo"}"#.to_string(),
+            ..Default::default()
+        }]);
+        let args = |line: &str| line.split(' ').map(str::to_string).collect::<Vec<_>>();
+
+        assert!(mock.respond(&args("bucket create --key demo"), None).stdout.contains("demo"));
+        let other = mock.respond(&args("auth status"), None);
+        assert!(other.success);
+        assert_eq!(other.stdout, "{}");
+        assert_eq!(mock.take_calls(), vec!["raps bucket create --key demo", "raps auth status"]);
+        assert!(mock.take_calls().is_empty());
+    }
+
+    #[test]
+    fn test_fixtures_and_injected_failures() {
+        let args = |line: &str| line.split(' ').map(str::to_string).collect::<Vec<_>>();
+        let create = "create".to_string();
+
+        // Recorded from a "real" run, with the token redacted
+        let recorder = FixtureRecorder::default();
+        recorder.record(
+            &args("bucket create --key demo-1234 --policy transient"),
+            Some(&create),
+            &CommandResult::new(0, r#"{"bucketKey": "demo-1234", "access_token": "abc"}"#.to_string(), String::new(), Duration::from_millis(250)),
+        );
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fixtures.yaml");
+        recorder.save(&path).unwrap();
+        let fixtures = load_fixtures(&path).unwrap();
+        assert_eq!(fixtures[0].matches, "bucket create");
+        assert_eq!(fixtures[0].latency_ms, 250);
+        assert!(fixtures[0].stdout.contains("[REDACTED]") && !fixtures[0].stdout.contains("abc"));
+        assert_eq!(redact("Authorization: Bearer abc.def"), "Authorization: Bearer [REDACTED]");
+
+        // Replayed only for the step it was recorded in
+        let mock = MockBackend::new(fixtures);
+        let replayed = mock.respond(&args("bucket create --key demo-9999"), Some(&create));
+        assert!(replayed.stdout.contains("demo-1234"));
+        assert_eq!(replayed.duration, Duration::from_millis(250));
+        assert_eq!(mock.respond(&args("bucket create --key demo-9999"), None).stdout, "{}");
+
+        mock.set_failures(vec!["create=conflict".parse().unwrap()]);
+        let failed = mock.respond(&args("bucket create --key demo-9999"), Some(&create));
+        assert!(!failed.success);
+        assert_eq!(ApsErrorKind::from_result(&failed), Some(ApsErrorKind::Conflict));
+        assert!("create=meltdown".parse::<FailureInjection>().is_err());
+    }
+}
@@ -1,49 +1,242 @@
 // Workflow Engine module for RAPS Demo Workflows
 //
 // This module provides the core execution engine for running individual workflow
-// scripts with progress tracking and error handling.
+// scripts with progress tracking and error handling. `WorkflowEngine` is the
+// single entry point used by the TUI, the CLI and embedding tools: it owns
+// discovery and the executor, so listing, validating, running and cancelling
+// workflows behave the same everywhere.
 
+pub mod artifacts;
+pub mod builtin;
+pub mod catalog;
+pub mod chaos;
+pub mod detail;
 pub mod client;
+pub mod diagram;
+pub mod diff;
 pub mod discovery;
+pub mod environment;
+pub mod errors;
+pub mod events;
 pub mod executor;
+pub mod exit_code;
+pub mod history;
+pub mod hooks;
+pub mod locks;
+pub mod metrics;
+pub mod mock;
+pub mod output;
+pub mod pack;
+pub mod parse_cache;
+pub mod placeholders;
+pub mod policy;
+pub mod rate_limit;
+pub mod region;
+pub mod run_summary;
+pub mod schedule;
+pub mod snapshot;
+pub mod tour;
+pub mod translation;
 pub mod types;
+pub mod vars;
+pub mod watch;
+pub mod watchdog;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::mpsc;
+
+use crate::config::DemoConfig;
+use crate::error::{DiscoveryError, RapsDemoError, RunError};
+use client::ProfileEnvironments;
+use crate::resource::{InterruptedWorkflowCleanup, ResourceManager};
+use history::{DurationHistory, SharedHistory};
+use metrics::WorkflowMetrics;
+use parse_cache::ParseCache;
 
 // Re-export commonly used types
 pub use discovery::*;
+pub use errors::ApsErrorKind;
+pub use events::{EventBus, UpdateReceiver};
 pub use executor::*;
 pub use types::*;
+pub use watchdog::{StallReport, WatchdogConfig};
+
+/// Error of a run the executor refused to start or couldn't control; library
+/// errors it passed on (an unknown profile) and missing prerequisites stay
+/// matchable
+fn run_error(error: anyhow::Error) -> RapsDemoError {
+    match error.downcast::<RapsDemoError>() {
+        Ok(error) => error,
+        Err(error) => match error.downcast::<PrerequisiteError>() {
+            Ok(missing) => RunError::Prerequisites(missing).into(),
+            Err(error) => RunError::Other(error).into(),
+        },
+    }
+}
 
 /// High-level workflow engine that coordinates discovery and execution
 pub struct WorkflowEngine {
     /// Workflow discovery instance
     discovery: WorkflowDiscovery,
+    /// Workflows found by the last discovery, in discovery order
+    workflows: Vec<WorkflowMetadata>,
     /// Workflow executor
     executor: Arc<WorkflowExecutor>,
-    /// Progress receiver for execution updates
-    update_receiver: Option<mpsc::UnboundedReceiver<ExecutionUpdate>>,
+    /// Run duration history shared with the executor
+    history: Option<SharedHistory>,
+    /// Demo settings (execution timeout, ...)
+    config: DemoConfig,
+    /// Resource manager recording interrupted workflows
+    resource_manager: Option<ResourceManager>,
 }
 
 impl WorkflowEngine {
     /// Create a new workflow engine instance
-    pub fn new<P: AsRef<std::path::Path>>(workflows_dir: P) -> Result<Self> {
+    pub fn new<P: AsRef<Path>>(workflows_dir: P) -> Result<Self, RapsDemoError> {
+        Self::with_executor(workflows_dir, WorkflowExecutor::new())
+    }
+
+    /// Create a workflow engine running workflows with `executor`
+    pub fn with_executor<P: AsRef<Path>>(workflows_dir: P, executor: WorkflowExecutor) -> Result<Self, RapsDemoError> {
+        Self::with_dirs([workflows_dir.as_ref().to_path_buf()], executor)
+    }
+
+    /// Create a workflow engine over several workflows directories, later ones
+    /// overriding same-ID workflows of earlier ones
+    pub fn with_dirs<I: IntoIterator<Item = PathBuf>>(
+        workflows_dirs: I,
+        executor: WorkflowExecutor,
+    ) -> Result<Self, RapsDemoError> {
+        Self::with_discovery(workflows_dirs, ParseCache::default(), false, executor)
+    }
+
+    fn with_discovery<I: IntoIterator<Item = PathBuf>>(
+        workflows_dirs: I,
+        cache: ParseCache,
+        include_builtin: bool,
+        executor: WorkflowExecutor,
+    ) -> Result<Self, RapsDemoError> {
         tracing::debug!("Initializing workflow engine");
 
-        let discovery = WorkflowDiscovery::new(workflows_dir)?;
-        let (executor, receiver) = WorkflowExecutor::new().with_progress_reporting();
+        // Rescans reuse the files parsed on creation
+        let (discovery, workflows) = WorkflowDiscovery::open(workflows_dirs, cache, include_builtin)?;
 
         Ok(Self {
             discovery,
+            workflows,
             executor: Arc::new(executor),
-            update_receiver: Some(receiver),
+            history: None,
+            config: DemoConfig::default(),
+            resource_manager: None,
         })
     }
 
+    /// Open `workflows_dir` (creating it if missing) with an executor that
+    /// records run durations into the saved history and, if given, `metrics`
+    pub fn open<P: AsRef<Path>>(workflows_dir: P, metrics: Option<Arc<WorkflowMetrics>>) -> Result<Self, RapsDemoError> {
+        Self::open_with_executor(
+            &[workflows_dir.as_ref().to_path_buf()],
+            WorkflowExecutor::new(),
+            metrics,
+            ParseCache::default(),
+            false,
+        )
+    }
+
+    /// Like [`WorkflowEngine::open`], over several directories, starting
+    /// from a configured executor and reusing the headers of `cache` whose
+    /// files haven't changed (e.g. [`ParseCache::load`] for the one saved by
+    /// the last start); `include_builtin` also lists the embedded pack, in
+    /// the same scan
+    pub fn open_with_executor(
+        workflows_dirs: &[PathBuf],
+        executor: WorkflowExecutor,
+        metrics: Option<Arc<WorkflowMetrics>>,
+        cache: ParseCache,
+        include_builtin: bool,
+    ) -> Result<Self, RapsDemoError> {
+        for workflows_dir in workflows_dirs {
+            if !workflows_dir.exists() {
+                std::fs::create_dir_all(workflows_dir)
+                    .with_context(|| format!("Failed to create workflows directory: {}", workflows_dir.display()))
+                    .map_err(DiscoveryError::Other)?;
+            }
+        }
+
+        let history = DurationHistory::load().shared();
+        let mut executor = executor.with_history(Arc::clone(&history));
+        if let Some(metrics) = metrics {
+            executor = executor.with_metrics(metrics);
+        }
+        let mut engine = Self::with_discovery(workflows_dirs.iter().cloned(), cache, include_builtin, executor)?;
+        engine.history = Some(history);
+        Ok(engine)
+    }
+
+    /// Also list the embedded built-in workflow pack
+    pub fn with_builtin_workflows(mut self) -> Result<Self, RapsDemoError> {
+        self.workflows = self.discovery.include_builtin()?;
+        Ok(self)
+    }
+
+    /// Use `config` for execution defaults
+    pub fn with_config(mut self, config: DemoConfig) -> Self {
+        self.set_config(config);
+        self
+    }
+
+    fn set_config(&mut self, config: DemoConfig) {
+        self.discovery.set_policy(config.policy.clone());
+        self.discovery.set_strict_placeholders(config.strict_placeholders);
+        self.discovery.set_vars_env(config.vars_env.clone());
+        self.discovery.set_region(config.region);
+        self.config = config;
+    }
+
+    /// Apply reloaded settings and RAPS profiles to the runs started from
+    /// now on; running workflows finish with the settings they started with
+    ///
+    /// Settings used while building the engine (workflows and assets
+    /// directories, rate limits, the subprocess environment, metrics) still
+    /// need a restart.
+    pub fn reconfigure(&mut self, config: DemoConfig, profiles: ProfileEnvironments) {
+        // Runs, locks and subscribers live behind the executor's shared
+        // state, so a reconfigured copy takes over without disturbing them
+        let executor = self
+            .executor
+            .as_ref()
+            .clone()
+            .with_profiles(profiles)
+            .with_policy(config.policy.clone())
+            .with_strict_placeholders(config.strict_placeholders)
+            .with_region(config.region)
+            .with_auto_reauth(config.auto_reauth)
+            .with_max_output_bytes(config.max_output_bytes)
+            .with_watchdog(config.watchdog.clone());
+        self.executor = Arc::new(executor);
+        self.set_config(config);
+    }
+
+    /// Record interrupted workflows with `resource_manager` instead of the default one
+    pub fn with_resource_manager(mut self, resource_manager: ResourceManager) -> Self {
+        self.resource_manager = Some(resource_manager);
+        self
+    }
+
+    /// Discovered workflows, in discovery order
+    pub fn list(&self) -> &[WorkflowMetadata] {
+        &self.workflows
+    }
+
+    /// Directories the workflows are discovered in
+    pub fn workflows_dirs(&self) -> &[PathBuf] {
+        self.discovery.workflows_dirs()
+    }
+
     /// Get discovered workflows
-    pub fn get_workflows(&self) -> &std::collections::HashMap<WorkflowId, WorkflowDefinition> {
+    pub fn get_workflows(&self) -> &DiscoveredWorkflows {
         self.discovery.get_workflows()
     }
 
@@ -52,18 +245,124 @@ impl WorkflowEngine {
         self.discovery.get_workflow(id)
     }
 
+    /// Categories declared by the discovered workflows
+    pub fn categories(&self) -> &CategoryRegistry {
+        self.discovery.categories()
+    }
+
     /// Refresh workflow discovery
-    pub fn refresh(&mut self) -> Result<Vec<WorkflowMetadata>> {
-        self.discovery.refresh()
+    pub fn refresh(&mut self) -> Result<Vec<WorkflowMetadata>, RapsDemoError> {
+        self.workflows = self.discovery.refresh()?;
+        Ok(self.workflows.clone())
+    }
+
+    /// Re-read one workflow file, keeping the previous version if it is invalid
+    pub fn reload_workflow<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<(WorkflowDefinition, ValidationResult), RapsDemoError> {
+        let (definition, result) = self.discovery.reload_workflow(path)?;
+        if result.is_valid {
+            let metadata = definition.metadata.clone();
+            match self.workflows.iter_mut().find(|w| w.id == metadata.id) {
+                Some(existing) => *existing = metadata,
+                None => self.workflows.push(metadata),
+            }
+        }
+        Ok((definition, result))
+    }
+
+    /// Validate a workflow by ID
+    pub fn validate(&self, workflow_id: &WorkflowId) -> Result<ValidationResult, RapsDemoError> {
+        self.discovery.validate_workflow(workflow_id)
+    }
+
+    /// Default execution options, honoring the configured timeout
+    pub fn execution_options(&self) -> ExecutionOptions {
+        ExecutionOptions {
+            timeout: chrono::Duration::seconds(self.config.max_execution_timeout_seconds as i64),
+            seed: self.config.seed,
+            chaos: self.config.chaos.clone(),
+            keep_temp_dir: self.config.keep_temp_dirs,
+            ..Default::default()
+        }
     }
 
     /// Execute a workflow by ID
-    pub async fn execute(&self, workflow_id: &WorkflowId, options: ExecutionOptions) -> Result<ExecutionHandle> {
-        let workflow = self.discovery.get_workflow(workflow_id)
-            .ok_or_else(|| anyhow::anyhow!("Workflow not found: {}", workflow_id))?
-            .clone();
+    pub async fn execute(
+        &self,
+        workflow_id: &WorkflowId,
+        options: ExecutionOptions,
+    ) -> Result<ExecutionHandle, RapsDemoError> {
+        let workflow = Arc::clone(self.discovery.get_workflows().load(workflow_id)?);
+
+        let violations = self.config.policy.violations(&workflow);
+        if !violations.is_empty() {
+            return Err(RunError::Blocked {
+                workflow: workflow_id.clone(),
+                violations,
+            }
+            .into());
+        }
+
+        self.executor
+            .execute_workflow(workflow, options)
+            .await
+            .map_err(run_error)
+    }
+
+    /// Cancel a running execution
+    pub async fn cancel(&self, handle: &ExecutionHandle) -> Result<(), RapsDemoError> {
+        self.executor.cancel_execution(handle).await.map_err(run_error)
+    }
+
+    /// Pause an execution after its current step
+    pub async fn pause(&self, handle: &ExecutionHandle) -> Result<(), RapsDemoError> {
+        self.executor.pause_execution(handle).await.map_err(run_error)
+    }
+
+    /// Resume a paused execution
+    pub async fn resume(&self, handle: &ExecutionHandle) -> Result<(), RapsDemoError> {
+        self.executor.resume_execution(handle).await.map_err(run_error)
+    }
+
+    /// Retry, skip or abort the failed step an execution is holding on
+    pub async fn decide(&self, handle: &ExecutionHandle, decision: StepDecision) -> Result<(), RapsDemoError> {
+        self.executor.decide_step_failure(handle, decision).await.map_err(run_error)
+    }
+
+    /// Fail a stalled execution, killing its running command
+    pub async fn force_fail(&self, handle: &ExecutionHandle, reason: &str) -> Result<(), RapsDemoError> {
+        self.executor.force_fail(handle, reason).await.map_err(run_error)
+    }
+
+    /// Cancel every running execution, returning their handles
+    pub async fn cancel_all(&self) -> Result<Vec<ExecutionHandle>, RapsDemoError> {
+        self.executor.cancel_all().await.map_err(run_error)
+    }
+
+    /// Cancel running workflows and record them as interrupted, with cleanup
+    /// instructions for the resources they created
+    pub async fn interrupt(&mut self) -> Result<Vec<InterruptedWorkflowCleanup>, RapsDemoError> {
+        let handles = self.cancel_all().await?;
+        if handles.is_empty() {
+            return Ok(Vec::new());
+        }
+        let interrupted_at = Utc::now();
+        let workflow_ids: Vec<_> = handles.into_iter().map(|h| h.workflow_id).collect();
+        tracing::warn!("Interrupted workflows: {}", workflow_ids.join(", "));
+        let resource_manager = match self.resource_manager.take() {
+            Some(resource_manager) => resource_manager,
+            None => ResourceManager::new()?,
+        };
+        resource_manager
+            .record_interrupted_workflows(&workflow_ids, interrupted_at)
+            .await
+    }
 
-        self.executor.execute_workflow(workflow, options).await
+    /// Subscribe to the execution updates; every subscriber gets each update
+    pub fn subscribe(&self) -> UpdateReceiver {
+        self.executor.subscribe()
     }
 
     /// Get the executor for direct access
@@ -71,8 +370,119 @@ impl WorkflowEngine {
         &self.executor
     }
 
-    /// Take the update receiver (can only be called once)
-    pub fn take_update_receiver(&mut self) -> Option<mpsc::UnboundedReceiver<ExecutionUpdate>> {
-        self.update_receiver.take()
+    /// Run duration history, when opened with [`WorkflowEngine::open`]
+    pub fn history(&self) -> Option<&SharedHistory> {
+        self.history.as_ref()
+    }
+
+    /// Demo settings in use
+    pub fn config(&self) -> &DemoConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ConfigError;
+    use std::collections::HashMap;
+
+    const WORKFLOW: &str = r#"
+metadata:
+  id: "engine-test"
+  name: "Engine Test"
+  description: "Workflow for engine tests"
+  category: "ObjectStorage"
+  prerequisites: []
+  estimated_duration: 30
+  required_assets: []
+steps:
+  - id: "list"
+    name: "List Buckets"
+    description: "List buckets"
+    command:
+      type: "bucket"
+      action: "list"
+      bucket_name: "engine-test"
+    expected_duration: 5
+    cleanup_commands: []
+cleanup: []
+"#;
+
+    #[tokio::test]
+    async fn test_engine_list_validate_and_subscribe() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("engine-test.yaml"), WORKFLOW).unwrap();
+
+        let config = DemoConfig {
+            max_execution_timeout_seconds: 60,
+            ..Default::default()
+        };
+        let mut engine = WorkflowEngine::new(dir.path()).unwrap().with_config(config);
+        let ids: Vec<_> = engine.list().iter().map(|w| w.id.as_str()).collect();
+        assert_eq!(ids, vec!["engine-test"]);
+        assert!(engine.validate(&"engine-test".to_string()).unwrap().is_valid);
+        assert!(engine.execute(&"missing".to_string(), engine.execution_options()).await.is_err());
+        assert_eq!(engine.execution_options().timeout, chrono::Duration::seconds(60));
+
+        let _tui = engine.subscribe();
+        let _history = engine.subscribe();
+        assert_eq!(engine.executor().events().subscriber_count(), 2);
+        assert!(engine.interrupt().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reconfigure_keeps_subscribers() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("engine-test.yaml"), WORKFLOW).unwrap();
+        let mut engine = WorkflowEngine::new(dir.path()).unwrap();
+        let _tui = engine.subscribe();
+
+        let config = DemoConfig {
+            max_execution_timeout_seconds: 90,
+            ..Default::default()
+        };
+        let profiles = HashMap::from([("staging".to_string(), HashMap::new())]);
+        engine.reconfigure(config, profiles);
+        assert_eq!(engine.execution_options().timeout, chrono::Duration::seconds(90));
+        assert!(engine.executor().raps_client().config().profiles.contains_key("staging"));
+        assert_eq!(engine.executor().events().subscriber_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_categorized() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing");
+        let error = WorkflowEngine::new(&missing).err().unwrap();
+        assert!(
+            matches!(&error, RapsDemoError::Discovery(DiscoveryError::MissingDirectory(path)) if *path == missing),
+            "{:?}",
+            error
+        );
+        assert!(format!("{:#}", error).contains("Workflows directory does not exist"));
+
+        std::fs::write(dir.path().join("engine-test.yaml"), WORKFLOW).unwrap();
+        let engine = WorkflowEngine::new(dir.path()).unwrap();
+        let error = engine.execute(&"missing".to_string(), engine.execution_options()).await.unwrap_err();
+        assert!(
+            matches!(&error, RapsDemoError::Discovery(DiscoveryError::WorkflowNotFound(id)) if id == "missing"),
+            "{:?}",
+            error
+        );
+
+        // An unknown profile is a discovery error, not a failed run
+        let options = ExecutionOptions {
+            profile: Some("nope".to_string()),
+            ..engine.execution_options()
+        };
+        let error = engine.execute(&"engine-test".to_string(), options).await.unwrap_err();
+        assert!(
+            matches!(&error, RapsDemoError::Discovery(DiscoveryError::UnknownProfile { profile, .. }) if profile == "nope"),
+            "{:?}",
+            error
+        );
+
+        let error = crate::config::ConfigManager::with_config_path(&missing).await.unwrap_err();
+        assert!(matches!(error, RapsDemoError::Config(ConfigError::NotFound(_))), "{:?}", error);
     }
 }
@@ -0,0 +1,183 @@
+// Size limits for captured command output
+//
+// Commands such as `object list` can print megabytes. Step results keep only
+// the head and tail of each stream, up to a configured size, with a marker in
+// between; the full output is written to a per-run folder under the config
+// directory so it can still be opened from the TUI or inspected afterwards.
+//
+// While a command runs, its output is read a line at a time for progress and
+// live output. Progress bars redraw their line with a bare `\r`, so that ends
+// a line as well as `\n` and `\r\n` do.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::config::types::ConfigPaths;
+
+use super::types::{ExecutionHandle, StepId};
+
+/// Default limit for each captured stream (64 KiB)
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// Folder for the full outputs of one run: `<outputs>/<workflow>/<run-id>`
+pub fn run_outputs_dir(handle: &ExecutionHandle) -> Result<PathBuf> {
+    Ok(ConfigPaths::outputs_dir()?
+        .join(&handle.workflow_id)
+        .join(handle.id.simple().to_string()))
+}
+
+/// Lines of a command's output stream as they are printed
+pub(crate) struct OutputLines<R> {
+    reader: R,
+    pending: Vec<u8>,
+    eof: bool,
+}
+
+impl<R: AsyncRead + Unpin> OutputLines<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self {
+            reader,
+            pending: Vec::new(),
+            eof: false,
+        }
+    }
+
+    /// The next line and the terminator it ended with (empty for a last line
+    /// without one), or `None` at the end of the stream. Cancel safe: a call
+    /// dropped while waiting for output loses nothing.
+    pub(crate) async fn next_line(&mut self) -> std::io::Result<Option<(Vec<u8>, Vec<u8>)>> {
+        loop {
+            if let Some(end) = self.pending.iter().position(|&b| b == b'\n' || b == b'\r') {
+                let crlf = self.pending[end] == b'\r' && self.pending.get(end + 1) == Some(&b'\n');
+                // A `\r` at the end of what was read may be half of a `\r\n`
+                let undecided = self.pending[end] == b'\r' && end + 1 == self.pending.len() && !self.eof;
+                if !undecided {
+                    let mut line: Vec<u8> = self.pending.drain(..end + if crlf { 2 } else { 1 }).collect();
+                    let terminator = line.split_off(end);
+                    return Ok(Some((line, terminator)));
+                }
+            } else if self.eof {
+                return Ok((!self.pending.is_empty()).then(|| (std::mem::take(&mut self.pending), Vec::new())));
+            }
+
+            let mut chunk = [0u8; 8192];
+            let read = self.reader.read(&mut chunk).await?;
+            if read == 0 {
+                self.eof = true;
+            }
+            self.pending.extend_from_slice(&chunk[..read]);
+        }
+    }
+}
+
+/// The lines of captured output as a terminal ended up showing them: of a
+/// line redrawn with `\r` (a progress bar), only its last state
+pub fn displayed_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines().map(|line| line.rsplit('\r').find(|state| !state.is_empty()).unwrap_or_default())
+}
+
+/// Keep the first and last `max_bytes / 2` bytes of `text`, with a marker
+/// naming what was cut; text within the limit (or a limit of 0) is unchanged
+pub fn truncate_head_tail(text: &str, max_bytes: usize, full_output: Option<&Path>) -> String {
+    if max_bytes == 0 || text.len() <= max_bytes {
+        return text.to_string();
+    }
+    let mut head_end = max_bytes / 2;
+    while !text.is_char_boundary(head_end) {
+        head_end -= 1;
+    }
+    let mut tail_start = text.len() - max_bytes / 2;
+    while !text.is_char_boundary(tail_start) {
+        tail_start += 1;
+    }
+
+    let mut marker = format!("\n... [{} bytes truncated", tail_start - head_end);
+    if let Some(path) = full_output {
+        marker.push_str(&format!("; full output: {}", path.display()));
+    }
+    marker.push_str("] ...\n");
+    format!("{}{}{}", &text[..head_end], marker, &text[tail_start..])
+}
+
+/// Limit a step's stdout and stderr to `max_bytes` each, writing streams
+/// over the limit in full to `dir/<step>.<stream>.log`
+///
+/// Returns the truncated streams and the files written.
+pub fn limit_step_output(
+    dir: &Path,
+    step_id: &StepId,
+    stdout: &str,
+    stderr: &str,
+    max_bytes: usize,
+) -> Result<(String, String, Vec<PathBuf>)> {
+    let mut files = Vec::new();
+    let mut limit = |stream: &str, text: &str| -> Result<String> {
+        if max_bytes == 0 || text.len() <= max_bytes {
+            return Ok(text.to_string());
+        }
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        let path = dir.join(format!("{}.{}.log", step_id, stream));
+        std::fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+        let truncated = truncate_head_tail(text, max_bytes, Some(&path));
+        files.push(path);
+        Ok(truncated)
+    };
+    let stdout = limit("stdout", stdout)?;
+    let stderr = limit("stderr", stderr)?;
+    Ok((stdout, stderr, files))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_output_lines_end_at_any_line_break() {
+        let mut lines = OutputLines::new(&b"one\ntwo\r\n 10%\r 55%\rdone"[..]);
+        let mut read = Vec::new();
+        while let Some((line, terminator)) = lines.next_line().await.unwrap() {
+            read.push((String::from_utf8(line).unwrap(), String::from_utf8(terminator).unwrap()));
+        }
+        let expected = [("one", "\n"), ("two", "\r\n"), (" 10%", "\r"), (" 55%", "\r"), ("done", "")];
+        assert_eq!(read, expected.map(|(l, t)| (l.to_string(), t.to_string())));
+    }
+
+    #[test]
+    fn test_displayed_lines() {
+        let lines: Vec<&str> = displayed_lines("start\r\n 10%\r 90%\rdone\r\nnext\n").collect();
+        assert_eq!(lines, vec!["start", "done", "next"]);
+    }
+
+    #[tokio::test]
+    async fn test_output_lines_join_a_split_crlf() {
+        let (mut writer, reader) = tokio::io::duplex(64);
+        let mut lines = OutputLines::new(reader);
+        tokio::io::AsyncWriteExt::write_all(&mut writer, b"a\r").await.unwrap();
+        let next = tokio::spawn(async move { lines.next_line().await.unwrap() });
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        tokio::io::AsyncWriteExt::write_all(&mut writer, b"\nb").await.unwrap();
+        assert_eq!(next.await.unwrap(), Some((b"a".to_vec(), b"\r\n".to_vec())));
+    }
+
+    #[test]
+    fn test_truncate_and_spill() {
+        assert_eq!(truncate_head_tail("short", 10, None), "short");
+        assert_eq!(
+            truncate_head_tail("0123456789abcdef", 8, None),
+            "0123\n... [8 bytes truncated] ...\ncdef"
+        );
+        // Cuts never split a character
+        let cut = truncate_head_tail("ééééé", 6, None);
+        assert_eq!(cut, "é\n... [6 bytes truncated] ...\né");
+
+        let dir = tempfile::tempdir().unwrap();
+        let big = "x".repeat(100);
+        let (stdout, stderr, files) =
+            limit_step_output(dir.path(), &"list".to_string(), &big, "small", 20).unwrap();
+        assert_eq!(stderr, "small");
+        assert_eq!(files, vec![dir.path().join("list.stdout.log")]);
+        assert_eq!(std::fs::read_to_string(&files[0]).unwrap(), big);
+        assert!(stdout.contains("[80 bytes truncated; full output: "));
+    }
+}
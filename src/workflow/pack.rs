@@ -0,0 +1,631 @@
+// Workflow packs for RAPS Demo Workflows
+//
+// A `.rapspack` is a ZIP archive with a `pack.yaml` manifest at its root, the
+// pack's workflow files under `workflows/` and an optional `assets.yaml`
+// manifest. `build_pack` creates one from a source directory (adding
+// provenance: who built it, when, with which raps-demo version and from
+// where); `install_pack` unpacks a file or URL into the packs directory,
+// where each installed pack's `workflows/` folder is added to discovery.
+// Installing with a pinned version refuses any other version of the pack.
+//
+// Packs can run arbitrary Custom commands, so they are checksummed: the
+// manifest lists the SHA-256 of every file, and `build_pack` publishes the
+// archive's SHA-256 next to it (`<pack>.rapspack.sha256`). Installing checks
+// the archive against `--sha256` or the published checksum and refuses packs
+// without one unless unverified packs are allowed. Installed packs whose
+// files no longer match, or that have files the manifest doesn't list, are
+// reported as modified and not loaded.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// File extension of workflow packs
+pub const PACK_EXTENSION: &str = "rapspack";
+
+/// Manifest file at the root of a pack
+pub const MANIFEST_FILE: &str = "pack.yaml";
+
+/// Folder holding a pack's workflows
+pub const WORKFLOWS_DIR: &str = "workflows";
+
+/// Optional asset manifest of a pack
+pub const ASSETS_MANIFEST: &str = "assets.yaml";
+
+/// Extension of published archive checksums (`sha256sum` format)
+pub const CHECKSUM_EXTENSION: &str = "sha256";
+
+/// Whether an installed pack can be trusted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackTrust {
+    /// The archive matched a published or given SHA-256
+    Verified,
+    /// Installed without a checksum to check against
+    #[default]
+    Unverified,
+    /// Files changed since installation
+    Modified,
+}
+
+impl std::fmt::Display for PackTrust {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            PackTrust::Verified => "verified",
+            PackTrust::Unverified => "unverified",
+            PackTrust::Modified => "modified",
+        })
+    }
+}
+
+/// Lowercase hex SHA-256 of `bytes`
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Checksum from a `sha256sum`-style line (`<hex>  <file>` or just `<hex>`)
+fn parse_checksum(content: &str) -> Result<String> {
+    let checksum = content.split_whitespace().next().unwrap_or_default().to_ascii_lowercase();
+    if checksum.len() != 64 || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+        anyhow::bail!("Invalid SHA-256 checksum '{}'", checksum);
+    }
+    Ok(checksum)
+}
+
+/// Where a pack came from
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Person or team that built the pack
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Repository or location of the pack sources
+    #[serde(default)]
+    pub source: Option<String>,
+    /// When the pack was built
+    #[serde(default)]
+    pub built_at: Option<DateTime<Utc>>,
+    /// raps-demo version that built the pack
+    #[serde(default)]
+    pub built_with: Option<String>,
+    /// File or URL the pack was installed from
+    #[serde(default)]
+    pub installed_from: Option<String>,
+}
+
+/// Contents of `pack.yaml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackManifest {
+    /// Pack name, also its directory name once installed
+    pub name: String,
+    /// Pack version
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub provenance: Provenance,
+    /// Workflow files, relative to `workflows/` (filled in by `build_pack`)
+    #[serde(default)]
+    pub workflows: Vec<String>,
+    /// SHA-256 of every packed file, keyed by its path in the archive
+    #[serde(default)]
+    pub checksums: BTreeMap<String, String>,
+    /// Whether the installed version is pinned
+    #[serde(default)]
+    pub pinned: bool,
+    /// Verification result recorded at installation
+    #[serde(default)]
+    pub trust: PackTrust,
+}
+
+/// Check `name` is usable as the name of a pack's directory: a single path
+/// component that isn't hidden (so not `.` or `..` either)
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !name.starts_with('.');
+    if !valid {
+        anyhow::bail!("Invalid pack name '{}' (use letters, digits, '-', '_' and '.')", name);
+    }
+    Ok(())
+}
+
+impl PackManifest {
+    /// Check the name is usable as a directory name
+    pub fn validate(&self) -> Result<()> {
+        validate_name(&self.name)?;
+        if self.version.trim().is_empty() {
+            anyhow::bail!("Pack '{}' has no version", self.name);
+        }
+        Ok(())
+    }
+}
+
+/// Build a `.rapspack` from `source_dir` (with `pack.yaml`, `workflows/` and
+/// optionally `assets.yaml`), returning the archive path
+pub fn build_pack(source_dir: &Path, output: Option<PathBuf>) -> Result<PathBuf> {
+    let mut manifest = read_manifest(&source_dir.join(MANIFEST_FILE))?;
+    manifest.validate()?;
+
+    let workflows_dir = source_dir.join(WORKFLOWS_DIR);
+    let mut files = Vec::new();
+    for entry in WalkDir::new(&workflows_dir).into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "yaml" || ext == "yml") {
+            let relative = path.strip_prefix(&workflows_dir)?.to_string_lossy().replace('\\', "/");
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read workflow file: {}", path.display()))?;
+            // Catch broken workflows before they are shipped
+            if !super::tour::is_tour_file(path) && !super::vars::is_vars_file(path) {
+                serde_yaml::from_str::<super::WorkflowDefinition>(&content)
+                    .with_context(|| format!("Failed to parse workflow YAML: {}", path.display()))?;
+            }
+            files.push((relative, content));
+        }
+    }
+    if files.is_empty() {
+        anyhow::bail!("No workflows found in {}", workflows_dir.display());
+    }
+    files.sort();
+
+    let mut entries: Vec<(String, Vec<u8>)> = files
+        .into_iter()
+        .map(|(relative, content)| (format!("{}/{}", WORKFLOWS_DIR, relative), content.into_bytes()))
+        .collect();
+    let assets = source_dir.join(ASSETS_MANIFEST);
+    if assets.is_file() {
+        entries.push((ASSETS_MANIFEST.to_string(), std::fs::read(&assets)?));
+    }
+
+    manifest.workflows = entries
+        .iter()
+        .filter_map(|(name, _)| name.strip_prefix(&format!("{}/", WORKFLOWS_DIR)).map(str::to_string))
+        .collect();
+    manifest.checksums = entries.iter().map(|(name, bytes)| (name.clone(), sha256_hex(bytes))).collect();
+    manifest.pinned = false;
+    manifest.trust = PackTrust::Unverified;
+    manifest.provenance.built_at = Some(Utc::now());
+    manifest.provenance.built_with = Some(env!("CARGO_PKG_VERSION").to_string());
+    manifest.provenance.installed_from = None;
+
+    let output = output.unwrap_or_else(|| {
+        PathBuf::from(format!("{}-{}.{}", manifest.name, manifest.version, PACK_EXTENSION))
+    });
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::FileOptions::default();
+    zip.start_file(MANIFEST_FILE, options)?;
+    zip.write_all(serde_yaml::to_string(&manifest)?.as_bytes())?;
+    for (name, bytes) in &entries {
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(bytes)?;
+    }
+    let archive = zip.finish().context("Failed to finish pack archive")?.into_inner();
+
+    std::fs::write(&output, &archive).with_context(|| format!("Failed to write pack: {}", output.display()))?;
+    let file_name = output.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    std::fs::write(
+        checksum_path(&output),
+        format!("{}  {}\n", sha256_hex(&archive), file_name),
+    )
+    .context("Failed to write pack checksum")?;
+
+    Ok(output)
+}
+
+/// Where the checksum of a pack archive is published
+pub fn checksum_path(archive: &Path) -> PathBuf {
+    let mut path = archive.as_os_str().to_owned();
+    path.push(format!(".{}", CHECKSUM_EXTENSION));
+    PathBuf::from(path)
+}
+
+/// Options for installing a pack
+#[derive(Debug, Clone, Default)]
+pub struct InstallOptions {
+    /// Require this version and pin it
+    pub version: Option<String>,
+    /// Replace a pinned pack with another version
+    pub force: bool,
+    /// Expected SHA-256 of the archive (the published checksum otherwise)
+    pub sha256: Option<String>,
+    /// Install packs that have no checksum to verify against
+    pub allow_unverified: bool,
+}
+
+/// Install a pack from a file path or an http(s) URL into `packs_dir`,
+/// verifying it against the checksum published next to it
+pub async fn install_pack(source: &str, packs_dir: &Path, options: &InstallOptions) -> Result<PackManifest> {
+    let remote = source.starts_with("http://") || source.starts_with("https://");
+    let bytes = if remote {
+        let response = reqwest::get(source)
+            .await
+            .with_context(|| format!("Failed to download pack: {}", source))?
+            .error_for_status()
+            .with_context(|| format!("Failed to download pack: {}", source))?;
+        response.bytes().await?.to_vec()
+    } else {
+        std::fs::read(source).with_context(|| format!("Failed to read pack: {}", source))?
+    };
+
+    let mut options = options.clone();
+    if options.sha256.is_none() {
+        let published = if remote {
+            let url = format!("{}.{}", source, CHECKSUM_EXTENSION);
+            match reqwest::get(&url).await.and_then(|r| r.error_for_status()) {
+                Ok(response) => Some(response.text().await?),
+                Err(e) => {
+                    tracing::debug!("No published checksum at {}: {}", url, e);
+                    None
+                },
+            }
+        } else {
+            std::fs::read_to_string(checksum_path(Path::new(source))).ok()
+        };
+        options.sha256 = published.as_deref().map(parse_checksum).transpose()?;
+    }
+    install_pack_bytes(&bytes, source, packs_dir, &options)
+}
+
+/// Install a pack from its archive bytes
+pub fn install_pack_bytes(
+    bytes: &[u8],
+    source: &str,
+    packs_dir: &Path,
+    options: &InstallOptions,
+) -> Result<PackManifest> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).context("Not a valid .rapspack archive")?;
+    let mut manifest: PackManifest = {
+        let mut file = archive
+            .by_name(MANIFEST_FILE)
+            .with_context(|| format!("Pack has no {}", MANIFEST_FILE))?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+        serde_yaml::from_str(&content).with_context(|| format!("Failed to parse {}", MANIFEST_FILE))?
+    };
+    manifest.validate()?;
+
+    manifest.trust = match &options.sha256 {
+        Some(expected) => {
+            let expected = parse_checksum(expected)?;
+            let actual = sha256_hex(bytes);
+            if actual != expected {
+                anyhow::bail!(
+                    "Checksum mismatch for pack '{}': expected {}, got {}",
+                    manifest.name,
+                    expected,
+                    actual
+                );
+            }
+            PackTrust::Verified
+        },
+        None if options.allow_unverified => {
+            tracing::warn!("Installing unverified pack '{}' from {}", manifest.name, source);
+            PackTrust::Unverified
+        },
+        None => anyhow::bail!(
+            "No SHA-256 checksum published for pack '{}'; pass --sha256 or --allow-unverified",
+            manifest.name
+        ),
+    };
+
+    // Read and check every file before touching an existing installation
+    let mut files = Vec::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if file.is_dir() || file.name() == MANIFEST_FILE {
+            continue;
+        }
+        // Reject entries escaping the pack directory
+        let Some(relative) = file.enclosed_name().map(Path::to_path_buf) else {
+            anyhow::bail!("Pack contains an unsafe path: {}", file.name());
+        };
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        if manifest.checksums.get(file.name()) != Some(&sha256_hex(&bytes)) {
+            anyhow::bail!("Pack file {} does not match the pack manifest", file.name());
+        }
+        files.push((relative, bytes));
+    }
+
+    if let Some(version) = &options.version {
+        if *version != manifest.version {
+            anyhow::bail!(
+                "Pack '{}' is version {}, but version {} was requested",
+                manifest.name,
+                manifest.version,
+                version
+            );
+        }
+    }
+
+    let target = packs_dir.join(&manifest.name);
+    if let Ok(installed) = read_manifest(&target.join(MANIFEST_FILE)) {
+        if installed.pinned && installed.version != manifest.version && !options.force {
+            anyhow::bail!(
+                "Pack '{}' is pinned to version {}; use --force to install {}",
+                installed.name,
+                installed.version,
+                manifest.version
+            );
+        }
+    }
+
+    // Unpack next to the installed packs (hidden, so it isn't listed) and
+    // move the complete pack into place: a failed install leaves the
+    // previous version as it was
+    std::fs::create_dir_all(packs_dir)
+        .with_context(|| format!("Failed to create packs directory: {}", packs_dir.display()))?;
+    let staging = tempfile::Builder::new()
+        .prefix(".install-")
+        .tempdir_in(packs_dir)
+        .context("Failed to create staging directory")?;
+    for (relative, bytes) in files {
+        let path = staging.path().join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, bytes).with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    manifest.pinned = options.version.is_some();
+    manifest.provenance.installed_from = Some(source.to_string());
+    std::fs::write(staging.path().join(MANIFEST_FILE), serde_yaml::to_string(&manifest)?)
+        .context("Failed to record installed pack")?;
+
+    let previous = packs_dir.join(format!(".previous-{}", manifest.name));
+    if target.exists() {
+        if previous.exists() {
+            std::fs::remove_dir_all(&previous)?;
+        }
+        std::fs::rename(&target, &previous)
+            .with_context(|| format!("Failed to replace previous version: {}", target.display()))?;
+    }
+    if let Err(e) = std::fs::rename(staging.path(), &target) {
+        if previous.exists() {
+            let _ = std::fs::rename(&previous, &target);
+        }
+        return Err(e).with_context(|| format!("Failed to install pack into {}", target.display()));
+    }
+    if previous.exists() {
+        std::fs::remove_dir_all(&previous)
+            .with_context(|| format!("Failed to remove previous version: {}", previous.display()))?;
+    }
+    Ok(manifest)
+}
+
+/// Remove an installed pack
+pub fn remove_pack(name: &str, packs_dir: &Path) -> Result<()> {
+    validate_name(name)?;
+    let target = packs_dir.join(name);
+    if !target.join(MANIFEST_FILE).is_file() {
+        anyhow::bail!("Pack '{}' is not installed", name);
+    }
+    std::fs::remove_dir_all(&target).with_context(|| format!("Failed to remove {}", target.display()))
+}
+
+/// An installed pack
+#[derive(Debug, Clone)]
+pub struct InstalledPack {
+    pub manifest: PackManifest,
+    /// Directory the pack was unpacked into
+    pub path: PathBuf,
+}
+
+impl InstalledPack {
+    /// Directory with the pack's workflows
+    pub fn workflows_dir(&self) -> PathBuf {
+        self.path.join(WORKFLOWS_DIR)
+    }
+
+    /// The pack's asset manifest, if it has one
+    pub fn assets_manifest(&self) -> Option<PathBuf> {
+        Some(self.path.join(ASSETS_MANIFEST)).filter(|path| path.is_file())
+    }
+
+    /// Trust recorded at installation, or `Modified` when a packed file no
+    /// longer matches its checksum, is missing, or a file was added
+    pub fn trust(&self) -> PackTrust {
+        let mut unchecked = self.manifest.checksums.len();
+        let mut modified = false;
+        for entry in WalkDir::new(&self.path).into_iter() {
+            let Ok(entry) = entry else {
+                modified = true;
+                break;
+            };
+            if entry.file_type().is_dir() {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(&self.path).unwrap_or(entry.path());
+            let name = relative.to_string_lossy().replace('\\', "/");
+            if name == MANIFEST_FILE {
+                continue;
+            }
+            let matches = self.manifest.checksums.get(&name).is_some_and(|checksum| {
+                std::fs::read(entry.path()).is_ok_and(|bytes| sha256_hex(&bytes) == *checksum)
+            });
+            if !matches {
+                modified = true;
+                break;
+            }
+            unchecked -= 1;
+        }
+        if modified || unchecked > 0 {
+            PackTrust::Modified
+        } else {
+            self.manifest.trust
+        }
+    }
+
+    /// Short description for listings, e.g. `sales-demos 1.2.0 (verified)`
+    pub fn label(&self) -> String {
+        format!("{} {} ({})", self.manifest.name, self.manifest.version, self.trust())
+    }
+}
+
+/// The pack installed in `packs_dir` a workflow file belongs to, if any:
+/// the file must be under `<packs_dir>/<pack>/workflows/`
+pub fn installed_pack_for(script_path: &Path, packs_dir: &Path) -> Option<InstalledPack> {
+    let mut components = script_path.strip_prefix(packs_dir).ok()?.components();
+    let (Some(Component::Normal(name)), Some(Component::Normal(workflows))) =
+        (components.next(), components.next())
+    else {
+        return None;
+    };
+    if workflows != WORKFLOWS_DIR {
+        return None;
+    }
+    let path = packs_dir.join(name);
+    let manifest = read_manifest(&path.join(MANIFEST_FILE)).ok()?;
+    Some(InstalledPack { manifest, path })
+}
+
+/// Installed packs, sorted by name
+pub fn list_packs(packs_dir: &Path) -> Vec<InstalledPack> {
+    let Ok(entries) = std::fs::read_dir(packs_dir) else {
+        return Vec::new();
+    };
+    let mut packs: Vec<InstalledPack> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        // Hidden directories are installs in progress
+        .filter(|path| path.is_dir() && !path.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')))
+        .filter_map(|path| match read_manifest(&path.join(MANIFEST_FILE)) {
+            Ok(manifest) => Some(InstalledPack { manifest, path }),
+            Err(e) => {
+                tracing::warn!("Skipping pack in {}: {:#}", path.display(), e);
+                None
+            },
+        })
+        .collect();
+    packs.sort_by(|a, b| a.manifest.name.cmp(&b.manifest.name));
+    packs
+}
+
+fn read_manifest(path: &Path) -> Result<PackManifest> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read pack manifest: {}", path.display()))?;
+    serde_yaml::from_str(&content).with_context(|| format!("Failed to parse pack manifest: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack_source(dir: &Path, version: &str) {
+        std::fs::create_dir_all(dir.join("workflows/oss")).unwrap();
+        std::fs::write(
+            dir.join(MANIFEST_FILE),
+            format!("name: sales-demos\nversion: \"{}\"\nprovenance:\n  author: SE team\n", version),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("workflows/oss/bucket.yaml"),
+            super::super::builtin::BUILTIN_WORKFLOWS[1].source,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_build_install_pin_and_remove() {
+        let temp = tempfile::tempdir().unwrap();
+        let source = temp.path().join("src");
+        let packs = temp.path().join("packs");
+        pack_source(&source, "1.0.0");
+
+        let archive = build_pack(&source, Some(temp.path().join("p1.rapspack"))).unwrap();
+        let bytes = std::fs::read(&archive).unwrap();
+        let pinned = InstallOptions {
+            version: Some("1.0.0".to_string()),
+            allow_unverified: true,
+            ..Default::default()
+        };
+        let manifest = install_pack_bytes(&bytes, "p1.rapspack", &packs, &pinned).unwrap();
+        assert_eq!(manifest.workflows, vec!["oss/bucket.yaml"]);
+        assert_eq!(manifest.provenance.author.as_deref(), Some("SE team"));
+        assert!(manifest.provenance.built_at.is_some());
+
+        let installed = list_packs(&packs);
+        assert_eq!(installed.len(), 1);
+        assert!(installed[0].manifest.pinned);
+        assert!(installed[0].workflows_dir().join("oss/bucket.yaml").is_file());
+
+        // A pinned pack is not replaced by another version without --force
+        pack_source(&source, "2.0.0");
+        let bytes = std::fs::read(build_pack(&source, Some(temp.path().join("p2.rapspack"))).unwrap()).unwrap();
+        let unpinned = InstallOptions {
+            allow_unverified: true,
+            ..Default::default()
+        };
+        assert!(install_pack_bytes(&bytes, "p2", &packs, &unpinned).is_err());
+        assert!(install_pack_bytes(&bytes, "p2", &packs, &pinned).is_err());
+        let forced = InstallOptions {
+            force: true,
+            ..unpinned
+        };
+        assert_eq!(install_pack_bytes(&bytes, "p2", &packs, &forced).unwrap().version, "2.0.0");
+
+        // Nothing is left of the staged install or the replaced version
+        let entries: Vec<_> = std::fs::read_dir(&packs).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(entries, vec!["sales-demos"]);
+
+        // Names are single directory names, not paths
+        std::fs::create_dir_all(temp.path().join("victim")).unwrap();
+        std::fs::write(temp.path().join("victim").join(MANIFEST_FILE), "name: victim\n").unwrap();
+        assert!(remove_pack("../victim", &packs).is_err());
+        assert!(remove_pack("..", &packs).is_err());
+        assert!(temp.path().join("victim").is_dir());
+
+        remove_pack("sales-demos", &packs).unwrap();
+        assert!(list_packs(&packs).is_empty());
+        assert!(remove_pack("sales-demos", &packs).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_checksum_verification_and_trust() {
+        let temp = tempfile::tempdir().unwrap();
+        let source = temp.path().join("src");
+        let packs = temp.path().join("packs");
+        pack_source(&source, "1.0.0");
+        let archive = build_pack(&source, Some(temp.path().join("p.rapspack"))).unwrap();
+        let archive = archive.to_string_lossy().into_owned();
+
+        // The published checksum next to the archive is picked up
+        let manifest = install_pack(&archive, &packs, &InstallOptions::default()).await.unwrap();
+        assert_eq!(manifest.trust, PackTrust::Verified);
+        let installed = installed_pack_for(&packs.join("sales-demos/workflows/oss/bucket.yaml"), &packs).unwrap();
+        assert_eq!(installed.label(), "sales-demos 1.0.0 (verified)");
+        // Only workflows of packs in the packs directory belong to a pack
+        assert!(installed_pack_for(&packs.join("sales-demos/workflows/oss/bucket.yaml"), &source).is_none());
+        assert!(installed_pack_for(&packs.join("sales-demos/other/workflows/x.yaml"), &packs).is_none());
+
+        // Adding a file the manifest doesn't list is detected
+        let added = installed.workflows_dir().join("oss/extra.yaml");
+        std::fs::write(&added, "id: extra\n").unwrap();
+        assert_eq!(installed.trust(), PackTrust::Modified);
+        std::fs::remove_file(&added).unwrap();
+        assert_eq!(installed.trust(), PackTrust::Verified);
+
+        // Editing an installed file is detected
+        std::fs::write(installed.workflows_dir().join("oss/bucket.yaml"), "id: changed\n").unwrap();
+        assert_eq!(installed.trust(), PackTrust::Modified);
+
+        // A wrong checksum is rejected, and so is a missing one unless allowed
+        let wrong = InstallOptions {
+            sha256: Some("0".repeat(64)),
+            ..Default::default()
+        };
+        let error = install_pack(&archive, &packs, &wrong).await.unwrap_err();
+        assert!(error.to_string().contains("Checksum mismatch"));
+        std::fs::remove_file(checksum_path(Path::new(&archive))).unwrap();
+        assert!(install_pack(&archive, &packs, &InstallOptions::default()).await.is_err());
+        let unverified = InstallOptions {
+            allow_unverified: true,
+            ..Default::default()
+        };
+        let manifest = install_pack(&archive, &packs, &unverified).await.unwrap();
+        assert_eq!(manifest.trust, PackTrust::Unverified);
+    }
+}
@@ -0,0 +1,285 @@
+// Cache of parsed workflow headers
+//
+// Discovery runs more than once per start (the built-in pack and the
+// configuration each trigger a rescan) and again on every refresh, and with a
+// pack of hundreds of workflows reading and parsing the YAML dominates
+// startup. Discovery only parses the header of each file (metadata,
+// dependencies, categories) and leaves the steps for when the workflow is
+// used; the cache keeps each header with the size, modification time and
+// SHA-256 of the file it came from. A file whose size and modification time
+// are unchanged isn't read at all, which is what keeps a refresh fast on
+// network filesystems; otherwise its content is hashed and only parsed again
+// if the digest differs. The timestamps are only trusted when the file was
+// last modified well before it was cached, so an edit right after caching
+// that keeps the size within the timestamp granularity still gets hashed. The
+// binary saves the cache to the configuration directory as JSON, which is
+// much faster to read back than the YAML, so the next start benefits too.
+// Entries written by another version of the demo are dropped, since the
+// format may have changed.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::discovery::WorkflowHeader;
+use crate::config::types::ConfigPaths;
+
+/// Version of the demo that wrote a cache file
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Files modified less than this before they were cached are hashed on
+/// the next lookup, as an edit within the timestamp granularity would
+/// leave the modification time as it was
+const TIMESTAMP_GRANULARITY: Duration = Duration::from_secs(2);
+
+/// A parsed header and the digest of the file it was parsed from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// SHA-256 of the file content, in hex
+    digest: String,
+    /// The file's size and modification time when cached
+    stamp: Option<FileStamp>,
+    header: WorkflowHeader,
+}
+
+/// Size and modification time of a file, and when they were taken
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct FileStamp {
+    len: u64,
+    modified: SystemTime,
+    cached_at: SystemTime,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok()?,
+            cached_at: SystemTime::now(),
+        })
+    }
+
+    /// Whether the file still has this size and modification time, and the
+    /// time was taken long enough after the last edit to tell edits apart
+    fn unchanged(&self, path: &Path) -> bool {
+        let settled = self.modified + TIMESTAMP_GRANULARITY <= self.cached_at;
+        settled
+            && fs::metadata(path).is_ok_and(|metadata| {
+                metadata.len() == self.len && metadata.modified().is_ok_and(|modified| modified == self.modified)
+            })
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: String,
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+/// Parsed workflow headers keyed by file path
+#[derive(Debug, Default)]
+pub struct ParseCache {
+    entries: HashMap<PathBuf, CacheEntry>,
+    /// Where the cache is saved (`None`: kept in memory only)
+    path: Option<PathBuf>,
+    /// Whether entries changed since loading or saving
+    dirty: bool,
+}
+
+/// Key of `path`, so the same file is found from any working directory
+fn key(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Digest of a file's content
+fn digest(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+impl ParseCache {
+    /// Load the cache saved in the configuration directory, starting empty
+    /// if there is none or it can't be read
+    pub fn load() -> Self {
+        match ConfigPaths::parse_cache_file() {
+            Ok(path) => Self::load_from(path),
+            Err(e) => {
+                tracing::debug!("No parse cache location available: {}", e);
+                Self::default()
+            },
+        }
+    }
+
+    /// Load the cache saved at `path`, which is also where it will be saved
+    pub fn load_from<P: Into<PathBuf>>(path: P) -> Self {
+        let path = path.into();
+        let entries = match fs::read(&path) {
+            Ok(content) => match serde_json::from_slice::<CacheFile>(&content) {
+                Ok(file) if file.version == VERSION => file.entries,
+                Ok(_) => HashMap::new(),
+                Err(e) => {
+                    tracing::warn!("Ignoring unreadable parse cache {:?}: {}", path, e);
+                    HashMap::new()
+                },
+            },
+            Err(_) => HashMap::new(),
+        };
+        Self {
+            entries,
+            path: Some(path),
+            dirty: false,
+        }
+    }
+
+    /// The header parsed from `path` if its size and modification time are
+    /// as when it was cached, without reading the file
+    pub fn get_unchanged(&self, path: &Path) -> Option<&WorkflowHeader> {
+        let entry = self.entries.get(&key(path))?;
+        entry.stamp.as_ref()?.unchanged(path).then_some(&entry.header)
+    }
+
+    /// The header parsed from `path` when it held `content`
+    pub fn get(&self, path: &Path, content: &str) -> Option<&WorkflowHeader> {
+        let entry = self.entries.get(&key(path))?;
+        (entry.digest == digest(content)).then_some(&entry.header)
+    }
+
+    /// Remember `header` as parsed from `path` holding `content`
+    pub fn insert(&mut self, path: &Path, content: &str, header: &WorkflowHeader) {
+        self.entries.insert(
+            key(path),
+            CacheEntry {
+                digest: digest(content),
+                stamp: FileStamp::of(path),
+                header: header.clone(),
+            },
+        );
+        self.dirty = true;
+    }
+
+    /// Number of cached files
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no file is cached
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Save the cache if it changed, dropping the entries of deleted files;
+    /// the file is replaced in one step, so a crash or a concurrent start
+    /// never sees it half written
+    pub fn save(&mut self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if !self.dirty {
+            return Ok(());
+        }
+        self.entries.retain(|file, _| file.exists());
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create directory: {:?}", parent))?;
+        }
+        let file = CacheFile {
+            version: VERSION.to_string(),
+            entries: std::mem::take(&mut self.entries),
+        };
+        let result = serde_json::to_vec(&file)
+            .context("Failed to serialize parse cache")
+            .and_then(|content| write_replacing(path, &content));
+        self.entries = file.entries;
+        self.dirty = false;
+        result
+    }
+}
+
+/// Write `content` to a temporary file next to `path` and rename it over `path`
+fn write_replacing(path: &Path, content: &[u8]) -> Result<()> {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let mut temp = tempfile::NamedTempFile::new_in(dir)
+        .with_context(|| format!("Failed to create temporary file in {:?}", dir))?;
+    temp.write_all(content)
+        .with_context(|| format!("Failed to write parse cache: {:?}", temp.path()))?;
+    temp.persist(path)
+        .map_err(|e| e.error)
+        .with_context(|| format!("Failed to write parse cache: {:?}", path))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::discovery::WorkflowDefinition;
+
+    #[test]
+    fn test_cache_survives_save_and_misses_after_edit() {
+        let dir = tempfile::tempdir().unwrap();
+        let workflow = dir.path().join("upload.yaml");
+        let yaml = std::fs::read_to_string("workflows/oss/bucket-lifecycle.yaml").unwrap();
+        std::fs::write(&workflow, &yaml).unwrap();
+        let header: WorkflowHeader = serde_yaml::from_str(&yaml).unwrap();
+
+        let cache_dir = dir.path().join("cache");
+        let cache_file = cache_dir.join("cache.json");
+        let mut cache = ParseCache::load_from(&cache_file);
+        assert!(cache.get(&workflow, &yaml).is_none());
+        cache.insert(&workflow, &yaml, &header);
+        cache.save().unwrap();
+        // Written through a temporary file that is renamed into place
+        assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 1);
+
+        // The JSON round trip gives back the same header
+        let mut cache = ParseCache::load_from(&cache_file);
+        assert_eq!(cache.get(&workflow, &yaml), Some(&header));
+
+        // An edit keeping the size misses, whatever the modification time
+        let edited = yaml.replacen("bucket", "BUCKET", 1);
+        assert_eq!(edited.len(), yaml.len());
+        assert!(cache.get(&workflow, &edited).is_none());
+
+        // Files just written aren't trusted by their timestamps...
+        assert!(cache.get_unchanged(&workflow).is_none());
+        // ...but those settled before they were cached are, until edited
+        let old = SystemTime::now() - Duration::from_secs(60);
+        fs::File::options().write(true).open(&workflow).unwrap().set_modified(old).unwrap();
+        cache.insert(&workflow, &yaml, &header);
+        assert_eq!(cache.get_unchanged(&workflow), Some(&header));
+        std::fs::write(&workflow, format!("{}\n", yaml)).unwrap();
+        assert!(cache.get_unchanged(&workflow).is_none());
+
+        // Deleted files are dropped on save
+        cache.insert(&workflow, &edited, &header);
+        std::fs::remove_file(&workflow).unwrap();
+        cache.save().unwrap();
+        assert!(ParseCache::load_from(&cache_file).is_empty());
+    }
+
+    #[test]
+    fn test_bundled_workflows_round_trip_through_json() {
+        for entry in walkdir::WalkDir::new("workflows").into_iter().filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.extension().map_or(true, |ext| ext != "yaml")
+                || crate::workflow::tour::is_tour_file(path)
+                || crate::workflow::vars::is_vars_file(path)
+            {
+                continue;
+            }
+            let yaml = std::fs::read_to_string(path).unwrap();
+            let definition: WorkflowDefinition = serde_yaml::from_str(&yaml).unwrap();
+            let json = serde_json::to_vec(&definition).unwrap();
+            let cached: WorkflowDefinition = serde_json::from_slice(&json).unwrap();
+            assert_eq!(cached, definition, "{}", path.display());
+
+            let header: WorkflowHeader = serde_yaml::from_str(&yaml).unwrap();
+            let json = serde_json::to_vec(&header).unwrap();
+            let cached: WorkflowHeader = serde_json::from_slice(&json).unwrap();
+            assert_eq!(cached, header, "{}", path.display());
+        }
+    }
+}
@@ -0,0 +1,603 @@
+// Placeholders for RAPS Demo Workflows
+//
+// Step commands, environment values and working directories may contain
+// `{name}` placeholders. Besides the built-ins (`{uuid}`, `{timestamp}`,
+// `{temp_dir}`) there are four namespaces:
+//
+//   params.<name>        workflow parameters (`params:` in the workflow YAML)
+//   steps.<id>.<key>     values captured from an earlier step's JSON output
+//   env.<NAME>           environment variables of the raps-demo process
+//   vars.<name>          shared variables of the workflows directory (`vars.yaml`)
+//
+// Captured outputs are also available under their bare key for older
+// workflows. In strict mode (the default) a placeholder that cannot be
+// resolved fails validation or the step instead of reaching RAPS verbatim.
+//
+// Values can be piped through filters, e.g. `{urn|base64}` for the URL-safe
+// base64 form APS expects, `{timestamp|date:%Y%m%d}` or
+// `{bucket|default:demo|lower}`.
+//
+// Generators produce per-run values for resource names: `{random:8}`
+// (lowercase letters and digits), `{seq}` / `{seq:3}` (the run's number in
+// this session), `{date}` / `{date:%Y%m}` and `{username}`. They are derived
+// from the run's `{seed}`, so a fixed seed reproduces the same names (and
+// `{uuid}`) for recorded demos, while each value stays the same within a run.
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+use super::discovery::WorkflowDefinition;
+use super::hooks::HookAction;
+use super::types::ExecutionStep;
+
+/// Placeholders the executor defines for every run, including generators
+pub const BUILTIN_PLACEHOLDERS: [&str; 8] = ["uuid", "timestamp", "temp_dir", "seed", "seq", "username", "random", "date"];
+
+/// Longest `{random:N}` and widest `{seq:N}`; larger arguments are undefined
+/// rather than a huge allocation (bucket names are at most 63 characters)
+const MAX_GENERATED_LEN: usize = 64;
+
+/// Extra placeholders available to hooks
+pub const HOOK_PLACEHOLDERS: [&str; 5] = ["workflow_id", "execution_id", "event", "step_id", "error"];
+
+/// Prefix of workflow parameters
+pub const PARAMS_PREFIX: &str = "params.";
+
+/// Prefix of captured step outputs
+pub const STEPS_PREFIX: &str = "steps.";
+
+/// Prefix of environment variables
+pub const ENV_PREFIX: &str = "env.";
+
+/// Prefix of shared variables
+pub const VARS_PREFIX: &str = "vars.";
+
+/// Filters that can follow a placeholder name
+pub const FILTERS: [&str; 10] = [
+    "base64", "date", "default", "lower", "short", "slug", "trim", "truncate", "upper", "urlencode",
+];
+
+/// A `{name|filter|filter:arg}` or `{generator:arg|filter}` expression
+struct Expression<'a> {
+    name: &'a str,
+    arg: Option<&'a str>,
+    filters: Vec<(&'a str, Option<&'a str>)>,
+}
+
+impl<'a> Expression<'a> {
+    fn parse(expression: &'a str) -> Option<Self> {
+        let mut parts = expression.split('|');
+        let head = parts.next()?;
+        let (name, arg) = match head.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (head, None),
+        };
+        if !is_placeholder_name(name) || (arg.is_some() && !matches!(name, "random" | "seq" | "date")) {
+            return None;
+        }
+        let filters = parts
+            .map(|filter| match filter.split_once(':') {
+                Some((filter, arg)) => (filter.trim(), Some(arg)),
+                None => (filter.trim(), None),
+            })
+            .collect();
+        Some(Self { name, arg, filters })
+    }
+
+    fn default_value(&self) -> Option<&'a str> {
+        self.filters
+            .iter()
+            .find(|(filter, _)| *filter == "default")
+            .map(|(_, arg)| arg.unwrap_or_default())
+    }
+}
+
+/// Contents of the `{placeholders}` in `text` (name plus any filters), in
+/// order of appearance
+pub fn placeholder_expressions(text: &str) -> Vec<&str> {
+    let mut expressions = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) if Expression::parse(&after[..end]).is_some() => {
+                expressions.push(&after[..end]);
+                rest = &after[end + 1..];
+            },
+            _ => rest = after,
+        }
+    }
+    expressions
+}
+
+/// Names of the `{placeholders}` in `text`, in order of appearance
+pub fn placeholder_names(text: &str) -> Vec<&str> {
+    placeholder_expressions(text)
+        .into_iter()
+        .filter_map(|expression| Expression::parse(expression).map(|e| e.name))
+        .collect()
+}
+
+/// Whether `name` looks like a placeholder (so JSON braces are left alone)
+fn is_placeholder_name(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// Look up a placeholder, running generators and falling back to the
+/// environment for `env.*`
+fn lookup(name: &str, arg: Option<&str>, placeholders: &HashMap<String, String>) -> Option<String> {
+    match name {
+        "random" => {
+            let seed = placeholders.get("seed")?.parse().ok()?;
+            let length = arg.map_or(Some(8), generated_len)?;
+            Some(random_string(seed, length))
+        },
+        "seq" => {
+            let seq: u64 = placeholders.get("seq")?.parse().ok()?;
+            let width = arg.map_or(Some(0), generated_len)?;
+            Some(format!("{:0width$}", seq, width = width))
+        },
+        "date" => apply_filter(placeholders.get("timestamp")?, "date", arg).ok(),
+        _ => placeholders
+            .get(name)
+            .cloned()
+            .or_else(|| name.strip_prefix(ENV_PREFIX).and_then(|var| std::env::var(var).ok())),
+    }
+}
+
+/// Length argument of a generator, `None` if unparsable or above [`MAX_GENERATED_LEN`]
+fn generated_len(arg: &str) -> Option<usize> {
+    arg.trim().parse().ok().filter(|&len| len <= MAX_GENERATED_LEN)
+}
+
+/// Next value of a splitmix64 generator
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Lowercase letters and digits derived from `seed` (valid in bucket names)
+pub fn random_string(seed: u64, length: usize) -> String {
+    const ALPHABET: &[u8; 36] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+    let mut state = seed ^ (length as u64).wrapping_mul(0xD6E8_FEB8_6659_FD93);
+    (0..length)
+        .map(|_| ALPHABET[(splitmix64(&mut state) % 36) as usize] as char)
+        .collect()
+}
+
+/// Version 4 UUID derived from `seed`
+pub fn seeded_uuid(seed: u64) -> uuid::Uuid {
+    let mut state = seed;
+    let high = splitmix64(&mut state) as u128;
+    let low = splitmix64(&mut state) as u128;
+    uuid::Builder::from_random_bytes(((high << 64) | low).to_be_bytes()).into_uuid()
+}
+
+/// OS user name, made safe for bucket names (`demo` when unknown)
+pub fn username() -> String {
+    let user = std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default();
+    match apply_filter(&user, "slug", None) {
+        Ok(slug) if !slug.is_empty() => slug,
+        _ => "demo".to_string(),
+    }
+}
+
+/// Replace placeholders in `template`, leaving unknown ones (and ones with
+/// failing filters) in place and recording them in `undefined`
+pub fn resolve(template: &str, placeholders: &HashMap<String, String>, undefined: &mut Vec<String>) -> String {
+    let mut resolved = template.to_string();
+    for expression in placeholder_expressions(template) {
+        let Some(parsed) = Expression::parse(expression) else {
+            continue;
+        };
+        let value = lookup(parsed.name, parsed.arg, placeholders)
+            .or_else(|| parsed.default_value().map(str::to_string))
+            .and_then(|value| {
+                parsed
+                    .filters
+                    .iter()
+                    .try_fold(value, |value, (filter, arg)| apply_filter(&value, filter, *arg).ok())
+            });
+        match value {
+            Some(value) => resolved = resolved.replace(&format!("{{{}}}", expression), &value),
+            None => {
+                if !undefined.iter().any(|u| u == expression) {
+                    undefined.push(expression.to_string());
+                }
+            },
+        }
+    }
+    resolved
+}
+
+/// Replace placeholders in every string inside a JSON value
+pub fn resolve_json(value: Value, placeholders: &HashMap<String, String>, undefined: &mut Vec<String>) -> Value {
+    match value {
+        Value::String(s) => Value::String(resolve(&s, placeholders, undefined)),
+        Value::Array(arr) => Value::Array(arr.into_iter().map(|v| resolve_json(v, placeholders, undefined)).collect()),
+        Value::Object(obj) => Value::Object(
+            obj.into_iter()
+                .map(|(k, v)| (k, resolve_json(v, placeholders, undefined)))
+                .collect(),
+        ),
+        _ => value,
+    }
+}
+
+/// Apply one filter to a placeholder value
+pub fn apply_filter(value: &str, filter: &str, arg: Option<&str>) -> Result<String, String> {
+    use crate::utils::encoding::{base64url_encode, url_encode};
+    Ok(match filter {
+        "base64" => base64url_encode(value.as_bytes()),
+        "date" => {
+            let time: DateTime<Utc> = match value.parse::<i64>() {
+                Ok(seconds) => Utc
+                    .timestamp_opt(seconds, 0)
+                    .single()
+                    .ok_or_else(|| format!("'{}' is not a valid timestamp", value))?,
+                Err(_) => DateTime::parse_from_rfc3339(value)
+                    .map_err(|_| format!("'{}' is not a timestamp or RFC 3339 date", value))?
+                    .with_timezone(&Utc),
+            };
+            let format = arg.unwrap_or("%Y%m%d");
+            let mut formatted = String::new();
+            std::fmt::Write::write_fmt(&mut formatted, format_args!("{}", time.format(format)))
+                .map_err(|_| format!("invalid date format '{}'", format))?;
+            formatted
+        },
+        "default" => value.to_string(),
+        "lower" => value.to_lowercase(),
+        "upper" => value.to_uppercase(),
+        "trim" => value.trim().to_string(),
+        "short" => value.chars().take(8).collect(),
+        "truncate" => {
+            let length: usize = arg
+                .and_then(|n| n.trim().parse().ok())
+                .ok_or_else(|| "truncate needs a length, e.g. truncate:12".to_string())?;
+            value.chars().take(length).collect()
+        },
+        "slug" => {
+            let slug: String = value
+                .to_lowercase()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                .collect();
+            slug.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-")
+        },
+        "urlencode" => url_encode(value),
+        other => return Err(format!("unknown filter '{}'", other)),
+    })
+}
+
+/// Placeholders of a workflow that cannot be resolved: unknown parameters,
+/// unset environment variables and outputs of steps that do not run earlier.
+/// Bare names that are not built-ins are returned separately, since they may
+/// be captured from a step's output at run time.
+pub fn check_workflow(workflow: &WorkflowDefinition) -> (Vec<String>, Vec<String>) {
+    let mut errors = Vec::new();
+    let mut unknown_bare = Vec::new();
+    let mut earlier_steps = HashSet::new();
+    let mut check_texts = |texts: &[String], location: &str, steps: &HashSet<&str>| {
+        for text in texts {
+            for expression in placeholder_expressions(text) {
+                let Some(parsed) = Expression::parse(expression) else {
+                    continue;
+                };
+                for (filter, _) in &parsed.filters {
+                    if !FILTERS.contains(filter) {
+                        errors.push(format!(
+                            "Unknown filter '{}' in placeholder '{{{}}}' in {}",
+                            filter, expression, location
+                        ));
+                    }
+                }
+                let name = parsed.name;
+                match check_name(name, workflow, steps, &[]) {
+                    _ if parsed.default_value().is_some() => {},
+                    NameCheck::Known => {},
+                    NameCheck::Undefined(reason) => errors.push(format!(
+                        "Undefined placeholder '{}' in {} ({})",
+                        name, location, reason
+                    )),
+                    NameCheck::Bare => unknown_bare.push(format!("'{}' in {}", name, location)),
+                }
+            }
+        }
+    };
+
+    // Cleanup runs after the steps, so a step's cleanup may use its own
+    // outputs and the workflow's cleanup those of every step
+    for step in &workflow.steps {
+        let location = format!("step {}", step.id);
+        check_texts(&step_texts(step), &location, &earlier_steps);
+        earlier_steps.insert(step.id.as_str());
+        let cleanup: Vec<String> = step.cleanup_commands.iter().flat_map(json_strings).collect();
+        check_texts(&cleanup, &format!("cleanup of {}", location), &earlier_steps);
+    }
+    let cleanup: Vec<String> = workflow.cleanup.iter().flat_map(json_strings).collect();
+    check_texts(&cleanup, "workflow cleanup", &earlier_steps);
+
+    // Hooks run after any step, so every step's outputs may be referenced
+    let all_steps: HashSet<&str> = workflow.steps.iter().map(|s| s.id.as_str()).collect();
+    let hooks = workflow.hooks.all().chain(workflow.steps.iter().flat_map(|s| s.hooks.all()));
+    for hook in hooks {
+        let texts = match hook {
+            HookAction::Command(command) => json_strings(command),
+            HookAction::Http(http) => {
+                let mut texts = vec![http.url.clone()];
+                texts.extend(http.body.iter().cloned());
+                texts
+            },
+        };
+        for text in &texts {
+            for name in placeholder_names(text) {
+                if let NameCheck::Undefined(reason) = check_name(name, workflow, &all_steps, &HOOK_PLACEHOLDERS) {
+                    errors.push(format!("Undefined placeholder '{}' in hook {} ({})", name, hook.describe(), reason));
+                }
+            }
+        }
+    }
+
+    (errors, unknown_bare)
+}
+
+/// Captured outputs that the steps from index `start` on take from earlier
+/// steps; a run starting there skips those steps, so it must be given these
+/// values as parameters. `{steps.<id>.<key>}` and `{<id>.<key>}` name the
+/// step producing them and are returned as `steps.<id>.<key>`. A bare output
+/// such as `{urn}` comes from some step before the first step using it, so
+/// it is returned when the run starts at or after that first use.
+pub fn skipped_step_outputs(workflow: &WorkflowDefinition, start: usize) -> Vec<String> {
+    if start == 0 {
+        return Vec::new();
+    }
+    let all_steps: HashSet<&str> = workflow.steps.iter().map(|s| s.id.as_str()).collect();
+    let skipped: HashSet<&str> = workflow.steps.iter().take(start).map(|s| s.id.as_str()).collect();
+    let mut used_before_start = HashSet::new();
+    let mut names = Vec::new();
+    for (index, step) in workflow.steps.iter().enumerate() {
+        let texts = step_texts(step).into_iter().chain(step.cleanup_commands.iter().flat_map(json_strings));
+        for text in texts {
+            for parsed in placeholder_expressions(&text).into_iter().filter_map(Expression::parse) {
+                if parsed.default_value().is_some() {
+                    continue;
+                }
+                let name = parsed.name;
+                match check_name(name, workflow, &all_steps, &[]) {
+                    NameCheck::Bare if index < start => {
+                        used_before_start.insert(name.to_string());
+                    },
+                    NameCheck::Bare if index == start || used_before_start.contains(name) => names.push(name.to_string()),
+                    NameCheck::Known if index >= start => {
+                        let output = name.strip_prefix(STEPS_PREFIX).unwrap_or(name);
+                        let is_namespaced =
+                            [PARAMS_PREFIX, VARS_PREFIX, ENV_PREFIX].iter().any(|prefix| name.starts_with(prefix));
+                        if let Some((step_id, key)) = output.split_once('.').filter(|_| !is_namespaced) {
+                            if skipped.contains(step_id) {
+                                names.push(format!("{}{}.{}", STEPS_PREFIX, step_id, key));
+                            }
+                        }
+                    },
+                    _ => {},
+                }
+            }
+        }
+    }
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Texts of a step that may contain placeholders, apart from its cleanup
+/// commands: its command, environment values and working directory
+fn step_texts(step: &ExecutionStep) -> Vec<String> {
+    let mut texts = json_strings(&step.command);
+    texts.extend(step.env.values().cloned());
+    if let Some(dir) = &step.working_dir {
+        texts.push(dir.to_string_lossy().into_owned());
+    }
+    texts
+}
+
+enum NameCheck {
+    Known,
+    Undefined(&'static str),
+    /// A bare name that may be a captured output
+    Bare,
+}
+
+fn check_name(name: &str, workflow: &WorkflowDefinition, steps: &HashSet<&str>, extra: &[&str]) -> NameCheck {
+    if BUILTIN_PLACEHOLDERS.contains(&name) || extra.contains(&name) {
+        NameCheck::Known
+    } else if let Some(param) = name.strip_prefix(PARAMS_PREFIX) {
+        if workflow.params.contains_key(param) {
+            NameCheck::Known
+        } else {
+            NameCheck::Undefined("no such parameter")
+        }
+    } else if let Some(var) = name.strip_prefix(VARS_PREFIX) {
+        if workflow.vars.contains_key(var) {
+            NameCheck::Known
+        } else {
+            NameCheck::Undefined("no such variable in vars.yaml")
+        }
+    } else if let Some(var) = name.strip_prefix(ENV_PREFIX) {
+        if std::env::var_os(var).is_some() {
+            NameCheck::Known
+        } else {
+            NameCheck::Undefined("environment variable not set")
+        }
+    } else if let Some(output) = name.strip_prefix(STEPS_PREFIX) {
+        match output.split_once('.') {
+            Some((step, _)) if steps.contains(step) => NameCheck::Known,
+            _ => NameCheck::Undefined("no earlier step with that ID"),
+        }
+    } else if name.split_once('.').is_some_and(|(step, _)| steps.contains(step)) {
+        // Older `<step_id>.<key>` form
+        NameCheck::Known
+    } else {
+        NameCheck::Bare
+    }
+}
+
+/// All string values of a serializable value
+fn json_strings<T: serde::Serialize>(value: &T) -> Vec<String> {
+    fn collect(value: &serde_json::Value, out: &mut Vec<String>) {
+        match value {
+            serde_json::Value::String(s) => out.push(s.clone()),
+            serde_json::Value::Array(items) => items.iter().for_each(|v| collect(v, out)),
+            serde_json::Value::Object(map) => map.values().for_each(|v| collect(v, out)),
+            _ => {},
+        }
+    }
+    let mut out = Vec::new();
+    if let Ok(json) = serde_json::to_value(value) {
+        collect(&json, &mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholder_names_and_resolve() {
+        assert_eq!(
+            placeholder_names(r#"{"json": 1} demo-{uuid}/{steps.upload.object_id}{ bad }"#),
+            vec!["uuid", "steps.upload.object_id"]
+        );
+
+        let placeholders = HashMap::from([("uuid".to_string(), "42".to_string())]);
+        let path = std::env::var("PATH").unwrap();
+        let mut undefined = Vec::new();
+        let resolved = resolve("b-{uuid}-{env.PATH}-{bucket_key}", &placeholders, &mut undefined);
+        assert_eq!(resolved, format!("b-42-{}-{{bucket_key}}", path));
+        assert_eq!(undefined, vec!["bucket_key"]);
+    }
+
+    #[test]
+    fn test_filters() {
+        let placeholders = HashMap::from([
+            ("urn".to_string(), "urn:adsk.objects:os.object:b/m.rvt".to_string()),
+            ("timestamp".to_string(), "1700000000".to_string()),
+            ("uuid".to_string(), "3f2504e0-4f89-11d3-9a0c-0305e82c3301".to_string()),
+            ("object_key".to_string(), " My Model.RVT ".to_string()),
+        ]);
+        let mut undefined = Vec::new();
+        let resolve = |template: &str, undefined: &mut Vec<String>| resolve(template, &placeholders, undefined);
+
+        assert_eq!(
+            resolve("{urn|base64}", &mut undefined),
+            "dXJuOmFkc2sub2JqZWN0czpvcy5vYmplY3Q6Yi9tLnJ2dA"
+        );
+        assert_eq!(resolve("{timestamp|date:%Y-%m-%d}", &mut undefined), "2023-11-14");
+        assert_eq!(resolve("{uuid|short}", &mut undefined), "3f2504e0");
+        assert_eq!(resolve("{object_key|trim|lower}", &mut undefined), "my model.rvt");
+        assert_eq!(resolve("{object_key|slug}", &mut undefined), "my-model-rvt");
+        assert_eq!(resolve("{bucket|default:demo|upper}", &mut undefined), "DEMO");
+        assert!(undefined.is_empty());
+
+        assert_eq!(resolve("{uuid|reverse}", &mut undefined), "{uuid|reverse}");
+        assert_eq!(undefined, vec!["uuid|reverse"]);
+    }
+
+    #[test]
+    fn test_generators() {
+        let placeholders = HashMap::from([
+            ("seed".to_string(), "7".to_string()),
+            ("seq".to_string(), "4".to_string()),
+            ("timestamp".to_string(), "1700000000".to_string()),
+        ]);
+        let mut undefined = Vec::new();
+        let name = resolve("demo-{random:8}-{seq:3}-{date}", &placeholders, &mut undefined);
+        assert!(undefined.is_empty());
+        let random = &name[5..13];
+        assert!(random.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()));
+        assert_eq!(&name[13..], "-004-20231114");
+
+        // Same seed, same names; the value is stable within a run
+        assert_eq!(resolve("{random:8}", &placeholders, &mut undefined), random);
+        assert_eq!(random_string(7, 8), random);
+        assert_ne!(random_string(8, 8), random);
+        assert_eq!(seeded_uuid(7), seeded_uuid(7));
+        assert_eq!(seeded_uuid(7).get_version_num(), 4);
+
+        assert_eq!(resolve("{date:%Y}", &placeholders, &mut undefined), "2023");
+        // Only generators take an argument
+        assert!(placeholder_names("{uuid:8}").is_empty());
+
+        // Oversized lengths are undefined, like unparsable ones
+        assert_eq!(resolve("{random:64}", &placeholders, &mut undefined).len(), 64);
+        assert!(undefined.is_empty());
+        assert_eq!(resolve("{random:4000000000}", &placeholders, &mut undefined), "{random:4000000000}");
+        assert_eq!(resolve("{seq:999999999}", &placeholders, &mut undefined), "{seq:999999999}");
+        assert_eq!(undefined, vec!["random:4000000000", "seq:999999999"]);
+    }
+
+    #[test]
+    fn test_check_workflow_namespaces() {
+        let yaml = r#"
+metadata:
+  id: ns
+  name: Namespaces
+  description: Placeholder namespaces
+  category: oss
+  prerequisites: []
+  estimated_duration: 60
+  required_assets: []
+params:
+  prefix: demo
+steps:
+  - id: create
+    name: Create
+    description: Create
+    command:
+      type: bucket
+      action: create
+      bucket_name: "{params.prefix}-{uuid}"
+    cleanup_commands:
+      - type: bucket
+        action: delete
+        bucket_name: "{steps.create.bucket_key}"
+  - id: upload
+    name: Upload
+    description: Upload
+    command:
+      type: object
+      action: upload
+      bucket_name: "{steps.create.bucket_key}"
+      object_key: "{params.missing}-{steps.later.key}-{object_id}"
+  - id: later
+    name: Later
+    description: Later
+    command:
+      type: auth
+      action: status
+cleanup:
+  - type: bucket
+    action: delete
+    bucket_name: "{steps.later.key}"
+"#;
+        let workflow: WorkflowDefinition = serde_yaml::from_str(yaml).unwrap();
+        // Cleanup runs after the steps: a step's cleanup may use its own
+        // outputs, the workflow's cleanup those of any step
+        let (errors, bare) = check_workflow(&workflow);
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].contains("'params.missing' in step upload"));
+        assert!(errors[1].contains("'steps.later.key' in step upload"));
+        assert_eq!(bare, vec!["'object_id' in step upload"]);
+
+        // Starting at `upload` skips `create`, whose bucket key it needs
+        assert!(skipped_step_outputs(&workflow, 0).is_empty());
+        // `object_id` is first used by `upload`, so some step before it
+        // produces it
+        assert_eq!(skipped_step_outputs(&workflow, 1), vec!["object_id", "steps.create.bucket_key"]);
+        assert!(skipped_step_outputs(&workflow, 2).is_empty());
+    }
+}
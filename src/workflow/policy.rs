@@ -0,0 +1,373 @@
+// Step policy for RAPS Demo Workflows
+//
+// Locked-down environments can restrict what workflows may do through a
+// `[policy]` table in demo.toml: forbid Custom commands (the free-form step
+// type that can run any RAPS subcommand) and HTTP hooks, allow or deny
+// specific RAPS subcommands, and require bucket and folder names to carry a
+// demo prefix. The policy is checked when workflows are validated and again
+// by the executor once placeholders have been resolved.
+//
+// The executor also applies the demo naming convention (see
+// `ResourceNaming`) to the buckets, objects and folders that commands create
+// or delete: names that do not look like demo resources are logged, rejected
+// or rewritten with a demo prefix, depending on `naming`. Each rewrite is
+// recorded for the run and applied to every later command, cleanup and hook
+// naming the same resource, whether the name is written out or comes from a
+// placeholder, so a run never creates one bucket and deletes another.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::hooks::HookAction;
+use super::types::{BucketAction, DataMgmtAction, ObjectAction, RapsCommand};
+use super::WorkflowDefinition;
+use crate::resource::types::ResourceNaming;
+
+/// What to do with resource names outside the demo naming convention
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NamingMode {
+    /// Do not check names
+    Off,
+    /// Log a warning and run the command unchanged
+    #[default]
+    Warn,
+    /// Fail the step
+    Reject,
+    /// Prefix the name, e.g. `shared` becomes `raps-demo-shared`
+    Rewrite,
+}
+
+/// Names the naming policy rewrote during a run, by kind (`Bucket`,
+/// `Object` or `Folder`) and original name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NameRewrites(BTreeMap<(&'static str, String), String>);
+
+impl NameRewrites {
+    /// Use the rewritten names for the resources `command` refers to
+    pub fn apply(&self, command: &mut RapsCommand) {
+        if self.0.is_empty() {
+            return;
+        }
+        for (kind, name) in named_resources(command) {
+            if let Some(rewritten) = self.0.get(&(kind, name.clone())) {
+                *name = rewritten.clone();
+            }
+        }
+    }
+
+    /// Original and rewritten names
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|((_, original), rewritten)| (original.as_str(), rewritten.as_str()))
+    }
+
+    /// Whether no name was rewritten
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Restrictions on workflow steps (`[policy]` in demo.toml)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StepPolicy {
+    /// Allow Custom commands with arbitrary RAPS arguments
+    pub allow_custom_commands: bool,
+    /// Allow HTTP callback hooks
+    pub allow_http_hooks: bool,
+    /// Prefix every bucket and created folder name must start with, e.g. `demo-`
+    pub resource_name_prefix: Option<String>,
+    /// RAPS subcommands workflows may run, e.g. `bucket` or `object upload`
+    /// (everything when empty)
+    pub allowed_commands: Vec<String>,
+    /// RAPS subcommands that are refused, e.g. `bucket delete`
+    pub denied_commands: Vec<String>,
+    /// Enforcement of the demo naming convention on created and deleted
+    /// buckets, objects and folders
+    pub naming: NamingMode,
+}
+
+impl Default for StepPolicy {
+    fn default() -> Self {
+        Self {
+            allow_custom_commands: true,
+            allow_http_hooks: true,
+            resource_name_prefix: None,
+            allowed_commands: Vec::new(),
+            denied_commands: Vec::new(),
+            naming: NamingMode::default(),
+        }
+    }
+}
+
+impl StepPolicy {
+    /// Check a command with resolved placeholders
+    pub fn check_command(&self, command: &RapsCommand) -> Result<()> {
+        self.check(command, false)
+    }
+
+    /// Apply the naming mode to the names `command` creates or deletes,
+    /// returning a note for each name that was outside the convention;
+    /// names rewritten earlier in the run (`rewrites`) are used first, and
+    /// new rewrites are added to them
+    pub fn enforce_naming(&self, command: &mut RapsCommand, rewrites: &mut NameRewrites) -> Result<Vec<String>> {
+        rewrites.apply(command);
+        let mut notes = Vec::new();
+        if self.naming == NamingMode::Off {
+            return Ok(notes);
+        }
+        for (kind, name) in mutated_names(command) {
+            if ResourceNaming::is_demo_name(name) {
+                continue;
+            }
+            match self.naming {
+                NamingMode::Off => {},
+                NamingMode::Warn => notes.push(format!("{} '{}' does not follow the demo naming convention", kind, name)),
+                NamingMode::Reject => {
+                    anyhow::bail!("{} '{}' does not follow the demo naming convention", kind, name)
+                },
+                NamingMode::Rewrite => {
+                    let rewritten = match kind {
+                        "Bucket" => ResourceNaming::demo_bucket_name_for(name),
+                        "Object" => ResourceNaming::demo_object_key_for(name),
+                        _ => ResourceNaming::demo_folder_name_for(name),
+                    };
+                    notes.push(format!("{} '{}' renamed to '{}'", kind, name, rewritten));
+                    rewrites.0.insert((kind, name.clone()), rewritten.clone());
+                    *name = rewritten;
+                },
+            }
+        }
+        Ok(notes)
+    }
+
+    /// Check a hook with resolved placeholders
+    pub fn check_hook(&self, hook: &HookAction) -> Result<()> {
+        match hook {
+            HookAction::Http(hook) if !self.allow_http_hooks => {
+                anyhow::bail!("HTTP hooks are not allowed by policy ({})", hook.url)
+            },
+            HookAction::Http(_) => Ok(()),
+            HookAction::Command(command) => self.check_command(command),
+        }
+    }
+
+    /// Policy violations of a workflow definition; names still containing
+    /// placeholders are left to the execution-time check
+    pub fn violations(&self, workflow: &WorkflowDefinition) -> Vec<String> {
+        let mut violations = Vec::new();
+        for step in &workflow.steps {
+            for command in std::iter::once(&step.command).chain(&step.cleanup_commands) {
+                if let Err(e) = self.check(command, true) {
+                    violations.push(format!("Step '{}': {}", step.id, e));
+                }
+            }
+            for hook in step.hooks.all() {
+                if let Err(e) = self.check_hook_template(hook) {
+                    violations.push(format!("Hook in step '{}': {}", step.id, e));
+                }
+            }
+        }
+        for hook in workflow.hooks.all() {
+            if let Err(e) = self.check_hook_template(hook) {
+                violations.push(format!("Workflow hook: {}", e));
+            }
+        }
+        violations
+    }
+
+    fn check_hook_template(&self, hook: &HookAction) -> Result<()> {
+        match hook {
+            HookAction::Command(command) => self.check(command, true),
+            HookAction::Http(_) => self.check_hook(hook),
+        }
+    }
+
+    fn check(&self, command: &RapsCommand, skip_templated: bool) -> Result<()> {
+        if matches!(command, RapsCommand::Custom { .. }) && !self.allow_custom_commands {
+            anyhow::bail!("Custom commands are not allowed by policy");
+        }
+
+        let path = command_path(command);
+        let name = path.join(" ");
+        if let Some(rule) = self.denied_commands.iter().find(|rule| rule_matches(rule, &path)) {
+            anyhow::bail!("'{}' is denied by policy (rule '{}')", name, rule);
+        }
+        if !self.allowed_commands.is_empty() && !self.allowed_commands.iter().any(|rule| rule_matches(rule, &path)) {
+            anyhow::bail!("'{}' is not an allowed command", name);
+        }
+
+        if let Some(prefix) = &self.resource_name_prefix {
+            for resource in resource_names(command) {
+                if skip_templated && resource.contains('{') {
+                    continue;
+                }
+                if !resource.starts_with(prefix.as_str()) {
+                    anyhow::bail!("Resource name '{}' does not start with the required prefix '{}'", resource, prefix);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Words naming a command, e.g. `["bucket", "create"]`
+fn command_path(command: &RapsCommand) -> Vec<String> {
+    fn word<T: Serialize>(action: &T) -> String {
+        serde_json::to_value(action)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default()
+    }
+    match command {
+        RapsCommand::Auth { action } => vec!["auth".to_string(), word(action)],
+        RapsCommand::Bucket { action, .. } => vec!["bucket".to_string(), word(action)],
+        RapsCommand::Object { action, .. } => vec!["object".to_string(), word(action)],
+        RapsCommand::Translate { action, .. } => vec!["translate".to_string(), word(action)],
+        RapsCommand::DataManagement { action, .. } => vec!["data-management".to_string(), word(action)],
+        RapsCommand::DesignAutomation { action, .. } => vec!["design-automation".to_string(), word(action)],
+        RapsCommand::Custom { command, args } => command
+            .split_whitespace()
+            .chain(args.iter().map(String::as_str).take_while(|arg| !arg.starts_with('-')))
+            .map(str::to_string)
+            .collect(),
+    }
+}
+
+/// Whether a rule such as `bucket` or `bucket delete` covers a command
+fn rule_matches(rule: &str, path: &[String]) -> bool {
+    let words: Vec<&str> = rule.split_whitespace().collect();
+    !words.is_empty() && words.len() <= path.len() && words.iter().zip(path).all(|(w, p)| w == p)
+}
+
+/// Bucket and folder names a command touches
+fn resource_names(command: &RapsCommand) -> Vec<&str> {
+    match command {
+        RapsCommand::Bucket { params, .. } => params.bucket_name.as_deref().into_iter().collect(),
+        RapsCommand::Object { params, .. } => vec![params.bucket_name.as_str()],
+        RapsCommand::DataManagement {
+            action: DataMgmtAction::FolderCreate,
+            params,
+        } => params.folder_name.as_deref().into_iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Names of the buckets, objects and folders a command creates or deletes
+fn mutated_names(command: &mut RapsCommand) -> Vec<(&'static str, &mut String)> {
+    match command {
+        RapsCommand::Bucket {
+            action: BucketAction::Create | BucketAction::Delete,
+            params,
+        } => params.bucket_name.as_mut().map(|name| ("Bucket", name)).into_iter().collect(),
+        RapsCommand::Object {
+            action: ObjectAction::Upload | ObjectAction::Delete,
+            params,
+        } => std::iter::once(("Bucket", &mut params.bucket_name))
+            .chain(params.object_key.as_mut().map(|key| ("Object", key)))
+            .collect(),
+        RapsCommand::DataManagement {
+            action: DataMgmtAction::FolderCreate,
+            params,
+        } => params.folder_name.as_mut().map(|name| ("Folder", name)).into_iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Names of the buckets, objects and folders a command refers to
+fn named_resources(command: &mut RapsCommand) -> Vec<(&'static str, &mut String)> {
+    match command {
+        RapsCommand::Bucket { params, .. } => params.bucket_name.as_mut().map(|name| ("Bucket", name)).into_iter().collect(),
+        RapsCommand::Object { params, .. } => std::iter::once(("Bucket", &mut params.bucket_name))
+            .chain(params.object_key.as_mut().map(|key| ("Object", key)))
+            .collect(),
+        RapsCommand::DataManagement { params, .. } => {
+            params.folder_name.as_mut().map(|name| ("Folder", name)).into_iter().collect()
+        },
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::types::{BucketAction, BucketParams};
+
+    fn bucket(action: BucketAction, name: &str) -> RapsCommand {
+        RapsCommand::Bucket {
+            action,
+            params: BucketParams {
+                bucket_name: Some(name.to_string()),
+                retention_policy: None,
+                region: None,
+                force: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_policy_rules() {
+        let custom = RapsCommand::Custom {
+            command: "webhook".to_string(),
+            args: vec!["delete".to_string(), "--id".to_string(), "x".to_string()],
+        };
+        let open = StepPolicy::default();
+        assert!(open.check_command(&custom).is_ok());
+        assert!(open.check_command(&bucket(BucketAction::Delete, "prod")).is_ok());
+
+        let locked = StepPolicy {
+            allow_custom_commands: false,
+            resource_name_prefix: Some("demo-".to_string()),
+            denied_commands: vec!["bucket delete".to_string()],
+            ..Default::default()
+        };
+        assert!(locked.check_command(&custom).is_err());
+        assert!(locked.check_command(&bucket(BucketAction::Delete, "demo-x")).is_err());
+        assert!(locked.check_command(&bucket(BucketAction::Create, "prod")).is_err());
+        assert!(locked.check_command(&bucket(BucketAction::Create, "demo-x")).is_ok());
+        // Placeholders are only checked once resolved
+        assert!(locked.check(&bucket(BucketAction::Create, "{bucket}"), true).is_ok());
+        assert!(locked.check_command(&bucket(BucketAction::Create, "{bucket}")).is_err());
+
+        let allow_list = StepPolicy {
+            allowed_commands: vec!["auth".to_string(), "webhook list".to_string()],
+            ..Default::default()
+        };
+        assert!(allow_list.check_command(&custom).is_err());
+        assert!(allow_list.check_command(&bucket(BucketAction::List, "demo")).is_err());
+        assert!(allow_list
+            .check_command(&RapsCommand::Auth {
+                action: crate::workflow::types::AuthAction::Status
+            })
+            .is_ok());
+    }
+
+    #[test]
+    fn test_naming_enforcement() {
+        let policy = |naming| StepPolicy {
+            naming,
+            ..Default::default()
+        };
+
+        let mut rewrites = NameRewrites::default();
+        let mut command = bucket(BucketAction::Delete, "production");
+        assert_eq!(policy(NamingMode::Warn).enforce_naming(&mut command, &mut rewrites).unwrap().len(), 1);
+        assert_eq!(command, bucket(BucketAction::Delete, "production"));
+        assert!(policy(NamingMode::Reject).enforce_naming(&mut command, &mut rewrites).is_err());
+        assert!(policy(NamingMode::Off).enforce_naming(&mut command, &mut rewrites).unwrap().is_empty());
+        assert!(rewrites.is_empty());
+
+        policy(NamingMode::Rewrite).enforce_naming(&mut command, &mut rewrites).unwrap();
+        assert_eq!(command, bucket(BucketAction::Delete, "raps-demo-production"));
+        assert_eq!(rewrites.iter().collect::<Vec<_>>(), vec![("production", "raps-demo-production")]);
+        // Demo names and read-only commands are left alone
+        assert!(policy(NamingMode::Reject).enforce_naming(&mut command, &mut rewrites).unwrap().is_empty());
+        let mut details = bucket(BucketAction::Details, "production");
+        assert!(policy(NamingMode::Reject).enforce_naming(&mut details, &mut NameRewrites::default()).is_ok());
+
+        // Once rewritten, later commands naming the bucket use the new name
+        policy(NamingMode::Rewrite).enforce_naming(&mut details, &mut rewrites).unwrap();
+        assert_eq!(details, bucket(BucketAction::Details, "raps-demo-production"));
+    }
+}
@@ -0,0 +1,268 @@
+// Rate limiting for RAPS CLI calls
+//
+// APS answers bursts of requests with 429 and a `Retry-After` delay. The
+// client shares one token bucket between every command it runs (all workflows
+// running at once go through the same `RapsClient`), and a 429 pauses the
+// whole bucket until the delay has passed. The executor retries the failed
+// step after the delay plus some jitter, so concurrent runs don't all wake up
+// at the same moment.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::client::CommandResult;
+
+/// Longest delay taken from APS or computed from the configured rate; a
+/// `Retry-After: 1e300` or a rate of 1e-300 waits this long instead of
+/// overflowing
+const MAX_DELAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// `secs` as a delay, negative and NaN values as none and huge ones capped
+/// at [`MAX_DELAY`]
+fn delay_from_secs(secs: f64) -> Duration {
+    Duration::try_from_secs_f64(secs.max(0.0)).map_or(MAX_DELAY, |delay| delay.min(MAX_DELAY))
+}
+
+/// Rate limiting settings (`[rate_limit]` in `demo.toml`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Sustained RAPS commands per second across all runs (0 disables the limiter)
+    pub requests_per_second: f64,
+    /// Commands allowed in a burst before the rate applies
+    pub burst: u32,
+    /// Times a rate-limited step is retried before it fails
+    pub max_retries: u32,
+    /// Wait in seconds when APS gives no `Retry-After`
+    pub default_wait_seconds: u64,
+    /// Longest wait in seconds, whatever `Retry-After` says
+    pub max_wait_seconds: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 5.0,
+            burst: 10,
+            max_retries: 3,
+            default_wait_seconds: 5,
+            max_wait_seconds: 120,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Wait before retry number `attempt` (from 1): `Retry-After` when given,
+    /// otherwise an exponential backoff, capped and with up to 20% jitter
+    pub fn retry_delay(&self, retry_after: Option<Duration>, attempt: u32) -> Duration {
+        let base = retry_after.unwrap_or_else(|| {
+            Duration::from_secs(self.default_wait_seconds.saturating_mul(1 << attempt.saturating_sub(1).min(6)))
+        });
+        let base = base.min(Duration::from_secs(self.max_wait_seconds));
+        base + jitter(base / 5)
+    }
+}
+
+/// Token bucket shared by every command of a client
+#[derive(Debug)]
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+    /// Set by a 429: nothing runs before this
+    paused_until: Option<Instant>,
+}
+
+impl RateLimiter {
+    /// Limiter allowing `config.burst` commands at once, then `requests_per_second`
+    pub fn new(config: &RateLimitConfig) -> Self {
+        let burst = f64::from(config.burst.max(1));
+        Self {
+            rate: config.requests_per_second,
+            burst,
+            state: Mutex::new(BucketState {
+                tokens: burst,
+                refilled_at: Instant::now(),
+                paused_until: None,
+            }),
+        }
+    }
+
+    /// Wait until a command may run
+    pub async fn acquire(&self) {
+        loop {
+            match self.try_acquire(Instant::now()) {
+                None => return,
+                Some(wait) => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+
+    /// Take a token at `now`, or return how long to wait for one
+    fn try_acquire(&self, now: Instant) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(until) = state.paused_until {
+            if until > now {
+                return Some(until - now);
+            }
+            state.paused_until = None;
+        }
+        // An infinite rate, like a zero one, means no limit
+        if self.rate <= 0.0 || !self.rate.is_finite() {
+            return None;
+        }
+
+        let elapsed = now.saturating_duration_since(state.refilled_at).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.rate).min(self.burst);
+        state.refilled_at = now;
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            None
+        } else {
+            Some(delay_from_secs((1.0 - state.tokens) / self.rate))
+        }
+    }
+
+    /// Hold every command back for `wait` (after APS answered 429)
+    pub fn pause_for(&self, wait: Duration) {
+        let now = Instant::now();
+        let until = now.checked_add(wait.min(MAX_DELAY)).unwrap_or(now);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.paused_until.map_or(true, |current| current < until) {
+            state.paused_until = Some(until);
+        }
+    }
+}
+
+/// Delay APS asked for in a rate-limited command's output
+///
+/// Looks for a `retry_after` / `Retry-After` field in JSON error output, then
+/// for a `Retry-After:` header line; values are seconds or an HTTP date.
+pub fn retry_after(result: &CommandResult) -> Option<Duration> {
+    for output in [&result.stderr, &result.stdout] {
+        for line in output.lines().map(str::trim) {
+            if let Ok(json) = serde_json::from_str::<Value>(line) {
+                if let Some(delay) = json_retry_after(&json) {
+                    return Some(delay);
+                }
+            }
+            let lower = line.to_lowercase();
+            if let Some(rest) = lower.strip_prefix("retry-after:") {
+                // Parse the original text, HTTP dates are case-sensitive
+                if let Some(delay) = parse_delay(line[line.len() - rest.len()..].trim()) {
+                    return Some(delay);
+                }
+            }
+        }
+    }
+    None
+}
+
+fn json_retry_after(value: &Value) -> Option<Duration> {
+    let error = value.get("error").filter(|e| e.is_object()).unwrap_or(value);
+    let headers = error.get("headers");
+    ["retry_after", "retryAfter", "Retry-After", "retry-after"]
+        .iter()
+        .find_map(|key| error.get(key).or_else(|| headers?.get(key)))
+        .and_then(|field| match field {
+            Value::Number(n) => n.as_f64().map(delay_from_secs),
+            Value::String(s) => parse_delay(s),
+            _ => None,
+        })
+}
+
+/// Seconds (`"30"`, `"30s"`) or an HTTP date, as a delay from now
+fn parse_delay(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.trim_end_matches('s').parse::<f64>() {
+        return Some(delay_from_secs(secs));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or_default())
+}
+
+/// Random delay up to `max`
+fn jitter(max: Duration) -> Duration {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().subsec_nanos();
+    let fraction = f64::from(nanos % 1000) / 1000.0;
+    max.mul_f64(fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_and_pause() {
+        let limiter = RateLimiter::new(&RateLimitConfig {
+            requests_per_second: 2.0,
+            burst: 2,
+            ..Default::default()
+        });
+        let now = Instant::now();
+        assert!(limiter.try_acquire(now).is_none());
+        assert!(limiter.try_acquire(now).is_none());
+        // The bucket is empty: the next token comes in half a second
+        let wait = limiter.try_acquire(now).unwrap();
+        assert!(wait > Duration::from_millis(400) && wait <= Duration::from_millis(500));
+        assert!(limiter.try_acquire(now + Duration::from_millis(500)).is_none());
+
+        limiter.pause_for(Duration::from_secs(30));
+        assert!(limiter.try_acquire(Instant::now()).unwrap() > Duration::from_secs(29));
+        limiter.pause_for(Duration::MAX);
+        assert!(limiter.try_acquire(Instant::now()).unwrap() <= MAX_DELAY);
+    }
+
+    #[test]
+    fn test_extreme_rates_dont_overflow() {
+        let limiter = RateLimiter::new(&RateLimitConfig {
+            requests_per_second: 1e-300,
+            burst: 1,
+            ..Default::default()
+        });
+        let now = Instant::now();
+        assert!(limiter.try_acquire(now).is_none());
+        assert_eq!(limiter.try_acquire(now), Some(MAX_DELAY));
+
+        for rate in [f64::INFINITY, f64::NAN] {
+            let limiter = RateLimiter::new(&RateLimitConfig {
+                requests_per_second: rate,
+                burst: 1,
+                ..Default::default()
+            });
+            assert!((0..3).all(|_| limiter.try_acquire(now).is_none()));
+        }
+    }
+
+    #[test]
+    fn test_retry_after_parsing() {
+        let failed = |stderr: &str| {
+            CommandResult::new(1, String::new(), stderr.to_string(), Duration::from_millis(1))
+        };
+        let json = r#"{"error": {"code": "TOO_MANY_REQUESTS", "status": 429, "retry_after": 12}}"#;
+        assert_eq!(retry_after(&failed(json)), Some(Duration::from_secs(12)));
+        let header = "HTTP 429 Too Many Requests\nRetry-After: 7\n";
+        assert_eq!(retry_after(&failed(header)), Some(Duration::from_secs(7)));
+        assert_eq!(retry_after(&failed("rate limited")), None);
+        // Absurd delays are capped instead of overflowing
+        assert_eq!(retry_after(&failed("Retry-After: 1e300")), Some(MAX_DELAY));
+        assert_eq!(retry_after(&failed(r#"{"retry_after": 1e300}"#)), Some(MAX_DELAY));
+        assert_eq!(retry_after(&failed("Retry-After: NaN")), Some(Duration::ZERO));
+
+        let config = RateLimitConfig::default();
+        let delay = config.retry_delay(Some(Duration::from_secs(10)), 1);
+        assert!(delay >= Duration::from_secs(10) && delay <= Duration::from_secs(12));
+        // Backoff doubles without a Retry-After and stays under the cap
+        assert!(config.retry_delay(None, 2) >= Duration::from_secs(10));
+        assert!(config.retry_delay(Some(Duration::from_secs(3600)), 1) <= Duration::from_secs(144));
+    }
+}
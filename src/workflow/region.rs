@@ -0,0 +1,214 @@
+// APS regions for RAPS Demo Workflows
+//
+// OSS buckets live in one region (US, EMEA or APAC) and Model Derivative
+// translates in a region too. A demo configured for a region (`region` in
+// `demo.toml`, or in the active profile's demo settings) creates its buckets
+// and translates there unless a step says otherwise. Validation rejects
+// workflows that create buckets in several regions and warns when a
+// translation runs in a different region than the bucket its model was
+// uploaded to.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use super::discovery::WorkflowDefinition;
+use super::placeholders;
+use super::types::{BucketAction, ObjectAction, RapsCommand, TranslateAction};
+
+/// Region of APS data and processing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Region {
+    Us,
+    Emea,
+    Apac,
+}
+
+impl Region {
+    /// Value of the RAPS `--region` option
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Us => "US",
+            Self::Emea => "EMEA",
+            Self::Apac => "APAC",
+        }
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Region {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "US" => Ok(Self::Us),
+            "EMEA" | "EU" => Ok(Self::Emea),
+            "APAC" | "AUS" => Ok(Self::Apac),
+            _ => Err(format!("unknown region '{}' (expected US, EMEA or APAC)", s)),
+        }
+    }
+}
+
+/// Give a bucket creation or translation without a region the demo's `region`
+pub fn apply_default(command: &mut RapsCommand, region: Region) {
+    let target = match command {
+        RapsCommand::Bucket {
+            action: BucketAction::Create,
+            params,
+        } => &mut params.region,
+        RapsCommand::Translate {
+            action: TranslateAction::Start,
+            params,
+        } => &mut params.region,
+        _ => return,
+    };
+    if target.is_none() {
+        *target = Some(region.as_str().to_string());
+    }
+}
+
+/// Region problems of a workflow, as `(errors, warnings)`: unknown region
+/// names, buckets created in different regions, and translations running in
+/// another region than the bucket of the latest upload before them (Model
+/// Derivative translates in the US unless told otherwise). Regions and
+/// bucket names given as placeholders count once shared variables and
+/// parameter defaults are filled in; ones that still depend on the run are
+/// not checked.
+pub fn check_workflow(workflow: &WorkflowDefinition, default: Option<Region>) -> (Vec<String>, Vec<String>) {
+    let mut known = HashMap::new();
+    for (name, value) in &workflow.vars {
+        known.insert(format!("{}{}", placeholders::VARS_PREFIX, name), value.clone());
+    }
+    for (name, value) in &workflow.params {
+        let value = placeholders::resolve(value, &known, &mut Vec::new());
+        known.insert(format!("{}{}", placeholders::PARAMS_PREFIX, name), value);
+    }
+
+    let (mut errors, mut warnings) = (Vec::new(), Vec::new());
+    let name_of = |text: &str| placeholders::resolve(text, &known, &mut Vec::new());
+    let region_of = |text: &Option<String>, step_id: &str, errors: &mut Vec<String>| -> Option<Region> {
+        let Some(text) = text else {
+            return default;
+        };
+        let mut undefined = Vec::new();
+        let resolved = placeholders::resolve(text, &known, &mut undefined);
+        if !undefined.is_empty() {
+            return None;
+        }
+        match resolved.parse() {
+            Ok(region) => Some(region),
+            Err(e) => {
+                errors.push(format!("Step '{}': {}", step_id, e));
+                None
+            },
+        }
+    };
+
+    let mut buckets: Vec<(String, Region)> = Vec::new();
+    let mut uploaded_to: Option<String> = None;
+    for step in &workflow.steps {
+        match &step.command {
+            RapsCommand::Bucket {
+                action: BucketAction::Create,
+                params,
+            } => {
+                if let Some(region) = region_of(&params.region, &step.id, &mut errors) {
+                    buckets.push((name_of(params.bucket_name.as_deref().unwrap_or_default()), region));
+                }
+            },
+            RapsCommand::Object {
+                action: ObjectAction::Upload,
+                params,
+            } => uploaded_to = Some(name_of(&params.bucket_name)),
+            RapsCommand::Translate {
+                action: TranslateAction::Start,
+                params,
+            } => {
+                let translation = region_of(&params.region, &step.id, &mut errors).unwrap_or(Region::Us);
+                let source = uploaded_to
+                    .as_ref()
+                    .and_then(|bucket| buckets.iter().find(|(name, _)| name == bucket));
+                if let Some((bucket, region)) = source.filter(|(_, region)| *region != translation) {
+                    warnings.push(format!(
+                        "Step '{}' translates in {} a model uploaded to bucket '{}' in {}",
+                        step.id, translation, bucket, region
+                    ));
+                }
+            },
+            _ => {},
+        }
+    }
+
+    if buckets.iter().any(|(_, region)| *region != buckets[0].1) {
+        let list: Vec<String> = buckets.iter().map(|(bucket, region)| format!("'{}' in {}", bucket, region)).collect();
+        errors.push(format!("Workflow creates buckets in different regions: {}", list.join(", ")));
+    }
+    (errors, warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKFLOW: &str = r#"
+metadata:
+  id: "regions"
+  name: "Regions"
+  description: "Upload and translate"
+  category: "ModelDerivative"
+  estimated_duration: 60
+params:
+  bucket: "demo-bucket"
+steps:
+  - id: "create"
+    name: "Create"
+    description: "Create the bucket"
+    command: {type: bucket, action: create, bucket_name: "{params.bucket}", retention_policy: transient, region: "{vars.region}"}
+  - id: "upload"
+    name: "Upload"
+    description: "Upload the model"
+    command: {type: object, action: upload, bucket_name: "{params.bucket}", object_key: model.rvt, file_path: model.rvt}
+  - id: "translate"
+    name: "Translate"
+    description: "Translate the model"
+    command: {type: translate, action: start, urn: "{steps.upload.urn}", format: svf2}
+"#;
+
+    #[test]
+    fn test_region_checks() {
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        workflow.vars.insert("region".to_string(), "emea".to_string());
+        let (errors, warnings) = check_workflow(&workflow, None);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(warnings, vec!["Step 'translate' translates in US a model uploaded to bucket 'demo-bucket' in EMEA"]);
+
+        // With the demo's region, translations follow the buckets
+        let mut translate = workflow.steps[2].command.clone();
+        apply_default(&mut translate, Region::Emea);
+        assert!(matches!(translate, RapsCommand::Translate { params, .. } if params.region.as_deref() == Some("EMEA")));
+
+        // A second bucket in the default region
+        let mut second = workflow.steps[0].clone();
+        second.id = "create-2".to_string();
+        apply_default(&mut second.command, Region::Apac);
+        if let RapsCommand::Bucket { params, .. } = &mut second.command {
+            assert_eq!(params.region.as_deref(), Some("{vars.region}"));
+            params.region = None;
+            params.bucket_name = Some("other".to_string());
+        }
+        workflow.steps.push(second);
+        let (errors, _) = check_workflow(&workflow, Some(Region::Us));
+        assert_eq!(errors, vec!["Workflow creates buckets in different regions: 'demo-bucket' in EMEA, 'other' in US"]);
+
+        workflow.vars.insert("region".to_string(), "mars".to_string());
+        let (errors, _) = check_workflow(&workflow, None);
+        assert_eq!(errors, vec!["Step 'create': unknown region 'mars' (expected US, EMEA or APAC)"]);
+    }
+}
@@ -0,0 +1,244 @@
+// Text summaries of a run for the CLI runner
+//
+// `--no-tui` runs print what the TUI shows around a run: before it, a short
+// preamble with the number of steps, the expected duration (from history
+// when there is any), the cost estimate and the resources the steps create;
+// after it, a table of the steps with their status, duration and exit code,
+// followed by the total time and the outcome.
+
+use super::discovery::WorkflowDefinition;
+use super::executor::ExecutionError;
+use super::history::{format_duration_secs, DurationHistory};
+use super::types::{
+    BucketAction, CostEstimate, DataMgmtAction, ExecutionResult, ExecutionStatus, ObjectAction, RapsCommand, StepResult,
+    TranslateAction,
+};
+
+/// Resources a command creates, as `kind name`
+fn created_resource(command: &RapsCommand) -> Option<String> {
+    match command {
+        RapsCommand::Bucket {
+            action: BucketAction::Create,
+            params,
+        } => Some(format!("bucket {}", params.bucket_name.as_deref().unwrap_or("?"))),
+        RapsCommand::Object {
+            action: ObjectAction::Upload,
+            params,
+        } => {
+            let name = params
+                .object_key
+                .clone()
+                .or_else(|| params.file_path.as_ref().map(|path| path.display().to_string()))
+                .unwrap_or_else(|| "?".to_string());
+            Some(format!("object {} in {}", name, params.bucket_name))
+        },
+        RapsCommand::Translate {
+            action: TranslateAction::Start,
+            params,
+        } => Some(format!("derivative ({})", params.format.as_deref().unwrap_or("svf2"))),
+        RapsCommand::DataManagement {
+            action: DataMgmtAction::FolderCreate,
+            params,
+        } => Some(format!("folder {}", params.folder_name.as_deref().unwrap_or("?"))),
+        _ => None,
+    }
+}
+
+/// The cost estimate as shown to users; a workflow without one isn't
+/// assumed to be free
+pub fn cost_label(estimate: Option<&CostEstimate>) -> String {
+    match estimate {
+        Some(cost) if cost.max_cost_usd <= 0.0 => format!("free ({})", cost.description),
+        Some(cost) => format!("up to ${:.2} ({})", cost.max_cost_usd, cost.description),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Lines printed before a run of `workflow`
+pub fn preamble(workflow: &WorkflowDefinition, history: Option<&DurationHistory>) -> Vec<String> {
+    let metadata = &workflow.metadata;
+    let mut duration = format!("~{}", format_duration_secs(metadata.estimated_duration.num_seconds() as f64));
+    if let Some(summary) = history.and_then(|h| h.summary(&metadata.id)) {
+        duration.push_str(&format!(", {}", summary));
+    }
+    let cost = cost_label(metadata.cost_estimate.as_ref());
+    let creates: Vec<String> = workflow.steps.iter().filter_map(|step| created_resource(&step.command)).collect();
+
+    vec![
+        format!("  Steps:    {}", workflow.steps.len()),
+        format!("  Duration: {}", duration),
+        format!("  Cost:     {}", cost),
+        format!(
+            "  Creates:  {}",
+            if creates.is_empty() { "nothing".to_string() } else { creates.join(", ") }
+        ),
+    ]
+}
+
+/// Short status label of a step
+fn status_label(status: &ExecutionStatus) -> &'static str {
+    match status {
+        ExecutionStatus::Completed => "ok",
+        ExecutionStatus::AlreadyExists => "exists",
+        ExecutionStatus::Skipped => "skipped",
+        ExecutionStatus::Failed => "failed",
+        ExecutionStatus::Cancelled => "cancelled",
+        ExecutionStatus::Pending | ExecutionStatus::Running | ExecutionStatus::Paused => "unfinished",
+    }
+}
+
+/// Table of finished steps with their status, duration and exit code
+pub fn step_table(steps: &[StepResult]) -> Vec<String> {
+    let width = steps.iter().map(|s| s.step_id.len()).max().unwrap_or(0).max(4);
+    let mut lines = vec![format!("  {:<width$}  {:<10}  {:>8}  Exit", "Step", "Status", "Time", width = width)];
+    for step in steps {
+        let time = step
+            .end_time
+            .map(|end| format_duration_secs((end - step.start_time).num_milliseconds().max(0) as f64 / 1000.0))
+            .unwrap_or_else(|| "-".to_string());
+        let exit = step.exit_code.map(|code| code.to_string()).unwrap_or_else(|| "-".to_string());
+        lines.push(format!(
+            "  {:<width$}  {:<10}  {:>8}  {}",
+            step.step_id,
+            status_label(&step.status),
+            time,
+            exit,
+            width = width
+        ));
+    }
+    lines
+}
+
+/// Row of the step a run failed in, which isn't reported as completed, from
+/// the run's error; `None` when no step failed (e.g. a timeout)
+pub fn failed_step(error: &ExecutionError) -> Option<StepResult> {
+    let step_id = error.failed_step.clone()?;
+    let end_time = chrono::Utc::now();
+    let command = error.command_result.as_ref();
+    let duration = command
+        .and_then(|result| chrono::Duration::from_std(result.duration).ok())
+        .unwrap_or_else(chrono::Duration::zero);
+    Some(StepResult {
+        step_id,
+        status: ExecutionStatus::Failed,
+        start_time: end_time - duration,
+        end_time: Some(end_time),
+        stdout: String::new(),
+        stderr: String::new(),
+        exit_code: command.map(|result| result.exit_code),
+        created_resources: Vec::new(),
+        full_output: Vec::new(),
+        json_output: None,
+    })
+}
+
+/// Table of the steps of a finished run, with the total time and outcome
+pub fn result_table(result: &ExecutionResult) -> Vec<String> {
+    let mut lines = step_table(&result.step_results);
+    lines.push(format!(
+        "  Total: {} in {}, {} of {} steps",
+        if result.success { "succeeded" } else { "failed" },
+        format_duration_secs(result.duration.num_milliseconds().max(0) as f64 / 1000.0),
+        result.steps_completed,
+        result.total_steps
+    ));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::client::CommandResult;
+    use chrono::{Duration, Utc};
+
+    const WORKFLOW: &str = r#"
+metadata:
+  id: "upload"
+  name: "Upload"
+  description: "Upload a model"
+  category: "ObjectStorage"
+  estimated_duration: 90
+  cost_estimate: {description: "Storage", max_cost_usd: 0.05}
+steps:
+  - id: "create-bucket"
+    name: "Create"
+    description: "Create the bucket"
+    command: {type: bucket, action: create, bucket_name: "demo"}
+  - id: "upload"
+    name: "Upload"
+    description: "Upload the model"
+    command: {type: object, action: upload, bucket_name: "demo", file_path: "model.rvt"}
+"#;
+
+    #[test]
+    fn test_preamble_and_result_table() {
+        let workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        assert_eq!(
+            preamble(&workflow, None),
+            vec![
+                "  Steps:    2",
+                "  Duration: ~1m 30s",
+                "  Cost:     up to $0.05 (Storage)",
+                "  Creates:  bucket demo, object model.rvt in demo",
+            ]
+        );
+
+        let start = Utc::now();
+        let step = |id: &str, status, secs, exit_code| StepResult {
+            step_id: id.to_string(),
+            status,
+            start_time: start,
+            end_time: Some(start + Duration::seconds(secs)),
+            stdout: String::new(),
+            stderr: String::new(),
+            exit_code,
+            created_resources: Vec::new(),
+            full_output: Vec::new(),
+            json_output: None,
+        };
+        let result = ExecutionResult {
+            workflow_id: "upload".to_string(),
+            success: false,
+            duration: Duration::seconds(75),
+            steps_completed: 2,
+            total_steps: 2,
+            resources_created: Vec::new(),
+            cleanup_performed: false,
+            cleanup_results: Vec::new(),
+            step_results: vec![
+                step("create-bucket", ExecutionStatus::AlreadyExists, 2, Some(0)),
+                step("upload", ExecutionStatus::Failed, 73, Some(3)),
+            ],
+            outputs: Default::default(),
+        };
+        assert_eq!(
+            result_table(&result),
+            vec![
+                "  Step           Status          Time  Exit",
+                "  create-bucket  exists            2s  0",
+                "  upload         failed        1m 13s  3",
+                "  Total: failed in 1m 15s, 2 of 2 steps",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_preamble_cost_without_estimate() {
+        let mut workflow: WorkflowDefinition = serde_yaml::from_str(WORKFLOW).unwrap();
+        workflow.metadata.cost_estimate.as_mut().unwrap().max_cost_usd = 0.0;
+        assert_eq!(preamble(&workflow, None)[2], "  Cost:     free (Storage)");
+        workflow.metadata.cost_estimate = None;
+        assert_eq!(preamble(&workflow, None)[2], "  Cost:     unknown");
+    }
+
+    #[test]
+    fn test_failed_step_row() {
+        let command = CommandResult::new(3, String::new(), "boom".to_string(), std::time::Duration::from_secs(4));
+        let error = ExecutionError::from_command_failure("upload".to_string(), command, Vec::new());
+        assert_eq!(
+            step_table(&[failed_step(&error).unwrap()]),
+            vec!["  Step    Status          Time  Exit", "  upload  failed            4s  3"]
+        );
+        assert!(failed_step(&ExecutionError::new("Workflow timed out".to_string())).is_none());
+    }
+}
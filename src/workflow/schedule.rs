@@ -0,0 +1,321 @@
+// Scheduled workflow runs
+//
+// Teams use the demos as nightly regression tests of the RAPS CLI. Schedules
+// pair a workflow with a cron expression (`0 6 * * *`) and live in
+// `schedule.toml` in the configuration directory. `raps-demo schedule run`
+// stays in the foreground, runs each workflow non-interactively when its
+// expression next matches (local time) and records the outcome of the last run
+// next to the schedule; durations go to the regular history.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDateTime, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::types::WorkflowId;
+use crate::config::types::ConfigPaths;
+
+/// Five-field cron expression: minute, hour, day of month, month, day of week
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days: Vec<u32>,
+    months: Vec<u32>,
+    weekdays: Vec<u32>,
+    /// Day of month was restricted (not `*`)
+    days_restricted: bool,
+    /// Day of week was restricted (not `*`)
+    weekdays_restricted: bool,
+}
+
+impl CronExpr {
+    /// Parse an expression such as `0 6 * * *`, `*/15 8-18 * * 1-5` or `30 2 1,15 * *`
+    pub fn parse(expr: &str) -> Result<Self> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            bail!(
+                "Invalid cron expression '{}': expected 5 fields (minute hour day month weekday)",
+                expr
+            );
+        }
+        let field = |index: usize, name: &str, min: u32, max: u32| {
+            parse_field(fields[index], min, max)
+                .with_context(|| format!("Invalid {} field '{}' in cron expression '{}'", name, fields[index], expr))
+        };
+        // Sunday is both 0 and 7
+        let mut weekdays: Vec<u32> = field(4, "weekday", 0, 7)?.into_iter().map(|d| d % 7).collect();
+        weekdays.sort_unstable();
+        weekdays.dedup();
+        Ok(Self {
+            minutes: field(0, "minute", 0, 59)?,
+            hours: field(1, "hour", 0, 23)?,
+            days: field(2, "day", 1, 31)?,
+            months: field(3, "month", 1, 12)?,
+            weekdays,
+            days_restricted: fields[2] != "*",
+            weekdays_restricted: fields[4] != "*",
+        })
+    }
+
+    /// Whether the expression matches the minute of `time`
+    pub fn matches(&self, time: &NaiveDateTime) -> bool {
+        let day = self.days.contains(&time.day());
+        let weekday = self.weekdays.contains(&time.weekday().num_days_from_sunday());
+        // As in cron, a restricted day of month and day of week match either
+        let day_matches = match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        };
+        day_matches
+            && self.minutes.contains(&time.minute())
+            && self.hours.contains(&time.hour())
+            && self.months.contains(&time.month())
+    }
+
+    /// First matching minute strictly after `after`, within the next four years
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut time = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = after + Duration::days(4 * 366);
+        while time <= limit {
+            if !self.months.contains(&time.month()) {
+                // Jump to the first minute of the next month
+                let (year, month) = if time.month() == 12 { (time.year() + 1, 1) } else { (time.year(), time.month() + 1) };
+                time = chrono::NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.hours.contains(&time.hour()) {
+                time = time.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if self.matches(&time) {
+                return Some(time);
+            }
+            time += Duration::minutes(1);
+        }
+        None
+    }
+
+    /// Next matching local time after `after`
+    pub fn next_local(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let mut naive = after.naive_local();
+        // Times skipped by a daylight saving change don't exist; try the next match
+        loop {
+            naive = self.next_after(naive)?;
+            if let Some(time) = Local.from_local_datetime(&naive).earliest() {
+                return Some(time);
+            }
+        }
+    }
+}
+
+/// Values allowed by one cron field
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Vec<u32>> {
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().context("step is not a number")?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            bail!("step must be at least 1");
+        }
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (start.parse::<u32>()?, end.parse::<u32>()?)
+        } else {
+            let value = range.parse::<u32>()?;
+            // `5/10` means from 5 to the maximum
+            (value, if part.contains('/') { max } else { value })
+        };
+        if start < min || end > max || start > end {
+            bail!("values must be between {} and {}", min, max);
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+    values.sort_unstable();
+    values.dedup();
+    Ok(values)
+}
+
+/// A workflow run on a cron expression
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleEntry {
+    /// Name of the schedule (defaults to the workflow ID)
+    pub name: String,
+    /// Workflow to run
+    pub workflow_id: WorkflowId,
+    /// When to run it, e.g. `0 6 * * *`
+    pub cron: String,
+    /// Profile to run instead of every step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// When the last scheduled run finished
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<DateTime<Utc>>,
+    /// Whether it succeeded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<bool>,
+    /// Summary of its outcome
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_message: Option<String>,
+}
+
+impl ScheduleEntry {
+    /// Parsed cron expression
+    pub fn expr(&self) -> Result<CronExpr> {
+        CronExpr::parse(&self.cron)
+    }
+
+    /// Record the outcome of a run that finished now
+    pub fn record(&mut self, success: bool, message: String) {
+        self.last_run = Some(Utc::now());
+        self.last_success = Some(success);
+        self.last_message = Some(message);
+    }
+}
+
+/// Scheduled runs, persisted to `schedule.toml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Schedule {
+    pub entries: Vec<ScheduleEntry>,
+    /// File the schedule is persisted to, if any
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Schedule {
+    /// Load the schedule from the default file (empty if there is none)
+    pub fn load() -> Result<Self> {
+        Self::load_from(ConfigPaths::schedule_file()?)
+    }
+
+    /// Load the schedule from `path` (empty if it doesn't exist)
+    pub fn load_from<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut schedule = if path.exists() {
+            let content = fs::read_to_string(path).with_context(|| format!("Failed to read schedule file: {:?}", path))?;
+            toml::from_str::<Schedule>(&content).with_context(|| format!("Failed to parse schedule file: {:?}", path))?
+        } else {
+            Self::default()
+        };
+        schedule.path = Some(path.to_path_buf());
+        Ok(schedule)
+    }
+
+    /// Save the schedule to the file it was loaded from
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create directory: {:?}", parent))?;
+        }
+        let content = toml::to_string_pretty(self).context("Failed to serialize schedule")?;
+        fs::write(path, content).with_context(|| format!("Failed to write schedule file: {:?}", path))
+    }
+
+    /// Add an entry, rejecting invalid expressions and duplicate names
+    pub fn add(&mut self, entry: ScheduleEntry) -> Result<()> {
+        entry.expr()?;
+        if self.get(&entry.name).is_some() {
+            bail!("A schedule named '{}' already exists", entry.name);
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Remove the entry called `name`
+    pub fn remove(&mut self, name: &str) -> Result<ScheduleEntry> {
+        let index = self
+            .entries
+            .iter()
+            .position(|e| e.name == name)
+            .with_context(|| format!("No schedule named '{}'", name))?;
+        Ok(self.entries.remove(index))
+    }
+
+    /// Entry called `name`
+    pub fn get(&self, name: &str) -> Option<&ScheduleEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    /// Mutable entry called `name`
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut ScheduleEntry> {
+        self.entries.iter_mut().find(|e| e.name == name)
+    }
+
+    /// When each entry next runs after `after`, soonest first
+    pub fn upcoming(&self, after: DateTime<Local>) -> Vec<(DateTime<Local>, &ScheduleEntry)> {
+        let mut upcoming: Vec<_> = self
+            .entries
+            .iter()
+            .filter_map(|entry| Some((entry.expr().ok()?.next_local(after)?, entry)))
+            .collect();
+        upcoming.sort_by_key(|(time, _)| *time);
+        upcoming
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        // 2024-01-01 was a Monday
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap().and_hms_opt(hour, minute, 30).unwrap()
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let daily = CronExpr::parse("0 6 * * *").unwrap();
+        assert_eq!(daily.next_after(at(1, 5, 59)), Some(at(1, 6, 0).with_second(0).unwrap()));
+        assert_eq!(daily.next_after(at(1, 6, 0)), Some(at(2, 6, 0).with_second(0).unwrap()));
+
+        // Weekdays only: Friday evening skips to Monday
+        let weekdays = CronExpr::parse("*/15 8-18 * * 1-5").unwrap();
+        assert_eq!(weekdays.next_after(at(5, 18, 50)), Some(at(8, 8, 0).with_second(0).unwrap()));
+        assert_eq!(weekdays.next_after(at(8, 9, 1)), Some(at(8, 9, 15).with_second(0).unwrap()));
+
+        // Day of month and day of week together match either (the 15th or a Sunday)
+        let either = CronExpr::parse("0 0 15 * 7").unwrap();
+        assert_eq!(either.next_after(at(1, 12, 0)), Some(at(7, 0, 0).with_second(0).unwrap()));
+        assert_eq!(either.next_after(at(14, 12, 0)), Some(at(15, 0, 0).with_second(0).unwrap()));
+
+        assert!(CronExpr::parse("0 6 * *").is_err());
+        assert!(CronExpr::parse("61 * * * *").is_err());
+        assert!(CronExpr::parse("*/0 * * * *").is_err());
+    }
+
+    #[test]
+    fn test_schedule_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schedule.toml");
+        let mut schedule = Schedule::load_from(&path).unwrap();
+        let entry = ScheduleEntry {
+            name: "nightly".to_string(),
+            workflow_id: "bucket-lifecycle".to_string(),
+            cron: "0 6 * * *".to_string(),
+            profile: None,
+            last_run: None,
+            last_success: None,
+            last_message: None,
+        };
+        schedule.add(entry.clone()).unwrap();
+        assert!(schedule.add(entry.clone()).is_err());
+        assert!(schedule
+            .add(ScheduleEntry { name: "bad".to_string(), cron: "soon".to_string(), ..entry })
+            .is_err());
+
+        schedule.get_mut("nightly").unwrap().record(true, "3 steps".to_string());
+        schedule.save().unwrap();
+        let loaded = Schedule::load_from(&path).unwrap();
+        assert_eq!(loaded.entries.len(), 1);
+        assert_eq!(loaded.entries[0].last_success, Some(true));
+        assert_eq!(loaded.upcoming(Local::now()).len(), 1);
+    }
+}
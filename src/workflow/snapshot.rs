@@ -0,0 +1,313 @@
+// Snapshot tests for workflows
+//
+// `raps-demo test` runs workflows against the mock backend and compares what
+// they did with a committed snapshot: the RAPS command lines in order, the
+// outputs captured from them and whether the run succeeded. Each workflow has
+// one `<id>.snap.yaml` holding the test's inputs (parameters and canned
+// responses) and the recorded snapshot, so workflow packs can run in CI
+// without credentials or APS. Run-specific values (`{temp_dir}`,
+// `{timestamp}`) are written back as placeholders and the seed is fixed, so
+// snapshots only change when the workflow does. A case can also replay fixture
+// files recorded from real runs and inject failures into steps, to pin down
+// how a workflow handles them.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::chaos::ChaosOptions;
+use super::mock::{self, FailureInjection, MockBackend, MockResponse};
+use super::watch::diff_lines;
+use super::{ExecutionOptions, ExecutionUpdate, VariableSource, WorkflowEngine};
+
+/// Seed of every snapshot run, so generated names are stable
+pub const SNAPSHOT_SEED: u64 = 0;
+
+/// Built-ins whose values differ between runs even with a fixed seed
+const VOLATILE_PLACEHOLDERS: [&str; 2] = ["temp_dir", "timestamp"];
+
+/// What a workflow did in a mock run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Whether the run succeeded
+    pub success: bool,
+    /// RAPS command lines, in the order they ran
+    pub commands: Vec<String>,
+    /// Captured outputs as `steps.<id>.<key>`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub outputs: BTreeMap<String, String>,
+}
+
+impl Snapshot {
+    /// Lines compared between snapshots
+    fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("success: {}", self.success)];
+        lines.extend(self.commands.iter().map(|c| format!("command: {}", c)));
+        lines.extend(self.outputs.iter().map(|(k, v)| format!("output: {} = {}", k, v)));
+        lines
+    }
+}
+
+/// Contents of a `<id>.snap.yaml` file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotCase {
+    /// Workflow parameters for the run
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+    /// Canned RAPS output
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub responses: Vec<MockResponse>,
+    /// Fixture files (relative to the snapshot directory) answering the
+    /// commands `responses` don't
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fixtures: Vec<PathBuf>,
+    /// Steps whose commands fail, as `step-id` or `step-id=kind`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub inject_failures: Vec<String>,
+    /// Expected outcome, written by `--update`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<Snapshot>,
+}
+
+impl SnapshotCase {
+    /// Snapshot file of `workflow_id` in `dir`
+    pub fn path(dir: &Path, workflow_id: &str) -> PathBuf {
+        dir.join(format!("{}.snap.yaml", workflow_id))
+    }
+
+    /// Load a case, or an empty one if the file doesn't exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read snapshot: {}", path.display()))?;
+        serde_yaml::from_str(&content).with_context(|| format!("Failed to parse snapshot: {}", path.display()))
+    }
+
+    /// Write the case to `path`
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        let content = serde_yaml::to_string(self).context("Failed to serialize snapshot")?;
+        fs::write(path, content).with_context(|| format!("Failed to write snapshot: {}", path.display()))
+    }
+}
+
+/// Result of testing one workflow
+#[derive(Debug, Clone, PartialEq)]
+pub enum TestOutcome {
+    /// Matched the snapshot
+    Passed,
+    /// Differed from the snapshot (`-` expected, `+` actual)
+    Failed(Vec<String>),
+    /// No snapshot yet, or `--update`: the run was recorded
+    Recorded,
+}
+
+/// Workflow IDs that have a snapshot file in `dir`
+pub fn snapshot_ids(dir: &Path) -> Vec<String> {
+    let mut ids: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            name.strip_suffix(".snap.yaml").map(str::to_string)
+        })
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// Run `workflow_id` against `mock` (the engine's mock backend) and compare
+/// it with its snapshot in `dir`, recording the run if there is none or
+/// `update` is set
+pub async fn test_workflow(
+    engine: &WorkflowEngine,
+    mock: &MockBackend,
+    dir: &Path,
+    workflow_id: &str,
+    update: bool,
+) -> Result<TestOutcome> {
+    let path = SnapshotCase::path(dir, workflow_id);
+    let mut case = SnapshotCase::load(&path)?;
+    let actual = run_case(engine, mock, dir, workflow_id, &case).await?;
+
+    match &case.snapshot {
+        Some(expected) if !update => {
+            let diff = diff_lines(&expected.lines(), &actual.lines());
+            Ok(if diff.is_empty() { TestOutcome::Passed } else { TestOutcome::Failed(diff) })
+        },
+        _ => {
+            case.snapshot = Some(actual);
+            case.save(&path)?;
+            Ok(TestOutcome::Recorded)
+        },
+    }
+}
+
+/// Run one case (with fixtures relative to `dir`) to the end and collect its
+/// snapshot
+pub async fn run_case(
+    engine: &WorkflowEngine,
+    mock: &MockBackend,
+    dir: &Path,
+    workflow_id: &str,
+    case: &SnapshotCase,
+) -> Result<Snapshot> {
+    let mut responses = case.responses.clone();
+    for fixtures in &case.fixtures {
+        responses.extend(mock::load_fixtures(&dir.join(fixtures))?);
+    }
+    let failures = case
+        .inject_failures
+        .iter()
+        .map(|spec| spec.parse())
+        .collect::<Result<Vec<FailureInjection>>>()?;
+    mock.set_responses(responses);
+    mock.set_failures(failures);
+    mock.take_calls();
+
+    let mut receiver = engine.subscribe();
+    let options = ExecutionOptions {
+        interactive: false,
+        auto_cleanup: true,
+        seed: Some(SNAPSHOT_SEED),
+        chaos: ChaosOptions::default(),
+        params: case.params.clone().into_iter().collect(),
+        ..engine.execution_options()
+    };
+    let handle = engine.execute(&workflow_id.to_string(), options).await?;
+
+    let mut volatile = HashMap::new();
+    let mut outputs = BTreeMap::new();
+    let success = loop {
+        let Some(update) = receiver.recv().await else {
+            anyhow::bail!("Execution updates stopped before {} finished", workflow_id);
+        };
+        if update.handle().id != handle.id {
+            continue;
+        }
+        match update {
+            ExecutionUpdate::VariablesUpdated { source, values, .. } => match source {
+                VariableSource::BuiltIn => volatile.extend(
+                    values.into_iter().filter(|(name, _)| VOLATILE_PLACEHOLDERS.contains(&name.as_str())),
+                ),
+                VariableSource::StepOutput(step_id) => {
+                    outputs.extend(values.into_iter().map(|(key, value)| (format!("steps.{}.{}", step_id, key), value)))
+                },
+                VariableSource::Param => {},
+            },
+            ExecutionUpdate::Completed { result, .. } => break result.success,
+            ExecutionUpdate::Failed { .. } | ExecutionUpdate::Cancelled { .. } => break false,
+            _ => {},
+        }
+    };
+
+    let normalize = |text: String| {
+        volatile.iter().fold(text, |text, (name, value)| {
+            if value.is_empty() {
+                text
+            } else {
+                text.replace(value.as_str(), &format!("{{{}}}", name))
+            }
+        })
+    };
+    Ok(Snapshot {
+        success,
+        commands: mock.take_calls().into_iter().map(normalize).collect(),
+        outputs: outputs.into_iter().map(|(k, v)| (k, normalize(v))).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::client::RapsClientConfig;
+    use crate::workflow::WorkflowExecutor;
+    use std::sync::Arc;
+
+    const WORKFLOW: &str = r#"
+metadata:
+  id: "snapshot-demo"
+  name: "Snapshot Demo"
+  description: "Creates a bucket and lists its objects"
+  category: "ObjectStorage"
+  estimated_duration: 60
+steps:
+  - id: "create"
+    name: "Create Bucket"
+    description: "Create it"
+    command: { type: "bucket", action: "create", bucket_name: "demo-{random:4}", retention_policy: "transient" }
+  - id: "list"
+    name: "List Objects"
+    description: "List them"
+    command: { type: "object", action: "list", bucket_name: "{steps.create.bucketKey}" }
+cleanup: []
+"#;
+
+    #[tokio::test]
+    async fn test_record_then_compare() {
+        let dir = tempfile::tempdir().unwrap();
+        let workflows = dir.path().join("workflows");
+        fs::create_dir_all(&workflows).unwrap();
+        fs::write(workflows.join("snapshot-demo.yaml"), WORKFLOW).unwrap();
+
+        let mock = Arc::new(MockBackend::default());
+        let executor = WorkflowExecutor::with_config(RapsClientConfig {
+            mock: Some(Arc::clone(&mock)),
+            ..Default::default()
+        });
+        let engine = WorkflowEngine::with_dirs([workflows], executor).unwrap();
+
+        let snapshots = dir.path().join("snapshots");
+        SnapshotCase {
+            responses: vec![MockResponse {
+                matches: "bucket create".to_string(),
+                stdout: r#"{"bucketKey": "demo-bucket"}"#.to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+        .save(&SnapshotCase::path(&snapshots, "snapshot-demo"))
+        .unwrap();
+
+        let outcome = test_workflow(&engine, &mock, &snapshots, "snapshot-demo", false).await.unwrap();
+        assert_eq!(outcome, TestOutcome::Recorded);
+        let recorded = SnapshotCase::load(&SnapshotCase::path(&snapshots, "snapshot-demo")).unwrap();
+        let snapshot = recorded.snapshot.clone().unwrap();
+        assert!(snapshot.success);
+        // Prerequisite checks (version, auth) come first
+        let commands = &snapshot.commands[snapshot.commands.len() - 2..];
+        assert!(commands[0].starts_with("raps bucket create --key demo-"), "{:?}", snapshot.commands);
+        assert!(commands[1].contains("demo-bucket"), "{:?}", snapshot.commands);
+        assert_eq!(snapshot.outputs.get("steps.create.bucketKey").map(String::as_str), Some("demo-bucket"));
+        assert_eq!(snapshot_ids(&snapshots), vec!["snapshot-demo"]);
+
+        // Same seed, same commands
+        let outcome = test_workflow(&engine, &mock, &snapshots, "snapshot-demo", false).await.unwrap();
+        assert_eq!(outcome, TestOutcome::Passed);
+
+        // A failure injected into a step changes the outcome
+        let mut injected = recorded.clone();
+        injected.inject_failures = vec!["list=not-found".to_string()];
+        injected.save(&SnapshotCase::path(&snapshots, "snapshot-demo")).unwrap();
+        match test_workflow(&engine, &mock, &snapshots, "snapshot-demo", false).await.unwrap() {
+            TestOutcome::Failed(diff) => assert!(diff.contains(&"+ success: false".to_string()), "{:?}", diff),
+            other => panic!("expected a failure, got {:?}", other),
+        }
+
+        // A failing command changes the outcome
+        let mut failing = recorded;
+        failing.responses[0].exit_code = 1;
+        failing.save(&SnapshotCase::path(&snapshots, "snapshot-demo")).unwrap();
+        match test_workflow(&engine, &mock, &snapshots, "snapshot-demo", false).await.unwrap() {
+            TestOutcome::Failed(diff) => assert!(diff.contains(&"- success: true".to_string()), "{:?}", diff),
+            other => panic!("expected a failure, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// Guided tours for RAPS Demo Workflows
+//
+// A tour strings individual workflows together with narration screens and
+// quiz/checkpoint popups into one scripted demo. Tours live next to the
+// workflows as `*.tour.yaml` files:
+//
+//   id: aps-in-20-minutes
+//   name: APS in 20 Minutes
+//   items:
+//     - narration: { title: Authentication, text: "First we log in..." }
+//     - workflow: auth-management-demo
+//     - checkpoint:
+//         question: Which token type does a server-to-server app use?
+//         options: [2-legged, 3-legged]
+//         answer: 1
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Suffixes identifying tour files (skipped by workflow discovery)
+pub const TOUR_FILE_SUFFIXES: [&str; 2] = [".tour.yaml", ".tour.yml"];
+
+/// A scripted sequence of workflows, narration and checkpoints
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TourDefinition {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Items are written as `- narration: {...}` maps rather than YAML tags
+    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
+    pub items: Vec<TourItem>,
+    /// File the tour was loaded from
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+/// One stop of a tour
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TourItem {
+    /// A screen of text shown between workflows
+    Narration { title: String, text: String },
+    /// Run the workflow with this ID
+    Workflow(String),
+    /// A question for the audience, optionally with a correct answer
+    Checkpoint(Checkpoint),
+}
+
+/// Quiz or discussion popup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub question: String,
+    #[serde(default)]
+    pub options: Vec<String>,
+    /// 1-based index of the correct option
+    #[serde(default)]
+    pub answer: Option<usize>,
+    /// Shown once the question has been answered
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+impl TourDefinition {
+    /// IDs of the workflows the tour runs, in order
+    pub fn workflow_ids(&self) -> impl Iterator<Item = &str> {
+        self.items.iter().filter_map(|item| match item {
+            TourItem::Workflow(id) => Some(id.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Problems with the tour, given the IDs of the known workflows
+    pub fn validate<'a>(&self, known_workflows: impl IntoIterator<Item = &'a str> + Clone) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.items.is_empty() {
+            errors.push(format!("Tour '{}' has no items", self.id));
+        }
+        for id in self.workflow_ids() {
+            if !known_workflows.clone().into_iter().any(|known| known == id) {
+                errors.push(format!("Tour '{}' references unknown workflow '{}'", self.id, id));
+            }
+        }
+        for item in &self.items {
+            if let TourItem::Checkpoint(checkpoint) = item {
+                if let Some(answer) = checkpoint.answer {
+                    if answer == 0 || answer > checkpoint.options.len() {
+                        errors.push(format!(
+                            "Checkpoint '{}' answer {} is not one of its {} options",
+                            checkpoint.question,
+                            answer,
+                            checkpoint.options.len()
+                        ));
+                    }
+                }
+            }
+        }
+        errors
+    }
+}
+
+/// Whether a path is a tour file rather than a workflow
+pub fn is_tour_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| TOUR_FILE_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)))
+}
+
+/// Load a tour from a YAML file
+pub fn load_tour<P: AsRef<Path>>(path: P) -> Result<TourDefinition> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read tour: {:?}", path))?;
+    let mut tour: TourDefinition =
+        serde_yaml::from_str(&content).with_context(|| format!("Failed to parse tour: {:?}", path))?;
+    tour.path = path.to_path_buf();
+    Ok(tour)
+}
+
+/// Load every tour file under `dir`, sorted by name; unreadable tours are logged and skipped
+pub fn discover_tours<P: AsRef<Path>>(dir: P) -> Vec<TourDefinition> {
+    let mut tours: Vec<TourDefinition> = WalkDir::new(dir.as_ref())
+        .follow_links(true)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && is_tour_file(e.path()))
+        .filter_map(|e| match load_tour(e.path()) {
+            Ok(tour) => Some(tour),
+            Err(e) => {
+                tracing::error!("Failed to load tour: {:#}", e);
+                None
+            },
+        })
+        .collect();
+    tours.sort_by(|a, b| a.name.cmp(&b.name));
+    tours
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_and_validate_tour() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("intro.tour.yaml");
+        fs::write(
+            &path,
+            r#"
+id: intro
+name: Intro Tour
+items:
+  - narration: { title: Welcome, text: "Let's start" }
+  - workflow: oss-bucket-lifecycle
+  - workflow: missing-workflow
+  - checkpoint:
+      question: Which region?
+      options: [US, EMEA]
+      answer: 3
+"#,
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("workflow.yaml"), "metadata: {}").unwrap();
+
+        let tours = discover_tours(temp_dir.path());
+        assert_eq!(tours.len(), 1);
+        let tour = &tours[0];
+        assert_eq!(tour.path, path);
+        assert_eq!(tour.items.len(), 4);
+        assert_eq!(
+            tour.workflow_ids().collect::<Vec<_>>(),
+            vec!["oss-bucket-lifecycle", "missing-workflow"]
+        );
+
+        let errors = tour.validate(["oss-bucket-lifecycle"]);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("missing-workflow"));
+        assert!(errors[1].contains("answer 3"));
+        assert!(!is_tour_file(Path::new("workflows/oss/upload.yaml")));
+    }
+}
@@ -0,0 +1,184 @@
+// Model Derivative translation status
+//
+// `raps translate status` prints the job's manifest: an overall `status`
+// (pending, inprogress, success, failed, timeout), a `progress` string such as
+// `45% complete` and one entry per derivative with its own status and error
+// messages. Steps that wait for a translation poll it and report the parsed
+// percentage as step progress, and a failed job names the derivatives that
+// failed and why instead of just "exit code 1". Once a job succeeded, the
+// manifest also lists what can be downloaded (OBJ, thumbnails, the properties
+// database, ...).
+
+use serde_json::Value;
+
+use super::client::parse_progress_percent;
+
+/// Parsed translation manifest
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranslationStatus {
+    /// Overall status as reported (`inprogress`, `success`, ...)
+    pub status: String,
+    /// Overall progress from 0.0 to 1.0
+    pub progress: f32,
+    /// Failed derivatives as `<output type>: <error messages>`
+    pub failed_derivatives: Vec<String>,
+}
+
+impl TranslationStatus {
+    /// Parse `translate status` / `translate manifest` output; `None` when it
+    /// is not a manifest
+    pub fn parse(json: &Value) -> Option<Self> {
+        let status = json.get("status").and_then(Value::as_str)?.to_lowercase();
+        let progress = match json.get("progress").and_then(Value::as_str) {
+            Some(text) if text.eq_ignore_ascii_case("complete") => 1.0,
+            Some(text) => parse_progress_percent(text).unwrap_or(0.0),
+            None => 0.0,
+        };
+        let progress = if status == "success" { 1.0 } else { progress };
+
+        let failed_derivatives = json
+            .get("derivatives")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|d| matches!(d.get("status").and_then(Value::as_str), Some("failed" | "timeout")))
+            .map(|derivative| {
+                let output = derivative.get("outputType").and_then(Value::as_str).unwrap_or("derivative");
+                let messages: Vec<&str> = derivative
+                    .get("messages")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter(|m| m.get("type").and_then(Value::as_str) == Some("error"))
+                    .filter_map(|m| m.get("message").and_then(Value::as_str))
+                    .collect();
+                if messages.is_empty() {
+                    format!("{}: failed", output)
+                } else {
+                    format!("{}: {}", output, messages.join("; "))
+                }
+            })
+            .collect();
+
+        Some(Self {
+            status,
+            progress,
+            failed_derivatives,
+        })
+    }
+
+    /// Whether the job has finished, successfully or not
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status.as_str(), "success" | "failed" | "timeout")
+    }
+
+    /// Whether the job finished with every derivative translated
+    pub fn is_success(&self) -> bool {
+        self.status == "success" && self.failed_derivatives.is_empty()
+    }
+
+    /// One-line summary for step progress, e.g. `Translating: 45% (inprogress)`
+    pub fn summary(&self) -> String {
+        format!("Translating: {:.0}% ({})", self.progress * 100.0, self.status)
+    }
+}
+
+/// Manifest role of the properties database resource
+const PROPERTY_DATABASE_ROLE: &str = "Autodesk.CloudPlatform.PropertyDatabase";
+
+/// A derivative that `translate download --format` can fetch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivativeFile {
+    /// Value for `--format`, e.g. `obj`, `thumbnail` or `propertydb`
+    pub format: String,
+    /// Human-readable description for pickers
+    pub label: String,
+}
+
+/// Successfully translated derivatives in a manifest, one per format
+pub fn downloadable_derivatives(manifest: &Value) -> Vec<DerivativeFile> {
+    let mut files: Vec<DerivativeFile> = Vec::new();
+    let mut add = |format: &str, label: String| {
+        if !files.iter().any(|f| f.format == format) {
+            files.push(DerivativeFile {
+                format: format.to_string(),
+                label,
+            });
+        }
+    };
+
+    let derivatives = manifest.get("derivatives").and_then(Value::as_array).into_iter().flatten();
+    for derivative in derivatives.filter(|d| d.get("status").and_then(Value::as_str) == Some("success")) {
+        if let Some(output) = derivative.get("outputType").and_then(Value::as_str) {
+            let label = match derivative.get("name").and_then(Value::as_str) {
+                Some(name) => format!("{} ({})", output.to_uppercase(), name),
+                None => output.to_uppercase(),
+            };
+            add(output, label);
+        }
+        let mut children: Vec<&Value> = derivative.get("children").and_then(Value::as_array).into_iter().flatten().collect();
+        while let Some(child) = children.pop() {
+            match child.get("role").and_then(Value::as_str) {
+                Some("thumbnail") => add("thumbnail", "Thumbnails".to_string()),
+                Some(PROPERTY_DATABASE_ROLE) => add("propertydb", "Properties database".to_string()),
+                _ => {},
+            }
+            children.extend(child.get("children").and_then(Value::as_array).into_iter().flatten());
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translation_status() {
+        let running = serde_json::json!({"status": "inprogress", "progress": "45% complete"});
+        let status = TranslationStatus::parse(&running).unwrap();
+        assert_eq!(status.progress, 0.45);
+        assert!(!status.is_finished());
+        assert_eq!(status.summary(), "Translating: 45% (inprogress)");
+
+        let failed = serde_json::json!({
+            "status": "failed",
+            "progress": "complete",
+            "derivatives": [
+                {"outputType": "svf2", "status": "failed", "messages": [
+                    {"type": "warning", "message": "Missing link"},
+                    {"type": "error", "message": "Unsupported file version"}
+                ]},
+                {"outputType": "thumbnail", "status": "success"}
+            ]
+        });
+        let status = TranslationStatus::parse(&failed).unwrap();
+        assert!(status.is_finished() && !status.is_success());
+        assert_eq!(status.progress, 1.0);
+        assert_eq!(status.failed_derivatives, vec!["svf2: Unsupported file version"]);
+
+        assert!(TranslationStatus::parse(&serde_json::json!({"bucketKey": "demo"})).is_none());
+    }
+
+    #[test]
+    fn test_downloadable_derivatives() {
+        let manifest = serde_json::json!({
+            "status": "success",
+            "derivatives": [
+                {"outputType": "svf2", "name": "house.rvt", "status": "success", "children": [
+                    {"type": "geometry", "role": "3d", "children": [
+                        {"type": "resource", "role": "thumbnail", "mime": "image/png"}
+                    ]},
+                    {"type": "resource", "role": "Autodesk.CloudPlatform.PropertyDatabase"}
+                ]},
+                {"outputType": "obj", "status": "success"},
+                {"outputType": "ifc", "status": "failed"}
+            ]
+        });
+        let files = downloadable_derivatives(&manifest);
+        let formats: Vec<&str> = files.iter().map(|f| f.format.as_str()).collect();
+        assert_eq!(formats, vec!["svf2", "propertydb", "thumbnail", "obj"]);
+        assert_eq!(files[0].label, "SVF2 (house.rvt)");
+        assert!(downloadable_derivatives(&serde_json::json!({})).is_empty());
+    }
+}
@@ -5,10 +5,13 @@
 
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use uuid::Uuid;
 
+use super::chaos::ChaosOptions;
+use super::hooks::{HookAction, StepHooks};
+
 // Use shared serde helpers
 use crate::utils::serde_helpers::{duration_serde, optional_duration_serde};
 
@@ -25,33 +28,131 @@ pub type StepId = String;
 pub type AssetPath = PathBuf;
 
 /// Workflow category for organization and filtering
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "kebab-case")]
+///
+/// Built-in APS categories accept several spellings (`oss`, `object-storage`,
+/// `ObjectStorage`, ...). Any other value is kept as a custom category whose
+/// display metadata can be declared by workflow packs (see [`CategoryInfo`]).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum WorkflowCategory {
     /// Object Storage Service workflows
-    #[serde(alias = "oss", alias = "object-storage")]
     ObjectStorage,
     /// Model Derivative workflows
-    #[serde(alias = "model-derivative", alias = "md")]
     ModelDerivative,
     /// Data Management workflows
-    #[serde(alias = "data-management", alias = "dm")]
     DataManagement,
     /// Design Automation workflows
-    #[serde(alias = "design-automation", alias = "da")]
     DesignAutomation,
     /// Autodesk Construction Cloud workflows
-    #[serde(alias = "construction-cloud", alias = "acc")]
     ConstructionCloud,
     /// Reality Capture workflows
-    #[serde(alias = "reality-capture", alias = "rc")]
     RealityCapture,
     /// Webhook management workflows
-    #[serde(alias = "webhooks")]
     Webhooks,
     /// End-to-end workflows combining multiple services
-    #[serde(alias = "end-to-end", alias = "e2e")]
     EndToEnd,
+    /// Category declared by a third-party workflow pack
+    Custom(String),
+}
+
+impl WorkflowCategory {
+    /// Built-in categories in their default display order
+    pub const BUILT_IN: [WorkflowCategory; 8] = [
+        WorkflowCategory::ObjectStorage,
+        WorkflowCategory::ModelDerivative,
+        WorkflowCategory::DataManagement,
+        WorkflowCategory::DesignAutomation,
+        WorkflowCategory::ConstructionCloud,
+        WorkflowCategory::RealityCapture,
+        WorkflowCategory::Webhooks,
+        WorkflowCategory::EndToEnd,
+    ];
+
+    /// Parse a category from its ID or one of the built-in aliases
+    pub fn from_id(id: &str) -> Self {
+        let normalized: String = id
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .collect::<String>()
+            .to_ascii_lowercase();
+        match normalized.as_str() {
+            "objectstorage" | "oss" => WorkflowCategory::ObjectStorage,
+            "modelderivative" | "md" => WorkflowCategory::ModelDerivative,
+            "datamanagement" | "dm" => WorkflowCategory::DataManagement,
+            "designautomation" | "da" => WorkflowCategory::DesignAutomation,
+            "constructioncloud" | "acc" => WorkflowCategory::ConstructionCloud,
+            "realitycapture" | "rc" => WorkflowCategory::RealityCapture,
+            "webhooks" => WorkflowCategory::Webhooks,
+            "endtoend" | "e2e" => WorkflowCategory::EndToEnd,
+            _ => WorkflowCategory::Custom(id.trim().to_string()),
+        }
+    }
+
+    /// Canonical category ID, as written to YAML
+    pub fn id(&self) -> &str {
+        match self {
+            WorkflowCategory::ObjectStorage => "object-storage",
+            WorkflowCategory::ModelDerivative => "model-derivative",
+            WorkflowCategory::DataManagement => "data-management",
+            WorkflowCategory::DesignAutomation => "design-automation",
+            WorkflowCategory::ConstructionCloud => "construction-cloud",
+            WorkflowCategory::RealityCapture => "reality-capture",
+            WorkflowCategory::Webhooks => "webhooks",
+            WorkflowCategory::EndToEnd => "end-to-end",
+            WorkflowCategory::Custom(id) => id,
+        }
+    }
+
+    /// Default display metadata; custom categories get generic defaults
+    pub fn default_info(&self) -> CategoryInfo {
+        let (icon, sort_order, color) = match self {
+            WorkflowCategory::ObjectStorage => ("[OSS]", 10, "cyan"),
+            WorkflowCategory::ModelDerivative => ("[MD]", 20, "green"),
+            WorkflowCategory::DataManagement => ("[DM]", 30, "blue"),
+            WorkflowCategory::DesignAutomation => ("[DA]", 40, "magenta"),
+            WorkflowCategory::ConstructionCloud => ("[ACC]", 50, "yellow"),
+            WorkflowCategory::RealityCapture => ("[RC]", 60, "lightgreen"),
+            WorkflowCategory::Webhooks => ("[WH]", 70, "lightblue"),
+            WorkflowCategory::EndToEnd => ("[E2E]", 80, "lightmagenta"),
+            WorkflowCategory::Custom(id) => {
+                let abbrev: String = id
+                    .chars()
+                    .filter(|c| c.is_ascii_alphanumeric())
+                    .take(3)
+                    .collect::<String>()
+                    .to_ascii_uppercase();
+                return CategoryInfo {
+                    id: id.clone(),
+                    display_name: id.clone(),
+                    icon: Some(format!("[{}]", abbrev)),
+                    sort_order: CategoryInfo::DEFAULT_SORT_ORDER,
+                    color: None,
+                };
+            },
+        };
+        CategoryInfo {
+            id: self.id().to_string(),
+            display_name: self.to_string(),
+            icon: Some(icon.to_string()),
+            sort_order,
+            color: Some(color.to_string()),
+        }
+    }
+}
+
+impl Serialize for WorkflowCategory {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.id())
+    }
+}
+
+impl<'de> Deserialize<'de> for WorkflowCategory {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        if id.trim().is_empty() {
+            return Err(serde::de::Error::custom("category cannot be empty"));
+        }
+        Ok(WorkflowCategory::from_id(&id))
+    }
 }
 
 impl std::fmt::Display for WorkflowCategory {
@@ -65,25 +166,101 @@ impl std::fmt::Display for WorkflowCategory {
             WorkflowCategory::RealityCapture => write!(f, "Reality Capture"),
             WorkflowCategory::Webhooks => write!(f, "Webhooks"),
             WorkflowCategory::EndToEnd => write!(f, "End-to-End"),
+            WorkflowCategory::Custom(id) => write!(f, "{}", id),
         }
     }
 }
 
+/// Display metadata for a workflow category
+///
+/// Workflow files may declare categories in a top-level `categories:` list:
+///
+/// ```yaml
+/// categories:
+///   - id: tandem
+///     display_name: Tandem Digital Twins
+///     icon: "[TDM]"
+///     sort_order: 90
+///     color: magenta
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryInfo {
+    /// Category ID referenced by `metadata.category`
+    pub id: String,
+    /// Name shown in the sidebar
+    pub display_name: String,
+    /// Short tag shown next to workflow names (e.g. `[OSS]`)
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Position in the sidebar (lower first)
+    #[serde(default = "CategoryInfo::default_sort_order")]
+    pub sort_order: i32,
+    /// Color name for the category header (e.g. `cyan`, `lightmagenta`, `#ff8800`)
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+impl CategoryInfo {
+    /// Sort order for categories that don't specify one
+    pub const DEFAULT_SORT_ORDER: i32 = 1000;
+
+    fn default_sort_order() -> i32 {
+        Self::DEFAULT_SORT_ORDER
+    }
+}
+
+/// Known categories: the built-ins plus any declared by workflow files
+#[derive(Debug, Clone)]
+pub struct CategoryRegistry {
+    categories: HashMap<String, CategoryInfo>,
+}
+
+impl Default for CategoryRegistry {
+    fn default() -> Self {
+        let categories = WorkflowCategory::BUILT_IN
+            .iter()
+            .map(|c| (c.id().to_string(), c.default_info()))
+            .collect();
+        Self { categories }
+    }
+}
+
+impl CategoryRegistry {
+    /// Register (or override) a category declaration
+    pub fn register(&mut self, info: CategoryInfo) {
+        let id = WorkflowCategory::from_id(&info.id).id().to_string();
+        self.categories.insert(id, info);
+    }
+
+    /// Whether the category has been declared (built-ins always are)
+    pub fn is_declared(&self, category: &WorkflowCategory) -> bool {
+        self.categories.contains_key(category.id())
+    }
+
+    /// Display metadata for a category, falling back to defaults
+    pub fn info(&self, category: &WorkflowCategory) -> CategoryInfo {
+        self.categories
+            .get(category.id())
+            .cloned()
+            .unwrap_or_else(|| category.default_info())
+    }
+}
+
 /// Prerequisite type for workflow execution
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum PrerequisiteType {
     /// Valid APS authentication required
-    #[serde(alias = "authentication", alias = "auth")]
+    #[serde(alias = "authentication", alias = "auth", alias = "Authentication")]
     Authentication,
     /// Specific permissions required
-    #[serde(alias = "permissions", alias = "perms")]
+    #[serde(alias = "permissions", alias = "perms", alias = "Permissions")]
     Permissions,
     /// External tool or service required
-    #[serde(alias = "external-tool", alias = "tool")]
+    #[serde(alias = "external-tool", alias = "tool", alias = "ExternalTool")]
     ExternalTool,
     /// Specific asset files required
-    #[serde(alias = "assets", alias = "files")]
+    #[serde(alias = "assets", alias = "files", alias = "Assets")]
     Assets,
 }
 
@@ -122,6 +299,9 @@ pub struct WorkflowMetadata {
     pub description: String,
     /// Category for organization
     pub category: WorkflowCategory,
+    /// Free-form labels for filtering and catalogs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     /// Prerequisites for execution
     #[serde(default)]
     pub prerequisites: Vec<Prerequisite>,
@@ -154,6 +334,21 @@ pub enum ExecutionStatus {
     Failed,
     /// Cancelled by user
     Cancelled,
+    /// Failed, then skipped by the user to continue the workflow
+    Skipped,
+    /// Not run because its `skip_if` check found the result already in place
+    AlreadyExists,
+}
+
+/// What to do about a failed step held for a decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepDecision {
+    /// Run the step again
+    Retry,
+    /// Record the step as skipped and continue with the next one
+    Skip,
+    /// Fail the workflow
+    Abort,
 }
 
 /// Options for workflow execution
@@ -168,6 +363,33 @@ pub struct ExecutionOptions {
     /// Maximum time to wait for completion
     #[serde(with = "duration_serde")]
     pub timeout: Duration,
+    /// Keep the execution's temporary directory after it finishes
+    #[serde(default)]
+    pub keep_temp_dir: bool,
+    /// Parameter values overriding the workflow's defaults
+    #[serde(default)]
+    pub params: HashMap<String, String>,
+    /// Seed for `{uuid}` and the `{random}` generator, to reproduce names
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Hold a failed step for a retry/skip/abort decision instead of failing
+    /// the run (always the case in interactive mode)
+    #[serde(default)]
+    pub ask_on_failure: bool,
+    /// Skip the steps before this one; outputs of skipped steps that later
+    /// steps use must be given as `steps.<id>.<key>` parameters
+    #[serde(default)]
+    pub start_at_step: Option<StepId>,
+    /// Run only the steps of this workflow profile
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Faults injected into step commands, for training
+    #[serde(default)]
+    pub chaos: ChaosOptions,
+    /// Earlier runs (`upload:latest`) whose recorded outputs are used as
+    /// parameters, in addition to the workflow's `uses_outputs:`
+    #[serde(default)]
+    pub from_outputs: Vec<String>,
 }
 
 impl Default for ExecutionOptions {
@@ -177,6 +399,14 @@ impl Default for ExecutionOptions {
             verbose: false,
             auto_cleanup: true,
             timeout: Duration::minutes(30),
+            keep_temp_dir: false,
+            params: HashMap::new(),
+            seed: None,
+            ask_on_failure: false,
+            start_at_step: None,
+            profile: None,
+            chaos: ChaosOptions::default(),
+            from_outputs: Vec::new(),
         }
     }
 }
@@ -285,6 +515,12 @@ pub struct ObjectParams {
     pub file_path: Option<PathBuf>,
     pub batch: Option<bool>,
     pub expires_in: Option<u64>,
+    /// Upload in parts of this many MB (multipart upload for large models)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk_size_mb: Option<u64>,
+    /// Parts uploaded at the same time in a multipart upload
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallel_parts: Option<u32>,
 }
 
 /// Translation actions
@@ -295,6 +531,7 @@ pub enum TranslateAction {
     Status,
     Download,
     Manifest,
+    Thumbnail,
 }
 
 /// Translation operation parameters
@@ -304,6 +541,9 @@ pub struct TranslateParams {
     pub format: Option<String>,
     pub output_dir: Option<PathBuf>,
     pub wait: Option<bool>,
+    /// Region the translation runs in (US, EMEA or APAC)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
 }
 
 /// Data Management actions
@@ -319,7 +559,7 @@ pub enum DataMgmtAction {
 }
 
 /// Data Management operation parameters
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DataMgmtParams {
     pub hub_id: Option<String>,
     pub project_id: Option<String>,
@@ -339,7 +579,7 @@ pub enum DesignAutoAction {
 }
 
 /// Design Automation operation parameters
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DesignAutoParams {
     pub app_bundle_id: Option<String>,
     pub activity_id: Option<String>,
@@ -359,12 +599,47 @@ pub struct ExecutionStep {
     pub description: String,
     /// RAPS command to execute
     pub command: RapsCommand,
-    /// Expected duration for this step
-    #[serde(with = "optional_duration_serde", default)]
+    /// Expected duration for this step (also accepted as
+    /// `estimated_duration`); weights the overall progress
+    #[serde(with = "optional_duration_serde", default, alias = "estimated_duration")]
     pub expected_duration: Option<Duration>,
     /// Commands to run for cleanup if this step fails
     #[serde(default)]
     pub cleanup_commands: Vec<RapsCommand>,
+    /// Directory to run the command in (placeholders allowed); relative
+    /// paths are resolved against the execution's temporary directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<PathBuf>,
+    /// Files to keep after the run (globs relative to the working directory)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<String>,
+    /// Extra environment variables for the command (placeholders allowed)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+    /// Environment variables filled from secrets (variable name -> secret key)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub secrets: HashMap<String, String>,
+    /// RAPS profile (account) to run this step as, instead of the workflow's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Step-level hooks (optional)
+    #[serde(default, skip_serializing_if = "StepHooks::is_empty")]
+    pub hooks: StepHooks,
+    /// Check run before the step (a RAPS command or HTTP request); when it
+    /// succeeds, the step's result already exists and the step is skipped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_if: Option<HookAction>,
+    /// Asset files this step needs, checked with the workflow's
+    /// `required_assets` before the run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires_assets: Vec<AssetPath>,
+    /// What the command typically prints, shown in the Steps tab and
+    /// exported reports (documentation only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_output: Option<String>,
+    /// Teaching notes for the step (documentation only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
 }
 
 /// Result of executing a workflow step
@@ -386,6 +661,13 @@ pub struct StepResult {
     pub exit_code: Option<i32>,
     /// Resources created during this step
     pub created_resources: Vec<ResourceId>,
+    /// Files holding the full output when `stdout`/`stderr` were truncated
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub full_output: Vec<PathBuf>,
+    /// JSON output as the command printed it, before redaction; kept in
+    /// memory for the TUI (signed URLs for QR codes), never stored
+    #[serde(skip)]
+    pub json_output: Option<serde_json::Value>,
 }
 
 /// Complete workflow execution result
@@ -406,8 +688,28 @@ pub struct ExecutionResult {
     pub resources_created: Vec<ResourceId>,
     /// Whether cleanup was performed
     pub cleanup_performed: bool,
+    /// Outcome of each cleanup command, in the order they ran
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cleanup_results: Vec<CleanupResult>,
     /// Results from individual steps
     pub step_results: Vec<StepResult>,
+    /// Values of the workflow's declared outputs
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub outputs: BTreeMap<String, String>,
+}
+
+/// Outcome of a cleanup command run after a workflow
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CleanupResult {
+    /// Step whose cleanup this is; `None` for the workflow's `cleanup`
+    pub step_id: Option<StepId>,
+    /// Command line as run
+    pub command: String,
+    /// Whether the command succeeded
+    pub success: bool,
+    /// Why it failed or was not run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 /// Progress information for ongoing execution
@@ -0,0 +1,99 @@
+// Shared variables for the workflows of a directory
+//
+// A `vars.yaml` next to the workflows holds values many of them use: the
+// bucket prefix, the region, the hub ID. Workflows refer to them as
+// `{vars.<name>}`, in commands as well as in parameter defaults, so a pack
+// is retargeted to another region or tenant by editing one file. An overlay
+// `vars.<env>.yaml` (selected with `--env` or `vars_env` in `demo.toml`)
+// overrides single values for one environment. With several workflows
+// directories, later directories override earlier ones.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Base name of variables files
+const VARS_STEM: &str = "vars";
+
+/// Whether `path` is a variables file (`vars.yaml` or `vars.<env>.yaml`)
+/// rather than a workflow
+pub fn is_vars_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let Some(stem) = name.strip_suffix(".yaml").or_else(|| name.strip_suffix(".yml")) else {
+        return false;
+    };
+    stem == VARS_STEM || stem.strip_prefix(VARS_STEM).is_some_and(|env| env.starts_with('.') && env.len() > 1)
+}
+
+/// Variables files of `dir` for environment `env`, base file first
+fn vars_files(dir: &Path, env: Option<&str>) -> Vec<PathBuf> {
+    let mut stems = vec![VARS_STEM.to_string()];
+    if let Some(env) = env {
+        stems.push(format!("{}.{}", VARS_STEM, env));
+    }
+    stems
+        .iter()
+        .flat_map(|stem| ["yaml", "yml"].map(|ext| dir.join(format!("{}.{}", stem, ext))))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Variables of `dirs` for environment `env`: each directory's `vars.yaml`,
+/// then its overlay, later directories overriding earlier ones
+pub fn load_vars(dirs: &[PathBuf], env: Option<&str>) -> Result<BTreeMap<String, String>> {
+    let mut vars = BTreeMap::new();
+    for path in dirs.iter().flat_map(|dir| vars_files(dir, env)) {
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read variables file: {}", path.display()))?;
+        let values: BTreeMap<String, serde_yaml::Value> = serde_yaml::from_str::<Option<_>>(&content)
+            .with_context(|| format!("Failed to parse variables file: {}", path.display()))?
+            .unwrap_or_default();
+        for (name, value) in values {
+            let value = match value {
+                serde_yaml::Value::String(text) => text,
+                serde_yaml::Value::Number(number) => number.to_string(),
+                serde_yaml::Value::Bool(flag) => flag.to_string(),
+                _ => anyhow::bail!("Variable '{}' in {} is not a string, number or boolean", name, path.display()),
+            };
+            vars.insert(name, value);
+        }
+    }
+    if let Some(env) = env {
+        let has_overlay =
+            |dir: &PathBuf| ["yaml", "yml"].iter().any(|ext| dir.join(format!("{}.{}.{}", VARS_STEM, env, ext)).is_file());
+        if !dirs.iter().any(has_overlay) {
+            tracing::warn!("No {}.{}.yaml overlay in the workflows directories", VARS_STEM, env);
+        }
+    }
+    Ok(vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_vars_and_overlays() {
+        let (shared, user) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+        fs::write(shared.path().join("vars.yaml"), "bucket_prefix: demo\nregion: US\nhub_id: b.123\n").unwrap();
+        fs::write(shared.path().join("vars.emea.yaml"), "region: EMEA\n").unwrap();
+        fs::write(user.path().join("vars.yml"), "hub_id: b.456\nretries: 3\n").unwrap();
+        let dirs = vec![shared.path().to_path_buf(), user.path().to_path_buf()];
+
+        let base = load_vars(&dirs, None).unwrap();
+        assert_eq!(base["region"], "US");
+        assert_eq!(base["hub_id"], "b.456");
+        assert_eq!(base["retries"], "3");
+        let emea = load_vars(&dirs, Some("emea")).unwrap();
+        assert_eq!(emea["region"], "EMEA");
+        assert_eq!(emea["bucket_prefix"], "demo");
+
+        assert!(is_vars_file(Path::new("packs/vars.emea.yaml")));
+        assert!(!is_vars_file(Path::new("variables.yaml")));
+        assert!(!is_vars_file(Path::new("vars-demo.yaml")));
+    }
+}
@@ -0,0 +1,163 @@
+// Watch mode for workflow authors
+//
+// `raps-demo watch <id>` re-validates a workflow whenever its YAML or one of
+// its required assets changes, prints how the resolved RAPS commands differ
+// from the previous version and then runs (or, with `--dry-run`, only shows)
+// the workflow. Files are polled by modification time, which is enough for a
+// handful of paths and works the same on every platform.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::client::RapsClient;
+use super::discovery::WorkflowDefinition;
+use super::placeholders;
+
+/// Files a watched workflow depends on: its YAML and its required assets
+pub fn watched_paths(workflow: &WorkflowDefinition) -> Vec<PathBuf> {
+    let mut paths = vec![workflow.metadata.script_path.clone()];
+    for asset in workflow.required_assets() {
+        if !paths.contains(&asset) {
+            paths.push(asset);
+        }
+    }
+    paths
+}
+
+/// Modification times of a set of files, to notice edits between polls
+#[derive(Debug, Clone, Default)]
+pub struct FileStamps {
+    stamps: BTreeMap<PathBuf, Option<SystemTime>>,
+}
+
+impl FileStamps {
+    /// Current modification times of `paths` (`None` for missing files)
+    pub fn capture(paths: &[PathBuf]) -> Self {
+        Self {
+            stamps: paths.iter().map(|path| (path.clone(), modified(path))).collect(),
+        }
+    }
+
+    /// Files created, deleted or modified since `self` was captured
+    pub fn changed_since(&self, now: &FileStamps) -> Vec<PathBuf> {
+        let mut changed: Vec<PathBuf> = now
+            .stamps
+            .iter()
+            .filter(|(path, stamp)| self.stamps.get(*path) != Some(stamp))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.extend(self.stamps.keys().filter(|path| !now.stamps.contains_key(*path)).cloned());
+        changed
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Each step's RAPS command line with parameters resolved, one line per step
+///
+/// Values only known at run time (step outputs, `{uuid}`, `{timestamp}`) stay
+/// as placeholders, so consecutive versions compare cleanly.
+pub fn resolved_commands(
+    client: &RapsClient,
+    workflow: &WorkflowDefinition,
+    params: &HashMap<String, String>,
+) -> Vec<String> {
+    workflow
+        .steps
+        .iter()
+        .zip(resolved_command_lines(client, workflow, params))
+        .map(|(step, line)| format!("{}: {}", step.id, line))
+        .collect()
+}
+
+/// Each step's RAPS command line with parameters resolved, without step IDs
+pub fn resolved_command_lines(
+    client: &RapsClient,
+    workflow: &WorkflowDefinition,
+    params: &HashMap<String, String>,
+) -> Vec<String> {
+    let mut values: HashMap<String, String> = HashMap::new();
+    for (name, value) in workflow.params.iter().chain(params) {
+        values.insert(format!("{}{}", placeholders::PARAMS_PREFIX, name), value.clone());
+    }
+
+    workflow
+        .steps
+        .iter()
+        .map(|step| {
+            let mut undefined = Vec::new();
+            let command = serde_json::to_value(&step.command)
+                .ok()
+                .map(|json| placeholders::resolve_json(json, &values, &mut undefined))
+                .and_then(|json| serde_json::from_value(json).ok())
+                .unwrap_or_else(|| step.command.clone());
+            client.display_command_line(&command)
+        })
+        .collect()
+}
+
+/// Line diff of `old` and `new`: removed lines prefixed `- `, added lines `+ `,
+/// unchanged lines dropped
+pub fn diff_lines(old: &[String], new: &[String]) -> Vec<String> {
+    // Longest common subsequence table, from the end of both lists
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            diff.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_diff_and_stamps() {
+        let old = lines(&["a: raps bucket list", "b: raps object list demo", "c: raps auth status"]);
+        let new = lines(&["a: raps bucket list", "b: raps object list demo-2", "c: raps auth status", "d: raps auth logout"]);
+        assert_eq!(
+            diff_lines(&old, &new),
+            vec!["- b: raps object list demo", "+ b: raps object list demo-2", "+ d: raps auth logout"]
+        );
+        assert!(diff_lines(&old, &old).is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("workflow.yaml");
+        let missing = dir.path().join("model.rvt");
+        std::fs::write(&file, "steps: []").unwrap();
+        let paths = vec![file.clone(), missing.clone()];
+        let before = FileStamps::capture(&paths);
+        assert!(before.changed_since(&FileStamps::capture(&paths)).is_empty());
+
+        std::fs::write(&missing, "model").unwrap();
+        assert_eq!(before.changed_since(&FileStamps::capture(&paths)), vec![missing]);
+    }
+}
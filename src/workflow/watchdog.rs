@@ -0,0 +1,142 @@
+// Watchdog for stuck executions
+//
+// A RAPS command can hang (a stalled upload, a translation poll that never
+// answers) and leave its run in Running forever. Each run records when it last
+// showed signs of life: a step starting, reporting progress or finishing. The
+// executor checks that periodically and, past the configured threshold, marks
+// the run stalled and publishes a `Stalled` update with what it was doing, so
+// the user can kill the command or fail the run.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::history::format_duration_secs;
+use super::types::StepId;
+
+/// Watchdog settings (`[watchdog]` in `demo.toml`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    /// Watch running executions for stalls
+    pub enabled: bool,
+    /// Seconds without step progress before a run counts as stalled
+    pub stall_threshold_seconds: u64,
+    /// Seconds between checks
+    pub check_interval_seconds: u64,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            stall_threshold_seconds: 300,
+            check_interval_seconds: 10,
+        }
+    }
+}
+
+impl WatchdogConfig {
+    /// Time without progress after which a run is stalled
+    pub fn threshold(&self) -> Duration {
+        Duration::from_secs(self.stall_threshold_seconds)
+    }
+
+    /// Time between checks (at least 100ms)
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.check_interval_seconds).max(Duration::from_millis(100))
+    }
+}
+
+/// When an execution last made progress, shared with its command's progress callback
+#[derive(Debug, Clone)]
+pub struct ActivityClock(Arc<Mutex<Instant>>);
+
+impl ActivityClock {
+    /// Clock reading "active now"
+    pub fn new() -> Self {
+        Self(Arc::new(Mutex::new(Instant::now())))
+    }
+
+    /// Record activity now
+    pub fn touch(&self) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    /// Time since the last activity
+    pub fn idle_for(&self) -> Duration {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).elapsed()
+    }
+}
+
+impl Default for ActivityClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Diagnostics for a stalled execution
+#[derive(Debug, Clone, PartialEq)]
+pub struct StallReport {
+    /// Step that was running
+    pub step_id: Option<StepId>,
+    /// Its RAPS command line
+    pub command: Option<String>,
+    /// Time since the run started
+    pub running_for: Duration,
+    /// Time since the run last made progress
+    pub idle_for: Duration,
+}
+
+impl StallReport {
+    /// One-line description, e.g. `no progress for 5m 10s in step 'upload'
+    /// (raps object upload ...), running for 6m 02s`
+    pub fn summary(&self) -> String {
+        let mut text = format!("no progress for {}", format_duration_secs(self.idle_for.as_secs_f64()));
+        if let Some(step_id) = &self.step_id {
+            text.push_str(&format!(" in step '{}'", step_id));
+        }
+        if let Some(command) = &self.command {
+            text.push_str(&format!(" ({})", command));
+        }
+        text.push_str(&format!(
+            ", running for {}",
+            format_duration_secs(self.running_for.as_secs_f64())
+        ));
+        text
+    }
+}
+
+/// Outcome of checking one execution
+#[derive(Debug, Clone, PartialEq)]
+pub enum HealthCheck {
+    /// The execution is no longer running; stop watching it
+    Finished,
+    /// Making progress, or already reported as stalled
+    Healthy,
+    /// Newly stalled
+    Stalled(StallReport),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stall_summary() {
+        let report = StallReport {
+            step_id: Some("upload".to_string()),
+            command: Some("raps object upload demo model.rvt".to_string()),
+            running_for: Duration::from_secs(362),
+            idle_for: Duration::from_secs(310),
+        };
+        assert_eq!(
+            report.summary(),
+            "no progress for 5m 10s in step 'upload' (raps object upload demo model.rvt), running for 6m 02s"
+        );
+
+        let clock = ActivityClock::new();
+        assert!(clock.idle_for() < Duration::from_secs(1));
+        assert_eq!(WatchdogConfig::default().threshold(), Duration::from_secs(300));
+    }
+}
@@ -35,3 +35,38 @@ async fn test_tui_initialization() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_bundled_tours_reference_known_workflows() -> Result<()> {
+    use raps_demo_workflows::workflow::{discovery::WorkflowDiscovery, tour::discover_tours};
+
+    let mut discovery = WorkflowDiscovery::new("workflows")?;
+    discovery.discover_workflows()?;
+    let tours = discover_tours("workflows");
+    assert!(!tours.is_empty());
+    for tour in &tours {
+        let errors = tour.validate(discovery.get_workflows().keys().map(String::as_str));
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_builtin_pack_is_listed_and_valid() -> Result<()> {
+    use raps_demo_workflows::workflow::builtin::{is_builtin, BUILTIN_CATEGORY, BUILTIN_WORKFLOWS};
+    use raps_demo_workflows::workflow::{discovery::WorkflowDiscovery, WorkflowCategory};
+
+    let temp_dir = tempfile::TempDir::new()?;
+    let mut discovery = WorkflowDiscovery::new(temp_dir.path())?;
+    let workflows = discovery.include_builtin()?;
+    assert_eq!(workflows.len(), BUILTIN_WORKFLOWS.len());
+    for workflow in &workflows {
+        assert!(is_builtin(&workflow.script_path));
+        assert_eq!(workflow.category, WorkflowCategory::from_id(BUILTIN_CATEGORY));
+        let result = discovery.validate_workflow(&workflow.id)?;
+        assert!(result.is_valid, "{}: {:?}", workflow.id, result.errors);
+    }
+
+    Ok(())
+}
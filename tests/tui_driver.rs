@@ -0,0 +1,236 @@
+// End-to-end TUI flows through the headless driver (`--features test-driver`)
+
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::time::Duration;
+
+use raps_demo_workflows::assets::{AssetCategory, AssetDefinition, AssetRegistry};
+use raps_demo_workflows::tui::driver::HeadlessDriver;
+use raps_demo_workflows::workflow::client::RapsClientConfig;
+use raps_demo_workflows::workflow::mock::MockBackend;
+use raps_demo_workflows::RapsDemoBuilder;
+
+/// Serve `body` to one HTTP request on a local port, returning its URL
+fn serve_once(body: &'static [u8]) -> Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}/house.rvt", listener.local_addr()?);
+    std::thread::spawn(move || {
+        if let Ok((mut stream, _)) = listener.accept() {
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                match stream.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buf[..n]),
+                }
+            }
+            let header = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+            let _ = stream.write_all(header.as_bytes());
+            let _ = stream.write_all(body);
+        }
+    });
+    Ok(url)
+}
+
+// A plain (current-thread) runtime, as the download runs off the UI loop
+#[tokio::test]
+async fn test_preflight_download_and_run() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let workflows_dir = dir.path().join("workflows");
+    let assets_dir = dir.path().join("assets");
+    std::fs::create_dir_all(&workflows_dir)?;
+    let model = assets_dir.join("revit").join("house.rvt");
+    std::fs::write(
+        workflows_dir.join("upload-model.yaml"),
+        format!(
+            r#"metadata:
+  id: upload-model
+  name: Upload Model
+  description: Uploads the sample model.
+  category: oss
+  estimated_duration: 5
+  required_assets:
+    - {}
+
+steps:
+  - id: check-status
+    name: Check Auth Status
+    description: Verifies that the CLI is authenticated.
+    command:
+      type: auth
+      action: status
+"#,
+            model.display()
+        ),
+    )?;
+
+    let registry = AssetRegistry::from_assets(vec![AssetDefinition {
+        name: "House".to_string(),
+        description: "Sample house".to_string(),
+        url: serve_once(b"model")?,
+        category: AssetCategory::Revit,
+        is_archive: false,
+        estimated_size_mb: 0.1,
+    }]);
+    let mock = Arc::new(MockBackend::default());
+    let app = RapsDemoBuilder::new()
+        .workflows_dir(&workflows_dir)
+        .assets_dir(&assets_dir)
+        .executor_config(RapsClientConfig {
+            mock: Some(Arc::clone(&mock)),
+            ..Default::default()
+        })
+        .build_tui()?
+        .with_asset_registry(registry);
+    let mut driver = HeadlessDriver::new(app, 120, 40)?;
+
+    // Select the workflow: the model is missing, so it can't run yet
+    driver.click_text("Upload Model").await?;
+    driver.key(KeyCode::Enter).await?;
+    assert!(driver.contains("Missing Requirements"), "{}", driver.screen().join("\n"));
+    assert!(mock.take_calls().is_empty());
+
+    // Download it from the Assets tab
+    driver.key(KeyCode::Esc).await?;
+    driver.key(KeyCode::Char('4')).await?;
+    driver.key(KeyCode::Char('d')).await?;
+    assert!(driver.logs().iter().any(|l| l.contains("Downloading: House")));
+    driver.wait_for_log("Downloaded to", Duration::from_secs(10)).await?;
+    assert_eq!(std::fs::read(&model)?, b"model");
+
+    // Now it runs against the mock backend
+    driver.key(KeyCode::Enter).await?;
+    driver.wait_for("completed successfully", Duration::from_secs(10)).await?;
+    assert!(mock.take_calls().iter().any(|call| call.contains("auth status")));
+
+    Ok(())
+}
+
+/// Sidebar row under the selection
+fn selected_row(driver: &HeadlessDriver) -> String {
+    driver.screen().into_iter().find(|row| row.contains("> ")).unwrap_or_default()
+}
+
+#[tokio::test]
+async fn test_sidebar_collapse_expand_and_navigation() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let workflows_dir = dir.path().join("workflows");
+    std::fs::create_dir_all(&workflows_dir)?;
+    for (id, name, category) in [
+        ("bucket-one", "Bucket One", "oss"),
+        ("bucket-two", "Bucket Two", "oss"),
+        ("project-one", "Project One", "acc"),
+    ] {
+        std::fs::write(
+            workflows_dir.join(format!("{}.yaml", id)),
+            format!(
+                r#"metadata:
+  id: {id}
+  name: {name}
+  description: Test workflow.
+  category: {category}
+  estimated_duration: 5
+
+steps:
+  - id: check-status
+    name: Check Auth Status
+    description: Verifies that the CLI is authenticated.
+    command:
+      type: auth
+      action: status
+"#
+            ),
+        )?;
+    }
+    let app = RapsDemoBuilder::new()
+        .workflows_dir(&workflows_dir)
+        .assets_dir(dir.path().join("assets"))
+        .build_tui()?;
+    let mut driver = HeadlessDriver::new(app, 120, 40)?;
+    let screen = |driver: &HeadlessDriver| driver.screen().join("\n");
+
+    // Collapsing a group keeps its header selected, with the next group a row below
+    driver.click_text("Bucket Two").await?;
+    driver.key(KeyCode::Up).await?;
+    driver.key(KeyCode::Up).await?;
+    assert!(selected_row(&driver).contains("[-] Object Storage"), "{}", screen(&driver));
+    driver.key(KeyCode::Left).await?;
+    assert!(selected_row(&driver).contains("[+] Object Storage"), "{}", screen(&driver));
+    assert!(!driver.contains("Bucket Two"));
+    driver.key(KeyCode::Down).await?;
+    driver.key(KeyCode::Down).await?;
+    assert!(selected_row(&driver).contains("Project One"), "{}", screen(&driver));
+
+    // Clicking a header toggles it and selects it, whatever was selected before
+    driver.click_text("[+] Object Storage").await?;
+    assert!(selected_row(&driver).contains("[-] Object Storage"), "{}", screen(&driver));
+    driver.key(KeyCode::Down).await?;
+    driver.key(KeyCode::Down).await?;
+    assert!(selected_row(&driver).contains("Bucket Two"), "{}", screen(&driver));
+
+    // The same from the last group, and expanding it again
+    driver.key(KeyCode::Down).await?;
+    driver.key(KeyCode::Left).await?;
+    assert!(selected_row(&driver).contains("[+] Construction Cloud"), "{}", screen(&driver));
+    driver.key(KeyCode::Right).await?;
+    driver.key(KeyCode::Down).await?;
+    assert!(selected_row(&driver).contains("Project One"), "{}", screen(&driver));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_countdown_of_the_longest_accepted_duration() -> Result<()> {
+    use raps_demo_workflows::utils::serde_helpers::MAX_DURATION_SECONDS;
+    use raps_demo_workflows::workflow::mock::MockResponse;
+
+    let dir = tempfile::TempDir::new()?;
+    let workflows_dir = dir.path().join("workflows");
+    std::fs::create_dir_all(&workflows_dir)?;
+    std::fs::write(
+        workflows_dir.join("long-wait.yaml"),
+        format!(
+            r#"metadata:
+  id: long-wait
+  name: Long Wait
+  description: Expects to take a year.
+  category: oss
+  estimated_duration: {max}
+
+steps:
+  - id: wait
+    name: Wait
+    description: Takes as long as it may.
+    command:
+      type: auth
+      action: status
+    expected_duration: {max}
+"#,
+            max = MAX_DURATION_SECONDS
+        ),
+    )?;
+    let mock = Arc::new(MockBackend::new(vec![MockResponse {
+        latency_ms: 1000,
+        ..Default::default()
+    }]));
+    let app = RapsDemoBuilder::new()
+        .workflows_dir(&workflows_dir)
+        .assets_dir(dir.path().join("assets"))
+        .executor_config(RapsClientConfig {
+            mock: Some(mock),
+            ..Default::default()
+        })
+        .build_tui()?;
+    let mut driver = HeadlessDriver::new(app, 160, 50)?;
+
+    driver.click_text("Long Wait").await?;
+    driver.key(KeyCode::Char('2')).await?;
+    driver.key(KeyCode::Enter).await?;
+    driver.wait_for("Remaining: ~", Duration::from_secs(5)).await?;
+    driver.wait_for("completed successfully", Duration::from_secs(10)).await?;
+
+    Ok(())
+}
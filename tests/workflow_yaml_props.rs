@@ -0,0 +1,234 @@
+// Property-based tests of workflow YAML parsing
+//
+// Workflow files are edited by hand, so discovery has to survive whatever ends
+// up in them: malformed or truncated YAML, absurd durations, deep nesting and
+// unknown enum variants. A file that can't be used must be skipped (or fail
+// validation) without panicking and without taking the other workflows down.
+
+use proptest::prelude::*;
+use raps_demo_workflows::utils::serde_helpers::MAX_DURATION_SECONDS;
+use raps_demo_workflows::workflow::client::RapsClientConfig;
+use raps_demo_workflows::workflow::discovery::WorkflowDiscovery;
+use raps_demo_workflows::workflow::mock::{MockBackend, MockResponse};
+use raps_demo_workflows::workflow::{ExecutionOptions, ExecutionUpdate};
+use raps_demo_workflows::RapsDemoBuilder;
+use std::sync::Arc;
+
+const VALID: &str = r#"metadata:
+  id: upload-model
+  name: Upload Model
+  description: Uploads a model and translates it.
+  category: oss
+  prerequisites:
+    - type: authentication
+      description: Valid APS credentials required
+  estimated_duration: 60
+  required_assets: []
+
+steps:
+  - id: create-bucket
+    name: Create Bucket
+    description: Creates a bucket.
+    command:
+      type: bucket
+      action: create
+      bucket_name: demo-{uuid}
+    expected_duration: 5
+  - id: check-status
+    name: Check Auth Status
+    description: Verifies that the CLI is authenticated.
+    command:
+      type: auth
+      action: status
+"#;
+
+/// Discover `content` next to a known-good workflow and use every workflow
+/// found the way the TUI does; panics fail the property
+fn discover(content: &str) {
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(dir.path().join("fuzzed.yaml"), content).unwrap();
+    std::fs::write(dir.path().join("valid.yaml"), VALID.replace("upload-model", "known-good")).unwrap();
+
+    let mut discovery = WorkflowDiscovery::new(dir.path()).unwrap();
+    if discovery.discover_workflows().is_err() {
+        return;
+    }
+    assert!(discovery.get_workflow(&"known-good".to_string()).is_some());
+    // Listed workflows whose steps don't parse fail validation
+    for id in discovery.get_workflows().keys() {
+        let validated = discovery.validate_workflow(id);
+        assert_eq!(validated.is_ok(), discovery.get_workflow(id).is_some(), "{}", id);
+        let _ = discovery.get_workflow_dependencies(id);
+    }
+    for (id, workflow) in discovery.get_workflows().iter() {
+        let _ = workflow.required_assets();
+        let fraction = workflow.progress_fraction(workflow.steps.len());
+        assert!(fraction.is_finite(), "progress {} for {}", fraction, id);
+        let _ = workflow.metadata.estimated_duration.num_seconds();
+    }
+}
+
+/// Second counts chrono can represent at the edges of what is accepted:
+/// around the limit, around chrono's own limit and just below zero
+fn extreme_seconds() -> impl Strategy<Value = i64> {
+    let chrono_max = i64::MAX / 1000;
+    prop_oneof![
+        MAX_DURATION_SECONDS - 2..=MAX_DURATION_SECONDS + 2,
+        chrono_max - 2..=chrono_max,
+        -chrono_max..=-chrono_max + 2,
+        -2i64..=0,
+    ]
+}
+
+/// Durations as users might (mis)type them
+fn duration() -> impl Strategy<Value = String> {
+    prop_oneof![
+        any::<i64>().prop_map(|n| n.to_string()),
+        extreme_seconds().prop_map(|n| n.to_string()),
+        Just("99999999999999999999999".to_string()),
+        Just("1e400".to_string()),
+        Just("-0".to_string()),
+        Just(".nan".to_string()),
+        Just("30s".to_string()),
+        Just("~".to_string()),
+    ]
+}
+
+/// Known and unknown values of the YAML enums
+fn variant(known: &'static [&'static str]) -> impl Strategy<Value = String> {
+    prop_oneof![
+        proptest::sample::select(known).prop_map(str::to_string),
+        "[A-Za-z_-]{0,12}",
+    ]
+}
+
+prop_compose! {
+    fn workflow_yaml()(
+        category in variant(&["oss", "model-derivative", "data-management", "acc", "custom"]),
+        prerequisite in variant(&["authentication", "assets", "permissions", "tool"]),
+        estimated in duration(),
+        steps in proptest::collection::vec(
+            (variant(&["bucket", "object", "auth", "translate", "shell"]), variant(&["create", "delete", "status", "upload"]), duration()),
+            0..6,
+        ),
+    ) -> String {
+        let mut yaml = format!(
+            "metadata:\n  id: generated\n  name: Generated\n  description: Generated workflow\n  category: {}\n  prerequisites:\n    - type: {}\n      description: Needed\n  estimated_duration: {}\n  required_assets: []\n\nsteps:\n",
+            category, prerequisite, estimated
+        );
+        for (i, (kind, action, expected)) in steps.iter().enumerate() {
+            yaml.push_str(&format!(
+                "  - id: step-{i}\n    name: Step {i}\n    description: Step\n    command:\n      type: {}\n      action: {}\n    expected_duration: {}\n",
+                kind, action, expected
+            ));
+        }
+        yaml
+    }
+}
+
+/// Run a workflow whose steps expect `expected` seconds against the mock
+/// backend, asking for its progress (and remaining time) while it runs
+fn run_with_durations(estimated: i64, expected: &[i64]) {
+    let dir = tempfile::TempDir::new().unwrap();
+    let mut yaml = format!(
+        "metadata:\n  id: extreme\n  name: Extreme\n  description: Extreme durations\n  category: oss\n  estimated_duration: {}\n\nsteps:\n",
+        estimated
+    );
+    for (i, seconds) in expected.iter().enumerate() {
+        yaml.push_str(&format!(
+            "  - id: step-{i}\n    name: Step {i}\n    description: Step\n    command:\n      type: auth\n      action: status\n    expected_duration: {}\n",
+            seconds
+        ));
+    }
+    std::fs::write(dir.path().join("extreme.yaml"), yaml).unwrap();
+
+    let mock = Arc::new(MockBackend::new(vec![MockResponse {
+        latency_ms: 5,
+        ..Default::default()
+    }]));
+    let engine = RapsDemoBuilder::new()
+        .workflows_dir(dir.path())
+        .executor_config(RapsClientConfig {
+            mock: Some(mock),
+            ..Default::default()
+        })
+        .build_engine()
+        .unwrap();
+    let accepted = [estimated].iter().chain(expected).all(|s| (0..=MAX_DURATION_SECONDS).contains(s));
+    let id = "extreme".to_string();
+    assert_eq!(engine.get_workflow(&id).is_some(), accepted);
+    if !accepted {
+        return;
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    runtime.block_on(async {
+        let mut updates = engine.subscribe();
+        let options = ExecutionOptions {
+            interactive: false,
+            ..Default::default()
+        };
+        let handle = engine.execute(&id, options).await.unwrap();
+        loop {
+            let _ = engine.executor().get_execution_progress(&handle).await;
+            match updates.recv().await {
+                Some(ExecutionUpdate::Completed { result, .. }) => {
+                    assert!(result.success);
+                    break;
+                },
+                Some(ExecutionUpdate::Failed { error, .. }) => panic!("run failed: {}", error.message),
+                Some(_) => {},
+                None => panic!("updates closed"),
+            }
+        }
+    });
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn arbitrary_text_never_panics(content in "\\PC{0,400}") {
+        discover(&content);
+    }
+
+    #[test]
+    fn generated_workflows_never_panic(yaml in workflow_yaml()) {
+        discover(&yaml);
+    }
+
+    #[test]
+    fn extreme_durations_are_rejected_or_run(
+        estimated in extreme_seconds(),
+        expected in proptest::collection::vec(extreme_seconds(), 1..4),
+    ) {
+        run_with_durations(estimated, &expected);
+    }
+
+    #[test]
+    fn longest_accepted_durations_run(
+        estimated in MAX_DURATION_SECONDS - 2..=MAX_DURATION_SECONDS,
+        expected in proptest::collection::vec(MAX_DURATION_SECONDS - 2..=MAX_DURATION_SECONDS, 1..4),
+    ) {
+        run_with_durations(estimated, &expected);
+    }
+
+    #[test]
+    fn damaged_workflows_never_panic(cut in 0..VALID.len(), insert in "[\\[\\]{}:'\"\\-#&*!|> \\t\\n]{1,3}") {
+        // Truncated mid-edit, then with stray YAML syntax at the cut
+        let cut = (0..=cut).rev().find(|&i| VALID.is_char_boundary(i)).unwrap_or(0);
+        discover(&VALID[..cut]);
+        discover(&format!("{}{}{}", &VALID[..cut], insert, &VALID[cut..]));
+    }
+
+    #[test]
+    fn deep_nesting_never_panics(depth in 1usize..2000, mapping in any::<bool>()) {
+        let nested = if mapping {
+            format!("{}1{}", "{a: ".repeat(depth), "}".repeat(depth))
+        } else {
+            format!("{}{}", "[".repeat(depth), "]".repeat(depth))
+        };
+        discover(&VALID.replace("required_assets: []", &format!("required_assets: {}", nested)));
+        discover(&format!("{}\nextra: {}\n", VALID, nested));
+    }
+}